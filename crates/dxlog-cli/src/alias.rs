@@ -0,0 +1,151 @@
+use std::{ffi::OsString, path::Path};
+
+use anyhow::Result;
+use clap::CommandFactory;
+use dxlog::{load_config, Config};
+
+use crate::cli::Cli;
+
+/// Position of the command in `args`, after the program name and any global
+/// options. `--repo` is the only global option taking a value.
+pub fn command_index(args: &[OsString]) -> Option<usize> {
+    let mut index = 1;
+    while let Some(arg) = args.get(index) {
+        match arg.to_str() {
+            Some("--repo") => index += 2,
+            Some("--") => return None,
+            Some(arg) if arg.starts_with('-') => index += 1,
+            _ => return Some(index),
+        }
+    }
+    None
+}
+
+/// Value of a `--repo` given before the command.
+fn repo_arg(args: &[OsString]) -> Option<&Path> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        let arg = arg.to_str()?;
+        if arg == "--repo" {
+            args.get(i + 1).map(Path::new)
+        } else {
+            arg.strip_prefix("--repo=").map(Path::new)
+        }
+    })
+}
+
+/// Replaces a command named in `[aliases]` with the command it stands for,
+/// e.g. `dxlog standup -v` with `dxlog report --since 1d -v`.
+///
+/// Built-in commands take precedence over aliases of the same name. Outside
+/// a repository, or if its config cannot be read, `args` are returned as they
+/// are and the error is left to the command.
+pub fn expand(args: Vec<OsString>) -> Result<Vec<OsString>> {
+    let Some(index) = command_index(&args) else {
+        return Ok(args);
+    };
+    let Some(name) = args[index].to_str() else {
+        return Ok(args);
+    };
+    if Cli::command().find_subcommand(name).is_some() {
+        return Ok(args);
+    }
+
+    let repo = repo_arg(&args[..index]);
+    let config = match repo {
+        Some(repo) => Config::load(repo),
+        None => load_config(),
+    };
+    let Some(command) = config
+        .ok()
+        .and_then(|config| config.aliases.get(name).cloned())
+    else {
+        return Ok(args);
+    };
+    let words = shlex::split(&command).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Alias '{}' in [aliases] is not a valid command: {}",
+            name,
+            command
+        )
+    })?;
+
+    let mut expanded = args[..index].to_vec();
+    expanded.extend(words.into_iter().map(OsString::from));
+    expanded.extend_from_slice(&args[index + 1..]);
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+
+    const ALIASES: &str = r#"
+[aliases]
+standup = "report --since 1d"
+hypothesis = "knowledge list"
+again = "again -v"
+ping = "pong"
+pong = "ping"
+broken = "report 'unclosed"
+"#;
+
+    /// Expands `line` in a repository with [`ALIASES`], given by `--repo`.
+    fn expand_in_repo(line: &str) -> Result<Vec<String>> {
+        static REPOS: AtomicUsize = AtomicUsize::new(0);
+        let root = std::env::temp_dir().join(format!(
+            "dxlog-alias-{}-{}",
+            std::process::id(),
+            REPOS.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("dxlog.toml"), ALIASES).unwrap();
+        let args = ["dxlog", "--repo", root.to_str().unwrap()]
+            .into_iter()
+            .chain(line.split_whitespace())
+            .map(OsString::from)
+            .collect();
+        let expanded = expand(args).map(|args| {
+            args.into_iter()
+                .skip(3)
+                .map(|arg| arg.into_string().unwrap())
+                .collect()
+        });
+        let _ = fs::remove_dir_all(&root);
+        expanded
+    }
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(
+            expand_in_repo("standup -v").unwrap(),
+            ["report", "--since", "1d", "-v"]
+        );
+        assert_eq!(expand_in_repo("report -v").unwrap(), ["report", "-v"]);
+        assert!(expand_in_repo("broken")
+            .unwrap_err()
+            .to_string()
+            .contains("Alias 'broken'"));
+    }
+
+    #[test]
+    fn test_built_in_commands_win_over_aliases() {
+        assert_eq!(
+            expand_in_repo("hypothesis list").unwrap(),
+            ["hypothesis", "list"]
+        );
+    }
+
+    #[test]
+    fn test_aliases_expand_once() {
+        // An alias naming itself or another alias is not expanded again, so
+        // it cannot loop; clap then reports the command as unknown.
+        assert_eq!(expand_in_repo("again").unwrap(), ["again", "-v"]);
+        assert_eq!(expand_in_repo("ping").unwrap(), ["pong"]);
+        assert_eq!(expand_in_repo("pong").unwrap(), ["ping"]);
+    }
+}
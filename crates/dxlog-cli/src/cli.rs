@@ -1,16 +1,108 @@
-use std::path::PathBuf;
+use std::{
+    ffi::OsString,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+};
 
-use dxlog::init_repository;
+use clap::{builder::FalseyValueParser, error::ErrorKind, CommandFactory, Parser};
+use clap_complete::Shell;
+use dxlog::{
+    init_repository, is_dry_run, take_hook_failures, take_notification_failures,
+    take_planned_changes, take_skipped_entries, RunOptions, DRY_RUN_ENV_VAR, OFFLINE_ENV_VAR,
+    REPO_ENV_VAR, STRICT_ENV_VAR,
+};
 
-use crate::commands::{
-    HypothesisCommands, KnowledgeCommands, LiteratureCommands, ReferenceCommands,
+#[cfg(feature = "llm")]
+use crate::commands::SuggestTagsArgs;
+use crate::{
+    commands::{
+        undo, ActionsCommands, AttachArgs, AttachmentsCommands, BackupCommands, BoardArgs,
+        CiteArgs, ConfigCommands, DatasetCommands, DecryptArgs, DoctorArgs, DueArgs, EncryptArgs,
+        EntryArgs, ExperimentCommands, ExportCommands, HypothesisCommands, IdeaArgs,
+        ImportCommands, JournalCommands, KnowledgeCommands, LiteratureCommands, MeetingCommands,
+        MigrateArgs, MilestoneCommands, NoteCommands, OpenArgs, ProjectCommands, RecentArgs,
+        ReferenceCommands, RelatedArgs, ReportArgs, SearchArgs, ShowArgs, StatsArgs, SyncArgs,
+        TagCommands, TemplateCommands, TrashCommands, WatchArgs,
+    },
+    completion, confirm, docs, logging, mcp, pick, server,
 };
 
 #[derive(clap::Parser)]
-#[command(author, version, about = "A research log management tool for tracking hypotheses, literature, and knowledge", long_about = None)]
+#[command(name = "dxlog", author, version, about = "A research log management tool for tracking hypotheses, literature, and knowledge", long_about = None)]
 pub struct Cli {
+    /// Repository to operate on
+    #[arg(
+        long,
+        global = true,
+        env = REPO_ENV_VAR,
+        value_name = "PATH",
+        help = "Path to the dxlog repository (default: found by searching upward from the current directory)"
+    )]
+    repo: Option<PathBuf>,
+
+    /// Never access the network
+    #[arg(
+        long,
+        global = true,
+        env = OFFLINE_ENV_VAR,
+        value_parser = FalseyValueParser::new(),
+        help = "Don't fetch metadata from the network; only cached responses are used"
+    )]
+    offline: bool,
+
+    /// Show file changes instead of making them
+    #[arg(
+        long,
+        global = true,
+        env = DRY_RUN_ENV_VAR,
+        value_parser = FalseyValueParser::new(),
+        help = "Print the files that would be written, moved or removed without changing anything"
+    )]
+    dry_run: bool,
+
+    /// Fail on malformed entries
+    #[arg(
+        long,
+        global = true,
+        env = STRICT_ENV_VAR,
+        value_parser = FalseyValueParser::new(),
+        help = "Fail on entry files that cannot be parsed instead of skipping them with a warning"
+    )]
+    strict: bool,
+
+    /// Show what dxlog does
+    #[arg(
+        short,
+        long,
+        global = true,
+        action = clap::ArgAction::Count,
+        help = "Log scanned directories, file changes and HTTP requests to stderr (-vv for every file read); DXLOG_LOG takes tracing filter directives instead"
+    )]
+    verbose: u8,
+
+    /// Answer yes to every question
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Don't ask for confirmation; answer yes to every question"
+    )]
+    yes: bool,
+
+    /// Print every command
+    #[arg(
+        long,
+        help = "Print every command and subcommand with a one-line description"
+    )]
+    help_all: bool,
+
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
+
+    /// The command line after aliases are expanded, with any entry chosen
+    /// for a missing ID filled in.
+    #[arg(skip)]
+    args: Vec<OsString>,
 }
 
 #[derive(clap::Subcommand)]
@@ -30,38 +122,561 @@ pub enum Commands {
     },
 
     /// Manage research hypotheses
+    #[command(visible_alias = "hyp")]
     Hypothesis {
         #[command(subcommand)]
         command: HypothesisCommands,
     },
 
     /// Manage literature reviews
+    #[command(visible_alias = "lit")]
     Literature {
         #[command(subcommand)]
         command: LiteratureCommands,
     },
 
     /// Manage knowledge base entries
+    #[command(visible_alias = "kb")]
     Knowledge {
         #[command(subcommand)]
         command: KnowledgeCommands,
     },
 
+    /// Keep a daily lab notebook
+    Journal {
+        #[command(subcommand)]
+        command: JournalCommands,
+    },
+
+    /// Keep a registry of datasets and their provenance
+    Dataset {
+        #[command(subcommand)]
+        command: DatasetCommands,
+    },
+
+    /// Keep meeting notes with attendees and action items
+    Meeting {
+        #[command(subcommand)]
+        command: MeetingCommands,
+    },
+
+    /// Track the action items agreed in meetings
+    Actions {
+        #[command(subcommand)]
+        command: ActionsCommands,
+    },
+
+    /// Capture ideas in the inbox and promote them to entries
+    ///
+    /// Examples:
+    ///   dxlog idea "Does shielding halve the error rate?"
+    ///   dxlog idea list
+    ///   dxlog idea promote I-007 --to hypothesis
+    Idea(IdeaArgs),
+
+    /// Plan and run experiments, recording the code they ran on
+    Experiment {
+        #[command(subcommand)]
+        command: ExperimentCommands,
+    },
+
+    /// Manage entries of a log type declared in dxlog.toml
+    ///
+    /// Log types beyond the built-in ones are declared under [types] with
+    /// their statuses, and optionally a template, directory and handle prefix.
+    ///
+    /// Examples:
+    ///   dxlog entry protocol new "PCR Amplification"
+    ///   dxlog entry protocol list --status draft
+    ///   dxlog entry protocol status P-001 validated
+    Entry(EntryArgs),
+
+    /// Group entries into projects
+    Project {
+        #[command(subcommand)]
+        command: ProjectCommands,
+    },
+
+    /// Track progress towards target dates
+    Milestone {
+        #[command(subcommand)]
+        command: MilestoneCommands,
+    },
+
     /// Manage references between entries
+    #[command(visible_alias = "ref")]
     Reference {
         #[command(subcommand)]
         command: ReferenceCommands,
     },
+
+    /// Add notes to entries
+    Note {
+        #[command(subcommand)]
+        command: NoteCommands,
+    },
+
+    /// Print an entry, decrypting its body if it is encrypted
+    ///
+    /// Example:
+    ///   dxlog show H-042
+    Show(ShowArgs),
+
+    /// Open an entry in $VISUAL or $EDITOR
+    ///
+    /// The body of an encrypted entry is decrypted into a private temporary
+    /// file for editing and encrypted again when the editor exits.
+    ///
+    /// Example:
+    ///   dxlog open H-042
+    Open(OpenArgs),
+
+    /// Encrypt the body of an entry
+    ///
+    /// Encrypts the markdown body with age to the recipients under
+    /// [encryption] in dxlog.toml. The frontmatter stays readable, so the
+    /// entry is still listed and filtered. `show`, `open` and `note add`
+    /// decrypt it with your identity from DXLOG_IDENTITY or
+    /// ~/.config/dxlog/identity.txt.
+    ///
+    /// Example:
+    ///   dxlog encrypt H-042
+    Encrypt(EncryptArgs),
+
+    /// Store the body of an encrypted entry in plain text again
+    ///
+    /// Example:
+    ///   dxlog decrypt H-042
+    Decrypt(DecryptArgs),
+
+    /// Store a file with an entry
+    ///
+    /// Copies the file to attachments/<id>/ and records its name, size and
+    /// SHA-256 in the entry, so `dxlog doctor` can tell if it changes or goes
+    /// missing. Attaching a file of the same name again replaces it. The text
+    /// of a PDF attached to a literature entry is extracted next to it, for
+    /// `dxlog search` and `dxlog literature quote`.
+    ///
+    /// Examples:
+    ///   dxlog attach E-003 results/confusion.png
+    ///   dxlog attach L-012 ~/Downloads/2401.12345.pdf
+    Attach(AttachArgs),
+
+    /// List the files attached to entries
+    Attachments {
+        #[command(subcommand)]
+        command: AttachmentsCommands,
+    },
+
+    /// Inspect and edit entry templates
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommands,
+    },
+
+    /// Rename tags across all entries
+    Tag {
+        #[command(subcommand)]
+        command: TagCommands,
+    },
+
+    /// Manage deleted entries
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
+
+    /// Snapshot and restore the entry directories
+    Backup {
+        #[command(subcommand)]
+        command: BackupCommands,
+    },
+
+    /// Revert the last change to entries
+    ///
+    /// Creating an entry, changing its status, deleting it and adding a
+    /// reference are recorded in .dxlog/operations.json. This reverts the most
+    /// recent of them, bringing deleted entries back from the trash; run it
+    /// again to revert the one before. Nothing is changed if the files involved
+    /// have been edited since.
+    ///
+    /// Examples:
+    ///   dxlog undo
+    ///   dxlog --dry-run undo
+    Undo,
+
+    /// Check the repository for problems
+    ///
+    /// Verifies every attachment against the size and checksum recorded when
+    /// it was attached, and fails if any is missing or was modified.
+    ///
+    /// Example:
+    ///   dxlog doctor
+    Doctor(DoctorArgs),
+
+    /// Check entries and rebuild outputs as files change
+    ///
+    /// Watches the entry directories and reads every entry that is created or
+    /// changed, reporting those whose frontmatter is invalid. With --site, the
+    /// static site is exported again after each change, and with --embeddings
+    /// (dxlog built with the `llm` feature) changed entries are embedded for
+    /// `dxlog search --semantic`.
+    ///
+    /// Examples:
+    ///   dxlog watch
+    ///   dxlog watch --site ./site
+    Watch(WatchArgs),
+
+    /// Commit changed entries and sync them with the git remote
+    ///
+    /// Commits changed and new files in the entry directories, pulls with
+    /// rebase and pushes. If an entry was changed on both sides but only in
+    /// its frontmatter, offers to merge the two versions: tags and references
+    /// are combined and the rest, the status included, is taken from the
+    /// version updated last. Other conflicts are left to resolve by hand;
+    /// running `dxlog sync` again afterwards finishes the sync.
+    ///
+    /// Examples:
+    ///   dxlog sync
+    ///   dxlog sync --remote origin -m "Results of the week"
+    ///   dxlog --dry-run sync
+    Sync(SyncArgs),
+
+    /// Upgrade entries written by older versions of dxlog
+    ///
+    /// Rewrites the frontmatter of every entry with an older schema_version in
+    /// the current format, after backing up the entry directories. Older entries
+    /// are upgraded in memory whenever they are read, so this only updates the files.
+    ///
+    /// Examples:
+    ///   dxlog migrate
+    ///   dxlog --dry-run migrate
+    Migrate(MigrateArgs),
+
+    /// Read and change repository settings
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Export research logs to other formats
+    Export {
+        #[command(subcommand)]
+        command: ExportCommands,
+    },
+
+    /// Import entries exported from a repository
+    Import {
+        #[command(subcommand)]
+        command: ImportCommands,
+    },
+
+    /// Summarize recent research activity
+    ///
+    /// Lists new entries, status changes, completed literature reviews and stale
+    /// entries per author. The output is rendered through templates/report.jinja.
+    ///
+    /// Examples:
+    ///   dxlog report
+    ///   dxlog report --since 2w --out report.md
+    Report(ReportArgs),
+
+    /// Search entries of all types
+    ///
+    /// Lists entries whose title, tags, body or attached PDFs contain the
+    /// query, ignoring case, newest first. With --semantic (dxlog built with the `llm`
+    /// feature), ranks entries by how close their meaning is to the query
+    /// instead, using embeddings from the [llm] API that are kept in
+    /// .dxlog/embeddings.json and updated for new and changed entries.
+    ///
+    /// With --workspace, searches the repositories registered in
+    /// ~/.config/dxlog/workspaces.toml, showing IDs qualified with the
+    /// repository, such as qec:H-001.
+    ///
+    /// Examples:
+    ///   dxlog search "surface code"
+    ///   dxlog search noise --type literature
+    ///   dxlog search --semantic "catastrophic forgetting mitigation"
+    ///   dxlog search "surface code" --workspace all
+    Search(SearchArgs),
+
+    /// Count entries by type and status
+    ///
+    /// With --workspace, counts the entries of each repository registered in
+    /// ~/.config/dxlog/workspaces.toml instead of the current one.
+    ///
+    /// Examples:
+    ///   dxlog stats
+    ///   dxlog stats --workspace all
+    Stats(StatsArgs),
+
+    /// List the most recently created or changed entries
+    ///
+    /// Shows entries of all types, newest activity first, to pick up where you
+    /// left off. Entries without a recorded update use the last commit touching them.
+    ///
+    /// Examples:
+    ///   dxlog recent
+    ///   dxlog recent -n 20 --since 1w
+    Recent(RecentArgs),
+
+    /// Suggest entries an entry may be missing references to
+    ///
+    /// Ranks the other entries by the tags they share with it and by how alike
+    /// their titles and bodies are, leaving out those already linked either way.
+    /// With --add, asks for each suggestion whether to reference it.
+    ///
+    /// Examples:
+    ///   dxlog related 1a2b3c4d
+    ///   dxlog related H-004 -n 5 --add
+    Related(RelatedArgs),
+
+    /// Suggest tags for an entry with a language model
+    ///
+    /// Sends the entry's title, abstract and notes, with the tags already in
+    /// use, to the API configured under [llm] and asks before adding the
+    /// suggested tags. Bodies of encrypted entries are not sent. The entry's
+    /// tags are only replaced with --overwrite.
+    ///
+    /// Examples:
+    ///   dxlog suggest-tags 1a2b3c4d
+    ///   dxlog suggest-tags L-012 --overwrite
+    #[cfg(feature = "llm")]
+    SuggestTags(SuggestTagsArgs),
+
+    /// List open entries with a due date
+    ///
+    /// Shows active or suspended hypotheses and knowledge drafts that have a
+    /// due date, soonest first. Overdue entries are always shown.
+    ///
+    /// Examples:
+    ///   dxlog due
+    ///   dxlog due --days 14
+    Due(DueArgs),
+
+    /// Show entries as a board with one column per status
+    ///
+    /// Gives a quick view of the research pipeline: each column lists the
+    /// entries with that status, with the count in its header.
+    ///
+    /// Examples:
+    ///   dxlog board
+    ///   dxlog board --type literature
+    Board(BoardArgs),
+
+    /// Print a Pandoc citation for literature entries
+    ///
+    /// Prints the `[@citekey]` snippet to paste into a manuscript. Cite several
+    /// entries at once to get a combined citation. Export the keys with
+    /// `dxlog literature export --format csl-json`.
+    ///
+    /// Examples:
+    ///   dxlog cite 6f1g3def
+    ///   dxlog cite 6f1g3def 9a2b7c4d
+    Cite(CiteArgs),
+
+    /// Serve the repository over a JSON REST API
+    ///
+    /// Exposes hypotheses, literature, knowledge and references under /api so
+    /// other tools (e.g. a web frontend) can read and update the logs. Binds to
+    /// localhost unless --host is given.
+    ///
+    /// Examples:
+    ///   dxlog serve
+    ///   dxlog serve --port 3000 --host 0.0.0.0
+    Serve {
+        /// Port to listen on
+        #[arg(short, long, default_value_t = 8080, help = "Port to listen on")]
+        port: u16,
+
+        /// Address to bind to
+        #[arg(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST), help = "Address to bind to")]
+        host: IpAddr,
+    },
+
+    /// Run a Model Context Protocol server over stdio
+    ///
+    /// Lets AI assistants search, read and update the research log. Register
+    /// the command with your MCP client; it is not meant to be run by hand.
+    ///
+    /// Example client configuration:
+    ///   { "command": "dxlog", "args": ["--repo", "/path/to/research", "mcp"] }
+    Mcp,
+
+    /// Generate shell completions
+    ///
+    /// Prints a completion script for the given shell. Besides subcommands and
+    /// flags, the script completes entry IDs and tags from the current repository.
+    ///
+    /// Examples:
+    ///   source <(dxlog completion bash)
+    ///   dxlog completion zsh > ~/.zfunc/_dxlog
+    ///   dxlog completion fish > ~/.config/fish/completions/dxlog.fish
+    Completion {
+        /// Shell to generate completions for
+        #[arg(help = "Target shell (bash, zsh, fish, powershell, elvish)")]
+        shell: Shell,
+    },
+
+    /// Generate man pages
+    ///
+    /// Writes a man page for dxlog and one for every subcommand, named like
+    /// dxlog-hypothesis-new.1, to the given directory.
+    ///
+    /// Examples:
+    ///   dxlog man ./man/man1
+    ///   dxlog man /usr/local/share/man/man1 && mandb
+    Man {
+        /// Directory to write the pages to
+        #[arg(help = "Directory for the man pages (created if missing)")]
+        dir: PathBuf,
+    },
+}
+
+/// Prints what the library skipped or failed at on this thread without
+/// failing the command, and forgets it. Long-running commands call this after
+/// each request or change so warnings are reported once and do not pile up.
+pub fn print_warnings() {
+    for skipped in take_skipped_entries() {
+        eprintln!(
+            "Warning: skipped {}: {}",
+            skipped.path.display(),
+            skipped.reason
+        );
+    }
+    for failure in take_notification_failures() {
+        eprintln!("Warning: notification not sent: {}", failure);
+    }
+    for failure in take_hook_failures() {
+        eprintln!("Warning: {}", failure);
+    }
+    // Long-running commands never reach the end of the run, where the plan is printed.
+    for change in take_planned_changes() {
+        eprintln!("Dry run: skipped {}", change);
+    }
 }
 
 impl Cli {
+    /// Parses `args`, letting the user choose the entry if an entry ID is missing.
+    pub fn parse_args(args: Vec<OsString>) -> anyhow::Result<Self> {
+        match Cli::try_parse_from(&args) {
+            Ok(mut cli) => {
+                cli.args = args;
+                Ok(cli)
+            }
+            Err(err) => match pick::missing_id(&args, &err)? {
+                Some(args) => Cli::parse_args(args),
+                None => err.exit(),
+            },
+        }
+    }
+
     pub fn run(&self) -> anyhow::Result<()> {
-        match &self.command {
+        logging::init(self.verbose);
+
+        // --repo, --offline, --dry-run and --strict, or their environment
+        // variables, hold for everything the library does in this run.
+        RunOptions {
+            repo: self.repo.clone(),
+            dry_run: self.dry_run,
+            strict: self.strict,
+            offline: self.offline,
+        }
+        .apply();
+        if self.yes {
+            confirm::assume_yes();
+        }
+
+        if self.help_all {
+            return docs::print_command_tree();
+        }
+        let Some(command) = &self.command else {
+            Cli::command()
+                .error(ErrorKind::MissingSubcommand, "a command is required")
+                .exit();
+        };
+
+        // Partial IDs matching several entries are settled before anything
+        // runs, with the entries the user chooses.
+        let resolved = match pick::ambiguous_ids(&self.args)? {
+            Some(args) => Some(Cli::try_parse_from(&args)?),
+            None => None,
+        };
+        let command = resolved
+            .as_ref()
+            .and_then(|cli| cli.command.as_ref())
+            .unwrap_or(command);
+        let result = Self::execute(command);
+        if let Err(err) = &result {
+            pick::print_matches(err);
+        }
+
+        if is_dry_run() {
+            let planned = take_planned_changes();
+            if planned.is_empty() {
+                println!("Dry run: no files would change");
+            } else {
+                println!("Dry run: nothing was changed. Planned changes:");
+                for change in planned {
+                    println!("  {}", change);
+                }
+            }
+        }
+        print_warnings();
+        result
+    }
+
+    fn execute(command: &Commands) -> anyhow::Result<()> {
+        match command {
             Commands::Init { path } => init_repository(path),
             Commands::Hypothesis { command } => command.execute(),
             Commands::Literature { command } => command.execute(),
             Commands::Knowledge { command } => command.execute(),
+            Commands::Journal { command } => command.execute(),
+            Commands::Dataset { command } => command.execute(),
+            Commands::Meeting { command } => command.execute(),
+            Commands::Actions { command } => command.execute(),
+            Commands::Idea(args) => args.execute(),
+            Commands::Experiment { command } => command.execute(),
+            Commands::Entry(args) => args.execute(),
+            Commands::Project { command } => command.execute(),
+            Commands::Milestone { command } => command.execute(),
             Commands::Reference { command } => command.execute(),
+            Commands::Note { command } => command.execute(),
+            Commands::Show(args) => args.execute(),
+            Commands::Open(args) => args.execute(),
+            Commands::Encrypt(args) => args.execute(),
+            Commands::Decrypt(args) => args.execute(),
+            Commands::Attach(args) => args.execute(),
+            Commands::Attachments { command } => command.execute(),
+            Commands::Template { command } => command.execute(),
+            Commands::Tag { command } => command.execute(),
+            Commands::Trash { command } => command.execute(),
+            Commands::Backup { command } => command.execute(),
+            Commands::Undo => undo(),
+            Commands::Doctor(args) => args.execute(),
+            Commands::Watch(args) => args.execute(),
+            Commands::Sync(args) => args.execute(),
+            Commands::Migrate(args) => args.execute(),
+            Commands::Config { command } => command.execute(),
+            Commands::Export { command } => command.execute(),
+            Commands::Import { command } => command.execute(),
+            Commands::Report(args) => args.execute(),
+            Commands::Search(args) => args.execute(),
+            Commands::Stats(args) => args.execute(),
+            Commands::Recent(args) => args.execute(),
+            Commands::Related(args) => args.execute(),
+            #[cfg(feature = "llm")]
+            Commands::SuggestTags(args) => args.execute(),
+            Commands::Due(args) => args.execute(),
+            Commands::Board(args) => args.execute(),
+            Commands::Cite(args) => args.execute(),
+            Commands::Serve { port, host } => server::serve(SocketAddr::new(*host, *port)),
+            Commands::Mcp => mcp::run(),
+            Commands::Completion { shell } => completion::write_registration(*shell),
+            Commands::Man { dir } => docs::write_man_pages(dir).map(|pages| {
+                println!("Wrote {} man pages to {}", pages.len(), dir.display());
+            }),
         }
     }
 }
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{list_action_items, load_config};
+
+use crate::{completion, table};
+
+#[derive(clap::Subcommand, Clone)]
+pub enum ActionsCommands {
+    /// List the action items of all meetings
+    ///
+    /// Items are shown earliest due first; items without a due date follow,
+    /// oldest meeting first. Check items off with `dxlog meeting action done`.
+    ///
+    /// Examples:
+    ///   dxlog actions list --open
+    ///   dxlog actions list --open --owner ada
+    ///   dxlog actions list --project thesis
+    List {
+        /// Only unchecked items
+        #[arg(long, help_heading = "FILTERS", help = "Show only items not done yet")]
+        open: bool,
+
+        /// Filter by owner
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only items owned by this person, ignoring case"
+        )]
+        owner: Option<String>,
+
+        /// Filter by project
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only items from meetings in this project",
+            add = ArgValueCompleter::new(completion::projects)
+        )]
+        project: Option<String>,
+    },
+}
+
+impl ActionsCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::List {
+                open,
+                owner,
+                project,
+            } => {
+                let actions = list_action_items(*open, owner.as_deref(), project.as_deref())?;
+                if actions.is_empty() {
+                    println!("No action items");
+                    return Ok(());
+                }
+
+                let date_format = load_config()?.date_format;
+                let today = chrono::Local::now().date_naive();
+                let mut rows = table::new(&[
+                    "MEETING ID",
+                    "MEETING",
+                    "#",
+                    "DONE",
+                    "OWNER",
+                    "DUE",
+                    "ACTION",
+                ]);
+                for action in actions {
+                    let due = match action.item.due {
+                        Some(due) if !action.item.done => format!(
+                            "{} ({})",
+                            due.format(&date_format),
+                            table::remaining((due - today).num_days())
+                        ),
+                        Some(due) => due.format(&date_format).to_string(),
+                        None => String::new(),
+                    };
+                    rows.add_row(vec![
+                        table::dim(&table::entry_id(
+                            &action.meeting_id.to_string(),
+                            action.meeting_handle.as_deref(),
+                        )),
+                        Cell::new(&action.meeting),
+                        Cell::new(action.index),
+                        Cell::new(if action.item.done { "x" } else { "" }),
+                        Cell::new(action.item.owner.as_deref().unwrap_or_default()),
+                        Cell::new(due),
+                        Cell::new(&action.item.text),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{attach_file, format_size, list_attachments};
+
+use crate::{completion, table};
+
+#[derive(clap::Args, Clone)]
+pub struct AttachArgs {
+    /// ID of the entry (can be partial)
+    #[arg(
+        help = "ID of the entry to attach the file to",
+        add = ArgValueCompleter::new(completion::any_ids)
+    )]
+    id: String,
+
+    /// File to attach
+    #[arg(help = "Path to the file to store with the entry")]
+    file: PathBuf,
+}
+
+impl AttachArgs {
+    pub fn execute(&self) -> Result<()> {
+        let attached = attach_file(&self.id, &self.file)?;
+        println!(
+            "{} {} on \"{}\" ({}, sha256 {})",
+            if attached.replaced {
+                "Replaced"
+            } else {
+                "Attached"
+            },
+            attached.path.display(),
+            attached.title,
+            format_size(attached.attachment.size),
+            &attached.attachment.sha256[..12]
+        );
+        if let Some(text) = &attached.text {
+            println!("Extracted its text to {}", text.display());
+        }
+        Ok(())
+    }
+}
+
+#[derive(clap::Subcommand, Clone)]
+pub enum AttachmentsCommands {
+    /// List the files attached to an entry
+    ///
+    /// Example:
+    ///   dxlog attachments list E-003
+    List {
+        /// ID of the entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
+        id: String,
+    },
+}
+
+impl AttachmentsCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::List { id } => {
+                let entry = list_attachments(id)?;
+                if entry.attachments.is_empty() {
+                    println!("No attachments on \"{}\"", entry.title);
+                    return Ok(());
+                }
+                println!(
+                    "Attachments of \"{}\" in {}",
+                    entry.title,
+                    entry.dir.display()
+                );
+                let mut rows = table::new(&["NAME", "SIZE", "SHA-256"]);
+                for attachment in &entry.attachments {
+                    rows.add_row(vec![
+                        Cell::new(&attachment.name),
+                        Cell::new(format_size(attachment.size)),
+                        table::dim(&attachment.sha256),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+        }
+    }
+}
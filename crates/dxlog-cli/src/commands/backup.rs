@@ -0,0 +1,181 @@
+use anyhow::Result;
+use comfy_table::Cell;
+use dxlog::{
+    create_backup, is_dry_run, list_backups, pull_backup, push_backup, restore_backup, Backup,
+};
+
+use crate::confirm::{self, Impact};
+use crate::table;
+
+#[derive(clap::Subcommand, Clone)]
+pub enum BackupCommands {
+    /// Snapshot the entry directories
+    ///
+    /// Archives the active, archive, knowledge base, journal, dataset,
+    /// meeting, inbox and experiment directories into .dxlog/backups. Bulk
+    /// changes such as `dxlog tag rename` take one automatically.
+    ///
+    /// Examples:
+    ///   dxlog backup create
+    ///   dxlog backup create --label before-cleanup
+    Create {
+        /// Short description added to the backup name
+        #[arg(short, long)]
+        label: Option<String>,
+    },
+
+    /// List backups, newest first
+    ///
+    /// Example:
+    ///   dxlog backup list
+    List,
+
+    /// Replace the entry directories with a backup
+    ///
+    /// Entries created since the backup was taken are removed. The current state
+    /// is backed up first, so a restore can be undone by restoring that backup.
+    ///
+    /// Example:
+    ///   dxlog backup restore 20250301-142233
+    Restore {
+        /// Name of the backup (can be partial)
+        name: String,
+    },
+
+    /// Upload a backup to S3 or WebDAV
+    ///
+    /// Takes a new backup, or uses the one named, and uploads it to the remote
+    /// from --remote or `remote` under [backup] in dxlog.toml: s3://bucket/prefix,
+    /// or the https:// URL of a WebDAV folder. The backup is encrypted to the
+    /// [encryption] recipients first unless [backup] sets `encrypt = false`.
+    ///
+    /// S3 credentials are read from AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY and
+    /// AWS_SESSION_TOKEN; the WebDAV login from DXLOG_WEBDAV_USER and
+    /// DXLOG_WEBDAV_PASSWORD.
+    ///
+    /// Examples:
+    ///   dxlog backup push
+    ///   dxlog backup push 20250301-142233 --remote s3://lab-backups/dxlog
+    Push {
+        /// Name of the backup to upload (can be partial; default: a new backup)
+        name: Option<String>,
+
+        /// Remote to upload to
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "s3://bucket/prefix or https:// WebDAV URL (default: `remote` under [backup])"
+        )]
+        remote: Option<String>,
+    },
+
+    /// Download a backup from S3 or WebDAV
+    ///
+    /// Downloads the latest backup on the remote, or the one named, into
+    /// .dxlog/backups, decrypting it with your age identity if it was
+    /// encrypted. Restore it afterwards with `dxlog backup restore`.
+    ///
+    /// Examples:
+    ///   dxlog backup pull
+    ///   dxlog backup pull 20250301 --remote https://dav.example.org/dxlog
+    Pull {
+        /// Name of the backup to download (can be partial; default: the latest)
+        name: Option<String>,
+
+        /// Remote to download from
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "s3://bucket/prefix or https:// WebDAV URL (default: `remote` under [backup])"
+        )]
+        remote: Option<String>,
+    },
+}
+
+/// Size in bytes as a short human readable string, e.g. `12.4 KiB`.
+fn format_size(bytes: u64) -> String {
+    let mut size = bytes as f64;
+    for unit in ["B", "KiB", "MiB"] {
+        if size < 1024.0 {
+            return if unit == "B" {
+                format!("{} B", bytes)
+            } else {
+                format!("{:.1} {}", size, unit)
+            };
+        }
+        size /= 1024.0;
+    }
+    format!("{:.1} GiB", size)
+}
+
+fn print_created(backup: &Backup) {
+    println!(
+        "Saved backup {} ({})",
+        backup.name,
+        format_size(backup.size)
+    );
+}
+
+impl BackupCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Create { label } => {
+                print_created(&create_backup(label.as_deref())?);
+                Ok(())
+            }
+            Self::List => {
+                let mut rows = table::new(&["NAME", "CREATED", "SIZE"]);
+                for backup in list_backups()? {
+                    let created_at = backup
+                        .created_at
+                        .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_default();
+                    rows.add_row(vec![
+                        Cell::new(&backup.name),
+                        Cell::new(created_at),
+                        table::dim(&format_size(backup.size)),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            Self::Restore { name } => {
+                confirm::require(
+                    &format!("Replace all entries with the backup {}?", name),
+                    Impact::Destructive,
+                )?;
+                let (restored, before) = restore_backup(name)?;
+                println!("Restored backup {}", restored.name);
+                println!(
+                    "The entries as they were before are saved as backup {}",
+                    before.name
+                );
+                Ok(())
+            }
+            Self::Push { name, remote } => {
+                let (backup, file_name) = push_backup(remote.as_deref(), name.as_deref())?;
+                if !is_dry_run() {
+                    println!(
+                        "Uploaded backup {} ({}) as {}",
+                        backup.name,
+                        format_size(backup.size),
+                        file_name
+                    );
+                }
+                Ok(())
+            }
+            Self::Pull { name, remote } => {
+                let backup = pull_backup(remote.as_deref(), name.as_deref())?;
+                if !is_dry_run() {
+                    println!(
+                        "Downloaded backup {} ({})",
+                        backup.name,
+                        format_size(backup.size)
+                    );
+                    println!("Restore it with `dxlog backup restore {}`", backup.name);
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use comfy_table::Cell;
+use dxlog::board;
+
+use crate::table;
+
+#[derive(clap::Args, Clone)]
+pub struct BoardArgs {
+    /// Log type to show
+    #[arg(
+        long = "type",
+        value_name = "TYPE",
+        default_value = "hypothesis",
+        value_parser = ["hypothesis", "literature", "knowledge", "journal", "dataset", "meeting", "idea", "experiment"],
+        help = "Log type whose entries are shown"
+    )]
+    kind: String,
+}
+
+impl BoardArgs {
+    pub fn execute(&self) -> Result<()> {
+        let columns = board(&self.kind)?;
+        if columns.iter().all(|column| column.cards.is_empty()) {
+            println!("No {} entries yet", self.kind);
+            return Ok(());
+        }
+
+        let headers: Vec<String> = columns
+            .iter()
+            .map(|column| format!("{} ({})", column.status.to_uppercase(), column.cards.len()))
+            .collect();
+        let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+        let mut rows = table::new(&headers);
+        let height = columns.iter().map(|c| c.cards.len()).max().unwrap_or(0);
+        for i in 0..height {
+            rows.add_row(columns.iter().map(|column| match column.cards.get(i) {
+                Some(card) => Cell::new(format!(
+                    "{} {}",
+                    card.handle
+                        .clone()
+                        .unwrap_or_else(|| table::short(&card.id.to_string(), 8)),
+                    card.title
+                )),
+                None => Cell::new(""),
+            }));
+        }
+        table::print(&rows);
+        Ok(())
+    }
+}
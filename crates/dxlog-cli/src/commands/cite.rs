@@ -0,0 +1,27 @@
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use dxlog::citekeys;
+
+use crate::completion;
+
+#[derive(clap::Args, Clone)]
+pub struct CiteArgs {
+    /// IDs of the literature entries (can be partial)
+    #[arg(
+        required = true,
+        help = "Unique identifiers or first few characters of the literature IDs",
+        add = ArgValueCompleter::new(completion::literature_ids)
+    )]
+    ids: Vec<String>,
+}
+
+impl CiteArgs {
+    pub fn execute(&self) -> Result<()> {
+        let keys: Vec<String> = citekeys(&self.ids)?
+            .into_iter()
+            .map(|key| format!("@{}", key))
+            .collect();
+        println!("[{}]", keys.join("; "));
+        Ok(())
+    }
+}
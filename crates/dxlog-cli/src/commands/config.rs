@@ -0,0 +1,62 @@
+use anyhow::Result;
+use dxlog::{get_config_value, set_config_value, show_config};
+
+#[derive(clap::Subcommand, Clone)]
+pub enum ConfigCommands {
+    /// Print the value of a config key
+    ///
+    /// Keys use dots for sections. Settings missing from dxlog.toml print their default.
+    ///
+    /// Examples:
+    ///   dxlog config get stale-days
+    ///   dxlog config get storage.active-dir
+    Get {
+        /// Config key
+        #[arg(help = "Dotted config key (e.g. storage.archive-dir)")]
+        key: String,
+    },
+
+    /// Change a config value
+    ///
+    /// The value is validated before dxlog.toml is written. Storage and template
+    /// paths must stay inside the repository.
+    ///
+    /// Examples:
+    ///   dxlog config set stale-days 30
+    ///   dxlog config set storage.archive-dir archive/old
+    Set {
+        /// Config key
+        #[arg(help = "Dotted config key (e.g. storage.archive-dir)")]
+        key: String,
+
+        /// New value
+        #[arg(help = "Value to store")]
+        value: String,
+    },
+
+    /// Show the effective configuration
+    ///
+    /// Example:
+    ///   dxlog config show
+    Show,
+}
+
+impl ConfigCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Get { key } => {
+                println!("{}", get_config_value(key)?);
+                Ok(())
+            }
+            Self::Set { key, value } => {
+                set_config_value(key, value)?;
+                println!("Set {} = {}", key, value);
+                Ok(())
+            }
+            Self::Show => {
+                print!("{}", show_config()?);
+                Ok(())
+            }
+        }
+    }
+}
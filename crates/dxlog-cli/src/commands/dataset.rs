@@ -0,0 +1,548 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{
+    create_dataset, format_size, list_datasets_in, parse_size, rename_dataset,
+    update_dataset_source, update_dataset_status, utils::TagFilter, DatasetSource, DatasetStatus,
+    ListFilter, SortKey, TextQuery,
+};
+
+use super::{print_renamed, read_body};
+use crate::confirm::{self, Impact};
+use crate::workspace::WorkspaceArgs;
+use crate::{completion, table};
+
+/// Provenance of a dataset, as given on the command line.
+#[derive(clap::Args, Clone)]
+pub struct SourceArgs {
+    /// Where the data comes from
+    #[arg(
+        long,
+        help_heading = "PROVENANCE",
+        help = "URL the data was downloaded from"
+    )]
+    url: Option<String>,
+
+    /// License of the data
+    #[arg(
+        long,
+        help_heading = "PROVENANCE",
+        help = "License the data is published under, e.g. CC-BY-4.0"
+    )]
+    license: Option<String>,
+
+    /// Release of the data
+    #[arg(
+        long = "version",
+        value_name = "VERSION",
+        help_heading = "PROVENANCE",
+        help = "Release or version of the data, e.g. 2012 or v2.1"
+    )]
+    data_version: Option<String>,
+
+    /// Checksum of the data
+    #[arg(
+        long,
+        help_heading = "PROVENANCE",
+        help = "Checksum identifying the exact data, e.g. sha256:9f86d0..."
+    )]
+    hash: Option<String>,
+
+    /// Local copy of the data
+    #[arg(
+        long,
+        help_heading = "PROVENANCE",
+        help = "Local copy of the data, relative to the repository root"
+    )]
+    path: Option<PathBuf>,
+
+    /// Size of the data
+    #[arg(
+        long,
+        value_parser = parse_size,
+        help_heading = "PROVENANCE",
+        help = "Size of the data, e.g. 700M or 1.5GB (default: measured from --path)"
+    )]
+    size: Option<u64>,
+}
+
+impl SourceArgs {
+    fn is_empty(&self) -> bool {
+        self.url.is_none()
+            && self.license.is_none()
+            && self.data_version.is_none()
+            && self.hash.is_none()
+            && self.path.is_none()
+            && self.size.is_none()
+    }
+
+    /// Overwrites the fields of `source` that were given.
+    fn apply(&self, source: &mut DatasetSource) {
+        let fields = [
+            (&self.url, &mut source.url),
+            (&self.license, &mut source.license),
+            (&self.data_version, &mut source.version),
+            (&self.hash, &mut source.hash),
+        ];
+        for (given, field) in fields {
+            if given.is_some() {
+                field.clone_from(given);
+            }
+        }
+        if self.path.is_some() {
+            source.path.clone_from(&self.path);
+        }
+        if self.size.is_some() {
+            source.size = self.size;
+        }
+    }
+}
+
+#[derive(clap::Subcommand, Clone)]
+pub enum DatasetCommands {
+    /// Register a dataset
+    ///
+    /// Creates a dataset entry in the 'candidate' status, recording where the
+    /// data comes from. Link hypotheses to the datasets they use with
+    /// `dxlog reference add <hypothesis> <dataset> --kind uses`.
+    ///
+    /// Examples:
+    ///   dxlog dataset new "ImageNet" --url https://image-net.org --license custom --version 2012
+    ///   dxlog dataset new "Cavity scans" --path data/cavity-scans --hash sha256:9f86d0 -t optics
+    ///   dxlog dataset new "Census extract" --size 1.5GB --body-file notes.md
+    New {
+        /// Name of the dataset
+        #[arg(help = "Name of the dataset, used as the entry title")]
+        name: String,
+
+        #[command(flatten)]
+        source: SourceArgs,
+
+        /// Tags for categorization
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "ORGANIZATION",
+            help = "Comma-separated list of tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+
+        /// Named template to render the entry from
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Template from [templates.named] (default: chosen by tag, else the default template)",
+            add = ArgValueCompleter::new(completion::template_names)
+        )]
+        template: Option<String>,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Create the entry even if another dataset already has this name"
+        )]
+        allow_duplicate_title: bool,
+
+        /// Initial content of the entry
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "stdin",
+            help = "Fill the entry body with the contents of this file"
+        )]
+        body_file: Option<PathBuf>,
+
+        /// Read the initial content from standard input
+        #[arg(long, help = "Fill the entry body with text read from standard input")]
+        stdin: bool,
+    },
+
+    /// Update the provenance of a dataset
+    ///
+    /// Only the given fields change, e.g. after downloading a new release.
+    ///
+    /// Example:
+    ///   dxlog dataset update D-003 --version 2024.1 --hash sha256:2c26b4
+    Update {
+        /// ID of the dataset (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::dataset_ids)
+        )]
+        id: String,
+
+        #[command(flatten)]
+        source: SourceArgs,
+    },
+
+    /// Mark a dataset as in use
+    ///
+    /// Example:
+    ///   dxlog dataset use D-003
+    Use {
+        /// ID of the dataset (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::candidate_dataset_ids)
+        )]
+        id: String,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Mark a dataset as deprecated
+    ///
+    /// The entry stays in the registry so results obtained with the data can
+    /// still be traced back to it.
+    ///
+    /// Example:
+    ///   dxlog dataset deprecate D-003
+    Deprecate {
+        /// ID of the dataset (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::dataset_ids)
+        )]
+        id: String,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Change the name of a dataset
+    ///
+    /// Renames the file to match the new name (staging the rename if the file
+    /// is tracked by git) and updates links to it in other entries.
+    ///
+    /// Example:
+    ///   dxlog dataset rename D-003 "ImageNet-1k"
+    Rename {
+        /// ID of the dataset (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::dataset_ids)
+        )]
+        id: String,
+
+        /// New name
+        #[arg(help = "The new name of the dataset")]
+        name: String,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Rename the entry even if another dataset already has this name"
+        )]
+        allow_duplicate_title: bool,
+    },
+
+    /// List datasets with optional filters
+    ///
+    /// Examples:
+    ///   dxlog dataset list
+    ///   dxlog dataset list --status in_use
+    ///   dxlog dataset list --tags optics --sort title
+    List {
+        /// Filter by dataset status
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only datasets with specified status"
+        )]
+        status: Option<DatasetStatus>,
+
+        /// Filter by tags
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only entries with specified tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+
+        /// Filter by alternative tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only entries with at least one of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        any_tag: Vec<String>,
+
+        /// Filter out tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Hide entries with any of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        exclude_tag: Vec<String>,
+
+        /// Filter by author
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created by this author (name or email)"
+        )]
+        author: Option<String>,
+
+        /// Filter by project
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries assigned to this project",
+            add = ArgValueCompleter::new(completion::projects)
+        )]
+        project: Option<String>,
+
+        /// Only entries created on or after this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created on or after this date (YYYY-MM-DD)"
+        )]
+        since: Option<NaiveDate>,
+
+        /// Only entries created on or before this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created on or before this date (YYYY-MM-DD)"
+        )]
+        until: Option<NaiveDate>,
+
+        /// Only entries whose title contains this text
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries whose title contains this text, ignoring case"
+        )]
+        query: Option<String>,
+
+        /// Treat the query as a regular expression
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Treat --query as a regular expression"
+        )]
+        regex: bool,
+
+        /// Search the body as well as the title
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Match --query against the body as well as the title"
+        )]
+        body: bool,
+
+        /// Sort order
+        #[arg(
+            long,
+            default_value = "date",
+            help_heading = "ORDERING",
+            help = "Field to sort entries by"
+        )]
+        sort: SortKey,
+
+        /// Reverse the sort order
+        #[arg(long, help_heading = "ORDERING", help = "Reverse the sort order")]
+        reverse: bool,
+
+        /// Maximum number of results
+        #[arg(
+            short,
+            long,
+            help_heading = "ORDERING",
+            help = "Show at most this many entries"
+        )]
+        limit: Option<usize>,
+
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
+    },
+}
+
+impl DatasetCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::New {
+                name,
+                source,
+                tags,
+                template,
+                allow_duplicate_title,
+                body_file,
+                stdin,
+            } => {
+                let body = read_body(body_file.as_deref(), *stdin)?;
+                let mut dataset_source = DatasetSource::default();
+                source.apply(&mut dataset_source);
+                let dataset = create_dataset(
+                    name,
+                    dataset_source,
+                    tags.clone(),
+                    body.as_deref(),
+                    template.as_deref(),
+                    *allow_duplicate_title,
+                )?;
+                println!(
+                    "New Dataset \"{}\" ({}) created with id: {}",
+                    dataset.base.title,
+                    dataset.base.handle.as_deref().unwrap_or_default(),
+                    dataset.base.id
+                );
+                Ok(())
+            }
+            Self::Update { id, source } => {
+                if source.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "Nothing to update. Give at least one of --url, --license, --version, --hash, --path or --size"
+                    ));
+                }
+                confirm::require(
+                    &format!("Update the provenance of Dataset {}?", id),
+                    Impact::Change,
+                )?;
+                let dataset = update_dataset_source(id, |s| source.apply(s))?;
+                println!("Updated Dataset \"{}\"", dataset.base.title);
+                Ok(())
+            }
+            Self::Use { id, reason, force } => {
+                confirm::require(&format!("Mark Dataset {} as in use?", id), Impact::Change)?;
+                update_dataset_status(id, DatasetStatus::InUse, reason.as_deref(), *force)?;
+                println!("Update Dataset {}; Status => InUse", id);
+                Ok(())
+            }
+            Self::Deprecate { id, reason, force } => {
+                confirm::require(&format!("Deprecate Dataset {}?", id), Impact::Change)?;
+                update_dataset_status(id, DatasetStatus::Deprecated, reason.as_deref(), *force)?;
+                println!("Update Dataset {}; Status => Deprecated", id);
+                Ok(())
+            }
+            Self::Rename {
+                id,
+                name,
+                allow_duplicate_title,
+            } => {
+                confirm::require(
+                    &format!("Rename Dataset {} to \"{}\"?", id, name),
+                    Impact::Change,
+                )?;
+                let renamed = rename_dataset(id, name, *allow_duplicate_title)?;
+                print_renamed("Dataset", &renamed.log.base.title, &renamed);
+                Ok(())
+            }
+            Self::List {
+                status,
+                tags,
+                any_tag,
+                exclude_tag,
+                author,
+                project,
+                since,
+                until,
+                query,
+                regex,
+                body,
+                sort,
+                reverse,
+                limit,
+                workspace,
+            } => {
+                let filter = ListFilter {
+                    status: status.clone(),
+                    tags: TagFilter {
+                        all: tags.clone().unwrap_or_default(),
+                        any: any_tag.clone(),
+                        none: exclude_tag.clone(),
+                    },
+                    author: author.clone(),
+                    project: project.clone(),
+                    since: *since,
+                    until: *until,
+                    query: query
+                        .as_deref()
+                        .map(|query| TextQuery::new(query, *regex, *body))
+                        .transpose()?,
+                    sort: *sort,
+                    reverse: *reverse,
+                    limit: *limit,
+                    ..Default::default()
+                };
+                let mut rows = table::new(&[
+                    "DATASET ID",
+                    "NAME",
+                    "STATUS",
+                    "VERSION",
+                    "LICENSE",
+                    "SIZE",
+                    "CREATED",
+                ]);
+
+                let results = workspace.each_repo(|config| {
+                    Ok((
+                        list_datasets_in(config, &filter)?,
+                        config.date_format.clone(),
+                    ))
+                })?;
+                for (repo, (entries, date_format)) in results {
+                    for entry in entries {
+                        let id = entry.base.id.to_string();
+                        let created = entry.base.display_date(&date_format);
+                        let source = &entry.source;
+
+                        rows.add_row(vec![
+                            table::dim(&table::repo_entry_id(
+                                repo.as_deref(),
+                                &id,
+                                entry.base.handle.as_deref(),
+                            )),
+                            Cell::new(&entry.base.title),
+                            table::status(&entry.status.to_string()),
+                            Cell::new(source.version.as_deref().unwrap_or_default()),
+                            Cell::new(source.license.as_deref().unwrap_or_default()),
+                            Cell::new(source.size.map(format_size).unwrap_or_default()),
+                            Cell::new(created),
+                        ]);
+                    }
+                }
+                table::print(&rows);
+                Ok(())
+            }
+        }
+    }
+}
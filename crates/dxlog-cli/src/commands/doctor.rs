@@ -0,0 +1,36 @@
+use anyhow::Result;
+use dxlog::{load_config, verify_attachments, AttachmentIssue};
+
+#[derive(clap::Args, Clone)]
+pub struct DoctorArgs {}
+
+impl DoctorArgs {
+    pub fn execute(&self) -> Result<()> {
+        let config = load_config()?;
+        let check = verify_attachments(&config)?;
+        println!(
+            "Checked {} attachment{}",
+            check.checked,
+            if check.checked == 1 { "" } else { "s" }
+        );
+        if check.problems.is_empty() {
+            return Ok(());
+        }
+        for problem in &check.problems {
+            let issue = match problem.issue {
+                AttachmentIssue::Missing => "missing",
+                AttachmentIssue::Modified => "modified",
+            };
+            let path = problem
+                .path
+                .strip_prefix(&config.root)
+                .unwrap_or(&problem.path);
+            println!("  {:<9}{}  {}", issue, problem.entry, path.display());
+        }
+        Err(anyhow::anyhow!(
+            "{} attachment{} failed verification",
+            check.problems.len(),
+            if check.problems.len() == 1 { "" } else { "s" }
+        ))
+    }
+}
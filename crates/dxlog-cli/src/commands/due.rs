@@ -0,0 +1,45 @@
+use anyhow::Result;
+use comfy_table::Cell;
+use dxlog::{due_items, load_config};
+
+use crate::table;
+
+#[derive(clap::Args, Clone)]
+pub struct DueArgs {
+    /// Only upcoming entries due within this many days
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        help = "Only show upcoming entries due within N days (overdue entries are always shown)"
+    )]
+    days: Option<i64>,
+}
+
+impl DueArgs {
+    pub fn execute(&self) -> Result<()> {
+        let items = due_items(self.days)?;
+        if items.is_empty() {
+            println!("Nothing due");
+            return Ok(());
+        }
+
+        let date_format = load_config()?.date_format;
+        let mut rows = table::new(&["DUE", "REMAINING", "TYPE", "ID", "TITLE", "STATUS"]);
+        for item in items {
+            rows.add_row(vec![
+                Cell::new(item.due.format(&date_format)),
+                Cell::new(table::remaining(item.days_remaining)),
+                Cell::new(item.kind),
+                table::dim(&table::entry_id(
+                    &item.id.to_string(),
+                    item.handle.as_deref(),
+                )),
+                Cell::new(&item.title),
+                table::status(&item.status),
+            ]);
+        }
+        table::print(&rows);
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use dxlog::{decrypt_entry, encrypt_entry};
+
+use crate::completion;
+
+#[derive(clap::Args, Clone)]
+pub struct EncryptArgs {
+    /// ID of the entry (can be partial)
+    #[arg(
+        help = "Unique identifier or first few characters of the entry ID",
+        add = ArgValueCompleter::new(completion::any_ids)
+    )]
+    id: String,
+}
+
+impl EncryptArgs {
+    pub fn execute(&self) -> Result<()> {
+        let path = encrypt_entry(&self.id)?;
+        println!("Encrypted the body of {}", path.display());
+        Ok(())
+    }
+}
+
+#[derive(clap::Args, Clone)]
+pub struct DecryptArgs {
+    /// ID of the entry (can be partial)
+    #[arg(
+        help = "Unique identifier or first few characters of the entry ID",
+        add = ArgValueCompleter::new(completion::any_ids)
+    )]
+    id: String,
+}
+
+impl DecryptArgs {
+    pub fn execute(&self) -> Result<()> {
+        let path = decrypt_entry(&self.id)?;
+        println!("Stored the body of {} in plain text", path.display());
+        Ok(())
+    }
+}
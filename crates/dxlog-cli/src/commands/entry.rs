@@ -0,0 +1,236 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{
+    create_custom_entry, list_custom_entries, load_config, update_custom_entry_status,
+    utils::TagFilter,
+};
+
+use super::read_body;
+use crate::confirm::{self, Impact};
+use crate::{completion, table};
+
+#[derive(clap::Args, Clone)]
+pub struct EntryArgs {
+    /// Log type declared under [types]
+    #[arg(
+        value_name = "TYPE",
+        help = "Name of a log type declared under [types] in dxlog.toml",
+        add = ArgValueCompleter::new(completion::log_types)
+    )]
+    kind: String,
+
+    #[command(subcommand)]
+    command: EntryCommands,
+}
+
+#[derive(clap::Subcommand, Clone)]
+pub enum EntryCommands {
+    /// Create a new entry
+    ///
+    /// The entry starts in the first status of its type and is rendered from
+    /// the type's template.
+    ///
+    /// Examples:
+    ///   dxlog entry protocol new "PCR Amplification" --tags wetlab
+    ///   dxlog entry protocol new "Western Blot" --body-file blot.md
+    New {
+        /// Title of the entry
+        #[arg(help = "The main title of your entry")]
+        title: String,
+
+        /// Tags for categorization
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "ORGANIZATION",
+            help = "Comma-separated list of tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Create the entry even if one of the same type already has this title"
+        )]
+        allow_duplicate_title: bool,
+
+        /// Initial content of the entry
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "stdin",
+            help = "Fill the entry body with the contents of this file"
+        )]
+        body_file: Option<PathBuf>,
+
+        /// Read the initial content from standard input
+        #[arg(long, help = "Fill the entry body with text read from standard input")]
+        stdin: bool,
+    },
+
+    /// List entries
+    ///
+    /// Examples:
+    ///   dxlog entry protocol list
+    ///   dxlog entry protocol list --status validated --tags wetlab
+    List {
+        /// Filter by entry status
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries with specified status",
+            add = ArgValueCompleter::new(completion::log_type_statuses)
+        )]
+        status: Option<String>,
+
+        /// Filter by tags
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only entries with specified tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+
+        /// Filter by alternative tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only entries with at least one of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        any_tag: Vec<String>,
+
+        /// Filter out tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Hide entries with any of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        exclude_tag: Vec<String>,
+    },
+
+    /// Change the status of an entry
+    ///
+    /// Moves the entry to the directory [storage.layout] gives the new status,
+    /// if any.
+    ///
+    /// Example:
+    ///   dxlog entry protocol status P-003 validated
+    Status {
+        /// ID of the entry (can be partial)
+        #[arg(help = "Unique identifier or first few characters of the entry ID")]
+        id: String,
+
+        /// New status
+        #[arg(
+            help = "One of the statuses of the type in [types]",
+            add = ArgValueCompleter::new(completion::log_type_statuses)
+        )]
+        status: String,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+}
+
+impl EntryArgs {
+    pub fn execute(&self) -> Result<()> {
+        let kind = &self.kind;
+        match &self.command {
+            EntryCommands::New {
+                title,
+                tags,
+                allow_duplicate_title,
+                body_file,
+                stdin,
+            } => {
+                let body = read_body(body_file.as_deref(), *stdin)?;
+                let (entry, _) = create_custom_entry(
+                    kind,
+                    title,
+                    tags.clone(),
+                    body.as_deref(),
+                    *allow_duplicate_title,
+                )?;
+                println!(
+                    "New {} \"{}\" ({}) created with id: {}",
+                    kind,
+                    entry.base.title,
+                    entry.base.handle.as_deref().unwrap_or_default(),
+                    entry.base.id
+                );
+                Ok(())
+            }
+            EntryCommands::List {
+                status,
+                tags,
+                any_tag,
+                exclude_tag,
+            } => {
+                let tags = TagFilter {
+                    all: tags.clone().unwrap_or_default(),
+                    any: any_tag.clone(),
+                    none: exclude_tag.clone(),
+                };
+                let header = format!("{} ID", kind.to_uppercase());
+                let mut rows =
+                    table::new(&[&header, "TITLE", "STATUS", "CREATED", "AUTHOR", "TAGS"]);
+
+                let entries = list_custom_entries(kind, status.as_deref(), &tags)?;
+                let date_format = load_config()?.date_format;
+                for (entry, _) in entries {
+                    let id = entry.base.id.to_string();
+                    let created = entry.base.display_date(&date_format);
+                    let tags = entry.base.tags.into_iter().collect::<Vec<_>>().join(", ");
+
+                    rows.add_row(vec![
+                        table::dim(&table::entry_id(&id, entry.base.handle.as_deref())),
+                        Cell::new(&entry.base.title),
+                        table::status(&entry.status),
+                        Cell::new(created),
+                        Cell::new(&entry.base.created_by.name),
+                        Cell::new(tags),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            EntryCommands::Status {
+                id,
+                status,
+                reason,
+                force,
+            } => {
+                confirm::require(
+                    &format!("Mark {} {} as {}?", kind, id, status),
+                    Impact::Change,
+                )?;
+                let (entry, _) =
+                    update_custom_entry_status(kind, id, status, reason.as_deref(), *force)?;
+                println!("Update {} {}; Status => {}", kind, id, entry.status);
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,790 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{
+    add_experiment_metrics, attach_experiment_notebook, capture_environment, compare_experiments,
+    create_experiment, find_experiment, link_experiment_run, list_experiments_in, parse_metric,
+    read_metrics_file, rename_experiment, update_experiment_status, utils::TagFilter,
+    ExperimentLog, ExperimentStatus, ListFilter, SortKey, TextQuery, TrackerAuth,
+};
+
+use super::{print_renamed, read_body};
+use crate::confirm::{self, Impact};
+use crate::workspace::WorkspaceArgs;
+use crate::{completion, table};
+
+#[derive(clap::Subcommand, Clone)]
+pub enum ExperimentCommands {
+    /// Plan a new experiment
+    ///
+    /// Records the commit checked out in the repository, its branch and
+    /// whether tracked files have uncommitted changes, so the results can be
+    /// traced back to the code that produced them. With --lockfile, a hash of
+    /// the dependency lockfile is recorded as well.
+    ///
+    /// Examples:
+    ///   dxlog experiment new "Baseline on the 2024 runs"
+    ///   dxlog experiment new "Larger batch" --lockfile Cargo.lock
+    ///   dxlog experiment new "Fit in the analysis repo" --repo ../analysis --lockfile requirements.txt
+    New {
+        /// Title of the experiment
+        #[arg(help = "Title of the experiment, e.g. \"Baseline on the 2024 runs\"")]
+        title: String,
+
+        /// Dependency lockfile to hash
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "no_env",
+            help = "Lockfile to record a hash of, relative to the code repository (e.g. Cargo.lock)"
+        )]
+        lockfile: Option<PathBuf>,
+
+        /// Repository holding the code
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "no_env",
+            help = "Git repository of the code, relative to the dxlog root (default: the dxlog repository)"
+        )]
+        repo: Option<PathBuf>,
+
+        /// Skip recording the code state
+        #[arg(long, help = "Do not record the commit, branch or lockfile")]
+        no_env: bool,
+
+        /// Tags for categorization
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help = "Comma-separated list of tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+
+        /// Named template to render the entry from
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Template from [templates.named] (default: chosen by tag, else the default template)",
+            add = ArgValueCompleter::new(completion::template_names)
+        )]
+        template: Option<String>,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Create the entry even if another experiment already has this title"
+        )]
+        allow_duplicate_title: bool,
+
+        /// Initial content of the entry
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "stdin",
+            help = "Fill the goal with the contents of this file"
+        )]
+        body_file: Option<PathBuf>,
+
+        /// Read the initial content from standard input
+        #[arg(long, help = "Fill the goal with text read from standard input")]
+        stdin: bool,
+    },
+
+    /// Mark an experiment as running
+    ///
+    /// Example:
+    ///   dxlog experiment start E-003
+    Start {
+        /// ID of the experiment (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::open_experiment_ids)
+        )]
+        id: String,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Mark an experiment as completed
+    ///
+    /// Example:
+    ///   dxlog experiment complete E-003
+    Complete {
+        /// ID of the experiment (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::open_experiment_ids)
+        )]
+        id: String,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Mark an experiment as failed
+    ///
+    /// Example:
+    ///   dxlog experiment fail E-003
+    Fail {
+        /// ID of the experiment (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::open_experiment_ids)
+        )]
+        id: String,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Show the code state recorded for an experiment
+    ///
+    /// Example:
+    ///   dxlog experiment env E-003
+    Env {
+        /// ID of the experiment (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::experiment_ids)
+        )]
+        id: String,
+    },
+
+    /// Record and show the metrics of an experiment
+    Metric {
+        #[command(subcommand)]
+        command: MetricCommands,
+    },
+
+    /// Link an experiment to its run on W&B or MLflow
+    ///
+    /// Stores the URL of the run in the entry. With --fetch-metrics, the final
+    /// metrics of the run are recorded as well, as by `metric add`: the run
+    /// summary on W&B, or the latest value of each metric on MLflow.
+    ///
+    /// W&B needs an API key (https://wandb.ai/authorize), passed with
+    /// --wandb-api-key or WANDB_API_KEY. MLflow servers that require a token
+    /// get it from --mlflow-token or MLFLOW_TRACKING_TOKEN.
+    ///
+    /// Examples:
+    ///   dxlog experiment link-run E-003 https://wandb.ai/ada/cavity/runs/3kx9q2ab
+    ///   dxlog experiment link-run E-003 http://mlflow.lab:5000/#/experiments/4/runs/9f1c2e --fetch-metrics
+    LinkRun {
+        /// ID of the experiment (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::experiment_ids)
+        )]
+        id: String,
+
+        /// URL of the run
+        #[arg(help = "URL of the run in the W&B or MLflow web UI")]
+        url: String,
+
+        /// Record the final metrics of the run
+        #[arg(long, help = "Fetch the final metrics of the run into the entry")]
+        fetch_metrics: bool,
+
+        /// W&B API key
+        #[arg(
+            long,
+            env = "WANDB_API_KEY",
+            hide_env_values = true,
+            help = "W&B API key, to fetch metrics from W&B"
+        )]
+        wandb_api_key: Option<String>,
+
+        /// MLflow token
+        #[arg(
+            long,
+            env = "MLFLOW_TRACKING_TOKEN",
+            hide_env_values = true,
+            help = "Token for MLflow servers that require one"
+        )]
+        mlflow_token: Option<String>,
+    },
+
+    /// Attach a Jupyter notebook to an experiment
+    ///
+    /// Copies the notebook into the attachments directory, under the handle of
+    /// the experiment, and records it with its hash in the entry. With --link
+    /// the notebook stays where it is and only its path and hash are recorded.
+    /// --render also stores a self-contained HTML export, which stays readable
+    /// without Jupyter.
+    ///
+    /// Examples:
+    ///   dxlog experiment attach-notebook E-003 analysis/fit.ipynb
+    ///   dxlog experiment attach-notebook E-003 analysis/fit.ipynb --link --render
+    AttachNotebook {
+        /// ID of the experiment (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::experiment_ids)
+        )]
+        id: String,
+
+        /// Notebook to attach
+        #[arg(help = "Path to the .ipynb file")]
+        path: PathBuf,
+
+        /// Link instead of copying
+        #[arg(long, help = "Record the notebook where it lies instead of copying it")]
+        link: bool,
+
+        /// Store an HTML export
+        #[arg(long, help = "Also store an HTML export of the notebook")]
+        render: bool,
+    },
+
+    /// Compare the metrics of two experiments
+    ///
+    /// Shows every metric recorded on either experiment, with the change from
+    /// the first to the second.
+    ///
+    /// Example:
+    ///   dxlog experiment compare E-003 E-004
+    Compare {
+        /// ID of the baseline experiment (can be partial)
+        #[arg(
+            help = "Experiment to compare against",
+            add = ArgValueCompleter::new(completion::experiment_ids)
+        )]
+        a: String,
+
+        /// ID of the other experiment (can be partial)
+        #[arg(
+            help = "Experiment compared to the first one",
+            add = ArgValueCompleter::new(completion::experiment_ids)
+        )]
+        b: String,
+    },
+
+    /// Change the title of an experiment
+    ///
+    /// Renames the file to match the new title (staging the rename if the file
+    /// is tracked by git) and updates links to it in other entries.
+    ///
+    /// Example:
+    ///   dxlog experiment rename E-003 "Baseline on the 2024 and 2025 runs"
+    Rename {
+        /// ID of the experiment (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::experiment_ids)
+        )]
+        id: String,
+
+        /// New title
+        #[arg(help = "The new title of the experiment")]
+        title: String,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Rename the entry even if another experiment already has this title"
+        )]
+        allow_duplicate_title: bool,
+    },
+
+    /// List experiments with optional filters
+    ///
+    /// Examples:
+    ///   dxlog experiment list
+    ///   dxlog experiment list --status running
+    List {
+        /// Filter by experiment status
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only experiments with specified status"
+        )]
+        status: Option<ExperimentStatus>,
+
+        /// Filter by tags
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only entries with specified tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+
+        /// Filter by alternative tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only entries with at least one of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        any_tag: Vec<String>,
+
+        /// Filter out tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Hide entries with any of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        exclude_tag: Vec<String>,
+
+        /// Filter by author
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created by this author (name or email)"
+        )]
+        author: Option<String>,
+
+        /// Filter by project
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries assigned to this project",
+            add = ArgValueCompleter::new(completion::projects)
+        )]
+        project: Option<String>,
+
+        /// Only entries created on or after this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created on or after this date (YYYY-MM-DD)"
+        )]
+        since: Option<NaiveDate>,
+
+        /// Only entries created on or before this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created on or before this date (YYYY-MM-DD)"
+        )]
+        until: Option<NaiveDate>,
+
+        /// Only entries whose title contains this text
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries whose title contains this text, ignoring case"
+        )]
+        query: Option<String>,
+
+        /// Treat the query as a regular expression
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Treat --query as a regular expression"
+        )]
+        regex: bool,
+
+        /// Search the body as well as the title
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Match --query against the body as well as the title"
+        )]
+        body: bool,
+
+        /// Sort order
+        #[arg(
+            long,
+            default_value = "date",
+            help_heading = "ORDERING",
+            help = "Field to sort entries by"
+        )]
+        sort: SortKey,
+
+        /// Reverse the sort order
+        #[arg(long, help_heading = "ORDERING", help = "Reverse the sort order")]
+        reverse: bool,
+
+        /// Maximum number of results
+        #[arg(
+            short,
+            long,
+            help_heading = "ORDERING",
+            help = "Show at most this many entries"
+        )]
+        limit: Option<usize>,
+
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
+    },
+}
+
+#[derive(clap::Subcommand, Clone)]
+pub enum MetricCommands {
+    /// Record metrics on an experiment
+    ///
+    /// Metrics are given as name=value pairs, or read from a JSON object or
+    /// from the last row of a CSV file with a header row. A metric recorded
+    /// again replaces the earlier value.
+    ///
+    /// Examples:
+    ///   dxlog experiment metric add E-003 accuracy=0.91 loss=0.12
+    ///   dxlog experiment metric add E-003 --file results/metrics.json
+    ///   dxlog experiment metric add E-003 --file logs/train.csv
+    Add {
+        /// ID of the experiment (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::experiment_ids)
+        )]
+        id: String,
+
+        /// Metrics as name=value
+        #[arg(
+            value_name = "NAME=VALUE",
+            required_unless_present = "file",
+            help = "Metrics to record, e.g. accuracy=0.91"
+        )]
+        metrics: Vec<String>,
+
+        /// File to read metrics from
+        #[arg(
+            short,
+            long,
+            value_name = "PATH",
+            help = "JSON or CSV file to read metrics from"
+        )]
+        file: Option<PathBuf>,
+    },
+
+    /// List the metrics of an experiment
+    ///
+    /// Example:
+    ///   dxlog experiment metric list E-003
+    List {
+        /// ID of the experiment (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::experiment_ids)
+        )]
+        id: String,
+    },
+}
+
+impl MetricCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Add { id, metrics, file } => {
+                let mut values = match file {
+                    Some(file) => read_metrics_file(file)?,
+                    None => BTreeMap::new(),
+                };
+                for metric in metrics {
+                    let (name, value) = parse_metric(metric)?;
+                    values.insert(name, value);
+                }
+                let count = values.len();
+                let experiment = add_experiment_metrics(id, values)?;
+                println!(
+                    "Recorded {} metric{} on Experiment \"{}\"",
+                    count,
+                    if count == 1 { "" } else { "s" },
+                    experiment.base.title
+                );
+                Ok(())
+            }
+            Self::List { id } => {
+                let experiment = find_experiment(id)?;
+                let mut rows = table::new(&["METRIC", "VALUE"]);
+                for (name, value) in &experiment.metrics {
+                    rows.add_row(vec![Cell::new(name), Cell::new(value)]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ExperimentCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::New {
+                title,
+                lockfile,
+                repo,
+                no_env,
+                tags,
+                template,
+                allow_duplicate_title,
+                body_file,
+                stdin,
+            } => {
+                let body = read_body(body_file.as_deref(), *stdin)?;
+                let environment = if *no_env {
+                    None
+                } else {
+                    Some(capture_environment(repo.as_deref(), lockfile.as_deref())?)
+                };
+                let experiment = create_experiment(
+                    title,
+                    environment,
+                    tags.clone(),
+                    body.as_deref(),
+                    template.as_deref(),
+                    *allow_duplicate_title,
+                )?;
+                println!(
+                    "New Experiment \"{}\" ({}) created with id: {}",
+                    experiment.base.title,
+                    experiment.base.handle.as_deref().unwrap_or_default(),
+                    experiment.base.id
+                );
+                if let Some(environment) = &experiment.environment {
+                    println!("Recorded code state: {}", environment.describe());
+                }
+                Ok(())
+            }
+            Self::Start { id, reason, force } => {
+                update_experiment_status(id, ExperimentStatus::Running, reason.as_deref(), *force)?;
+                println!("Update Experiment {}; Status => Running", id);
+                Ok(())
+            }
+            Self::Complete { id, reason, force } => {
+                confirm::require(
+                    &format!("Mark Experiment {} as completed?", id),
+                    Impact::Change,
+                )?;
+                update_experiment_status(
+                    id,
+                    ExperimentStatus::Completed,
+                    reason.as_deref(),
+                    *force,
+                )?;
+                println!("Update Experiment {}; Status => Completed", id);
+                Ok(())
+            }
+            Self::Fail { id, reason, force } => {
+                confirm::require(
+                    &format!("Mark Experiment {} as failed?", id),
+                    Impact::Change,
+                )?;
+                update_experiment_status(id, ExperimentStatus::Failed, reason.as_deref(), *force)?;
+                println!("Update Experiment {}; Status => Failed", id);
+                Ok(())
+            }
+            Self::Env { id } => {
+                let experiment = find_experiment(id)?;
+                let Some(environment) = experiment.environment else {
+                    println!(
+                        "No code state was recorded for Experiment \"{}\"",
+                        experiment.base.title
+                    );
+                    return Ok(());
+                };
+                println!("Commit:    {}", environment.commit);
+                if let Some(branch) = &environment.branch {
+                    println!("Branch:    {}", branch);
+                }
+                println!(
+                    "Dirty:     {}",
+                    if environment.dirty { "yes" } else { "no" }
+                );
+                if let Some(lockfile) = &environment.lockfile {
+                    println!("Lockfile:  {}", lockfile.display());
+                }
+                if let Some(hash) = &environment.lockfile_hash {
+                    println!("Hash:      {}", hash);
+                }
+                Ok(())
+            }
+            Self::Metric { command } => command.execute(),
+            Self::LinkRun {
+                id,
+                url,
+                fetch_metrics,
+                wandb_api_key,
+                mlflow_token,
+            } => {
+                let auth = TrackerAuth {
+                    wandb_api_key: wandb_api_key.clone(),
+                    mlflow_token: mlflow_token.clone(),
+                };
+                let (experiment, metrics) =
+                    link_experiment_run(id, url, fetch_metrics.then_some(&auth))?;
+                println!("Linked Experiment \"{}\" to {}", experiment.base.title, url);
+                if *fetch_metrics {
+                    println!(
+                        "Recorded {} metric{} from the run",
+                        metrics.len(),
+                        if metrics.len() == 1 { "" } else { "s" }
+                    );
+                }
+                Ok(())
+            }
+            Self::AttachNotebook {
+                id,
+                path,
+                link,
+                render,
+            } => {
+                let (experiment, attachment) =
+                    attach_experiment_notebook(id, path, *link, *render)?;
+                println!(
+                    "{} {} to Experiment \"{}\"",
+                    if *link { "Linked" } else { "Attached" },
+                    attachment.path.display(),
+                    experiment.base.title
+                );
+                println!("Hash:      {}", attachment.hash);
+                if let Some(html) = &attachment.html {
+                    println!("Rendered:  {}", html.display());
+                }
+                Ok(())
+            }
+            Self::Compare { a, b } => {
+                let comparison = compare_experiments(a, b)?;
+                let label = |experiment: &ExperimentLog, id: &str| {
+                    experiment
+                        .base
+                        .handle
+                        .clone()
+                        .unwrap_or_else(|| id.to_string())
+                };
+                let (label_a, label_b) = (label(&comparison.a, a), label(&comparison.b, b));
+                let mut rows = table::new(&["METRIC", &label_a, &label_b, "CHANGE"]);
+                let value = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+                for metric in &comparison.metrics {
+                    rows.add_row(vec![
+                        Cell::new(&metric.name),
+                        Cell::new(value(metric.a)),
+                        Cell::new(value(metric.b)),
+                        Cell::new(metric.display_delta().unwrap_or_default()),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            Self::Rename {
+                id,
+                title,
+                allow_duplicate_title,
+            } => {
+                confirm::require(
+                    &format!("Rename Experiment {} to \"{}\"?", id, title),
+                    Impact::Change,
+                )?;
+                let renamed = rename_experiment(id, title, *allow_duplicate_title)?;
+                print_renamed("Experiment", &renamed.log.base.title, &renamed);
+                Ok(())
+            }
+            Self::List {
+                status,
+                tags,
+                any_tag,
+                exclude_tag,
+                author,
+                project,
+                since,
+                until,
+                query,
+                regex,
+                body,
+                sort,
+                reverse,
+                limit,
+                workspace,
+            } => {
+                let filter = ListFilter {
+                    status: status.clone(),
+                    tags: TagFilter {
+                        all: tags.clone().unwrap_or_default(),
+                        any: any_tag.clone(),
+                        none: exclude_tag.clone(),
+                    },
+                    author: author.clone(),
+                    project: project.clone(),
+                    since: *since,
+                    until: *until,
+                    query: query
+                        .as_deref()
+                        .map(|query| TextQuery::new(query, *regex, *body))
+                        .transpose()?,
+                    sort: *sort,
+                    reverse: *reverse,
+                    limit: *limit,
+                    ..Default::default()
+                };
+                let mut rows =
+                    table::new(&["EXPERIMENT ID", "TITLE", "STATUS", "COMMIT", "CREATED"]);
+
+                let results = workspace.each_repo(|config| {
+                    Ok((
+                        list_experiments_in(config, &filter)?,
+                        config.date_format.clone(),
+                    ))
+                })?;
+                for (repo, (entries, date_format)) in results {
+                    for entry in entries {
+                        let id = entry.base.id.to_string();
+                        let commit = entry
+                            .environment
+                            .as_ref()
+                            .map(|environment| environment.describe())
+                            .unwrap_or_default();
+
+                        rows.add_row(vec![
+                            table::dim(&table::repo_entry_id(
+                                repo.as_deref(),
+                                &id,
+                                entry.base.handle.as_deref(),
+                            )),
+                            Cell::new(&entry.base.title),
+                            table::status(&entry.status.to_string()),
+                            Cell::new(commit),
+                            Cell::new(entry.base.display_date(&date_format)),
+                        ]);
+                    }
+                }
+                table::print(&rows);
+                Ok(())
+            }
+        }
+    }
+}
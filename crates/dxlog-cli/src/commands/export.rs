@@ -0,0 +1,239 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use dxlog::{
+    export_bundle, export_entry, export_feed, export_ical, export_mdbook, export_notion,
+    export_site, BookGrouping, EntryFormat,
+};
+
+use crate::completion;
+
+#[derive(clap::Subcommand, Clone)]
+pub enum ExportCommands {
+    /// Export the knowledge base as a static HTML site
+    ///
+    /// Renders every published knowledge entry and completed literature review
+    /// to HTML, with an index page grouped by tags. References between exported
+    /// entries become links.
+    ///
+    /// Example:
+    ///   dxlog export site --out ./site
+    Site {
+        /// Output directory
+        #[arg(short, long, help = "Directory to write the HTML files to")]
+        out: PathBuf,
+    },
+
+    /// Export the knowledge base as the source of an mdBook
+    ///
+    /// Writes every published knowledge entry and completed literature review
+    /// as a chapter, and a SUMMARY.md arranging them by tag or project, so the
+    /// knowledge base can be published with mdBook's search and theming.
+    /// book.toml is only written if missing, to keep changes to its title and
+    /// theme.
+    ///
+    /// Examples:
+    ///   dxlog export mdbook --out book/
+    ///   dxlog export mdbook --out book/ --group-by project && mdbook build book/
+    Mdbook {
+        /// Output directory
+        #[arg(short, long, help = "Directory of the book (book.toml and src/)")]
+        out: PathBuf,
+
+        /// How chapters are arranged
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = BookGrouping::Tag,
+            help = "Arrange chapters by tag or by project"
+        )]
+        group_by: BookGrouping,
+    },
+
+    /// Export a single entry as a standalone HTML page or PDF
+    ///
+    /// Renders the entry's markdown with its metadata, and lists the entries
+    /// it references in a bibliography, to share one finding outside the
+    /// repository. PDFs are printed with a headless Chromium or Google Chrome
+    /// found on PATH, or the browser named by DXLOG_BROWSER.
+    ///
+    /// Examples:
+    ///   dxlog export entry 8i3j5jkl
+    ///   dxlog export entry 8i3j5jkl --format pdf --out finding.pdf
+    Entry {
+        /// ID of the entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
+        id: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = EntryFormat::Html, help = "Output format")]
+        format: EntryFormat,
+
+        /// Output file
+        #[arg(
+            short,
+            long,
+            help = "File to write (default: the entry's file name with the format's extension)"
+        )]
+        out: Option<PathBuf>,
+    },
+
+    /// Export due dates and milestones as an iCalendar file
+    ///
+    /// Writes an all-day event for each open entry with a due date (as listed
+    /// by `dxlog due`) and for each milestone, to import or subscribe to in a
+    /// calendar app.
+    ///
+    /// Example:
+    ///   dxlog export ical --out research.ics
+    Ical {
+        /// Output file
+        #[arg(short, long, help = "File to write the calendar to (.ics)")]
+        out: PathBuf,
+    },
+
+    /// Export an Atom feed of recently published entries
+    ///
+    /// Lists the most recently published knowledge entries and completed
+    /// literature reviews, newest first, with their full text, so colleagues
+    /// can follow the research log in a feed reader. With --base-url, entries
+    /// link to their pages in the site written by `dxlog export site`.
+    ///
+    /// Examples:
+    ///   dxlog export feed --out feed.xml
+    ///   dxlog export feed --out site/feed.xml --base-url https://lab.example.org/log
+    Feed {
+        /// Output file
+        #[arg(short, long, help = "File to write the feed to (.xml)")]
+        out: PathBuf,
+
+        /// Title of the feed
+        #[arg(
+            long,
+            default_value = "Research log",
+            help = "Title shown by feed readers"
+        )]
+        title: String,
+
+        /// URL the exported site is served from
+        #[arg(long, help = "URL where the output of `dxlog export site` is served")]
+        base_url: Option<String>,
+
+        /// Maximum number of entries
+        #[arg(
+            short = 'n',
+            long,
+            default_value_t = 20,
+            help = "Include at most this many entries"
+        )]
+        limit: usize,
+    },
+
+    /// Export published knowledge to a Notion database
+    ///
+    /// Creates a page per published knowledge entry with its title and body,
+    /// and its tags and status if the database has a multi-select "Tags" and a
+    /// select "Status" property. Entries exported before update their page
+    /// instead of adding another.
+    ///
+    /// Create an integration at https://www.notion.so/my-integrations, share
+    /// the database with it, and pass its token with --token or NOTION_TOKEN.
+    ///
+    /// Example:
+    ///   dxlog export notion --database 8a5bc1d2e3f44a5b9c8d7e6f5a4b3c2d
+    Notion {
+        /// Notion database ID
+        #[arg(long, help = "ID of the Notion database, from its URL")]
+        database: String,
+
+        /// Notion integration token
+        #[arg(
+            long,
+            env = "NOTION_TOKEN",
+            hide_env_values = true,
+            help = "Token of a Notion integration the database is shared with"
+        )]
+        token: String,
+    },
+
+    /// Export every entry to a single JSON file
+    ///
+    /// Writes the frontmatter, references included, and body of every entry
+    /// to a versioned JSON bundle, for backups, moving the repository to
+    /// another machine with `dxlog import bundle`, or analysis in Python.
+    /// Attached files are not included.
+    ///
+    /// Example:
+    ///   dxlog export bundle --out dump.json
+    Bundle {
+        /// Output file
+        #[arg(short, long, help = "File to write the bundle to (.json)")]
+        out: PathBuf,
+    },
+}
+
+impl ExportCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Site { out } => {
+                let count = export_site(out)?;
+                println!("Exported {} entries to {}", count, out.display());
+                Ok(())
+            }
+            Self::Mdbook { out, group_by } => {
+                let count = export_mdbook(out, *group_by)?;
+                println!("Exported {} entries to {}", count, out.display());
+                Ok(())
+            }
+            Self::Entry { id, format, out } => {
+                let path = export_entry(id, *format, out.as_deref())?;
+                println!("Exported {} to {}", id, path.display());
+                Ok(())
+            }
+            Self::Bundle { out } => {
+                let count = export_bundle(out)?;
+                println!("Exported {} entries to {}", count, out.display());
+                Ok(())
+            }
+            Self::Ical { out } => {
+                let count = export_ical(out)?;
+                println!("Exported {} events to {}", count, out.display());
+                Ok(())
+            }
+            Self::Feed {
+                out,
+                title,
+                base_url,
+                limit,
+            } => {
+                let count = export_feed(out, title, base_url.as_deref(), *limit)?;
+                println!("Exported {} entries to {}", count, out.display());
+                Ok(())
+            }
+            Self::Notion { database, token } => {
+                let results = export_notion(token, database)?;
+                let mut failed = 0;
+                for (log, result) in &results {
+                    match result {
+                        Ok(true) => println!("Created page for \"{}\"", log.base.title),
+                        Ok(false) => println!("Updated page for \"{}\"", log.base.title),
+                        Err(e) => {
+                            failed += 1;
+                            eprintln!("Failed to export \"{}\": {:#}", log.base.title, e)
+                        }
+                    }
+                }
+                println!(
+                    "Exported {} entries to Notion, {} failed",
+                    results.len() - failed,
+                    failed
+                );
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,5 +1,22 @@
+use std::path::PathBuf;
+
 use anyhow::Result;
-use dxlog::{create_hypothesis, list_hypotheses, update_hypothesis_status, HypothesisStatus};
+use chrono::NaiveDate;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{
+    add_hypothesis_evidence, assign_hypothesis, assignee_filter, create_hypothesis, is_dry_run,
+    link_hypothesis_commit, list_hypotheses_in, list_hypothesis_commits, list_hypothesis_evidence,
+    remove_hypothesis_evidence, rename_hypothesis, reopen_hypothesis, select_hypotheses,
+    set_hypothesis_due, sync_hypothesis_issues, update_hypothesis_statuses, utils::TagFilter,
+    EvidenceDirection, EvidenceStrength, HypothesisStatus, IssueAction, LinkedCommit, ListFilter,
+    SortKey, TextQuery,
+};
+
+use super::{confirm_status_change, full_ids, print_renamed, read_body, SelectArgs};
+use crate::confirm::{self, Impact};
+use crate::workspace::WorkspaceArgs;
+use crate::{completion, table};
 
 // crates/dxlog-cli/src/commands/hypothesis.rs
 #[derive(clap::Subcommand, Clone)]
@@ -12,6 +29,8 @@ pub enum HypothesisCommands {
     /// Examples:
     ///   dxlog hypothesis new "Quantum error correction impact" --tags quantum,error-correction
     ///   dxlog hypothesis new "FPGA optimization patterns" -t hardware,performance
+    ///   dxlog hypothesis new "Cache-aware tiling" --body-file draft.md
+    ///   dxlog hypothesis new "Sparse attention scaling" --due 2025-06-30
     New {
         /// Title of the hypothesis (wrap in quotes if it contains spaces)
         #[arg(help = "The main title of your hypothesis")]
@@ -23,9 +42,47 @@ pub enum HypothesisCommands {
             long,
             value_delimiter = ',',
             help_heading = "ORGANIZATION",
-            help = "Comma-separated list of tags (e.g., quantum,physics)"
+            help = "Comma-separated list of tags (e.g., quantum,physics)",
+            add = ArgValueCompleter::new(completion::tags)
         )]
         tags: Option<Vec<String>>,
+
+        /// Named template to render the entry from
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Template from [templates.named] (default: chosen by tag, else the default template)",
+            add = ArgValueCompleter::new(completion::template_names)
+        )]
+        template: Option<String>,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Create the entry even if one of the same type already has this title"
+        )]
+        allow_duplicate_title: bool,
+
+        /// Date the hypothesis is due
+        #[arg(
+            long,
+            value_name = "DATE",
+            help = "Date the hypothesis should be resolved by (YYYY-MM-DD); listed by `dxlog due`"
+        )]
+        due: Option<NaiveDate>,
+
+        /// Initial content of the entry
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "stdin",
+            help = "Fill the entry body with the contents of this file"
+        )]
+        body_file: Option<PathBuf>,
+
+        /// Read the initial content from standard input
+        #[arg(long, help = "Fill the entry body with text read from standard input")]
+        stdin: bool,
     },
 
     /// Mark hypothesis as proven
@@ -33,12 +90,33 @@ pub enum HypothesisCommands {
     /// Updates the status of a hypothesis to 'proven' when evidence confirms it.
     /// This will move the hypothesis to the knowledge base.
     ///
-    /// Example:
+    /// Examples:
     ///   dxlog hypothesis proven 1f418cae
+    ///   dxlog hypothesis proven 1f418cae 2a7b9def
     Proven {
-        /// ID of the hypothesis (can be partial)
-        #[arg(help = "Unique identifier or first few characters of the hypothesis ID")]
-        id: String,
+        /// IDs of the hypotheses (can be partial)
+        #[arg(
+            value_name = "ID",
+            required_unless_present = "all",
+            help = "Unique identifiers or first few characters of the hypothesis IDs",
+            add = ArgValueCompleter::new(completion::active_hypothesis_ids)
+        )]
+        ids: Vec<String>,
+
+        #[command(flatten)]
+        select: SelectArgs,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
     },
 
     /// Mark hypothesis as disproven
@@ -46,12 +124,33 @@ pub enum HypothesisCommands {
     /// Updates the status of a hypothesis to 'disproven' when evidence refutes it.
     /// This will move the hypothesis to the knowledge base.
     ///
-    /// Example:
+    /// Examples:
     ///   dxlog hypothesis disproven 2a7b9def
+    ///   dxlog hypothesis disproven 2a7b9def --reason "No effect below 4 K"
     Disproven {
-        /// ID of the hypothesis (can be partial)
-        #[arg(help = "Unique identifier or first few characters of the hypothesis ID")]
-        id: String,
+        /// IDs of the hypotheses (can be partial)
+        #[arg(
+            value_name = "ID",
+            required_unless_present = "all",
+            help = "Unique identifiers or first few characters of the hypothesis IDs",
+            add = ArgValueCompleter::new(completion::active_hypothesis_ids)
+        )]
+        ids: Vec<String>,
+
+        #[command(flatten)]
+        select: SelectArgs,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
     },
 
     /// Mark hypothesis as inconclusive
@@ -62,9 +161,29 @@ pub enum HypothesisCommands {
     /// Example:
     ///   dxlog hypothesis inconclusive 3c8d0fed
     Inconclusive {
-        /// ID of the hypothesis (can be partial)
-        #[arg(help = "Unique identifier or first few characters of the hypothesis ID")]
-        id: String,
+        /// IDs of the hypotheses (can be partial)
+        #[arg(
+            value_name = "ID",
+            required_unless_present = "all",
+            help = "Unique identifiers or first few characters of the hypothesis IDs",
+            add = ArgValueCompleter::new(completion::active_hypothesis_ids)
+        )]
+        ids: Vec<String>,
+
+        #[command(flatten)]
+        select: SelectArgs,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
     },
 
     /// Temporarily suspend research on a hypothesis
@@ -72,30 +191,253 @@ pub enum HypothesisCommands {
     /// Marks a hypothesis as 'suspended' when work needs to be paused.
     /// The hypothesis remains in the active directory.
     ///
-    /// Example:
+    /// Several hypotheses can be named at once, or chosen with `--all` and
+    /// filters; they are listed for confirmation before any is changed.
+    ///
+    /// Examples:
     ///   dxlog hypothesis suspend 4d9e1ghi
+    ///   dxlog hypothesis suspend 4d9e1ghi 5e0f2jkl
+    ///   dxlog hypothesis suspend --tag legacy --status active --all
     Suspend {
+        /// IDs of the hypotheses (can be partial)
+        #[arg(
+            value_name = "ID",
+            required_unless_present = "all",
+            help = "Unique identifiers or first few characters of the hypothesis IDs",
+            add = ArgValueCompleter::new(completion::active_hypothesis_ids)
+        )]
+        ids: Vec<String>,
+
+        #[command(flatten)]
+        select: SelectArgs,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Set the status of a hypothesis
+    ///
+    /// Accepts every status of the repository, including those added under
+    /// `[statuses.hypothesis]` in the config. The file moves to the directory
+    /// `[storage.layout.hypothesis]` assigns to the status, if any.
+    ///
+    /// Examples:
+    ///   dxlog hypothesis status 1f418cae needs-replication
+    ///   dxlog hypothesis status 1f418cae 2a7b9def needs-replication
+    Status {
+        /// IDs of the hypotheses (can be partial)
+        #[arg(
+            value_name = "ID",
+            required = true,
+            help = "Unique identifiers or first few characters of the hypothesis IDs",
+            add = ArgValueCompleter::new(completion::hypothesis_ids)
+        )]
+        ids: Vec<String>,
+
+        /// New status
+        #[arg(add = ArgValueCompleter::new(completion::hypothesis_statuses))]
+        status: HypothesisStatus,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Reopen a concluded or abandoned hypothesis
+    ///
+    /// Sets the status back to 'active' when new evidence surfaces, moves the
+    /// hypothesis back to the active directory and records the reversal in its
+    /// status history. The configured workflow is not applied.
+    ///
+    /// Examples:
+    ///   dxlog hypothesis reopen 1f418cae
+    ///   dxlog hypothesis reopen 1f418cae --reason "Replication failed"
+    Reopen {
+        /// ID of the hypothesis (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the hypothesis ID",
+            add = ArgValueCompleter::new(completion::hypothesis_ids)
+        )]
+        id: String,
+
+        /// Why the hypothesis is being reopened
+        #[arg(short, long, help = "Reason recorded in the status history")]
+        reason: Option<String>,
+    },
+
+    /// Set or clear the due date of a hypothesis
+    ///
+    /// Open entries with a due date are listed by `dxlog due`.
+    ///
+    /// Examples:
+    ///   dxlog hypothesis due 1f418cae 2025-06-30
+    ///   dxlog hypothesis due 1f418cae --clear
+    Due {
+        /// ID of the hypothesis (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the hypothesis ID",
+            add = ArgValueCompleter::new(completion::active_hypothesis_ids)
+        )]
+        id: String,
+
+        /// Due date
+        #[arg(
+            required_unless_present = "clear",
+            help = "Date the hypothesis should be resolved by (YYYY-MM-DD)"
+        )]
+        date: Option<NaiveDate>,
+
+        /// Remove the due date
+        #[arg(
+            long,
+            conflicts_with = "date",
+            help = "Remove the due date instead of setting one"
+        )]
+        clear: bool,
+    },
+
+    /// Link a git commit to a hypothesis
+    ///
+    /// Records the commit SHA in the hypothesis frontmatter, tying the code
+    /// that tests a hypothesis to the hypothesis itself. The commit must exist
+    /// in the current repository; branch names and other revisions are resolved
+    /// to the commit they point at.
+    ///
+    /// Examples:
+    ///   dxlog hypothesis link-commit 1f418cae 3e5c9a1
+    ///   dxlog hypothesis link-commit 1f418cae HEAD
+    LinkCommit {
         /// ID of the hypothesis (can be partial)
-        #[arg(help = "Unique identifier or first few characters of the hypothesis ID")]
+        #[arg(
+            help = "Unique identifier or first few characters of the hypothesis ID",
+            add = ArgValueCompleter::new(completion::hypothesis_ids)
+        )]
         id: String,
+
+        /// Commit SHA or revision
+        #[arg(help = "Commit SHA, branch name, or other git revision")]
+        sha: String,
+    },
+
+    /// Show the git commits linked to a hypothesis
+    ///
+    /// Example:
+    ///   dxlog hypothesis commits 1f418cae
+    Commits {
+        /// ID of the hypothesis (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the hypothesis ID",
+            add = ArgValueCompleter::new(completion::hypothesis_ids)
+        )]
+        id: String,
+    },
+
+    /// Manage evidence for or against a hypothesis
+    Evidence {
+        #[command(subcommand)]
+        command: EvidenceCommands,
+    },
+
+    /// Assign the hypothesis to a team member
+    ///
+    /// The member must be listed under [team] in dxlog.toml, and can be given
+    /// by handle, email or full name. Replaces any earlier assignee.
+    ///
+    /// Example:
+    ///   dxlog hypothesis assign 1f418cae ada
+    Assign {
+        /// ID of the hypothesis (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the hypothesis ID",
+            add = ArgValueCompleter::new(completion::hypothesis_ids)
+        )]
+        id: String,
+
+        /// Team member
+        #[arg(
+            help = "Handle, email or name of a member under [team]",
+            add = ArgValueCompleter::new(completion::team_members)
+        )]
+        member: String,
+    },
+
+    /// Remove the assignee
+    ///
+    /// Example:
+    ///   dxlog hypothesis unassign 1f418cae
+    Unassign {
+        /// ID of the hypothesis (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the hypothesis ID",
+            add = ArgValueCompleter::new(completion::hypothesis_ids)
+        )]
+        id: String,
+    },
+
+    /// Change the title of an entry
+    ///
+    /// Renames the file to match the new title (staging the rename if the file
+    /// is tracked by git) and updates links to it in other entries.
+    ///
+    /// Example:
+    ///   dxlog hypothesis rename 1a2b3c4d "Error rates drop below threshold with surface codes"
+    Rename {
+        /// ID of the hypothesis entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::hypothesis_ids)
+        )]
+        id: String,
+
+        /// New title
+        #[arg(help = "The new title of the entry")]
+        title: String,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Rename the entry even if one of the same type already has this title"
+        )]
+        allow_duplicate_title: bool,
     },
 
     /// List hypotheses with optional filters
     ///
-    /// Display all hypotheses, optionally filtered by status and/or tags.
+    /// Display all hypotheses, optionally filtered by status, tags, author,
+    /// and creation date.
     ///
     /// Examples:
     ///   dxlog hypothesis list
     ///   dxlog hypothesis list --status active
     ///   dxlog hypothesis list --tags quantum,physics
     ///   dxlog hypothesis list -s proven -t quantum
+    ///   dxlog hypothesis list --author alice --since 2025-01-01
+    ///   dxlog hypothesis list --sort title --limit 10
     List {
         /// Filter by hypothesis status
         #[arg(
             short,
             long,
             help_heading = "FILTERS",
-            help = "Show only hypotheses with specified status"
+            help = "Show only hypotheses with specified status",
+            add = ArgValueCompleter::new(completion::hypothesis_statuses)
         )]
         status: Option<HypothesisStatus>,
 
@@ -105,82 +447,609 @@ pub enum HypothesisCommands {
             long,
             value_delimiter = ',',
             help_heading = "FILTERS",
-            help = "Show only hypotheses with specified tags"
+            help = "Show only hypotheses with specified tags",
+            add = ArgValueCompleter::new(completion::tags)
         )]
         tags: Option<Vec<String>>,
+
+        /// Filter by alternative tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only hypotheses with at least one of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        any_tag: Vec<String>,
+
+        /// Filter out tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Hide hypotheses with any of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        exclude_tag: Vec<String>,
+
+        /// Filter by author
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only hypotheses created by this author (name or email)"
+        )]
+        author: Option<String>,
+
+        /// Filter by project
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries assigned to this project",
+            add = ArgValueCompleter::new(completion::projects)
+        )]
+        project: Option<String>,
+
+        /// Filter by assignee
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries assigned to this person (name or email, or `me`)"
+        )]
+        assignee: Option<String>,
+
+        /// Only entries created on or after this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only hypotheses created on or after this date (YYYY-MM-DD)"
+        )]
+        since: Option<NaiveDate>,
+
+        /// Only entries created on or before this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only hypotheses created on or before this date (YYYY-MM-DD)"
+        )]
+        until: Option<NaiveDate>,
+
+        /// Only entries whose title contains this text
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only hypotheses whose title contains this text, ignoring case"
+        )]
+        query: Option<String>,
+
+        /// Treat the query as a regular expression
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Treat --query as a regular expression"
+        )]
+        regex: bool,
+
+        /// Search the body as well as the title
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Match --query against the body as well as the title"
+        )]
+        body: bool,
+
+        /// Sort order
+        #[arg(
+            long,
+            default_value = "date",
+            help_heading = "ORDERING",
+            help = "Field to sort hypotheses by"
+        )]
+        sort: SortKey,
+
+        /// Reverse the sort order
+        #[arg(long, help_heading = "ORDERING", help = "Reverse the sort order")]
+        reverse: bool,
+
+        /// Maximum number of results
+        #[arg(
+            short,
+            long,
+            help_heading = "ORDERING",
+            help = "Show at most this many hypotheses"
+        )]
+        limit: Option<usize>,
+
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
+    },
+
+    /// Sync hypotheses to GitHub issues
+    ///
+    /// Opens an issue per active hypothesis with its title and body, and its
+    /// tags as labels. Hypotheses synced before update their issue instead of
+    /// opening another, and the issues of hypotheses that have been concluded
+    /// or abandoned since are closed. The issue number is kept in the
+    /// hypothesis frontmatter.
+    ///
+    /// Pass a token that can write issues with --token or GITHUB_TOKEN.
+    ///
+    /// Example:
+    ///   dxlog hypothesis sync-issues --github-repo acme/lab
+    SyncIssues {
+        /// GitHub repository
+        #[arg(
+            long,
+            value_name = "OWNER/NAME",
+            help = "GitHub repository to write the issues to"
+        )]
+        github_repo: String,
+
+        /// GitHub token
+        #[arg(
+            long,
+            env = "GITHUB_TOKEN",
+            hide_env_values = true,
+            help = "GitHub token allowed to write issues of the repository"
+        )]
+        token: String,
     },
 }
 
-impl HypothesisCommands {
+#[derive(clap::Subcommand, Clone)]
+pub enum EvidenceCommands {
+    /// Attach evidence to a hypothesis
+    ///
+    /// Examples:
+    ///   dxlog hypothesis evidence add 1f418cae "Error rate drops 40% with shielding" --direction supporting --strength strong
+    ///   dxlog hypothesis evidence add 1f418cae "No effect at 4K" -d refuting --link 5e0f2abc
+    Add {
+        /// ID of the hypothesis (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the hypothesis ID",
+            add = ArgValueCompleter::new(completion::hypothesis_ids)
+        )]
+        id: String,
+
+        /// What was observed
+        #[arg(help = "Short description of the evidence")]
+        description: String,
+
+        /// Whether the evidence supports or refutes the hypothesis
+        #[arg(short, long, help = "supporting or refuting")]
+        direction: EvidenceDirection,
+
+        /// How strong the evidence is
+        #[arg(
+            short,
+            long,
+            default_value = "moderate",
+            help = "weak, moderate, or strong"
+        )]
+        strength: EvidenceStrength,
+
+        /// Related entry or URL
+        #[arg(short, long, help = "ID of a related entry, or a URL")]
+        link: Option<String>,
+    },
+
+    /// List the evidence attached to a hypothesis
+    ///
+    /// Example:
+    ///   dxlog hypothesis evidence list 1f418cae
+    List {
+        /// ID of the hypothesis (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the hypothesis ID",
+            add = ArgValueCompleter::new(completion::hypothesis_ids)
+        )]
+        id: String,
+    },
+
+    /// Remove an evidence item
+    ///
+    /// Example:
+    ///   dxlog hypothesis evidence remove 1f418cae 2
+    Remove {
+        /// ID of the hypothesis (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the hypothesis ID",
+            add = ArgValueCompleter::new(completion::hypothesis_ids)
+        )]
+        id: String,
+
+        /// Position of the item as shown by `evidence list`
+        #[arg(help = "Number of the evidence item to remove (see `evidence list`)")]
+        index: usize,
+    },
+}
+
+impl EvidenceCommands {
     pub fn execute(&self) -> Result<()> {
         match self {
-            Self::New { title, tags } => {
-                let new_hypothesis = create_hypothesis(title, tags.clone())?;
+            Self::Add {
+                id,
+                description,
+                direction,
+                strength,
+                link,
+            } => {
+                let evidence =
+                    add_hypothesis_evidence(id, description, *direction, *strength, link.clone())?;
                 println!(
-                    "New Hypothesis \"{}\" created with id: {}",
-                    new_hypothesis.base.title, new_hypothesis.base.id
+                    "Added {} {} evidence to Hypothesis {}",
+                    evidence.strength, evidence.direction, id
                 );
                 Ok(())
             }
-            Self::Proven { id } => {
-                update_hypothesis_status(id, HypothesisStatus::Proven)?;
-                println!("Update Hypothesis {}; Status => Proven", id);
+            Self::List { id } => {
+                let mut rows =
+                    table::new(&["#", "DIRECTION", "STRENGTH", "DATE", "DESCRIPTION", "LINK"]);
+                for (i, evidence) in list_hypothesis_evidence(id)?.iter().enumerate() {
+                    rows.add_row(vec![
+                        Cell::new(i + 1),
+                        Cell::new(evidence.direction),
+                        Cell::new(evidence.strength),
+                        Cell::new(&evidence.date),
+                        Cell::new(&evidence.description),
+                        Cell::new(evidence.link.as_deref().unwrap_or("")),
+                    ]);
+                }
+                table::print(&rows);
                 Ok(())
             }
-            Self::Disproven { id } => {
-                update_hypothesis_status(id, HypothesisStatus::Disproven)?;
-                println!("Update Hypothesis {}; Status => Disproven", id);
+            Self::Remove { id, index } => {
+                let removed = remove_hypothesis_evidence(id, *index)?;
+                println!(
+                    "Removed evidence \"{}\" from Hypothesis {}",
+                    removed.description, id
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Sets the status of the hypotheses named by `ids`, or matching `filter` if
+/// none are given, reporting it as `label`.
+fn update_status(
+    ids: &[String],
+    filter: ListFilter<HypothesisStatus>,
+    status: HypothesisStatus,
+    label: &str,
+    reason: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let entries = select_hypotheses(ids, &filter)?;
+    confirm_status_change(
+        "Hypothesis",
+        ids,
+        &entries,
+        |subject| format!("Mark {} as {}?", subject, status),
+        Impact::Change,
+    )?;
+    let changed = update_hypothesis_statuses(&full_ids(&entries), status.clone(), reason, force)?;
+    if let [id] = ids {
+        println!("Update Hypothesis {}; Status => {}", id, label);
+    } else {
+        for log in changed {
+            let id = log
+                .base
+                .handle
+                .unwrap_or_else(|| table::short(&log.base.id.to_string(), 8));
+            println!("Update Hypothesis {}; Status => {}", id, label);
+        }
+    }
+    Ok(())
+}
+
+impl HypothesisCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::New {
+                title,
+                tags,
+                template,
+                allow_duplicate_title,
+                due,
+                body_file,
+                stdin,
+            } => {
+                let body = read_body(body_file.as_deref(), *stdin)?;
+                let new_hypothesis = create_hypothesis(
+                    title,
+                    tags.clone(),
+                    body.as_deref(),
+                    template.as_deref(),
+                    *allow_duplicate_title,
+                    *due,
+                )?;
+                println!(
+                    "New Hypothesis \"{}\" ({}) created with id: {}",
+                    new_hypothesis.base.title,
+                    new_hypothesis.base.handle.as_deref().unwrap_or_default(),
+                    new_hypothesis.base.id
+                );
                 Ok(())
             }
-            Self::Inconclusive { id } => {
-                update_hypothesis_status(id, HypothesisStatus::Inconclusive)?;
-                println!("Update Hypothesis {}; Status => Inconclusive", id);
+            Self::Proven {
+                ids,
+                select,
+                reason,
+                force,
+            } => update_status(
+                ids,
+                select.filter()?,
+                HypothesisStatus::PROVEN,
+                "Proven",
+                reason.as_deref(),
+                *force,
+            ),
+            Self::Disproven {
+                ids,
+                select,
+                reason,
+                force,
+            } => update_status(
+                ids,
+                select.filter()?,
+                HypothesisStatus::DISPROVEN,
+                "Disproven",
+                reason.as_deref(),
+                *force,
+            ),
+            Self::Inconclusive {
+                ids,
+                select,
+                reason,
+                force,
+            } => update_status(
+                ids,
+                select.filter()?,
+                HypothesisStatus::INCONCLUSIVE,
+                "Inconclusive",
+                reason.as_deref(),
+                *force,
+            ),
+            Self::Suspend {
+                ids,
+                select,
+                reason,
+                force,
+            } => update_status(
+                ids,
+                select.filter()?,
+                HypothesisStatus::SUSPENDED,
+                "Suspended",
+                reason.as_deref(),
+                *force,
+            ),
+            Self::Status {
+                ids,
+                status,
+                reason,
+                force,
+            } => update_status(
+                ids,
+                ListFilter::default(),
+                status.clone(),
+                status.as_str(),
+                reason.as_deref(),
+                *force,
+            ),
+            Self::Reopen { id, reason } => {
+                confirm::require(&format!("Reopen Hypothesis {}?", id), Impact::Change)?;
+                reopen_hypothesis(id, reason.as_deref())?;
+                println!("Update Hypothesis {}; Status => Active", id);
                 Ok(())
             }
-            Self::Suspend { id } => {
-                update_hypothesis_status(id, HypothesisStatus::Suspended)?;
-                println!("Update Hypothesis {}; Status => Suspended", id);
+            Self::Due { id, date, clear } => {
+                if *clear {
+                    confirm::require(
+                        &format!("Clear the due date of Hypothesis {}?", id),
+                        Impact::Change,
+                    )?;
+                }
+                let log = set_hypothesis_due(id, *date)?;
+                match log.due {
+                    Some(due) => println!("Update Hypothesis {}; Due => {}", id, due),
+                    None => println!("Update Hypothesis {}; Due date cleared", id),
+                }
                 Ok(())
             }
-            Self::List { status, tags } => {
-                let hypotheses = list_hypotheses(status.clone(), tags.clone())?;
+            Self::LinkCommit { id, sha } => {
+                let commit = link_hypothesis_commit(id, sha)?;
                 println!(
-                    "{:<18} {:<20} {:<12} {:<18} {:<18} TAGS",
-                    "HYPOTHESIS ID", "TITLE", "STATUS", "CREATED", "AUTHOR"
+                    "Linked commit {} \"{}\" to Hypothesis {}",
+                    table::short(&commit.sha, 10),
+                    commit.summary,
+                    id
                 );
-
-                for hypothesis in hypotheses {
-                    let id = hypothesis.base.id.to_string();
-                    let short_id = &id[..12];
-                    let title = if hypothesis.base.title.len() > 20 {
-                        format!("{}...", &hypothesis.base.title[..17])
-                    } else {
-                        hypothesis.base.title.clone()
-                    };
-                    let author = if hypothesis.base.created_by.name.len() > 12 {
-                        format!("{}...", &hypothesis.base.created_by.name[..9])
-                    } else {
-                        hypothesis.base.created_by.name.clone()
+                Ok(())
+            }
+            Self::Commits { id } => {
+                let commits = list_hypothesis_commits(id)?;
+                let mut rows = table::new(&["COMMIT", "DATE", "AUTHOR", "MESSAGE"]);
+                for commit in commits {
+                    let commit = match commit {
+                        LinkedCommit::Found(commit) => commit,
+                        LinkedCommit::Missing { sha } => {
+                            rows.add_row(vec![
+                                table::dim(&table::short(&sha, 10)),
+                                Cell::new(""),
+                                Cell::new(""),
+                                Cell::new("(commit not found in this repository)"),
+                            ]);
+                            continue;
+                        }
                     };
+                    rows.add_row(vec![
+                        table::dim(&table::short(&commit.sha, 10)),
+                        Cell::new(&commit.date),
+                        Cell::new(&commit.author),
+                        Cell::new(&commit.summary),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            Self::Evidence { command } => command.execute(),
+            Self::Assign { id, member } => {
+                let log = assign_hypothesis(id, Some(member))?;
+                if let Some(assignee) = &log.base.assignee {
+                    println!("Update Hypothesis {}; Assignee => {}", id, assignee.name);
+                }
+                Ok(())
+            }
+            Self::Unassign { id } => {
+                confirm::require(&format!("Unassign Hypothesis {}?", id), Impact::Change)?;
+                assign_hypothesis(id, None)?;
+                println!("Update Hypothesis {}; Assignee removed", id);
+                Ok(())
+            }
+            Self::Rename {
+                id,
+                title,
+                allow_duplicate_title,
+            } => {
+                confirm::require(
+                    &format!("Rename Hypothesis {} to \"{}\"?", id, title),
+                    Impact::Change,
+                )?;
+                let renamed = rename_hypothesis(id, title, *allow_duplicate_title)?;
+                print_renamed("Hypothesis", &renamed.log.base.title, &renamed);
+                Ok(())
+            }
+            Self::List {
+                status,
+                tags,
+                any_tag,
+                exclude_tag,
+                author,
+                project,
+                assignee,
+                since,
+                until,
+                query,
+                regex,
+                body,
+                sort,
+                reverse,
+                limit,
+                workspace,
+            } => {
+                let filter = ListFilter {
+                    status: status.clone(),
+                    tags: TagFilter {
+                        all: tags.clone().unwrap_or_default(),
+                        any: any_tag.clone(),
+                        none: exclude_tag.clone(),
+                    },
+                    author: author.clone(),
+                    project: project.clone(),
+                    assignee: assignee.as_deref().map(assignee_filter).transpose()?,
+                    since: *since,
+                    until: *until,
+                    query: query
+                        .as_deref()
+                        .map(|query| TextQuery::new(query, *regex, *body))
+                        .transpose()?,
+                    sort: *sort,
+                    reverse: *reverse,
+                    limit: *limit,
+                };
+                let mut rows = table::new(&[
+                    "HYPOTHESIS ID",
+                    "TITLE",
+                    "STATUS",
+                    "EVIDENCE",
+                    "CREATED",
+                    "AUTHOR",
+                    "TAGS",
+                ]);
+
+                let results = workspace.each_repo(|config| {
+                    Ok((
+                        list_hypotheses_in(config, &filter)?,
+                        config.date_format.clone(),
+                    ))
+                })?;
+                for (repo, (hypotheses, date_format)) in results {
+                    for hypothesis in hypotheses {
+                        let id = hypothesis.base.id.to_string();
+                        let (supporting, refuting) = hypothesis.evidence_summary();
+                        let evidence = format!("+{}/-{}", supporting, refuting);
 
-                    let tags = hypothesis
-                        .base
-                        .tags
-                        .into_iter()
-                        .collect::<Vec<_>>()
-                        .join(", ");
+                        let created = hypothesis.base.display_date(&date_format);
+                        let tags = hypothesis
+                            .base
+                            .tags
+                            .into_iter()
+                            .collect::<Vec<_>>()
+                            .join(", ");
+
+                        rows.add_row(vec![
+                            table::dim(&table::repo_entry_id(
+                                repo.as_deref(),
+                                &id,
+                                hypothesis.base.handle.as_deref(),
+                            )),
+                            Cell::new(&hypothesis.base.title),
+                            table::status(&hypothesis.status.to_string()),
+                            Cell::new(evidence),
+                            Cell::new(created),
+                            Cell::new(&hypothesis.base.created_by.name),
+                            Cell::new(tags),
+                        ]);
+                    }
+                }
 
+                table::print(&rows);
+                Ok(())
+            }
+            Self::SyncIssues { github_repo, token } => {
+                let results = sync_hypothesis_issues(token, github_repo)?;
+                let dry_run = is_dry_run();
+                let mut failed = 0;
+                for (log, action, result) in &results {
+                    let title = &log.base.title;
+                    match result {
+                        Ok(_) if dry_run => {
+                            let verb = match action {
+                                IssueAction::Create => "create",
+                                IssueAction::Update => "update",
+                                IssueAction::Reopen => "reopen",
+                                IssueAction::Close => "close",
+                            };
+                            println!("Would {} the issue for \"{}\"", verb, title)
+                        }
+                        Ok(number) => {
+                            let verb = match action {
+                                IssueAction::Create => "Created",
+                                IssueAction::Update => "Updated",
+                                IssueAction::Reopen => "Reopened",
+                                IssueAction::Close => "Closed",
+                            };
+                            println!("{} issue #{} for \"{}\"", verb, number, title)
+                        }
+                        Err(e) => {
+                            failed += 1;
+                            eprintln!("Failed to sync \"{}\": {:#}", title, e)
+                        }
+                    }
+                }
+                if !dry_run {
                     println!(
-                        "{:<18} {:<20} {:<12} {:<18} {:<18} {}",
-                        short_id,
-                        title,
-                        hypothesis.status.to_string(),
-                        hypothesis.base.date,
-                        author,
-                        tags
+                        "Synced {} hypotheses to {}, {} failed",
+                        results.len() - failed,
+                        github_repo,
+                        failed
                     );
                 }
-
                 Ok(())
             }
         }
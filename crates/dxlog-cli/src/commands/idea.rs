@@ -0,0 +1,223 @@
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{
+    capture_idea, list_ideas_in, promote_idea, utils::TagFilter, IdeaStatus, ListFilter,
+    PromoteTarget, SortKey,
+};
+
+use crate::confirm::{self, Impact};
+use crate::workspace::WorkspaceArgs;
+use crate::{completion, table};
+
+#[derive(clap::Args, Clone)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct IdeaArgs {
+    /// The idea, in one line
+    #[arg(help = "The idea to capture, e.g. \"Does shielding halve the error rate?\"")]
+    text: Option<String>,
+
+    /// Tags for categorization
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        help = "Comma-separated list of tags",
+        add = ArgValueCompleter::new(completion::tags)
+    )]
+    tags: Option<Vec<String>>,
+
+    #[command(subcommand)]
+    command: Option<IdeaCommands>,
+}
+
+#[derive(clap::Subcommand, Clone)]
+pub enum IdeaCommands {
+    /// List the ideas in the inbox
+    ///
+    /// Examples:
+    ///   dxlog idea list
+    ///   dxlog idea list --all
+    List {
+        /// Include promoted and archived ideas
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show archived ideas as well as those in the inbox"
+        )]
+        all: bool,
+
+        /// Filter by tags
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only ideas with specified tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+
+        /// Sort order
+        #[arg(
+            long,
+            default_value = "date",
+            help_heading = "ORDERING",
+            help = "Field to sort ideas by"
+        )]
+        sort: SortKey,
+
+        /// Reverse the sort order
+        #[arg(long, help_heading = "ORDERING", help = "Reverse the sort order")]
+        reverse: bool,
+
+        /// Maximum number of results
+        #[arg(
+            short,
+            long,
+            help_heading = "ORDERING",
+            help = "Show at most this many ideas"
+        )]
+        limit: Option<usize>,
+
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
+    },
+
+    /// Turn an idea into a hypothesis or knowledge entry
+    ///
+    /// Creates the entry from the idea, with its tags and any notes added to
+    /// it, and archives the idea.
+    ///
+    /// Examples:
+    ///   dxlog idea promote I-007 --to hypothesis
+    ///   dxlog idea promote I-007 --to knowledge --title "Shielding and error rates"
+    Promote {
+        /// ID of the idea (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the idea ID",
+            add = ArgValueCompleter::new(completion::inbox_idea_ids)
+        )]
+        id: String,
+
+        /// Type of the new entry
+        #[arg(long, value_name = "TYPE", help = "hypothesis or knowledge")]
+        to: PromoteTarget,
+
+        /// Title of the new entry
+        #[arg(long, help = "Title of the new entry (default: the idea itself)")]
+        title: Option<String>,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Create the entry even if another of its type already has this title"
+        )]
+        allow_duplicate_title: bool,
+    },
+}
+
+impl IdeaArgs {
+    pub fn execute(&self) -> Result<()> {
+        if let Some(command) = &self.command {
+            return command.execute();
+        }
+        let Some(text) = &self.text else {
+            return Err(anyhow::anyhow!(
+                "Nothing to capture. Try `dxlog idea \"...\"` or `dxlog idea list`"
+            ));
+        };
+        let idea = capture_idea(text, self.tags.clone())?;
+        println!(
+            "Captured idea {}",
+            idea.base.handle.as_deref().unwrap_or_default()
+        );
+        Ok(())
+    }
+}
+
+impl IdeaCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::List {
+                all,
+                tags,
+                sort,
+                reverse,
+                limit,
+                workspace,
+            } => {
+                let filter = ListFilter {
+                    status: (!all).then_some(IdeaStatus::Inbox),
+                    tags: TagFilter {
+                        all: tags.clone().unwrap_or_default(),
+                        ..Default::default()
+                    },
+                    sort: *sort,
+                    reverse: *reverse,
+                    limit: *limit,
+                    ..Default::default()
+                };
+                let mut headers = vec!["IDEA ID", "IDEA", "TAGS", "CREATED"];
+                if *all {
+                    headers.insert(2, "STATUS");
+                }
+                let mut rows = table::new(&headers);
+
+                let results = workspace.each_repo(|config| {
+                    Ok((list_ideas_in(config, &filter)?, config.date_format.clone()))
+                })?;
+                for (repo, (entries, date_format)) in results {
+                    for entry in entries {
+                        let id = entry.base.id.to_string();
+                        let mut tags: Vec<&str> =
+                            entry.base.tags.iter().map(String::as_str).collect();
+                        tags.sort_unstable();
+
+                        let mut row = vec![
+                            table::dim(&table::repo_entry_id(
+                                repo.as_deref(),
+                                &id,
+                                entry.base.handle.as_deref(),
+                            )),
+                            Cell::new(&entry.base.title),
+                            Cell::new(tags.join(", ")),
+                            Cell::new(entry.base.display_date(&date_format)),
+                        ];
+                        if *all {
+                            row.insert(2, table::status(&entry.status.to_string()));
+                        }
+                        rows.add_row(row);
+                    }
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            Self::Promote {
+                id,
+                to,
+                title,
+                allow_duplicate_title,
+            } => {
+                let kind = match to {
+                    PromoteTarget::Hypothesis => "Hypothesis",
+                    PromoteTarget::Knowledge => "Knowledge",
+                };
+                confirm::require(
+                    &format!("Promote Idea {} to a {} entry?", id, kind.to_lowercase()),
+                    Impact::Change,
+                )?;
+                let promotion = promote_idea(id, *to, title.as_deref(), *allow_duplicate_title)?;
+                println!(
+                    "New {} ({}) created with id: {}; Idea {} archived",
+                    kind,
+                    promotion.handle.as_deref().unwrap_or_default(),
+                    promotion.id,
+                    promotion.idea.base.handle.as_deref().unwrap_or(id)
+                );
+                Ok(())
+            }
+        }
+    }
+}
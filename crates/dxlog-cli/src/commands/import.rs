@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use dxlog::import_bundle;
+
+#[derive(clap::Subcommand, Clone)]
+pub enum ImportCommands {
+    /// Create the entries of a JSON bundle
+    ///
+    /// Reads a bundle written by `dxlog export bundle` and creates its entries
+    /// with their IDs, handles and references. Entries whose ID is already in
+    /// the repository are skipped, and an entry whose handle is taken gets the
+    /// next free one.
+    ///
+    /// Examples:
+    ///   dxlog import bundle dump.json
+    ///   dxlog --dry-run import bundle dump.json
+    Bundle {
+        /// Bundle to import
+        #[arg(help = "JSON file written by `dxlog export bundle`")]
+        file: PathBuf,
+    },
+}
+
+impl ImportCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Bundle { file } => {
+                let import = import_bundle(file)?;
+                for (taken, renumbered) in &import.renumbered {
+                    println!("{} is taken here; imported as {}", taken, renumbered);
+                }
+                println!(
+                    "Imported {} entries from {}; skipped {} already in the repository",
+                    import.imported,
+                    file.display(),
+                    import.skipped
+                );
+                Ok(())
+            }
+        }
+    }
+}
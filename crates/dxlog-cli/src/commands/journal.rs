@@ -0,0 +1,234 @@
+use std::io::IsTerminal;
+
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{
+    is_dry_run, journal_today, list_journal_in, parse_since, update_journal_status,
+    utils::TagFilter, JournalStatus, ListFilter,
+};
+
+use super::open_in_editor;
+use crate::confirm::{self, Impact};
+use crate::workspace::WorkspaceArgs;
+use crate::{completion, table};
+
+#[derive(clap::Subcommand, Clone)]
+pub enum JournalCommands {
+    /// Open today's lab notebook entry
+    ///
+    /// Creates today's entry from the journal template if it does not exist yet,
+    /// then opens it in $VISUAL or $EDITOR. Journal entries can reference and be
+    /// referenced by other entries like any other log.
+    ///
+    /// Examples:
+    ///   dxlog journal today
+    ///   dxlog journal today --no-edit
+    Today {
+        /// Named template to render a new entry from
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Template from [templates.named] (default: the journal template)",
+            add = ArgValueCompleter::new(completion::template_names)
+        )]
+        template: Option<String>,
+
+        /// Only print the path
+        #[arg(
+            long,
+            help = "Print the path of the entry instead of opening an editor"
+        )]
+        no_edit: bool,
+    },
+
+    /// Archive a journal entry
+    ///
+    /// Example:
+    ///   dxlog journal archive 3f2a9c1d
+    Archive {
+        /// ID of the journal entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::journal_ids)
+        )]
+        id: String,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// List journal entries, newest first
+    ///
+    /// Examples:
+    ///   dxlog journal list
+    ///   dxlog journal list --since 2w
+    ///   dxlog journal list --since 2025-03-01 --tags cryo
+    List {
+        /// Only recent days
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only days since this period or date, e.g. 7d, 2w or 2025-03-01"
+        )]
+        since: Option<String>,
+
+        /// Filter by tags
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only entries with specified tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+
+        /// Filter by alternative tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only entries with at least one of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        any_tag: Vec<String>,
+
+        /// Filter out tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Hide entries with any of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        exclude_tag: Vec<String>,
+
+        /// Filter by author
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created by this author (name or email)"
+        )]
+        author: Option<String>,
+
+        /// Filter by project
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries assigned to this project",
+            add = ArgValueCompleter::new(completion::projects)
+        )]
+        project: Option<String>,
+
+        /// Maximum number of results
+        #[arg(
+            short,
+            long,
+            help_heading = "ORDERING",
+            help = "Show at most this many entries"
+        )]
+        limit: Option<usize>,
+
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
+    },
+}
+
+impl JournalCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Today { template, no_edit } => {
+                let (journal, path, created) = journal_today(template.as_deref())?;
+                if created {
+                    println!(
+                        "New Journal \"{}\" ({}) created with id: {}",
+                        journal.base.title,
+                        journal.base.handle.as_deref().unwrap_or_default(),
+                        journal.base.id
+                    );
+                }
+                // Nothing to open in a dry run, and no one to edit without a terminal.
+                if *no_edit || is_dry_run() || !std::io::stdin().is_terminal() {
+                    println!("{}", path.display());
+                    return Ok(());
+                }
+                open_in_editor(&path)
+            }
+            Self::Archive { id, reason, force } => {
+                confirm::require(&format!("Archive Journal {}?", id), Impact::Destructive)?;
+                update_journal_status(id, JournalStatus::Archived, reason.as_deref(), *force)?;
+                println!("Update Journal {}; Status => Archived", id);
+                Ok(())
+            }
+            Self::List {
+                since,
+                tags,
+                any_tag,
+                exclude_tag,
+                author,
+                project,
+                limit,
+                workspace,
+            } => {
+                let since = since
+                    .as_deref()
+                    .map(|since| parse_since(since, chrono::Local::now().date_naive()))
+                    .transpose()?;
+                let filter = ListFilter {
+                    tags: TagFilter {
+                        all: tags.clone().unwrap_or_default(),
+                        any: any_tag.clone(),
+                        none: exclude_tag.clone(),
+                    },
+                    author: author.clone(),
+                    project: project.clone(),
+                    since,
+                    reverse: true,
+                    limit: *limit,
+                    ..Default::default()
+                };
+                let mut rows = table::new(&["JOURNAL ID", "DATE", "TITLE", "AUTHOR", "TAGS"]);
+
+                let results = workspace.each_repo(|config| {
+                    Ok((
+                        list_journal_in(config, &filter)?,
+                        config.date_format.clone(),
+                    ))
+                })?;
+                for (repo, (entries, date_format)) in results {
+                    for entry in entries {
+                        let id = entry.base.id.to_string();
+                        let date = entry.base.display_date(&date_format);
+                        let tags = entry.base.tags.into_iter().collect::<Vec<_>>().join(", ");
+
+                        rows.add_row(vec![
+                            table::dim(&table::repo_entry_id(
+                                repo.as_deref(),
+                                &id,
+                                entry.base.handle.as_deref(),
+                            )),
+                            Cell::new(date),
+                            Cell::new(&entry.base.title),
+                            Cell::new(&entry.base.created_by.name),
+                            Cell::new(tags),
+                        ]);
+                    }
+                }
+                table::print(&rows);
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,6 +1,20 @@
 // crates/dxlog-cli/src/commands/knowledge.rs
+use std::path::PathBuf;
+
 use anyhow::Result;
-use dxlog::{create_knowledge, list_knowledge, update_knowledge_status, KnowledgeStatus};
+use chrono::NaiveDate;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{
+    approve_knowledge, assign_knowledge, assignee_filter, create_knowledge, export_anki,
+    list_knowledge_in, rename_knowledge, request_knowledge_review, set_knowledge_due,
+    update_knowledge_status, utils::TagFilter, KnowledgeStatus, ListFilter, SortKey, TextQuery,
+};
+
+use super::{print_renamed, read_body};
+use crate::confirm::{self, Impact};
+use crate::workspace::WorkspaceArgs;
+use crate::{completion, table};
 
 #[derive(clap::Subcommand, Clone)]
 pub enum KnowledgeCommands {
@@ -12,6 +26,9 @@ pub enum KnowledgeCommands {
     /// Examples:
     ///   dxlog knowledge new "Quantum Error Correction Guide" --tags quantum,guide
     ///   dxlog knowledge new "ML Model Evaluation Methods" -t ml,evaluation
+    ///   dxlog knowledge new "Benchmark Notes" --body-file notes.md
+    ///   dxlog knowledge new "Calibration Procedure" --due 2025-06-30
+    ///   some-tool --summary | dxlog knowledge new "Tool Summary" --stdin
     New {
         /// Title of the knowledge entry
         #[arg(help = "The main title of your knowledge entry")]
@@ -23,9 +40,47 @@ pub enum KnowledgeCommands {
             long,
             value_delimiter = ',',
             help_heading = "ORGANIZATION",
-            help = "Comma-separated list of tags"
+            help = "Comma-separated list of tags",
+            add = ArgValueCompleter::new(completion::tags)
         )]
         tags: Option<Vec<String>>,
+
+        /// Named template to render the entry from
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Template from [templates.named] (default: chosen by tag, else the default template)",
+            add = ArgValueCompleter::new(completion::template_names)
+        )]
+        template: Option<String>,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Create the entry even if one of the same type already has this title"
+        )]
+        allow_duplicate_title: bool,
+
+        /// Date the draft is due
+        #[arg(
+            long,
+            value_name = "DATE",
+            help = "Date the draft should be published by (YYYY-MM-DD); listed by `dxlog due`"
+        )]
+        due: Option<NaiveDate>,
+
+        /// Initial content of the entry
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "stdin",
+            help = "Fill the entry body with the contents of this file"
+        )]
+        body_file: Option<PathBuf>,
+
+        /// Read the initial content from standard input
+        #[arg(long, help = "Fill the entry body with text read from standard input")]
+        stdin: bool,
     },
 
     /// Publish a knowledge entry
@@ -37,8 +92,98 @@ pub enum KnowledgeCommands {
     ///   dxlog knowledge publish 8i3j5jkl
     Publish {
         /// ID of the knowledge entry (can be partial)
-        #[arg(help = "Unique identifier or first few characters of the entry ID")]
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::draft_knowledge_ids)
+        )]
+        id: String,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Ask a team member to review a draft
+    ///
+    /// Sets the status to 'in_review' and records the reviewer, who must be
+    /// listed under [team]. With `require_review = true` under [workflow], an
+    /// entry can only be published once its review is approved.
+    ///
+    /// Example:
+    ///   dxlog knowledge request-review 8i3j5jkl --reviewer ada
+    RequestReview {
+        /// ID of the knowledge entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::draft_knowledge_ids)
+        )]
+        id: String,
+
+        /// Who should review the entry
+        #[arg(
+            long,
+            help = "Handle, email or name of a member under [team]",
+            add = ArgValueCompleter::new(completion::team_members)
+        )]
+        reviewer: String,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Approve an entry in review
+    ///
+    /// Records the approval date next to the reviewer in the frontmatter.
+    ///
+    /// Example:
+    ///   dxlog knowledge approve 8i3j5jkl
+    Approve {
+        /// ID of the knowledge entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::in_review_knowledge_ids)
+        )]
+        id: String,
+    },
+
+    /// Set or clear the due date of a knowledge draft
+    ///
+    /// Open entries with a due date are listed by `dxlog due`.
+    ///
+    /// Examples:
+    ///   dxlog knowledge due 8i3j5jkl 2025-06-30
+    ///   dxlog knowledge due 8i3j5jkl --clear
+    Due {
+        /// ID of the knowledge entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::draft_knowledge_ids)
+        )]
         id: String,
+
+        /// Due date
+        #[arg(
+            required_unless_present = "clear",
+            help = "Date the draft should be published by (YYYY-MM-DD)"
+        )]
+        date: Option<NaiveDate>,
+
+        /// Remove the due date
+        #[arg(
+            long,
+            conflicts_with = "date",
+            help = "Remove the due date instead of setting one"
+        )]
+        clear: bool,
     },
 
     /// Archive a knowledge entry
@@ -49,19 +194,100 @@ pub enum KnowledgeCommands {
     ///   dxlog knowledge archive 9k4l6mno
     Archive {
         /// ID of the knowledge entry (can be partial)
-        #[arg(help = "Unique identifier or first few characters of the entry ID")]
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::knowledge_ids)
+        )]
+        id: String,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Assign the knowledge to a team member
+    ///
+    /// The member must be listed under [team] in dxlog.toml, and can be given
+    /// by handle, email or full name. Replaces any earlier assignee.
+    ///
+    /// Example:
+    ///   dxlog knowledge assign 8i3j5jkl ada
+    Assign {
+        /// ID of the knowledge entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::knowledge_ids)
+        )]
+        id: String,
+
+        /// Team member
+        #[arg(
+            help = "Handle, email or name of a member under [team]",
+            add = ArgValueCompleter::new(completion::team_members)
+        )]
+        member: String,
+    },
+
+    /// Remove the assignee
+    ///
+    /// Example:
+    ///   dxlog knowledge unassign 8i3j5jkl
+    Unassign {
+        /// ID of the knowledge entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::knowledge_ids)
+        )]
         id: String,
     },
 
+    /// Change the title of an entry
+    ///
+    /// Renames the file to match the new title (staging the rename if the file
+    /// is tracked by git) and updates links to it in other entries.
+    ///
+    /// Example:
+    ///   dxlog knowledge rename 8i3j5jkl "Quantum Error Correction Handbook"
+    Rename {
+        /// ID of the knowledge entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::knowledge_ids)
+        )]
+        id: String,
+
+        /// New title
+        #[arg(help = "The new title of the entry")]
+        title: String,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Rename the entry even if one of the same type already has this title"
+        )]
+        allow_duplicate_title: bool,
+    },
+
     /// List knowledge entries with optional filters
     ///
-    /// Display all knowledge entries, optionally filtered by status and/or tags.
+    /// Display all knowledge entries, optionally filtered by status, tags, author,
+    /// and creation date.
     ///
     /// Examples:
     ///   dxlog knowledge list
     ///   dxlog knowledge list --status published
     ///   dxlog knowledge list --tags guide
     ///   dxlog knowledge list -s draft -t quantum
+    ///   dxlog knowledge list --author alice --since 2025-01-01
+    ///   dxlog knowledge list --sort title --limit 10
     List {
         /// Filter by entry status
         #[arg(
@@ -78,65 +304,338 @@ pub enum KnowledgeCommands {
             long,
             value_delimiter = ',',
             help_heading = "FILTERS",
-            help = "Show only entries with specified tags"
+            help = "Show only entries with specified tags",
+            add = ArgValueCompleter::new(completion::tags)
         )]
         tags: Option<Vec<String>>,
+
+        /// Filter by alternative tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only entries with at least one of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        any_tag: Vec<String>,
+
+        /// Filter out tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Hide entries with any of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        exclude_tag: Vec<String>,
+
+        /// Filter by author
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created by this author (name or email)"
+        )]
+        author: Option<String>,
+
+        /// Filter by project
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries assigned to this project",
+            add = ArgValueCompleter::new(completion::projects)
+        )]
+        project: Option<String>,
+
+        /// Filter by assignee
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries assigned to this person (name or email, or `me`)"
+        )]
+        assignee: Option<String>,
+
+        /// Only entries created on or after this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created on or after this date (YYYY-MM-DD)"
+        )]
+        since: Option<NaiveDate>,
+
+        /// Only entries created on or before this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created on or before this date (YYYY-MM-DD)"
+        )]
+        until: Option<NaiveDate>,
+
+        /// Only entries whose title contains this text
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries whose title contains this text, ignoring case"
+        )]
+        query: Option<String>,
+
+        /// Treat the query as a regular expression
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Treat --query as a regular expression"
+        )]
+        regex: bool,
+
+        /// Search the body as well as the title
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Match --query against the body as well as the title"
+        )]
+        body: bool,
+
+        /// Sort order
+        #[arg(
+            long,
+            default_value = "date",
+            help_heading = "ORDERING",
+            help = "Field to sort entries by"
+        )]
+        sort: SortKey,
+
+        /// Reverse the sort order
+        #[arg(long, help_heading = "ORDERING", help = "Reverse the sort order")]
+        reverse: bool,
+
+        /// Maximum number of results
+        #[arg(
+            short,
+            long,
+            help_heading = "ORDERING",
+            help = "Show at most this many entries"
+        )]
+        limit: Option<usize>,
+
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
     },
+
+    /// Export published knowledge for other tools
+    ///
+    /// With --format anki, writes one flashcard per published entry for
+    /// spaced-repetition review: the title on the front, the `## Summary`
+    /// section (or the whole body) on the back, and the entry's tags. Import
+    /// the file in Anki with File > Import; importing it again updates the
+    /// existing cards.
+    ///
+    /// Example:
+    ///   dxlog knowledge export --format anki --out knowledge-cards.txt
+    Export {
+        /// Export format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Anki)]
+        format: ExportFormat,
+
+        /// Output file
+        #[arg(short, long, help = "File to write the export to")]
+        out: PathBuf,
+    },
+}
+
+/// Formats for `dxlog knowledge export`.
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub enum ExportFormat {
+    /// Anki flashcards, as tab-separated text
+    Anki,
 }
 
 impl KnowledgeCommands {
     pub fn execute(&self) -> Result<()> {
         match self {
-            Self::New { title, tags } => {
-                let knowledge = create_knowledge(title, tags.clone())?;
+            Self::New {
+                title,
+                tags,
+                template,
+                allow_duplicate_title,
+                due,
+                body_file,
+                stdin,
+            } => {
+                let body = read_body(body_file.as_deref(), *stdin)?;
+                let knowledge = create_knowledge(
+                    title,
+                    tags.clone(),
+                    body.as_deref(),
+                    template.as_deref(),
+                    *allow_duplicate_title,
+                    *due,
+                )?;
                 println!(
-                    "New Knowledge \"{}\" created with id: {}",
-                    knowledge.base.title, knowledge.base.id
+                    "New Knowledge \"{}\" ({}) created with id: {}",
+                    knowledge.base.title,
+                    knowledge.base.handle.as_deref().unwrap_or_default(),
+                    knowledge.base.id
                 );
                 Ok(())
             }
-            Self::Publish { id } => {
-                update_knowledge_status(id, KnowledgeStatus::Published)?;
+            Self::Publish { id, reason, force } => {
+                confirm::require(&format!("Publish Knowledge {}?", id), Impact::Change)?;
+                update_knowledge_status(id, KnowledgeStatus::Published, reason.as_deref(), *force)?;
                 println!("Update Knowledge {}; Status => Published", id);
                 Ok(())
             }
-            Self::Archive { id } => {
-                update_knowledge_status(id, KnowledgeStatus::Archived)?;
+            Self::RequestReview {
+                id,
+                reviewer,
+                force,
+            } => {
+                let knowledge = request_knowledge_review(id, reviewer, *force)?;
+                if let Some(review) = &knowledge.review {
+                    println!(
+                        "Update Knowledge {}; Status => InReview (reviewer: {})",
+                        id, review.reviewer.name
+                    );
+                }
+                Ok(())
+            }
+            Self::Approve { id } => {
+                confirm::require(&format!("Approve Knowledge {}?", id), Impact::Change)?;
+                let knowledge = approve_knowledge(id)?;
+                println!("Approved Knowledge \"{}\"", knowledge.base.title);
+                Ok(())
+            }
+            Self::Due { id, date, clear } => {
+                if *clear {
+                    confirm::require(
+                        &format!("Clear the due date of Knowledge {}?", id),
+                        Impact::Change,
+                    )?;
+                }
+                let log = set_knowledge_due(id, *date)?;
+                match log.due {
+                    Some(due) => println!("Update Knowledge {}; Due => {}", id, due),
+                    None => println!("Update Knowledge {}; Due date cleared", id),
+                }
+                Ok(())
+            }
+            Self::Archive { id, reason, force } => {
+                confirm::require(&format!("Archive Knowledge {}?", id), Impact::Destructive)?;
+                update_knowledge_status(id, KnowledgeStatus::Archived, reason.as_deref(), *force)?;
                 println!("Update Knowledge {}; Status => Archived", id);
                 Ok(())
             }
-            Self::List { status, tags } => {
-                println!(
-                    "{:<18} {:<20} {:<12} {:<18} {:<18} TAGS",
-                    "KNOWLEDGE ID", "TITLE", "STATUS", "CREATED", "AUTHOR"
-                );
+            Self::Assign { id, member } => {
+                let log = assign_knowledge(id, Some(member))?;
+                if let Some(assignee) = &log.base.assignee {
+                    println!("Update Knowledge {}; Assignee => {}", id, assignee.name);
+                }
+                Ok(())
+            }
+            Self::Unassign { id } => {
+                confirm::require(&format!("Unassign Knowledge {}?", id), Impact::Change)?;
+                assign_knowledge(id, None)?;
+                println!("Update Knowledge {}; Assignee removed", id);
+                Ok(())
+            }
+            Self::Rename {
+                id,
+                title,
+                allow_duplicate_title,
+            } => {
+                confirm::require(
+                    &format!("Rename Knowledge {} to \"{}\"?", id, title),
+                    Impact::Change,
+                )?;
+                let renamed = rename_knowledge(id, title, *allow_duplicate_title)?;
+                print_renamed("Knowledge", &renamed.log.base.title, &renamed);
+                Ok(())
+            }
+            Self::List {
+                status,
+                tags,
+                any_tag,
+                exclude_tag,
+                author,
+                project,
+                assignee,
+                since,
+                until,
+                query,
+                regex,
+                body,
+                sort,
+                reverse,
+                limit,
+                workspace,
+            } => {
+                let filter = ListFilter {
+                    status: status.clone(),
+                    tags: TagFilter {
+                        all: tags.clone().unwrap_or_default(),
+                        any: any_tag.clone(),
+                        none: exclude_tag.clone(),
+                    },
+                    author: author.clone(),
+                    project: project.clone(),
+                    assignee: assignee.as_deref().map(assignee_filter).transpose()?,
+                    since: *since,
+                    until: *until,
+                    query: query
+                        .as_deref()
+                        .map(|query| TextQuery::new(query, *regex, *body))
+                        .transpose()?,
+                    sort: *sort,
+                    reverse: *reverse,
+                    limit: *limit,
+                };
+                let mut rows = table::new(&[
+                    "KNOWLEDGE ID",
+                    "TITLE",
+                    "STATUS",
+                    "CREATED",
+                    "AUTHOR",
+                    "TAGS",
+                ]);
 
-                let entries = list_knowledge(status.clone(), tags.clone())?;
-                for entry in entries {
-                    let id = entry.base.id.to_string();
-                    let short_id = &id[..12];
-                    let title = if entry.base.title.len() > 20 {
-                        format!("{}...", &entry.base.title[..17])
-                    } else {
-                        entry.base.title.clone()
-                    };
-                    let author = if entry.base.created_by.name.len() > 12 {
-                        format!("{}...", &entry.base.created_by.name[..9])
-                    } else {
-                        entry.base.created_by.name.clone()
-                    };
-
-                    let tags = entry.base.tags.into_iter().collect::<Vec<_>>().join(", ");
+                let results = workspace.each_repo(|config| {
+                    Ok((
+                        list_knowledge_in(config, &filter)?,
+                        config.date_format.clone(),
+                    ))
+                })?;
+                for (repo, (entries, date_format)) in results {
+                    for entry in entries {
+                        let id = entry.base.id.to_string();
+                        let created = entry.base.display_date(&date_format);
+                        let tags = entry.base.tags.into_iter().collect::<Vec<_>>().join(", ");
 
-                    println!(
-                        "{:<18} {:<20} {:<12} {:<18} {:<18} {}",
-                        short_id,
-                        title,
-                        entry.status.to_string(),
-                        entry.base.date,
-                        author,
-                        tags
-                    );
+                        rows.add_row(vec![
+                            table::dim(&table::repo_entry_id(
+                                repo.as_deref(),
+                                &id,
+                                entry.base.handle.as_deref(),
+                            )),
+                            Cell::new(&entry.base.title),
+                            table::status(&entry.status.to_string()),
+                            Cell::new(created),
+                            Cell::new(&entry.base.created_by.name),
+                            Cell::new(tags),
+                        ]);
+                    }
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            Self::Export { format, out } => {
+                match format {
+                    ExportFormat::Anki => {
+                        let count = export_anki(out)?;
+                        println!("Exported {} cards to {}", count, out.display());
+                    }
                 }
                 Ok(())
             }
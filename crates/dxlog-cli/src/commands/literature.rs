@@ -1,46 +1,190 @@
+use std::path::PathBuf;
+
 use anyhow::Result;
+use chrono::NaiveDate;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
 use dxlog::{
-    create_literature, delete_literature, list_literature, update_literature_status,
-    LiteratureStatus,
+    assign_literature, assignee_filter, create_literature, delete_literature, distill_literature,
+    import_literature, import_literature_urls, list_literature_in, literature_csl,
+    quote_literature, rate_literature, refresh_all_literature, refresh_literature,
+    rename_literature, reopen_literature, select_literature, sync_bibtex,
+    update_literature_statuses, utils::TagFilter, FieldChange, ImportSummary, ListFilter,
+    LiteratureLog, LiteratureStatus, ReadingProgress, SortKey, TextQuery,
 };
 
+use super::zotero::ZoteroCommands;
+use super::{confirm_status_change, full_ids, print_renamed, SelectArgs};
+use crate::confirm::{self, Impact};
+use crate::workspace::WorkspaceArgs;
+use crate::{completion, table};
+
 #[derive(clap::Subcommand, Clone)]
 pub enum LiteratureCommands {
     /// Create a new literature review entry
     ///
-    /// Creates a new literature review from an arXiv paper, GitHub repository,
-    /// or DOI. Automatically extracts metadata from the source.
+    /// Creates a new literature review from an arXiv paper, bioRxiv/medRxiv
+    /// preprint, OpenReview submission, GitHub repository, or DOI. Automatically
+    /// extracts metadata from the source.
     ///
     /// Examples:
     ///   dxlog literature new --url https://arxiv.org/abs/2401.12345 --tags quantum,ml
+    ///   dxlog literature new --url https://www.biorxiv.org/content/10.1101/2023.01.01.522405v2
+    ///   dxlog literature new --url "https://openreview.net/forum?id=abc123" --reviews
     ///   dxlog literature new --url https://github.com/username/repo -t software
     ///   dxlog literature new --url 10.1234/journal.paper -t biology
+    ///   dxlog --offline literature new --url 10.1234/journal.paper --title "Paper title"
     New {
         /// URL or DOI of the source material
-        #[arg(long, help = "arXiv URL, GitHub repository URL, or DOI")]
+        #[arg(
+            long,
+            help = "arXiv, bioRxiv, medRxiv or OpenReview URL, GitHub repository URL, or DOI"
+        )]
         url: String,
 
+        /// Title to use instead of the fetched one
+        #[arg(
+            long,
+            help = "Entry title; required with --offline unless the source's metadata is cached"
+        )]
+        title: Option<String>,
+
         /// Tags for categorization
         #[arg(
             short,
             long,
             value_delimiter = ',',
             help_heading = "ORGANIZATION",
-            help = "Comma-separated list of tags"
+            help = "Comma-separated list of tags",
+            add = ArgValueCompleter::new(completion::tags)
         )]
         tags: Option<Vec<String>>,
+
+        /// Named template to render the entry from
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Template from [templates.named] (default: chosen by tag, else the default template)",
+            add = ArgValueCompleter::new(completion::template_names)
+        )]
+        template: Option<String>,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Create the entry even if one of the same type already has this title"
+        )]
+        allow_duplicate_title: bool,
+
+        /// Copy OpenReview reviews into the entry
+        #[arg(
+            long,
+            help = "Add the official reviews of an OpenReview submission to the entry"
+        )]
+        reviews: bool,
+    },
+
+    /// Import literature from a BibTeX or RIS file
+    ///
+    /// Creates one literature entry per record in a Zotero, Mendeley, or other
+    /// reference manager export. Records whose DOI is already in the collection
+    /// are skipped.
+    ///
+    /// Examples:
+    ///   dxlog literature import library.bib --tags imported
+    ///   dxlog literature import export.ris -t zotero,quantum
+    Import {
+        /// Path to the .bib or .ris file
+        #[arg(help = "BibTeX (.bib) or RIS (.ris) file to import")]
+        file: PathBuf,
+
+        /// Tags applied to every imported entry
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "ORGANIZATION",
+            help = "Comma-separated list of tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+    },
+
+    /// Create literature entries from a list of URLs
+    ///
+    /// Reads one arXiv/bioRxiv/medRxiv URL, GitHub repository URL, or DOI per line and creates an
+    /// entry for each. Blank lines and lines starting with '#' are ignored. Links
+    /// that fail are reported and the rest are still imported.
+    ///
+    /// Examples:
+    ///   dxlog literature import-urls urls.txt
+    ///   dxlog literature import-urls neurips-links.txt --tags neurips,to-read
+    ImportUrls {
+        /// Path to the list of URLs
+        #[arg(help = "Text file with one URL or DOI per line")]
+        file: PathBuf,
+
+        /// Tags applied to every created entry
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "ORGANIZATION",
+            help = "Comma-separated list of tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+    },
+
+    /// Re-fetch metadata for existing entries
+    ///
+    /// Updates the title, DOI, GitHub stars, citation count and other source
+    /// fields from the original source and prints what changed.
+    ///
+    /// Examples:
+    ///   dxlog literature refresh 6f1g3def
+    ///   dxlog literature refresh --all
+    Refresh {
+        /// ID of the literature entry (can be partial)
+        #[arg(
+            required_unless_present = "all",
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::literature_ids)
+        )]
+        id: Option<String>,
+
+        /// Refresh every literature entry
+        #[arg(long, conflicts_with = "id", help = "Refresh every literature entry")]
+        all: bool,
     },
 
     /// Delete a literature review entry
     ///
-    /// Permanently removes a literature review entry.
+    /// Moves the entry to the trash. Use `dxlog trash restore` to bring it back.
+    /// Entries that other entries reference are only deleted with --cascade or --force.
     ///
-    /// Example:
+    /// Examples:
     ///   dxlog literature delete 5e0f2abc
+    ///   dxlog literature delete 5e0f2abc --cascade
     Delete {
         /// ID of the literature entry (can be partial)
-        #[arg(help = "Unique identifier or first few characters of the entry ID")]
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::literature_ids)
+        )]
         id: String,
+
+        /// Remove references to the entry from the entries that have them
+        #[arg(long, help = "Also remove references to this entry from other entries")]
+        cascade: bool,
+
+        /// Delete even though other entries reference it
+        #[arg(
+            long,
+            conflicts_with = "cascade",
+            help = "Delete even if other entries reference it, leaving their references dangling"
+        )]
+        force: bool,
     },
 
     /// Mark literature review as completed
@@ -48,35 +192,292 @@ pub enum LiteratureCommands {
     /// Updates status to 'completed' when review is finished.
     /// Moves the entry to the knowledge base.
     ///
-    /// Example:
+    /// Examples:
     ///   dxlog literature complete 6f1g3def
+    ///   dxlog literature complete --tag reading-group --status in-progress --all
     Complete {
-        /// ID of the literature entry (can be partial)
-        #[arg(help = "Unique identifier or first few characters of the entry ID")]
-        id: String,
+        /// IDs of the literature entries (can be partial)
+        #[arg(
+            value_name = "ID",
+            required_unless_present = "all",
+            help = "Unique identifiers or first few characters of the entry IDs",
+            add = ArgValueCompleter::new(completion::in_progress_literature_ids)
+        )]
+        ids: Vec<String>,
+
+        #[command(flatten)]
+        select: SelectArgs,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
     },
 
     /// Archive a literature review
     ///
-    /// Moves the literature review to the archive directory.
+    /// Moves the literature review to the archive directory. Several entries
+    /// can be named at once, or chosen with `--all` and filters; they are
+    /// listed for confirmation before any is archived.
     ///
-    /// Example:
+    /// Examples:
     ///   dxlog literature archive 7h2i4ghi
+    ///   dxlog literature archive 7h2i4ghi 8j3k5jkl 9l4m6mno
+    ///   dxlog literature archive --tag legacy --all
     Archive {
+        /// IDs of the literature entries (can be partial)
+        #[arg(
+            value_name = "ID",
+            required_unless_present = "all",
+            help = "Unique identifiers or first few characters of the entry IDs",
+            add = ArgValueCompleter::new(completion::literature_ids)
+        )]
+        ids: Vec<String>,
+
+        #[command(flatten)]
+        select: SelectArgs,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Reopen a completed or archived literature review
+    ///
+    /// Sets the status back to 'in_progress', moves the review back to the
+    /// active directory and records the reversal in its status history. The
+    /// configured workflow is not applied.
+    ///
+    /// Examples:
+    ///   dxlog literature reopen 6f1g3def
+    ///   dxlog literature reopen 6f1g3def --reason "Revised version published"
+    Reopen {
+        /// ID of the literature entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::literature_ids)
+        )]
+        id: String,
+
+        /// Why the review is being reopened
+        #[arg(short, long, help = "Reason recorded in the status history")]
+        reason: Option<String>,
+    },
+
+    /// Rate a paper and record how thoroughly it was read
+    ///
+    /// Both are shown by `dxlog literature list`, to pick which reviews are
+    /// worth revisiting. Either can be set on its own.
+    ///
+    /// Examples:
+    ///   dxlog literature rate 6f1g3def 4
+    ///   dxlog literature rate 6f1g3def 5 --progress deep-read
+    ///   dxlog literature rate 6f1g3def --progress skimmed
+    ///   dxlog literature rate 6f1g3def --clear
+    Rate {
+        /// ID of the literature entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::literature_ids)
+        )]
+        id: String,
+
+        /// Rating from 1 to 5
+        #[arg(
+            required_unless_present_any = ["progress", "clear"],
+            value_parser = clap::value_parser!(u8).range(1..=5),
+            help = "How worthwhile the paper is, from 1 to 5"
+        )]
+        rating: Option<u8>,
+
+        /// Reading progress
+        #[arg(short, long, help = "How thoroughly the paper has been read")]
+        progress: Option<ReadingProgress>,
+
+        /// Remove the rating and reading progress
+        #[arg(
+            long,
+            conflicts_with_all = ["rating", "progress"],
+            help = "Remove the rating and reading progress instead of setting them"
+        )]
+        clear: bool,
+    },
+
+    /// Turn a review's takeaways into a knowledge draft
+    ///
+    /// Creates a knowledge entry citing the paper, with a `## Key takeaways`
+    /// section holding the review's `## Takeaways` section, or left to fill in
+    /// if it has none. The draft gets the review's tags and references it.
+    ///
+    /// Examples:
+    ///   dxlog literature distill 6f1g3def
+    ///   dxlog literature distill L-012 --title "Surface codes scale with distance"
+    Distill {
+        /// ID of the literature entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::literature_ids)
+        )]
+        id: String,
+
+        /// Title of the knowledge entry
+        #[arg(
+            long,
+            help = "Title of the knowledge entry (default: the paper's title)"
+        )]
+        title: Option<String>,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Create the entry even if another knowledge entry already has this title"
+        )]
+        allow_duplicate_title: bool,
+    },
+
+    /// Find passages of the attached PDFs containing a phrase
+    ///
+    /// Searches the text extracted from the PDFs attached to the entry, ignoring
+    /// case and line breaks, and prints each passage with its page. PDFs
+    /// attached before their text was extracted are extracted first.
+    ///
+    /// Examples:
+    ///   dxlog literature quote 6f1g3def "catastrophic forgetting"
+    ///   dxlog literature quote L-012 "surface code threshold"
+    Quote {
+        /// ID of the literature entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::literature_ids)
+        )]
+        id: String,
+
+        /// Phrase to find
+        #[arg(help = "Phrase to find in the attached PDFs")]
+        phrase: String,
+    },
+
+    /// Write a summary of a paper with a language model
+    ///
+    /// Sends the title, abstract and notes to the API configured under [llm]
+    /// and writes the reply into a `## Summary` section. Bodies of encrypted
+    /// entries are not sent. An existing summary is only replaced with
+    /// --overwrite.
+    ///
+    /// Examples:
+    ///   dxlog literature summarize 6f1g3def
+    ///   dxlog literature summarize L-012 --overwrite
+    #[cfg(feature = "llm")]
+    Summarize {
         /// ID of the literature entry (can be partial)
-        #[arg(help = "Unique identifier or first few characters of the entry ID")]
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::literature_ids)
+        )]
         id: String,
+
+        /// Replace an existing summary
+        #[arg(long, help = "Replace the entry's existing summary")]
+        overwrite: bool,
+
+        /// API key
+        #[arg(
+            long,
+            help = "API key (default: the environment variable named by [llm] api_key_env, OPENAI_API_KEY)"
+        )]
+        api_key: Option<String>,
+    },
+
+    /// Assign the literature to a team member
+    ///
+    /// The member must be listed under [team] in dxlog.toml, and can be given
+    /// by handle, email or full name. Replaces any earlier assignee.
+    ///
+    /// Example:
+    ///   dxlog literature assign 5e0f2abc ada
+    Assign {
+        /// ID of the literature entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::literature_ids)
+        )]
+        id: String,
+
+        /// Team member
+        #[arg(
+            help = "Handle, email or name of a member under [team]",
+            add = ArgValueCompleter::new(completion::team_members)
+        )]
+        member: String,
+    },
+
+    /// Remove the assignee
+    ///
+    /// Example:
+    ///   dxlog literature unassign 5e0f2abc
+    Unassign {
+        /// ID of the literature entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::literature_ids)
+        )]
+        id: String,
+    },
+
+    /// Change the title of an entry
+    ///
+    /// Renames the file to match the new title (staging the rename if the file
+    /// is tracked by git) and updates links to it in other entries.
+    ///
+    /// Example:
+    ///   dxlog literature rename 5e0f2abc "Surface codes review"
+    Rename {
+        /// ID of the literature entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::literature_ids)
+        )]
+        id: String,
+
+        /// New title
+        #[arg(help = "The new title of the entry")]
+        title: String,
+
+        /// Skip the duplicate title check
+        #[arg(
+            long,
+            help = "Rename the entry even if one of the same type already has this title"
+        )]
+        allow_duplicate_title: bool,
     },
 
     /// List literature reviews with optional filters
     ///
-    /// Display all literature reviews, optionally filtered by status and/or tags.
+    /// Display all literature reviews, optionally filtered by status, tags, author,
+    /// and creation date.
     ///
     /// Examples:
     ///   dxlog literature list
     ///   dxlog literature list --status completed
     ///   dxlog literature list --tags quantum,physics
     ///   dxlog literature list -s in_progress -t ml
+    ///   dxlog literature list --author alice --since 2025-01-01
+    ///   dxlog literature list --sort title --limit 10
     List {
         /// Filter by review status
         #[arg(
@@ -93,68 +494,578 @@ pub enum LiteratureCommands {
             long,
             value_delimiter = ',',
             help_heading = "FILTERS",
-            help = "Show only reviews with specified tags"
+            help = "Show only reviews with specified tags",
+            add = ArgValueCompleter::new(completion::tags)
         )]
         tags: Option<Vec<String>>,
+
+        /// Filter by alternative tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only reviews with at least one of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        any_tag: Vec<String>,
+
+        /// Filter out tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Hide reviews with any of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        exclude_tag: Vec<String>,
+
+        /// Filter by author
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only reviews created by this author (name or email)"
+        )]
+        author: Option<String>,
+
+        /// Filter by project
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries assigned to this project",
+            add = ArgValueCompleter::new(completion::projects)
+        )]
+        project: Option<String>,
+
+        /// Filter by assignee
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries assigned to this person (name or email, or `me`)"
+        )]
+        assignee: Option<String>,
+
+        /// Only entries created on or after this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only reviews created on or after this date (YYYY-MM-DD)"
+        )]
+        since: Option<NaiveDate>,
+
+        /// Only entries created on or before this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only reviews created on or before this date (YYYY-MM-DD)"
+        )]
+        until: Option<NaiveDate>,
+
+        /// Only entries whose title contains this text
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only reviews whose title contains this text, ignoring case"
+        )]
+        query: Option<String>,
+
+        /// Treat the query as a regular expression
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Treat --query as a regular expression"
+        )]
+        regex: bool,
+
+        /// Search the body as well as the title
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Match --query against the body as well as the title"
+        )]
+        body: bool,
+
+        /// Sort order
+        #[arg(
+            long,
+            default_value = "date",
+            help_heading = "ORDERING",
+            help = "Field to sort reviews by"
+        )]
+        sort: SortKey,
+
+        /// Reverse the sort order
+        #[arg(long, help_heading = "ORDERING", help = "Reverse the sort order")]
+        reverse: bool,
+
+        /// Maximum number of results
+        #[arg(
+            short,
+            long,
+            help_heading = "ORDERING",
+            help = "Show at most this many reviews"
+        )]
+        limit: Option<usize>,
+
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
+    },
+
+    /// Import from and push reviews to Zotero
+    Zotero {
+        #[command(subcommand)]
+        command: ZoteroCommands,
     },
+
+    /// Keep a BibTeX file up to date with the literature collection
+    ///
+    /// Adds entries for new literature, rewrites entries whose metadata changed,
+    /// and removes entries it wrote earlier whose literature entry was deleted.
+    /// Entries it writes carry a `dxlog` field with the literature ID; all other
+    /// entries in the file are left untouched. Prints what changed.
+    ///
+    /// Examples:
+    ///   dxlog literature sync-bib ../paper/refs.bib
+    ///   dxlog --dry-run literature sync-bib refs.bib
+    SyncBib {
+        /// Path to the BibTeX file
+        #[arg(help = "BibTeX file to update (created if missing)")]
+        file: PathBuf,
+    },
+
+    /// Export the literature collection as a bibliography
+    ///
+    /// Writes every literature entry keyed by its citation key, for Pandoc,
+    /// Zotero and other reference tools. Entries created before citation keys
+    /// existed are given one first.
+    ///
+    /// Examples:
+    ///   dxlog literature export --format csl-json
+    ///   dxlog literature export --format csl-json --out references.json
+    Export {
+        /// Bibliography format
+        #[arg(long, value_enum, default_value_t = ExportFormat::CslJson)]
+        format: ExportFormat,
+
+        /// Output file
+        #[arg(short, long, help = "File to write to instead of standard output")]
+        out: Option<PathBuf>,
+    },
+}
+
+/// Bibliography formats for `dxlog literature export`.
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub enum ExportFormat {
+    /// CSL JSON, as read by Pandoc and Zotero
+    CslJson,
 }
 
 impl LiteratureCommands {
     pub fn execute(&self) -> Result<()> {
         match self {
-            Self::New { url, tags } => {
-                let new_literature = create_literature(url, tags.clone())?;
+            Self::New {
+                url,
+                title,
+                tags,
+                template,
+                allow_duplicate_title,
+                reviews,
+            } => {
+                let new_literature = create_literature(
+                    url,
+                    title.as_deref(),
+                    tags.clone(),
+                    template.as_deref(),
+                    *allow_duplicate_title,
+                    *reviews,
+                )?;
                 println!(
-                    "New Literture  \"{}\" created with id: {}",
-                    new_literature.base.title, new_literature.base.id
+                    "New Literture  \"{}\" ({}) created with id: {}",
+                    new_literature.base.title,
+                    new_literature.base.handle.as_deref().unwrap_or_default(),
+                    new_literature.base.id
                 );
 
                 Ok(())
             }
-            Self::Delete { id } => delete_literature(id),
-            Self::Complete { id } => update_literature_status(id, LiteratureStatus::Completed),
-            Self::Archive { id } => update_literature_status(id, LiteratureStatus::Archived),
-            Self::List { status, tags } => {
+            Self::Import { file, tags } => {
+                print_import_summary(&import_literature(file, tags.clone())?);
+                Ok(())
+            }
+            Self::ImportUrls { file, tags } => {
+                print_import_summary(&import_literature_urls(file, tags.clone())?);
+                Ok(())
+            }
+            Self::Refresh { id: Some(id), .. } => {
+                let (literature, changes) = refresh_literature(id)?;
+                print_refresh(&literature, &changes);
+                Ok(())
+            }
+            Self::Refresh { id: None, .. } => {
+                let results = refresh_all_literature()?;
+                let failed = results.iter().filter(|(_, r)| r.is_err()).count();
+                for (literature, changes) in &results {
+                    match changes {
+                        Ok(changes) => print_refresh(literature, changes),
+                        Err(e) => {
+                            eprintln!("Failed to refresh \"{}\": {:#}", literature.base.title, e)
+                        }
+                    }
+                }
+                println!("{} refreshed, {} failed", results.len() - failed, failed);
+                Ok(())
+            }
+            Self::Delete { id, cascade, force } => {
+                confirm::require(
+                    &format!("Move Literature {} to the trash?", id),
+                    Impact::Destructive,
+                )?;
+                let tombstone = delete_literature(id, *cascade, *force)?;
+                println!(
+                    "Moved Literature \"{}\" to trash (restore with `dxlog trash restore {}`)",
+                    tombstone.title,
+                    &tombstone.id.to_string()[..8]
+                );
+                Ok(())
+            }
+            Self::Complete {
+                ids,
+                select,
+                reason,
+                force,
+            } => {
+                let entries = select_literature(ids, &select.filter()?)?;
+                confirm_status_change(
+                    "Literature",
+                    ids,
+                    &entries,
+                    |subject| format!("Mark {} as completed?", subject),
+                    Impact::Change,
+                )?;
+                update_literature_statuses(
+                    &full_ids(&entries),
+                    LiteratureStatus::Completed,
+                    reason.as_deref(),
+                    *force,
+                )?;
+                Ok(())
+            }
+            Self::Archive {
+                ids,
+                select,
+                reason,
+                force,
+            } => {
+                let entries = select_literature(ids, &select.filter()?)?;
+                confirm_status_change(
+                    "Literature",
+                    ids,
+                    &entries,
+                    |subject| format!("Archive {}?", subject),
+                    Impact::Destructive,
+                )?;
+                update_literature_statuses(
+                    &full_ids(&entries),
+                    LiteratureStatus::Archived,
+                    reason.as_deref(),
+                    *force,
+                )?;
+                Ok(())
+            }
+            Self::Reopen { id, reason } => {
+                confirm::require(&format!("Reopen Literature {}?", id), Impact::Change)?;
+                reopen_literature(id, reason.as_deref())?;
+                Ok(())
+            }
+            Self::Rate {
+                id,
+                rating,
+                progress,
+                clear,
+            } => {
+                if *clear {
+                    confirm::require(
+                        &format!("Clear the rating of Literature {}?", id),
+                        Impact::Change,
+                    )?;
+                }
+                let log = rate_literature(id, *rating, *progress, *clear)?;
+                if log.rating.is_none() && log.reading_progress.is_none() {
+                    println!("Update Literature {}; Rating cleared", id);
+                } else {
+                    println!(
+                        "Update Literature {}; Rating => {}, Progress => {}",
+                        id,
+                        format_rating(log.rating).unwrap_or("-".to_string()),
+                        log.reading_progress
+                            .map_or("-".to_string(), |progress| progress.to_string())
+                    );
+                }
+                Ok(())
+            }
+            Self::Distill {
+                id,
+                title,
+                allow_duplicate_title,
+            } => {
+                let knowledge = distill_literature(id, title.as_deref(), *allow_duplicate_title)?;
                 println!(
-                    "{:<18} {:<20} {:<12} {:<18} {:<18} TAGS",
-                    "LITERATURE ID", "TITLE", "STATUS", "CREATED", "AUTHOR"
+                    "New Knowledge \"{}\" ({}) created with id: {}; references Literature {}",
+                    knowledge.base.title,
+                    knowledge.base.handle.as_deref().unwrap_or_default(),
+                    knowledge.base.id,
+                    id
                 );
+                Ok(())
+            }
+            Self::Quote { id, phrase } => {
+                let quotes = quote_literature(id, phrase)?;
+                if quotes.passages.is_empty() {
+                    println!(
+                        "No passages of Literature \"{}\" contain \"{}\"",
+                        quotes.title, phrase
+                    );
+                    return Ok(());
+                }
+                for passage in &quotes.passages {
+                    println!("{}, page {}:", passage.file, passage.page);
+                    println!("  {}\n", passage.text);
+                }
+                println!(
+                    "{} passage(s) of Literature \"{}\"",
+                    quotes.passages.len(),
+                    quotes.title
+                );
+                Ok(())
+            }
+            #[cfg(feature = "llm")]
+            Self::Summarize {
+                id,
+                overwrite,
+                api_key,
+            } => {
+                let summary = dxlog::summarize_literature(id, api_key.as_deref(), *overwrite)?;
+                println!("{}\n", summary.text);
+                let action = if summary.replaced {
+                    "Replaced"
+                } else {
+                    "Added"
+                };
+                println!("{} the summary of Literature \"{}\"", action, summary.title);
+                Ok(())
+            }
+            Self::Assign { id, member } => {
+                let log = assign_literature(id, Some(member))?;
+                if let Some(assignee) = &log.base.assignee {
+                    println!("Update Literature {}; Assignee => {}", id, assignee.name);
+                }
+                Ok(())
+            }
+            Self::Unassign { id } => {
+                confirm::require(&format!("Unassign Literature {}?", id), Impact::Change)?;
+                assign_literature(id, None)?;
+                println!("Update Literature {}; Assignee removed", id);
+                Ok(())
+            }
+            Self::Rename {
+                id,
+                title,
+                allow_duplicate_title,
+            } => {
+                confirm::require(
+                    &format!("Rename Literature {} to \"{}\"?", id, title),
+                    Impact::Change,
+                )?;
+                let renamed = rename_literature(id, title, *allow_duplicate_title)?;
+                print_renamed("Literature", &renamed.log.base.title, &renamed);
+                Ok(())
+            }
+            Self::List {
+                status,
+                tags,
+                any_tag,
+                exclude_tag,
+                author,
+                project,
+                assignee,
+                since,
+                until,
+                query,
+                regex,
+                body,
+                sort,
+                reverse,
+                limit,
+                workspace,
+            } => {
+                let filter = ListFilter {
+                    status: status.clone(),
+                    tags: TagFilter {
+                        all: tags.clone().unwrap_or_default(),
+                        any: any_tag.clone(),
+                        none: exclude_tag.clone(),
+                    },
+                    author: author.clone(),
+                    project: project.clone(),
+                    assignee: assignee.as_deref().map(assignee_filter).transpose()?,
+                    since: *since,
+                    until: *until,
+                    query: query
+                        .as_deref()
+                        .map(|query| TextQuery::new(query, *regex, *body))
+                        .transpose()?,
+                    sort: *sort,
+                    reverse: *reverse,
+                    limit: *limit,
+                };
+                let mut rows = table::new(&[
+                    "LITERATURE ID",
+                    "TITLE",
+                    "STATUS",
+                    "RATING",
+                    "READ",
+                    "CREATED",
+                    "AUTHOR",
+                    "TAGS",
+                ]);
 
-                let literature_entries = list_literature(status.clone(), tags.clone())?;
-
-                for literature in literature_entries {
-                    let id = literature.base.id.to_string();
-                    let short_id = &id[..12];
-                    let title = if literature.base.title.len() > 20 {
-                        format!("{}...", &literature.base.title[..17])
-                    } else {
-                        literature.base.title.clone()
-                    };
-                    let author = if literature.base.created_by.name.len() > 12 {
-                        format!("{}...", &literature.base.created_by.name[..9])
-                    } else {
-                        literature.base.created_by.name.clone()
-                    };
-
-                    let tags = literature
-                        .base
-                        .tags
-                        .into_iter()
-                        .collect::<Vec<_>>()
-                        .join(", ");
+                let results = workspace.each_repo(|config| {
+                    Ok((
+                        list_literature_in(config, &filter)?,
+                        config.date_format.clone(),
+                    ))
+                })?;
+                for (repo, (literature_entries, date_format)) in results {
+                    for literature in literature_entries {
+                        let id = literature.base.id.to_string();
+                        let created = literature.base.display_date(&date_format);
+                        let tags = literature
+                            .base
+                            .tags
+                            .into_iter()
+                            .collect::<Vec<_>>()
+                            .join(", ");
 
-                    println!(
-                        "{:<18} {:<20} {:<12} {:<18} {:<18} {}",
-                        short_id,
-                        title,
-                        literature.status.to_string(),
-                        literature.base.date,
-                        author,
-                        tags
+                        rows.add_row(vec![
+                            table::dim(&table::repo_entry_id(
+                                repo.as_deref(),
+                                &id,
+                                literature.base.handle.as_deref(),
+                            )),
+                            Cell::new(&literature.base.title),
+                            table::status(&literature.status.to_string()),
+                            Cell::new(format_rating(literature.rating).unwrap_or_default()),
+                            Cell::new(
+                                literature
+                                    .reading_progress
+                                    .map(|progress| progress.to_string())
+                                    .unwrap_or_default(),
+                            ),
+                            Cell::new(created),
+                            Cell::new(&literature.base.created_by.name),
+                            Cell::new(tags),
+                        ]);
+                    }
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            Self::Zotero { command } => command.execute(),
+            Self::SyncBib { file } => {
+                let sync = sync_bibtex(file)?;
+                for key in &sync.added {
+                    println!("Added {}", key);
+                }
+                for (key, changes) in &sync.updated {
+                    println!("Updated {}", key);
+                    for change in changes {
+                        println!(
+                            "  {}: {} -> {}",
+                            change.field,
+                            change.old.as_deref().unwrap_or("-"),
+                            change.new.as_deref().unwrap_or("-")
+                        );
+                    }
+                }
+                for key in &sync.removed {
+                    println!("Removed {}", key);
+                }
+                for key in &sync.conflicts {
+                    eprintln!(
+                        "Skipped {}: {} already has an entry with this key not written by dxlog",
+                        key,
+                        file.display()
                     );
                 }
+                println!(
+                    "{} added, {} updated, {} removed, {} unchanged",
+                    sync.added.len(),
+                    sync.updated.len(),
+                    sync.removed.len(),
+                    sync.unchanged
+                );
+                Ok(())
+            }
+            Self::Export { format, out } => {
+                let bibliography = match format {
+                    ExportFormat::CslJson => serde_json::to_string_pretty(&literature_csl()?)?,
+                };
+                match out {
+                    Some(path) => {
+                        std::fs::write(path, bibliography + "\n")?;
+                        println!("Exported literature to {}", path.display());
+                    }
+                    None => println!("{}", bibliography),
+                }
                 Ok(())
             }
         }
     }
 }
+
+pub(super) fn print_import_summary(summary: &ImportSummary) {
+    for literature in &summary.created {
+        println!(
+            "Imported \"{}\" ({})",
+            literature.base.title, literature.base.id
+        );
+    }
+    for title in &summary.duplicates {
+        println!("Skipped duplicate \"{}\"", title);
+    }
+    for (title, reason) in &summary.failed {
+        eprintln!("Failed to import \"{}\": {}", title, reason);
+    }
+    println!(
+        "{} imported, {} duplicates skipped, {} failed",
+        summary.created.len(),
+        summary.duplicates.len(),
+        summary.failed.len()
+    );
+}
+
+/// A rating as shown to the user, e.g. `4/5`.
+fn format_rating(rating: Option<u8>) -> Option<String> {
+    rating.map(|rating| format!("{}/5", rating))
+}
+
+fn print_refresh(literature: &LiteratureLog, changes: &[FieldChange]) {
+    let short_id = &literature.base.id.to_string()[..8];
+    if changes.is_empty() {
+        println!("\"{}\" ({}) is up to date", literature.base.title, short_id);
+        return;
+    }
+    println!("Updated \"{}\" ({})", literature.base.title, short_id);
+    for change in changes {
+        println!(
+            "  {}: {} -> {}",
+            change.field,
+            change.old.as_deref().unwrap_or("-"),
+            change.new.as_deref().unwrap_or("-")
+        );
+    }
+}
@@ -0,0 +1,559 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{
+    add_meeting_action, create_meeting, list_meeting_actions, list_meetings_in, load_config,
+    rename_meeting, set_meeting_action_done, update_meeting_status, utils::TagFilter, ActionItem,
+    ListFilter, MeetingDetails, MeetingStatus, SortKey, TextQuery,
+};
+
+use super::{print_renamed, read_body};
+use crate::confirm::{self, Impact};
+use crate::workspace::WorkspaceArgs;
+use crate::{completion, table};
+
+#[derive(clap::Subcommand, Clone)]
+pub enum MeetingCommands {
+    /// Record a meeting
+    ///
+    /// Creates a meeting entry for today, or for --date. Action items agreed
+    /// in the meeting are kept in its frontmatter and collected across all
+    /// meetings by `dxlog actions list`.
+    ///
+    /// Examples:
+    ///   dxlog meeting new "Advisor sync"
+    ///   dxlog meeting new "Advisor sync" --attendees Ada,Charles --project thesis
+    ///   dxlog meeting new "Group meeting" --date 2025-03-04 --action "Share the cavity scans"
+    New {
+        /// Title of the meeting
+        #[arg(help = "Title of the meeting, e.g. \"Advisor sync\"")]
+        title: String,
+
+        /// Day of the meeting
+        #[arg(
+            short,
+            long,
+            value_name = "DATE",
+            help = "Day the meeting took place (YYYY-MM-DD, default: today)"
+        )]
+        date: Option<NaiveDate>,
+
+        /// People present
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Comma-separated list of attendees"
+        )]
+        attendees: Vec<String>,
+
+        /// Project the meeting was about
+        #[arg(
+            short,
+            long,
+            help_heading = "ORGANIZATION",
+            help = "Project from [projects] the meeting belongs to",
+            add = ArgValueCompleter::new(completion::projects)
+        )]
+        project: Option<String>,
+
+        /// Action items agreed in the meeting
+        #[arg(
+            short,
+            long = "action",
+            value_name = "TEXT",
+            help = "Action item agreed in the meeting; repeat for several"
+        )]
+        actions: Vec<String>,
+
+        /// Tags for categorization
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "ORGANIZATION",
+            help = "Comma-separated list of tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+
+        /// Named template to render the entry from
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Template from [templates.named] (default: chosen by tag, else the default template)",
+            add = ArgValueCompleter::new(completion::template_names)
+        )]
+        template: Option<String>,
+
+        /// Initial content of the entry
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "stdin",
+            help = "Fill the notes with the contents of this file"
+        )]
+        body_file: Option<PathBuf>,
+
+        /// Read the initial content from standard input
+        #[arg(long, help = "Fill the notes with text read from standard input")]
+        stdin: bool,
+    },
+
+    /// Manage the action items of a meeting
+    Action {
+        #[command(subcommand)]
+        command: ActionCommands,
+    },
+
+    /// Archive a meeting
+    ///
+    /// Example:
+    ///   dxlog meeting archive M-004
+    Archive {
+        /// ID of the meeting (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::open_meeting_ids)
+        )]
+        id: String,
+
+        /// Why the status is changing
+        #[arg(
+            short,
+            long,
+            help = "Reason recorded in the status history and the entry's notes"
+        )]
+        reason: Option<String>,
+
+        /// Bypass the configured workflow
+        #[arg(long, help = "Change the status even if [workflow] does not allow it")]
+        force: bool,
+    },
+
+    /// Change the title of a meeting
+    ///
+    /// Renames the file to match the new title (staging the rename if the file
+    /// is tracked by git) and updates links to it in other entries.
+    ///
+    /// Example:
+    ///   dxlog meeting rename M-004 "Advisor sync: thesis outline"
+    Rename {
+        /// ID of the meeting (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::meeting_ids)
+        )]
+        id: String,
+
+        /// New title
+        #[arg(help = "The new title of the meeting")]
+        title: String,
+    },
+
+    /// List meetings with optional filters
+    ///
+    /// Examples:
+    ///   dxlog meeting list
+    ///   dxlog meeting list --project thesis --since 2025-01-01
+    List {
+        /// Filter by meeting status
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only meetings with specified status"
+        )]
+        status: Option<MeetingStatus>,
+
+        /// Filter by tags
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only entries with specified tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+
+        /// Filter by alternative tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Show only entries with at least one of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        any_tag: Vec<String>,
+
+        /// Filter out tags
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help_heading = "FILTERS",
+            help = "Hide entries with any of these tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        exclude_tag: Vec<String>,
+
+        /// Filter by author
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created by this author (name or email)"
+        )]
+        author: Option<String>,
+
+        /// Filter by project
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries assigned to this project",
+            add = ArgValueCompleter::new(completion::projects)
+        )]
+        project: Option<String>,
+
+        /// Only entries created on or after this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created on or after this date (YYYY-MM-DD)"
+        )]
+        since: Option<NaiveDate>,
+
+        /// Only entries created on or before this date
+        #[arg(
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries created on or before this date (YYYY-MM-DD)"
+        )]
+        until: Option<NaiveDate>,
+
+        /// Only entries whose title contains this text
+        #[arg(
+            short,
+            long,
+            help_heading = "FILTERS",
+            help = "Show only entries whose title contains this text, ignoring case"
+        )]
+        query: Option<String>,
+
+        /// Treat the query as a regular expression
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Treat --query as a regular expression"
+        )]
+        regex: bool,
+
+        /// Search the body as well as the title
+        #[arg(
+            long,
+            requires = "query",
+            help_heading = "FILTERS",
+            help = "Match --query against the body as well as the title"
+        )]
+        body: bool,
+
+        /// Sort order
+        #[arg(
+            long,
+            default_value = "date",
+            help_heading = "ORDERING",
+            help = "Field to sort entries by"
+        )]
+        sort: SortKey,
+
+        /// Reverse the sort order
+        #[arg(long, help_heading = "ORDERING", help = "Reverse the sort order")]
+        reverse: bool,
+
+        /// Maximum number of results
+        #[arg(
+            short,
+            long,
+            help_heading = "ORDERING",
+            help = "Show at most this many entries"
+        )]
+        limit: Option<usize>,
+
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
+    },
+}
+
+#[derive(clap::Subcommand, Clone)]
+pub enum ActionCommands {
+    /// Add an action item to a meeting
+    ///
+    /// Examples:
+    ///   dxlog meeting action add M-004 "Send the draft to Charles"
+    ///   dxlog meeting action add M-004 "Book beam time" --owner ada --due 2025-04-01
+    Add {
+        /// ID of the meeting (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::meeting_ids)
+        )]
+        id: String,
+
+        /// What has to be done
+        #[arg(help = "Description of the action item")]
+        text: String,
+
+        /// Who does it
+        #[arg(short, long, help = "Person responsible for the item")]
+        owner: Option<String>,
+
+        /// When it is due
+        #[arg(
+            long,
+            value_name = "DATE",
+            help = "Date the item should be done by (YYYY-MM-DD)"
+        )]
+        due: Option<NaiveDate>,
+    },
+
+    /// Check off an action item
+    ///
+    /// Example:
+    ///   dxlog meeting action done M-004 2
+    Done {
+        /// ID of the meeting (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::meeting_ids)
+        )]
+        id: String,
+
+        /// Position of the item as shown by `action list`
+        #[arg(help = "Number of the action item (see `meeting action list`)")]
+        index: usize,
+    },
+
+    /// Uncheck an action item
+    ///
+    /// Example:
+    ///   dxlog meeting action reopen M-004 2
+    Reopen {
+        /// ID of the meeting (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::meeting_ids)
+        )]
+        id: String,
+
+        /// Position of the item as shown by `action list`
+        #[arg(help = "Number of the action item (see `meeting action list`)")]
+        index: usize,
+    },
+
+    /// List the action items of a meeting
+    ///
+    /// Example:
+    ///   dxlog meeting action list M-004
+    List {
+        /// ID of the meeting (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::meeting_ids)
+        )]
+        id: String,
+    },
+}
+
+impl ActionCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Add {
+                id,
+                text,
+                owner,
+                due,
+            } => {
+                let item = ActionItem {
+                    text: text.clone(),
+                    owner: owner.clone(),
+                    due: *due,
+                    done: false,
+                };
+                add_meeting_action(id, item)?;
+                println!("Added action item to Meeting {}", id);
+                Ok(())
+            }
+            Self::Done { id, index } => {
+                let item = set_meeting_action_done(id, *index, true)?;
+                println!("Done: \"{}\"", item.text);
+                Ok(())
+            }
+            Self::Reopen { id, index } => {
+                let item = set_meeting_action_done(id, *index, false)?;
+                println!("Reopened: \"{}\"", item.text);
+                Ok(())
+            }
+            Self::List { id } => {
+                let date_format = load_config()?.date_format;
+                let mut rows = table::new(&["#", "DONE", "OWNER", "DUE", "ACTION"]);
+                for (i, item) in list_meeting_actions(id)?.iter().enumerate() {
+                    rows.add_row(vec![
+                        Cell::new(i + 1),
+                        Cell::new(if item.done { "x" } else { "" }),
+                        Cell::new(item.owner.as_deref().unwrap_or_default()),
+                        Cell::new(
+                            item.due
+                                .map(|due| due.format(&date_format).to_string())
+                                .unwrap_or_default(),
+                        ),
+                        Cell::new(&item.text),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl MeetingCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::New {
+                title,
+                date,
+                attendees,
+                project,
+                actions,
+                tags,
+                template,
+                body_file,
+                stdin,
+            } => {
+                let body = read_body(body_file.as_deref(), *stdin)?;
+                let details = MeetingDetails {
+                    date: *date,
+                    attendees: attendees.clone(),
+                    project: project.clone(),
+                    action_items: actions
+                        .iter()
+                        .map(|text| ActionItem {
+                            text: text.clone(),
+                            owner: None,
+                            due: None,
+                            done: false,
+                        })
+                        .collect(),
+                };
+                let meeting = create_meeting(
+                    title,
+                    details,
+                    tags.clone(),
+                    body.as_deref(),
+                    template.as_deref(),
+                )?;
+                println!(
+                    "New Meeting \"{}\" ({}) created with id: {}",
+                    meeting.base.title,
+                    meeting.base.handle.as_deref().unwrap_or_default(),
+                    meeting.base.id
+                );
+                Ok(())
+            }
+            Self::Action { command } => command.execute(),
+            Self::Archive { id, reason, force } => {
+                confirm::require(&format!("Archive Meeting {}?", id), Impact::Change)?;
+                update_meeting_status(id, MeetingStatus::Archived, reason.as_deref(), *force)?;
+                println!("Update Meeting {}; Status => Archived", id);
+                Ok(())
+            }
+            Self::Rename { id, title } => {
+                confirm::require(
+                    &format!("Rename Meeting {} to \"{}\"?", id, title),
+                    Impact::Change,
+                )?;
+                let renamed = rename_meeting(id, title)?;
+                print_renamed("Meeting", &renamed.log.base.title, &renamed);
+                Ok(())
+            }
+            Self::List {
+                status,
+                tags,
+                any_tag,
+                exclude_tag,
+                author,
+                project,
+                since,
+                until,
+                query,
+                regex,
+                body,
+                sort,
+                reverse,
+                limit,
+                workspace,
+            } => {
+                let filter = ListFilter {
+                    status: status.clone(),
+                    tags: TagFilter {
+                        all: tags.clone().unwrap_or_default(),
+                        any: any_tag.clone(),
+                        none: exclude_tag.clone(),
+                    },
+                    author: author.clone(),
+                    project: project.clone(),
+                    since: *since,
+                    until: *until,
+                    query: query
+                        .as_deref()
+                        .map(|query| TextQuery::new(query, *regex, *body))
+                        .transpose()?,
+                    sort: *sort,
+                    reverse: *reverse,
+                    limit: *limit,
+                    ..Default::default()
+                };
+                let mut rows = table::new(&[
+                    "MEETING ID",
+                    "TITLE",
+                    "DATE",
+                    "STATUS",
+                    "ATTENDEES",
+                    "OPEN ACTIONS",
+                ]);
+
+                let results = workspace.each_repo(|config| {
+                    Ok((
+                        list_meetings_in(config, &filter)?,
+                        config.date_format.clone(),
+                    ))
+                })?;
+                for (repo, (entries, date_format)) in results {
+                    for entry in entries {
+                        let id = entry.base.id.to_string();
+                        let open = entry.action_items.iter().filter(|a| !a.done).count();
+
+                        rows.add_row(vec![
+                            table::dim(&table::repo_entry_id(
+                                repo.as_deref(),
+                                &id,
+                                entry.base.handle.as_deref(),
+                            )),
+                            Cell::new(&entry.base.title),
+                            Cell::new(entry.date.format(&date_format)),
+                            table::status(&entry.status.to_string()),
+                            Cell::new(entry.attendees.join(", ")),
+                            Cell::new(open),
+                        ]);
+                    }
+                }
+                table::print(&rows);
+                Ok(())
+            }
+        }
+    }
+}
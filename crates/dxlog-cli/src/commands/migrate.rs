@@ -0,0 +1,41 @@
+use anyhow::Result;
+use dxlog::{migrate_entries, SCHEMA_VERSION};
+
+use crate::confirm::{self, Impact};
+
+#[derive(clap::Args, Clone)]
+pub struct MigrateArgs {}
+
+impl MigrateArgs {
+    pub fn execute(&self) -> Result<()> {
+        confirm::require(
+            "Rewrite entries written by older versions of dxlog?",
+            Impact::Change,
+        )?;
+        let report = migrate_entries()?;
+        let Some(backup) = &report.backup else {
+            println!(
+                "All entries are up to date (schema version {})",
+                SCHEMA_VERSION
+            );
+            return Ok(());
+        };
+
+        println!("Saved backup {}", backup.name);
+        println!(
+            "Migrated {} entries to schema version {}",
+            report.migrated.len(),
+            SCHEMA_VERSION
+        );
+        for (path, error) in &report.failed {
+            eprintln!("Failed to migrate {}: {}", path.display(), error);
+        }
+        if !report.failed.is_empty() {
+            return Err(anyhow::anyhow!(
+                "{} entries could not be migrated; fix them and run `dxlog migrate` again",
+                report.failed.len()
+            ));
+        }
+        Ok(())
+    }
+}
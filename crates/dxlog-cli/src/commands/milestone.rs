@@ -0,0 +1,157 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{attach_to_milestone, create_milestone, load_config, milestone_status};
+
+use crate::confirm::{self, Impact};
+use crate::{completion, table};
+
+#[derive(clap::Subcommand, Clone)]
+pub enum MilestoneCommands {
+    /// Create a milestone with a target date
+    ///
+    /// Example:
+    ///   dxlog milestone new "NeurIPS submission" --due 2025-05-15
+    New {
+        /// Title of the milestone
+        #[arg(help = "Name of the milestone, e.g. a deadline or deliverable")]
+        title: String,
+
+        /// Target date
+        #[arg(long, help = "Date the milestone is due (YYYY-MM-DD)")]
+        due: NaiveDate,
+    },
+
+    /// Attach an entry to a milestone
+    ///
+    /// Works on entries of any type. An entry counts towards at most one
+    /// milestone, so this replaces any earlier attachment.
+    ///
+    /// Example:
+    ///   dxlog milestone attach 7f3a2b1c "NeurIPS submission"
+    Attach {
+        /// ID of the entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
+        id: String,
+
+        /// Milestone title or ID
+        #[arg(help = "Title of the milestone, or the first characters of its ID")]
+        milestone: String,
+    },
+
+    /// Detach an entry from its milestone
+    ///
+    /// Example:
+    ///   dxlog milestone detach 7f3a2b1c
+    Detach {
+        /// ID of the entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
+        id: String,
+    },
+
+    /// Show progress towards milestones
+    ///
+    /// Without a milestone, lists all of them, soonest due first, with the
+    /// number of attached entries resolved and the days remaining. With one,
+    /// also lists its entries.
+    ///
+    /// Examples:
+    ///   dxlog milestone status
+    ///   dxlog milestone status "NeurIPS submission"
+    Status {
+        /// Milestone title or ID
+        #[arg(help = "Title of the milestone, or the first characters of its ID")]
+        milestone: Option<String>,
+    },
+}
+
+impl MilestoneCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::New { title, due } => {
+                let milestone = create_milestone(title, *due)?;
+                println!(
+                    "New Milestone \"{}\" due {} created with id: {}",
+                    milestone.title, milestone.due, milestone.id
+                );
+                Ok(())
+            }
+            Self::Attach { id, milestone } => {
+                let (title, milestone) = attach_to_milestone(id, Some(milestone))?;
+                if let Some(milestone) = milestone {
+                    println!("Attached \"{}\" to milestone {}", title, milestone.title);
+                }
+                Ok(())
+            }
+            Self::Detach { id } => {
+                confirm::require(
+                    &format!("Detach {} from its milestone?", id),
+                    Impact::Change,
+                )?;
+                let (title, _) = attach_to_milestone(id, None)?;
+                println!("Detached \"{}\" from its milestone", title);
+                Ok(())
+            }
+            Self::Status { milestone } => {
+                let statuses = milestone_status(milestone.as_deref())?;
+                if statuses.is_empty() {
+                    println!("No milestones yet. Create one with `dxlog milestone new`");
+                    return Ok(());
+                }
+                let date_format = load_config()?.date_format;
+
+                if milestone.is_none() {
+                    let mut rows =
+                        table::new(&["MILESTONE ID", "TITLE", "DUE", "REMAINING", "RESOLVED"]);
+                    for status in statuses {
+                        rows.add_row(vec![
+                            table::dim(&table::short(&status.milestone.id.to_string(), 8)),
+                            Cell::new(&status.milestone.title),
+                            Cell::new(status.milestone.due.format(&date_format)),
+                            Cell::new(table::remaining(status.days_remaining)),
+                            Cell::new(format!("{}/{}", status.resolved(), status.entries.len())),
+                        ]);
+                    }
+                    table::print(&rows);
+                    return Ok(());
+                }
+
+                for status in statuses {
+                    println!(
+                        "{}: due {} ({}), {}/{} entries resolved",
+                        status.milestone.title,
+                        status.milestone.due.format(&date_format),
+                        table::remaining(status.days_remaining),
+                        status.resolved(),
+                        status.entries.len()
+                    );
+                    if status.entries.is_empty() {
+                        continue;
+                    }
+                    println!();
+                    let mut rows = table::new(&["TYPE", "ID", "TITLE", "STATUS"]);
+                    for entry in &status.entries {
+                        rows.add_row(vec![
+                            Cell::new(entry.kind),
+                            table::dim(&table::entry_id(
+                                &entry.id.to_string(),
+                                entry.handle.as_deref(),
+                            )),
+                            Cell::new(&entry.title),
+                            table::status(&entry.status),
+                        ]);
+                    }
+                    table::print(&rows);
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,9 +1,238 @@
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{
+    is_dry_run, load_config, utils::TagFilter, EntryMatch, ListFilter, LogStatus, Renamed,
+};
+
+use crate::confirm::{self, Impact};
+use crate::{completion, table};
+
+mod actions;
+mod attach;
+mod backup;
+mod board;
+mod cite;
+mod config;
+mod dataset;
+mod doctor;
+mod due;
+mod encrypt;
+mod entry;
+mod experiment;
+mod export;
 mod hypothesis;
+mod idea;
+mod import;
+mod journal;
 mod knowledge;
 mod literature;
+mod meeting;
+mod migrate;
+mod milestone;
+mod note;
+mod project;
+mod recent;
 mod references;
+mod related;
+mod report;
+mod search;
+mod show;
+mod stats;
+#[cfg(feature = "llm")]
+mod suggest_tags;
+mod sync;
+mod tag;
+mod template;
+mod trash;
+mod undo;
+mod watch;
+mod zotero;
 
+pub use actions::ActionsCommands;
+pub use attach::{AttachArgs, AttachmentsCommands};
+pub use backup::BackupCommands;
+pub use board::BoardArgs;
+pub use cite::CiteArgs;
+pub use config::ConfigCommands;
+pub use dataset::DatasetCommands;
+pub use doctor::DoctorArgs;
+pub use due::DueArgs;
+pub use encrypt::{DecryptArgs, EncryptArgs};
+pub use entry::EntryArgs;
+pub use experiment::ExperimentCommands;
+pub use export::ExportCommands;
 pub use hypothesis::HypothesisCommands;
+pub use idea::IdeaArgs;
+pub use import::ImportCommands;
+pub use journal::JournalCommands;
 pub use knowledge::KnowledgeCommands;
 pub use literature::LiteratureCommands;
+pub use meeting::MeetingCommands;
+pub use migrate::MigrateArgs;
+pub use milestone::MilestoneCommands;
+pub use note::NoteCommands;
+pub use project::ProjectCommands;
+pub use recent::RecentArgs;
 pub use references::ReferenceCommands;
+pub use related::RelatedArgs;
+pub use report::ReportArgs;
+pub use search::SearchArgs;
+pub use show::{OpenArgs, ShowArgs};
+pub use stats::StatsArgs;
+#[cfg(feature = "llm")]
+pub use suggest_tags::SuggestTagsArgs;
+pub use sync::SyncArgs;
+pub use tag::TagCommands;
+pub use template::TemplateCommands;
+pub use trash::TrashCommands;
+pub use undo::undo;
+pub use watch::WatchArgs;
+
+/// Opens `path` in `$VISUAL` or `$EDITOR` (`vi` if neither is set) and waits for it to exit.
+fn open_in_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let status = Command::new(&editor)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to start editor '{}'", editor))?;
+    if !status.success() {
+        return Err(anyhow::anyhow!(
+            "Editor '{}' exited with {}",
+            editor,
+            status
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the initial body of a new entry from `--body-file` or `--stdin`.
+fn read_body(body_file: Option<&Path>, stdin: bool) -> Result<Option<String>> {
+    if let Some(path) = body_file {
+        let body = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Some(body))
+    } else if stdin {
+        let mut body = String::new();
+        std::io::stdin().read_to_string(&mut body)?;
+        Ok(Some(body))
+    } else {
+        Ok(None)
+    }
+}
+
+fn print_renamed<T>(kind: &str, title: &str, renamed: &Renamed<T>) {
+    println!("Renamed {} to \"{}\"", kind, title);
+    if renamed.new_path != renamed.old_path {
+        println!(
+            "  {} -> {}",
+            renamed.old_path.display(),
+            renamed.new_path.display()
+        );
+    }
+    if !renamed.relinked.is_empty() {
+        println!("Updated links in {} entries:", renamed.relinked.len());
+        for path in &renamed.relinked {
+            println!("  {}", path.display());
+        }
+    }
+}
+
+/// Chooses the entries a status command applies to by filters rather than by ID.
+#[derive(clap::Args, Clone)]
+pub struct SelectArgs {
+    /// Apply to every matching entry
+    #[arg(
+        long,
+        conflicts_with = "ids",
+        help = "Apply to every entry matching --tag and --status instead of the given IDs"
+    )]
+    all: bool,
+
+    /// Filter by tags
+    #[arg(
+        long,
+        value_delimiter = ',',
+        requires = "all",
+        help_heading = "FILTERS",
+        help = "With --all, only entries with all of these tags",
+        add = ArgValueCompleter::new(completion::tags)
+    )]
+    tag: Vec<String>,
+
+    /// Filter by status
+    #[arg(
+        id = "with_status",
+        long = "status",
+        value_name = "STATUS",
+        requires = "all",
+        help_heading = "FILTERS",
+        help = "With --all, only entries with this status"
+    )]
+    status: Option<String>,
+}
+
+impl SelectArgs {
+    fn filter<S: LogStatus>(&self) -> Result<ListFilter<S>> {
+        let status = match &self.status {
+            Some(name) => Some(S::parse(&load_config()?, name)?),
+            None => None,
+        };
+        Ok(ListFilter {
+            status,
+            tags: TagFilter::all(self.tag.iter().cloned()),
+            ..Default::default()
+        })
+    }
+}
+
+/// Asks before changing the status of `entries`, chosen by `ids` or `--all`.
+///
+/// A single entry named by ID is confirmed like any other change; otherwise
+/// the entries are listed and the change is confirmed whatever `[cli] confirm`
+/// says, with `--yes` answering yes. `question` turns the entry, e.g.
+/// "Hypothesis 1f418cae", or "these N entries" into the question to ask.
+fn confirm_status_change(
+    kind: &str,
+    ids: &[String],
+    entries: &[EntryMatch],
+    question: impl Fn(&str) -> String,
+    impact: Impact,
+) -> Result<()> {
+    match (ids, entries) {
+        ([id], [_]) => confirm::require(&question(&format!("{} {}", kind, id)), impact),
+        (_, []) => Err(anyhow::anyhow!("No entries match --tag and --status")),
+        _ => {
+            let mut rows = table::new(&["ID", "TITLE", "STATUS"]);
+            for entry in entries {
+                rows.add_row(vec![
+                    table::dim(&table::entry_id(
+                        &entry.id.to_string(),
+                        entry.handle.as_deref(),
+                    )),
+                    Cell::new(&entry.title),
+                    table::status(&entry.status),
+                ]);
+            }
+            table::print(&rows);
+            if is_dry_run() {
+                return Ok(());
+            }
+            if !confirm::ask(&question(&format!("these {} entries", entries.len())))? {
+                return Err(anyhow::anyhow!("Cancelled"));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Full IDs of `entries`, to change exactly the entries that were confirmed.
+fn full_ids(entries: &[EntryMatch]) -> Vec<String> {
+    entries.iter().map(|entry| entry.id.to_string()).collect()
+}
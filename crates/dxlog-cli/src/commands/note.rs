@@ -0,0 +1,40 @@
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use dxlog::add_note;
+
+use crate::completion;
+
+#[derive(clap::Subcommand, Clone)]
+pub enum NoteCommands {
+    /// Append a timestamped note to an entry
+    ///
+    /// Adds a dated bullet to the "## Notes" section of the entry, creating the
+    /// section if needed. Works for hypotheses, literature reviews and knowledge entries.
+    ///
+    /// Examples:
+    ///   dxlog note add 1a2b3c4d "Batch 3 shows the same drift"
+    Add {
+        /// ID of the entry (can be partial)
+        #[arg(
+            help = "ID of the entry to add the note to",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
+        id: String,
+
+        /// Text of the note
+        #[arg(help = "Observation to record")]
+        text: String,
+    },
+}
+
+impl NoteCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Add { id, text } => {
+                let line = add_note(id, text)?;
+                println!("Added note to {}: {}", id, line.trim_start_matches("- "));
+                Ok(())
+            }
+        }
+    }
+}
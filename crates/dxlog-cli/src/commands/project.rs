@@ -0,0 +1,130 @@
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{assign_project, list_projects, project_status};
+
+use crate::confirm::{self, Impact};
+use crate::{completion, table};
+
+#[derive(clap::Subcommand, Clone)]
+pub enum ProjectCommands {
+    /// List the projects registered under [projects]
+    ///
+    /// Example:
+    ///   dxlog project list
+    List,
+
+    /// Summarize a project
+    ///
+    /// Counts the project's hypotheses, literature, knowledge and journal
+    /// entries per status.
+    ///
+    /// Example:
+    ///   dxlog project status optics
+    Status {
+        /// Project name
+        #[arg(
+            help = "Name of a project under [projects]",
+            add = ArgValueCompleter::new(completion::projects)
+        )]
+        name: String,
+    },
+
+    /// Put an entry into a project
+    ///
+    /// Works on entries of any type. An entry belongs to at most one project,
+    /// so this replaces any earlier assignment.
+    ///
+    /// Example:
+    ///   dxlog project assign 7f3a2b1c optics
+    Assign {
+        /// ID of the entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
+        id: String,
+
+        /// Project name
+        #[arg(
+            help = "Name of a project under [projects]",
+            add = ArgValueCompleter::new(completion::projects)
+        )]
+        name: String,
+    },
+
+    /// Take an entry out of its project
+    ///
+    /// Example:
+    ///   dxlog project unassign 7f3a2b1c
+    Unassign {
+        /// ID of the entry (can be partial)
+        #[arg(
+            help = "Unique identifier or first few characters of the entry ID",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
+        id: String,
+    },
+}
+
+impl ProjectCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::List => {
+                let projects = list_projects()?;
+                if projects.is_empty() {
+                    println!("No projects configured. Add them under [projects] in dxlog.toml");
+                    return Ok(());
+                }
+                let mut rows = table::new(&["PROJECT", "ENTRIES", "DESCRIPTION"]);
+                for project in projects {
+                    rows.add_row(vec![
+                        Cell::new(&project.name),
+                        Cell::new(project.total()),
+                        Cell::new(&project.description),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            Self::Status { name } => {
+                let status = project_status(name)?;
+                if status.description.is_empty() {
+                    println!("Project {}", status.name);
+                } else {
+                    println!("Project {}: {}", status.name, status.description);
+                }
+                println!();
+
+                let mut rows = table::new(&["TYPE", "TOTAL", "BY STATUS"]);
+                for counts in &status.counts {
+                    let by_status = counts
+                        .statuses
+                        .iter()
+                        .filter(|(_, count)| *count > 0)
+                        .map(|(status, count)| format!("{} {}", count, status))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    rows.add_row(vec![
+                        Cell::new(counts.kind),
+                        Cell::new(counts.total),
+                        Cell::new(by_status),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            Self::Assign { id, name } => {
+                let title = assign_project(id, Some(name))?;
+                println!("Assigned \"{}\" to project {}", title, name);
+                Ok(())
+            }
+            Self::Unassign { id } => {
+                confirm::require(&format!("Remove {} from its project?", id), Impact::Change)?;
+                let title = assign_project(id, None)?;
+                println!("Removed \"{}\" from its project", title);
+                Ok(())
+            }
+        }
+    }
+}
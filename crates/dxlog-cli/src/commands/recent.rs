@@ -0,0 +1,63 @@
+use anyhow::Result;
+use comfy_table::Cell;
+use dxlog::{load_config, parse_since, recent_entries};
+
+use crate::table;
+
+#[derive(clap::Args, Clone)]
+pub struct RecentArgs {
+    /// Number of entries to show
+    #[arg(
+        short = 'n',
+        long,
+        default_value_t = 10,
+        help = "Number of entries to show"
+    )]
+    limit: usize,
+
+    /// Only entries active in this period
+    #[arg(
+        short,
+        long,
+        help = "Only entries created or changed in this period, e.g. 3d, 2w, or a date (YYYY-MM-DD)"
+    )]
+    since: Option<String>,
+}
+
+impl RecentArgs {
+    pub fn execute(&self) -> Result<()> {
+        let since = self
+            .since
+            .as_deref()
+            .map(|since| parse_since(since, chrono::Local::now().date_naive()))
+            .transpose()?;
+        let entries = recent_entries(self.limit, since)?;
+        if entries.is_empty() {
+            println!("No entries found");
+            return Ok(());
+        }
+
+        let date_format = load_config()?.date_format;
+        let mut rows = table::new(&["WHEN", "ACTION", "TYPE", "ID", "TITLE", "STATUS"]);
+        for entry in entries {
+            let when = format!(
+                "{} {}",
+                entry.at.format(&date_format),
+                entry.at.format("%H:%M")
+            );
+            rows.add_row(vec![
+                Cell::new(when),
+                Cell::new(entry.action),
+                Cell::new(entry.kind),
+                table::dim(&table::entry_id(
+                    &entry.id.to_string(),
+                    entry.handle.as_deref(),
+                )),
+                Cell::new(&entry.title),
+                table::status(&entry.status),
+            ]);
+        }
+        table::print(&rows);
+        Ok(())
+    }
+}
@@ -1,7 +1,13 @@
-use std::io::Write;
-
 use anyhow::Result;
-use dxlog::{add_reference, force_add_reference, list_references, remove_reference};
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{
+    add_reference, analyze_references, force_add_reference, list_references, remove_reference,
+    GraphEntry, ReferenceKind,
+};
+
+use crate::confirm::{self, Impact};
+use crate::{completion, table};
 
 #[derive(clap::Subcommand, Clone)]
 pub enum ReferenceCommands {
@@ -9,17 +15,44 @@ pub enum ReferenceCommands {
     ///
     /// Creates a directional reference from one entry to another.
     /// References should typically point to completed/published entries.
+    /// The target can be in another repository registered in
+    /// ~/.config/dxlog/workspaces.toml, named by a repo-qualified ID such as
+    /// lab:K-004 or lab:<uuid>; the repository is recorded with the reference.
     ///
-    /// Example:
+    /// Examples:
     ///   dxlog reference add 1a2b3c4d 5e6f7g8h
+    ///   dxlog reference add 1a2b3c4d 5e6f7g8h --kind supports
+    ///   dxlog reference add 1a2b3c4d 5e6f7g8h --wiki-link
+    ///   dxlog reference add 1a2b3c4d qec:H-004
     Add {
         /// ID of the source entry (can be partial)
-        #[arg(help = "ID of the entry that will contain the reference")]
+        #[arg(
+            help = "ID of the entry that will contain the reference",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
         source_id: String,
 
         /// ID of the target entry (can be partial)
-        #[arg(help = "ID of the entry being referenced")]
+        #[arg(
+            help = "ID of the entry being referenced, or <repo>:<id> for an entry of another workspace repository",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
         target_id: String,
+
+        /// How the source relates to the target
+        #[arg(
+            short,
+            long,
+            help = "Relationship of the source to the target (supports, refutes, extends, uses)"
+        )]
+        kind: Option<ReferenceKind>,
+
+        /// Also link the target from the source's body
+        #[arg(
+            long,
+            help = "Add an Obsidian [[wiki-link]] to the target under '## References' in the source"
+        )]
+        wiki_link: bool,
     },
 
     /// Remove a reference between entries
@@ -30,24 +63,62 @@ pub enum ReferenceCommands {
     ///   dxlog reference remove 1a2b3c4d 5e6f7g8h
     Remove {
         /// ID of the source entry (can be partial)
-        #[arg(help = "ID of the entry containing the reference")]
+        #[arg(
+            help = "ID of the entry containing the reference",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
         source_id: String,
 
         /// ID of the target entry (can be partial)
-        #[arg(help = "ID of the referenced entry to remove")]
+        #[arg(
+            help = "ID of the referenced entry to remove",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
         target_id: String,
     },
 
     /// List all references for an entry
     ///
-    /// Shows all entries referenced by the specified entry.
+    /// Shows all entries referenced by the specified entry, including entries
+    /// its body links to with [[wiki-links]] (marked LINK). Entries of other
+    /// repositories in the workspace are shown with repo-qualified IDs.
     ///
-    /// Example:
+    /// Examples:
     ///   dxlog reference list 1a2b3c4d
+    ///   dxlog reference list 1a2b3c4d --kind supports
     List {
         /// ID of the entry (can be partial)
-        #[arg(help = "Show references for this entry ID")]
+        #[arg(
+            help = "Show references for this entry ID",
+            add = ArgValueCompleter::new(completion::any_ids)
+        )]
         id: String,
+
+        /// Only references of this kind
+        #[arg(
+            short,
+            long,
+            help = "Show only references of this kind (supports, refutes, extends, uses)"
+        )]
+        kind: Option<ReferenceKind>,
+    },
+
+    /// Show how entries are connected by references
+    ///
+    /// Lists orphaned entries (no references in or out), the most referenced
+    /// entries and the groups of entries connected by references.
+    ///
+    /// Examples:
+    ///   dxlog reference analyze
+    ///   dxlog reference analyze --top 5
+    Analyze {
+        /// Number of most referenced entries to show
+        #[arg(
+            long,
+            default_value_t = 10,
+            help = "Show this many most referenced entries"
+        )]
+        top: usize,
     },
 }
 
@@ -57,15 +128,17 @@ impl ReferenceCommands {
             Self::Add {
                 source_id,
                 target_id,
-            } => match add_reference(source_id, target_id) {
+                kind,
+                wiki_link,
+            } => match add_reference(source_id, target_id, *kind, *wiki_link) {
                 Ok(_) => {
                     println!("Added reference from {} to {}", source_id, target_id);
                     Ok(())
                 }
                 Err(e) if e.to_string().starts_with("Warning:") => {
                     eprintln!("{}", e);
-                    if confirm_action("Do you want to add the reference anyway? [y/N]: ")? {
-                        force_add_reference(source_id, target_id)?;
+                    if confirm::ask("Do you want to add the reference anyway?")? {
+                        force_add_reference(source_id, target_id, *kind, *wiki_link)?;
                         println!("Added reference from {} to {}", source_id, target_id);
                         Ok(())
                     } else {
@@ -78,15 +151,18 @@ impl ReferenceCommands {
                 source_id,
                 target_id,
             } => {
+                confirm::require(
+                    &format!("Remove the reference from {} to {}?", source_id, target_id),
+                    Impact::Change,
+                )?;
                 remove_reference(source_id, target_id)?;
                 println!("Removed reference from {} to {}", source_id, target_id);
                 Ok(())
             }
-            Self::List { id } => {
-                println!("{:<12} {:<12} {:<20} {:<30}", "ID", "TYPE", "TITLE", "TAGS");
-                let references = list_references(id)?;
+            Self::List { id, kind } => {
+                let mut rows = table::new(&["ID", "TYPE", "KIND", "LINK", "TITLE", "TAGS"]);
+                let references = list_references(id, *kind)?;
                 for reference in references {
-                    let short_id = &reference.id[..8];
                     let tags_str = reference
                         .tags
                         .iter()
@@ -94,23 +170,67 @@ impl ReferenceCommands {
                         .collect::<Vec<String>>()
                         .join(", ");
 
+                    let kind = reference
+                        .kind
+                        .map(|k| k.to_string())
+                        .unwrap_or_else(|| "-".to_string());
+
+                    let link = if reference.wiki_link { "yes" } else { "-" };
+
+                    rows.add_row(vec![
+                        table::dim(&table::repo_entry_id(
+                            reference.repo.as_deref(),
+                            &reference.id,
+                            reference.handle.as_deref(),
+                        )),
+                        Cell::new(&reference.type_),
+                        Cell::new(kind),
+                        Cell::new(link),
+                        Cell::new(&reference.title),
+                        Cell::new(tags_str),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            Self::Analyze { top } => {
+                let analysis = analyze_references(*top)?;
+
+                println!("Orphaned entries ({}):", analysis.orphans.len());
+                for entry in &analysis.orphans {
+                    print_entry(entry);
+                }
+
+                println!("\nMost referenced:");
+                for ranked in &analysis.most_referenced {
                     println!(
-                        "{:<12} {:<12} {:<20} {:<30}",
-                        short_id, reference.type_, reference.title, tags_str
+                        "  {:>4}  {:<10} {:<12} {}",
+                        ranked.references,
+                        table::short(&ranked.entry.id, 8),
+                        ranked.entry.type_,
+                        ranked.entry.title
                     );
                 }
+
+                println!("\nConnected groups ({}):", analysis.components.len());
+                for (i, component) in analysis.components.iter().enumerate() {
+                    println!("  Group {} ({} entries)", i + 1, component.len());
+                    for entry in component {
+                        print!("  ");
+                        print_entry(entry);
+                    }
+                }
                 Ok(())
             }
         }
     }
 }
 
-fn confirm_action(prompt: &str) -> Result<bool> {
-    print!("{}", prompt);
-    std::io::stdout().flush()?;
-
-    let mut input = String::new();
-    std::io::stdin().read_line(&mut input)?;
-
-    Ok(input.trim().to_lowercase() == "y")
+fn print_entry(entry: &GraphEntry) {
+    println!(
+        "  {:<10} {:<12} {}",
+        table::short(&entry.id, 8),
+        entry.type_,
+        entry.title
+    );
 }
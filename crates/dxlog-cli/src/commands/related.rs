@@ -0,0 +1,91 @@
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{add_reference, force_add_reference, related_entries, RelatedEntry};
+
+use crate::{completion, confirm, table};
+
+#[derive(clap::Args, Clone)]
+pub struct RelatedArgs {
+    /// ID of the entry (can be partial)
+    #[arg(
+        help = "ID of the entry to find related entries for",
+        add = ArgValueCompleter::new(completion::any_ids)
+    )]
+    id: String,
+
+    /// Number of entries to show
+    #[arg(
+        short = 'n',
+        long,
+        default_value_t = 10,
+        help = "Number of entries to show"
+    )]
+    limit: usize,
+
+    /// Offer to reference each suggestion
+    #[arg(
+        long,
+        help = "Ask, for each suggestion, whether to add a reference to it"
+    )]
+    add: bool,
+}
+
+impl RelatedArgs {
+    pub fn execute(&self) -> Result<()> {
+        let entries = related_entries(&self.id, self.limit)?;
+        if entries.is_empty() {
+            println!("No related entries found");
+            return Ok(());
+        }
+
+        let mut rows = table::new(&["SCORE", "TYPE", "ID", "TITLE", "STATUS", "SHARED TAGS"]);
+        for entry in &entries {
+            rows.add_row(vec![
+                Cell::new(format!("{:.2}", entry.score)),
+                Cell::new(entry.kind),
+                table::dim(&table::entry_id(
+                    &entry.id.to_string(),
+                    entry.handle.as_deref(),
+                )),
+                Cell::new(&entry.title),
+                table::status(&entry.status),
+                Cell::new(entry.shared_tags.join(", ")),
+            ]);
+        }
+        table::print(&rows);
+
+        if self.add {
+            for entry in &entries {
+                self.offer_reference(entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn offer_reference(&self, entry: &RelatedEntry) -> Result<()> {
+        let target = table::entry_id(&entry.id.to_string(), entry.handle.as_deref());
+        let question = format!(
+            "Add a reference from {} to {} \"{}\"?",
+            self.id, target, entry.title
+        );
+        if !confirm::ask(&question)? {
+            return Ok(());
+        }
+
+        let target_id = entry.id.to_string();
+        match add_reference(&self.id, &target_id, None, false) {
+            Ok(_) => {}
+            Err(e) if e.to_string().starts_with("Warning:") => {
+                eprintln!("{}", e);
+                if !confirm::ask("Do you want to add the reference anyway?")? {
+                    return Ok(());
+                }
+                force_add_reference(&self.id, &target_id, None, false)?;
+            }
+            Err(e) => return Err(e),
+        }
+        println!("Added reference from {} to {}", self.id, target);
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use dxlog::{generate_report, parse_since};
+
+#[derive(clap::Args, Clone)]
+pub struct ReportArgs {
+    /// Start of the reporting period
+    #[arg(
+        short,
+        long,
+        default_value = "7d",
+        help = "Period to cover, e.g. 7d, 2w, or a start date (YYYY-MM-DD)"
+    )]
+    since: String,
+
+    /// Output file
+    #[arg(short, long, help = "Write the report to this file instead of stdout")]
+    out: Option<PathBuf>,
+}
+
+impl ReportArgs {
+    pub fn execute(&self) -> Result<()> {
+        let since = parse_since(&self.since, chrono::Local::now().date_naive())?;
+        let report = generate_report(since)?;
+
+        match &self.out {
+            Some(path) => {
+                std::fs::write(path, &report)
+                    .with_context(|| format!("Failed to write file: {}", path.display()))?;
+                println!("Wrote report to {}", path.display());
+            }
+            None => print!("{}", report),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{search_logs_in, SearchHit};
+
+use crate::workspace::{self, WorkspaceArgs};
+use crate::{completion, table};
+
+#[derive(clap::Args, Clone)]
+pub struct SearchArgs {
+    /// Text to search for
+    #[arg(help = "Text to search for")]
+    query: String,
+
+    /// Log type to search
+    #[arg(
+        short = 't',
+        long = "type",
+        value_name = "TYPE",
+        help = "Only search entries of this log type",
+        add = ArgValueCompleter::new(completion::all_log_types)
+    )]
+    kind: Option<String>,
+
+    /// Number of entries to show
+    #[arg(
+        short = 'n',
+        long,
+        help = "Number of entries to show (default: all matches, or 10 with --semantic)"
+    )]
+    limit: Option<usize>,
+
+    /// Rank entries by meaning
+    #[cfg(feature = "llm")]
+    #[arg(
+        long,
+        help = "Rank entries by how close their meaning is to the query, using embeddings from the [llm] API"
+    )]
+    semantic: bool,
+
+    /// API key
+    #[cfg(feature = "llm")]
+    #[arg(
+        long,
+        requires = "semantic",
+        help = "API key (default: the environment variable named by [llm] api_key_env, OPENAI_API_KEY)"
+    )]
+    api_key: Option<String>,
+
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+}
+
+impl SearchArgs {
+    pub fn execute(&self) -> Result<()> {
+        #[cfg(feature = "llm")]
+        if self.semantic {
+            let limit = self.limit.unwrap_or(10);
+            let mut hits = workspace::flatten(self.workspace.each_repo(|config| {
+                dxlog::semantic_search_in(
+                    config,
+                    &self.query,
+                    self.kind.as_deref(),
+                    limit,
+                    self.api_key.as_deref(),
+                )
+            })?);
+            hits.sort_by(|(_, a), (_, b)| b.score.total_cmp(&a.score));
+            hits.truncate(limit);
+            let scores: Vec<String> = hits
+                .iter()
+                .map(|(_, hit)| format!("{:.2}", hit.score))
+                .collect();
+            let hits: Vec<(Option<String>, SearchHit)> = hits
+                .into_iter()
+                .map(|(repo, hit)| (repo, hit.entry))
+                .collect();
+            print_hits(&hits, Some(&scores));
+            return Ok(());
+        }
+
+        let mut hits = workspace::flatten(
+            self.workspace
+                .each_repo(|config| search_logs_in(config, &self.query, self.kind.as_deref()))?,
+        );
+        // Each repository's hits are newest first; keep that across repositories
+        hits.sort_by_key(|(_, hit)| std::cmp::Reverse(hit.created_at));
+        if let Some(limit) = self.limit {
+            hits.truncate(limit);
+        }
+        print_hits(&hits, None);
+        Ok(())
+    }
+}
+
+fn print_hits(hits: &[(Option<String>, SearchHit)], scores: Option<&[String]>) {
+    if hits.is_empty() {
+        println!("No entries found");
+        return;
+    }
+
+    let mut headers = vec!["TYPE", "ID", "TITLE", "STATUS", "TAGS"];
+    if scores.is_some() {
+        headers.insert(0, "SCORE");
+    }
+    let mut rows = table::new(&headers);
+    for (i, (repo, hit)) in hits.iter().enumerate() {
+        let mut row = vec![
+            Cell::new(&hit.kind),
+            table::dim(&table::repo_entry_id(
+                repo.as_deref(),
+                &hit.id.to_string(),
+                hit.handle.as_deref(),
+            )),
+            Cell::new(&hit.title),
+            table::status(&hit.status),
+            Cell::new(hit.tags.join(", ")),
+        ];
+        if let Some(scores) = scores {
+            row.insert(0, Cell::new(&scores[i]));
+        }
+        rows.add_row(row);
+    }
+    table::print(&rows);
+}
@@ -0,0 +1,77 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap_complete::ArgValueCompleter;
+use dxlog::{read_entry, write_entry_body};
+
+use super::open_in_editor;
+use crate::completion;
+
+#[derive(clap::Args, Clone)]
+pub struct ShowArgs {
+    /// ID of the entry (can be partial)
+    #[arg(
+        help = "Unique identifier or first few characters of the entry ID",
+        add = ArgValueCompleter::new(completion::any_ids)
+    )]
+    id: String,
+}
+
+impl ShowArgs {
+    pub fn execute(&self) -> Result<()> {
+        let entry = read_entry(&self.id)?;
+        println!("{}", entry.content.trim_end());
+        Ok(())
+    }
+}
+
+#[derive(clap::Args, Clone)]
+pub struct OpenArgs {
+    /// ID of the entry (can be partial)
+    #[arg(
+        help = "Unique identifier or first few characters of the entry ID",
+        add = ArgValueCompleter::new(completion::any_ids)
+    )]
+    id: String,
+}
+
+impl OpenArgs {
+    pub fn execute(&self) -> Result<()> {
+        let entry = read_entry(&self.id)?;
+        if !entry.encrypted {
+            return open_in_editor(&entry.path);
+        }
+
+        // The decrypted body only lives in a private file for as long as the editor runs.
+        let path = std::env::temp_dir().join(format!(
+            "dxlog-{}-{}.md",
+            std::process::id(),
+            entry.path.file_stem().unwrap_or_default().to_string_lossy()
+        ));
+        let edited = edit_privately(&path, &entry.body);
+        let _ = fs::remove_file(&path);
+        let edited = edited?;
+        if edited == entry.body {
+            println!("No changes to \"{}\"", entry.title);
+            return Ok(());
+        }
+        let path = write_entry_body(&self.id, &edited)?;
+        println!("Encrypted the changes to {}", path.display());
+        Ok(())
+    }
+}
+
+fn edit_privately(path: &PathBuf, body: &str) -> Result<String> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(body.as_bytes()))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    open_in_editor(path)?;
+    fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
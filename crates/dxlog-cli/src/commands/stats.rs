@@ -0,0 +1,54 @@
+use anyhow::Result;
+use comfy_table::Cell;
+use dxlog::entry_counts_in;
+
+use crate::table;
+use crate::workspace::WorkspaceArgs;
+
+#[derive(clap::Args, Clone)]
+pub struct StatsArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+}
+
+impl StatsArgs {
+    pub fn execute(&self) -> Result<()> {
+        let results = self.workspace.each_repo(entry_counts_in)?;
+
+        let mut headers = vec!["TYPE", "TOTAL", "BY STATUS"];
+        if self.workspace.is_set() {
+            headers.insert(0, "REPO");
+        }
+        let mut rows = table::new(&headers);
+        let mut total = 0;
+        for (repo, counts) in &results {
+            for counts in counts {
+                total += counts.total;
+                let by_status = counts
+                    .statuses
+                    .iter()
+                    .filter(|(_, count)| *count > 0)
+                    .map(|(status, count)| format!("{} {}", count, status))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut row = vec![
+                    Cell::new(counts.kind),
+                    Cell::new(counts.total),
+                    Cell::new(by_status),
+                ];
+                if let Some(repo) = repo {
+                    row.insert(0, Cell::new(repo));
+                }
+                rows.add_row(row);
+            }
+        }
+        table::print(&rows);
+
+        if self.workspace.is_set() {
+            println!("\n{} entries in {} repositories", total, results.len());
+        } else {
+            println!("\n{} entries", total);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use dxlog::{add_tags, is_dry_run, suggest_tags};
+
+use crate::{completion, confirm};
+
+#[derive(clap::Args, Clone)]
+pub struct SuggestTagsArgs {
+    /// ID of the entry (can be partial)
+    #[arg(
+        help = "ID of the entry to suggest tags for",
+        add = ArgValueCompleter::new(completion::any_ids)
+    )]
+    id: String,
+
+    /// Replace the entry's tags
+    #[arg(
+        long,
+        help = "Replace the entry's tags with the suggestions instead of adding them"
+    )]
+    overwrite: bool,
+
+    /// API key
+    #[arg(
+        long,
+        help = "API key (default: the environment variable named by [llm] api_key_env, OPENAI_API_KEY)"
+    )]
+    api_key: Option<String>,
+}
+
+impl SuggestTagsArgs {
+    pub fn execute(&self) -> Result<()> {
+        let suggestions = suggest_tags(&self.id, self.api_key.as_deref())?;
+        let new: Vec<&String> = suggestions
+            .suggested
+            .iter()
+            .filter(|tag| !suggestions.current.contains(tag))
+            .collect();
+        let unchanged = if self.overwrite {
+            suggestions.suggested.is_empty() || suggestions.suggested == suggestions.current
+        } else {
+            new.is_empty()
+        };
+
+        println!("Current tags: {}", or_none(&suggestions.current.join(", ")));
+        println!(
+            "Suggested:    {}",
+            or_none(&suggestions.suggested.join(", "))
+        );
+        if unchanged {
+            println!("No new tags to add");
+            return Ok(());
+        }
+
+        let question = if self.overwrite {
+            format!(
+                "Replace the tags of \"{}\" with {}?",
+                suggestions.title,
+                suggestions.suggested.join(", ")
+            )
+        } else {
+            let new: Vec<&str> = new.iter().map(|tag| tag.as_str()).collect();
+            format!("Add {} to \"{}\"?", new.join(", "), suggestions.title)
+        };
+        if !is_dry_run() && !confirm::ask(&question)? {
+            return Err(anyhow::anyhow!("Cancelled"));
+        }
+        add_tags(&self.id, &suggestions.suggested, self.overwrite)?;
+        println!("Updated the tags of \"{}\"", suggestions.title);
+        Ok(())
+    }
+}
+
+fn or_none(tags: &str) -> &str {
+    if tags.is_empty() {
+        "(none)"
+    } else {
+        tags
+    }
+}
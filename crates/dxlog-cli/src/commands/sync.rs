@@ -0,0 +1,101 @@
+use std::path::Path;
+
+use anyhow::Result;
+use dxlog::{is_dry_run, load_config, sync_repository, FrontmatterConflict, SyncOptions};
+
+use crate::confirm;
+
+#[derive(clap::Args, Clone)]
+pub struct SyncArgs {
+    /// Remote to pull from and push to
+    #[arg(
+        long,
+        help = "Remote to pull from and push to (default: the upstream of the current branch)"
+    )]
+    remote: Option<String>,
+
+    /// Commit message
+    #[arg(
+        short,
+        long,
+        help = "Message of the commit of local changes (default: \"dxlog sync: N changed files\")"
+    )]
+    message: Option<String>,
+
+    /// Skip pushing
+    #[arg(long, help = "Commit and pull, but do not push")]
+    no_push: bool,
+}
+
+fn describe(conflict: &FrontmatterConflict, relative: &str) -> String {
+    match (
+        &conflict.remote_status,
+        &conflict.local_status,
+        &conflict.status,
+    ) {
+        (Some(remote), Some(local), Some(kept)) if remote != local => format!(
+            "{} changed on both sides, only in its frontmatter. Merge tags and references and keep the status '{}' (remote: '{}', local: '{}') of the version updated last?",
+            relative, kept, remote, local
+        ),
+        _ => format!(
+            "{} changed on both sides, only in its frontmatter. Merge tags and references and keep the rest of the version updated last?",
+            relative
+        ),
+    }
+}
+
+impl SyncArgs {
+    pub fn execute(&self) -> Result<()> {
+        let root = load_config()?.root;
+        let relative = |path: &Path| {
+            path.strip_prefix(&root)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        };
+        let options = SyncOptions {
+            remote: self.remote.clone(),
+            message: self.message.clone(),
+            push: !self.no_push,
+        };
+
+        let report = sync_repository(&options, |conflict| {
+            confirm::ask(&describe(conflict, &relative(&conflict.path)))
+        })?;
+
+        let files = |count: usize| {
+            format!(
+                "{} changed file{}",
+                count,
+                if count == 1 { "" } else { "s" }
+            )
+        };
+        if is_dry_run() {
+            println!("Would commit {}:", files(report.committed.len()));
+            for path in &report.committed {
+                println!("  {}", relative(path));
+            }
+            return Ok(());
+        }
+        if !report.committed.is_empty() {
+            println!("Committed {}", files(report.committed.len()));
+        }
+        for path in &report.resolved {
+            println!("  merged   {}", relative(path));
+        }
+        if !report.unresolved.is_empty() {
+            for path in &report.unresolved {
+                println!("  conflict {}", relative(path));
+            }
+            return Err(anyhow::anyhow!(
+                "Resolve the conflicts and stage the files with `git add`, then run `dxlog sync` again to finish"
+            ));
+        }
+        if report.pushed {
+            println!("Synced with the remote");
+        } else {
+            println!("Pulled from the remote");
+        }
+        Ok(())
+    }
+}
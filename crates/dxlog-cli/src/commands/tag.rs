@@ -0,0 +1,83 @@
+use anyhow::Result;
+use dxlog::{rename_tag, tag_tree, TagNode};
+
+use crate::confirm::{self, Impact};
+
+#[derive(clap::Subcommand, Clone)]
+pub enum TagCommands {
+    /// Rename a tag on every entry
+    ///
+    /// Entries that already have the new tag keep a single copy of it, and
+    /// tags below the old one in the hierarchy move along. The entry
+    /// directories are backed up first; undo with `dxlog backup restore`.
+    ///
+    /// Example:
+    ///   dxlog tag rename nlp natural-language
+    Rename {
+        /// Tag to rename
+        old: String,
+        /// New name of the tag
+        new: String,
+    },
+
+    /// Show the tag hierarchy with entry counts
+    ///
+    /// Tags are nested by `/`, so `ml/transformers` sits below `ml`. Each tag
+    /// counts the entries tagged with it or anything below it.
+    ///
+    /// Example:
+    ///   dxlog tag tree
+    Tree,
+}
+
+/// Prints `nodes` below a parent, drawing the branches after `prefix`.
+fn print_children(nodes: &[TagNode], prefix: &str) {
+    for (i, node) in nodes.iter().enumerate() {
+        let (branch, nested) = if i + 1 == nodes.len() {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        println!("{}{}{} ({})", prefix, branch, node.name, node.count);
+        print_children(&node.children, &format!("{}{}", prefix, nested));
+    }
+}
+
+impl TagCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Rename { old, new } => {
+                confirm::require(
+                    &format!("Rename tag '{}' to '{}' on every entry?", old, new),
+                    Impact::Change,
+                )?;
+                let renamed = rename_tag(old, new)?;
+                if renamed.entries.is_empty() {
+                    println!("No entries are tagged '{}'", old);
+                    return Ok(());
+                }
+                if let Some(backup) = &renamed.backup {
+                    println!("Saved backup {}", backup.name);
+                }
+                println!(
+                    "Renamed tag '{}' to '{}' on {} entries",
+                    old,
+                    new,
+                    renamed.entries.len()
+                );
+                Ok(())
+            }
+            Self::Tree => {
+                let tree = tag_tree()?;
+                if tree.is_empty() {
+                    println!("No tags found");
+                }
+                for root in &tree {
+                    println!("{} ({})", root.name, root.count);
+                    print_children(&root.children, "");
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::Result;
+use clap::builder::PossibleValuesParser;
+use clap_complete::ArgValueCompleter;
+use comfy_table::Cell;
+use dxlog::{
+    list_templates, prepare_template_edit, read_template, template_variables, TEMPLATE_KINDS,
+};
+
+use super::open_in_editor;
+use crate::{completion, table};
+
+#[derive(clap::Subcommand, Clone)]
+pub enum TemplateCommands {
+    /// List the templates new entries are created from
+    ///
+    /// Shows the default template of each log type and the named templates
+    /// configured under [templates.named], with the tags that select them.
+    ///
+    /// Examples:
+    ///   dxlog template list
+    ///   dxlog template list hypothesis
+    List {
+        /// Only list templates of this log type
+        #[arg(
+            value_parser = PossibleValuesParser::new(TEMPLATE_KINDS),
+            help = "Log type to list templates for"
+        )]
+        kind: Option<String>,
+    },
+
+    /// Print a template
+    ///
+    /// Examples:
+    ///   dxlog template show knowledge
+    ///   dxlog template show hypothesis --name benchmark
+    Show {
+        /// Log type of the template
+        #[arg(value_parser = PossibleValuesParser::new(TEMPLATE_KINDS), help = "Log type")]
+        kind: String,
+
+        /// Named template to show instead of the default one
+        #[arg(
+            short,
+            long,
+            help = "Name of the template (default: the type's default template)",
+            add = ArgValueCompleter::new(completion::template_names)
+        )]
+        name: Option<String>,
+    },
+
+    /// List the variables a template can use
+    ///
+    /// Includes the values defined under [templates.vars], available as `vars.<name>`.
+    ///
+    /// Examples:
+    ///   dxlog template vars literature
+    Vars {
+        /// Log type of the template
+        #[arg(value_parser = PossibleValuesParser::new(TEMPLATE_KINDS), help = "Log type")]
+        kind: String,
+    },
+
+    /// Open a template in $VISUAL or $EDITOR
+    ///
+    /// A default template that does not exist yet is first created from the
+    /// built-in one.
+    ///
+    /// Examples:
+    ///   dxlog template edit literature
+    ///   dxlog template edit hypothesis --name benchmark
+    Edit {
+        /// Log type of the template
+        #[arg(value_parser = PossibleValuesParser::new(TEMPLATE_KINDS), help = "Log type")]
+        kind: String,
+
+        /// Named template to edit instead of the default one
+        #[arg(
+            short,
+            long,
+            help = "Name of the template (default: the type's default template)",
+            add = ArgValueCompleter::new(completion::template_names)
+        )]
+        name: Option<String>,
+    },
+}
+
+impl TemplateCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::List { kind } => {
+                let mut rows = table::new(&["TYPE", "NAME", "PATH", "TAGS"]);
+                for template in list_templates(kind.as_deref())? {
+                    let missing = if template.exists { "" } else { " (missing)" };
+                    rows.add_row(vec![
+                        Cell::new(&template.kind),
+                        Cell::new(&template.name),
+                        Cell::new(format!("{}{}", template.path.display(), missing)),
+                        Cell::new(template.tags.join(", ")),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            Self::Show { kind, name } => {
+                let (_, content) = read_template(kind, name.as_deref())?;
+                print!("{}", content);
+                Ok(())
+            }
+            Self::Vars { kind } => {
+                let variables = template_variables(kind)?;
+                let width = variables.iter().map(|v| v.name.len()).max().unwrap_or(0);
+                for variable in variables {
+                    println!("{:<width$}  {}", variable.name, variable.description);
+                }
+                Ok(())
+            }
+            Self::Edit { kind, name } => {
+                let path = prepare_template_edit(kind, name.as_deref())?;
+                open_in_editor(&path)
+            }
+        }
+    }
+}
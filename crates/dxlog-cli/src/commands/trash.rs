@@ -0,0 +1,77 @@
+use anyhow::Result;
+use comfy_table::Cell;
+use dxlog::{empty_trash, list_trash, restore_from_trash};
+
+use crate::confirm::{self, Impact};
+use crate::table;
+
+#[derive(clap::Subcommand, Clone)]
+pub enum TrashCommands {
+    /// List deleted entries
+    ///
+    /// Example:
+    ///   dxlog trash list
+    List,
+
+    /// Restore a deleted entry
+    ///
+    /// Moves the entry back to the location it was deleted from.
+    ///
+    /// Example:
+    ///   dxlog trash restore 5e0f2abc
+    Restore {
+        /// ID of the deleted entry (can be partial)
+        #[arg(help = "Unique identifier or first few characters of the entry ID")]
+        id: String,
+    },
+
+    /// Permanently remove everything in the trash
+    ///
+    /// Example:
+    ///   dxlog trash empty
+    Empty,
+}
+
+impl TrashCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::List => {
+                let mut rows = table::new(&["ENTRY ID", "TYPE", "TITLE", "DELETED"]);
+                for tombstone in list_trash()? {
+                    let id = tombstone.id.to_string();
+                    let deleted_at = chrono::DateTime::parse_from_rfc3339(&tombstone.deleted_at)
+                        .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or(tombstone.deleted_at);
+
+                    rows.add_row(vec![
+                        table::dim(&table::entry_id(&id, tombstone.handle.as_deref())),
+                        Cell::new(&tombstone.kind),
+                        Cell::new(&tombstone.title),
+                        Cell::new(deleted_at),
+                    ]);
+                }
+                table::print(&rows);
+                Ok(())
+            }
+            Self::Restore { id } => {
+                let tombstone = restore_from_trash(id)?;
+                println!(
+                    "Restored {} \"{}\" to {}",
+                    tombstone.kind,
+                    tombstone.title,
+                    tombstone.original_path.display()
+                );
+                Ok(())
+            }
+            Self::Empty => {
+                confirm::require(
+                    "Permanently delete everything in the trash?",
+                    Impact::Destructive,
+                )?;
+                let count = empty_trash()?;
+                println!("Permanently deleted {} entries", count);
+                Ok(())
+            }
+        }
+    }
+}
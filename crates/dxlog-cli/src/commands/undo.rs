@@ -0,0 +1,19 @@
+use anyhow::Result;
+use dxlog::{last_operation, undo_last_operation};
+
+use crate::confirm::{self, Impact};
+
+/// Reverts the last operation recorded in the repository's journal.
+pub fn undo() -> Result<()> {
+    let Some(operation) = last_operation()? else {
+        println!("Nothing to undo");
+        return Ok(());
+    };
+    confirm::require(
+        &format!("Undo \"{}\"?", operation.description),
+        Impact::Destructive,
+    )?;
+    let operation = undo_last_operation()?;
+    println!("Undid \"{}\"", operation.description);
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use dxlog::{load_config, watch_entries, WatchEvent, WatchOptions};
+
+use crate::cli::print_warnings;
+
+#[derive(clap::Args, Clone)]
+pub struct WatchArgs {
+    /// Directory to export the site to
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Export the static site to this directory on every change, as `dxlog export site` does"
+    )]
+    site: Option<PathBuf>,
+
+    /// Keep the embeddings up to date
+    #[cfg(feature = "llm")]
+    #[arg(
+        long,
+        help = "Embed new and changed entries for `dxlog search --semantic` on every change"
+    )]
+    embeddings: bool,
+
+    /// API key
+    #[cfg(feature = "llm")]
+    #[arg(
+        long,
+        requires = "embeddings",
+        help = "API key (default: the environment variable named by [llm] api_key_env, OPENAI_API_KEY)"
+    )]
+    api_key: Option<String>,
+}
+
+impl WatchArgs {
+    pub fn execute(&self) -> Result<()> {
+        let root = load_config()?.root;
+        let relative = |path: &Path| {
+            path.strip_prefix(&root)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        };
+        let options = WatchOptions {
+            site: self.site.clone(),
+            #[cfg(feature = "llm")]
+            embeddings: self.embeddings,
+            #[cfg(feature = "llm")]
+            api_key: self.api_key.clone(),
+        };
+
+        // Warnings are printed after each change rather than when watching stops
+        watch_entries(&options, |event| {
+            match event {
+                WatchEvent::Started { dirs } => {
+                    let dirs: Vec<String> = dirs.iter().map(|dir| relative(dir)).collect();
+                    println!("Watching {}; press Ctrl-C to stop", dirs.join(", "));
+                }
+                WatchEvent::Changed { path } => println!("  changed  {}", relative(&path)),
+                WatchEvent::Invalid { path, reason } => {
+                    println!("  invalid  {}: {}", relative(&path), reason)
+                }
+                WatchEvent::Removed { path } => println!("  removed  {}", relative(&path)),
+                WatchEvent::SiteExported { dir, entries } => {
+                    println!("Exported {} entries to {}", entries, dir.display())
+                }
+                WatchEvent::Embedded { entries } => println!(
+                    "Embedded {} entr{}",
+                    entries,
+                    if entries == 1 { "y" } else { "ies" }
+                ),
+                WatchEvent::Failed { action, error } => {
+                    eprintln!("Warning: failed to {}: {}", action, error)
+                }
+            }
+            print_warnings();
+        })
+    }
+}
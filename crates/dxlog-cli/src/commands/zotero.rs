@@ -0,0 +1,85 @@
+use anyhow::Result;
+use clap_complete::ArgValueCompleter;
+use dxlog::{import_zotero, push_zotero_notes};
+
+use super::literature::print_import_summary;
+use crate::completion;
+
+#[derive(clap::Subcommand, Clone)]
+pub enum ZoteroCommands {
+    /// Create literature entries from a Zotero collection
+    ///
+    /// Fetches the items of a collection through the Zotero web API and creates
+    /// an entry for each one not imported before. Items whose DOI is already in
+    /// the collection are skipped. With --push-notes, completed reviews of
+    /// items imported from Zotero are then added to their items as notes.
+    ///
+    /// Create an API key at https://www.zotero.org/settings/keys; pass it with
+    /// --api-key or the ZOTERO_API_KEY environment variable.
+    ///
+    /// Examples:
+    ///   dxlog literature zotero import --collection "Thesis" --tags thesis
+    ///   dxlog literature zotero import --collection QX7A2B9C --group 123456 --push-notes
+    Import {
+        /// Zotero API key
+        #[arg(
+            long,
+            env = "ZOTERO_API_KEY",
+            hide_env_values = true,
+            help = "Zotero API key with read access (and write access for --push-notes)"
+        )]
+        api_key: String,
+
+        /// Collection name or key
+        #[arg(short, long, help = "Name or key of the Zotero collection")]
+        collection: String,
+
+        /// Group library ID
+        #[arg(long, help = "ID of a group library (default: your personal library)")]
+        group: Option<u64>,
+
+        /// Tags applied to every imported entry
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help_heading = "ORGANIZATION",
+            help = "Comma-separated list of tags",
+            add = ArgValueCompleter::new(completion::tags)
+        )]
+        tags: Option<Vec<String>>,
+
+        /// Push completed reviews back to Zotero
+        #[arg(long, help = "Add completed reviews to their Zotero items as notes")]
+        push_notes: bool,
+    },
+}
+
+impl ZoteroCommands {
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Import {
+                api_key,
+                collection,
+                group,
+                tags,
+                push_notes,
+            } => {
+                print_import_summary(&import_zotero(api_key, *group, collection, tags.clone())?);
+                if !push_notes {
+                    return Ok(());
+                }
+                for (literature, note) in push_zotero_notes(api_key)? {
+                    match note {
+                        Ok(_) => println!("Pushed review \"{}\" to Zotero", literature.base.title),
+                        Err(e) => eprintln!(
+                            "Failed to push review \"{}\": {:#}",
+                            literature.base.title, e
+                        ),
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
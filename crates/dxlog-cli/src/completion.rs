@@ -0,0 +1,276 @@
+use std::{collections::BTreeSet, ffi::OsStr, io::Write};
+
+use anyhow::Result;
+use clap_complete::{engine::CompletionCandidate, env::Shells, Shell};
+use dxlog::{
+    list_datasets, list_experiments, list_hypotheses, list_ideas, list_journal, list_knowledge,
+    list_literature, list_meetings, list_templates, load_config, utils::BaseLog, DatasetStatus,
+    HypothesisStatus, IdeaStatus, KnowledgeStatus, ListFilter, LiteratureStatus, LogStatus,
+    MeetingStatus,
+};
+
+/// Environment variable the generated scripts use to call back into dxlog.
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Writes the completion script for `shell` to stdout.
+///
+/// The script delegates back to `dxlog` on every <TAB>, which lets entry IDs and
+/// tags be completed from the current repository.
+pub fn write_registration(shell: Shell) -> Result<()> {
+    let name = shell.to_string();
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&name)
+        .ok_or_else(|| anyhow::anyhow!("Completions are not supported for {}", name))?;
+
+    let mut stdout = std::io::stdout();
+    completer.write_registration(COMPLETE_VAR, "dxlog", "dxlog", "dxlog", &mut stdout)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn id_candidates<'a>(
+    current: &OsStr,
+    logs: impl IntoIterator<Item = &'a BaseLog>,
+) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let mut candidates = Vec::new();
+    for log in logs {
+        let help = Some(log.title.clone().into());
+        if log.id.to_string().starts_with(current.as_ref()) {
+            candidates.push(CompletionCandidate::new(log.id.to_string()).help(help.clone()));
+        }
+        // Handles are only offered once typing has started, so an empty word
+        // does not list every entry twice.
+        if let Some(handle) = &log.handle {
+            if !current.is_empty() && handle.to_lowercase().starts_with(&current.to_lowercase()) {
+                candidates.push(CompletionCandidate::new(handle).help(help));
+            }
+        }
+    }
+    candidates
+}
+
+pub fn hypothesis_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let logs = list_hypotheses(&ListFilter::default()).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn active_hypothesis_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let filter = ListFilter::with_status(HypothesisStatus::ACTIVE);
+    let logs = list_hypotheses(&filter).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn literature_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let logs = list_literature(&ListFilter::default()).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn in_progress_literature_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let filter = ListFilter::with_status(LiteratureStatus::InProgress);
+    let logs = list_literature(&filter).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn knowledge_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let logs = list_knowledge(&ListFilter::default()).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn draft_knowledge_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let filter = ListFilter::with_status(KnowledgeStatus::Draft);
+    let logs = list_knowledge(&filter).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn in_review_knowledge_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let filter = ListFilter::with_status(KnowledgeStatus::InReview);
+    let logs = list_knowledge(&filter).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn journal_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let logs = list_journal(&ListFilter::default()).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn dataset_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let logs = list_datasets(&ListFilter::default()).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn candidate_dataset_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let filter = ListFilter::with_status(DatasetStatus::Candidate);
+    let logs = list_datasets(&filter).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn meeting_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let logs = list_meetings(&ListFilter::default()).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn open_meeting_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let filter = ListFilter::with_status(MeetingStatus::Open);
+    let logs = list_meetings(&filter).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn experiment_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let logs = list_experiments(&ListFilter::default()).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn open_experiment_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let logs = list_experiments(&ListFilter::default()).unwrap_or_default();
+    let open = logs.iter().filter(|l| !l.status.is_finished());
+    id_candidates(current, open.map(|l| &l.base))
+}
+
+pub fn inbox_idea_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let filter = ListFilter::with_status(IdeaStatus::Inbox);
+    let logs = list_ideas(&filter).unwrap_or_default();
+    id_candidates(current, logs.iter().map(|l| &l.base))
+}
+
+pub fn any_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let mut candidates = hypothesis_ids(current);
+    candidates.extend(literature_ids(current));
+    candidates.extend(knowledge_ids(current));
+    candidates.extend(journal_ids(current));
+    candidates.extend(dataset_ids(current));
+    candidates.extend(meeting_ids(current));
+    candidates.extend(experiment_ids(current));
+    candidates
+}
+
+/// Completes the last item of a comma-separated tag list.
+pub fn tags(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let (prefix, partial) = match current.rfind(',') {
+        Some(idx) => current.split_at(idx + 1),
+        None => ("", current.as_ref()),
+    };
+
+    let mut known = BTreeSet::new();
+    for log in list_hypotheses(&ListFilter::default()).unwrap_or_default() {
+        known.extend(log.base.tags);
+    }
+    for log in list_literature(&ListFilter::default()).unwrap_or_default() {
+        known.extend(log.base.tags);
+    }
+    for log in list_knowledge(&ListFilter::default()).unwrap_or_default() {
+        known.extend(log.base.tags);
+    }
+    for log in list_journal(&ListFilter::default()).unwrap_or_default() {
+        known.extend(log.base.tags);
+    }
+    for log in list_datasets(&ListFilter::default()).unwrap_or_default() {
+        known.extend(log.base.tags);
+    }
+    for log in list_meetings(&ListFilter::default()).unwrap_or_default() {
+        known.extend(log.base.tags);
+    }
+    for log in list_ideas(&ListFilter::default()).unwrap_or_default() {
+        known.extend(log.base.tags);
+    }
+    for log in list_experiments(&ListFilter::default()).unwrap_or_default() {
+        known.extend(log.base.tags);
+    }
+
+    known
+        .into_iter()
+        .filter(|tag| tag.starts_with(partial))
+        .map(|tag| CompletionCandidate::new(tag).add_prefix(prefix))
+        .collect()
+}
+
+pub fn hypothesis_statuses(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let config = load_config().unwrap_or_default();
+    HypothesisStatus::all(&config)
+        .into_iter()
+        .map(|status| status.to_string())
+        .filter(|status| status.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Completes the log types declared under [types].
+pub fn log_types(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    dxlog::log_types()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|log_type| !log_type.built_in && log_type.name.starts_with(current.as_ref()))
+        .map(|log_type| {
+            let help = (!log_type.description.is_empty()).then(|| log_type.description.into());
+            CompletionCandidate::new(log_type.name).help(help)
+        })
+        .collect()
+}
+
+/// Completes every log type, built in or declared under [types].
+pub fn all_log_types(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    dxlog::log_types()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|log_type| log_type.name.starts_with(current.as_ref()))
+        .map(|log_type| CompletionCandidate::new(log_type.name))
+        .collect()
+}
+
+/// Completes the statuses of every log type declared under [types]; the
+/// type given earlier on the command line is not known to the completer.
+pub fn log_type_statuses(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let statuses: BTreeSet<String> = dxlog::log_types()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|log_type| !log_type.built_in)
+        .flat_map(|log_type| log_type.statuses)
+        .collect();
+    statuses
+        .into_iter()
+        .filter(|status| status.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+pub fn projects(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let projects = load_config().map(|c| c.projects).unwrap_or_default();
+    projects
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(current.as_ref()))
+        .map(|(name, project)| {
+            let help = (!project.description.is_empty()).then(|| project.description.into());
+            CompletionCandidate::new(name).help(help)
+        })
+        .collect()
+}
+
+pub fn team_members(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let team = load_config().map(|c| c.team).unwrap_or_default();
+    team.into_iter()
+        .filter(|(handle, _)| handle.starts_with(current.as_ref()))
+        .map(|(handle, member)| CompletionCandidate::new(handle).help(Some(member.name.into())))
+        .collect()
+}
+
+pub fn template_names(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let names: BTreeSet<String> = list_templates(None)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    names
+        .into_iter()
+        .filter(|name| name.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
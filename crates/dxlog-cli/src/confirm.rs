@@ -0,0 +1,67 @@
+//! Confirmation prompts, governed by `[cli] confirm` and `--yes`.
+
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+use dxlog::{is_dry_run, load_config, ConfirmMode};
+
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Answers every question with yes, for `--yes`.
+pub fn assume_yes() {
+    ASSUME_YES.store(true, Ordering::Relaxed);
+}
+
+/// How far-reaching a command is, which decides whether `[cli] confirm` asks about it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Impact {
+    /// Moves or rewrites an existing entry.
+    Change,
+    /// Deletes or archives entries.
+    Destructive,
+}
+
+/// Asks `question` if the configured mode calls for it, failing if the user declines.
+///
+/// Nothing is asked with `--yes` or `--dry-run`.
+pub fn require(question: &str, impact: Impact) -> Result<()> {
+    if is_dry_run() {
+        return Ok(());
+    }
+    let mode = load_config()
+        .map(|config| config.cli.confirm)
+        .unwrap_or_default();
+    let needed = match mode {
+        ConfirmMode::Always => true,
+        ConfirmMode::Destructive => impact == Impact::Destructive,
+        ConfirmMode::Never => false,
+    };
+    if needed && !ask(question)? {
+        return Err(anyhow::anyhow!("Cancelled"));
+    }
+    Ok(())
+}
+
+/// Asks a yes/no question whatever the configured mode; `--yes` still answers it.
+///
+/// Without a terminal to ask on, this fails rather than waiting for input.
+pub fn ask(question: &str) -> Result<bool> {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        return Ok(true);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(anyhow::anyhow!(
+            "{} Standard input is not a terminal; pass --yes to answer yes",
+            question
+        ));
+    }
+
+    print!("{} [y/N]: ", question);
+    std::io::stdout().flush()?;
+
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,88 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use clap::{Command, CommandFactory};
+use clap_mangen::Man;
+
+use crate::cli::Cli;
+
+/// The command line with global options propagated and the `help`
+/// subcommands left out.
+fn command() -> Command {
+    let mut command = Cli::command();
+    command.build();
+    command
+}
+
+fn subcommands(command: &Command) -> impl Iterator<Item = &Command> {
+    command
+        .get_subcommands()
+        .filter(|sub| !sub.is_hide_set() && sub.get_name() != "help")
+}
+
+fn write_page(command: &Command, dir: &Path, written: &mut Vec<PathBuf>) -> Result<()> {
+    // Built subcommands are displayed as `dxlog-hypothesis-new`, which names the page.
+    let path = Man::new(command.clone())
+        .generate_to(dir)
+        .with_context(|| format!("Failed to write a man page to {}", dir.display()))?;
+    written.push(path);
+
+    for sub in subcommands(command) {
+        write_page(sub, dir, written)?;
+    }
+    Ok(())
+}
+
+/// Writes a man page for `dxlog` and every subcommand to `dir`, e.g.
+/// `dxlog.1` and `dxlog-hypothesis-new.1`. Returns the paths written.
+pub fn write_man_pages(dir: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut written = Vec::new();
+    write_page(&command(), dir, &mut written)?;
+    Ok(written)
+}
+
+fn write_tree(command: &Command, depth: usize, out: &mut impl Write) -> Result<()> {
+    let subs: Vec<&Command> = subcommands(command).collect();
+    let width = subs
+        .iter()
+        .map(|sub| sub.get_name().len())
+        .max()
+        .unwrap_or(0);
+    for sub in subs {
+        let about = sub
+            .get_about()
+            .map(|about| about.to_string())
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{}{:width$}  {}",
+            "  ".repeat(depth),
+            sub.get_name(),
+            about
+        )?;
+        write_tree(sub, depth + 1, out)?;
+    }
+    Ok(())
+}
+
+/// Prints every command and subcommand with its one-line description, for
+/// `dxlog --help-all`.
+pub fn print_command_tree() -> Result<()> {
+    let command = command();
+    let mut stdout = std::io::stdout();
+    if let Some(about) = command.get_about() {
+        writeln!(stdout, "{} - {}\n", command.get_name(), about)?;
+    }
+    write_tree(&command, 0, &mut stdout)?;
+    writeln!(
+        stdout,
+        "\nRun `dxlog <command> --help` for the options of a command."
+    )?;
+    stdout.flush()?;
+    Ok(())
+}
@@ -0,0 +1,31 @@
+//! Diagnostic output of dxlog, written to stderr.
+
+use tracing_subscriber::{fmt::time::uptime, EnvFilter};
+
+/// Environment variable holding `tracing` filter directives, e.g. `dxlog=debug`
+/// or `dxlog_tools=trace,dxlog=info`. Takes precedence over `-v`.
+pub const LOG_ENV_VAR: &str = "DXLOG_LOG";
+
+/// Filter used without [`LOG_ENV_VAR`]: warnings only by default, what dxlog
+/// reads, writes and fetches with `-v`, and every entry file it parses with `-vv`.
+fn default_filter(verbosity: u8) -> &'static str {
+    match verbosity {
+        0 => "warn",
+        1 => "warn,dxlog=debug,dxlog_tools=debug",
+        _ => "warn,dxlog=trace,dxlog_tools=trace",
+    }
+}
+
+/// Sends tracing output to stderr, timed from the start of the command.
+pub fn init(verbosity: u8) {
+    let filter = match std::env::var(LOG_ENV_VAR) {
+        Ok(directives) if !directives.trim().is_empty() => EnvFilter::new(directives),
+        _ => EnvFilter::new(default_filter(verbosity)),
+    };
+    // Only fails if a subscriber is already set, which is then kept.
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_timer(uptime())
+        .with_writer(std::io::stderr)
+        .try_init();
+}
@@ -1,11 +1,26 @@
+mod alias;
 mod cli;
 mod commands;
+mod completion;
+mod confirm;
+mod docs;
+mod logging;
+mod mcp;
+mod pick;
+mod server;
+mod table;
+mod workspace;
 
-use clap::Parser;
+use clap::CommandFactory;
+use clap_complete::CompleteEnv;
 use cli::Cli;
 
 fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+    CompleteEnv::with_factory(Cli::command)
+        .var(completion::COMPLETE_VAR)
+        .complete();
+
+    let cli = Cli::parse_args(alias::expand(std::env::args_os().collect())?)?;
 
     cli.run()
 }
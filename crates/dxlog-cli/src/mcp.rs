@@ -0,0 +1,382 @@
+use std::io::{self, BufRead, Write};
+
+use anyhow::Result;
+use dxlog::{
+    add_reference, load_config, search_logs, Config, DatasetManager, DatasetStatus,
+    ExperimentManager, ExperimentStatus, HypothesisManager, HypothesisStatus, IdeaManager,
+    JournalManager, KnowledgeManager, KnowledgeStatus, LiteratureManager, LiteratureStatus,
+    LogStatus, MeetingManager, MeetingStatus, ReferenceKind,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+use crate::cli::print_warnings;
+
+/// MCP revision implemented here, used when the client does not ask for one.
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Deserialize)]
+struct Request {
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+#[derive(Deserialize)]
+struct CreateArgs {
+    title: String,
+    tags: Option<Vec<String>>,
+    body: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchArgs {
+    query: String,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IdArgs {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct ReferenceArgs {
+    source: String,
+    target: String,
+    kind: Option<ReferenceKind>,
+}
+
+#[derive(Deserialize)]
+struct StatusArgs {
+    id: String,
+    status: String,
+    reason: Option<String>,
+    #[serde(default)]
+    force: bool,
+}
+
+/// Serves the Model Context Protocol over stdin/stdout until stdin is closed.
+///
+/// Messages are newline-delimited JSON-RPC 2.0. Nothing else may be written to
+/// stdout while the server runs.
+pub fn run() -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => handle(request),
+            Err(err) => Some(error_response(Value::Null, PARSE_ERROR, &err.to_string())),
+        };
+        if let Some(response) = response {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the response for a request, or `None` for notifications.
+fn handle(request: Request) -> Option<Value> {
+    let id = request.id?;
+    let result = match request.method.as_str() {
+        "initialize" => initialize(&request.params),
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tools() }),
+        "tools/call" => match serde_json::from_value::<ToolCall>(request.params) {
+            Ok(call) => {
+                let result = call_tool(&call);
+                print_warnings();
+                result
+            }
+            Err(err) => return Some(error_response(id, INVALID_PARAMS, &err.to_string())),
+        },
+        method => {
+            let message = format!("Method not found: {}", method);
+            return Some(error_response(id, METHOD_NOT_FOUND, &message));
+        }
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    let version = params
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .unwrap_or(PROTOCOL_VERSION);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "dxlog", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "create_hypothesis",
+            "description": "Create a new research hypothesis in 'active' status. Returns the new entry.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string", "description": "Title of the hypothesis" },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "body": { "type": "string", "description": "Markdown content for the entry" }
+                },
+                "required": ["title"]
+            }
+        },
+        {
+            "name": "create_knowledge",
+            "description": "Create a new knowledge base entry in 'draft' status. Returns the new entry.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string", "description": "Title of the entry" },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "body": { "type": "string", "description": "Markdown content for the entry" }
+                },
+                "required": ["title"]
+            }
+        },
+        {
+            "name": "search_logs",
+            "description": "Search hypotheses, literature reviews, knowledge entries, journal days, datasets, meetings, ideas and experiments by title, tag or body text (case-insensitive). Returns matching entries, newest first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Text to look for" },
+                    "type": {
+                        "type": "string",
+                        "enum": ["hypothesis", "literature", "knowledge", "journal", "dataset", "meeting", "idea", "experiment"],
+                        "description": "Only search this log type"
+                    }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "get_entry",
+            "description": "Fetch an entry's frontmatter and markdown body by ID. The ID can be partial.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Entry ID, its first characters, or its handle such as H-042" }
+                },
+                "required": ["id"]
+            }
+        },
+        {
+            "name": "add_reference",
+            "description": "Record that one entry references another, optionally saying why.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "source": { "type": "string", "description": "ID of the referencing entry" },
+                    "target": { "type": "string", "description": "ID of the referenced entry" },
+                    "kind": { "type": "string", "enum": ["supports", "refutes", "extends", "uses"] }
+                },
+                "required": ["source", "target"]
+            }
+        },
+        {
+            "name": "update_status",
+            "description": "Change the status of an entry, e.g. mark a hypothesis 'proven' or publish a knowledge entry. Respects the repository's workflow rules unless force is set.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Entry ID, its first characters, or its handle such as H-042" },
+                    "status": { "type": "string", "description": "New status, e.g. proven, completed, published" },
+                    "reason": { "type": "string", "description": "Why the status is changing; recorded in the status history and the entry's notes" },
+                    "force": { "type": "boolean", "description": "Bypass the configured workflow" }
+                },
+                "required": ["id", "status"]
+            }
+        }
+    ])
+}
+
+/// Runs a tool. Failures are reported to the model as tool errors rather than
+/// protocol errors, so it can correct itself.
+fn call_tool(call: &ToolCall) -> Value {
+    match run_tool(call) {
+        Ok(output) => json!({
+            "content": [{ "type": "text", "text": serde_json::to_string_pretty(&output).unwrap_or_default() }],
+        }),
+        Err(err) => json!({
+            "content": [{ "type": "text", "text": format!("{:#}", err) }],
+            "isError": true,
+        }),
+    }
+}
+
+fn args<T: DeserializeOwned>(call: &ToolCall) -> Result<T> {
+    serde_json::from_value(call.arguments.clone())
+        .map_err(|e| anyhow::anyhow!("Invalid arguments for {}: {}", call.name, e))
+}
+
+fn run_tool(call: &ToolCall) -> Result<Value> {
+    match call.name.as_str() {
+        "create_hypothesis" => {
+            let args: CreateArgs = args(call)?;
+            let manager = HypothesisManager::new(load_config()?);
+            Ok(serde_json::to_value(manager.create(
+                &args.title,
+                args.tags,
+                args.body.as_deref(),
+                None,
+                false,
+                None,
+            )?)?)
+        }
+        "create_knowledge" => {
+            let args: CreateArgs = args(call)?;
+            let manager = KnowledgeManager::new(load_config()?);
+            Ok(serde_json::to_value(manager.create(
+                &args.title,
+                args.tags,
+                args.body.as_deref(),
+                None,
+                false,
+                None,
+            )?)?)
+        }
+        "search_logs" => {
+            let args: SearchArgs = args(call)?;
+            Ok(serde_json::to_value(search_logs(
+                &args.query,
+                args.kind.as_deref(),
+            )?)?)
+        }
+        "get_entry" => {
+            let args: IdArgs = args(call)?;
+            get_entry(&load_config()?, &args.id)
+        }
+        "add_reference" => {
+            let args: ReferenceArgs = args(call)?;
+            add_reference(&args.source, &args.target, args.kind, false)?;
+            Ok(json!({ "source": args.source, "target": args.target, "kind": args.kind }))
+        }
+        "update_status" => {
+            let args: StatusArgs = args(call)?;
+            update_status(&load_config()?, &args)
+        }
+        name => Err(anyhow::anyhow!("Unknown tool '{}'", name)),
+    }
+}
+
+fn with_body<T: serde::Serialize>(kind: &str, log: &T, body: String) -> Result<Value> {
+    let mut value = serde_json::to_value(log)?;
+    if let Value::Object(fields) = &mut value {
+        fields.insert("type".to_string(), json!(kind));
+        fields.insert("body".to_string(), json!(body));
+    }
+    Ok(value)
+}
+
+fn get_entry(config: &Config, id: &str) -> Result<Value> {
+    let h_manager = HypothesisManager::new(config.clone());
+    let l_manager = LiteratureManager::new(config.clone());
+    let k_manager = KnowledgeManager::new(config.clone());
+    let j_manager = JournalManager::new(config.clone());
+    let d_manager = DatasetManager::new(config.clone());
+    let m_manager = MeetingManager::new(config.clone());
+    let i_manager = IdeaManager::new(config.clone());
+    let e_manager = ExperimentManager::new(config.clone());
+
+    if let Ok((log, path)) = h_manager.find(id) {
+        with_body("hypothesis", &log, h_manager.manager.read_body(&path)?)
+    } else if let Ok((log, path)) = l_manager.find(id) {
+        with_body("literature", &log, l_manager.manager.read_body(&path)?)
+    } else if let Ok((log, path)) = k_manager.find(id) {
+        with_body("knowledge", &log, k_manager.manager.read_body(&path)?)
+    } else if let Ok((log, path)) = j_manager.find(id) {
+        with_body("journal", &log, j_manager.manager.read_body(&path)?)
+    } else if let Ok((log, path)) = d_manager.find(id) {
+        with_body("dataset", &log, d_manager.manager.read_body(&path)?)
+    } else if let Ok((log, path)) = m_manager.find(id) {
+        with_body("meeting", &log, m_manager.manager.read_body(&path)?)
+    } else if let Ok((log, path)) = i_manager.find(id) {
+        with_body("idea", &log, i_manager.manager.read_body(&path)?)
+    } else if let Ok((log, path)) = e_manager.find(id) {
+        with_body("experiment", &log, e_manager.manager.read_body(&path)?)
+    } else {
+        Err(anyhow::anyhow!(
+            "No entry found with ID starting with '{}'",
+            id
+        ))
+    }
+}
+
+fn parse_status<T: LogStatus>(config: &Config, status: &str) -> Result<T> {
+    T::parse(config, status)
+        .map_err(|_| anyhow::anyhow!("'{}' is not a valid status for this entry", status))
+}
+
+fn update_status(config: &Config, args: &StatusArgs) -> Result<Value> {
+    let h_manager = HypothesisManager::new(config.clone());
+    let l_manager = LiteratureManager::new(config.clone());
+    let k_manager = KnowledgeManager::new(config.clone());
+    let d_manager = DatasetManager::new(config.clone());
+    let m_manager = MeetingManager::new(config.clone());
+    let e_manager = ExperimentManager::new(config.clone());
+
+    if h_manager.find(&args.id).is_ok() {
+        let status: HypothesisStatus = parse_status(config, &args.status)?;
+        h_manager.update_status(&args.id, status, args.reason.as_deref(), args.force)?;
+        Ok(serde_json::to_value(h_manager.find(&args.id)?.0)?)
+    } else if l_manager.find(&args.id).is_ok() {
+        let status: LiteratureStatus = parse_status(config, &args.status)?;
+        l_manager.update_status(&args.id, status, args.reason.as_deref(), args.force)?;
+        Ok(serde_json::to_value(l_manager.find(&args.id)?.0)?)
+    } else if k_manager.find(&args.id).is_ok() {
+        let status: KnowledgeStatus = parse_status(config, &args.status)?;
+        k_manager.update_status(&args.id, status, args.reason.as_deref(), args.force)?;
+        Ok(serde_json::to_value(k_manager.find(&args.id)?.0)?)
+    } else if d_manager.find(&args.id).is_ok() {
+        let status: DatasetStatus = parse_status(config, &args.status)?;
+        d_manager.update_status(&args.id, status, args.reason.as_deref(), args.force)?;
+        Ok(serde_json::to_value(d_manager.find(&args.id)?.0)?)
+    } else if m_manager.find(&args.id).is_ok() {
+        let status: MeetingStatus = parse_status(config, &args.status)?;
+        m_manager.update_status(&args.id, status, args.reason.as_deref(), args.force)?;
+        Ok(serde_json::to_value(m_manager.find(&args.id)?.0)?)
+    } else if e_manager.find(&args.id).is_ok() {
+        let status: ExperimentStatus = parse_status(config, &args.status)?;
+        e_manager.update_status(&args.id, status, args.reason.as_deref(), args.force)?;
+        Ok(serde_json::to_value(e_manager.find(&args.id)?.0)?)
+    } else {
+        Err(anyhow::anyhow!(
+            "No entry found with ID starting with '{}'",
+            args.id
+        ))
+    }
+}
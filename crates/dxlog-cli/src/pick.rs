@@ -0,0 +1,238 @@
+//! Choosing an entry from a fuzzy-searchable list when an ID is missing or
+//! matches more than one entry.
+
+use std::{ffi::OsString, io::IsTerminal};
+
+use anyhow::Result;
+use clap::{
+    error::{ContextKind, ContextValue, ErrorKind},
+    CommandFactory,
+};
+use comfy_table::Cell;
+use dialoguer::{theme::ColorfulTheme, FuzzySelect};
+use dxlog::{matching_entries, EntryMatch, LogError};
+
+use crate::{alias, cli::Cli, table};
+
+/// Commands named after the entry type they work on.
+const TYPE_COMMANDS: &[&str] = &[
+    "hypothesis",
+    "literature",
+    "knowledge",
+    "journal",
+    "dataset",
+    "meeting",
+    "idea",
+    "experiment",
+];
+
+/// The list is only offered when someone is there to choose; otherwise the
+/// usual error is reported.
+fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// Type of entry the command works on, e.g. `hypothesis` for `dxlog hyp proven`,
+/// or `None` for commands taking entries of any type.
+fn command_kind(args: &[OsString]) -> Option<&'static str> {
+    let name = args.get(alias::command_index(args)?)?.to_str()?;
+    let command = Cli::command();
+    let name = command.find_subcommand(name)?.get_name();
+    TYPE_COMMANDS.iter().copied().find(|kind| *kind == name)
+}
+
+/// Lets the user choose one of `entries` and returns its full ID.
+fn choose(prompt: &str, entries: &[EntryMatch]) -> Result<String> {
+    let items: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}  {}  ({}, {})",
+                table::entry_id(&entry.id.to_string(), entry.handle.as_deref()),
+                entry.title,
+                entry.kind,
+                entry.status
+            )
+        })
+        .collect();
+    let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(&items)
+        .default(0)
+        .interact_opt()?;
+    let index = selection.ok_or_else(|| anyhow::anyhow!("Cancelled"))?;
+    Ok(entries[index].id.to_string())
+}
+
+/// For a command line that failed to parse because an entry ID is missing,
+/// lets the user choose the entry and returns the command line with its ID
+/// added. Returns `None` for other errors and without a terminal.
+pub fn missing_id(args: &[OsString], err: &clap::Error) -> Result<Option<Vec<OsString>>> {
+    if err.kind() != ErrorKind::MissingRequiredArgument || !is_interactive() {
+        return Ok(None);
+    }
+    let Some(ContextValue::Strings(missing)) = err.get(ContextKind::InvalidArg) else {
+        return Ok(None);
+    };
+    let [name] = missing.as_slice() else {
+        return Ok(None);
+    };
+    // Commands taking several IDs show theirs as `<ID>...`.
+    let name = name.trim_end_matches("...");
+    if !name.ends_with("ID>") {
+        return Ok(None);
+    }
+    // Outside a repository there is nothing to choose from.
+    let Ok(entries) = matching_entries(command_kind(args), "") else {
+        return Ok(None);
+    };
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    let id = choose(&format!("Choose an entry for {}", name), &entries)?;
+    let mut args = args.to_vec();
+    args.push(id.into());
+    Ok(Some(args))
+}
+
+/// For a command line with partial IDs that match several entries, lets the
+/// user choose one for each and returns the command line with their full IDs
+/// in place of the partial ones. This happens before the command runs, so
+/// nothing is changed until every entry is known.
+///
+/// Returns `None` if no ID is ambiguous, and without a terminal, in which
+/// case the command reports the ambiguity itself.
+pub fn ambiguous_ids(args: &[OsString]) -> Result<Option<Vec<OsString>>> {
+    if !is_interactive() {
+        return Ok(None);
+    }
+    let kind = command_kind(args);
+    let mut resolved = args.to_vec();
+    for (position, partial_id) in entry_id_args(args) {
+        // Outside a repository there is nothing to choose from.
+        let Ok(entries) = matching_entries(kind, &partial_id) else {
+            return Ok(None);
+        };
+        if entries.len() < 2 {
+            continue;
+        }
+        let id = choose(
+            &format!("'{}' matches {} entries", partial_id, entries.len()),
+            &entries,
+        )?;
+        resolved[position] = id.into();
+    }
+    Ok(Some(resolved).filter(|resolved| resolved.as_slice() != args))
+}
+
+/// The entry IDs `args` gives the command it runs, with their positions in
+/// `args`. Entry IDs are the arguments shown like `<ID>` or `<TARGET_ID>`.
+fn entry_id_args(args: &[OsString]) -> Vec<(usize, String)> {
+    let Ok(matches) = Cli::command().try_get_matches_from(args) else {
+        return Vec::new();
+    };
+    // Clap numbers the arguments of a subcommand from its name, so the
+    // position of each subcommand name is kept to place them in `args`.
+    let mut command = Cli::command();
+    let mut matches = &matches;
+    let mut offset = 0;
+    while let Some((name, sub)) = matches.subcommand() {
+        let Some(next) = command.find_subcommand(name) else {
+            break;
+        };
+        let Some(index) = (offset + 1..args.len()).find(|&i| {
+            args[i]
+                .to_str()
+                .and_then(|arg| command.find_subcommand(arg))
+                .is_some_and(|found| found.get_name() == name)
+        }) else {
+            return Vec::new();
+        };
+        command = next.clone();
+        matches = sub;
+        offset = index;
+    }
+
+    let mut values = Vec::new();
+    for arg in command.get_positionals() {
+        let name = match arg.get_value_names() {
+            Some([name, ..]) => name.to_string(),
+            _ => arg.get_id().as_str().to_uppercase(),
+        };
+        if !name.ends_with("ID") && !name.ends_with("IDS") {
+            continue;
+        }
+        let id = arg.get_id().as_str();
+        if let (Ok(Some(raw)), Some(indices)) = (matches.try_get_raw(id), matches.indices_of(id)) {
+            values.extend(indices.zip(raw));
+        }
+    }
+    values.sort_by_key(|(index, _)| *index);
+
+    // Clap counts `--option=value` as two arguments, so an index can be ahead
+    // of the argument's position, but never behind it. Taking the IDs in
+    // order, each is the first equal argument after the one before.
+    let mut ids = Vec::new();
+    let mut after = offset;
+    for (index, value) in values {
+        let Some(partial_id) = value.to_str() else {
+            continue;
+        };
+        let last = (offset + index).min(args.len() - 1);
+        if let Some(position) = (after + 1..=last).find(|&i| args[i] == value) {
+            ids.push((position, partial_id.to_string()));
+            after = position;
+        }
+    }
+    ids
+}
+
+/// Prints the entries an ambiguous ID matches, so the user can tell which
+/// one they meant.
+pub fn print_matches(err: &anyhow::Error) {
+    let Some(LogError::Ambiguous { matches, .. }) = err.downcast_ref::<LogError>() else {
+        return;
+    };
+    let mut rows = table::new(&["TYPE", "ID", "TITLE", "STATUS"]);
+    for entry in matches {
+        rows.add_row(vec![
+            Cell::new(&entry.kind),
+            table::dim(&table::entry_id(
+                &entry.id.to_string(),
+                entry.handle.as_deref(),
+            )),
+            Cell::new(&entry.title),
+            table::status(&entry.status),
+        ]);
+    }
+    table::eprint(&rows);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<OsString> {
+        line.split_whitespace().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_entry_id_args_keep_their_positions() {
+        let ids = |line: &str| entry_id_args(&args(line));
+
+        assert_eq!(
+            ids("dxlog reference add abc abc"),
+            vec![(3, "abc".to_string()), (4, "abc".to_string())]
+        );
+        assert_eq!(
+            ids("dxlog reference add --kind=supports abc abc"),
+            vec![(4, "abc".to_string()), (5, "abc".to_string())]
+        );
+        assert_eq!(
+            ids("dxlog --offline ref add abc --wiki-link abc"),
+            vec![(4, "abc".to_string()), (6, "abc".to_string())]
+        );
+        assert_eq!(ids("dxlog hypothesis list"), Vec::new());
+    }
+}
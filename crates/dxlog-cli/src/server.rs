@@ -0,0 +1,738 @@
+use std::net::SocketAddr;
+
+use anyhow::Result;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use dxlog::{
+    add_reference_in, assignee_filter, list_references_in, load_config, remove_reference_in,
+    utils::TagFilter, Config, EntryMatch, HypothesisLog, HypothesisManager, HypothesisStatus,
+    KnowledgeLog, KnowledgeManager, KnowledgeStatus, ListFilter, LiteratureLog, LiteratureManager,
+    LiteratureStatus, LogError, LogStatus, ReferenceInfo, ReferenceKind, TextQuery,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::print_warnings;
+
+#[derive(Clone)]
+struct AppState {
+    config: Config,
+}
+
+struct ApiError(anyhow::Error);
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+    /// The entries an ambiguous ID matches.
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    matches: Vec<EntryMatch>,
+}
+
+impl ApiError {
+    /// Failures reading or writing the repository, or of the task serving the
+    /// request, are the server's; anything else rejects the request itself.
+    fn is_internal(&self) -> bool {
+        self.0
+            .chain()
+            .any(|cause| cause.is::<std::io::Error>() || cause.is::<tokio::task::JoinError>())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self.0.downcast_ref() {
+            Some(LogError::NotFound { .. }) => StatusCode::NOT_FOUND,
+            Some(LogError::DuplicateTitle { .. } | LogError::Referenced { .. }) => {
+                StatusCode::CONFLICT
+            }
+            Some(LogError::Ambiguous { .. }) => StatusCode::BAD_REQUEST,
+            None if self.is_internal() => StatusCode::INTERNAL_SERVER_ERROR,
+            None => StatusCode::BAD_REQUEST,
+        };
+        let matches = match self.0.downcast_ref() {
+            Some(LogError::Ambiguous { matches, .. }) => matches.clone(),
+            _ => Vec::new(),
+        };
+        let body = ErrorBody {
+            error: format!("{:#}", self.0),
+            matches,
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+impl<E: Into<anyhow::Error>> From<E> for ApiError {
+    fn from(err: E) -> Self {
+        Self(err.into())
+    }
+}
+
+type ApiResult<T> = std::result::Result<Json<T>, ApiError>;
+
+/// Runs blocking library code (file and git I/O) off the async executor.
+async fn blocking<T, F>(f: F) -> ApiResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    let value = tokio::task::spawn_blocking(|| {
+        let value = f();
+        print_warnings();
+        value
+    })
+    .await??;
+    Ok(Json(value))
+}
+
+/// Parses a value the same way the CLI does; `in_progress` and `in-progress` are both accepted.
+fn parse_value<T: ValueEnum>(value: &str) -> Result<T> {
+    T::from_str(&value.replace('_', "-"), true).map_err(|_| {
+        let expected: Vec<String> = T::value_variants()
+            .iter()
+            .filter_map(|v| v.to_possible_value())
+            .map(|v| v.get_name().to_string())
+            .collect();
+        anyhow::anyhow!(
+            "Invalid value '{}', expected one of: {}",
+            value,
+            expected.join(", ")
+        )
+    })
+}
+
+fn split_tags(tags: Option<String>) -> Vec<String> {
+    tags.map(|t| t.split(',').map(|s| s.trim().to_string()).collect())
+        .unwrap_or_default()
+}
+
+fn parse_date(value: Option<String>) -> Result<Option<NaiveDate>> {
+    value
+        .map(|d| {
+            NaiveDate::parse_from_str(&d, "%Y-%m-%d")
+                .map_err(|_| anyhow::anyhow!("Invalid date '{}', expected YYYY-MM-DD", d))
+        })
+        .transpose()
+}
+
+/// Query parameters shared by the list endpoints, mirroring the `list` command flags.
+#[derive(Deserialize)]
+struct ListQuery {
+    status: Option<String>,
+    /// Comma-separated, like `--tags`.
+    tags: Option<String>,
+    /// Comma-separated, like `--any-tag`.
+    any_tags: Option<String>,
+    /// Comma-separated, like `--exclude-tag`.
+    exclude_tags: Option<String>,
+    author: Option<String>,
+    project: Option<String>,
+    assignee: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    query: Option<String>,
+    #[serde(default)]
+    regex: bool,
+    #[serde(default)]
+    body: bool,
+    sort: Option<String>,
+    #[serde(default)]
+    reverse: bool,
+    limit: Option<usize>,
+}
+
+impl ListQuery {
+    fn into_filter<S: LogStatus>(self, config: &Config) -> Result<ListFilter<S>> {
+        Ok(ListFilter {
+            status: self
+                .status
+                .as_deref()
+                .map(|status| S::parse(config, status))
+                .transpose()?,
+            tags: TagFilter {
+                all: split_tags(self.tags),
+                any: split_tags(self.any_tags),
+                none: split_tags(self.exclude_tags),
+            },
+            author: self.author,
+            project: self.project,
+            assignee: self.assignee.as_deref().map(assignee_filter).transpose()?,
+            since: parse_date(self.since)?,
+            until: parse_date(self.until)?,
+            query: self
+                .query
+                .as_deref()
+                .map(|query| TextQuery::new(query, self.regex, self.body))
+                .transpose()?,
+            sort: self
+                .sort
+                .as_deref()
+                .map(parse_value)
+                .transpose()?
+                .unwrap_or_default(),
+            reverse: self.reverse,
+            limit: self.limit,
+        })
+    }
+}
+
+/// A single entry together with its markdown body.
+#[derive(Serialize)]
+struct Entry<T> {
+    #[serde(flatten)]
+    log: T,
+    body: String,
+}
+
+#[derive(Deserialize)]
+struct NewEntry {
+    title: String,
+    tags: Option<Vec<String>>,
+    body: Option<String>,
+    template: Option<String>,
+    #[serde(default)]
+    allow_duplicate_title: bool,
+    due: Option<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+struct NewLiterature {
+    url: String,
+    title: Option<String>,
+    tags: Option<Vec<String>>,
+    template: Option<String>,
+    #[serde(default)]
+    allow_duplicate_title: bool,
+    #[serde(default)]
+    include_reviews: bool,
+}
+
+#[derive(Deserialize)]
+struct StatusChange {
+    status: String,
+    reason: Option<String>,
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Deserialize)]
+struct TagsChange {
+    tags: Vec<String>,
+    /// Keep the entry's other tags instead of replacing them.
+    #[serde(default)]
+    keep: bool,
+}
+
+#[derive(Deserialize)]
+struct BodyChange {
+    body: String,
+}
+
+/// Query parameters of the references endpoint, mirroring `reference list`.
+#[derive(Deserialize)]
+struct ReferenceQuery {
+    kind: Option<ReferenceKind>,
+}
+
+#[derive(Deserialize)]
+struct NewReference {
+    source: String,
+    target: String,
+    kind: Option<ReferenceKind>,
+    #[serde(default)]
+    wiki_link: bool,
+}
+
+async fn list_hypotheses(
+    State(state): State<AppState>,
+    Query(query): Query<ListQuery>,
+) -> ApiResult<Vec<HypothesisLog>> {
+    blocking(move || {
+        let filter = query.into_filter(&state.config)?;
+        HypothesisManager::new(state.config).list(&filter)
+    })
+    .await
+}
+
+async fn create_hypothesis(
+    State(state): State<AppState>,
+    Json(new): Json<NewEntry>,
+) -> std::result::Result<(StatusCode, Json<HypothesisLog>), ApiError> {
+    let created = blocking(move || {
+        HypothesisManager::new(state.config).create(
+            &new.title,
+            new.tags,
+            new.body.as_deref(),
+            new.template.as_deref(),
+            new.allow_duplicate_title,
+            new.due,
+        )
+    })
+    .await?;
+    Ok((StatusCode::CREATED, created))
+}
+
+async fn get_hypothesis(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Entry<HypothesisLog>> {
+    blocking(move || {
+        let manager = HypothesisManager::new(state.config);
+        let (log, path) = manager.find(&id)?;
+        let body = manager.manager.read_body(&path)?;
+        Ok(Entry { log, body })
+    })
+    .await
+}
+
+async fn update_hypothesis_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(change): Json<StatusChange>,
+) -> ApiResult<HypothesisLog> {
+    blocking(move || {
+        let status = HypothesisStatus::parse(&state.config, &change.status)?;
+        let manager = HypothesisManager::new(state.config);
+        manager.update_status(&id, status, change.reason.as_deref(), change.force)?;
+        Ok(manager.find(&id)?.0)
+    })
+    .await
+}
+
+async fn update_hypothesis_tags(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(change): Json<TagsChange>,
+) -> ApiResult<HypothesisLog> {
+    blocking(move || {
+        let manager = HypothesisManager::new(state.config);
+        let (log, _) = manager
+            .manager
+            .update_tags(&id, &change.tags, !change.keep)?;
+        Ok(log)
+    })
+    .await
+}
+
+async fn update_hypothesis_body(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(change): Json<BodyChange>,
+) -> ApiResult<Entry<HypothesisLog>> {
+    blocking(move || {
+        let manager = HypothesisManager::new(state.config);
+        let (log, path) = manager.manager.update_body(&id, |_| change.body)?;
+        let body = manager.manager.read_body(&path)?;
+        Ok(Entry { log, body })
+    })
+    .await
+}
+
+async fn list_literature(
+    State(state): State<AppState>,
+    Query(query): Query<ListQuery>,
+) -> ApiResult<Vec<LiteratureLog>> {
+    blocking(move || {
+        let filter = query.into_filter(&state.config)?;
+        LiteratureManager::new(state.config).list(&filter)
+    })
+    .await
+}
+
+async fn create_literature(
+    State(state): State<AppState>,
+    Json(new): Json<NewLiterature>,
+) -> std::result::Result<(StatusCode, Json<LiteratureLog>), ApiError> {
+    let created = blocking(move || {
+        LiteratureManager::new(state.config).create(
+            &new.url,
+            new.title.as_deref(),
+            new.tags,
+            new.template.as_deref(),
+            new.allow_duplicate_title,
+            new.include_reviews,
+        )
+    })
+    .await?;
+    Ok((StatusCode::CREATED, created))
+}
+
+async fn get_literature(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Entry<LiteratureLog>> {
+    blocking(move || {
+        let manager = LiteratureManager::new(state.config);
+        let (log, path) = manager.find(&id)?;
+        let body = manager.manager.read_body(&path)?;
+        Ok(Entry { log, body })
+    })
+    .await
+}
+
+async fn update_literature_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(change): Json<StatusChange>,
+) -> ApiResult<LiteratureLog> {
+    blocking(move || {
+        let status = LiteratureStatus::parse(&state.config, &change.status)?;
+        let manager = LiteratureManager::new(state.config);
+        manager.update_status(&id, status, change.reason.as_deref(), change.force)?;
+        Ok(manager.find(&id)?.0)
+    })
+    .await
+}
+
+async fn update_literature_tags(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(change): Json<TagsChange>,
+) -> ApiResult<LiteratureLog> {
+    blocking(move || {
+        let manager = LiteratureManager::new(state.config);
+        let (log, _) = manager
+            .manager
+            .update_tags(&id, &change.tags, !change.keep)?;
+        Ok(log)
+    })
+    .await
+}
+
+async fn update_literature_body(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(change): Json<BodyChange>,
+) -> ApiResult<Entry<LiteratureLog>> {
+    blocking(move || {
+        let manager = LiteratureManager::new(state.config);
+        let (log, path) = manager.manager.update_body(&id, |_| change.body)?;
+        let body = manager.manager.read_body(&path)?;
+        Ok(Entry { log, body })
+    })
+    .await
+}
+
+async fn list_knowledge(
+    State(state): State<AppState>,
+    Query(query): Query<ListQuery>,
+) -> ApiResult<Vec<KnowledgeLog>> {
+    blocking(move || {
+        let filter = query.into_filter(&state.config)?;
+        KnowledgeManager::new(state.config).list(&filter)
+    })
+    .await
+}
+
+async fn create_knowledge(
+    State(state): State<AppState>,
+    Json(new): Json<NewEntry>,
+) -> std::result::Result<(StatusCode, Json<KnowledgeLog>), ApiError> {
+    let created = blocking(move || {
+        KnowledgeManager::new(state.config).create(
+            &new.title,
+            new.tags,
+            new.body.as_deref(),
+            new.template.as_deref(),
+            new.allow_duplicate_title,
+            new.due,
+        )
+    })
+    .await?;
+    Ok((StatusCode::CREATED, created))
+}
+
+async fn get_knowledge(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Entry<KnowledgeLog>> {
+    blocking(move || {
+        let manager = KnowledgeManager::new(state.config);
+        let (log, path) = manager.find(&id)?;
+        let body = manager.manager.read_body(&path)?;
+        Ok(Entry { log, body })
+    })
+    .await
+}
+
+async fn update_knowledge_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(change): Json<StatusChange>,
+) -> ApiResult<KnowledgeLog> {
+    blocking(move || {
+        let status = KnowledgeStatus::parse(&state.config, &change.status)?;
+        let manager = KnowledgeManager::new(state.config);
+        manager.update_status(&id, status, change.reason.as_deref(), change.force)?;
+        Ok(manager.find(&id)?.0)
+    })
+    .await
+}
+
+async fn update_knowledge_tags(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(change): Json<TagsChange>,
+) -> ApiResult<KnowledgeLog> {
+    blocking(move || {
+        let manager = KnowledgeManager::new(state.config);
+        let (log, _) = manager
+            .manager
+            .update_tags(&id, &change.tags, !change.keep)?;
+        Ok(log)
+    })
+    .await
+}
+
+async fn update_knowledge_body(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(change): Json<BodyChange>,
+) -> ApiResult<Entry<KnowledgeLog>> {
+    blocking(move || {
+        let manager = KnowledgeManager::new(state.config);
+        let (log, path) = manager.manager.update_body(&id, |_| change.body)?;
+        let body = manager.manager.read_body(&path)?;
+        Ok(Entry { log, body })
+    })
+    .await
+}
+
+async fn get_references(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<ReferenceQuery>,
+) -> ApiResult<Vec<ReferenceInfo>> {
+    blocking(move || list_references_in(&state.config, &id, query.kind)).await
+}
+
+async fn create_reference(
+    State(state): State<AppState>,
+    Json(new): Json<NewReference>,
+) -> std::result::Result<StatusCode, ApiError> {
+    let Json(()) = blocking(move || {
+        add_reference_in(
+            &state.config,
+            &new.source,
+            &new.target,
+            new.kind,
+            new.wiki_link,
+        )
+    })
+    .await?;
+    Ok(StatusCode::CREATED)
+}
+
+async fn delete_reference(
+    State(state): State<AppState>,
+    Path((source, target)): Path<(String, String)>,
+) -> std::result::Result<StatusCode, ApiError> {
+    let Json(()) = blocking(move || remove_reference_in(&state.config, &source, &target)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn router(config: Config) -> Router {
+    Router::new()
+        .route(
+            "/api/hypotheses",
+            get(list_hypotheses).post(create_hypothesis),
+        )
+        .route("/api/hypotheses/{id}", get(get_hypothesis))
+        .route("/api/hypotheses/{id}/status", put(update_hypothesis_status))
+        .route("/api/hypotheses/{id}/tags", put(update_hypothesis_tags))
+        .route("/api/hypotheses/{id}/body", put(update_hypothesis_body))
+        .route(
+            "/api/literature",
+            get(list_literature).post(create_literature),
+        )
+        .route("/api/literature/{id}", get(get_literature))
+        .route("/api/literature/{id}/status", put(update_literature_status))
+        .route("/api/literature/{id}/tags", put(update_literature_tags))
+        .route("/api/literature/{id}/body", put(update_literature_body))
+        .route("/api/knowledge", get(list_knowledge).post(create_knowledge))
+        .route("/api/knowledge/{id}", get(get_knowledge))
+        .route("/api/knowledge/{id}/status", put(update_knowledge_status))
+        .route("/api/knowledge/{id}/tags", put(update_knowledge_tags))
+        .route("/api/knowledge/{id}/body", put(update_knowledge_body))
+        .route("/api/references", post(create_reference))
+        .route("/api/references/{id}", get(get_references))
+        .route(
+            "/api/references/{source}/{target}",
+            delete(delete_reference),
+        )
+        .with_state(AppState { config })
+}
+
+/// Serves the REST API for the current repository until the process is stopped.
+pub fn serve(addr: SocketAddr) -> Result<()> {
+    let config = load_config()?;
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async move {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        println!("Serving {} on http://{}", config.root.display(), addr);
+        axum::serve(listener, router(config)).await?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+    };
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    /// A repository set up by `dxlog init` in a fresh temporary directory,
+    /// removed when dropped.
+    struct TempRepo {
+        root: PathBuf,
+    }
+
+    impl TempRepo {
+        fn new(name: &str) -> Self {
+            let root =
+                std::env::temp_dir().join(format!("dxlog-server-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+            dxlog::init_repository(&root).unwrap();
+            let mut settings = fs::read_to_string(root.join("dxlog.toml")).unwrap();
+            settings.push_str("\n[author]\nname = \"Ada Lovelace\"\nemail = \"ada@example.org\"\n");
+            fs::write(root.join("dxlog.toml"), settings).unwrap();
+            Self { root }
+        }
+
+        fn config(&self) -> Config {
+            Config::load(&self.root).unwrap()
+        }
+    }
+
+    impl Drop for TempRepo {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    /// Sends one request to the API of `config`'s repository and returns the
+    /// response status with its JSON body, `null` if it has none.
+    fn send(config: &Config, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let response = router(config.clone()).oneshot(request).await.unwrap();
+            let status = response.status();
+            let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+            (status, body)
+        })
+    }
+
+    fn create_hypothesis(config: &Config) -> String {
+        let (status, body) = send(
+            config,
+            "POST",
+            "/api/hypotheses",
+            Some(json!({ "title": "Light bends", "tags": ["optics"] })),
+        );
+        assert_eq!(status, StatusCode::CREATED);
+        body["id"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_unknown_id_is_not_found() {
+        let repo = TempRepo::new("not-found");
+        let config = repo.config();
+        create_hypothesis(&config);
+
+        let (status, body) = send(&config, "GET", "/api/hypotheses/ffffffff", None);
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].as_str().unwrap().contains("ffffffff"));
+    }
+
+    #[test]
+    fn test_repository_failure_is_internal() {
+        let repo = TempRepo::new("internal");
+        let config = repo.config();
+        // The directory entries are kept in cannot be read.
+        let active_dir = repo.root.join(&config.storage.active_dir);
+        fs::remove_dir_all(&active_dir).unwrap();
+        fs::write(&active_dir, "").unwrap();
+
+        let (status, body) = send(&config, "GET", "/api/hypotheses", None);
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(body["error"].is_string());
+    }
+
+    #[test]
+    fn test_update_tags() {
+        let repo = TempRepo::new("tags");
+        let config = repo.config();
+        let id = create_hypothesis(&config);
+        let uri = format!("/api/hypotheses/{id}/tags");
+
+        let (status, body) = send(&config, "PUT", &uri, Some(json!({ "tags": ["lensing"] })));
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["tags"], json!(["lensing"]));
+
+        let (status, body) = send(
+            &config,
+            "PUT",
+            &uri,
+            Some(json!({ "tags": ["gravity"], "keep": true })),
+        );
+        assert_eq!(status, StatusCode::OK);
+        let mut tags: Vec<_> = body["tags"].as_array().unwrap().iter().collect();
+        tags.sort_by_key(|tag| tag.as_str());
+        assert_eq!(tags, [&json!("gravity"), &json!("lensing")]);
+    }
+
+    #[test]
+    fn test_update_body() {
+        let repo = TempRepo::new("body");
+        let config = repo.config();
+        let id = create_hypothesis(&config);
+        let uri = format!("/api/hypotheses/{id}/body");
+
+        let (status, body) = send(
+            &config,
+            "PUT",
+            &uri,
+            Some(json!({ "body": "Starlight near the sun.\n" })),
+        );
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], json!(id));
+        assert!(body["body"]
+            .as_str()
+            .unwrap()
+            .contains("Starlight near the sun."));
+
+        let (_, body) = send(&config, "GET", &format!("/api/hypotheses/{id}"), None);
+        assert!(body["body"]
+            .as_str()
+            .unwrap()
+            .contains("Starlight near the sun."));
+
+        let (status, _) = send(
+            &config,
+            "PUT",
+            "/api/hypotheses/ffffffff/body",
+            Some(json!({ "body": "" })),
+        );
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,140 @@
+//! Tables for list output.
+//!
+//! Tables fit the terminal, wrapping long cells rather than cutting them.
+//! Colors are only used on a terminal and never when `NO_COLOR` is set.
+
+use comfy_table::{presets, Attribute, Cell, Color, ContentArrangement, Table};
+
+/// A borderless table with the given column headers.
+pub fn new(headers: &[&str]) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(presets::NOTHING)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(
+            headers
+                .iter()
+                .map(|header| styled(Cell::new(header), None, true)),
+        );
+    // Terminals that report no size would squeeze every column to one character.
+    if table.width().is_some_and(|width| width < 20) {
+        table.set_content_arrangement(ContentArrangement::Disabled);
+    }
+    for (i, column) in table.column_iter_mut().enumerate() {
+        column.set_padding((0, if i + 1 < headers.len() { 2 } else { 0 }));
+    }
+    table
+}
+
+/// Prints `table` without the padding after the last column.
+pub fn print(table: &Table) {
+    for line in table.lines() {
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Like [`print`], but to stderr.
+pub fn eprint(table: &Table) {
+    for line in table.lines() {
+        eprintln!("{}", line.trim_end());
+    }
+}
+
+/// A status, colored by whether the entry is open, concluded or put away.
+pub fn status(status: &str) -> Cell {
+    let color = match status {
+        "active" | "in_progress" | "draft" | "in_review" | "open" | "candidate" | "planned"
+        | "running" => Color::Yellow,
+        "proven" | "completed" | "published" | "in_use" => Color::Green,
+        "disproven" | "abandoned" | "failed" => Color::Red,
+        "inconclusive" => Color::Magenta,
+        _ => Color::DarkGrey,
+    };
+    styled(Cell::new(status), Some(color), false)
+}
+
+/// A cell for secondary information, such as IDs.
+pub fn dim(text: &str) -> Cell {
+    styled(Cell::new(text), Some(Color::DarkGrey), false)
+}
+
+fn styled(cell: Cell, color: Option<Color>, bold: bool) -> Cell {
+    if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        return cell;
+    }
+    let cell = match color {
+        Some(color) => cell.fg(color),
+        None => cell,
+    };
+    if bold {
+        cell.add_attribute(Attribute::Bold)
+    } else {
+        cell
+    }
+}
+
+/// The first `len` characters of `text`, for IDs and hashes.
+pub fn short(text: &str, len: usize) -> String {
+    text.chars().take(len).collect()
+}
+
+/// An entry's ID as tables show it: its handle and the start of its UUID, or
+/// more of the UUID if it has no handle.
+pub fn entry_id(id: &str, handle: Option<&str>) -> String {
+    match handle {
+        Some(handle) => format!("{} {}", handle, short(id, 8)),
+        None => short(id, 12),
+    }
+}
+
+/// [`entry_id`] qualified with the repository the entry is in, such as
+/// `qec:H-001 1f418cae`, for entries of other repositories in the workspace.
+pub fn repo_entry_id(repo: Option<&str>, id: &str, handle: Option<&str>) -> String {
+    match repo {
+        Some(repo) => dxlog::qualified_id(repo, &entry_id(id, handle)),
+        None => entry_id(id, handle),
+    }
+}
+
+/// Time left until a due date, e.g. `3 days left` or `1 day overdue`.
+pub fn remaining(days: i64) -> String {
+    match days {
+        0 => "due today".to_string(),
+        1 => "1 day left".to_string(),
+        -1 => "1 day overdue".to_string(),
+        days if days < 0 => format!("{} days overdue", -days),
+        days => format!("{} days left", days),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_keeps_whole_characters() {
+        assert_eq!(short("Überprüfung", 3), "Übe");
+        assert_eq!(short("Überprüfung", 11), "Überprüfung");
+        assert_eq!(short("光の屈折", 2), "光の");
+        assert_eq!(short("光の屈折", 8), "光の屈折");
+    }
+
+    #[test]
+    fn test_multi_byte_titles_wrap_at_the_width() {
+        for title in [
+            "Überprüfung der Lichtablenkung",
+            "重力による光の屈折の観測について",
+        ] {
+            let mut table = new(&["ID", "TITLE"]);
+            table.force_no_tty().set_width(20);
+            table.add_row(vec![Cell::new("H-001"), Cell::new(title)]);
+            let lines = table.lines().collect::<Vec<_>>();
+            assert!(lines.len() > 2, "{title} was not wrapped");
+            let wrapped: String = lines[1..]
+                .iter()
+                .map(|line| line.trim_start_matches("H-001").trim())
+                .collect();
+            assert_eq!(wrapped.replace(' ', ""), title.replace(' ', ""));
+        }
+    }
+}
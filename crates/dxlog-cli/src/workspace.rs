@@ -0,0 +1,54 @@
+//! `--workspace`: lists entries of the repositories registered in the
+//! workspaces file instead of the current one.
+
+use anyhow::Result;
+use dxlog::{load_config, select_repos, Config};
+
+#[derive(clap::Args, Clone, Default)]
+pub struct WorkspaceArgs {
+    /// Repositories to include
+    #[arg(
+        long,
+        value_name = "all|NAME,...",
+        help = "Include the repositories registered in ~/.config/dxlog/workspaces.toml: all of them, or a comma-separated list of names"
+    )]
+    workspace: Option<String>,
+}
+
+impl WorkspaceArgs {
+    /// Whether --workspace was given.
+    pub fn is_set(&self) -> bool {
+        self.workspace.is_some()
+    }
+
+    /// Runs `f` with the config of the current repository, or with --workspace
+    /// with that of each selected repository, pairing each result with the
+    /// name of the repository.
+    ///
+    /// A repository that fails, config included, is skipped with a warning.
+    pub fn each_repo<T>(
+        &self,
+        mut f: impl FnMut(&Config) -> Result<T>,
+    ) -> Result<Vec<(Option<String>, T)>> {
+        let Some(selection) = &self.workspace else {
+            return Ok(vec![(None, f(&load_config()?)?)]);
+        };
+        let mut results = Vec::new();
+        for repo in select_repos(selection)? {
+            match Config::load(&repo.root).and_then(|config| f(&config)) {
+                Ok(result) => results.push((Some(repo.name), result)),
+                Err(err) => eprintln!("Warning: skipping repository {}: {:#}", repo.name, err),
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// The results of [`WorkspaceArgs::each_repo`] as one list, each with the
+/// repository it came from.
+pub fn flatten<T>(results: Vec<(Option<String>, Vec<T>)>) -> Vec<(Option<String>, T)> {
+    results
+        .into_iter()
+        .flat_map(|(repo, items)| items.into_iter().map(move |item| (repo.clone(), item)))
+        .collect()
+}
@@ -1,35 +1,63 @@
 use anyhow::{Context, Result};
-use reqwest::blocking::Client;
+use reqwest::Client;
 use scraper::{Html, Selector};
-use std::time::Duration;
+
+use crate::{cache, http};
 
 #[derive(Debug)]
 pub struct ArxivMetadata {
     pub title: String,
     pub abstract_text: String,
-    pub doi: String,
+    pub authors: Vec<String>,
+    /// Journal DOI, if the paper has been published.
+    pub doi: Option<String>,
 }
 
 fn extract_arxiv_id(url: &str) -> Option<String> {
     url.split('/')
-        .last()
+        .next_back()
         .map(|s| s.trim_start_matches("abs/").to_string())
 }
 
-pub fn fetch_arxiv_metadata(url: &str) -> Result<ArxivMetadata> {
-    let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
+/// Fetches arXiv abstract pages.
+#[derive(Clone)]
+pub struct ArxivClient {
+    http: Client,
+}
+
+impl Default for ArxivClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    // Convert URL to abs format if needed
-    let arxiv_id = extract_arxiv_id(url).context("Failed to extract arXiv ID")?;
-    let abs_url = format!("https://arxiv.org/abs/{}", arxiv_id);
+impl ArxivClient {
+    /// Creates a client on the [`shared_client`](crate::shared_client).
+    pub fn new() -> Self {
+        Self::with_client(http::shared_client())
+    }
 
-    let response = client
-        .get(abs_url)
-        .header("User-Agent", "dxlog/0.1.0")
-        .send()?
-        .text()?;
+    pub fn with_client(http: Client) -> Self {
+        Self { http }
+    }
 
-    let document = Html::parse_document(&response);
+    pub async fn fetch(&self, url: &str) -> Result<ArxivMetadata> {
+        // Convert URL to abs format if needed
+        let arxiv_id = extract_arxiv_id(url).context("Failed to extract arXiv ID")?;
+        let abs_url = format!("https://arxiv.org/abs/{}", arxiv_id);
+
+        let response = cache::get(&self.http, &abs_url, &[]).await?;
+        parse_abs_page(&response)
+    }
+}
+
+/// Blocking version of [`ArxivClient::fetch`].
+pub fn fetch_arxiv_metadata(url: &str) -> Result<ArxivMetadata> {
+    http::block_on(|client| async move { ArxivClient::with_client(client).fetch(url).await })
+}
+
+fn parse_abs_page(response: &str) -> Result<ArxivMetadata> {
+    let document = Html::parse_document(response);
 
     // Extract abstract
     let abstract_selector = Selector::parse("blockquote.abstract").unwrap();
@@ -52,20 +80,42 @@ pub fn fetch_arxiv_metadata(url: &str) -> Result<ArxivMetadata> {
         .trim()
         .to_string();
 
+    let authors_selector = Selector::parse("div.authors a").unwrap();
+    let authors = document
+        .select(&authors_selector)
+        .map(|el| el.text().collect::<String>().trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+
     // Extract DOI - it's in a link with class "arxiv-doi"
     let doi_selector = Selector::parse("#arxiv-doi-link").unwrap();
-    let doi = document
-        .select(&doi_selector)
-        .next()
-        .and_then(|el| {
-            el.attr("href")
-                .map(|href| href.trim_start_matches("https://doi.org/").to_string())
-        })
-        .expect("DOI not found");
+    let doi = document.select(&doi_selector).next().and_then(|el| {
+        el.attr("href")
+            .map(|href| href.trim_start_matches("https://doi.org/").to_string())
+    });
 
     Ok(ArxivMetadata {
         title,
         abstract_text,
+        authors,
         doi,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_abs_page() {
+        let page = r#"<html><body>
+            <h1 class="title mathjax"><span class="descriptor">Title:</span>Attention Is All You Need</h1>
+            <div class="authors"><span class="descriptor">Authors:</span><a href="/a/vaswani_a_1">Ashish Vaswani</a>, <a href="/a/shazeer_n_1">Noam Shazeer</a></div>
+            <blockquote class="abstract mathjax"><span class="descriptor">Abstract:</span>The dominant models...</blockquote>
+            </body></html>"#;
+
+        let metadata = parse_abs_page(page).unwrap();
+        assert_eq!(metadata.authors, vec!["Ashish Vaswani", "Noam Shazeer"]);
+        assert_eq!(metadata.doi, None);
+    }
+}
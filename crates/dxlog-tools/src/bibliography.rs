@@ -0,0 +1,307 @@
+use anyhow::{Context, Result};
+use std::ops::Range;
+
+/// A single record from a reference manager export (BibTeX or RIS).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BibRecord {
+    pub entry_type: String,
+    pub key: Option<String>,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub year: Option<String>,
+    pub doi: Option<String>,
+    pub url: Option<String>,
+    pub pdf_url: Option<String>,
+    pub abstract_text: Option<String>,
+}
+
+/// A BibTeX entry with all of its fields, as written in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BibEntry {
+    pub entry_type: String,
+    pub key: String,
+    /// Field names (lowercased) and values, braces and extra whitespace removed.
+    pub fields: Vec<(String, String)>,
+    /// Byte range of the entry in the input, from `@` to the closing delimiter.
+    pub span: Range<usize>,
+}
+
+impl BibEntry {
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Splits a BibTeX file into its entries. `@comment`, `@string` and `@preamble`
+/// blocks are skipped, as is text between entries, such as `%` comments or an
+/// email address in a header.
+pub fn parse_bibtex_entries(input: &str) -> Result<Vec<BibEntry>> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+
+    while let Some(at) = input[offset..].find('@') {
+        let start = offset + at;
+        let rest = &input[start + 1..];
+        // An entry is `@type{` or `@type(`; any other `@` is text between entries
+        let name_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let open = name_len + (rest[name_len..].len() - rest[name_len..].trim_start().len());
+        if name_len == 0 || !rest[open..].starts_with(['{', '(']) {
+            offset = start + 1;
+            continue;
+        }
+        let entry_type = rest[..name_len].to_lowercase();
+        let body_len = matching_close(&rest[open..])
+            .with_context(|| format!("Unterminated BibTeX entry of type '{}'", entry_type))?;
+        let body = &rest[open + 1..open + body_len];
+        offset = start + 1 + open + body_len + 1;
+
+        if matches!(entry_type.as_str(), "comment" | "string" | "preamble") {
+            continue;
+        }
+
+        let (key, fields) = match body.split_once(',') {
+            Some((key, fields)) => (key.trim(), fields),
+            None => (body.trim(), ""),
+        };
+        entries.push(BibEntry {
+            entry_type,
+            key: key.to_string(),
+            fields: bibtex_fields(fields),
+            span: start..offset,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Parses a BibTeX file. `@comment`, `@string` and `@preamble` blocks are skipped.
+pub fn parse_bibtex(input: &str) -> Result<Vec<BibRecord>> {
+    let records = parse_bibtex_entries(input)?
+        .into_iter()
+        .map(|entry| {
+            let mut record = BibRecord {
+                key: (!entry.key.is_empty()).then(|| entry.key.clone()),
+                entry_type: entry.entry_type,
+                ..Default::default()
+            };
+            for (name, value) in entry.fields {
+                match name.as_str() {
+                    "title" => record.title = value,
+                    "author" => {
+                        record.authors = value
+                            .split(" and ")
+                            .map(|a| a.trim().to_string())
+                            .filter(|a| !a.is_empty())
+                            .collect()
+                    }
+                    "year" => record.year = Some(value),
+                    "doi" => record.doi = Some(normalize_doi(&value)),
+                    "url" => record.url = Some(value),
+                    "abstract" => record.abstract_text = Some(value),
+                    "file" | "pdf" if value.to_lowercase().ends_with(".pdf") => {
+                        record.pdf_url = Some(value)
+                    }
+                    _ => {}
+                }
+            }
+            record
+        })
+        .collect();
+
+    Ok(records)
+}
+
+/// Returns the byte offset of the delimiter closing the one that starts `s`.
+fn matching_close(s: &str) -> Option<usize> {
+    let (open, close) = if s.starts_with('(') {
+        ('(', ')')
+    } else {
+        ('{', '}')
+    };
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+fn bibtex_fields(fields: &str) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+    let mut rest = fields;
+
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq]
+            .trim()
+            .trim_start_matches(',')
+            .trim()
+            .to_lowercase();
+        let after_eq = &rest[eq + 1..];
+        let value_part = after_eq.trim_start();
+        let value_start = eq + 1 + (after_eq.len() - value_part.len());
+
+        let (raw, consumed) = if let Some(braced) = value_part.strip_prefix('{') {
+            match matching_close(value_part) {
+                Some(end) => (&value_part[1..end], end + 1),
+                None => (braced, value_part.len()),
+            }
+        } else if let Some(stripped) = value_part.strip_prefix('"') {
+            match stripped.find('"') {
+                Some(end) => (&stripped[..end], end + 2),
+                None => (stripped, value_part.len()),
+            }
+        } else {
+            let end = value_part.find(',').unwrap_or(value_part.len());
+            (value_part[..end].trim(), end)
+        };
+
+        result.push((name, clean_value(raw)));
+        rest = &rest[value_start + consumed..];
+    }
+
+    result
+}
+
+fn clean_value(raw: &str) -> String {
+    raw.replace(['{', '}'], "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parses an RIS file as exported by Zotero, Mendeley, EndNote and most publishers.
+pub fn parse_ris(input: &str) -> Result<Vec<BibRecord>> {
+    let mut records = Vec::new();
+    let mut current: Option<BibRecord> = None;
+
+    for line in input.lines() {
+        let line = line.trim_end();
+        if line.get(2..5) != Some("  -") {
+            continue;
+        }
+        let tag = &line[..2];
+        let value = line[5..].trim().to_string();
+
+        if tag == "TY" {
+            current = Some(BibRecord {
+                entry_type: value.to_lowercase(),
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let record = current
+            .as_mut()
+            .with_context(|| format!("RIS tag '{}' found outside of a record", tag))?;
+        match tag {
+            "ER" => records.extend(current.take()),
+            "TI" | "T1" => record.title = value,
+            "AU" | "A1" => record.authors.push(value),
+            "PY" | "Y1" | "DA" if record.year.is_none() => {
+                record.year = value.split('/').next().map(|y| y.to_string())
+            }
+            "DO" => record.doi = Some(normalize_doi(&value)),
+            "UR" => record.url = Some(value),
+            "L1" => record.pdf_url = Some(value),
+            "AB" | "N2" => record.abstract_text = Some(value),
+            "ID" => record.key = Some(value),
+            _ => {}
+        }
+    }
+
+    if current.is_some() {
+        return Err(anyhow::anyhow!("RIS record is missing its 'ER' terminator"));
+    }
+
+    Ok(records)
+}
+
+/// Strips resolver prefixes so DOIs can be compared directly.
+pub fn normalize_doi(doi: &str) -> String {
+    let doi = doi.trim();
+    doi.trim_start_matches("https://doi.org/")
+        .trim_start_matches("http://dx.doi.org/")
+        .trim_start_matches("doi:")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bibtex() {
+        let input = r#"
+@comment{ignored}
+@article{smith2024quantum,
+  title = {Quantum {Error} Correction at Scale},
+  author = {Smith, Jane and Doe, John},
+  year = 2024,
+  doi = "10.1234/ABC.5678",
+  url = {https://arxiv.org/abs/2401.12345}
+}
+"#;
+
+        let records = parse_bibtex(input).unwrap();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.key.as_deref(), Some("smith2024quantum"));
+        assert_eq!(record.title, "Quantum Error Correction at Scale");
+        assert_eq!(record.authors, vec!["Smith, Jane", "Doe, John"]);
+        assert_eq!(record.year.as_deref(), Some("2024"));
+        assert_eq!(record.doi.as_deref(), Some("10.1234/abc.5678"));
+    }
+
+    #[test]
+    fn test_parse_bibtex_entries() {
+        let input = "% mine\n@misc{own, note = {kept}}\n\n@article{smith2024, title = {A}, dxlog = {123}}\n";
+
+        let entries = parse_bibtex_entries(input).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(&input[entries[0].span.clone()], "@misc{own, note = {kept}}");
+        assert_eq!(entries[1].key, "smith2024");
+        assert_eq!(entries[1].field("dxlog"), Some("123"));
+        assert!(input[entries[1].span.end..].trim().is_empty());
+    }
+
+    #[test]
+    fn test_parse_bibtex_with_header() {
+        let input = r#"% Exported by Zotero for ada@example.org
+% Questions: lab@example.org (or @ada on chat)
+@preamble{"\newcommand{\noop}[1]{}"}
+@comment{jabref-meta: databaseType:bibtex;}
+
+@article{smith2024,
+  title = {Noise in Qubits},
+}
+"#;
+
+        let records = parse_bibtex(input).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].key.as_deref(), Some("smith2024"));
+        assert_eq!(records[0].title, "Noise in Qubits");
+    }
+
+    #[test]
+    fn test_parse_ris() {
+        let input = "TY  - JOUR\nTI  - Noise in Qubits\nAU  - Smith, Jane\nAU  - Doe, John\nPY  - 2023/05/01\nDO  - https://doi.org/10.1/XYZ\nER  - \n";
+
+        let records = parse_ris(input).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].title, "Noise in Qubits");
+        assert_eq!(records[0].authors.len(), 2);
+        assert_eq!(records[0].year.as_deref(), Some("2023"));
+        assert_eq!(records[0].doi.as_deref(), Some("10.1/xyz"));
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::{cache, http};
+
+#[derive(Debug)]
+pub struct BiorxivMetadata {
+    pub title: String,
+    pub abstract_text: String,
+    pub authors: Vec<String>,
+    pub doi: String,
+    /// `biorxiv` or `medrxiv`.
+    pub server: String,
+}
+
+#[derive(Deserialize)]
+struct DetailsResponse {
+    collection: Vec<Preprint>,
+}
+
+#[derive(Deserialize)]
+struct Preprint {
+    doi: String,
+    title: String,
+    authors: String,
+    #[serde(rename = "abstract")]
+    abstract_text: String,
+}
+
+/// Extracts the server and DOI from a bioRxiv or medRxiv URL, e.g.
+/// `https://www.biorxiv.org/content/10.1101/2023.01.01.522405v2.full`.
+fn parse_biorxiv_url(url: &str) -> Option<(&'static str, String)> {
+    let server = if url.contains("biorxiv.org") {
+        "biorxiv"
+    } else if url.contains("medrxiv.org") {
+        "medrxiv"
+    } else {
+        return None;
+    };
+
+    let start = url.find("10.1101/")?;
+    let (prefix, suffix) = url[start..].split_at("10.1101/".len());
+    let suffix = suffix.split(['?', '#']).next()?;
+    let id = suffix
+        .trim_end_matches('/')
+        .trim_end_matches(".pdf")
+        .trim_end_matches(".full")
+        .trim_end_matches(".abstract")
+        .trim_end_matches(".full-text");
+    // Drop the version suffix, e.g. "v2".
+    let id = match id.rsplit_once('v') {
+        Some((base, version))
+            if !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            base
+        }
+        _ => id,
+    };
+    if id.is_empty() {
+        return None;
+    }
+    Some((server, format!("{}{}", prefix, id)))
+}
+
+/// Returns the DOI of a bioRxiv or medRxiv preprint URL without fetching anything.
+pub fn biorxiv_doi(url: &str) -> Option<String> {
+    parse_biorxiv_url(url).map(|(_, doi)| doi)
+}
+
+/// Fetches preprints from the bioRxiv/medRxiv API.
+#[derive(Clone)]
+pub struct BiorxivClient {
+    http: Client,
+}
+
+impl Default for BiorxivClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BiorxivClient {
+    /// Creates a client on the [`shared_client`](crate::shared_client).
+    pub fn new() -> Self {
+        Self::with_client(http::shared_client())
+    }
+
+    pub fn with_client(http: Client) -> Self {
+        Self { http }
+    }
+
+    /// Fetches the latest version of a bioRxiv or medRxiv preprint.
+    pub async fn fetch(&self, url: &str) -> Result<BiorxivMetadata> {
+        let (server, doi) = parse_biorxiv_url(url)
+            .context("Failed to extract a DOI from the bioRxiv/medRxiv URL")?;
+
+        let api_url = format!("https://api.biorxiv.org/details/{}/{}/na/json", server, doi);
+        let response = cache::get(&self.http, &api_url, &[]).await?;
+        let response: DetailsResponse = serde_json::from_str(&response)?;
+
+        // Versions are listed oldest first.
+        let preprint = response
+            .collection
+            .into_iter()
+            .next_back()
+            .with_context(|| format!("No {} preprint found for DOI {}", server, doi))?;
+
+        Ok(BiorxivMetadata {
+            title: preprint.title.trim().to_string(),
+            abstract_text: preprint.abstract_text.trim().to_string(),
+            authors: preprint
+                .authors
+                .split(';')
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .map(String::from)
+                .collect(),
+            doi: preprint.doi,
+            server: server.to_string(),
+        })
+    }
+}
+
+/// Blocking version of [`BiorxivClient::fetch`].
+pub fn fetch_biorxiv_metadata(url: &str) -> Result<BiorxivMetadata> {
+    http::block_on(|client| async move { BiorxivClient::with_client(client).fetch(url).await })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_biorxiv_url() {
+        assert_eq!(
+            parse_biorxiv_url("https://www.biorxiv.org/content/10.1101/2023.01.01.522405v2.full"),
+            Some(("biorxiv", "10.1101/2023.01.01.522405".to_string()))
+        );
+        assert_eq!(
+            parse_biorxiv_url("https://www.medrxiv.org/content/10.1101/2020.04.14.20062463v1"),
+            Some(("medrxiv", "10.1101/2020.04.14.20062463".to_string()))
+        );
+        assert_eq!(
+            parse_biorxiv_url("https://biorxiv.org/content/10.1101/123456"),
+            Some(("biorxiv", "10.1101/123456".to_string()))
+        );
+        assert_eq!(parse_biorxiv_url("https://www.biorxiv.org/"), None);
+        assert_eq!(parse_biorxiv_url("https://arxiv.org/abs/2401.12345"), None);
+    }
+}
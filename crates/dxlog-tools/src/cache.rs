@@ -0,0 +1,169 @@
+//! On-disk cache of API responses, keyed by URL.
+//!
+//! Responses are kept under `$DXLOG_CACHE_DIR`, `$XDG_CACHE_HOME/dxlog` or
+//! `~/.cache/dxlog` and reused for a day. Failing to read or write the cache is
+//! never an error; the request simply goes to the network.
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use std::cell::Cell;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+
+/// Environment variable that, when set to anything but `0`/`false`/`no`/`off`, forbids
+/// network access. Only cached responses are used, however old.
+pub const OFFLINE_ENV_VAR: &str = "DXLOG_OFFLINE";
+
+/// Environment variable overriding the cache location.
+const CACHE_DIR_ENV_VAR: &str = "DXLOG_CACHE_DIR";
+
+/// How long a cached response is used before it is fetched again.
+const MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+thread_local! {
+    static BYPASS: Cell<bool> = const { Cell::new(false) };
+}
+
+static OFFLINE: RwLock<Option<bool>> = RwLock::new(None);
+
+/// Turns network access off or on for the rest of the process, in place of
+/// [`OFFLINE_ENV_VAR`].
+pub fn set_offline(offline: bool) {
+    *OFFLINE.write().unwrap_or_else(|e| e.into_inner()) = Some(offline);
+}
+
+/// Whether network access has been turned off with [`set_offline`], or else
+/// through [`OFFLINE_ENV_VAR`].
+pub fn is_offline() -> bool {
+    if let Some(offline) = *OFFLINE.read().unwrap_or_else(|e| e.into_inner()) {
+        return offline;
+    }
+    std::env::var(OFFLINE_ENV_VAR)
+        .map(|v| {
+            !matches!(
+                v.trim().to_lowercase().as_str(),
+                "" | "0" | "false" | "no" | "off"
+            )
+        })
+        .unwrap_or(false)
+}
+
+/// Runs `f` with cached responses ignored, e.g. to pick up changed metadata.
+/// Fresh responses are still written to the cache.
+///
+/// This covers the blocking functions called by `f`, whose fetches run on the
+/// calling thread, but not async fetches spawned onto other threads.
+pub fn bypass_cache<T>(f: impl FnOnce() -> T) -> T {
+    let previous = BYPASS.with(|b| b.replace(true));
+    let value = f();
+    BYPASS.with(|b| b.set(previous));
+    value
+}
+
+/// GETs `url` and returns the response body, answering from the cache when possible.
+/// Unsuccessful responses are errors and are not cached.
+pub(crate) async fn get(client: &Client, url: &str, headers: &[(&str, &str)]) -> Result<String> {
+    let cache = Cache::locate();
+    let offline = is_offline();
+
+    if let Some(cache) = &cache {
+        let max_age = if offline { None } else { Some(MAX_AGE) };
+        if offline || !BYPASS.with(Cell::get) {
+            if let Some(body) = cache.read(url, max_age) {
+                debug!(url, "Using cached response");
+                return Ok(body);
+            }
+        }
+    }
+    if offline {
+        anyhow::bail!("Working offline and {} is not cached", url);
+    }
+
+    debug!(url, "GET");
+    let mut request = client.get(url);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let body = request
+        .send()
+        .await?
+        .error_for_status()
+        .with_context(|| format!("Request to {} failed", url))?
+        .text()
+        .await?;
+
+    if let Some(cache) = &cache {
+        cache.write(url, &body);
+    }
+    Ok(body)
+}
+
+struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    fn locate() -> Option<Self> {
+        let dir = std::env::var_os(CACHE_DIR_ENV_VAR)
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("XDG_CACHE_HOME").map(|d| PathBuf::from(d).join("dxlog")))
+            .or_else(|| std::env::var_os("HOME").map(|d| PathBuf::from(d).join(".cache/dxlog")))?;
+        Some(Self { dir })
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}", fnv1a(url.as_bytes())))
+    }
+
+    /// Returns the cached body if there is one no older than `max_age`.
+    fn read(&self, url: &str, max_age: Option<Duration>) -> Option<String> {
+        let path = self.path(url);
+        if let Some(max_age) = max_age {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            let age = SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default();
+            if age > max_age {
+                return None;
+            }
+        }
+        fs::read_to_string(path).ok()
+    }
+
+    fn write(&self, url: &str, body: &str) {
+        let _ = fs::create_dir_all(&self.dir).and_then(|_| fs::write(self.path(url), body));
+    }
+}
+
+/// 64-bit FNV-1a, used because file names must stay the same across builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let cache = Cache {
+            dir: std::env::temp_dir().join(format!("dxlog-cache-test-{}", std::process::id())),
+        };
+        let url = "https://api.example.org/works/1";
+
+        assert_eq!(cache.read(url, None), None);
+        cache.write(url, "{\"ok\":true}");
+        assert_eq!(
+            cache.read(url, Some(MAX_AGE)).as_deref(),
+            Some("{\"ok\":true}")
+        );
+        assert_eq!(cache.read("https://api.example.org/works/2", None), None);
+
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+}
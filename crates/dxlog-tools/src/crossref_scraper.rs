@@ -0,0 +1,123 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::{cache, http, normalize_doi};
+
+#[derive(Debug)]
+pub struct CrossrefWork {
+    pub doi: String,
+    pub title: String,
+    pub abstract_text: Option<String>,
+    /// Author names in the order listed, as "Given Family".
+    pub authors: Vec<String>,
+    /// Number of works citing this one, as known to Crossref.
+    pub citation_count: u32,
+}
+
+#[derive(Deserialize)]
+struct WorkResponse {
+    message: Work,
+}
+
+#[derive(Deserialize)]
+struct Work {
+    #[serde(rename = "DOI")]
+    doi: String,
+    #[serde(default)]
+    title: Vec<String>,
+    #[serde(rename = "abstract")]
+    abstract_text: Option<String>,
+    #[serde(default)]
+    author: Vec<Contributor>,
+    #[serde(rename = "is-referenced-by-count", default)]
+    citation_count: u32,
+}
+
+/// A Crossref contributor; organisations only have a `name`.
+#[derive(Deserialize)]
+struct Contributor {
+    given: Option<String>,
+    family: Option<String>,
+    name: Option<String>,
+}
+
+impl Contributor {
+    fn display_name(self) -> Option<String> {
+        match (self.given, self.family) {
+            (Some(given), Some(family)) => Some(format!("{} {}", given, family)),
+            (given, family) => family.or(given).or(self.name),
+        }
+    }
+}
+
+/// Looks up DOIs in the Crossref REST API.
+#[derive(Clone)]
+pub struct CrossrefClient {
+    http: Client,
+}
+
+impl Default for CrossrefClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrossrefClient {
+    /// Creates a client on the [`shared_client`](crate::shared_client).
+    pub fn new() -> Self {
+        Self::with_client(http::shared_client())
+    }
+
+    pub fn with_client(http: Client) -> Self {
+        Self { http }
+    }
+
+    pub async fn fetch(&self, doi: &str) -> Result<CrossrefWork> {
+        let doi = normalize_doi(doi);
+
+        let url = format!("https://api.crossref.org/works/{}", doi);
+        let response = cache::get(&self.http, &url, &[])
+            .await
+            .with_context(|| format!("DOI {} not found on Crossref", doi))?;
+        let response: WorkResponse = serde_json::from_str(&response)?;
+
+        let work = response.message;
+        Ok(CrossrefWork {
+            doi: work.doi,
+            title: work
+                .title
+                .into_iter()
+                .next()
+                .map(|t| t.trim().to_string())
+                .unwrap_or_default(),
+            abstract_text: work.abstract_text.map(|a| strip_tags(&a)),
+            authors: work
+                .author
+                .into_iter()
+                .filter_map(Contributor::display_name)
+                .collect(),
+            citation_count: work.citation_count,
+        })
+    }
+}
+
+/// Blocking version of [`CrossrefClient::fetch`].
+pub fn fetch_crossref_metadata(doi: &str) -> Result<CrossrefWork> {
+    http::block_on(|client| async move { CrossrefClient::with_client(client).fetch(doi).await })
+}
+
+/// Crossref abstracts are JATS XML; keep only the text.
+fn strip_tags(markup: &str) -> String {
+    let mut text = String::with_capacity(markup.len());
+    let mut in_tag = false;
+    for c in markup.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
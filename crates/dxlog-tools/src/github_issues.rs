@@ -0,0 +1,137 @@
+use anyhow::Result;
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::debug;
+
+use crate::{cache, http};
+
+const API_URL: &str = "https://api.github.com";
+
+/// An issue to write to a GitHub repository.
+#[derive(Debug, Clone)]
+pub struct IssueDraft {
+    pub title: String,
+    /// Issue text as markdown.
+    pub body: String,
+    /// Labels GitHub creates in the repository where they don't exist yet.
+    pub labels: Vec<String>,
+    /// Whether the issue is open; closed issues are closed as completed, or
+    /// as not planned with `not_planned`.
+    pub open: bool,
+    pub not_planned: bool,
+}
+
+#[derive(Deserialize)]
+struct Created {
+    number: u64,
+}
+
+/// Creates and updates issues through the GitHub REST API.
+#[derive(Clone)]
+pub struct GitHubIssuesClient {
+    http: Client,
+    token: String,
+}
+
+impl GitHubIssuesClient {
+    /// Creates a client on the [`shared_client`](crate::shared_client).
+    pub fn new(token: &str) -> Self {
+        Self::with_client(http::shared_client(), token)
+    }
+
+    pub fn with_client(http: Client, token: &str) -> Self {
+        Self {
+            http,
+            token: token.to_string(),
+        }
+    }
+
+    async fn send(&self, request: RequestBuilder, what: &str) -> Result<String> {
+        if cache::is_offline() {
+            anyhow::bail!("Working offline; the GitHub API needs network access",);
+        }
+        debug!("GitHub API: {}", what);
+        let response = request
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            let message = serde_json::from_str::<Value>(&body)
+                .ok()
+                .and_then(|error| error["message"].as_str().map(str::to_string))
+                .unwrap_or(body);
+            anyhow::bail!("Failed to {}: {} ({})", what, message, status);
+        }
+        Ok(body)
+    }
+
+    /// Writes `issue` to `repo` (`owner/name`), updating issue `number` if
+    /// given and creating one otherwise; new issues are always open. Returns
+    /// the issue number.
+    pub async fn write_issue(
+        &self,
+        repo: &str,
+        number: Option<u64>,
+        issue: &IssueDraft,
+    ) -> Result<u64> {
+        let mut fields = json!({
+            "title": issue.title,
+            "body": issue.body,
+            "labels": issue.labels,
+        });
+        match number {
+            Some(number) => {
+                fields["state"] = json!(if issue.open { "open" } else { "closed" });
+                if !issue.open {
+                    fields["state_reason"] = json!(if issue.not_planned {
+                        "not_planned"
+                    } else {
+                        "completed"
+                    });
+                }
+                self.send(
+                    self.http
+                        .patch(format!("{}/repos/{}/issues/{}", API_URL, repo, number))
+                        .json(&fields),
+                    &format!("update issue #{}", number),
+                )
+                .await?;
+                Ok(number)
+            }
+            None => {
+                let body = self
+                    .send(
+                        self.http
+                            .post(format!("{}/repos/{}/issues", API_URL, repo))
+                            .json(&fields),
+                        "create the issue",
+                    )
+                    .await?;
+                let created: Created = serde_json::from_str(&body)?;
+                Ok(created.number)
+            }
+        }
+    }
+}
+
+/// Blocking version of [`GitHubIssuesClient::write_issue`] for several
+/// issues. Each issue is paired with its number if it was written before.
+/// A failure is reported for that issue only.
+pub fn write_github_issues(
+    token: &str,
+    repo: &str,
+    issues: &[(Option<u64>, IssueDraft)],
+) -> Result<Vec<Result<u64>>> {
+    http::block_on(|client| async move {
+        let github = GitHubIssuesClient::with_client(client, token);
+        let mut written = Vec::new();
+        for (number, issue) in issues {
+            written.push(github.write_issue(repo, *number, issue).await);
+        }
+        Ok(written)
+    })
+}
@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
-use reqwest::blocking::Client;
+use reqwest::Client;
 use serde::Deserialize;
 
+use crate::{cache, http};
+
 #[derive(Debug, Deserialize)]
 pub struct GitHubRepo {
     pub name: String,
@@ -21,22 +23,47 @@ fn extract_repo_info(url: &str) -> Option<(String, String)> {
     }
 }
 
-pub fn fetch_github_metadata(url: &str) -> Result<GitHubRepo> {
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()?;
+/// Fetches repository details from the GitHub REST API.
+#[derive(Clone)]
+pub struct GitHubClient {
+    http: Client,
+}
+
+impl Default for GitHubClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GitHubClient {
+    /// Creates a client on the [`shared_client`](crate::shared_client).
+    pub fn new() -> Self {
+        Self::with_client(http::shared_client())
+    }
+
+    pub fn with_client(http: Client) -> Self {
+        Self { http }
+    }
 
-    let (owner, repo) = extract_repo_info(url).context("Invalid GitHub URL format")?;
+    pub async fn fetch(&self, url: &str) -> Result<GitHubRepo> {
+        let (owner, repo) = extract_repo_info(url).context("Invalid GitHub URL format")?;
 
-    let api_url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+        let api_url = format!("https://api.github.com/repos/{}/{}", owner, repo);
 
-    let response = client
-        .get(api_url)
-        .header("User-Agent", "dxlog/0.1.0")
-        .header("Accept", "application/vnd.github.v3+json")
-        .send()?;
+        let response = cache::get(
+            &self.http,
+            &api_url,
+            &[("Accept", "application/vnd.github.v3+json")],
+        )
+        .await?;
 
-    let repo_data: GitHubRepo = response.json()?;
+        let repo_data: GitHubRepo = serde_json::from_str(&response)?;
 
-    Ok(repo_data)
+        Ok(repo_data)
+    }
+}
+
+/// Blocking version of [`GitHubClient::fetch`].
+pub fn fetch_github_metadata(url: &str) -> Result<GitHubRepo> {
+    http::block_on(|client| async move { GitHubClient::with_client(client).fetch(url).await })
 }
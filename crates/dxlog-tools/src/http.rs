@@ -0,0 +1,43 @@
+use anyhow::Result;
+use reqwest::Client;
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Returns the process-wide client used by the scraper clients' `new`.
+///
+/// `reqwest::Client` is reference counted, so every clone shares one
+/// connection pool.
+pub fn shared_client() -> Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(build_client).clone()
+}
+
+fn build_client() -> Client {
+    Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent("dxlog/0.1.0")
+        .build()
+        .expect("failed to build the HTTP client")
+}
+
+/// Drives an async fetch to completion for the blocking API.
+///
+/// The blocking functions get a runtime and client of their own, so they can be
+/// mixed freely with the async clients. They must not be called from async code.
+pub(crate) fn block_on<T, Fut>(fetch: impl FnOnce(Client) -> Fut) -> Result<T>
+where
+    Fut: Future<Output = Result<T>>,
+{
+    static BLOCKING: OnceLock<(tokio::runtime::Runtime, Client)> = OnceLock::new();
+    let (runtime, client) = BLOCKING.get_or_init(|| {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("dxlog-http")
+            .enable_all()
+            .build()
+            .expect("failed to start the HTTP runtime");
+        (runtime, build_client())
+    });
+    runtime.block_on(fetch(client.clone()))
+}
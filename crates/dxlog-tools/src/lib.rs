@@ -1,5 +1,40 @@
 mod arxiv_scraper;
+mod bibliography;
+mod biorxiv_scraper;
+mod cache;
+mod crossref_scraper;
+mod github_issues;
 mod github_scraper;
+mod http;
+#[cfg(feature = "llm")]
+mod llm;
+mod notion;
+mod openreview_scraper;
+mod run_tracker;
+mod sync;
+mod webhook;
+mod zotero;
 
-pub use arxiv_scraper::{fetch_arxiv_metadata, ArxivMetadata};
-pub use github_scraper::{fetch_github_metadata, GitHubRepo};
+pub use arxiv_scraper::{fetch_arxiv_metadata, ArxivClient, ArxivMetadata};
+pub use bibliography::{
+    normalize_doi, parse_bibtex, parse_bibtex_entries, parse_ris, BibEntry, BibRecord,
+};
+pub use biorxiv_scraper::{biorxiv_doi, fetch_biorxiv_metadata, BiorxivClient, BiorxivMetadata};
+pub use cache::{bypass_cache, is_offline, set_offline, OFFLINE_ENV_VAR};
+pub use crossref_scraper::{fetch_crossref_metadata, CrossrefClient, CrossrefWork};
+pub use github_issues::{write_github_issues, GitHubIssuesClient, IssueDraft};
+pub use github_scraper::{fetch_github_metadata, GitHubClient, GitHubRepo};
+pub use http::shared_client;
+#[cfg(feature = "llm")]
+pub use llm::{complete_chat, embed};
+pub use notion::{markdown_to_blocks, write_notion_pages, NotionClient, NotionPage};
+pub use openreview_scraper::{
+    fetch_openreview_metadata, OpenReviewClient, OpenReviewPaper, OpenReviewReview,
+};
+pub use run_tracker::{fetch_run_metrics, MlflowClient, TrackedRun, TrackerAuth, WandbClient};
+pub use sync::{
+    download_backup, list_remote_backups, upload_backup, RemoteClient, RemoteStore,
+    WEBDAV_PASSWORD_ENV_VAR, WEBDAV_USER_ENV_VAR,
+};
+pub use webhook::{post_webhook, ChatService};
+pub use zotero::{add_zotero_note, fetch_zotero_collection, ZoteroClient, ZoteroItem};
@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::debug;
+
+use crate::{cache, http};
+
+/// Models can take far longer to answer than the other APIs.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Inputs sent per embeddings request.
+const EMBEDDING_BATCH: usize = 64;
+
+#[derive(Deserialize)]
+struct Completion {
+    choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: Message,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Embeddings {
+    data: Vec<Embedding>,
+}
+
+#[derive(Deserialize)]
+struct Embedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// POSTs `request` as JSON to `url` and returns the response body.
+fn post(url: &str, api_key: Option<&str>, request: &Value) -> Result<String> {
+    if cache::is_offline() {
+        anyhow::bail!("Working offline; not sending the request to {}", url);
+    }
+    debug!(url, "POST");
+    http::block_on(|client| async move {
+        let mut builder = client.post(url).timeout(TIMEOUT).json(request);
+        if let Some(api_key) = api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+        let response = builder
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", url))?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            let message = serde_json::from_str::<Value>(&body)
+                .ok()
+                .and_then(|error| error["error"]["message"].as_str().map(str::to_string))
+                .unwrap_or(body);
+            anyhow::bail!("{} failed: {} ({})", url, message, status);
+        }
+        Ok(body)
+    })
+}
+
+/// Sends `prompt` with the `system` instructions to the chat completions API
+/// of an OpenAI-compatible server at `endpoint` (e.g. `https://api.openai.com/v1`)
+/// and returns the model's reply. `api_key` may be left out for local servers.
+pub fn complete_chat(
+    endpoint: &str,
+    api_key: Option<&str>,
+    model: &str,
+    system: &str,
+    prompt: &str,
+) -> Result<String> {
+    let url = format!("{}/chat/completions", endpoint.trim_end_matches('/'));
+    let request = json!({
+        "model": model,
+        "messages": [
+            { "role": "system", "content": system },
+            { "role": "user", "content": prompt },
+        ],
+    });
+    reply(&post(&url, api_key, &request)?)
+}
+
+/// Embeds each of `inputs` with `model` through the embeddings API of an
+/// OpenAI-compatible server at `endpoint`, returning their vectors in order.
+pub fn embed(
+    endpoint: &str,
+    api_key: Option<&str>,
+    model: &str,
+    inputs: &[String],
+) -> Result<Vec<Vec<f32>>> {
+    let url = format!("{}/embeddings", endpoint.trim_end_matches('/'));
+    let mut vectors = Vec::with_capacity(inputs.len());
+    for batch in inputs.chunks(EMBEDDING_BATCH) {
+        let request = json!({ "model": model, "input": batch });
+        vectors.extend(embeddings(&post(&url, api_key, &request)?, batch.len())?);
+    }
+    Ok(vectors)
+}
+
+/// The vectors of an embeddings response for `count` inputs, in input order.
+fn embeddings(body: &str, count: usize) -> Result<Vec<Vec<f32>>> {
+    let mut response: Embeddings =
+        serde_json::from_str(body).context("Unexpected embeddings response")?;
+    if response.data.len() != count {
+        anyhow::bail!(
+            "Expected {} embeddings, the API returned {}",
+            count,
+            response.data.len()
+        );
+    }
+    response.data.sort_by_key(|embedding| embedding.index);
+    Ok(response
+        .data
+        .into_iter()
+        .map(|embedding| embedding.embedding)
+        .collect())
+}
+
+/// The text of the first choice of a chat completion.
+fn reply(body: &str) -> Result<String> {
+    let completion: Completion =
+        serde_json::from_str(body).context("Unexpected chat completion response")?;
+    let text = completion
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|choice| choice.message.content)
+        .unwrap_or_default();
+    let text = text.trim();
+    if text.is_empty() {
+        anyhow::bail!("The model returned an empty reply");
+    }
+    Ok(text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply() {
+        let body = r#"{"id":"x","choices":[{"index":0,"message":{"role":"assistant","content":"  A short summary.\n"},"finish_reason":"stop"}]}"#;
+        assert_eq!(reply(body).unwrap(), "A short summary.");
+        assert!(reply(r#"{"choices":[]}"#).is_err());
+        assert!(reply(r#"{"choices":[{"message":{"content":null}}]}"#).is_err());
+        assert!(reply("not json").is_err());
+    }
+
+    #[test]
+    fn test_embeddings() {
+        let body = r#"{"object":"list","data":[{"index":1,"embedding":[0.5,0.5]},{"index":0,"embedding":[1.0,0.0]}],"model":"m"}"#;
+        assert_eq!(
+            embeddings(body, 2).unwrap(),
+            vec![vec![1.0, 0.0], vec![0.5, 0.5]]
+        );
+        assert!(embeddings(body, 3).is_err());
+    }
+}
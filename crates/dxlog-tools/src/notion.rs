@@ -0,0 +1,544 @@
+use anyhow::{Context, Result};
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tracing::debug;
+
+use crate::{cache, http};
+
+const API_URL: &str = "https://api.notion.com/v1";
+const API_VERSION: &str = "2022-06-28";
+
+/// Most blocks Notion accepts in one request.
+const MAX_BLOCKS: usize = 100;
+
+/// Most characters Notion accepts in one rich text object.
+const MAX_TEXT: usize = 2000;
+
+/// Code block languages Notion knows; others are shown as plain text.
+const LANGUAGES: &[&str] = &[
+    "bash",
+    "c",
+    "c++",
+    "css",
+    "diff",
+    "go",
+    "haskell",
+    "html",
+    "java",
+    "javascript",
+    "json",
+    "julia",
+    "latex",
+    "markdown",
+    "python",
+    "r",
+    "ruby",
+    "rust",
+    "shell",
+    "sql",
+    "toml",
+    "typescript",
+    "yaml",
+];
+
+/// A page to write to a Notion database.
+#[derive(Debug, Clone)]
+pub struct NotionPage {
+    pub title: String,
+    pub tags: Vec<String>,
+    pub status: String,
+    /// Page content as markdown, converted to Notion blocks.
+    pub markdown: String,
+}
+
+/// The database properties pages are written to. Only the title property is
+/// required; tags and status are set when the database has a multi-select
+/// `Tags` and a select `Status` property.
+struct Schema {
+    title: String,
+    tags: Option<String>,
+    status: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Database {
+    properties: HashMap<String, Property>,
+}
+
+#[derive(Deserialize)]
+struct Property {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+struct Created {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct Children {
+    results: Vec<Created>,
+    has_more: bool,
+    next_cursor: Option<String>,
+}
+
+/// Writes pages to Notion databases through the Notion API.
+#[derive(Clone)]
+pub struct NotionClient {
+    http: Client,
+    token: String,
+}
+
+impl NotionClient {
+    /// Creates a client on the [`shared_client`](crate::shared_client).
+    pub fn new(token: &str) -> Self {
+        Self::with_client(http::shared_client(), token)
+    }
+
+    pub fn with_client(http: Client, token: &str) -> Self {
+        Self {
+            http,
+            token: token.to_string(),
+        }
+    }
+
+    async fn send(&self, request: RequestBuilder, what: &str) -> Result<String> {
+        if cache::is_offline() {
+            anyhow::bail!("Working offline; the Notion API needs network access",);
+        }
+        debug!("Notion API: {}", what);
+        let response = request
+            .bearer_auth(&self.token)
+            .header("Notion-Version", API_VERSION)
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            let message = serde_json::from_str::<Value>(&body)
+                .ok()
+                .and_then(|error| error["message"].as_str().map(str::to_string))
+                .unwrap_or(body);
+            anyhow::bail!("Failed to {}: {} ({})", what, message, status);
+        }
+        Ok(body)
+    }
+
+    async fn schema(&self, database: &str) -> Result<Schema> {
+        let body = self
+            .send(
+                self.http.get(format!("{}/databases/{}", API_URL, database)),
+                "read the Notion database",
+            )
+            .await?;
+        let database: Database = serde_json::from_str(&body)?;
+        let find = |name: &str, kind: &str| {
+            database
+                .properties
+                .iter()
+                .find(|(n, p)| n.eq_ignore_ascii_case(name) && p.kind == kind)
+                .map(|(n, _)| n.clone())
+        };
+        Ok(Schema {
+            title: database
+                .properties
+                .iter()
+                .find(|(_, p)| p.kind == "title")
+                .map(|(n, _)| n.clone())
+                .context("The Notion database has no title property")?,
+            tags: find("tags", "multi_select"),
+            status: find("status", "select"),
+        })
+    }
+
+    fn properties(schema: &Schema, page: &NotionPage) -> Value {
+        let mut properties = serde_json::Map::new();
+        properties.insert(
+            schema.title.clone(),
+            json!({ "title": rich_text(&page.title, &Style::default()) }),
+        );
+        if let Some(tags) = &schema.tags {
+            let options: Vec<Value> = page
+                .tags
+                .iter()
+                .map(|tag| json!({ "name": tag.replace(',', " ") }))
+                .collect();
+            properties.insert(tags.clone(), json!({ "multi_select": options }));
+        }
+        if let Some(status) = &schema.status {
+            properties.insert(status.clone(), json!({ "select": { "name": page.status } }));
+        }
+        Value::Object(properties)
+    }
+
+    async fn append_blocks(&self, page_id: &str, blocks: &[Value]) -> Result<()> {
+        for chunk in blocks.chunks(MAX_BLOCKS) {
+            self.send(
+                self.http
+                    .patch(format!("{}/blocks/{}/children", API_URL, page_id))
+                    .json(&json!({ "children": chunk })),
+                "add content to the Notion page",
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn clear_page(&self, page_id: &str) -> Result<()> {
+        let mut cursor: Option<String> = None;
+        let mut blocks = Vec::new();
+        loop {
+            let mut url = format!("{}/blocks/{}/children?page_size=100", API_URL, page_id);
+            if let Some(cursor) = &cursor {
+                url.push_str(&format!("&start_cursor={}", cursor));
+            }
+            let body = self
+                .send(self.http.get(url), "read the Notion page")
+                .await?;
+            let children: Children = serde_json::from_str(&body)?;
+            blocks.extend(children.results);
+            match children.next_cursor {
+                Some(next) if children.has_more => cursor = Some(next),
+                _ => break,
+            }
+        }
+        for block in blocks {
+            self.send(
+                self.http.delete(format!("{}/blocks/{}", API_URL, block.id)),
+                "clear the Notion page",
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Creates `page` in `database`, or replaces the properties and content of
+    /// the existing page `page_id`. Returns the page's ID.
+    pub async fn write_page(
+        &self,
+        database: &str,
+        page_id: Option<&str>,
+        page: &NotionPage,
+    ) -> Result<String> {
+        let schema = self.schema(database).await?;
+        let properties = Self::properties(&schema, page);
+        let blocks = markdown_to_blocks(&page.markdown);
+
+        match page_id {
+            Some(page_id) => {
+                self.send(
+                    self.http
+                        .patch(format!("{}/pages/{}", API_URL, page_id))
+                        .json(&json!({ "properties": properties })),
+                    "update the Notion page",
+                )
+                .await?;
+                self.clear_page(page_id).await?;
+                self.append_blocks(page_id, &blocks).await?;
+                Ok(page_id.to_string())
+            }
+            None => {
+                let first = blocks.len().min(MAX_BLOCKS);
+                let body = self
+                    .send(
+                        self.http.post(format!("{}/pages", API_URL)).json(&json!({
+                            "parent": { "database_id": database },
+                            "properties": properties,
+                            "children": &blocks[..first],
+                        })),
+                        "create the Notion page",
+                    )
+                    .await?;
+                let created: Created = serde_json::from_str(&body)?;
+                self.append_blocks(&created.id, &blocks[first..]).await?;
+                Ok(created.id)
+            }
+        }
+    }
+}
+
+/// Blocking version of [`NotionClient::write_page`] for several pages. Each
+/// page is paired with the ID of the page it was written to before, if any.
+/// A failure is reported for that page only.
+pub fn write_notion_pages(
+    token: &str,
+    database: &str,
+    pages: &[(Option<String>, NotionPage)],
+) -> Result<Vec<Result<String>>> {
+    http::block_on(|client| async move {
+        let notion = NotionClient::with_client(client, token);
+        let mut written = Vec::new();
+        for (page_id, page) in pages {
+            written.push(notion.write_page(database, page_id.as_deref(), page).await);
+        }
+        Ok(written)
+    })
+}
+
+#[derive(Default, Clone)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    strikethrough: bool,
+    code: bool,
+    link: Option<String>,
+}
+
+/// Rich text objects for `text`, split to stay within Notion's length limit.
+fn rich_text(text: &str, style: &Style) -> Vec<Value> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(MAX_TEXT)
+        .map(|chunk| {
+            let content: String = chunk.iter().collect();
+            json!({
+                "type": "text",
+                "text": {
+                    "content": content,
+                    "link": style.link.as_ref().map(|url| json!({ "url": url })),
+                },
+                "annotations": {
+                    "bold": style.bold,
+                    "italic": style.italic,
+                    "strikethrough": style.strikethrough,
+                    "code": style.code,
+                },
+            })
+        })
+        .collect()
+}
+
+/// A block being filled by [`markdown_to_blocks`]: its type, text, and other fields.
+type OpenBlock = (String, Vec<Value>, serde_json::Map<String, Value>);
+
+fn flush(current: &mut Option<OpenBlock>, blocks: &mut Vec<Value>) {
+    if let Some((kind, text, mut fields)) = current.take() {
+        if text.is_empty() && kind == "paragraph" {
+            return;
+        }
+        fields.insert("rich_text".to_string(), Value::Array(text));
+        blocks.push(json!({ "object": "block", "type": kind, kind: fields }));
+    }
+}
+
+fn push_text(current: &mut Option<OpenBlock>, text: &str, style: &Style) {
+    let (_, rich, _) =
+        current.get_or_insert_with(|| ("paragraph".to_string(), Vec::new(), Default::default()));
+    rich.extend(rich_text(text, style));
+}
+
+/// Converts markdown to Notion blocks: headings, paragraphs, lists, to-dos,
+/// quotes, code blocks and dividers, with bold, italic, strikethrough, code
+/// and links inline. Nested lists are flattened and tables become one
+/// paragraph per row.
+pub fn markdown_to_blocks(markdown: &str) -> Vec<Value> {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH;
+    let mut blocks = Vec::new();
+    let mut current: Option<OpenBlock> = None;
+    let mut style = Style::default();
+    let mut lists: Vec<bool> = Vec::new();
+    let mut quote = 0;
+
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                flush(&mut current, &mut blocks);
+                let kind = match level {
+                    HeadingLevel::H1 => "heading_1",
+                    HeadingLevel::H2 => "heading_2",
+                    _ => "heading_3",
+                };
+                current = Some((kind.to_string(), Vec::new(), Default::default()));
+            }
+            Event::Start(Tag::Paragraph) => {
+                let in_empty_item = current.as_ref().is_some_and(|(kind, text, _)| {
+                    kind != "paragraph" && kind != "quote" && text.is_empty()
+                });
+                if !in_empty_item {
+                    flush(&mut current, &mut blocks);
+                    let kind = if quote > 0 { "quote" } else { "paragraph" };
+                    current = Some((kind.to_string(), Vec::new(), Default::default()));
+                }
+            }
+            Event::Start(Tag::TableRow) | Event::Start(Tag::TableHead) => {
+                flush(&mut current, &mut blocks);
+                current = Some(("paragraph".to_string(), Vec::new(), Default::default()));
+            }
+            Event::End(TagEnd::TableCell) => {
+                if let Some((_, text, _)) = &mut current {
+                    text.extend(rich_text(" | ", &Style::default()));
+                }
+            }
+            Event::Start(Tag::List(start)) => {
+                flush(&mut current, &mut blocks);
+                lists.push(start.is_some());
+            }
+            Event::End(TagEnd::List(_)) => {
+                flush(&mut current, &mut blocks);
+                lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                flush(&mut current, &mut blocks);
+                let kind = if lists.last() == Some(&true) {
+                    "numbered_list_item"
+                } else {
+                    "bulleted_list_item"
+                };
+                current = Some((kind.to_string(), Vec::new(), Default::default()));
+            }
+            Event::TaskListMarker(checked) => {
+                if let Some((kind, _, extra)) = &mut current {
+                    *kind = "to_do".to_string();
+                    extra.insert("checked".to_string(), json!(checked));
+                }
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                flush(&mut current, &mut blocks);
+                quote += 1;
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                flush(&mut current, &mut blocks);
+                quote -= 1;
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                flush(&mut current, &mut blocks);
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        let info = info.split_whitespace().next().unwrap_or("").to_lowercase();
+                        match info.as_str() {
+                            "sh" | "zsh" | "console" => "shell".to_string(),
+                            "py" => "python".to_string(),
+                            "js" => "javascript".to_string(),
+                            "ts" => "typescript".to_string(),
+                            "rs" => "rust".to_string(),
+                            "tex" => "latex".to_string(),
+                            "md" => "markdown".to_string(),
+                            "yml" => "yaml".to_string(),
+                            "cpp" => "c++".to_string(),
+                            language if LANGUAGES.contains(&language) => language.to_string(),
+                            _ => "plain text".to_string(),
+                        }
+                    }
+                    CodeBlockKind::Indented => "plain text".to_string(),
+                };
+                let mut extra = serde_json::Map::new();
+                extra.insert("language".to_string(), json!(language));
+                current = Some(("code".to_string(), Vec::new(), extra));
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((_, text, _)) = &mut current {
+                    // The code arrives with its final newline, which Notion would show.
+                    if let Some(last) = text.last_mut() {
+                        let content = &mut last["text"]["content"];
+                        if let Some(trimmed) = content.as_str().map(|s| s.trim_end_matches('\n')) {
+                            *content = json!(trimmed);
+                        }
+                    }
+                }
+                flush(&mut current, &mut blocks);
+            }
+            Event::End(TagEnd::Heading(_)) | Event::End(TagEnd::Paragraph) => {
+                let in_item = current
+                    .as_ref()
+                    .is_some_and(|(kind, _, _)| kind.ends_with("list_item") || kind == "to_do");
+                if !in_item {
+                    flush(&mut current, &mut blocks);
+                }
+            }
+            Event::End(TagEnd::Item) => flush(&mut current, &mut blocks),
+            Event::Rule => {
+                flush(&mut current, &mut blocks);
+                blocks.push(json!({ "object": "block", "type": "divider", "divider": {} }));
+            }
+            Event::Start(Tag::Emphasis) => style.italic = true,
+            Event::End(TagEnd::Emphasis) => style.italic = false,
+            Event::Start(Tag::Strong) => style.bold = true,
+            Event::End(TagEnd::Strong) => style.bold = false,
+            Event::Start(Tag::Strikethrough) => style.strikethrough = true,
+            Event::End(TagEnd::Strikethrough) => style.strikethrough = false,
+            Event::Start(Tag::Link { dest_url, .. }) => style.link = Some(dest_url.to_string()),
+            Event::End(TagEnd::Link) => style.link = None,
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                push_text(&mut current, &text, &style)
+            }
+            Event::Code(text) => {
+                let code = Style {
+                    code: true,
+                    ..style.clone()
+                };
+                push_text(&mut current, &text, &code)
+            }
+            Event::SoftBreak => {
+                if let Some((_, text, _)) = &mut current {
+                    text.extend(rich_text(" ", &style));
+                }
+            }
+            Event::HardBreak => {
+                if let Some((_, text, _)) = &mut current {
+                    text.extend(rich_text("\n", &style));
+                }
+            }
+            _ => {}
+        }
+    }
+    flush(&mut current, &mut blocks);
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(blocks: &[Value]) -> Vec<(String, String)> {
+        blocks
+            .iter()
+            .map(|block| {
+                let kind = block["type"].as_str().unwrap().to_string();
+                let text = block[&kind]["rich_text"]
+                    .as_array()
+                    .map(|parts| {
+                        parts
+                            .iter()
+                            .map(|part| part["text"]["content"].as_str().unwrap())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (kind, text)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_markdown_to_blocks() {
+        let markdown = "# Title\n\nSome **bold** and `code`.\n\n- one\n- [x] done\n\n1. first\n\n> quoted\n\n```rs\nfn main() {}\n```\n\n---\n";
+        let blocks = markdown_to_blocks(markdown);
+        let expected = [
+            ("heading_1", "Title"),
+            ("paragraph", "Some bold and code."),
+            ("bulleted_list_item", "one"),
+            ("to_do", "done"),
+            ("numbered_list_item", "first"),
+            ("quote", "quoted"),
+            ("code", "fn main() {}"),
+            ("divider", ""),
+        ];
+        assert_eq!(
+            summary(&blocks),
+            expected
+                .iter()
+                .map(|(kind, text)| (kind.to_string(), text.to_string()))
+                .collect::<Vec<_>>()
+        );
+
+        let paragraph = &blocks[1]["paragraph"]["rich_text"];
+        assert_eq!(paragraph[1]["annotations"]["bold"], json!(true));
+        assert_eq!(paragraph[3]["annotations"]["code"], json!(true));
+        assert_eq!(blocks[3]["to_do"]["checked"], json!(true));
+        assert_eq!(blocks[6]["code"]["language"], json!("rust"));
+    }
+}
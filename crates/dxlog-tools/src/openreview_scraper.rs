@@ -0,0 +1,214 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::{cache, http};
+
+#[derive(Debug, Default)]
+pub struct OpenReviewPaper {
+    pub forum_id: String,
+    pub title: String,
+    pub abstract_text: String,
+    pub authors: Vec<String>,
+    /// e.g. "ICLR 2024 poster".
+    pub venue: Option<String>,
+    /// e.g. "Accept (poster)", from the program chairs' decision note.
+    pub decision: Option<String>,
+    pub reviews: Vec<OpenReviewReview>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OpenReviewReview {
+    pub rating: Option<String>,
+    pub confidence: Option<String>,
+    pub text: String,
+}
+
+/// Extracts the forum ID from a forum or PDF URL such as
+/// `https://openreview.net/forum?id=abc123&noteId=xyz`.
+fn extract_forum_id(url: &str) -> Option<String> {
+    let query = url.split_once('?')?.1;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "id")
+        .map(|(_, id)| id.split('#').next().unwrap_or(id).to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Fetches OpenReview submissions.
+#[derive(Clone)]
+pub struct OpenReviewClient {
+    http: Client,
+}
+
+impl Default for OpenReviewClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OpenReviewClient {
+    /// Creates a client on the [`shared_client`](crate::shared_client).
+    pub fn new() -> Self {
+        Self::with_client(http::shared_client())
+    }
+
+    pub fn with_client(http: Client) -> Self {
+        Self { http }
+    }
+
+    /// Fetches a paper together with its venue, decision and official reviews.
+    ///
+    /// Newer venues are served by the v2 API; older ones (before 2023) only by v1,
+    /// which is tried when v2 does not know the forum.
+    pub async fn fetch(&self, url: &str) -> Result<OpenReviewPaper> {
+        let forum_id =
+            extract_forum_id(url).context("Failed to extract the OpenReview forum ID")?;
+
+        for api in ["https://api2.openreview.net", "https://api.openreview.net"] {
+            let url = format!("{}/notes?forum={}", api, forum_id);
+            let response: Value = serde_json::from_str(&cache::get(&self.http, &url, &[]).await?)?;
+            if let Some(paper) = parse_forum(&forum_id, &response) {
+                return Ok(paper);
+            }
+        }
+        Err(anyhow::anyhow!("OpenReview forum {} not found", forum_id))
+    }
+}
+
+/// Blocking version of [`OpenReviewClient::fetch`].
+pub fn fetch_openreview_metadata(url: &str) -> Result<OpenReviewPaper> {
+    http::block_on(|client| async move { OpenReviewClient::with_client(client).fetch(url).await })
+}
+
+/// Builds the paper from all notes of a forum, or `None` if the submission is missing.
+fn parse_forum(forum_id: &str, response: &Value) -> Option<OpenReviewPaper> {
+    let notes = response.get("notes")?.as_array()?;
+    let submission = notes
+        .iter()
+        .find(|n| n.get("id").and_then(Value::as_str) == Some(forum_id))?;
+    let content = submission.get("content")?;
+
+    let mut paper = OpenReviewPaper {
+        forum_id: forum_id.to_string(),
+        title: text(content, "title").unwrap_or_default(),
+        abstract_text: text(content, "abstract").unwrap_or_default(),
+        authors: field(content, "authors")
+            .and_then(Value::as_array)
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        venue: text(content, "venue"),
+        ..Default::default()
+    };
+
+    for note in notes {
+        let Some(content) = note.get("content") else {
+            continue;
+        };
+        if is_invitation(note, "Decision") {
+            paper.decision = text(content, "decision");
+        } else if is_invitation(note, "Official_Review") {
+            // v2 reviews have a summary plus strengths/weaknesses; v1 a single "review" field.
+            let body = ["summary", "review", "main_review"]
+                .iter()
+                .find_map(|key| text(content, key))
+                .unwrap_or_default();
+            paper.reviews.push(OpenReviewReview {
+                rating: text_or_number(content, "rating"),
+                confidence: text_or_number(content, "confidence"),
+                text: body,
+            });
+        }
+    }
+
+    Some(paper)
+}
+
+/// v2 wraps every content field as `{"value": ...}`; v1 stores it directly.
+fn field<'a>(content: &'a Value, key: &str) -> Option<&'a Value> {
+    let value = content.get(key)?;
+    Some(value.get("value").unwrap_or(value))
+}
+
+fn text(content: &Value, key: &str) -> Option<String> {
+    field(content, key)
+        .and_then(Value::as_str)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn text_or_number(content: &Value, key: &str) -> Option<String> {
+    match field(content, key)? {
+        Value::Number(n) => Some(n.to_string()),
+        _ => text(content, key),
+    }
+}
+
+fn is_invitation(note: &Value, suffix: &str) -> bool {
+    let ends_with = |v: &Value| v.as_str().is_some_and(|s| s.ends_with(suffix));
+    note.get("invitation").is_some_and(ends_with)
+        || note
+            .get("invitations")
+            .and_then(Value::as_array)
+            .is_some_and(|all| all.iter().any(ends_with))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_forum() {
+        assert_eq!(
+            extract_forum_id("https://openreview.net/forum?id=abc123&noteId=xyz"),
+            Some("abc123".to_string())
+        );
+        assert_eq!(extract_forum_id("https://openreview.net/group"), None);
+
+        let response = serde_json::json!({
+            "notes": [
+                {
+                    "id": "r1",
+                    "invitations": ["ICLR.cc/2024/Conference/Submission1/-/Official_Review"],
+                    "content": {
+                        "summary": { "value": "Solid work." },
+                        "rating": { "value": 8 },
+                        "confidence": { "value": 4 }
+                    }
+                },
+                {
+                    "id": "d1",
+                    "invitations": ["ICLR.cc/2024/Conference/Submission1/-/Decision"],
+                    "content": { "decision": { "value": "Accept (poster)" } }
+                },
+                {
+                    "id": "abc123",
+                    "invitations": ["ICLR.cc/2024/Conference/-/Submission"],
+                    "content": {
+                        "title": { "value": "A Paper" },
+                        "abstract": { "value": " An abstract. " },
+                        "authors": { "value": ["Ada", "Alan"] },
+                        "venue": { "value": "ICLR 2024 poster" }
+                    }
+                }
+            ]
+        });
+
+        let paper = parse_forum("abc123", &response).unwrap();
+        assert_eq!(paper.title, "A Paper");
+        assert_eq!(paper.abstract_text, "An abstract.");
+        assert_eq!(paper.authors, vec!["Ada", "Alan"]);
+        assert_eq!(paper.venue.as_deref(), Some("ICLR 2024 poster"));
+        assert_eq!(paper.decision.as_deref(), Some("Accept (poster)"));
+        assert_eq!(paper.reviews.len(), 1);
+        assert_eq!(paper.reviews[0].rating.as_deref(), Some("8"));
+        assert_eq!(paper.reviews[0].text, "Solid work.");
+
+        assert!(parse_forum("missing", &response).is_none());
+    }
+}
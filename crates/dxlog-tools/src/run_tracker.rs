@@ -0,0 +1,326 @@
+use anyhow::{Context, Result};
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use tracing::debug;
+
+use crate::{cache, http};
+
+const WANDB_API_URL: &str = "https://api.wandb.ai/graphql";
+
+/// A run on an experiment tracker, as addressed by its URL in the web UI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrackedRun {
+    /// `https://wandb.ai/<entity>/<project>/runs/<run>`
+    Wandb {
+        entity: String,
+        project: String,
+        run: String,
+    },
+    /// `<server>/#/experiments/<experiment>/runs/<run>`
+    Mlflow { server: String, run: String },
+}
+
+impl TrackedRun {
+    /// Recognizes the URL of a Weights & Biases or MLflow run.
+    pub fn parse(url: &str) -> Result<Self> {
+        let url = url.trim();
+        let unsupported = || {
+            anyhow::anyhow!(
+                "Unsupported run URL '{}'. Expected a W&B run (https://wandb.ai/<entity>/<project>/runs/<run>) or an MLflow run (<server>/#/experiments/<id>/runs/<run>)",
+                url
+            )
+        };
+        if let Some((server, fragment)) = url.split_once("/#/") {
+            let parts: Vec<&str> = fragment.split(['/', '?']).collect();
+            return match parts[..] {
+                ["experiments", _, "runs", run, ..] if !run.is_empty() => Ok(Self::Mlflow {
+                    server: server.trim_end_matches('/').to_string(),
+                    run: run.to_string(),
+                }),
+                _ => Err(unsupported()),
+            };
+        }
+
+        let path = url
+            .strip_prefix("https://wandb.ai/")
+            .or_else(|| url.strip_prefix("http://wandb.ai/"))
+            .ok_or_else(unsupported)?;
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        match path.split('/').collect::<Vec<_>>()[..] {
+            [entity, project, "runs", run, ..]
+                if !entity.is_empty() && !project.is_empty() && !run.is_empty() =>
+            {
+                Ok(Self::Wandb {
+                    entity: entity.to_string(),
+                    project: project.to_string(),
+                    run: run.to_string(),
+                })
+            }
+            _ => Err(unsupported()),
+        }
+    }
+
+    /// Name of the tracker, for messages.
+    pub fn tracker(&self) -> &'static str {
+        match self {
+            Self::Wandb { .. } => "W&B",
+            Self::Mlflow { .. } => "MLflow",
+        }
+    }
+}
+
+/// Credentials for the trackers; each is only needed for runs on its tracker.
+#[derive(Debug, Clone, Default)]
+pub struct TrackerAuth {
+    pub wandb_api_key: Option<String>,
+    /// Bearer token, for MLflow servers that require one.
+    pub mlflow_token: Option<String>,
+}
+
+fn ensure_online(tracker: &str) -> Result<()> {
+    if cache::is_offline() {
+        anyhow::bail!("Working offline; the {} API needs network access", tracker);
+    }
+    Ok(())
+}
+
+async fn send(request: RequestBuilder, url: &str) -> Result<String> {
+    Ok(request
+        .send()
+        .await?
+        .error_for_status()
+        .with_context(|| format!("Request to {} failed", url))?
+        .text()
+        .await?)
+}
+
+#[derive(Deserialize)]
+struct WandbResponse {
+    data: Option<WandbData>,
+    #[serde(default)]
+    errors: Vec<WandbError>,
+}
+
+#[derive(Deserialize)]
+struct WandbData {
+    project: Option<WandbProject>,
+}
+
+#[derive(Deserialize)]
+struct WandbProject {
+    run: Option<WandbRun>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WandbRun {
+    /// JSON object encoded as a string.
+    summary_metrics: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WandbError {
+    message: String,
+}
+
+/// Reads run summaries through the Weights & Biases GraphQL API.
+#[derive(Clone)]
+pub struct WandbClient {
+    http: Client,
+    api_key: String,
+}
+
+impl WandbClient {
+    /// Creates a client on the [`shared_client`](crate::shared_client).
+    pub fn new(api_key: &str) -> Self {
+        Self::with_client(http::shared_client(), api_key)
+    }
+
+    pub fn with_client(http: Client, api_key: &str) -> Self {
+        Self {
+            http,
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// The final value of every numeric metric in the run's summary. Internal
+    /// values such as `_runtime` and `_step` are left out.
+    pub async fn summary_metrics(
+        &self,
+        entity: &str,
+        project: &str,
+        run: &str,
+    ) -> Result<BTreeMap<String, f64>> {
+        ensure_online("W&B")?;
+        let query = "query RunSummary($entity: String!, $project: String!, $run: String!) { \
+             project(name: $project, entityName: $entity) { run(name: $run) { summaryMetrics } } }";
+        let body = json!({
+            "query": query,
+            "variables": { "entity": entity, "project": project, "run": run },
+        });
+        debug!(url = WANDB_API_URL, run, "POST");
+        let request = self
+            .http
+            .post(WANDB_API_URL)
+            .basic_auth("api", Some(&self.api_key))
+            .json(&body);
+        let response = send(request, WANDB_API_URL).await?;
+        let response: WandbResponse = serde_json::from_str(&response)
+            .with_context(|| format!("Unexpected response from {}", WANDB_API_URL))?;
+        if let Some(error) = response.errors.first() {
+            anyhow::bail!("W&B rejected the request: {}", error.message);
+        }
+        let summary = response
+            .data
+            .and_then(|data| data.project)
+            .and_then(|project| project.run)
+            .with_context(|| format!("No W&B run '{}' in {}/{}", run, entity, project))?
+            .summary_metrics
+            .unwrap_or_default();
+        wandb_metrics(&summary)
+    }
+}
+
+fn wandb_metrics(summary: &str) -> Result<BTreeMap<String, f64>> {
+    if summary.trim().is_empty() {
+        return Ok(BTreeMap::new());
+    }
+    let summary: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(summary).context("Unexpected run summary from W&B")?;
+    Ok(summary
+        .into_iter()
+        .filter(|(name, _)| !name.starts_with('_'))
+        .filter_map(|(name, value)| Some((name, value.as_f64()?)))
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct MlflowResponse {
+    run: MlflowRun,
+}
+
+#[derive(Deserialize)]
+struct MlflowRun {
+    #[serde(default)]
+    data: MlflowRunData,
+}
+
+#[derive(Deserialize, Default)]
+struct MlflowRunData {
+    #[serde(default)]
+    metrics: Vec<MlflowMetric>,
+}
+
+#[derive(Deserialize)]
+struct MlflowMetric {
+    key: String,
+    value: f64,
+}
+
+/// Reads runs through the REST API of an MLflow tracking server.
+#[derive(Clone)]
+pub struct MlflowClient {
+    http: Client,
+    token: Option<String>,
+}
+
+impl MlflowClient {
+    /// Creates a client on the [`shared_client`](crate::shared_client).
+    pub fn new(token: Option<&str>) -> Self {
+        Self::with_client(http::shared_client(), token)
+    }
+
+    pub fn with_client(http: Client, token: Option<&str>) -> Self {
+        Self {
+            http,
+            token: token.map(str::to_string),
+        }
+    }
+
+    /// The latest value of every metric logged to the run.
+    pub async fn run_metrics(&self, server: &str, run: &str) -> Result<BTreeMap<String, f64>> {
+        ensure_online("MLflow")?;
+        let url = format!("{}/api/2.0/mlflow/runs/get", server);
+        debug!(url, run, "GET");
+        let mut request = self.http.get(&url).query(&[("run_id", run)]);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response = send(request, &url).await?;
+        let response: MlflowResponse = serde_json::from_str(&response)
+            .with_context(|| format!("Unexpected response from {}", url))?;
+        Ok(response
+            .run
+            .data
+            .metrics
+            .into_iter()
+            .map(|metric| (metric.key, metric.value))
+            .collect())
+    }
+}
+
+/// Blocking fetch of the final metrics of a run from its tracker.
+pub fn fetch_run_metrics(run: &TrackedRun, auth: &TrackerAuth) -> Result<BTreeMap<String, f64>> {
+    http::block_on(|client| async move {
+        match run {
+            TrackedRun::Wandb {
+                entity,
+                project,
+                run,
+            } => {
+                let api_key = auth.wandb_api_key.as_deref().context(
+                    "Fetching metrics from W&B needs an API key (--wandb-api-key or WANDB_API_KEY)",
+                )?;
+                WandbClient::with_client(client, api_key)
+                    .summary_metrics(entity, project, run)
+                    .await
+            }
+            TrackedRun::Mlflow { server, run } => {
+                MlflowClient::with_client(client, auth.mlflow_token.as_deref())
+                    .run_metrics(server, run)
+                    .await
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_run_urls() {
+        assert_eq!(
+            TrackedRun::parse("https://wandb.ai/ada/cavity/runs/3kx9q2ab?nw=nwuserada").unwrap(),
+            TrackedRun::Wandb {
+                entity: "ada".to_string(),
+                project: "cavity".to_string(),
+                run: "3kx9q2ab".to_string(),
+            }
+        );
+        assert_eq!(
+            TrackedRun::parse("http://mlflow.lab:5000/#/experiments/4/runs/9f1c2e/metrics")
+                .unwrap(),
+            TrackedRun::Mlflow {
+                server: "http://mlflow.lab:5000".to_string(),
+                run: "9f1c2e".to_string(),
+            }
+        );
+        assert!(TrackedRun::parse("https://wandb.ai/ada/cavity").is_err());
+        assert!(TrackedRun::parse("https://example.org/runs/1").is_err());
+    }
+
+    #[test]
+    fn test_wandb_metrics() {
+        let metrics = wandb_metrics(
+            r#"{"accuracy": 0.91, "loss": 0.12, "_step": 99, "model": "resnet", "val": {"loss": 0.2}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            metrics.into_iter().collect::<Vec<_>>(),
+            vec![("accuracy".to_string(), 0.91), ("loss".to_string(), 0.12)]
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use crate::{cache, http};
+
+/// Chat service an incoming webhook belongs to, which decides the message format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatService {
+    #[default]
+    Slack,
+    Discord,
+}
+
+impl ChatService {
+    /// `text` in bold, in the service's markup.
+    pub fn bold(self, text: &str) -> String {
+        match self {
+            ChatService::Slack => format!("*{}*", text),
+            ChatService::Discord => format!("**{}**", text),
+        }
+    }
+}
+
+/// Posts `text` to a Slack or Discord incoming webhook.
+pub fn post_webhook(url: &str, service: ChatService, text: &str) -> Result<()> {
+    if cache::is_offline() {
+        anyhow::bail!("Working offline; not posting to {}", url);
+    }
+    let body = match service {
+        ChatService::Slack => json!({ "text": text }),
+        ChatService::Discord => json!({ "content": text }),
+    };
+    debug!(url, "POST");
+    http::block_on(|client| async move {
+        client
+            .post(url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Webhook {} failed", url))?;
+        Ok(())
+    })
+}
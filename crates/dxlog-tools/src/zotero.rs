@@ -0,0 +1,333 @@
+use anyhow::{Context, Result};
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use tracing::debug;
+
+use crate::{cache, http, normalize_doi};
+
+const API_URL: &str = "https://api.zotero.org";
+
+/// Items fetched per request, the most the API allows.
+const PAGE_SIZE: usize = 100;
+
+/// A bibliographic item from a Zotero library.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoteroItem {
+    /// Key of the item within its library.
+    pub key: String,
+    pub title: String,
+    /// Author names in the order listed, as "Given Family".
+    pub authors: Vec<String>,
+    pub year: Option<String>,
+    pub doi: Option<String>,
+    pub url: Option<String>,
+    pub abstract_text: Option<String>,
+    /// Journal, proceedings or conference the item appeared in.
+    pub venue: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct KeyInfo {
+    #[serde(rename = "userID")]
+    user_id: u64,
+}
+
+#[derive(Deserialize)]
+struct Collection {
+    key: String,
+    data: CollectionData,
+}
+
+#[derive(Deserialize)]
+struct CollectionData {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    key: String,
+    data: ItemData,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ItemData {
+    item_type: String,
+    title: String,
+    creators: Vec<Creator>,
+    date: String,
+    #[serde(rename = "DOI")]
+    doi: String,
+    url: String,
+    abstract_note: String,
+    publication_title: String,
+    proceedings_title: String,
+    conference_name: String,
+}
+
+/// A Zotero creator: either split into first and last name, or a single `name`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Creator {
+    creator_type: String,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WriteResponse {
+    #[serde(default)]
+    successful: HashMap<String, Item>,
+    #[serde(default)]
+    failed: HashMap<String, WriteFailure>,
+}
+
+#[derive(Deserialize)]
+struct WriteFailure {
+    message: String,
+}
+
+fn non_empty(value: String) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+impl From<Item> for ZoteroItem {
+    fn from(item: Item) -> Self {
+        let data = item.data;
+        let authors = data
+            .creators
+            .into_iter()
+            .filter(|c| c.creator_type == "author")
+            .filter_map(|c| match (c.first_name, c.last_name) {
+                (Some(first), Some(last)) if !first.is_empty() => {
+                    Some(format!("{} {}", first, last))
+                }
+                (_, Some(last)) if !last.is_empty() => Some(last),
+                _ => c.name,
+            })
+            .collect();
+        let year = data
+            .date
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|part| part.len() == 4)
+            .map(str::to_string);
+        ZoteroItem {
+            key: item.key,
+            title: data.title.trim().to_string(),
+            authors,
+            year,
+            doi: non_empty(data.doi).map(|doi| normalize_doi(&doi)),
+            url: non_empty(data.url),
+            abstract_text: non_empty(data.abstract_note),
+            venue: non_empty(data.publication_title)
+                .or_else(|| non_empty(data.proceedings_title))
+                .or_else(|| non_empty(data.conference_name)),
+        }
+    }
+}
+
+/// Reads and annotates a Zotero library through the Zotero web API.
+///
+/// Libraries are addressed by their API path: `users/<id>` for a personal
+/// library (see [`ZoteroClient::user_library`]) or `groups/<id>` for a group.
+#[derive(Clone)]
+pub struct ZoteroClient {
+    http: Client,
+    api_key: String,
+}
+
+impl ZoteroClient {
+    /// Creates a client on the [`shared_client`](crate::shared_client).
+    pub fn new(api_key: &str) -> Self {
+        Self::with_client(http::shared_client(), api_key)
+    }
+
+    pub fn with_client(http: Client, api_key: &str) -> Self {
+        Self {
+            http,
+            api_key: api_key.to_string(),
+        }
+    }
+
+    fn request(&self, request: RequestBuilder) -> Result<RequestBuilder> {
+        if cache::is_offline() {
+            anyhow::bail!("Working offline; the Zotero API needs network access",);
+        }
+        Ok(request
+            .header("Zotero-API-Key", &self.api_key)
+            .header("Zotero-API-Version", "3"))
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T> {
+        let url = format!("{}/{}", API_URL, path);
+        debug!(url, "GET");
+        let response = self
+            .request(self.http.get(&url))?
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Request to {} failed", url))?
+            .text()
+            .await?;
+        serde_json::from_str(&response).with_context(|| format!("Unexpected response from {}", url))
+    }
+
+    /// The personal library of the API key's owner.
+    pub async fn user_library(&self) -> Result<String> {
+        let info: KeyInfo = self
+            .get(&format!("keys/{}", self.api_key))
+            .await
+            .context("Failed to look up the owner of the Zotero API key")?;
+        Ok(format!("users/{}", info.user_id))
+    }
+
+    /// Finds a collection in `library` by key or, ignoring case, by name.
+    pub async fn collection_key(&self, library: &str, collection: &str) -> Result<String> {
+        let mut start = 0;
+        loop {
+            let page: Vec<Collection> = self
+                .get(&format!(
+                    "{}/collections?limit={}&start={}",
+                    library, PAGE_SIZE, start
+                ))
+                .await?;
+            if let Some(found) = page.iter().find(|c| {
+                c.key == collection || c.data.name.to_lowercase() == collection.to_lowercase()
+            }) {
+                return Ok(found.key.clone());
+            }
+            if page.len() < PAGE_SIZE {
+                anyhow::bail!("No Zotero collection named '{}' in {}", collection, library);
+            }
+            start += PAGE_SIZE;
+        }
+    }
+
+    /// The top-level items of a collection, leaving out notes and attachments.
+    pub async fn collection_items(
+        &self,
+        library: &str,
+        collection_key: &str,
+    ) -> Result<Vec<ZoteroItem>> {
+        let mut items = Vec::new();
+        let mut start = 0;
+        loop {
+            let page: Vec<Item> = self
+                .get(&format!(
+                    "{}/collections/{}/items/top?format=json&limit={}&start={}",
+                    library, collection_key, PAGE_SIZE, start
+                ))
+                .await?;
+            let full = page.len() == PAGE_SIZE;
+            items.extend(
+                page.into_iter()
+                    .filter(|item| !matches!(item.data.item_type.as_str(), "note" | "attachment"))
+                    .map(ZoteroItem::from),
+            );
+            if !full {
+                return Ok(items);
+            }
+            start += PAGE_SIZE;
+        }
+    }
+
+    /// Adds a child note with the HTML `note` to the item `parent_key` and
+    /// returns the note's key.
+    pub async fn add_note(&self, library: &str, parent_key: &str, note: &str) -> Result<String> {
+        let url = format!("{}/{}/items", API_URL, library);
+        let body = json!([{ "itemType": "note", "parentItem": parent_key, "note": note }]);
+        debug!(url, "POST");
+        let response = self
+            .request(self.http.post(&url))?
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Request to {} failed", url))?
+            .text()
+            .await?;
+        let mut response: WriteResponse = serde_json::from_str(&response)
+            .with_context(|| format!("Unexpected response from {}", url))?;
+        if let Some(failure) = response.failed.remove("0") {
+            anyhow::bail!("Zotero rejected the note: {}", failure.message);
+        }
+        response
+            .successful
+            .remove("0")
+            .map(|note| note.key)
+            .context("Zotero did not report the new note")
+    }
+}
+
+/// Blocking fetch of a collection's items. Without `group`, the collection is
+/// looked up in the API key owner's library. Returns the library path with the items.
+pub fn fetch_zotero_collection(
+    api_key: &str,
+    group: Option<u64>,
+    collection: &str,
+) -> Result<(String, Vec<ZoteroItem>)> {
+    http::block_on(|client| async move {
+        let zotero = ZoteroClient::with_client(client, api_key);
+        let library = match group {
+            Some(group) => format!("groups/{}", group),
+            None => zotero.user_library().await?,
+        };
+        let key = zotero.collection_key(&library, collection).await?;
+        let items = zotero.collection_items(&library, &key).await?;
+        Ok((library, items))
+    })
+}
+
+/// Blocking version of [`ZoteroClient::add_note`].
+pub fn add_zotero_note(
+    api_key: &str,
+    library: &str,
+    parent_key: &str,
+    note: &str,
+) -> Result<String> {
+    http::block_on(|client| async move {
+        ZoteroClient::with_client(client, api_key)
+            .add_note(library, parent_key, note)
+            .await
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zotero_item() {
+        let item: Item = serde_json::from_str(
+            r#"{
+                "key": "ABCD2345",
+                "data": {
+                    "itemType": "conferencePaper",
+                    "title": " Attention Is All You Need ",
+                    "creators": [
+                        {"creatorType": "author", "firstName": "Ashish", "lastName": "Vaswani"},
+                        {"creatorType": "editor", "firstName": "Ed", "lastName": "Itor"},
+                        {"creatorType": "author", "name": "Google Brain"}
+                    ],
+                    "date": "December 2017",
+                    "DOI": "",
+                    "url": "https://arxiv.org/abs/1706.03762",
+                    "proceedingsTitle": "NeurIPS"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let item = ZoteroItem::from(item);
+        assert_eq!(item.key, "ABCD2345");
+        assert_eq!(item.title, "Attention Is All You Need");
+        assert_eq!(item.authors, vec!["Ashish Vaswani", "Google Brain"]);
+        assert_eq!(item.year.as_deref(), Some("2017"));
+        assert_eq!(item.doi, None);
+        assert_eq!(item.venue.as_deref(), Some("NeurIPS"));
+    }
+}
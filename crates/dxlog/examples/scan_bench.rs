@@ -0,0 +1,57 @@
+//! Times listing and ID lookup against a generated corpus.
+//!
+//! Run with `cargo run --release -p dxlog --example scan_bench [entries]`
+//! (default 5000 entries).
+
+use std::{fs, time::Instant};
+
+use dxlog::{init_repository, Config, HypothesisManager, ListFilter};
+
+fn main() -> anyhow::Result<()> {
+    let count: usize = std::env::args()
+        .nth(1)
+        .map(|n| n.parse())
+        .transpose()?
+        .unwrap_or(5000);
+
+    let root = std::env::temp_dir().join(format!("dxlog-bench-{}", uuid::Uuid::new_v4()));
+    init_repository(&root)?;
+    let config = Config::load(&root)?;
+
+    let mut ids = Vec::with_capacity(count);
+    for i in 0..count {
+        let id = uuid::Uuid::new_v4();
+        let content = format!(
+            "---\nid: {id}\ndate: 2025-01-{day:02}\ntitle: Hypothesis {i}\ntags:\n- bench\n\
+             created_by:\n  name: Bench\n  email: bench@example.org\nreferences: []\nstatus: Active\n\
+             ---\n\n# Hypothesis {i}\n\n{body}\n",
+            day = i % 28 + 1,
+            body = "Lorem ipsum dolor sit amet. ".repeat(40),
+        );
+        fs::write(
+            config
+                .storage
+                .active_dir
+                .join(format!("2025-01-01-hypothesis-{i}.md")),
+            content,
+        )?;
+        ids.push(id);
+    }
+
+    let manager = HypothesisManager::new(config);
+
+    let start = Instant::now();
+    let listed = manager.list(&ListFilter::default())?;
+    println!("list {} entries: {:?}", listed.len(), start.elapsed());
+
+    let start = Instant::now();
+    manager.find(&ids[count / 2].to_string())?;
+    println!("find by full id:    {:?}", start.elapsed());
+
+    let start = Instant::now();
+    manager.find(&ids[count / 2].to_string()[..8])?;
+    println!("find by partial id: {:?}", start.elapsed());
+
+    fs::remove_dir_all(&root)?;
+    Ok(())
+}
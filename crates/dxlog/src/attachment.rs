@@ -0,0 +1,288 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use tracing::warn;
+
+use crate::{
+    config::Config,
+    dry_run, load_config,
+    log_manager::LogManager,
+    lookup::{find_entry, AnyEntry},
+    operations,
+    pdf_text::{extract_pdf_text, is_pdf, text_path},
+    research_log::ResearchLog,
+    trash::Trash,
+    utils::{BaseLog, TagFilter},
+    CustomLogManager, DatasetManager, ExperimentManager, HypothesisManager, IdeaManager,
+    JournalManager, KnowledgeManager, ListFilter, LiteratureLog, LiteratureManager, MeetingManager,
+};
+
+/// A file stored with an entry by `dxlog attach`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    /// File name in the attachments directory of the entry.
+    pub name: String,
+    /// Size in bytes.
+    pub size: u64,
+    /// SHA-256 of the contents, hex encoded.
+    pub sha256: String,
+}
+
+/// Directory the attachments of an entry are kept in: `<attachments-dir>/<handle>/`,
+/// or the full ID for entries without a handle.
+pub fn attachment_dir(config: &Config, base: &BaseLog) -> PathBuf {
+    config
+        .storage
+        .attachments_dir
+        .join(base.handle.clone().unwrap_or_else(|| base.id.to_string()))
+}
+
+/// Size and SHA-256 of a file, read in one pass.
+fn checksum(path: &Path) -> Result<(u64, String)> {
+    let mut file =
+        fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+/// Outcome of [`attach_file`].
+#[derive(Debug)]
+pub struct Attached {
+    pub title: String,
+    /// Where the copy was stored.
+    pub path: PathBuf,
+    pub attachment: Attachment,
+    /// Whether an earlier attachment of the same name was overwritten.
+    pub replaced: bool,
+    /// File the text of a PDF attached to a literature entry was extracted to.
+    pub text: Option<PathBuf>,
+}
+
+fn attach(config: &Config, entry: &mut dyn AnyEntry, file: &Path) -> Result<Attached> {
+    let name = file
+        .file_name()
+        .and_then(|name| name.to_str())
+        .with_context(|| format!("{} is not a file", file.display()))?
+        .to_string();
+    let (size, sha256) = checksum(file)?;
+    let path = attachment_dir(config, entry.base()).join(&name);
+    let description = format!(
+        "attach '{}' to {} '{}'",
+        name,
+        entry.kind(),
+        entry.base().title
+    );
+
+    let attachment = Attachment { name, size, sha256 };
+    let replaced = operations::record(config, description, || {
+        // Keep the earlier file in the trash, where `dxlog undo` can bring it back from
+        if path.exists() {
+            Trash::new(config).put_replaced(&path)?;
+        }
+        dry_run::copy(file, &path)?;
+
+        let attachments = &mut entry.base_mut().attachments;
+        let replaced = match attachments.iter_mut().find(|a| a.name == attachment.name) {
+            Some(existing) => {
+                *existing = attachment.clone();
+                true
+            }
+            None => {
+                attachments.push(attachment.clone());
+                false
+            }
+        };
+        entry.save()?;
+        Ok(replaced)
+    })?;
+    Ok(Attached {
+        title: entry.base().title.clone(),
+        path,
+        attachment,
+        replaced,
+        text: None,
+    })
+}
+
+/// Copies `file` into the attachments directory of an entry and records its
+/// name, size and checksum in the entry. A file attached under the same name
+/// before is replaced, and kept in the trash until `dxlog undo` brings it
+/// back or the trash is emptied.
+///
+/// The text of a PDF attached to a literature entry is extracted next to it,
+/// for `dxlog search` and `dxlog literature quote`; a PDF without extractable
+/// text is still attached.
+pub fn attach_file(partial_id: &str, file: &Path) -> Result<Attached> {
+    if !file.is_file() {
+        return Err(anyhow::anyhow!("{} is not a file", file.display()));
+    }
+    let config = load_config()?;
+    let mut entry = find_entry(&config, partial_id)?;
+    let mut attached = attach(&config, entry.as_mut(), file)?;
+    if entry.kind() == LiteratureLog::KIND && is_pdf(&attached.attachment.name) {
+        let path = text_path(&attached.path);
+        match extract_pdf_text(file, &path) {
+            Ok(_) => attached.text = Some(path),
+            Err(err) => warn!("Attached without its text: {:#}", err),
+        }
+    }
+    Ok(attached)
+}
+
+/// The attachments of one entry, as returned by [`list_attachments`].
+#[derive(Debug)]
+pub struct EntryAttachments {
+    pub title: String,
+    pub dir: PathBuf,
+    pub attachments: Vec<Attachment>,
+}
+
+fn entry_attachments(config: &Config, base: &BaseLog) -> EntryAttachments {
+    EntryAttachments {
+        title: base.title.clone(),
+        dir: attachment_dir(config, base),
+        attachments: base.attachments.clone(),
+    }
+}
+
+pub fn list_attachments(partial_id: &str) -> Result<EntryAttachments> {
+    let config = load_config()?;
+    let entry = find_entry(&config, partial_id)?;
+    Ok(entry_attachments(&config, entry.base()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentIssue {
+    Missing,
+    /// The file no longer matches the size or checksum recorded for it.
+    Modified,
+}
+
+/// An attachment that failed [`verify_attachments`].
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachmentProblem {
+    /// Handle of the entry, or its title if it has none.
+    pub entry: String,
+    pub path: PathBuf,
+    pub issue: AttachmentIssue,
+}
+
+/// Outcome of [`verify_attachments`].
+#[derive(Debug, Default, Serialize)]
+pub struct AttachmentCheck {
+    /// Number of attachments checked.
+    pub checked: usize,
+    pub problems: Vec<AttachmentProblem>,
+}
+
+fn check_entries<T: ResearchLog>(
+    manager: &LogManager<T>,
+    check: &mut AttachmentCheck,
+) -> Result<()> {
+    for (log, _) in manager.list_log_files(&ListFilter::default())? {
+        check_entry(&manager.config, log.base(), check)?;
+    }
+    Ok(())
+}
+
+fn check_entry(config: &Config, base: &BaseLog, check: &mut AttachmentCheck) -> Result<()> {
+    let dir = attachment_dir(config, base);
+    for attachment in &base.attachments {
+        check.checked += 1;
+        let path = dir.join(&attachment.name);
+        let issue = match checksum(&path) {
+            Err(_) if !path.exists() => AttachmentIssue::Missing,
+            Err(err) => return Err(err),
+            Ok((size, sha256)) if size != attachment.size || sha256 != attachment.sha256 => {
+                AttachmentIssue::Modified
+            }
+            Ok(_) => continue,
+        };
+        check.problems.push(AttachmentProblem {
+            entry: base.handle.clone().unwrap_or_else(|| base.title.clone()),
+            path,
+            issue,
+        });
+    }
+    Ok(())
+}
+
+/// Checks every attachment against the size and checksum recorded when it
+/// was attached.
+pub fn verify_attachments(config: &Config) -> Result<AttachmentCheck> {
+    let mut check = AttachmentCheck::default();
+    check_entries(&HypothesisManager::new(config.clone()).manager, &mut check)?;
+    check_entries(&LiteratureManager::new(config.clone()).manager, &mut check)?;
+    check_entries(&KnowledgeManager::new(config.clone()).manager, &mut check)?;
+    check_entries(&JournalManager::new(config.clone()).manager, &mut check)?;
+    check_entries(&DatasetManager::new(config.clone()).manager, &mut check)?;
+    check_entries(&MeetingManager::new(config.clone()).manager, &mut check)?;
+    check_entries(&IdeaManager::new(config.clone()).manager, &mut check)?;
+    check_entries(&ExperimentManager::new(config.clone()).manager, &mut check)?;
+    for name in config.types.keys() {
+        let manager = CustomLogManager::new(config.clone(), name)?;
+        for (log, _) in manager.list(None, &TagFilter::default())? {
+            check_entry(config, &log.base, &mut check)?;
+        }
+    }
+    Ok(check)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempRepo;
+
+    #[test]
+    fn test_checksum() {
+        let dir = TempRepo::new();
+        let path = dir.root().join("data.txt");
+        fs::write(&path, "abc").unwrap();
+        assert_eq!(
+            checksum(&path).unwrap(),
+            (
+                3,
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_replaced_attachment_can_be_undone() {
+        use crate::{
+            md_frontmatter::update_markdown_frontmatter, test_support::author, HypothesisLog,
+        };
+        use std::collections::HashSet;
+
+        let repo = TempRepo::new();
+        let config = repo.load("");
+        let manager = HypothesisManager::new(config.clone()).manager;
+        let log = HypothesisLog::new("Noise".into(), HashSet::new(), author());
+        let content = update_markdown_frontmatter(&log, "# Noise").unwrap();
+        manager.save_log(&log, &content, false).unwrap();
+        let id = log.base.id.to_string();
+
+        let file = repo.root().join("scan.bin");
+        let attach_bytes = |bytes: &[u8]| {
+            fs::write(&file, bytes).unwrap();
+            let mut entry = find_entry(&config, &id).unwrap();
+            attach(&config, entry.as_mut(), &file).unwrap()
+        };
+        let first = attach_bytes(&[0, 159, 146, 150]);
+        let second = attach_bytes(&[255, 0, 1]);
+        assert!(second.replaced);
+        assert_eq!(fs::read(&second.path).unwrap(), [255, 0, 1]);
+
+        crate::operations::undo_last(&config).unwrap();
+        assert_eq!(fs::read(&first.path).unwrap(), [0, 159, 146, 150]);
+        let (log, _) = manager.find_log(&id).unwrap();
+        assert_eq!(log.base.attachments, vec![first.attachment]);
+    }
+}
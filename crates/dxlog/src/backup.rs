@@ -0,0 +1,406 @@
+//! Snapshots of the entry directories, kept as `.tar.zst` archives under
+//! `.dxlog/backups` so that bulk changes can be rolled back.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::debug;
+
+use dxlog_tools::{download_backup, list_remote_backups, upload_backup, RemoteStore};
+
+use crate::{dry_run, encryption, load_config, utils, Config};
+
+/// Directory, relative to the repository root, backups are written to.
+pub const BACKUP_DIR: &str = ".dxlog/backups";
+
+const BACKUP_EXTENSION: &str = ".tar.zst";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+/// Added to the names of backups encrypted before they are uploaded.
+const ENCRYPTED_EXTENSION: &str = ".age";
+
+/// A snapshot of the entry directories.
+#[derive(Debug, Clone, Serialize)]
+pub struct Backup {
+    /// File name without the extension: the time it was taken and its label,
+    /// e.g. `20250301-142233-tag-rename`.
+    pub name: String,
+    pub path: PathBuf,
+    pub created_at: Option<NaiveDateTime>,
+    /// Size of the archive in bytes.
+    pub size: u64,
+}
+
+impl Backup {
+    fn from_path(path: PathBuf) -> Option<Self> {
+        let name = path
+            .file_name()?
+            .to_str()?
+            .strip_suffix(BACKUP_EXTENSION)?
+            .to_string();
+        let created_at = name
+            .get(..15)
+            .and_then(|stamp| NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).ok());
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Some(Self {
+            name,
+            path,
+            created_at,
+            size,
+        })
+    }
+}
+
+/// The directories entries are stored in, leaving out those inside another one.
+pub(crate) fn snapshot_dirs(config: &Config) -> Vec<PathBuf> {
+    let storage = &config.storage;
+    let mut dirs = vec![
+        storage.active_dir.clone(),
+        storage.archive_dir.clone(),
+        storage.knowledge_base_dir.clone(),
+        storage.journal_dir.clone(),
+        storage.datasets_dir.clone(),
+        storage.meetings_dir.clone(),
+        storage.inbox_dir.clone(),
+        storage.experiments_dir.clone(),
+    ];
+    dirs.extend(config.types.keys().map(|name| config.type_dir(name)));
+    for kind in config.storage.layout.keys() {
+        dirs.extend(config.layout_dirs(kind));
+    }
+    dirs.sort();
+    dirs.dedup();
+    dirs.iter()
+        .filter(|dir| {
+            !dirs
+                .iter()
+                .any(|other| other != *dir && dir.starts_with(other))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Keeps letters, digits and dashes of a label so it is safe in a file name.
+fn clean_label(label: &str) -> String {
+    label
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+/// Archives the entry directories into a new backup named after the current
+/// time and `label`.
+pub(crate) fn create(config: &Config, label: Option<&str>) -> Result<Backup> {
+    let mut name = utils::now().format(TIMESTAMP_FORMAT).to_string();
+    if let Some(label) = label.map(clean_label).filter(|label| !label.is_empty()) {
+        name = format!("{}-{}", name, label);
+    }
+    let dir = config.root.join(BACKUP_DIR);
+    let mut path = dir.join(format!("{}{}", name, BACKUP_EXTENSION));
+    for n in 2.. {
+        if !path.exists() {
+            break;
+        }
+        path = dir.join(format!("{}-{}{}", name, n, BACKUP_EXTENSION));
+    }
+
+    if dry_run::plan(|| dry_run::PlannedChange::Write { path: path.clone() }) {
+        return Ok(Backup::from_path(path).expect("backup paths end in the extension"));
+    }
+    debug!(path = %path.display(), "Writing backup");
+    utils::ensure_directory(&dir)?;
+    write_archive(config, &path)
+        .with_context(|| format!("Failed to write backup {}", path.display()))?;
+    Ok(Backup::from_path(path).expect("backup paths end in the extension"))
+}
+
+fn write_archive(config: &Config, path: &Path) -> Result<()> {
+    let file = fs::File::create(path)?;
+    let mut archive = tar::Builder::new(zstd::Encoder::new(file, 0)?.auto_finish());
+    for dir in snapshot_dirs(config) {
+        if !dir.is_dir() {
+            continue;
+        }
+        let relative = dir.strip_prefix(&config.root).with_context(|| {
+            format!(
+                "Cannot back up {}: it is outside the repository",
+                dir.display()
+            )
+        })?;
+        archive.append_dir_all(relative, &dir)?;
+    }
+    archive.into_inner()?;
+    Ok(())
+}
+
+/// Backups of the repository, newest first.
+pub(crate) fn list(config: &Config) -> Result<Vec<Backup>> {
+    let dir = config.root.join(BACKUP_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<Backup> = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| Backup::from_path(entry.ok()?.path()))
+        .collect();
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(backups)
+}
+
+/// The item whose name is `partial_name`, or the only one whose name starts with it.
+fn select<T>(items: Vec<T>, name: impl Fn(&T) -> &str, partial_name: &str) -> Result<T> {
+    let mut matches: Vec<T> = items
+        .into_iter()
+        .filter(|item| name(item).starts_with(partial_name))
+        .collect();
+    if let Some(exact) = matches.iter().position(|item| name(item) == partial_name) {
+        return Ok(matches.swap_remove(exact));
+    }
+
+    match matches.len() {
+        0 => Err(anyhow::anyhow!(
+            "No backup found with name starting with '{}'",
+            partial_name
+        )),
+        1 => Ok(matches.remove(0)),
+        _ => Err(anyhow::anyhow!(
+            "Multiple backups found with name starting with '{}'. Please provide more characters.",
+            partial_name
+        )),
+    }
+}
+
+fn find(config: &Config, partial_name: &str) -> Result<Backup> {
+    select(list(config)?, |backup| &backup.name, partial_name)
+}
+
+/// Replaces the entry directories with the contents of a backup. The current
+/// state is backed up first, so the restore can itself be undone.
+///
+/// Returns the restored backup and the one taken before restoring.
+pub(crate) fn restore(config: &Config, partial_name: &str) -> Result<(Backup, Backup)> {
+    let backup = find(config, partial_name)?;
+    let before = create(config, Some("before-restore"))?;
+
+    let open = || -> Result<_> {
+        let file = fs::File::open(&backup.path)
+            .with_context(|| format!("Failed to open backup {}", backup.path.display()))?;
+        Ok(tar::Archive::new(zstd::Decoder::new(file)?))
+    };
+    if dry_run::is_dry_run() {
+        for dir in snapshot_dirs(config).into_iter().filter(|dir| dir.exists()) {
+            dry_run::plan(|| dry_run::PlannedChange::Remove { path: dir });
+        }
+        for entry in open()?.entries()? {
+            let entry = entry?;
+            if entry.header().entry_type().is_file() {
+                let path = config.root.join(entry.path()?);
+                dry_run::plan(|| dry_run::PlannedChange::Write { path });
+            }
+        }
+        return Ok((backup, before));
+    }
+
+    for dir in snapshot_dirs(config).into_iter().filter(|dir| dir.exists()) {
+        fs::remove_dir_all(&dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+    }
+    open()?.unpack(&config.root).with_context(|| {
+        format!(
+            "Failed to restore backup {}; the previous state is in {}",
+            backup.name, before.name
+        )
+    })?;
+    Ok((backup, before))
+}
+
+fn remote_store(config: &Config, remote: Option<&str>) -> Result<RemoteStore> {
+    let url = remote
+        .or(config.backup.remote.as_deref())
+        .context("No backup remote. Pass --remote or set `remote` under [backup] in dxlog.toml")?;
+    RemoteStore::parse(
+        url,
+        config.backup.region.as_deref(),
+        config.backup.endpoint.as_deref(),
+    )
+}
+
+/// Name of a backup file on the remote without its extensions.
+fn remote_name(file_name: &str) -> &str {
+    let name = file_name
+        .strip_suffix(ENCRYPTED_EXTENSION)
+        .unwrap_or(file_name);
+    name.strip_suffix(BACKUP_EXTENSION).unwrap_or(name)
+}
+
+/// Uploads a backup to the remote, encrypted to the `[encryption]`
+/// recipients unless `[backup] encrypt = false`. A new backup is taken
+/// unless `partial_name` names an existing one.
+///
+/// Returns the backup and the name of the file uploaded.
+pub(crate) fn push(
+    config: &Config,
+    remote: Option<&str>,
+    partial_name: Option<&str>,
+) -> Result<(Backup, String)> {
+    let store = remote_store(config, remote)?;
+    let encrypt = config.backup.encrypt();
+    if encrypt && config.encryption.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Backups are encrypted before they are uploaded, but there are no recipients. Add your age public key to `recipients` under [encryption] in dxlog.toml, or set `encrypt = false` under [backup]"
+        ));
+    }
+    let backup = match partial_name {
+        Some(partial_name) => find(config, partial_name)?,
+        None => create(config, Some("push"))?,
+    };
+    let mut file_name = format!("{}{}", backup.name, BACKUP_EXTENSION);
+    if encrypt {
+        file_name.push_str(ENCRYPTED_EXTENSION);
+    }
+    if dry_run::is_dry_run() {
+        return Ok((backup, file_name));
+    }
+
+    let data = fs::read(&backup.path)
+        .with_context(|| format!("Failed to read backup {}", backup.path.display()))?;
+    let data = if encrypt {
+        encryption::encrypt_data(config, &data)?
+    } else {
+        data
+    };
+    debug!(file = %file_name, "Uploading backup");
+    upload_backup(&store, &file_name, data)?;
+    Ok((backup, file_name))
+}
+
+/// Downloads a backup pushed with [`push`] into [`BACKUP_DIR`], decrypting it
+/// if it was encrypted, so it can be restored. The latest one is taken
+/// unless `partial_name` names another.
+pub(crate) fn pull(
+    config: &Config,
+    remote: Option<&str>,
+    partial_name: Option<&str>,
+) -> Result<Backup> {
+    let store = remote_store(config, remote)?;
+    let mut file_names: Vec<String> = list_remote_backups(&store)?
+        .into_iter()
+        .filter(|name| remote_name(name) != name.as_str())
+        .collect();
+    file_names.sort_by(|a, b| remote_name(a).cmp(remote_name(b)));
+    let file_name = match partial_name {
+        Some(partial_name) => select(file_names, |name| remote_name(name), partial_name)?,
+        None => file_names
+            .pop()
+            .context("There are no backups on the remote yet")?,
+    };
+
+    let name = remote_name(&file_name);
+    let path = config
+        .root
+        .join(BACKUP_DIR)
+        .join(format!("{}{}", name, BACKUP_EXTENSION));
+    if path.exists() {
+        return Err(anyhow::anyhow!(
+            "Backup {} is already in {}",
+            name,
+            BACKUP_DIR
+        ));
+    }
+    if dry_run::plan(|| dry_run::PlannedChange::Write { path: path.clone() }) {
+        return Ok(Backup::from_path(path).expect("backup paths end in the extension"));
+    }
+
+    debug!(file = %file_name, "Downloading backup");
+    let data = download_backup(&store, &file_name)?;
+    let data = if file_name.ends_with(ENCRYPTED_EXTENSION) {
+        encryption::decrypt_data(&data)
+            .with_context(|| format!("Failed to decrypt backup {}", name))?
+    } else {
+        data
+    };
+    utils::ensure_directory(&config.root.join(BACKUP_DIR))?;
+    fs::write(&path, data).with_context(|| format!("Failed to write file: {}", path.display()))?;
+    Ok(Backup::from_path(path).expect("backup paths end in the extension"))
+}
+
+pub fn create_backup(label: Option<&str>) -> Result<Backup> {
+    let config = load_config()?;
+    create(&config, label)
+}
+
+pub fn list_backups() -> Result<Vec<Backup>> {
+    let config = load_config()?;
+    list(&config)
+}
+
+pub fn restore_backup(partial_name: &str) -> Result<(Backup, Backup)> {
+    let config = load_config()?;
+    restore(&config, partial_name)
+}
+
+pub fn push_backup(remote: Option<&str>, partial_name: Option<&str>) -> Result<(Backup, String)> {
+    let config = load_config()?;
+    push(&config, remote, partial_name)
+}
+
+pub fn pull_backup(remote: Option<&str>, partial_name: Option<&str>) -> Result<Backup> {
+    let config = load_config()?;
+    pull(&config, remote, partial_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_snapshot_dirs_and_names() {
+        let mut config = Config::default();
+        let root = PathBuf::from("/repo");
+        config.storage.active_dir = root.join("logs");
+        config.storage.archive_dir = root.join("logs/archived");
+        config.storage.knowledge_base_dir = root.join("knowledge");
+        config.storage.journal_dir = root.join("journal");
+        config.storage.datasets_dir = root.join("knowledge/datasets");
+        config.storage.meetings_dir = root.join("journal/meetings");
+        config.storage.experiments_dir = root.join("knowledge/experiments");
+        config.storage.inbox_dir = root.join("logs/inbox");
+        config.root = root.clone();
+        config.storage.layout.insert(
+            "hypothesis".to_string(),
+            BTreeMap::from([("proven".to_string(), "results/{status}".to_string())]),
+        );
+
+        assert_eq!(
+            snapshot_dirs(&config),
+            vec![
+                root.join("journal"),
+                root.join("knowledge"),
+                root.join("logs"),
+                root.join("results/proven"),
+            ]
+        );
+
+        assert_eq!(clean_label("Tag rename: ml/NLP"), "tag-rename-ml-nlp");
+        let backup =
+            Backup::from_path(PathBuf::from("/b/20250301-142233-migrate.tar.zst")).unwrap();
+        assert_eq!(backup.name, "20250301-142233-migrate");
+        assert_eq!(
+            backup.created_at.unwrap().to_string(),
+            "2025-03-01 14:22:33"
+        );
+        assert!(Backup::from_path(PathBuf::from("/b/notes.txt")).is_none());
+
+        assert_eq!(
+            remote_name("20250301-142233-push.tar.zst.age"),
+            "20250301-142233-push"
+        );
+        assert_eq!(remote_name("20250301-142233.tar.zst"), "20250301-142233");
+        assert_eq!(remote_name("notes.txt"), "notes.txt");
+    }
+}
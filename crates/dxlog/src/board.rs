@@ -0,0 +1,67 @@
+use crate::research_log::LogStatus;
+use anyhow::Result;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    load_config, log_manager::LogManager, research_log::ResearchLog, DatasetLog, DatasetManager,
+    ExperimentLog, ExperimentManager, HypothesisLog, HypothesisManager, IdeaLog, IdeaManager,
+    JournalLog, JournalManager, KnowledgeLog, KnowledgeManager, ListFilter, LiteratureLog,
+    LiteratureManager, MeetingLog, MeetingManager,
+};
+
+/// An entry on the board.
+#[derive(Debug, Serialize)]
+pub struct BoardCard {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    pub title: String,
+}
+
+/// The entries of one log type with a given status.
+#[derive(Debug, Serialize)]
+pub struct BoardColumn {
+    pub status: String,
+    pub cards: Vec<BoardCard>,
+}
+
+/// Groups the entries of the log type `kind` by status, one column per status
+/// in the order the statuses are declared, including empty ones.
+pub fn board(kind: &str) -> Result<Vec<BoardColumn>> {
+    let config = load_config()?;
+    match kind {
+        HypothesisLog::KIND => columns(&HypothesisManager::new(config).manager),
+        LiteratureLog::KIND => columns(&LiteratureManager::new(config).manager),
+        KnowledgeLog::KIND => columns(&KnowledgeManager::new(config).manager),
+        JournalLog::KIND => columns(&JournalManager::new(config).manager),
+        DatasetLog::KIND => columns(&DatasetManager::new(config).manager),
+        MeetingLog::KIND => columns(&MeetingManager::new(config).manager),
+        IdeaLog::KIND => columns(&IdeaManager::new(config).manager),
+        ExperimentLog::KIND => columns(&ExperimentManager::new(config).manager),
+        _ => Err(anyhow::anyhow!(
+            "Unknown log type '{}'. Expected hypothesis, literature, knowledge, journal, dataset, meeting, idea or experiment",
+            kind
+        )),
+    }
+}
+
+fn columns<T: ResearchLog>(manager: &LogManager<T>) -> Result<Vec<BoardColumn>> {
+    let logs = manager.list_logs(&ListFilter::default())?;
+    Ok(T::Status::all(&manager.config)
+        .iter()
+        .map(|status| {
+            let status = status.to_string();
+            let cards = logs
+                .iter()
+                .filter(|log| log.status().to_string() == status)
+                .map(|log| BoardCard {
+                    id: log.base().id,
+                    handle: log.base().handle.clone(),
+                    title: log.base().title.clone(),
+                })
+                .collect();
+            BoardColumn { status, cards }
+        })
+        .collect())
+}
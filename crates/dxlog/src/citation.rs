@@ -0,0 +1,488 @@
+use anyhow::{Context, Result};
+use dxlog_tools::{parse_bibtex_entries, BibEntry};
+use serde::Serialize;
+use std::{collections::HashSet, path::Path};
+
+use crate::{dry_run, load_config, FieldChange, ListFilter, LiteratureLog, LiteratureManager};
+
+/// BibTeX field marking the entries `sync-bib` manages, holding the literature ID.
+const BIBTEX_ID_FIELD: &str = "dxlog";
+
+/// Words skipped when picking the title word of a citation key.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "on", "of", "in", "for", "and", "to", "with", "from", "towards", "toward",
+    "via", "is", "are", "at", "by",
+];
+
+/// A name in CSL JSON: split into family and given names, or kept whole.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum CslName {
+    Split { family: String, given: String },
+    Literal { literal: String },
+}
+
+#[derive(Debug, Serialize)]
+pub struct CslDate {
+    #[serde(rename = "date-parts")]
+    pub date_parts: Vec<Vec<i32>>,
+}
+
+/// A literature entry as a CSL JSON item, as read by Pandoc and Zotero.
+#[derive(Debug, Serialize)]
+pub struct CslItem {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub author: Vec<CslName>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issued: Option<CslDate>,
+    #[serde(rename = "container-title", skip_serializing_if = "Option::is_none")]
+    pub container_title: Option<String>,
+    #[serde(rename = "DOI", skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    #[serde(rename = "URL", skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Lowercase ASCII letters and digits of `text`, with accents dropped.
+fn key_part(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'à'..='å' | 'À'..='Å' => 'a',
+            'ç' | 'Ç' => 'c',
+            'è'..='ë' | 'È'..='Ë' => 'e',
+            'ì'..='ï' | 'Ì'..='Ï' => 'i',
+            'ñ' | 'Ñ' => 'n',
+            'ò'..='ö' | 'Ò'..='Ö' | 'ø' | 'Ø' => 'o',
+            'ù'..='ü' | 'Ù'..='Ü' => 'u',
+            'ý' | 'ÿ' | 'Ý' => 'y',
+            c => c,
+        })
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Family name of an author written as `Given Family` or `Family, Given`.
+fn family_name(author: &str) -> &str {
+    match author.split_once(',') {
+        Some((family, _)) => family.trim(),
+        None => author.split_whitespace().last().unwrap_or(author),
+    }
+}
+
+/// The citation key an entry would get if it were free: the first author's
+/// family name, the year and the first significant title word, e.g. `smith2024quantum`.
+fn base_citekey(literature: &LiteratureLog) -> String {
+    let source = &literature.source;
+    let author = source
+        .authors
+        .first()
+        .map(|author| key_part(family_name(author)))
+        .filter(|author| !author.is_empty())
+        .unwrap_or_else(|| "anon".to_string());
+    let year = source.year.map(|year| year.to_string()).unwrap_or_default();
+    let word = literature
+        .base
+        .title
+        .split_whitespace()
+        .map(key_part)
+        .find(|word| !word.is_empty() && !STOP_WORDS.contains(&word.as_str()))
+        .unwrap_or_default();
+    format!("{}{}{}", author, year, word)
+}
+
+/// A citation key for `literature` not in `taken`, which it is added to.
+/// Clashing keys get a letter suffix: `smith2024quantum`, `smith2024quantuma`, ...
+pub(crate) fn unique_citekey(literature: &LiteratureLog, taken: &mut HashSet<String>) -> String {
+    let base = base_citekey(literature);
+    let key = std::iter::once(base.clone())
+        .chain(('a'..='z').map(|suffix| format!("{}{}", base, suffix)))
+        .chain((2..).map(|n| format!("{}{}", base, n)))
+        .find(|key| !taken.contains(key))
+        .expect("the candidate keys are endless");
+    taken.insert(key.clone());
+    key
+}
+
+/// Every literature entry, giving entries created before citation keys existed
+/// a key and saving it, so keys stay stable once they have been used.
+fn keyed_literature(manager: &LiteratureManager) -> Result<Vec<LiteratureLog>> {
+    let entries = manager.manager.list_log_files(&ListFilter::default())?;
+    let mut taken: HashSet<String> = entries
+        .iter()
+        .filter_map(|(literature, _)| literature.citekey.clone())
+        .collect();
+
+    let mut keyed = Vec::new();
+    for (mut literature, path) in entries {
+        if literature.citekey.is_none() {
+            literature.citekey = Some(unique_citekey(&literature, &mut taken));
+            manager.manager.update_log(&mut literature, &path)?;
+        }
+        keyed.push(literature);
+    }
+    Ok(keyed)
+}
+
+/// A one-line reference to an entry for the body of another, e.g.
+/// `Jane Smith, Li Wei (2024). *Surface codes*. ICLR 2024. https://doi.org/... [@smith2024surface]`.
+pub(crate) fn citation(literature: &LiteratureLog) -> String {
+    let source = &literature.source;
+    let mut citation = source.authors.join(", ");
+    if let Some(year) = source.year {
+        citation.push_str(&format!(" ({})", year));
+    }
+    if !citation.is_empty() {
+        citation.push_str(". ");
+    }
+    citation.push_str(&format!("*{}*.", literature.base.title));
+    if let Some(venue) = &source.venue {
+        citation.push_str(&format!(" {}.", venue));
+    }
+    if let Some(url) = source.url() {
+        citation.push_str(&format!(" {}", url));
+    }
+    if let Some(key) = &literature.citekey {
+        citation.push_str(&format!(" [@{}]", key));
+    }
+    citation
+}
+
+fn csl_item(literature: LiteratureLog) -> CslItem {
+    let source = literature.source;
+    let author = source
+        .authors
+        .iter()
+        .map(|name| match name.split_once(',') {
+            Some((family, given)) => CslName::Split {
+                family: family.trim().to_string(),
+                given: given.trim().to_string(),
+            },
+            None => match name.trim().rsplit_once(' ') {
+                Some((given, family)) => CslName::Split {
+                    family: family.to_string(),
+                    given: given.trim().to_string(),
+                },
+                None => CslName::Literal {
+                    literal: name.trim().to_string(),
+                },
+            },
+        })
+        .collect();
+    let kind = if source.repository_url.is_some() && source.doi.is_none() {
+        "software"
+    } else if source.venue.is_some() {
+        "paper-conference"
+    } else {
+        "article"
+    };
+    CslItem {
+        url: source.url(),
+        id: literature.citekey.unwrap_or_default(),
+        kind,
+        title: literature.base.title,
+        author,
+        issued: source.year.map(|year| CslDate {
+            date_parts: vec![vec![year]],
+        }),
+        container_title: source.venue,
+        doi: source.doi,
+    }
+}
+
+/// All literature entries as CSL JSON items, by citation key.
+pub fn literature_csl() -> Result<Vec<CslItem>> {
+    let manager = LiteratureManager::new(load_config()?);
+    let mut items: Vec<CslItem> = keyed_literature(&manager)?
+        .into_iter()
+        .map(csl_item)
+        .collect();
+    items.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(items)
+}
+
+/// The citation keys of literature entries, in the order given.
+pub fn citekeys(partial_ids: &[String]) -> Result<Vec<String>> {
+    let manager = LiteratureManager::new(load_config()?);
+    let keyed = keyed_literature(&manager)?;
+    partial_ids
+        .iter()
+        .map(|partial_id| {
+            let (literature, _) = manager.find(partial_id)?;
+            keyed
+                .iter()
+                .find(|entry| entry.base.id == literature.base.id)
+                .and_then(|entry| entry.citekey.clone())
+                .ok_or_else(|| anyhow::anyhow!("'{}' has no citation key", literature.base.title))
+        })
+        .collect()
+}
+
+/// What [`sync_bibtex`] changed in a BibTeX file, by citation key.
+#[derive(Debug, Default, Serialize)]
+pub struct BibSync {
+    pub added: Vec<String>,
+    pub updated: Vec<(String, Vec<FieldChange>)>,
+    /// Entries written by an earlier sync whose literature entry is gone.
+    pub removed: Vec<String>,
+    /// Keys of literature entries left out because the file already has an entry
+    /// with that key which dxlog did not write.
+    pub conflicts: Vec<String>,
+    pub unchanged: usize,
+}
+
+fn escape_latex(text: &str) -> String {
+    text.chars().fold(String::new(), |mut escaped, c| {
+        if matches!(c, '&' | '%' | '$' | '#' | '_' | '{' | '}') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        escaped
+    })
+}
+
+/// Renders a literature entry as a BibTeX entry tagged with its ID.
+fn bibtex_entry(literature: &LiteratureLog) -> String {
+    let source = &literature.source;
+    let entry_type = if source.venue.is_some() {
+        "inproceedings"
+    } else if source.doi.is_some() {
+        "article"
+    } else {
+        "misc"
+    };
+    let mut fields = vec![(
+        "title",
+        format!("{{{}}}", escape_latex(&literature.base.title)),
+    )];
+    if !source.authors.is_empty() {
+        let authors: Vec<String> = source.authors.iter().map(|a| escape_latex(a)).collect();
+        fields.push(("author", authors.join(" and ")));
+    }
+    if let Some(year) = source.year {
+        fields.push(("year", year.to_string()));
+    }
+    if let Some(venue) = &source.venue {
+        fields.push(("booktitle", escape_latex(venue)));
+    }
+    if let Some(doi) = &source.doi {
+        fields.push(("doi", doi.clone()));
+    }
+    if let Some(url) = source.url() {
+        fields.push(("url", url));
+    }
+    fields.push((BIBTEX_ID_FIELD, literature.base.id.to_string()));
+
+    let mut entry = format!(
+        "@{}{{{},\n",
+        entry_type,
+        literature.citekey.as_deref().unwrap_or_default()
+    );
+    for (name, value) in fields {
+        entry.push_str(&format!("  {} = {{{}}},\n", name, value));
+    }
+    entry.push('}');
+    entry
+}
+
+/// Differences between two versions of a BibTeX entry, field by field.
+fn entry_changes(old: &BibEntry, new: &BibEntry) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    let mut change = |field: &str, old: Option<&str>, new: Option<&str>| {
+        if old != new {
+            changes.push(FieldChange {
+                field: field.to_string(),
+                old: old.map(str::to_string),
+                new: new.map(str::to_string),
+            });
+        }
+    };
+    change("type", Some(&old.entry_type), Some(&new.entry_type));
+    change("key", Some(&old.key), Some(&new.key));
+    let mut names: Vec<&str> = old.fields.iter().map(|(name, _)| name.as_str()).collect();
+    for (name, _) in &new.fields {
+        if !names.contains(&name.as_str()) {
+            names.push(name);
+        }
+    }
+    for name in names {
+        change(name, old.field(name), new.field(name));
+    }
+    changes
+}
+
+/// Brings the entries dxlog manages in `content` in line with `literature`:
+/// adds missing ones, rewrites changed ones and drops those whose literature
+/// entry is gone. Everything else in the file is left as it is.
+fn sync_bibtex_content(content: &str, literature: &[LiteratureLog]) -> Result<(String, BibSync)> {
+    let existing = parse_bibtex_entries(content)?;
+    let mut sync = BibSync::default();
+    let mut synced = String::new();
+    let mut copied = 0;
+    let mut written = HashSet::new();
+
+    for entry in &existing {
+        let Some(id) = entry.field(BIBTEX_ID_FIELD) else {
+            continue;
+        };
+        synced.push_str(&content[copied..entry.span.start]);
+        copied = entry.span.end;
+        match literature.iter().find(|l| l.base.id.to_string() == id) {
+            Some(literature) if written.insert(literature.base.id) => {
+                let rendered = bibtex_entry(literature);
+                let new = parse_bibtex_entries(&rendered)?.remove(0);
+                let changes = entry_changes(entry, &new);
+                if changes.is_empty() {
+                    synced.push_str(&content[entry.span.clone()]);
+                    sync.unchanged += 1;
+                } else {
+                    synced.push_str(&rendered);
+                    sync.updated.push((new.key, changes));
+                }
+            }
+            _ => {
+                // Drop the blank line that separated the entry from the next one.
+                let after = &content[copied..];
+                copied += after.len() - after.trim_start_matches(['\r', '\n']).len();
+                synced.truncate(synced.trim_end_matches([' ', '\t']).len());
+                sync.removed.push(entry.key.clone());
+            }
+        }
+    }
+    synced.push_str(&content[copied..]);
+    if !sync.removed.is_empty() {
+        synced.truncate(synced.trim_end().len());
+        synced.push('\n');
+    }
+
+    let taken: HashSet<&str> = existing
+        .iter()
+        .filter(|entry| entry.field(BIBTEX_ID_FIELD).is_none())
+        .map(|entry| entry.key.as_str())
+        .collect();
+    for literature in literature {
+        if written.contains(&literature.base.id) {
+            continue;
+        }
+        let key = literature.citekey.clone().unwrap_or_default();
+        if taken.contains(key.as_str()) {
+            sync.conflicts.push(key);
+            continue;
+        }
+        let trimmed = synced.trim_end().len();
+        synced.truncate(trimmed);
+        if !synced.is_empty() {
+            synced.push_str("\n\n");
+        }
+        synced.push_str(&bibtex_entry(literature));
+        synced.push('\n');
+        sync.added.push(key);
+    }
+
+    Ok((synced, sync))
+}
+
+/// Keeps the BibTeX file at `path` up to date with the literature collection.
+/// Entries dxlog writes carry a `dxlog` field with the literature ID; entries
+/// without it are never changed or removed. Creates the file if needed.
+pub fn sync_bibtex(path: &Path) -> Result<BibSync> {
+    let manager = LiteratureManager::new(load_config()?);
+    let mut literature = keyed_literature(&manager)?;
+    literature.sort_by(|a, b| a.citekey.cmp(&b.citekey));
+
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let (synced, sync) = sync_bibtex_content(&content, &literature)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    if synced != content {
+        dry_run::write(path, &synced)?;
+    }
+    Ok(sync)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{research_log::ResearchLog, test_support::author};
+
+    fn literature(title: &str, authors: &[&str], year: Option<i32>) -> LiteratureLog {
+        let mut log = LiteratureLog::new(title.to_string(), HashSet::new(), author());
+        log.source.authors = authors.iter().map(|a| a.to_string()).collect();
+        log.source.year = year;
+        log
+    }
+
+    #[test]
+    fn test_citation() {
+        let mut paper = literature("Surface codes", &["Jane Smith", "Li Wei"], Some(2024));
+        paper.source.venue = Some("ICLR 2024".to_string());
+        paper.source.doi = Some("10.1234/x".to_string());
+        paper.citekey = Some("smith2024surface".to_string());
+        assert_eq!(
+            citation(&paper),
+            "Jane Smith, Li Wei (2024). *Surface codes*. ICLR 2024. https://doi.org/10.1234/x [@smith2024surface]"
+        );
+        assert_eq!(citation(&literature("Notes", &[], None)), "*Notes*.");
+    }
+
+    #[test]
+    fn test_citekeys() {
+        let paper = literature(
+            "The Quantum Advantage of Surface Codes",
+            &["Jane Smith", "Li Wei"],
+            Some(2024),
+        );
+        assert_eq!(base_citekey(&paper), "smith2024quantum");
+        assert_eq!(
+            base_citekey(&literature("On Élan", &["Gödel, Kurt"], None)),
+            "godelelan"
+        );
+        assert_eq!(base_citekey(&literature("Notes", &[], None)), "anonnotes");
+
+        let mut taken = HashSet::new();
+        assert_eq!(unique_citekey(&paper, &mut taken), "smith2024quantum");
+        assert_eq!(unique_citekey(&paper, &mut taken), "smith2024quantuma");
+        assert_eq!(unique_citekey(&paper, &mut taken), "smith2024quantumb");
+    }
+
+    #[test]
+    fn test_sync_bibtex_content() {
+        let mut paper = literature("Surface Codes & Noise", &["Jane Smith"], Some(2024));
+        paper.citekey = Some("smith2024surface".to_string());
+        let mut gone = literature("Old", &[], None);
+        gone.citekey = Some("anonold".to_string());
+        let mut clash = literature("Mine", &[], None);
+        clash.citekey = Some("own".to_string());
+
+        let content = format!(
+            "@misc{{own, note = {{kept}}}}\n\n{}\n\n{}\n",
+            bibtex_entry(&gone),
+            bibtex_entry(&paper).replace("year = {2024}", "year = {2023}")
+        );
+        let literature = [paper, clash];
+        let (synced, sync) = sync_bibtex_content(&content, &literature).unwrap();
+        assert_eq!(
+            synced,
+            format!(
+                "@misc{{own, note = {{kept}}}}\n\n{}\n",
+                bibtex_entry(&literature[0])
+            )
+        );
+        assert!(synced.contains("title = {{Surface Codes \\& Noise}}"));
+        assert_eq!(sync.removed, vec!["anonold"]);
+        assert_eq!(sync.conflicts, vec!["own"]);
+        assert_eq!(sync.updated.len(), 1);
+        assert_eq!(sync.updated[0].1[0].field, "year");
+
+        let (resynced, sync) = sync_bibtex_content(&synced, &literature).unwrap();
+        assert_eq!(resynced, synced);
+        assert_eq!(sync.unchanged, 1);
+    }
+}
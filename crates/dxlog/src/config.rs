@@ -1,56 +1,1601 @@
-use anyhow::Result;
+use crate::{
+    log_type::LogTypeRegistry,
+    research_log::LogStatus,
+    utils::{self, Author},
+    DatasetStatus, ExperimentStatus, HypothesisStatus, IdeaStatus, JournalStatus, KnowledgeStatus,
+    LiteratureStatus, MeetingStatus,
+};
+use anyhow::{Context, Result};
+use dxlog_tools::ChatService;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use tracing::debug;
+use uuid::Uuid;
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+    sync::RwLock,
+};
+
+/// Name of the file marking the root of a dxlog repository.
+pub const CONFIG_FILE_NAME: &str = "dxlog.toml";
+
+/// Config file name used by earlier versions, still recognised when discovering the root.
+const LEGACY_CONFIG_FILE_NAME: &str = ".rlog.toml";
+
+/// The log types config sections can name, for error messages.
+pub(crate) const EXPECTED_TYPES: &str =
+    "hypothesis, literature, knowledge, journal, dataset, meeting, idea, experiment or one from [types]";
+
+/// Environment variable pointing at the repository to use, overriding discovery.
+pub const REPO_ENV_VAR: &str = "DXLOG_REPO";
+
+/// Settings that hold for a whole run rather than for a repository, given to
+/// the CLI as `--repo`, `--dry-run`, `--strict` and `--offline`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    /// Repository to use instead of the one around the working directory.
+    pub repo: Option<PathBuf>,
+    /// Only plan file changes; see [`take_planned_changes`](crate::take_planned_changes).
+    pub dry_run: bool,
+    /// Fail on entry files that cannot be parsed instead of skipping them.
+    pub strict: bool,
+    /// Never access the network; only cached responses are used.
+    pub offline: bool,
+}
+
+static RUN_OPTIONS: RwLock<Option<RunOptions>> = RwLock::new(None);
+
+/// Whether the environment variable `name` is set to anything but
+/// `0`/`false`/`no`/`off`.
+fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .map(|v| {
+            !matches!(
+                v.trim().to_lowercase().as_str(),
+                "" | "0" | "false" | "no" | "off"
+            )
+        })
+        .unwrap_or(false)
+}
+
+impl RunOptions {
+    /// The options given by [`REPO_ENV_VAR`], [`DRY_RUN_ENV_VAR`](crate::DRY_RUN_ENV_VAR),
+    /// [`STRICT_ENV_VAR`](crate::STRICT_ENV_VAR) and
+    /// [`OFFLINE_ENV_VAR`](crate::OFFLINE_ENV_VAR).
+    pub fn from_env() -> Self {
+        Self {
+            repo: std::env::var_os(REPO_ENV_VAR).map(PathBuf::from),
+            dry_run: env_flag(crate::DRY_RUN_ENV_VAR),
+            strict: env_flag(crate::STRICT_ENV_VAR),
+            offline: env_flag(crate::OFFLINE_ENV_VAR),
+        }
+    }
+
+    /// Makes these the options of the rest of the run, in place of the
+    /// environment variables.
+    pub fn apply(self) {
+        dxlog_tools::set_offline(self.offline);
+        *RUN_OPTIONS.write().unwrap_or_else(|e| e.into_inner()) = Some(self);
+    }
+}
+
+/// The options set with [`RunOptions::apply`], or else those of the environment.
+pub(crate) fn run_options() -> RunOptions {
+    RUN_OPTIONS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(RunOptions::from_env)
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case", default)]
 pub struct Config {
+    /// Repository root all relative paths are resolved against.
+    #[serde(skip)]
+    pub root: PathBuf,
+    // The snake_case aliases keep `.rlog.toml` files of earlier versions working
+    #[serde(alias = "date_format")]
     pub date_format: String,
     pub templates: TemplateConfig,
     pub storage: StorageConfig,
+    #[serde(alias = "stale_days")]
     pub stale_days: u64,
+    pub id_version: IdVersion,
+    #[serde(skip_serializing_if = "StatusesConfig::is_empty")]
+    pub statuses: StatusesConfig,
+    /// Log types of your own, keyed by name, e.g. `[types.protocol]`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub types: BTreeMap<String, LogTypeConfig>,
+    #[serde(skip_serializing_if = "WorkflowConfig::is_empty")]
+    pub workflow: WorkflowConfig,
+    /// Research threads entries can be grouped into, keyed by name, e.g.
+    /// `[projects.optics] description = "Cavity experiments"`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub projects: BTreeMap<String, ProjectConfig>,
+    /// People entries can be assigned to, keyed by handle, e.g.
+    /// `[team.ada] name = "Ada Lovelace" email = "ada@example.org"`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub team: BTreeMap<String, Author>,
+    #[serde(skip_serializing_if = "AuthorConfig::is_empty")]
+    pub author: AuthorConfig,
+    #[serde(skip_serializing_if = "NotificationConfig::is_empty")]
+    pub notifications: NotificationConfig,
+    #[serde(skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+    #[serde(skip_serializing_if = "EncryptionConfig::is_empty")]
+    pub encryption: EncryptionConfig,
+    #[serde(skip_serializing_if = "ExportConfig::is_empty")]
+    pub export: ExportConfig,
+    #[serde(skip_serializing_if = "BackupConfig::is_empty")]
+    pub backup: BackupConfig,
+    #[serde(skip_serializing_if = "LlmConfig::is_empty")]
+    pub llm: LlmConfig,
+    /// Commands of your own, expanded by the CLI, e.g.
+    /// `[aliases] standup = "report --since 1d"`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    pub cli: CliConfig,
+}
+
+/// Chat channels told about status changes.
+///
+/// ```toml
+/// [[notifications.webhooks]]
+/// url = "https://hooks.slack.com/services/T000/B000/XXXX"
+/// events = ["hypothesis:proven", "knowledge:published"]
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct NotificationConfig {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<Webhook>,
+}
+
+impl NotificationConfig {
+    pub fn is_empty(&self) -> bool {
+        self.webhooks.is_empty()
+    }
+}
+
+/// A Slack or Discord incoming webhook.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Webhook {
+    pub url: String,
+    /// `slack` (the default) or `discord`.
+    #[serde(default)]
+    pub service: ChatService,
+    /// Status changes to post, as `<type>:<status>` (`<type>:*` for any status
+    /// of a type). Every status change is posted when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+}
+
+impl Webhook {
+    /// Whether an entry of `kind` moving to `status` should be posted.
+    pub fn wants(&self, kind: &str, status: &str) -> bool {
+        self.events.is_empty()
+            || self.events.iter().any(|event| match event.split_once(':') {
+                Some((k, s)) => k == kind && (s == "*" || s == status),
+                None => false,
+            })
+    }
+}
+
+/// Shell commands run after an entry is created, changes status or is deleted.
+///
+/// ```toml
+/// [hooks]
+/// on_status_change = "./scripts/on-status-change.sh"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct HooksConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_create: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_status_change: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_delete: Option<String>,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        self.on_create.is_none() && self.on_status_change.is_none() && self.on_delete.is_none()
+    }
+}
+
+/// Who new entries are created by. What is left out comes from git config:
+/// `user.name`, `user.email`, `dxlog.orcid` and `dxlog.affiliation`.
+///
+/// ```toml
+/// [author]
+/// orcid = "0000-0002-1825-0097"
+/// affiliation = "Institute for Quantum Computing"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AuthorConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// ORCID iD, bare or as an `https://orcid.org/` URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orcid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub affiliation: Option<String>,
+}
+
+impl AuthorConfig {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.email.is_none()
+            && self.orcid.is_none()
+            && self.affiliation.is_none()
+    }
+}
+
+/// Keys the bodies of entries encrypted with `dxlog encrypt` are encrypted to.
+/// Everyone listed can read them with their own age identity.
+///
+/// ```toml
+/// [encryption]
+/// recipients = ["age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p"]
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct EncryptionConfig {
+    /// age public keys (`age1...`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recipients: Vec<String>,
+}
+
+impl EncryptionConfig {
+    pub fn is_empty(&self) -> bool {
+        self.recipients.is_empty()
+    }
+}
+
+/// Where `dxlog backup push` uploads backups and `dxlog backup pull` fetches
+/// them from, for repositories that cannot be pushed to a git host.
+///
+/// ```toml
+/// [backup]
+/// remote = "s3://lab-backups/dxlog"
+/// region = "eu-central-1"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// `s3://bucket/prefix`, or the `https://` URL of a WebDAV folder.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    /// Region of the S3 bucket, `us-east-1` if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// Base URL of an S3-compatible service such as MinIO, used instead of AWS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Whether backups are encrypted to the `[encryption]` recipients before
+    /// they are uploaded; true if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypt: Option<bool>,
+}
+
+impl BackupConfig {
+    pub fn is_empty(&self) -> bool {
+        self.remote.is_none()
+            && self.region.is_none()
+            && self.endpoint.is_none()
+            && self.encrypt.is_none()
+    }
+
+    pub fn encrypt(&self) -> bool {
+        self.encrypt.unwrap_or(true)
+    }
+}
+
+/// Settings of `dxlog export`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ExportConfig {
+    #[serde(skip_serializing_if = "RedactConfig::is_empty")]
+    pub redact: RedactConfig,
+}
+
+impl ExportConfig {
+    pub fn is_empty(&self) -> bool {
+        self.redact.is_empty()
+    }
+}
+
+/// What `dxlog export site`, `export mdbook` and `export entry` leave out of shared exports.
+///
+/// ```toml
+/// [export.redact]
+/// tags = ["internal"]
+/// fields = ["author", "references"]
+/// emails = true
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct RedactConfig {
+    /// Tags stripped from exported entries, with the tags nested under them.
+    /// Entries carrying one are left out of `dxlog export entry` bibliographies.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<RedactField>,
+    /// Replace email addresses in entry bodies.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub emails: bool,
+}
+
+impl RedactConfig {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.fields.is_empty() && !self.emails
+    }
+
+    pub fn redacts(&self, field: RedactField) -> bool {
+        self.fields.contains(&field)
+    }
+
+    /// Whether `tags` include a redacted tag or one nested under it.
+    pub fn hides(&self, tags: &HashSet<String>) -> bool {
+        self.tags.iter().any(|tag| utils::has_tag(tags, tag))
+    }
+}
+
+/// A frontmatter field that can be left out of exports.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RedactField {
+    Author,
+    Status,
+    Date,
+    Tags,
+    /// Source links such as arXiv, DOI and dataset URLs.
+    Sources,
+    /// Links to other entries and the bibliography.
+    References,
+}
+
+/// The OpenAI-compatible API behind `dxlog literature summarize`, `dxlog
+/// suggest-tags` and `dxlog search --semantic`, available when dxlog is built
+/// with the `llm` feature.
+///
+/// ```toml
+/// [llm]
+/// endpoint = "http://localhost:11434/v1"
+/// model = "llama3.1"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LlmConfig {
+    /// Base URL of the API, [`LlmConfig::DEFAULT_ENDPOINT`] if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Model `dxlog search --semantic` embeds entries with. Changing it
+    /// embeds every entry again.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+    /// Environment variable holding the API key, so the key stays out of this
+    /// file. [`LlmConfig::DEFAULT_API_KEY_ENV`] if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+}
+
+impl LlmConfig {
+    pub const DEFAULT_ENDPOINT: &'static str = "https://api.openai.com/v1";
+    pub const DEFAULT_MODEL: &'static str = "gpt-4o-mini";
+    pub const DEFAULT_EMBEDDING_MODEL: &'static str = "text-embedding-3-small";
+    pub const DEFAULT_API_KEY_ENV: &'static str = "OPENAI_API_KEY";
+
+    pub fn is_empty(&self) -> bool {
+        self.endpoint.is_none()
+            && self.model.is_none()
+            && self.embedding_model.is_none()
+            && self.api_key_env.is_none()
+    }
+
+    pub fn endpoint(&self) -> &str {
+        self.endpoint.as_deref().unwrap_or(Self::DEFAULT_ENDPOINT)
+    }
+
+    pub fn model(&self) -> &str {
+        self.model.as_deref().unwrap_or(Self::DEFAULT_MODEL)
+    }
+
+    pub fn embedding_model(&self) -> &str {
+        self.embedding_model
+            .as_deref()
+            .unwrap_or(Self::DEFAULT_EMBEDDING_MODEL)
+    }
+
+    /// The API key from the environment variable `api_key_env` names, if set.
+    pub fn api_key(&self) -> Option<String> {
+        let name = self
+            .api_key_env
+            .as_deref()
+            .unwrap_or(Self::DEFAULT_API_KEY_ENV);
+        std::env::var(name).ok().filter(|key| !key.is_empty())
+    }
+}
+
+/// A project entries can be assigned to.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ProjectConfig {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+/// Settings of the command line interface.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct CliConfig {
+    pub confirm: ConfirmMode,
+}
+
+/// Which commands ask for confirmation before they run. `--yes` answers for the user.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfirmMode {
+    /// Every command that moves, renames or deletes an existing entry.
+    Always,
+    /// Deleting entries, archiving them and emptying the trash.
+    #[default]
+    Destructive,
+    Never,
+}
+
+/// UUID version of the IDs given to new entries.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdVersion {
+    /// Random IDs, as dxlog wrote before UUIDv7.
+    V4,
+    /// IDs starting with their creation time, so they sort in the order entries were created.
+    #[default]
+    V7,
+}
+
+impl IdVersion {
+    /// A new ID of this version.
+    pub fn new_id(self) -> Uuid {
+        match self {
+            IdVersion::V4 => Uuid::new_v4(),
+            IdVersion::V7 => Uuid::now_v7(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case", default)]
 pub struct StorageConfig {
+    #[serde(alias = "active_dir")]
     pub active_dir: PathBuf,
+    #[serde(alias = "archive_dir")]
     pub archive_dir: PathBuf,
+    #[serde(alias = "knowledge_base_dir")]
     pub knowledge_base_dir: PathBuf,
+    pub journal_dir: PathBuf,
+    pub datasets_dir: PathBuf,
+    pub meetings_dir: PathBuf,
+    /// Ideas captured with `dxlog idea`, with the archived ones in `archived/` below it.
+    pub inbox_dir: PathBuf,
+    pub experiments_dir: PathBuf,
+    /// Files attached to logs, such as notebooks, one directory per log.
+    pub attachments_dir: PathBuf,
+    pub trash_dir: PathBuf,
+    /// File the milestones created with `dxlog milestone new` are kept in.
+    pub milestones_file: PathBuf,
+    /// Directory overrides per log type and status, relative to the repository
+    /// root. `{kind}` and `{status}` are substituted, e.g.
+    /// `[storage.layout.hypothesis] proven = "results/{status}"`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub layout: BTreeMap<String, BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case", default)]
 pub struct TemplateConfig {
     pub hypothesis: PathBuf,
     pub literature: PathBuf,
     pub knowledge: PathBuf,
+    pub journal: PathBuf,
+    pub dataset: PathBuf,
+    pub meeting: PathBuf,
+    pub experiment: PathBuf,
+    pub report: PathBuf,
+    /// Additional templates per log type, keyed by name, e.g.
+    /// `[templates.named.hypothesis.benchmark] path = "templates/benchmark.jinja"`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub named: BTreeMap<String, BTreeMap<String, NamedTemplate>>,
+    /// Values passed to every template as `vars`, e.g. `[templates.vars] lab = "Optics"`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, toml::Value>,
+}
+
+/// A template chosen with `--template <name>`, or for entries carrying one of `tags`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NamedTemplate {
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// A log type declared in the config, used through `dxlog entry <type>`.
+///
+/// ```toml
+/// [types.protocol]
+/// statuses = ["draft", "validated", "retired"]
+/// handle-prefix = "P"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct LogTypeConfig {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// Statuses in workflow order; new entries start in the first one.
+    pub statuses: Vec<String>,
+    /// Prefix of the entries' handles. Defaults to the first letter of the
+    /// type's name, upper-cased.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle_prefix: Option<String>,
+    /// Directory entries are kept in, relative to the repository root.
+    /// Defaults to the type's name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+    /// Template for new entries; without one they get a title and the body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<PathBuf>,
+}
+
+/// Statuses of the log types that can have their own, on top of or instead of
+/// the built-in ones.
+///
+/// ```toml
+/// [statuses.hypothesis]
+/// add = ["needs-replication"]
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct StatusesConfig {
+    #[serde(skip_serializing_if = "StatusSet::is_empty")]
+    pub hypothesis: StatusSet,
+}
+
+impl StatusesConfig {
+    pub fn is_empty(&self) -> bool {
+        self.hypothesis.is_empty()
+    }
+}
+
+/// Changes to the built-in statuses of a log type.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct StatusSet {
+    /// Statuses used instead of the built-in ones, in workflow order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub replace: Vec<String>,
+    /// Statuses added after the built-in (or replacing) ones.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub add: Vec<String>,
+}
+
+impl StatusSet {
+    pub fn is_empty(&self) -> bool {
+        self.replace.is_empty() && self.add.is_empty()
+    }
+
+    /// The statuses in effect, given the `built_in` ones. Names that don't
+    /// parse are left out; loading the config rejects them.
+    pub fn resolve<S: FromStr + PartialEq + Clone>(&self, built_in: &[S]) -> Vec<S> {
+        let mut statuses = if self.replace.is_empty() {
+            built_in.to_vec()
+        } else {
+            self.replace.iter().filter_map(|s| s.parse().ok()).collect()
+        };
+        for status in self.add.iter().filter_map(|s| s.parse().ok()) {
+            if !statuses.contains(&status) {
+                statuses.push(status);
+            }
+        }
+        statuses
+    }
+}
+
+/// Allowed status transitions per log type, keyed by the current status.
+///
+/// A status without an entry may move to any other status, so an empty section
+/// leaves the workflow unrestricted.
+///
+/// ```toml
+/// [workflow.hypothesis]
+/// active = ["suspended", "abandoned", "inconclusive"]
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct WorkflowConfig {
+    /// Only publish knowledge entries whose review has been approved.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub require_review: bool,
+    /// Only abandon or disprove entries with a reason (`--reason`).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub require_reason: bool,
+    /// Transitions keyed by log type, built in or from `[types]`.
+    #[serde(flatten)]
+    pub transitions: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+impl WorkflowConfig {
+    pub fn is_empty(&self) -> bool {
+        !self.require_review && !self.require_reason && self.transitions.is_empty()
+    }
+
+    /// Whether moving an entry to `status` needs a reason: with
+    /// `require_reason`, abandoning or disproving an entry does.
+    pub fn requires_reason(&self, status: &str) -> bool {
+        self.require_reason && matches!(status, "abandoned" | "disproven")
+    }
+
+    /// Declared transitions for a log type, if any.
+    pub fn transitions(&self, kind: &str) -> Option<&BTreeMap<String, Vec<String>>> {
+        self.transitions
+            .get(kind)
+            .filter(|transitions| !transitions.is_empty())
+    }
+
+    /// Checks moving the `kind` entry `title` from the status `from` to `to`
+    /// against the declared transitions. `known` are the statuses of the type.
+    pub fn check_transition(
+        &self,
+        kind: &str,
+        known: &[String],
+        title: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<()> {
+        let Some(transitions) = self.transitions(kind) else {
+            return Ok(());
+        };
+        check_statuses(transitions, kind, known)?;
+
+        match transitions.get(from) {
+            Some(allowed) if from != to && !allowed.iter().any(|s| s == to) => Err(anyhow::anyhow!(
+                "The workflow does not allow moving {} '{}' from {} to {} (allowed: {}). Use --force to override.",
+                kind,
+                title,
+                from,
+                to,
+                if allowed.is_empty() {
+                    "none".to_string()
+                } else {
+                    allowed.join(", ")
+                }
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Fails if `[workflow.<kind>]` names a status the type doesn't have.
+fn check_statuses(
+    transitions: &BTreeMap<String, Vec<String>>,
+    kind: &str,
+    known: &[String],
+) -> Result<()> {
+    for (from, targets) in transitions {
+        if let Some(unknown) = std::iter::once(from)
+            .chain(targets)
+            .find(|s| !known.contains(s))
+        {
+            return Err(anyhow::anyhow!(
+                "Unknown {} status '{}' in [workflow.{}]. Valid statuses: {}",
+                kind,
+                unknown,
+                kind,
+                known.join(", ")
+            ));
+        }
+    }
+    Ok(())
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            root: PathBuf::new(),
             date_format: "%Y-%m-%d".to_string(),
-            templates: TemplateConfig {
-                hypothesis: "templates/hypothesis.jinja".into(),
-                literature: "templates/literature.jinja".into(),
-                knowledge: "templates/knowledge.jinja".into(),
-            },
-            storage: StorageConfig {
-                active_dir: "research-logs".into(),
-                archive_dir: "archived".into(),
-                knowledge_base_dir: "knowledge-base".into(),
-            },
+            templates: TemplateConfig::default(),
+            storage: StorageConfig::default(),
             stale_days: 14,
+            id_version: IdVersion::default(),
+            statuses: StatusesConfig::default(),
+            types: BTreeMap::new(),
+            workflow: WorkflowConfig::default(),
+            projects: BTreeMap::new(),
+            team: BTreeMap::new(),
+            author: AuthorConfig::default(),
+            notifications: NotificationConfig::default(),
+            hooks: HooksConfig::default(),
+            encryption: EncryptionConfig::default(),
+            export: ExportConfig::default(),
+            backup: BackupConfig::default(),
+            llm: LlmConfig::default(),
+            aliases: BTreeMap::new(),
+            cli: CliConfig::default(),
         }
     }
 }
 
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            active_dir: "research-logs".into(),
+            archive_dir: "archived".into(),
+            knowledge_base_dir: "knowledge-base".into(),
+            journal_dir: "journal".into(),
+            datasets_dir: "datasets".into(),
+            meetings_dir: "meetings".into(),
+            inbox_dir: "inbox".into(),
+            experiments_dir: "experiments".into(),
+            attachments_dir: "attachments".into(),
+            trash_dir: "trash".into(),
+            milestones_file: "milestones.yaml".into(),
+            layout: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for TemplateConfig {
+    fn default() -> Self {
+        Self {
+            hypothesis: "templates/hypothesis.jinja".into(),
+            literature: "templates/literature.jinja".into(),
+            knowledge: "templates/knowledge.jinja".into(),
+            journal: "templates/journal.jinja".into(),
+            dataset: "templates/dataset.jinja".into(),
+            meeting: "templates/meeting.jinja".into(),
+            experiment: "templates/experiment.jinja".into(),
+            report: "templates/report.jinja".into(),
+            named: BTreeMap::new(),
+            vars: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config file of the repository rooted at `root`.
+    ///
+    /// Relative template and storage paths are resolved against `root`.
+    pub fn load(root: &Path) -> Result<Self> {
+        let (config_path, content) = read_config_file(root)?;
+        let mut config: Config = toml::from_str(&content)
+            .with_context(|| format!("Invalid config file: {}", config_path.display()))?;
+
+        debug!(path = %config_path.display(), "Loaded config");
+        config.root = root.to_path_buf();
+        config.resolve_paths();
+        config
+            .validate_statuses()
+            .and_then(|_| config.validate_types())
+            .and_then(|_| config.validate_layout())
+            .and_then(|_| config.validate_workflow())
+            .and_then(|_| config.validate_templates())
+            .and_then(|_| config.validate_notifications())
+            .and_then(|_| config.validate_encryption())
+            .and_then(|_| config.validate_authors())
+            .and_then(|_| config.validate_aliases())
+            .with_context(|| format!("Invalid config file: {}", config_path.display()))?;
+        Ok(config)
+    }
+
+    /// Directory `[storage.layout]` assigns to entries of `kind` in `status`, if any.
+    pub fn layout_dir(&self, kind: &str, status: &str) -> Option<PathBuf> {
+        let template = self.storage.layout.get(kind)?.get(status)?;
+        let dir = template.replace("{kind}", kind).replace("{status}", status);
+        Some(self.root.join(dir))
+    }
+
+    /// Every directory `[storage.layout]` can place entries of `kind` in.
+    pub fn layout_dirs(&self, kind: &str) -> Vec<PathBuf> {
+        self.storage
+            .layout
+            .get(kind)
+            .map(|statuses| {
+                statuses
+                    .keys()
+                    .filter_map(|status| self.layout_dir(kind, status))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Directory entries of the `[types]` entry `name` are kept in.
+    pub fn type_dir(&self, name: &str) -> PathBuf {
+        let dir = self.types.get(name).and_then(|t| t.dir.as_deref());
+        self.root.join(dir.unwrap_or(Path::new(name)))
+    }
+
+    /// Default template of a log type (`hypothesis`, `literature`, `knowledge`, `journal`,
+    /// `dataset`, `meeting`, `experiment` or `report`).
+    pub fn default_template(&self, kind: &str) -> Option<&Path> {
+        match kind {
+            "hypothesis" => Some(&self.templates.hypothesis),
+            "literature" => Some(&self.templates.literature),
+            "knowledge" => Some(&self.templates.knowledge),
+            "journal" => Some(&self.templates.journal),
+            "dataset" => Some(&self.templates.dataset),
+            "meeting" => Some(&self.templates.meeting),
+            "experiment" => Some(&self.templates.experiment),
+            "report" => Some(&self.templates.report),
+            _ => None,
+        }
+    }
+
+    /// Picks the template for a new entry of `kind`.
+    ///
+    /// An explicit `name` wins (`default` is the `[templates]` entry); otherwise the
+    /// first named template sharing a tag with the entry is used, then the default.
+    pub fn template_for(
+        &self,
+        kind: &str,
+        name: Option<&str>,
+        tags: &HashSet<String>,
+    ) -> Result<PathBuf> {
+        let default = self
+            .default_template(kind)
+            .with_context(|| format!("Unknown log type '{}'", kind))?;
+        let named = self.templates.named.get(kind);
+
+        match name {
+            Some("default") => Ok(default.to_path_buf()),
+            Some(name) => named
+                .and_then(|templates| templates.get(name))
+                .map(|template| template.path.clone())
+                .with_context(|| {
+                    let mut available = vec!["default".to_string()];
+                    available.extend(named.into_iter().flat_map(|t| t.keys().cloned()));
+                    format!(
+                        "Unknown {} template '{}'. Available: {}",
+                        kind,
+                        name,
+                        available.join(", ")
+                    )
+                }),
+            None => Ok(named
+                .and_then(|templates| {
+                    templates
+                        .values()
+                        .find(|template| template.tags.iter().any(|tag| tags.contains(tag)))
+                })
+                .map_or_else(|| default.to_path_buf(), |template| template.path.clone())),
+        }
+    }
+
+    fn validate_templates(&self) -> Result<()> {
+        for (kind, templates) in &self.templates.named {
+            if !matches!(
+                kind.as_str(),
+                "hypothesis"
+                    | "literature"
+                    | "knowledge"
+                    | "journal"
+                    | "dataset"
+                    | "meeting"
+                    | "experiment"
+            ) {
+                return Err(anyhow::anyhow!(
+                    "Unknown log type '{}' in [templates.named]. Expected hypothesis, literature, knowledge, journal, dataset, meeting or experiment",
+                    kind
+                ));
+            }
+            if templates.contains_key("default") {
+                return Err(anyhow::anyhow!(
+                    "[templates.named.{}] can't define 'default', it is the [templates] entry",
+                    kind
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_statuses(&self) -> Result<()> {
+        let set = &self.statuses.hypothesis;
+        // Hypotheses share directories with knowledge drafts and are told
+        // apart from other entries by their status.
+        let others: Vec<String> = [
+            status_names::<LiteratureStatus>(self),
+            status_names::<KnowledgeStatus>(self),
+            status_names::<JournalStatus>(self),
+            status_names::<DatasetStatus>(self),
+            status_names::<MeetingStatus>(self),
+            status_names::<IdeaStatus>(self),
+            status_names::<ExperimentStatus>(self),
+        ]
+        .concat();
+        for name in set.replace.iter().chain(&set.add) {
+            let status = HypothesisStatus::from_str(name)?;
+            if status.as_str() != name {
+                return Err(anyhow::anyhow!(
+                    "Invalid hypothesis status '{}' in [statuses.hypothesis], write it as '{}'",
+                    name,
+                    status
+                ));
+            }
+            if others.contains(name) {
+                return Err(anyhow::anyhow!(
+                    "Hypothesis status '{}' in [statuses.hypothesis] is already a status of another log type",
+                    name
+                ));
+            }
+        }
+        if !HypothesisStatus::all(self).contains(&HypothesisStatus::ACTIVE) {
+            return Err(anyhow::anyhow!(
+                "[statuses.hypothesis] must keep 'active', the status new hypotheses start in"
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_types(&self) -> Result<()> {
+        let mut prefixes: BTreeMap<String, &str> = LogTypeRegistry::BUILT_IN
+            .iter()
+            .map(|(name, prefix)| (prefix.to_string(), *name))
+            .collect();
+        for (name, log_type) in &self.types {
+            if LogTypeRegistry::BUILT_IN
+                .iter()
+                .any(|(built_in, _)| built_in == name)
+            {
+                return Err(anyhow::anyhow!(
+                    "[types.{}] can't redefine a built-in log type",
+                    name
+                ));
+            }
+            if !is_type_name(name) {
+                return Err(anyhow::anyhow!(
+                    "Invalid log type name '{}' in [types], use lowercase letters, digits and dashes",
+                    name
+                ));
+            }
+            if log_type.statuses.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "[types.{}] needs at least one status",
+                    name
+                ));
+            }
+            for (i, status) in log_type.statuses.iter().enumerate() {
+                if !is_type_name(status) || log_type.statuses[..i].contains(status) {
+                    return Err(anyhow::anyhow!(
+                        "Invalid status '{}' in [types.{}], use distinct lowercase names with dashes",
+                        status,
+                        name
+                    ));
+                }
+            }
+            let prefix = log_type_prefix(name, log_type);
+            if prefix.is_empty() || !prefix.bytes().all(|b| b.is_ascii_uppercase()) {
+                return Err(anyhow::anyhow!(
+                    "Invalid handle-prefix '{}' in [types.{}], use upper-case letters",
+                    prefix,
+                    name
+                ));
+            }
+            if let Some(other) = prefixes.insert(prefix.clone(), name) {
+                return Err(anyhow::anyhow!(
+                    "[types.{}] uses the handle prefix '{}' of {}; set a different handle-prefix",
+                    name,
+                    prefix,
+                    other
+                ));
+            }
+            if let Some(dir) = &log_type.dir {
+                ensure_inside_repo(&format!("types.{}.dir", name), &dir.to_string_lossy())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_layout(&self) -> Result<()> {
+        for (kind, statuses) in &self.storage.layout {
+            let known = self.statuses_of(kind).with_context(|| {
+                format!(
+                    "Unknown log type '{}' in [storage.layout]. Expected {}",
+                    kind, EXPECTED_TYPES
+                )
+            })?;
+            for (status, dir) in statuses {
+                if !known.contains(status) {
+                    return Err(anyhow::anyhow!(
+                        "Unknown {} status '{}' in [storage.layout.{}]. Valid statuses: {}",
+                        kind,
+                        status,
+                        kind,
+                        known.join(", ")
+                    ));
+                }
+                ensure_inside_repo(&format!("storage.layout.{}.{}", kind, status), dir)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_workflow(&self) -> Result<()> {
+        for (kind, transitions) in &self.workflow.transitions {
+            let known = self.statuses_of(kind).with_context(|| {
+                format!(
+                    "Unknown log type '{}' in [workflow]. Expected {}",
+                    kind, EXPECTED_TYPES
+                )
+            })?;
+            check_statuses(transitions, kind, &known)?;
+        }
+        Ok(())
+    }
+
+    /// The statuses of the log type `kind`, built in or declared in `[types]`.
+    fn statuses_of(&self, kind: &str) -> Option<Vec<String>> {
+        LogTypeRegistry::new(self)
+            .get(kind)
+            .map(|log_type| log_type.statuses.clone())
+    }
+
+    fn validate_encryption(&self) -> Result<()> {
+        for recipient in &self.encryption.recipients {
+            crate::encryption::parse_recipient(recipient)
+                .context("Invalid recipient in [encryption]")?;
+        }
+        Ok(())
+    }
+
+    fn validate_authors(&self) -> Result<()> {
+        if let Some(orcid) = &self.author.orcid {
+            utils::parse_orcid(orcid).context("Invalid orcid in [author]")?;
+        }
+        for (handle, member) in &self.team {
+            if let Some(orcid) = &member.orcid {
+                utils::parse_orcid(orcid)
+                    .with_context(|| format!("Invalid orcid of [team.{}]", handle))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_aliases(&self) -> Result<()> {
+        for (name, command) in &self.aliases {
+            if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+                return Err(anyhow::anyhow!(
+                    "Invalid alias name '{}' in [aliases]. Names are single words",
+                    name
+                ));
+            }
+            if command.trim().is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Alias '{}' in [aliases] has no command",
+                    name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_notifications(&self) -> Result<()> {
+        for event in self.notifications.webhooks.iter().flat_map(|w| &w.events) {
+            let (kind, status) = event.split_once(':').with_context(|| {
+                format!(
+                    "Invalid event '{}' in [[notifications.webhooks]]. Expected <type>:<status>, e.g. hypothesis:proven",
+                    event
+                )
+            })?;
+            let known = self.statuses_of(kind).with_context(|| {
+                format!(
+                    "Unknown log type '{}' in event '{}'. Expected {}",
+                    kind, event, EXPECTED_TYPES
+                )
+            })?;
+            if status != "*" && !known.iter().any(|s| s == status) {
+                return Err(anyhow::anyhow!(
+                    "Unknown {} status '{}' in event '{}'. Valid statuses: {}",
+                    kind,
+                    status,
+                    event,
+                    known.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_paths(&mut self) {
+        let root = self.root.clone();
+        for path in [
+            &mut self.templates.hypothesis,
+            &mut self.templates.literature,
+            &mut self.templates.knowledge,
+            &mut self.templates.journal,
+            &mut self.templates.dataset,
+            &mut self.templates.meeting,
+            &mut self.templates.experiment,
+            &mut self.templates.report,
+            &mut self.storage.active_dir,
+            &mut self.storage.archive_dir,
+            &mut self.storage.knowledge_base_dir,
+            &mut self.storage.journal_dir,
+            &mut self.storage.datasets_dir,
+            &mut self.storage.meetings_dir,
+            &mut self.storage.inbox_dir,
+            &mut self.storage.experiments_dir,
+            &mut self.storage.attachments_dir,
+            &mut self.storage.trash_dir,
+            &mut self.storage.milestones_file,
+        ] {
+            *path = root.join(&*path);
+        }
+        for template in self
+            .templates
+            .named
+            .values_mut()
+            .flat_map(|t| t.values_mut())
+        {
+            template.path = root.join(&template.path);
+        }
+        for template in self.types.values_mut().filter_map(|t| t.template.as_mut()) {
+            *template = root.join(&*template);
+        }
+    }
+}
+
+/// Whether `name` is fit for a log type or status: lowercase words joined by dashes.
+fn is_type_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+}
+
+/// The handle prefix of the `[types]` entry `name`.
+pub(crate) fn log_type_prefix(name: &str, log_type: &LogTypeConfig) -> String {
+    log_type.handle_prefix.clone().unwrap_or_else(|| {
+        name.chars()
+            .next()
+            .map(|c| c.to_ascii_uppercase().to_string())
+            .unwrap_or_default()
+    })
+}
+
+fn status_names<S: LogStatus>(config: &Config) -> Vec<String> {
+    S::all(config).iter().map(|s| s.to_string()).collect()
+}
+
+fn config_file(dir: &Path) -> Option<PathBuf> {
+    [CONFIG_FILE_NAME, LEGACY_CONFIG_FILE_NAME]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Walks up from `start` to the first directory containing a dxlog config file.
+pub fn find_repo_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| config_file(dir).is_some())
+        .map(Path::to_path_buf)
+}
+
+/// Returns the root of the current repository.
+///
+/// The repository is taken from `--repo` or `DXLOG_REPO` if given, otherwise
+/// it is found by walking up from the working directory, the same way git
+/// finds `.git`.
+pub fn repo_root() -> Result<PathBuf> {
+    if let Some(repo) = run_options().repo {
+        return Ok(repo);
+    }
+
+    let cwd = std::env::current_dir()?;
+    find_repo_root(&cwd).with_context(|| {
+        format!(
+            "Not inside a dxlog repository (no {} found in {} or any parent). \
+             Run `dxlog init` or pass --repo.",
+            CONFIG_FILE_NAME,
+            cwd.display()
+        )
+    })
+}
+
+/// Loads the config of the current repository.
 pub fn load_config() -> Result<Config> {
-    let config_path = std::path::Path::new(".rlog.toml");
-    if !config_path.exists() {
-        return Ok(Config::default());
+    Config::load(&repo_root()?)
+}
+
+fn read_config_file(root: &Path) -> Result<(PathBuf, String)> {
+    let config_path = config_file(root).with_context(|| {
+        format!(
+            "{} is not a dxlog repository (no {} found)",
+            root.display(),
+            CONFIG_FILE_NAME
+        )
+    })?;
+    let content = std::fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
+    Ok((config_path, content))
+}
+
+/// The effective settings of the repository at `root`, defaults included, with
+/// paths as written in the config file.
+fn effective_settings(root: &Path) -> Result<toml::Table> {
+    let (config_path, content) = read_config_file(root)?;
+    let config: Config = toml::from_str(&content)
+        .with_context(|| format!("Invalid config file: {}", config_path.display()))?;
+    Ok(toml::Table::try_from(config)?)
+}
+
+fn lookup<'a>(table: &'a toml::Table, key: &str) -> Option<&'a toml::Value> {
+    let (first, rest) = match key.split_once('.') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (key, None),
+    };
+    match (table.get(first)?, rest) {
+        (toml::Value::Table(inner), Some(rest)) => lookup(inner, rest),
+        (value, None) => Some(value),
+        _ => None,
+    }
+}
+
+fn display_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Returns the value of a dotted config key such as `storage.active-dir`.
+///
+/// Settings that are not in the file report their default. Keys dxlog does not
+/// use itself (e.g. under `[git]`) are read from the file as-is.
+pub fn get_config_value(key: &str) -> Result<String> {
+    read_config_value(&repo_root()?, key)
+}
+
+fn read_config_value(root: &Path, key: &str) -> Result<String> {
+    if let Some(value) = lookup(&effective_settings(root)?, key) {
+        return Ok(display_value(value));
     }
 
-    let content = std::fs::read_to_string(config_path)?;
-    let config: Config = toml::from_str(&content)?;
+    let (_, content) = read_config_file(root)?;
+    let raw: toml::Table = toml::from_str(&content)?;
+    lookup(&raw, key)
+        .map(display_value)
+        .with_context(|| format!("Unknown config key '{}'", key))
+}
 
-    Ok(config)
+/// Sets a dotted config key and writes it back to the config file.
+///
+/// The value is checked before anything is written: it must have the right type,
+/// storage and template paths must stay inside the repository, and the resulting
+/// file must still load. Comments and formatting elsewhere in the file are kept.
+pub fn set_config_value(key: &str, value: &str) -> Result<()> {
+    write_config_value(&repo_root()?, key, value)
+}
+
+fn write_config_value(root: &Path, key: &str, value: &str) -> Result<()> {
+    let defaults = toml::Table::try_from(Config::default())?;
+    let new_value = match lookup(&defaults, key) {
+        Some(toml::Value::String(_)) => toml_edit::Value::from(value),
+        Some(toml::Value::Integer(_)) => toml_edit::Value::from(
+            value
+                .parse::<i64>()
+                .with_context(|| format!("'{}' expects a whole number, got '{}'", key, value))?,
+        ),
+        Some(toml::Value::Table(_)) => {
+            return Err(anyhow::anyhow!(
+                "'{}' is a section, set one of its keys instead",
+                key
+            ))
+        }
+        _ => return Err(anyhow::anyhow!("Unknown config key '{}'", key)),
+    };
+
+    if key.starts_with("storage.") || key.starts_with("templates.") {
+        ensure_inside_repo(key, value)?;
+    }
+    if key == "date-format" {
+        ensure_valid_date_format(value)?;
+    }
+
+    let (config_path, content) = read_config_file(root)?;
+    let mut doc: toml_edit::DocumentMut = content
+        .parse()
+        .with_context(|| format!("Invalid config file: {}", config_path.display()))?;
+
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let leaf = parts.pop().unwrap_or(key);
+    let mut table = doc.as_table_mut();
+    for part in parts {
+        table = table
+            .entry(part)
+            .or_insert(toml_edit::table())
+            .as_table_mut()
+            .with_context(|| format!("'{}' in the config file is not a table", part))?;
+    }
+    match table.get_mut(leaf).and_then(|item| item.as_value_mut()) {
+        Some(existing) => {
+            // Keep any trailing comment attached to the old value.
+            let decor = existing.decor().clone();
+            *existing = new_value;
+            *existing.decor_mut() = decor;
+        }
+        None => {
+            table.insert(leaf, toml_edit::Item::Value(new_value));
+        }
+    }
+
+    let updated = doc.to_string();
+    toml::from_str::<Config>(&updated)
+        .with_context(|| format!("Setting '{}' to '{}' would break the config", key, value))?;
+    crate::dry_run::write(&config_path, &updated)
+        .with_context(|| format!("Failed to write config file: {}", config_path.display()))
+}
+
+fn ensure_inside_repo(key: &str, value: &str) -> Result<()> {
+    let path = Path::new(value);
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => depth -= 1,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "'{}' must be a path inside the repository, got '{}'",
+                    key,
+                    value
+                ))
+            }
+        }
+    }
+    if depth == 0 {
+        return Err(anyhow::anyhow!(
+            "'{}' cannot point at the repository root itself",
+            key
+        ));
+    }
+    Ok(())
+}
+
+fn ensure_valid_date_format(format: &str) -> Result<()> {
+    let invalid = chrono::format::StrftimeItems::new(format)
+        .any(|item| matches!(item, chrono::format::Item::Error));
+    if invalid {
+        return Err(anyhow::anyhow!("'{}' is not a valid date format", format));
+    }
+    Ok(())
+}
+
+/// Renders the effective configuration, defaults included, as TOML.
+pub fn show_config() -> Result<String> {
+    let settings = effective_settings(&repo_root()?)?;
+    Ok(toml::to_string_pretty(&settings)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempRepo;
+
+    #[test]
+    fn test_load_resolves_paths_against_root() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        let nested = root.join("research-logs/deep");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            root.join(CONFIG_FILE_NAME),
+            "date-format = \"%d.%m.%Y\"\n[storage]\nactive-dir = \"logs\"\n[git]\nenabled = true\n",
+        )
+        .unwrap();
+
+        assert_eq!(find_repo_root(&nested), Some(root.clone()));
+
+        let config = Config::load(&root).unwrap();
+        assert_eq!(config.date_format, "%d.%m.%Y");
+        assert_eq!(config.storage.active_dir, root.join("logs"));
+        assert_eq!(config.storage.archive_dir, root.join("archived"));
+        assert_eq!(
+            config.templates.knowledge,
+            root.join("templates/knowledge.jinja")
+        );
+    }
+
+    #[test]
+    fn test_legacy_config_file() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        std::fs::write(
+            root.join(LEGACY_CONFIG_FILE_NAME),
+            "date_format = \"%d.%m.%Y\"\nstale_days = 30\n\n[templates]\nhypothesis = \"tpl/h.jinja\"\n\n[storage]\nactive_dir = \"logs\"\narchive_dir = \"old\"\nknowledge_base_dir = \"kb\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load(&root).unwrap();
+        assert_eq!(config.date_format, "%d.%m.%Y");
+        assert_eq!(config.stale_days, 30);
+        assert_eq!(config.templates.hypothesis, root.join("tpl/h.jinja"));
+        assert_eq!(config.storage.active_dir, root.join("logs"));
+        assert_eq!(config.storage.archive_dir, root.join("old"));
+        assert_eq!(config.storage.knowledge_base_dir, root.join("kb"));
+    }
+
+    #[test]
+    fn test_set_value_validates_and_keeps_comments() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        std::fs::write(
+            root.join(CONFIG_FILE_NAME),
+            "stale-days = 14  # days before a log is stale\n",
+        )
+        .unwrap();
+
+        write_config_value(&root, "stale-days", "30").unwrap();
+        write_config_value(&root, "storage.archive-dir", "old/archive").unwrap();
+        assert!(write_config_value(&root, "storage.active-dir", "../elsewhere").is_err());
+        assert!(write_config_value(&root, "storage.active-dir", "/tmp/logs").is_err());
+        assert!(write_config_value(&root, "stale-days", "soon").is_err());
+        assert!(write_config_value(&root, "no-such-key", "1").is_err());
+
+        let content = std::fs::read_to_string(root.join(CONFIG_FILE_NAME)).unwrap();
+        assert!(content.contains("stale-days = 30  # days before a log is stale"));
+        assert_eq!(read_config_value(&root, "stale-days").unwrap(), "30");
+        assert_eq!(
+            read_config_value(&root, "storage.archive-dir").unwrap(),
+            "old/archive"
+        );
+        assert_eq!(
+            read_config_value(&root, "storage.active-dir").unwrap(),
+            "research-logs"
+        );
+    }
+
+    #[test]
+    fn test_storage_layout() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        let write = |layout: &str| {
+            std::fs::write(
+                root.join(CONFIG_FILE_NAME),
+                format!("[storage.layout.hypothesis]\n{}\n", layout),
+            )
+            .unwrap()
+        };
+
+        write("proven = \"results/{kind}/{status}\"\nactive = \"results/open\"");
+        let config = Config::load(&root).unwrap();
+        assert_eq!(
+            config.layout_dir("hypothesis", "proven"),
+            Some(root.join("results/hypothesis/proven"))
+        );
+        assert_eq!(config.layout_dir("hypothesis", "abandoned"), None);
+        assert_eq!(config.layout_dir("knowledge", "draft"), None);
+        assert_eq!(config.layout_dirs("hypothesis").len(), 2);
+
+        write("solved = \"results\"");
+        assert!(Config::load(&root).is_err());
+        write("proven = \"../results\"");
+        assert!(Config::load(&root).is_err());
+    }
+
+    #[test]
+    fn test_named_templates() {
+        let root = PathBuf::from("/repo");
+        let mut config: Config = toml::from_str(
+            "[templates.named.hypothesis.benchmark]\npath = \"templates/benchmark.jinja\"\ntags = [\"benchmark\", \"perf\"]\n",
+        )
+        .unwrap();
+        config.root = root.clone();
+        config.resolve_paths();
+        config.validate_templates().unwrap();
+
+        let tags = |t: &[&str]| t.iter().map(|s| s.to_string()).collect::<HashSet<_>>();
+        let benchmark = root.join("templates/benchmark.jinja");
+        let default = root.join("templates/hypothesis.jinja");
+        let pick = |name, t: &[&str]| config.template_for("hypothesis", name, &tags(t)).unwrap();
+
+        assert_eq!(pick(None, &["perf"]), benchmark);
+        assert_eq!(pick(None, &["ml"]), default);
+        assert_eq!(pick(Some("benchmark"), &[]), benchmark);
+        assert_eq!(pick(Some("default"), &["perf"]), default);
+        assert!(config
+            .template_for("hypothesis", Some("other"), &tags(&[]))
+            .is_err());
+        assert_eq!(
+            config
+                .template_for("knowledge", None, &tags(&["perf"]))
+                .unwrap(),
+            root.join("templates/knowledge.jinja")
+        );
+    }
+
+    #[test]
+    fn test_notification_events() {
+        let parse = |events: &str| -> Config {
+            toml::from_str(&format!(
+                "[[notifications.webhooks]]\nurl = \"https://example.org/hook\"\nservice = \"discord\"\nevents = {}\n",
+                events
+            ))
+            .unwrap()
+        };
+
+        let config = parse("[\"hypothesis:proven\", \"knowledge:*\"]");
+        config.validate_notifications().unwrap();
+        let webhook = &config.notifications.webhooks[0];
+        assert_eq!(webhook.service, ChatService::Discord);
+        assert!(webhook.wants("hypothesis", "proven"));
+        assert!(!webhook.wants("hypothesis", "abandoned"));
+        assert!(webhook.wants("knowledge", "draft"));
+        assert!(!webhook.wants("literature", "completed"));
+        assert!(parse("[]").notifications.webhooks[0].wants("journal", "closed"));
+
+        assert!(parse("[\"hypothesis\"]").validate_notifications().is_err());
+        assert!(parse("[\"hypothesis:solved\"]")
+            .validate_notifications()
+            .is_err());
+        assert!(parse("[\"paper:*\"]").validate_notifications().is_err());
+    }
+
+    #[test]
+    fn test_aliases() {
+        let parse = |aliases: &str| -> Config {
+            toml::from_str(&format!("[aliases]\n{}", aliases)).unwrap()
+        };
+
+        let config = parse("standup = \"report --since 1d\"\n");
+        config.validate_aliases().unwrap();
+        assert_eq!(config.aliases["standup"], "report --since 1d");
+        assert!(parse("\"stand up\" = \"report\"\n")
+            .validate_aliases()
+            .is_err());
+        assert!(parse("standup = \" \"\n").validate_aliases().is_err());
+    }
+
+    #[test]
+    fn test_custom_statuses() {
+        let parse = |statuses: &str| -> Config {
+            toml::from_str(&format!("[statuses.hypothesis]\n{}\n", statuses)).unwrap()
+        };
+        let names = |config: &Config| status_names::<HypothesisStatus>(config);
+
+        assert_eq!(
+            names(&Config::default()).len(),
+            HypothesisStatus::BUILT_IN.len()
+        );
+        let config = parse("add = [\"needs-replication\"]\n[storage.layout.hypothesis]\nneeds-replication = \"replication\"");
+        config.validate_statuses().unwrap();
+        config.validate_layout().unwrap();
+        assert_eq!(names(&config).last().unwrap(), "needs-replication");
+        assert_eq!(
+            HypothesisStatus::parse(&config, "Needs_Replication").unwrap(),
+            "needs-replication".parse().unwrap()
+        );
+        assert!(HypothesisStatus::parse(&Config::default(), "needs-replication").is_err());
+
+        let config = parse("replace = [\"active\", \"confirmed\", \"refuted\"]");
+        config.validate_statuses().unwrap();
+        assert_eq!(names(&config), ["active", "confirmed", "refuted"]);
+
+        assert!(parse("add = [\"Needs Replication\"]")
+            .validate_statuses()
+            .is_err());
+        assert!(parse("add = [\"draft\"]").validate_statuses().is_err());
+        assert!(parse("replace = [\"open\"]").validate_statuses().is_err());
+    }
 }
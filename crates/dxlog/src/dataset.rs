@@ -0,0 +1,403 @@
+use anyhow::Result;
+use minijinja::context;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    load_config,
+    log_manager::{ListFilter, LogManager, Renamed},
+    md_frontmatter::serialize_yaml_frontmatter,
+    migration::SCHEMA_VERSION,
+    research_log::ResearchLog,
+    template::{builtin_template, entry_context},
+    utils::{self, Author, BaseLog},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum DatasetStatus {
+    Candidate,
+    InUse,
+    Deprecated,
+}
+
+impl fmt::Display for DatasetStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            DatasetStatus::Candidate => "candidate",
+            DatasetStatus::InUse => "in_use",
+            DatasetStatus::Deprecated => "deprecated",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Where a dataset comes from and where its local copy is.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct DatasetSource {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Checksum of the data, e.g. `sha256:9f86d0...`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Local copy, relative to the repository root or absolute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Size in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+/// A dataset in the registry, so the provenance of the data is kept next to the
+/// hypotheses and reviews that use it.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DatasetLog {
+    #[serde(flatten)]
+    pub base: BaseLog,
+    pub status: DatasetStatus,
+    #[serde(flatten)]
+    pub source: DatasetSource,
+}
+
+impl ResearchLog for DatasetLog {
+    type Status = DatasetStatus;
+    const KIND: &'static str = "dataset";
+    const HANDLE_PREFIX: &'static str = "D";
+
+    fn base(&self) -> &BaseLog {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseLog {
+        &mut self.base
+    }
+
+    fn status(&self) -> &Self::Status {
+        &self.status
+    }
+
+    fn status_mut(&mut self) -> &mut Self::Status {
+        &mut self.status
+    }
+
+    fn new(title: String, tags: HashSet<String>, author: Author) -> Self {
+        let now = utils::now();
+        Self {
+            base: BaseLog {
+                schema_version: SCHEMA_VERSION,
+                id: Uuid::now_v7(),
+                handle: None,
+                created_at: now,
+                updated_at: Some(now),
+                title,
+                tags,
+                project: None,
+                milestone: None,
+                created_by: author,
+                assignee: None,
+                references: HashSet::new(),
+                history: Vec::new(),
+                attachments: Vec::new(),
+                encrypted: false,
+            },
+            status: DatasetStatus::Candidate,
+            source: DatasetSource::default(),
+        }
+    }
+
+    fn update_status(&mut self, new_status: Self::Status) {
+        self.status = new_status;
+    }
+
+    /// Candidates have not been vetted yet; data in use, or once used, has.
+    fn is_complete(&self) -> bool {
+        !matches!(self.status, DatasetStatus::Candidate)
+    }
+
+    fn get_target_path(&self, config: &Config, current_path: &Path) -> Result<PathBuf> {
+        let filename = current_path.file_name().unwrap();
+        if let Some(dir) = config.layout_dir(Self::KIND, &self.status.to_string()) {
+            return Ok(dir.join(filename));
+        }
+        // Deprecated datasets stay in the registry so older results can still
+        // be traced back to them.
+        Ok(config.storage.datasets_dir.join(filename))
+    }
+}
+
+pub struct DatasetManager {
+    pub manager: LogManager<DatasetLog>,
+}
+
+impl DatasetManager {
+    pub fn new(config: Config) -> Self {
+        let search_dirs = vec![config.storage.datasets_dir.clone()];
+
+        Self {
+            manager: LogManager::<DatasetLog>::new(config, search_dirs),
+        }
+    }
+
+    /// Registers a dataset as a candidate. Without a size, the size of the
+    /// local copy is recorded if there is one.
+    pub fn create(
+        &self,
+        name: &str,
+        mut source: DatasetSource,
+        tags: Option<Vec<String>>,
+        body: Option<&str>,
+        template: Option<&str>,
+        allow_duplicate_title: bool,
+    ) -> Result<DatasetLog> {
+        let config = &self.manager.config;
+        let author = utils::get_author(config)?;
+        if source.size.is_none() {
+            source.size = source
+                .path
+                .as_deref()
+                .and_then(|path| disk_size(&config.root.join(path)));
+        }
+        let mut dataset = DatasetLog::new(name.to_string(), utils::normalize_tags(tags), author);
+        dataset.source = source;
+        self.manager.assign_ids(&mut dataset)?;
+
+        let yaml = serialize_yaml_frontmatter(&dataset)?;
+        let template_path = config.template_for(DatasetLog::KIND, template, &dataset.base.tags)?;
+        // Repositories set up before datasets existed have no dataset template yet.
+        let template_content = match (template_path.exists(), template) {
+            (false, None | Some("default")) => builtin_template(DatasetLog::KIND)
+                .unwrap_or_default()
+                .to_string(),
+            _ => utils::load_entry_content(&template_path)?,
+        };
+
+        let body = body.map(str::trim);
+        let source = &dataset.source;
+        let rendered = utils::render_entry(
+            &template_content,
+            context! {
+                body => body,
+                url => source.url,
+                license => source.license,
+                version => source.version,
+                hash => source.hash,
+                path => source.path.as_ref().map(|p| p.display().to_string()),
+                size => source.size.map(format_size),
+                ..entry_context(config, &dataset.base, yaml)
+            },
+            body,
+        )?;
+
+        self.manager
+            .save_log(&dataset, &rendered, allow_duplicate_title)?;
+        Ok(dataset)
+    }
+
+    /// Retitles an entry, renaming its file and updating links to it.
+    pub fn rename(
+        &self,
+        partial_id: &str,
+        name: &str,
+        allow_duplicate_title: bool,
+    ) -> Result<Renamed<DatasetLog>> {
+        self.manager
+            .rename_log(partial_id, name, allow_duplicate_title)
+    }
+
+    /// Applies `edit` to the provenance of a dataset, e.g. when a new version is
+    /// downloaded.
+    pub fn update_source(
+        &self,
+        partial_id: &str,
+        edit: impl FnOnce(&mut DatasetSource),
+    ) -> Result<DatasetLog> {
+        let (mut dataset, file_path) = self.manager.find_log(partial_id)?;
+        edit(&mut dataset.source);
+        self.manager.update_log(&mut dataset, &file_path)?;
+        Ok(dataset)
+    }
+
+    pub fn update_status(
+        &self,
+        partial_id: &str,
+        new_status: DatasetStatus,
+        reason: Option<&str>,
+        force: bool,
+    ) -> Result<()> {
+        self.manager
+            .change_status(partial_id, new_status, reason, force)?;
+        Ok(())
+    }
+
+    pub fn list(&self, filter: &ListFilter<DatasetStatus>) -> Result<Vec<DatasetLog>> {
+        self.manager.list_logs(filter)
+    }
+
+    pub fn find(&self, partial_id: &str) -> Result<(DatasetLog, PathBuf)> {
+        self.manager.find_log(partial_id)
+    }
+}
+
+/// Total size in bytes of a file, or of the files below a directory.
+fn disk_size(path: &Path) -> Option<u64> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_dir() {
+        return Some(metadata.len());
+    }
+    let mut size = 0;
+    for entry in fs::read_dir(path).ok()? {
+        size += disk_size(&entry.ok()?.path()).unwrap_or(0);
+    }
+    Some(size)
+}
+
+const SIZE_UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+/// Formats a size in bytes with a binary unit, e.g. `1.5 GB`.
+pub fn format_size(bytes: u64) -> String {
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < SIZE_UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, SIZE_UNITS[unit])
+    }
+}
+
+/// Parses a size such as `734003200`, `700M` or `1.5 GB` into bytes. Units are
+/// binary: `1K` is 1024 bytes.
+pub fn parse_size(text: &str) -> Result<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid size '{}', expected e.g. 700M or 1.5GB", text))?;
+    let unit = unit.trim().to_uppercase();
+    let power = match unit.strip_suffix("IB").unwrap_or(&unit) {
+        "" | "B" => 0,
+        "K" | "KB" => 1,
+        "M" | "MB" => 2,
+        "G" | "GB" => 3,
+        "T" | "TB" => 4,
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Invalid size unit in '{}', expected B, K, M, G or T",
+                text
+            ))
+        }
+    };
+    Ok((number * 1024f64.powi(power)).round() as u64)
+}
+
+pub fn create_dataset(
+    name: &str,
+    source: DatasetSource,
+    tags: Option<Vec<String>>,
+    body: Option<&str>,
+    template: Option<&str>,
+    allow_duplicate_title: bool,
+) -> Result<DatasetLog> {
+    let config = load_config()?;
+    let manager = DatasetManager::new(config);
+    manager.create(name, source, tags, body, template, allow_duplicate_title)
+}
+
+pub fn update_dataset_source(
+    partial_id: &str,
+    edit: impl FnOnce(&mut DatasetSource),
+) -> Result<DatasetLog> {
+    let config = load_config()?;
+    let manager = DatasetManager::new(config);
+    manager.update_source(partial_id, edit)
+}
+
+pub fn update_dataset_status(
+    partial_id: &str,
+    new_status: DatasetStatus,
+    reason: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let manager = DatasetManager::new(config);
+    manager.update_status(partial_id, new_status, reason, force)
+}
+
+pub fn rename_dataset(
+    partial_id: &str,
+    name: &str,
+    allow_duplicate_title: bool,
+) -> Result<Renamed<DatasetLog>> {
+    let config = load_config()?;
+    let manager = DatasetManager::new(config);
+    manager.rename(partial_id, name, allow_duplicate_title)
+}
+
+pub fn list_datasets(filter: &ListFilter<DatasetStatus>) -> Result<Vec<DatasetLog>> {
+    list_datasets_in(&load_config()?, filter)
+}
+
+/// Like [`list_datasets`], in the repository of `config`.
+pub fn list_datasets_in(
+    config: &Config,
+    filter: &ListFilter<DatasetStatus>,
+) -> Result<Vec<DatasetLog>> {
+    let manager = DatasetManager::new(config.clone());
+    manager.list(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::author;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("734003200").unwrap(), 734_003_200);
+        assert_eq!(parse_size("512B").unwrap(), 512);
+        assert_eq!(parse_size("700M").unwrap(), 700 * 1024 * 1024);
+        assert_eq!(parse_size("1.5 GB").unwrap(), 1536 * 1024 * 1024);
+        assert_eq!(parse_size("2GiB").unwrap(), 2 * 1024 * 1024 * 1024);
+        assert_eq!(parse_size("3k").unwrap(), 3 * 1024);
+        assert!(parse_size("GB").is_err());
+        assert!(parse_size("12 parsecs").is_err());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(1536 * 1024 * 1024), "1.5 GB");
+    }
+
+    #[test]
+    fn test_source_fields_in_frontmatter() {
+        let mut dataset = DatasetLog::new("ImageNet".to_string(), HashSet::new(), author());
+        dataset.source.url = Some("https://image-net.org".to_string());
+        dataset.source.size = Some(1024);
+        let yaml = serde_yaml::to_string(&dataset).unwrap();
+        assert!(yaml.contains("url: https://image-net.org"));
+        assert!(yaml.contains("size: 1024"));
+        assert!(yaml.contains("status: Candidate"));
+        assert!(!yaml.contains("license"));
+
+        let parsed: DatasetLog = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.source, dataset.source);
+        assert_eq!(parsed.status, DatasetStatus::Candidate);
+    }
+}
@@ -0,0 +1,134 @@
+//! Dry-run mode for operations that write, move or delete entry files.
+//!
+//! Those operations go through [`write`], [`copy`], [`rename`] and [`remove_file`], or check
+//! [`plan`] themselves. In dry-run mode (`--dry-run` or [`DRY_RUN_ENV_VAR`]), these
+//! only record what they would have done; the caller collects the plan with
+//! [`take_planned_changes`].
+//! Otherwise they also note the change for the operation being recorded in the
+//! undo journal, if any (see [`crate::operations`]).
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::{
+    cell::RefCell,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use tracing::debug;
+
+use crate::{config::run_options, utils};
+
+/// Environment variable that, when set to anything but `0`/`false`/`no`/`off`, turns
+/// on dry-run mode.
+pub const DRY_RUN_ENV_VAR: &str = "DXLOG_DRY_RUN";
+
+thread_local! {
+    /// Kept per thread, so each request of `dxlog serve` or `dxlog mcp` only
+    /// gets the plan of its own changes.
+    static PLANNED: RefCell<Vec<PlannedChange>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A filesystem change skipped in dry-run mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PlannedChange {
+    Write { path: PathBuf },
+    Move { from: PathBuf, to: PathBuf },
+    Remove { path: PathBuf },
+}
+
+impl fmt::Display for PlannedChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlannedChange::Write { path } => write!(f, "write  {}", path.display()),
+            PlannedChange::Move { from, to } => {
+                write!(f, "move   {} -> {}", from.display(), to.display())
+            }
+            PlannedChange::Remove { path } => write!(f, "remove {}", path.display()),
+        }
+    }
+}
+
+/// Whether dry-run mode has been turned on, through [`RunOptions`](crate::RunOptions) or else
+/// [`DRY_RUN_ENV_VAR`].
+pub fn is_dry_run() -> bool {
+    run_options().dry_run
+}
+
+/// Returns the changes this thread recorded so far in dry-run mode, in order, and forgets them.
+pub fn take_planned_changes() -> Vec<PlannedChange> {
+    PLANNED.with(|planned| planned.take())
+}
+
+/// Records `change` if in dry-run mode, returning whether it should be skipped.
+pub(crate) fn plan(change: impl FnOnce() -> PlannedChange) -> bool {
+    if !is_dry_run() {
+        return false;
+    }
+    PLANNED.with(|planned| planned.borrow_mut().push(change()));
+    true
+}
+
+/// Writes `contents` to `path`, creating its directory if needed.
+pub(crate) fn write(path: &Path, contents: &str) -> Result<()> {
+    if plan(|| PlannedChange::Write {
+        path: path.to_path_buf(),
+    }) {
+        return Ok(());
+    }
+    crate::operations::note_write(path, contents);
+    debug!(path = %path.display(), "Writing");
+    if let Some(parent) = path.parent() {
+        utils::ensure_directory(parent)?;
+    }
+    fs::write(path, contents).with_context(|| format!("Failed to write file: {}", path.display()))
+}
+
+/// Moves `from` to `to`, creating the target directory if needed.
+pub(crate) fn rename(from: &Path, to: &Path) -> Result<()> {
+    if plan(|| PlannedChange::Move {
+        from: from.to_path_buf(),
+        to: to.to_path_buf(),
+    }) {
+        return Ok(());
+    }
+    crate::operations::note_move(from, to);
+    debug!(from = %from.display(), to = %to.display(), "Moving");
+    if let Some(parent) = to.parent() {
+        utils::ensure_directory(parent)?;
+    }
+    fs::rename(from, to)
+        .with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))
+}
+
+/// Copies `from` to `to`, creating the target directory if needed.
+///
+/// `to` should not exist: the undo journal only keeps what text files held
+/// before, so a file it replaces could not be restored.
+pub(crate) fn copy(from: &Path, to: &Path) -> Result<()> {
+    if plan(|| PlannedChange::Write {
+        path: to.to_path_buf(),
+    }) {
+        return Ok(());
+    }
+    crate::operations::note_copy(from, to);
+    debug!(from = %from.display(), to = %to.display(), "Copying");
+    if let Some(parent) = to.parent() {
+        utils::ensure_directory(parent)?;
+    }
+    fs::copy(from, to)
+        .map(|_| ())
+        .with_context(|| format!("Failed to copy {} to {}", from.display(), to.display()))
+}
+
+pub(crate) fn remove_file(path: &Path) -> Result<()> {
+    if plan(|| PlannedChange::Remove {
+        path: path.to_path_buf(),
+    }) {
+        return Ok(());
+    }
+    crate::operations::note_remove(path);
+    debug!(path = %path.display(), "Removing");
+    fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))
+}
@@ -0,0 +1,76 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    load_config, research_log::ResearchLog, HypothesisLog, HypothesisManager, HypothesisStatus,
+    KnowledgeLog, KnowledgeManager, KnowledgeStatus, ListFilter,
+};
+
+/// An open entry with a due date, as listed by [`due_items`].
+#[derive(Debug, Serialize)]
+pub struct DueItem {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: String,
+    pub status: String,
+    pub due: NaiveDate,
+    /// Days until the due date; negative once it has passed.
+    pub days_remaining: i64,
+}
+
+/// Lists open hypotheses (active or suspended) and knowledge drafts, including
+/// those in review, that have a due date, soonest first. Overdue items are always
+/// included; upcoming ones only if due within `within` days, when given.
+pub fn due_items(within: Option<i64>) -> Result<Vec<DueItem>> {
+    let config = load_config()?;
+    let today = chrono::Local::now().date_naive();
+    let mut items = Vec::new();
+
+    let hypotheses = HypothesisManager::new(config.clone()).list(&ListFilter::default())?;
+    for log in hypotheses {
+        if ![HypothesisStatus::ACTIVE, HypothesisStatus::SUSPENDED].contains(&log.status) {
+            continue;
+        }
+        if let Some(due) = log.due {
+            items.push(DueItem {
+                id: log.base.id,
+                handle: log.base.handle,
+                kind: HypothesisLog::KIND,
+                title: log.base.title,
+                status: log.status.to_string(),
+                due,
+                days_remaining: (due - today).num_days(),
+            });
+        }
+    }
+
+    let knowledge = KnowledgeManager::new(config).list(&ListFilter::default())?;
+    for log in knowledge {
+        if !matches!(
+            log.status,
+            KnowledgeStatus::Draft | KnowledgeStatus::InReview
+        ) {
+            continue;
+        }
+        if let Some(due) = log.due {
+            items.push(DueItem {
+                id: log.base.id,
+                handle: log.base.handle,
+                kind: KnowledgeLog::KIND,
+                title: log.base.title,
+                status: log.status.to_string(),
+                due,
+                days_remaining: (due - today).num_days(),
+            });
+        }
+    }
+
+    items.retain(|item| within.is_none_or(|days| item.days_remaining <= days));
+    items.sort_by(|a, b| a.due.cmp(&b.due).then_with(|| a.title.cmp(&b.title)));
+    Ok(items)
+}
@@ -0,0 +1,334 @@
+//! age encryption of entry bodies.
+//!
+//! An encrypted entry keeps its frontmatter readable, so it is still listed,
+//! filtered and linked like any other, while its markdown body is stored as an
+//! ASCII-armored age file encrypted to the `[encryption]` recipients. Reading
+//! it back takes an identity (private key) matching one of them, found through
+//! [`IDENTITY_ENV_VAR`] or in `~/.config/dxlog/identity.txt`.
+
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
+use anyhow::{Context, Result};
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::Config, load_config, log_manager::LogManager,
+    md_frontmatter::update_markdown_frontmatter, research_log::ResearchLog, DatasetManager,
+    ExperimentManager, HypothesisManager, IdeaManager, JournalManager, KnowledgeManager,
+    LiteratureManager, MeetingManager,
+};
+
+/// Environment variable holding the path of the age identity file used to
+/// decrypt entries.
+pub const IDENTITY_ENV_VAR: &str = "DXLOG_IDENTITY";
+
+/// Parses an age public key (`age1...`).
+pub(crate) fn parse_recipient(text: &str) -> Result<age::x25519::Recipient> {
+    text.trim()
+        .parse()
+        .map_err(|reason| anyhow::anyhow!("'{}' is not an age public key: {}", text, reason))
+}
+
+/// Where the identity file is looked for: `$DXLOG_IDENTITY`, then
+/// `$XDG_CONFIG_HOME/dxlog/identity.txt` or `~/.config/dxlog/identity.txt`.
+pub fn identity_path() -> Option<PathBuf> {
+    std::env::var_os(IDENTITY_ENV_VAR)
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("XDG_CONFIG_HOME").map(|d| PathBuf::from(d).join("dxlog/identity.txt"))
+        })
+        .or_else(|| {
+            std::env::var_os("HOME").map(|d| PathBuf::from(d).join(".config/dxlog/identity.txt"))
+        })
+}
+
+fn encryptor(config: &Config) -> Result<age::Encryptor> {
+    if config.encryption.recipients.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No recipients to encrypt to. Add your age public key to `recipients` under [encryption] in dxlog.toml"
+        ));
+    }
+    let recipients = config
+        .encryption
+        .recipients
+        .iter()
+        .map(|recipient| parse_recipient(recipient))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(age::Encryptor::with_recipients(
+        recipients
+            .iter()
+            .map(|recipient| recipient as &dyn age::Recipient),
+    )?)
+}
+
+/// Encrypts `body` to every recipient in `[encryption]`, ASCII-armored.
+pub(crate) fn encrypt_body(config: &Config, body: &str) -> Result<String> {
+    let mut armored = Vec::new();
+    let mut writer = encryptor(config)?.wrap_output(ArmoredWriter::wrap_output(
+        &mut armored,
+        Format::AsciiArmor,
+    )?)?;
+    writer.write_all(body.as_bytes())?;
+    writer.finish()?.finish()?;
+    let mut armored = String::from_utf8(armored).context("age armor is not UTF-8")?;
+    armored.push('\n');
+    Ok(armored)
+}
+
+/// Encrypts `data` to every recipient in `[encryption]`, as a binary age file.
+pub(crate) fn encrypt_data(config: &Config, data: &[u8]) -> Result<Vec<u8>> {
+    let mut encrypted = Vec::new();
+    let mut writer = encryptor(config)?.wrap_output(&mut encrypted)?;
+    writer.write_all(data)?;
+    writer.finish()?;
+    Ok(encrypted)
+}
+
+/// The identities in the file from [`identity_path`], and that path.
+fn identities() -> Result<(PathBuf, Vec<Box<dyn age::Identity>>)> {
+    let path = identity_path().with_context(|| {
+        format!(
+            "No age identity to decrypt with; set {} to your key file",
+            IDENTITY_ENV_VAR
+        )
+    })?;
+    let identities = age::IdentityFile::from_file(path.to_string_lossy().into_owned())
+        .with_context(|| {
+            format!(
+                "Failed to read the age identity {} (set {} to your key file)",
+                path.display(),
+                IDENTITY_ENV_VAR
+            )
+        })?
+        .into_identities()?;
+    Ok((path, identities))
+}
+
+/// Decrypts a body written by [`encrypt_body`] with the identity from [`identity_path`].
+pub(crate) fn decrypt_body(armored: &str) -> Result<String> {
+    let (path, identities) = identities()?;
+    decrypt_with(armored, &identities).map_err(|err| match err.downcast_ref() {
+        Some(age::DecryptError::NoMatchingKeys) => anyhow::anyhow!(
+            "The entry is not encrypted to the identity in {}",
+            path.display()
+        ),
+        _ => err,
+    })
+}
+
+/// Decrypts data written by [`encrypt_data`] with the identity from [`identity_path`].
+pub(crate) fn decrypt_data(encrypted: &[u8]) -> Result<Vec<u8>> {
+    let (path, identities) = identities()?;
+    let decryptor = age::Decryptor::new_buffered(encrypted).context("Not an age encrypted file")?;
+    let mut data = Vec::new();
+    decryptor
+        .decrypt(identities.iter().map(|identity| identity.as_ref()))
+        .map_err(|err| match err {
+            age::DecryptError::NoMatchingKeys => anyhow::anyhow!(
+                "The file is not encrypted to the identity in {}",
+                path.display()
+            ),
+            err => err.into(),
+        })?
+        .read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn decrypt_with(armored: &str, identities: &[Box<dyn age::Identity>]) -> Result<String> {
+    let decryptor = age::Decryptor::new_buffered(ArmoredReader::new(armored.trim().as_bytes()))
+        .context("The entry body is not an age encrypted file")?;
+    let mut body = String::new();
+    decryptor
+        .decrypt(identities.iter().map(|identity| identity.as_ref()))?
+        .read_to_string(&mut body)?;
+    Ok(body)
+}
+
+/// An entry with its body in plain text, as returned by [`read_entry`].
+#[derive(Debug)]
+pub struct EntryContent {
+    pub kind: &'static str,
+    pub title: String,
+    pub path: PathBuf,
+    pub encrypted: bool,
+    /// The whole entry file, frontmatter included, with the body decrypted.
+    pub content: String,
+    pub body: String,
+}
+
+fn read<T: ResearchLog>(manager: &LogManager<T>, log: T, path: PathBuf) -> Result<EntryContent> {
+    let body = manager.read_plain_body(&log, &path)?;
+    Ok(EntryContent {
+        kind: T::KIND,
+        title: log.base().title.clone(),
+        encrypted: log.base().encrypted,
+        content: update_markdown_frontmatter(&log, &body)?,
+        body,
+        path,
+    })
+}
+
+/// Reads an entry of any type, decrypting its body if it is encrypted.
+pub fn read_entry(partial_id: &str) -> Result<EntryContent> {
+    let config = load_config()?;
+    let h_manager = HypothesisManager::new(config.clone());
+    let l_manager = LiteratureManager::new(config.clone());
+    let k_manager = KnowledgeManager::new(config.clone());
+    let j_manager = JournalManager::new(config.clone());
+    let d_manager = DatasetManager::new(config.clone());
+    let m_manager = MeetingManager::new(config.clone());
+    let i_manager = IdeaManager::new(config.clone());
+    let e_manager = ExperimentManager::new(config);
+
+    if let Ok((log, path)) = h_manager.find(partial_id) {
+        read(&h_manager.manager, log, path)
+    } else if let Ok((log, path)) = l_manager.find(partial_id) {
+        read(&l_manager.manager, log, path)
+    } else if let Ok((log, path)) = k_manager.find(partial_id) {
+        read(&k_manager.manager, log, path)
+    } else if let Ok((log, path)) = j_manager.find(partial_id) {
+        read(&j_manager.manager, log, path)
+    } else if let Ok((log, path)) = d_manager.find(partial_id) {
+        read(&d_manager.manager, log, path)
+    } else if let Ok((log, path)) = m_manager.find(partial_id) {
+        read(&m_manager.manager, log, path)
+    } else if let Ok((log, path)) = i_manager.find(partial_id) {
+        read(&i_manager.manager, log, path)
+    } else if let Ok((log, path)) = e_manager.find(partial_id) {
+        read(&e_manager.manager, log, path)
+    } else {
+        Err(anyhow::anyhow!(
+            "No entry found with ID starting with '{}'",
+            partial_id
+        ))
+    }
+}
+
+/// Replaces the body of an entry of any type, encrypting it again if the
+/// entry is encrypted.
+pub fn write_entry_body(partial_id: &str, body: &str) -> Result<PathBuf> {
+    let config = load_config()?;
+    let h_manager = HypothesisManager::new(config.clone());
+    let l_manager = LiteratureManager::new(config.clone());
+    let k_manager = KnowledgeManager::new(config.clone());
+    let j_manager = JournalManager::new(config.clone());
+    let d_manager = DatasetManager::new(config.clone());
+    let m_manager = MeetingManager::new(config.clone());
+    let i_manager = IdeaManager::new(config.clone());
+    let e_manager = ExperimentManager::new(config);
+    let edit = |_: &str| body.to_string();
+
+    if h_manager.find(partial_id).is_ok() {
+        Ok(h_manager.manager.update_body(partial_id, edit)?.1)
+    } else if l_manager.find(partial_id).is_ok() {
+        Ok(l_manager.manager.update_body(partial_id, edit)?.1)
+    } else if k_manager.find(partial_id).is_ok() {
+        Ok(k_manager.manager.update_body(partial_id, edit)?.1)
+    } else if j_manager.find(partial_id).is_ok() {
+        Ok(j_manager.manager.update_body(partial_id, edit)?.1)
+    } else if d_manager.find(partial_id).is_ok() {
+        Ok(d_manager.manager.update_body(partial_id, edit)?.1)
+    } else if m_manager.find(partial_id).is_ok() {
+        Ok(m_manager.manager.update_body(partial_id, edit)?.1)
+    } else if i_manager.find(partial_id).is_ok() {
+        Ok(i_manager.manager.update_body(partial_id, edit)?.1)
+    } else if e_manager.find(partial_id).is_ok() {
+        Ok(e_manager.manager.update_body(partial_id, edit)?.1)
+    } else {
+        Err(anyhow::anyhow!(
+            "No entry found with ID starting with '{}'",
+            partial_id
+        ))
+    }
+}
+
+fn set_encrypted<T: ResearchLog>(
+    manager: &LogManager<T>,
+    mut log: T,
+    path: &Path,
+    encrypt: bool,
+) -> Result<PathBuf> {
+    match (log.base().encrypted, encrypt) {
+        (true, true) => return Err(anyhow::anyhow!("The entry is already encrypted")),
+        (false, false) => return Err(anyhow::anyhow!("The entry is not encrypted")),
+        _ => {}
+    }
+    let body = manager.read_plain_body(&log, path)?;
+    log.base_mut().encrypted = encrypt;
+    manager.write_body(&mut log, path, &body)?;
+    Ok(path.to_path_buf())
+}
+
+fn set_entry_encryption(partial_id: &str, encrypt: bool) -> Result<PathBuf> {
+    let config = load_config()?;
+    let h_manager = HypothesisManager::new(config.clone());
+    let l_manager = LiteratureManager::new(config.clone());
+    let k_manager = KnowledgeManager::new(config.clone());
+    let j_manager = JournalManager::new(config.clone());
+    let d_manager = DatasetManager::new(config.clone());
+    let m_manager = MeetingManager::new(config.clone());
+    let i_manager = IdeaManager::new(config.clone());
+    let e_manager = ExperimentManager::new(config);
+
+    if let Ok((log, path)) = h_manager.find(partial_id) {
+        set_encrypted(&h_manager.manager, log, &path, encrypt)
+    } else if let Ok((log, path)) = l_manager.find(partial_id) {
+        set_encrypted(&l_manager.manager, log, &path, encrypt)
+    } else if let Ok((log, path)) = k_manager.find(partial_id) {
+        set_encrypted(&k_manager.manager, log, &path, encrypt)
+    } else if let Ok((log, path)) = j_manager.find(partial_id) {
+        set_encrypted(&j_manager.manager, log, &path, encrypt)
+    } else if let Ok((log, path)) = d_manager.find(partial_id) {
+        set_encrypted(&d_manager.manager, log, &path, encrypt)
+    } else if let Ok((log, path)) = m_manager.find(partial_id) {
+        set_encrypted(&m_manager.manager, log, &path, encrypt)
+    } else if let Ok((log, path)) = i_manager.find(partial_id) {
+        set_encrypted(&i_manager.manager, log, &path, encrypt)
+    } else if let Ok((log, path)) = e_manager.find(partial_id) {
+        set_encrypted(&e_manager.manager, log, &path, encrypt)
+    } else {
+        Err(anyhow::anyhow!(
+            "No entry found with ID starting with '{}'",
+            partial_id
+        ))
+    }
+}
+
+/// Encrypts the body of an entry to the `[encryption]` recipients, leaving
+/// its frontmatter readable. Returns the entry file.
+pub fn encrypt_entry(partial_id: &str) -> Result<PathBuf> {
+    set_entry_encryption(partial_id, true)
+}
+
+/// Stores the body of an encrypted entry in plain text again.
+pub fn decrypt_entry(partial_id: &str) -> Result<PathBuf> {
+    set_entry_encryption(partial_id, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_round_trip() {
+        let identity = age::x25519::Identity::generate();
+        let mut config = Config::default();
+        config.encryption.recipients = vec![identity.to_public().to_string()];
+
+        let body = "## Results\n\nUnpublished: Q = 1.2e6\n";
+        let armored = encrypt_body(&config, body).unwrap();
+        assert!(armored.starts_with("-----BEGIN AGE ENCRYPTED FILE-----"));
+        assert!(!armored.contains("Unpublished"));
+
+        let identities: Vec<Box<dyn age::Identity>> = vec![Box::new(identity)];
+        assert_eq!(decrypt_with(&armored, &identities).unwrap(), body);
+        let other: Vec<Box<dyn age::Identity>> = vec![Box::new(age::x25519::Identity::generate())];
+        assert!(decrypt_with(&armored, &other).is_err());
+
+        config.encryption.recipients.clear();
+        assert!(encrypt_body(&config, body).is_err());
+        assert!(parse_recipient("age1notakey").is_err());
+    }
+}
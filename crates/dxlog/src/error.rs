@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use crate::EntryMatch;
+
+/// Errors callers may want to handle rather than just report.
+///
+/// They are returned inside [`anyhow::Error`]; use `downcast_ref::<LogError>()` to match on them.
+#[derive(Debug, thiserror::Error)]
+pub enum LogError {
+    /// An entry of the same type already has this title (compared case-insensitively).
+    #[error("A {kind} entry titled '{title}' already exists at: {}. Use --allow-duplicate-title to create it anyway", path.display())]
+    DuplicateTitle {
+        kind: String,
+        title: String,
+        path: PathBuf,
+    },
+
+    /// Other entries still reference the entry being deleted.
+    #[error("The {kind} entry '{title}' is referenced by: {}. Use --cascade to remove those references or --force to delete it anyway", sources.join(", "))]
+    Referenced {
+        kind: String,
+        title: String,
+        /// Short ID and title of each referencing entry.
+        sources: Vec<String>,
+    },
+
+    /// No entry matches an ID. `kind` is the log type looked in, or `entry`
+    /// for entries of any type.
+    #[error("No {kind} found with ID starting with '{partial_id}'")]
+    NotFound { kind: String, partial_id: String },
+
+    /// More than one entry of a type matches a partial ID.
+    #[error(
+        "Multiple logs found with ID starting with '{partial_id}'. Please provide more characters."
+    )]
+    Ambiguous {
+        partial_id: String,
+        /// Every matching entry, newest first.
+        matches: Vec<EntryMatch>,
+    },
+}
@@ -0,0 +1,741 @@
+use anyhow::{Context, Result};
+use dxlog_tools::{fetch_run_metrics, TrackedRun, TrackerAuth};
+use minijinja::context;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    dry_run, load_config,
+    log_manager::{ListFilter, LogManager, Renamed},
+    md_frontmatter::serialize_yaml_frontmatter,
+    migration::SCHEMA_VERSION,
+    operations,
+    research_log::ResearchLog,
+    template::{builtin_template, entry_context},
+    utils::{self, Author, BaseLog},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExperimentStatus {
+    Planned,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl fmt::Display for ExperimentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ExperimentStatus::Planned => "planned",
+            ExperimentStatus::Running => "running",
+            ExperimentStatus::Completed => "completed",
+            ExperimentStatus::Failed => "failed",
+        };
+        f.write_str(s)
+    }
+}
+
+impl ExperimentStatus {
+    /// Whether the experiment has finished, one way or the other.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+/// State of the code an experiment was set up with, recorded when the entry
+/// is created so results can be traced back to it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Environment {
+    pub commit: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Whether tracked files had uncommitted changes.
+    pub dirty: bool,
+    /// Dependency lockfile, e.g. `Cargo.lock` or `requirements.txt`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lockfile: Option<PathBuf>,
+    /// Hash of the lockfile as `git hash-object` computes it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lockfile_hash: Option<String>,
+}
+
+impl Environment {
+    /// Records the checked out commit of the repository containing `repo`, and
+    /// the hash of `lockfile` if given.
+    pub fn capture(repo: &Path, lockfile: Option<&Path>) -> Result<Self> {
+        let head = utils::head_state(repo)
+            .context("Cannot record the code state of the experiment (use --no-env to skip it)")?;
+        let lockfile_hash = lockfile
+            .map(|path| {
+                git2::Oid::hash_file(git2::ObjectType::Blob, repo.join(path))
+                    .with_context(|| format!("Failed to hash lockfile {}", path.display()))
+            })
+            .transpose()?;
+        Ok(Self {
+            commit: head.commit,
+            branch: head.branch,
+            dirty: head.dirty,
+            lockfile: lockfile.map(Path::to_path_buf),
+            lockfile_hash: lockfile_hash.map(|oid| oid.to_string()),
+        })
+    }
+
+    /// The commit, abbreviated, with `-dirty` appended if there were changes.
+    pub fn describe(&self) -> String {
+        let short = &self.commit[..self.commit.len().min(12)];
+        if self.dirty {
+            format!("{}-dirty", short)
+        } else {
+            short.to_string()
+        }
+    }
+}
+
+/// A run of code whose setup and results are recorded, usually to test a
+/// hypothesis.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExperimentLog {
+    #[serde(flatten)]
+    pub base: BaseLog,
+    pub status: ExperimentStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Environment>,
+    /// Results of the experiment by name, e.g. `accuracy: 0.91`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metrics: BTreeMap<String, f64>,
+    /// Run on W&B or MLflow the experiment was tracked in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_url: Option<String>,
+    /// Jupyter notebooks the experiment was run or analysed in.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notebooks: Vec<NotebookAttachment>,
+}
+
+/// A notebook attached with `dxlog experiment attach-notebook`. Paths are
+/// relative to the repository root unless the notebook lies outside it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NotebookAttachment {
+    /// The copy in the attachments directory, or the notebook itself when linked.
+    pub path: PathBuf,
+    /// Hash of the notebook as `git hash-object` computes it, so a linked
+    /// notebook can be checked for later edits.
+    pub hash: String,
+    /// Whether the notebook was linked where it lies instead of copied.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub linked: bool,
+    /// HTML export of the notebook, kept for archival.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html: Option<PathBuf>,
+}
+
+impl ResearchLog for ExperimentLog {
+    type Status = ExperimentStatus;
+    const KIND: &'static str = "experiment";
+    const HANDLE_PREFIX: &'static str = "E";
+
+    fn base(&self) -> &BaseLog {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseLog {
+        &mut self.base
+    }
+
+    fn status(&self) -> &Self::Status {
+        &self.status
+    }
+
+    fn status_mut(&mut self) -> &mut Self::Status {
+        &mut self.status
+    }
+
+    fn new(title: String, tags: HashSet<String>, author: Author) -> Self {
+        let now = utils::now();
+        Self {
+            base: BaseLog {
+                schema_version: SCHEMA_VERSION,
+                id: Uuid::now_v7(),
+                handle: None,
+                created_at: now,
+                updated_at: Some(now),
+                title,
+                tags,
+                project: None,
+                milestone: None,
+                created_by: author,
+                assignee: None,
+                references: HashSet::new(),
+                history: Vec::new(),
+                attachments: Vec::new(),
+                encrypted: false,
+            },
+            status: ExperimentStatus::Planned,
+            environment: None,
+            metrics: BTreeMap::new(),
+            run_url: None,
+            notebooks: Vec::new(),
+        }
+    }
+
+    fn update_status(&mut self, new_status: Self::Status) {
+        self.status = new_status;
+    }
+
+    fn is_complete(&self) -> bool {
+        self.status.is_finished()
+    }
+
+    fn get_target_path(&self, config: &Config, current_path: &Path) -> Result<PathBuf> {
+        let filename = current_path.file_name().unwrap();
+        if let Some(dir) = config.layout_dir(Self::KIND, &self.status.to_string()) {
+            return Ok(dir.join(filename));
+        }
+        Ok(config.storage.experiments_dir.join(filename))
+    }
+}
+
+pub struct ExperimentManager {
+    pub manager: LogManager<ExperimentLog>,
+}
+
+impl ExperimentManager {
+    pub fn new(config: Config) -> Self {
+        let search_dirs = vec![config.storage.experiments_dir.clone()];
+
+        Self {
+            manager: LogManager::<ExperimentLog>::new(config, search_dirs),
+        }
+    }
+
+    /// Creates a planned experiment with the environment already captured, if any.
+    pub fn create(
+        &self,
+        title: &str,
+        environment: Option<Environment>,
+        tags: Option<Vec<String>>,
+        body: Option<&str>,
+        template: Option<&str>,
+        allow_duplicate_title: bool,
+    ) -> Result<ExperimentLog> {
+        let config = &self.manager.config;
+        let author = utils::get_author(config)?;
+        let mut experiment =
+            ExperimentLog::new(title.to_string(), utils::normalize_tags(tags), author);
+        experiment.environment = environment;
+        self.manager.assign_ids(&mut experiment)?;
+
+        let yaml = serialize_yaml_frontmatter(&experiment)?;
+        let template_path =
+            config.template_for(ExperimentLog::KIND, template, &experiment.base.tags)?;
+        // Repositories set up before experiments existed have no experiment template yet.
+        let template_content = match (template_path.exists(), template) {
+            (false, None | Some("default")) => builtin_template(ExperimentLog::KIND)
+                .unwrap_or_default()
+                .to_string(),
+            _ => utils::load_entry_content(&template_path)?,
+        };
+
+        let body = body.map(str::trim);
+        let env = experiment.environment.as_ref();
+        let rendered = utils::render_entry(
+            &template_content,
+            context! {
+                body => body,
+                commit => env.map(|e| e.commit.clone()),
+                branch => env.and_then(|e| e.branch.clone()),
+                dirty => env.map(|e| e.dirty),
+                lockfile => env.and_then(|e| e.lockfile.as_ref()).map(|p| p.display().to_string()),
+                lockfile_hash => env.and_then(|e| e.lockfile_hash.clone()),
+                ..entry_context(config, &experiment.base, yaml)
+            },
+            body,
+        )?;
+
+        self.manager
+            .save_log(&experiment, &rendered, allow_duplicate_title)?;
+        Ok(experiment)
+    }
+
+    /// Retitles an entry, renaming its file and updating links to it.
+    pub fn rename(
+        &self,
+        partial_id: &str,
+        title: &str,
+        allow_duplicate_title: bool,
+    ) -> Result<Renamed<ExperimentLog>> {
+        self.manager
+            .rename_log(partial_id, title, allow_duplicate_title)
+    }
+
+    /// Records metrics on an experiment, replacing earlier values of the same
+    /// metrics.
+    pub fn add_metrics(
+        &self,
+        partial_id: &str,
+        metrics: BTreeMap<String, f64>,
+    ) -> Result<ExperimentLog> {
+        if metrics.is_empty() {
+            return Err(anyhow::anyhow!("No metrics given"));
+        }
+        let (mut experiment, file_path) = self.manager.find_log(partial_id)?;
+        experiment.metrics.extend(metrics);
+        self.manager.update_log(&mut experiment, &file_path)?;
+        Ok(experiment)
+    }
+
+    /// Links an experiment to its run on W&B or MLflow. With `fetch`, the
+    /// final metrics of the run are recorded too, as by [`Self::add_metrics`];
+    /// they are returned along with the entry.
+    pub fn link_run(
+        &self,
+        partial_id: &str,
+        url: &str,
+        fetch: Option<&TrackerAuth>,
+    ) -> Result<(ExperimentLog, BTreeMap<String, f64>)> {
+        let run = TrackedRun::parse(url)?;
+        let (mut experiment, file_path) = self.manager.find_log(partial_id)?;
+        let metrics = match fetch {
+            Some(auth) => fetch_run_metrics(&run, auth).with_context(|| {
+                format!("Failed to fetch the metrics of the {} run", run.tracker())
+            })?,
+            None => BTreeMap::new(),
+        };
+        experiment.run_url = Some(url.trim().to_string());
+        experiment.metrics.extend(metrics.clone());
+        self.manager.update_log(&mut experiment, &file_path)?;
+        Ok((experiment, metrics))
+    }
+
+    /// Attaches a Jupyter notebook to an experiment. The notebook is copied
+    /// into `<attachments-dir>/<handle>/`, or with `link` only hashed and
+    /// recorded where it lies. With `render`, an HTML export is stored next
+    /// to the copy. Attaching the same notebook again replaces the record.
+    /// `dxlog undo` removes the copy and the export again.
+    pub fn attach_notebook(
+        &self,
+        partial_id: &str,
+        notebook: &Path,
+        link: bool,
+        render: bool,
+    ) -> Result<(ExperimentLog, NotebookAttachment)> {
+        let config = &self.manager.config;
+        let content = fs::read_to_string(notebook)
+            .with_context(|| format!("Failed to read notebook {}", notebook.display()))?;
+        let file_name = notebook
+            .file_name()
+            .with_context(|| format!("{} is not a file", notebook.display()))?;
+        // Rendering also checks that the file is a notebook at all.
+        let html = crate::export::notebook_to_html(
+            &content,
+            &file_name.to_string_lossy(),
+            &config.date_format,
+        )
+        .with_context(|| format!("Failed to read {} as a notebook", notebook.display()))?;
+        let hash = git2::Oid::hash_file(git2::ObjectType::Blob, notebook)
+            .with_context(|| format!("Failed to hash notebook {}", notebook.display()))?;
+
+        let (mut experiment, file_path) = self.manager.find_log(partial_id)?;
+        let dir = config.storage.attachments_dir.join(
+            experiment
+                .base
+                .handle
+                .clone()
+                .unwrap_or_else(|| experiment.base.id.to_string()),
+        );
+        let description = format!(
+            "attach notebook '{}' to experiment '{}'",
+            file_name.to_string_lossy(),
+            experiment.base.title
+        );
+        let attachment = operations::record(config, description, || {
+            let path = if link {
+                fs::canonicalize(notebook)?
+            } else {
+                let target = dir.join(file_name);
+                dry_run::write(&target, &content)?;
+                target
+            };
+            let html = if render {
+                let target = dir.join(Path::new(file_name).with_extension("html"));
+                dry_run::write(&target, &html)?;
+                Some(target)
+            } else {
+                None
+            };
+
+            let root = fs::canonicalize(&config.root).unwrap_or_else(|_| config.root.clone());
+            let relative = |path: PathBuf| -> PathBuf {
+                path.strip_prefix(&config.root)
+                    .or_else(|_| path.strip_prefix(&root))
+                    .map(Path::to_path_buf)
+                    .unwrap_or(path.clone())
+            };
+            let attachment = NotebookAttachment {
+                path: relative(path),
+                hash: hash.to_string(),
+                linked: link,
+                html: html.map(relative),
+            };
+            experiment
+                .notebooks
+                .retain(|existing| existing.path != attachment.path);
+            experiment.notebooks.push(attachment.clone());
+            self.manager.update_log(&mut experiment, &file_path)?;
+            Ok(attachment)
+        })?;
+        Ok((experiment, attachment))
+    }
+
+    /// Lines up the metrics of two experiments, by metric name.
+    pub fn compare(&self, partial_id_a: &str, partial_id_b: &str) -> Result<MetricComparison> {
+        let (a, _) = self.manager.find_log(partial_id_a)?;
+        let (b, _) = self.manager.find_log(partial_id_b)?;
+        let names: BTreeSet<&String> = a.metrics.keys().chain(b.metrics.keys()).collect();
+        let metrics = names
+            .into_iter()
+            .map(|name| MetricDiff {
+                name: name.clone(),
+                a: a.metrics.get(name).copied(),
+                b: b.metrics.get(name).copied(),
+            })
+            .collect();
+        Ok(MetricComparison { a, b, metrics })
+    }
+
+    pub fn update_status(
+        &self,
+        partial_id: &str,
+        new_status: ExperimentStatus,
+        reason: Option<&str>,
+        force: bool,
+    ) -> Result<()> {
+        self.manager
+            .change_status(partial_id, new_status, reason, force)?;
+        Ok(())
+    }
+
+    pub fn list(&self, filter: &ListFilter<ExperimentStatus>) -> Result<Vec<ExperimentLog>> {
+        self.manager.list_logs(filter)
+    }
+
+    pub fn find(&self, partial_id: &str) -> Result<(ExperimentLog, PathBuf)> {
+        self.manager.find_log(partial_id)
+    }
+}
+
+/// Metrics of two experiments side by side, as returned by
+/// [`ExperimentManager::compare`].
+#[derive(Debug)]
+pub struct MetricComparison {
+    pub a: ExperimentLog,
+    pub b: ExperimentLog,
+    /// Every metric recorded on either experiment, by name.
+    pub metrics: Vec<MetricDiff>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricDiff {
+    pub name: String,
+    pub a: Option<f64>,
+    pub b: Option<f64>,
+}
+
+impl MetricDiff {
+    /// The change from the first experiment to the second, rounded to the
+    /// precision of the values themselves, with the relative change when
+    /// there is one: `+0.02 (+2.2%)`. `None` unless both recorded the metric.
+    pub fn display_delta(&self) -> Option<String> {
+        let (a, b) = (self.a?, self.b?);
+        let decimals = decimals(a).max(decimals(b));
+        let mut text = format!("{:+.*}", decimals, b - a);
+        if a != 0.0 {
+            text.push_str(&format!(" ({:+.1}%)", (b - a) / a.abs() * 100.0));
+        }
+        Some(text)
+    }
+}
+
+/// Number of decimals `value` is written with, up to 10.
+fn decimals(value: f64) -> usize {
+    let text = value.to_string();
+    text.split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len().min(10))
+}
+
+/// Parses a metric given as `name=value`, e.g. `accuracy=0.91`.
+pub fn parse_metric(text: &str) -> Result<(String, f64)> {
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid metric '{}'. Expected name=value, e.g. accuracy=0.91",
+            text
+        )
+    };
+    let (name, value) = text.split_once('=').ok_or_else(invalid)?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(invalid());
+    }
+    let value = value.trim().parse::<f64>().map_err(|_| invalid())?;
+    Ok((name.to_string(), value))
+}
+
+/// Reads metrics from a `.json` or `.csv` file.
+///
+/// A JSON file holds an object of metrics; nested objects are flattened into
+/// dotted names (`{"val": {"loss": 0.1}}` gives `val.loss`). A CSV file has a
+/// header row of metric names and the values are taken from its last row, so
+/// a log with one row per epoch gives the final metrics. Values that are not
+/// numbers, such as timestamps or run names, are skipped.
+pub fn read_metrics_file(path: &Path) -> Result<BTreeMap<String, f64>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read metrics file {}", path.display()))?;
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    let metrics = match extension.as_deref() {
+        Some("json") => {
+            let value: serde_json::Value = serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse {} as JSON", path.display()))?;
+            if !value.is_object() {
+                return Err(anyhow::anyhow!(
+                    "{} does not hold a JSON object of metrics",
+                    path.display()
+                ));
+            }
+            let mut metrics = BTreeMap::new();
+            flatten_json("", &value, &mut metrics);
+            metrics
+        }
+        Some("csv") => csv_metrics(&content)
+            .with_context(|| format!("Failed to parse {} as CSV", path.display()))?,
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Unsupported metrics file {}. Expected a .json or .csv file",
+                path.display()
+            ))
+        }
+    };
+    if metrics.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No numeric metrics found in {}",
+            path.display()
+        ));
+    }
+    Ok(metrics)
+}
+
+fn flatten_json(prefix: &str, value: &serde_json::Value, metrics: &mut BTreeMap<String, f64>) {
+    match value {
+        serde_json::Value::Number(number) => {
+            if let Some(number) = number.as_f64() {
+                metrics.insert(prefix.to_string(), number);
+            }
+        }
+        serde_json::Value::Object(object) => {
+            for (key, value) in object {
+                let name = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_json(&name, value, metrics);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn csv_metrics(content: &str) -> Result<BTreeMap<String, f64>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+    let headers = reader.headers()?.clone();
+    let Some(last) = reader.records().last().transpose()? else {
+        return Ok(BTreeMap::new());
+    };
+    Ok(headers
+        .iter()
+        .zip(last.iter())
+        .filter(|(name, _)| !name.is_empty())
+        .filter_map(|(name, value)| Some((name.to_string(), value.parse::<f64>().ok()?)))
+        .collect())
+}
+
+/// Records the state of the code in `repo`, relative to the repository root,
+/// or of the repository holding the research logs.
+pub fn capture_environment(repo: Option<&Path>, lockfile: Option<&Path>) -> Result<Environment> {
+    let config = load_config()?;
+    let repo = repo.map_or_else(|| config.root.clone(), |repo| config.root.join(repo));
+    Environment::capture(&repo, lockfile)
+}
+
+pub fn create_experiment(
+    title: &str,
+    environment: Option<Environment>,
+    tags: Option<Vec<String>>,
+    body: Option<&str>,
+    template: Option<&str>,
+    allow_duplicate_title: bool,
+) -> Result<ExperimentLog> {
+    let config = load_config()?;
+    let manager = ExperimentManager::new(config);
+    manager.create(
+        title,
+        environment,
+        tags,
+        body,
+        template,
+        allow_duplicate_title,
+    )
+}
+
+pub fn add_experiment_metrics(
+    partial_id: &str,
+    metrics: BTreeMap<String, f64>,
+) -> Result<ExperimentLog> {
+    let config = load_config()?;
+    let manager = ExperimentManager::new(config);
+    manager.add_metrics(partial_id, metrics)
+}
+
+pub fn link_experiment_run(
+    partial_id: &str,
+    url: &str,
+    fetch: Option<&TrackerAuth>,
+) -> Result<(ExperimentLog, BTreeMap<String, f64>)> {
+    let config = load_config()?;
+    let manager = ExperimentManager::new(config);
+    manager.link_run(partial_id, url, fetch)
+}
+
+pub fn attach_experiment_notebook(
+    partial_id: &str,
+    notebook: &Path,
+    link: bool,
+    render: bool,
+) -> Result<(ExperimentLog, NotebookAttachment)> {
+    let config = load_config()?;
+    let manager = ExperimentManager::new(config);
+    manager.attach_notebook(partial_id, notebook, link, render)
+}
+
+pub fn compare_experiments(partial_id_a: &str, partial_id_b: &str) -> Result<MetricComparison> {
+    let config = load_config()?;
+    let manager = ExperimentManager::new(config);
+    manager.compare(partial_id_a, partial_id_b)
+}
+
+pub fn update_experiment_status(
+    partial_id: &str,
+    new_status: ExperimentStatus,
+    reason: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let manager = ExperimentManager::new(config);
+    manager.update_status(partial_id, new_status, reason, force)
+}
+
+pub fn rename_experiment(
+    partial_id: &str,
+    title: &str,
+    allow_duplicate_title: bool,
+) -> Result<Renamed<ExperimentLog>> {
+    let config = load_config()?;
+    let manager = ExperimentManager::new(config);
+    manager.rename(partial_id, title, allow_duplicate_title)
+}
+
+pub fn find_experiment(partial_id: &str) -> Result<ExperimentLog> {
+    let config = load_config()?;
+    let manager = ExperimentManager::new(config);
+    Ok(manager.find(partial_id)?.0)
+}
+
+pub fn list_experiments(filter: &ListFilter<ExperimentStatus>) -> Result<Vec<ExperimentLog>> {
+    list_experiments_in(&load_config()?, filter)
+}
+
+/// Like [`list_experiments`], in the repository of `config`.
+pub fn list_experiments_in(
+    config: &Config,
+    filter: &ListFilter<ExperimentStatus>,
+) -> Result<Vec<ExperimentLog>> {
+    let manager = ExperimentManager::new(config.clone());
+    manager.list(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::author;
+
+    #[test]
+    fn test_environment_in_frontmatter() {
+        let mut experiment = ExperimentLog::new("Baseline".to_string(), HashSet::new(), author());
+        let yaml = serde_yaml::to_string(&experiment).unwrap();
+        assert!(!yaml.contains("environment"));
+
+        experiment.environment = Some(Environment {
+            commit: "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b".to_string(),
+            branch: Some("main".to_string()),
+            dirty: true,
+            lockfile: Some(PathBuf::from("Cargo.lock")),
+            lockfile_hash: Some("2c26b46b68ffc68ff99b453c1d30413413422d70".to_string()),
+        });
+        let yaml = serde_yaml::to_string(&experiment).unwrap();
+        assert!(yaml.contains("commit: 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b"));
+        assert!(yaml.contains("dirty: true"));
+
+        let parsed: ExperimentLog = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.environment, experiment.environment);
+        assert_eq!(parsed.environment.unwrap().describe(), "9f86d081884c-dirty");
+    }
+
+    #[test]
+    fn test_parse_metric() {
+        assert_eq!(
+            parse_metric("accuracy=0.91").unwrap(),
+            ("accuracy".to_string(), 0.91)
+        );
+        assert_eq!(
+            parse_metric(" val loss = 1e-3 ").unwrap(),
+            ("val loss".to_string(), 0.001)
+        );
+        assert!(parse_metric("accuracy").is_err());
+        assert!(parse_metric("=0.91").is_err());
+        assert!(parse_metric("accuracy=high").is_err());
+    }
+
+    #[test]
+    fn test_csv_metrics_from_last_row() {
+        let csv = "epoch,loss,accuracy,run\n1,0.52,0.71,a\n2,0.31,0.84,a\n";
+        let metrics = csv_metrics(csv).unwrap();
+        assert_eq!(metrics.len(), 3);
+        assert_eq!(metrics["epoch"], 2.0);
+        assert_eq!(metrics["loss"], 0.31);
+        assert!(!metrics.contains_key("run"));
+    }
+
+    #[test]
+    fn test_display_delta() {
+        let diff = |a, b| MetricDiff {
+            name: "accuracy".to_string(),
+            a,
+            b,
+        };
+        assert_eq!(
+            diff(Some(0.91), Some(0.93)).display_delta().unwrap(),
+            "+0.02 (+2.2%)"
+        );
+        assert_eq!(diff(Some(0.0), Some(-1.5)).display_delta().unwrap(), "-1.5");
+        assert_eq!(diff(Some(0.91), None).display_delta(), None);
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::{Context, Result};
+use std::{fs, path::Path};
+
+use super::site::markdown_to_html;
+use crate::{
+    load_config, md_frontmatter::extract_frontmatter, utils, KnowledgeLog, KnowledgeManager,
+    KnowledgeStatus, ListFilter,
+};
+
+/// Heading of the section used as the back of a card, when an entry has one.
+const SUMMARY_HEADING: &str = "## Summary";
+
+struct Card {
+    id: String,
+    front: String,
+    back: String,
+    tags: Vec<String>,
+}
+
+/// Writes published knowledge entries as Anki flashcards: the title on the
+/// front, the `## Summary` section (or the whole body) on the back, and the
+/// entry's tags. The file is Anki's tab-separated text format; the entry ID is
+/// the note's GUID, so importing again updates the cards instead of duplicating them.
+///
+/// Returns the number of cards written.
+pub fn export_anki(out: &Path) -> Result<usize> {
+    let config = load_config()?;
+    let knowledge = KnowledgeManager::new(config)
+        .manager
+        .list_log_files(&ListFilter::with_status(KnowledgeStatus::Published))?;
+
+    let mut cards = Vec::new();
+    for (log, path) in knowledge {
+        let content = utils::load_entry_content(&path)?;
+        let (_, body) = extract_frontmatter::<KnowledgeLog>(&content)?;
+        let mut tags: Vec<String> = log.base.tags.iter().map(|t| t.replace(' ', "_")).collect();
+        tags.sort();
+        cards.push(Card {
+            id: log.base.id.to_string(),
+            back: markdown_to_html(&card_back(&body)).trim_end().to_string(),
+            front: log.base.title,
+            tags,
+        });
+    }
+
+    fs::write(out, render(&cards))
+        .with_context(|| format!("Failed to write file: {}", out.display()))?;
+    Ok(cards.len())
+}
+
+/// The `## Summary` section of `body` if it has a non-empty one, otherwise the
+/// body without its leading title heading.
+fn card_back(body: &str) -> String {
+    let lines: Vec<&str> = body.lines().collect();
+    if let Some(start) = lines.iter().position(|l| l.trim_end() == SUMMARY_HEADING) {
+        let end = lines[start + 1..]
+            .iter()
+            .position(|l| l.starts_with("# ") || l.starts_with("## "))
+            .map_or(lines.len(), |i| start + 1 + i);
+        let summary = lines[start + 1..end].join("\n");
+        if !summary.trim().is_empty() {
+            return summary.trim().to_string();
+        }
+    }
+    let body = body.trim_start();
+    match body.strip_prefix("# ") {
+        Some(rest) => rest.split_once('\n').map_or("", |(_, rest)| rest),
+        None => body,
+    }
+    .trim()
+    .to_string()
+}
+
+fn render(cards: &[Card]) -> String {
+    let mut out = String::from("#separator:tab\n#html:true\n#guid column:1\n#tags column:4\n");
+    for card in cards {
+        let fields = [
+            card.id.clone(),
+            card.front.clone(),
+            card.back.clone(),
+            card.tags.join(" "),
+        ];
+        let fields: Vec<String> = fields.iter().map(|f| quote(f)).collect();
+        out.push_str(&fields.join("\t"));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quotes a field containing tabs, newlines or quotes, as Anki's importer expects.
+fn quote(field: &str) -> String {
+    if field.contains(['\t', '\n', '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_back() {
+        assert_eq!(
+            card_back("# Title\n\nBody text\n\n## Summary\n\nShort *answer*\n\n## Details\n\nMore"),
+            "Short *answer*"
+        );
+        assert_eq!(
+            card_back("# Title\n\nBody text\n\n## Summary\n\n"),
+            "Body text\n\n## Summary"
+        );
+        assert_eq!(quote("say \"hi\"\n"), "\"say \"\"hi\"\"\n\"");
+    }
+}
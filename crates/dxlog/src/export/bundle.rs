@@ -0,0 +1,461 @@
+//! Every entry of a repository in one versioned JSON file, for backups,
+//! moving to another machine and analysis with other tools.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+use crate::{
+    handle, load_config,
+    log_manager::LogManager,
+    md_frontmatter::update_markdown_frontmatter,
+    migration,
+    research_log::ResearchLog,
+    search::check_kind,
+    utils::{self, BaseLog, TagFilter},
+    Config, CustomLog, CustomLogManager, DatasetLog, DatasetManager, ExperimentLog,
+    ExperimentManager, HypothesisLog, HypothesisManager, IdeaLog, IdeaManager, JournalLog,
+    JournalManager, KnowledgeLog, KnowledgeManager, ListFilter, LiteratureLog, LiteratureManager,
+    MeetingLog, MeetingManager,
+};
+
+/// Value of [`Bundle::format`], telling bundles apart from other JSON files.
+pub const BUNDLE_FORMAT: &str = "dxlog-bundle";
+
+/// Version of the bundle layout written by [`export_bundle`]. Bundles of a
+/// later version are refused on import.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// The entries of a repository, as written by [`export_bundle`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Bundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<FixedOffset>,
+    pub entries: Vec<BundleEntry>,
+}
+
+/// One entry of a [`Bundle`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleEntry {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Where the entry was stored, relative to the repository root.
+    pub path: PathBuf,
+    /// The frontmatter, references included.
+    pub frontmatter: Value,
+    /// The markdown below the frontmatter, still encrypted for encrypted entries.
+    pub body: String,
+}
+
+/// Outcome of [`import_bundle`].
+#[derive(Debug, Default, Serialize)]
+pub struct BundleImport {
+    pub imported: usize,
+    /// Entries left out because an entry with the same ID exists.
+    pub skipped: usize,
+    /// Handles taken by other entries here, with the ones given instead.
+    pub renumbered: Vec<(String, String)>,
+}
+
+fn collect<T: ResearchLog>(manager: &LogManager<T>, entries: &mut Vec<BundleEntry>) -> Result<()> {
+    for (log, path) in manager.list_log_files(&ListFilter::default())? {
+        entries.push(BundleEntry {
+            kind: T::KIND.to_string(),
+            path: path
+                .strip_prefix(&manager.config.root)
+                .unwrap_or(&path)
+                .to_path_buf(),
+            frontmatter: serde_json::to_value(&log)?,
+            body: manager.read_body(&path)?,
+        });
+    }
+    Ok(())
+}
+
+fn collect_declared(manager: &CustomLogManager, entries: &mut Vec<BundleEntry>) -> Result<()> {
+    for (log, path) in manager.list(None, &TagFilter::default())? {
+        entries.push(BundleEntry {
+            kind: log.kind.clone(),
+            path: path
+                .strip_prefix(&manager.config.root)
+                .unwrap_or(&path)
+                .to_path_buf(),
+            frontmatter: serde_json::to_value(&log)?,
+            body: manager.read_body(&path)?,
+        });
+    }
+    Ok(())
+}
+
+fn bundle(config: &Config) -> Result<Bundle> {
+    let mut entries = Vec::new();
+    collect(
+        &HypothesisManager::new(config.clone()).manager,
+        &mut entries,
+    )?;
+    collect(
+        &LiteratureManager::new(config.clone()).manager,
+        &mut entries,
+    )?;
+    collect(&KnowledgeManager::new(config.clone()).manager, &mut entries)?;
+    collect(&JournalManager::new(config.clone()).manager, &mut entries)?;
+    collect(&DatasetManager::new(config.clone()).manager, &mut entries)?;
+    collect(&MeetingManager::new(config.clone()).manager, &mut entries)?;
+    collect(&IdeaManager::new(config.clone()).manager, &mut entries)?;
+    collect(
+        &ExperimentManager::new(config.clone()).manager,
+        &mut entries,
+    )?;
+    for name in config.types.keys() {
+        collect_declared(&CustomLogManager::new(config.clone(), name)?, &mut entries)?;
+    }
+    Ok(Bundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: utils::now(),
+        entries,
+    })
+}
+
+/// Writes every entry, with its frontmatter and body, to a JSON bundle that
+/// [`import_bundle`] reads back. Entries of the types declared in `[types]`
+/// are included; attached files are not.
+///
+/// Returns the number of entries written.
+pub fn export_bundle(out: &Path) -> Result<usize> {
+    let config = load_config()?;
+    let bundle = bundle(&config)?;
+    fs::write(out, serde_json::to_string_pretty(&bundle)?)
+        .with_context(|| format!("Failed to write file: {}", out.display()))?;
+    Ok(bundle.entries.len())
+}
+
+/// The entries of type `T` in a bundle, upgraded to the current frontmatter.
+fn parse<T: ResearchLog>(entries: &[BundleEntry]) -> Result<Vec<(T, &str)>> {
+    entries
+        .iter()
+        .filter(|entry| entry.kind == T::KIND)
+        .map(|entry| {
+            let invalid = || format!("Invalid {} {} in the bundle", T::KIND, entry.path.display());
+            let mut frontmatter = serde_yaml::to_value(&entry.frontmatter).with_context(invalid)?;
+            migration::upgrade(T::KIND, &mut frontmatter).with_context(invalid)?;
+            let log = serde_yaml::from_value(frontmatter).with_context(invalid)?;
+            Ok((log, entry.body.as_str()))
+        })
+        .collect()
+}
+
+/// The entries of the type declared in `[types]` that `manager` is for, upgraded
+/// to the current frontmatter.
+fn parse_declared<'a>(
+    manager: &CustomLogManager,
+    entries: &'a [BundleEntry],
+) -> Result<Vec<(CustomLog, &'a str)>> {
+    let name = &manager.log_type.name;
+    entries
+        .iter()
+        .filter(|entry| entry.kind == *name)
+        .map(|entry| {
+            let invalid = || format!("Invalid {} {} in the bundle", name, entry.path.display());
+            let mut frontmatter = serde_yaml::to_value(&entry.frontmatter).with_context(invalid)?;
+            migration::upgrade(name, &mut frontmatter).with_context(invalid)?;
+            let log: CustomLog = serde_yaml::from_value(frontmatter).with_context(invalid)?;
+            manager.log_type.status(&log.status).with_context(invalid)?;
+            Ok((log, entry.body.as_str()))
+        })
+        .collect()
+}
+
+/// The IDs and handles of one log type in the repository imported into.
+struct Taken {
+    ids: HashSet<Uuid>,
+    handles: HashSet<String>,
+}
+
+impl Taken {
+    fn new<'a>(existing: impl Iterator<Item = &'a BaseLog>) -> Self {
+        let mut taken = Taken {
+            ids: HashSet::new(),
+            handles: HashSet::new(),
+        };
+        for base in existing {
+            taken.ids.insert(base.id);
+            taken.handles.extend(base.handle.clone());
+        }
+        taken
+    }
+
+    /// Whether the entry `base` is to be imported, i.e. its ID is new here.
+    /// A handle taken by another entry is replaced by `next()`; one that is
+    /// kept is passed to `raise` to keep the counter ahead of it.
+    fn admit(
+        &mut self,
+        base: &mut BaseLog,
+        import: &mut BundleImport,
+        next: impl FnOnce() -> Result<String>,
+        raise: impl FnOnce(&str) -> Result<()>,
+    ) -> Result<bool> {
+        if !self.ids.insert(base.id) {
+            import.skipped += 1;
+            return Ok(false);
+        }
+        match base.handle.clone() {
+            Some(taken) if self.handles.contains(&taken) => {
+                let renumbered = next()?;
+                base.handle = Some(renumbered.clone());
+                import.renumbered.push((taken, renumbered));
+            }
+            Some(kept) => raise(&kept)?,
+            None => {}
+        }
+        self.handles.extend(base.handle.clone());
+        Ok(true)
+    }
+}
+
+fn write<T: ResearchLog>(
+    manager: &LogManager<T>,
+    logs: Vec<(T, &str)>,
+    import: &mut BundleImport,
+) -> Result<()> {
+    let existing = manager.list_logs(&ListFilter::default())?;
+    let mut taken = Taken::new(existing.iter().map(|log| log.base()));
+    for (mut log, body) in logs {
+        let admitted = taken.admit(
+            log.base_mut(),
+            import,
+            || handle::next(manager),
+            |kept| handle::raise(&manager.config, T::KIND, T::HANDLE_PREFIX, kept),
+        )?;
+        if admitted {
+            let content = update_markdown_frontmatter(&log, &format!("{}\n", body))?;
+            manager.save_log(&log, &content, true)?;
+            import.imported += 1;
+        }
+    }
+    Ok(())
+}
+
+fn write_declared(
+    manager: &CustomLogManager,
+    logs: Vec<(CustomLog, &str)>,
+    import: &mut BundleImport,
+) -> Result<()> {
+    let existing = manager.list(None, &TagFilter::default())?;
+    let mut taken = Taken::new(existing.iter().map(|(log, _)| &log.base));
+    let log_type = &manager.log_type;
+    for (mut log, body) in logs {
+        let admitted = taken.admit(
+            &mut log.base,
+            import,
+            || manager.next_handle(),
+            |kept| {
+                handle::raise(
+                    &manager.config,
+                    &log_type.name,
+                    &log_type.handle_prefix,
+                    kept,
+                )
+            },
+        )?;
+        if admitted {
+            let content = update_markdown_frontmatter(&log, &format!("{}\n", body))?;
+            manager.save(&log, &content)?;
+            import.imported += 1;
+        }
+    }
+    Ok(())
+}
+
+fn import(config: &Config, bundle: &Bundle) -> Result<BundleImport> {
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(anyhow::anyhow!("Not a dxlog bundle"));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(anyhow::anyhow!(
+            "The bundle was written by a newer version of dxlog (bundle version {}, this one reads up to {})",
+            bundle.version,
+            BUNDLE_VERSION
+        ));
+    }
+    for entry in &bundle.entries {
+        check_kind(config, Some(&entry.kind))
+            .with_context(|| format!("Invalid entry {} in the bundle", entry.path.display()))?;
+    }
+
+    // Every entry is read before any is written, so a bad bundle changes nothing.
+    let hypotheses = parse::<HypothesisLog>(&bundle.entries)?;
+    let literature = parse::<LiteratureLog>(&bundle.entries)?;
+    let knowledge = parse::<KnowledgeLog>(&bundle.entries)?;
+    let journal = parse::<JournalLog>(&bundle.entries)?;
+    let datasets = parse::<DatasetLog>(&bundle.entries)?;
+    let meetings = parse::<MeetingLog>(&bundle.entries)?;
+    let ideas = parse::<IdeaLog>(&bundle.entries)?;
+    let experiments = parse::<ExperimentLog>(&bundle.entries)?;
+    let declared = config
+        .types
+        .keys()
+        .map(|name| {
+            let manager = CustomLogManager::new(config.clone(), name)?;
+            let logs = parse_declared(&manager, &bundle.entries)?;
+            Ok((manager, logs))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut import = BundleImport::default();
+    write(
+        &HypothesisManager::new(config.clone()).manager,
+        hypotheses,
+        &mut import,
+    )?;
+    write(
+        &LiteratureManager::new(config.clone()).manager,
+        literature,
+        &mut import,
+    )?;
+    write(
+        &KnowledgeManager::new(config.clone()).manager,
+        knowledge,
+        &mut import,
+    )?;
+    write(
+        &JournalManager::new(config.clone()).manager,
+        journal,
+        &mut import,
+    )?;
+    write(
+        &DatasetManager::new(config.clone()).manager,
+        datasets,
+        &mut import,
+    )?;
+    write(
+        &MeetingManager::new(config.clone()).manager,
+        meetings,
+        &mut import,
+    )?;
+    write(
+        &IdeaManager::new(config.clone()).manager,
+        ideas,
+        &mut import,
+    )?;
+    write(
+        &ExperimentManager::new(config.clone()).manager,
+        experiments,
+        &mut import,
+    )?;
+    for (manager, logs) in declared {
+        write_declared(&manager, logs, &mut import)?;
+    }
+    Ok(import)
+}
+
+/// Creates the entries of a bundle written by [`export_bundle`], keeping their
+/// IDs, handles and references.
+///
+/// Entries whose ID is already in the repository are skipped, so importing
+/// the same bundle twice is harmless. An entry whose handle is taken by
+/// another entry gets the next free one. Entries of a type declared in
+/// `[types]` need the same type declared in this repository.
+pub fn import_bundle(file: &Path) -> Result<BundleImport> {
+    let config = load_config()?;
+    let content = utils::load_entry_content(file)?;
+    let bundle: Bundle = serde_json::from_str(&content)
+        .with_context(|| format!("Invalid bundle: {}", file.display()))?;
+    import(&config, &bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{author, TempRepo, AUTHOR_SETTINGS};
+
+    fn repository(repo: &TempRepo) -> Config {
+        let mut config = repo.config();
+        config.storage.active_dir = repo.root().join("logs");
+        config.storage.knowledge_base_dir = repo.root().join("kb");
+        config
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let (source_repo, target_repo) = (TempRepo::new(), TempRepo::new());
+        let source = repository(&source_repo);
+        let manager = HypothesisManager::new(source.clone()).manager;
+        let mut log = HypothesisLog::new("Noise scaling".into(), HashSet::new(), author());
+        manager.assign_ids(&mut log).unwrap();
+        let content = update_markdown_frontmatter(&log, "# Noise scaling\n").unwrap();
+        manager.save_log(&log, &content, false).unwrap();
+
+        let exported = bundle(&source).unwrap();
+        assert_eq!(exported.entries.len(), 1);
+        assert_eq!(exported.entries[0].kind, "hypothesis");
+        assert_eq!(exported.entries[0].body, "# Noise scaling");
+        let json = serde_json::to_string(&exported).unwrap();
+        let read: Bundle = serde_json::from_str(&json).unwrap();
+
+        let target = repository(&target_repo);
+        let imported = import(&target, &read).unwrap();
+        assert_eq!((imported.imported, imported.skipped), (1, 0));
+        let copies = HypothesisManager::new(target.clone())
+            .manager
+            .list_log_files(&ListFilter::default())
+            .unwrap();
+        assert_eq!(copies[0].0.base.id, log.base.id);
+        assert_eq!(copies[0].0.base.handle.as_deref(), Some("H-001"));
+
+        // The same bundle again changes nothing
+        let again = import(&target, &read).unwrap();
+        assert_eq!((again.imported, again.skipped), (0, 1));
+
+        // Another entry with the same handle is numbered anew
+        let mut other: Bundle = serde_json::from_str(&json).unwrap();
+        other.entries[0].frontmatter["id"] = Value::String(Uuid::new_v4().to_string());
+        let renumbered = import(&target, &other).unwrap();
+        assert_eq!(
+            renumbered.renumbered,
+            vec![("H-001".to_string(), "H-002".to_string())]
+        );
+
+        other.version = BUNDLE_VERSION + 1;
+        assert!(import(&target, &other).is_err());
+    }
+
+    #[test]
+    fn test_bundle_declared_types() {
+        let repos = [TempRepo::new(), TempRepo::new(), TempRepo::new()];
+        let declared =
+            |repo: &TempRepo, types: &str| repo.load(&format!("{AUTHOR_SETTINGS}\n{types}"));
+        let protocol = "[types.protocol]\nstatuses = [\"draft\", \"validated\"]\n";
+        let source = declared(&repos[0], protocol);
+        let manager = CustomLogManager::new(source.clone(), "protocol").unwrap();
+        let (log, _) = manager.create("PCR", None, None, false).unwrap();
+
+        let exported = bundle(&source).unwrap();
+        assert_eq!(exported.entries.len(), 1);
+        assert_eq!(exported.entries[0].kind, "protocol");
+
+        let target = declared(&repos[1], protocol);
+        let imported = import(&target, &exported).unwrap();
+        assert_eq!((imported.imported, imported.skipped), (1, 0));
+        let (copy, _) = CustomLogManager::new(target.clone(), "protocol")
+            .unwrap()
+            .find(&log.base.id.to_string())
+            .unwrap();
+        assert_eq!(copy.base.handle.as_deref(), Some("P-001"));
+        assert_eq!(copy.status, "draft");
+        let again = import(&target, &exported).unwrap();
+        assert_eq!((again.imported, again.skipped), (0, 1));
+
+        // Without the type declared the bundle is refused
+        let plain = declared(&repos[2], "");
+        assert!(import(&plain, &exported).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use anyhow::{Context, Result};
+use minijinja::{context, Environment};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use uuid::Uuid;
+
+use super::site::{dataset_sources, literature_sources, site_entry, write_page, SiteEntry, STYLE};
+use crate::{
+    load_config, research_log::ResearchLog, Config, DatasetLog, DatasetManager, ExperimentLog,
+    ExperimentManager, HypothesisLog, HypothesisManager, JournalLog, JournalManager, KnowledgeLog,
+    KnowledgeManager, LiteratureLog, LiteratureManager, MeetingLog, MeetingManager,
+};
+
+/// Environment variable naming the browser used to print PDFs.
+pub const BROWSER_ENV_VAR: &str = "DXLOG_BROWSER";
+
+/// Browsers that can print a page to PDF without opening a window, tried in order.
+const BROWSERS: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "microsoft-edge",
+];
+
+/// Output format of [`export_entry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EntryFormat {
+    Html,
+    Pdf,
+}
+
+impl EntryFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            EntryFormat::Html => "html",
+            EntryFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// An entry referenced by the exported one.
+#[derive(Serialize)]
+struct Citation {
+    kind: Option<String>,
+    text: String,
+    url: Option<String>,
+}
+
+/// Renders a single entry of any type to a standalone HTML page or PDF, with
+/// the entries it references listed in a bibliography.
+///
+/// Writes to `out`, or to the entry's file name with the format's extension in
+/// the current directory. PDFs are printed by a headless Chromium-based browser
+/// found on `PATH` or named by `DXLOG_BROWSER`. Returns the path written.
+pub fn export_entry(partial_id: &str, format: EntryFormat, out: Option<&Path>) -> Result<PathBuf> {
+    let config = load_config()?;
+    let mut entry = find_entry(&config, partial_id)?;
+    entry.redact(&config.export.redact);
+    let bibliography = bibliography(&config, &entry.reference_ids);
+
+    let mut env = Environment::new();
+    env.add_template(
+        "entry.html",
+        include_str!("../templates/export/entry.html.jinja"),
+    )?;
+    let html = env.get_template("entry.html")?.render(context! {
+        entry => entry,
+        bibliography => bibliography,
+        exported_at => chrono::Local::now().format(&config.date_format).to_string(),
+        style => STYLE,
+    })?;
+
+    let out = out
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| Path::new(&entry.file_name).with_extension(format.extension()));
+    match format {
+        EntryFormat::Html => write_page(&out, &html)?,
+        EntryFormat::Pdf => print_to_pdf(&html, &out)?,
+    }
+    Ok(out)
+}
+
+fn find_entry(config: &Config, partial_id: &str) -> Result<SiteEntry> {
+    let date_format = &config.date_format;
+    let h_manager = HypothesisManager::new(config.clone());
+    let l_manager = LiteratureManager::new(config.clone());
+    let k_manager = KnowledgeManager::new(config.clone());
+    let j_manager = JournalManager::new(config.clone());
+    let d_manager = DatasetManager::new(config.clone());
+    let m_manager = MeetingManager::new(config.clone());
+    let e_manager = ExperimentManager::new(config.clone());
+
+    if let Ok((log, path)) = h_manager.find(partial_id) {
+        site_entry(&log, &path, HypothesisLog::KIND, date_format, Vec::new())
+    } else if let Ok((log, path)) = l_manager.find(partial_id) {
+        let sources = literature_sources(&log);
+        site_entry(&log, &path, LiteratureLog::KIND, date_format, sources)
+    } else if let Ok((log, path)) = k_manager.find(partial_id) {
+        site_entry(&log, &path, KnowledgeLog::KIND, date_format, Vec::new())
+    } else if let Ok((log, path)) = j_manager.find(partial_id) {
+        site_entry(&log, &path, JournalLog::KIND, date_format, Vec::new())
+    } else if let Ok((log, path)) = d_manager.find(partial_id) {
+        let sources = dataset_sources(&log);
+        site_entry(&log, &path, DatasetLog::KIND, date_format, sources)
+    } else if let Ok((log, path)) = m_manager.find(partial_id) {
+        site_entry(&log, &path, MeetingLog::KIND, date_format, Vec::new())
+    } else if let Ok((log, path)) = e_manager.find(partial_id) {
+        site_entry(&log, &path, ExperimentLog::KIND, date_format, Vec::new())
+    } else {
+        Err(anyhow::anyhow!(
+            "No entry found with ID starting with '{}'",
+            partial_id
+        ))
+    }
+}
+
+/// Citations for the referenced entries that still exist, in reference order.
+/// Entries carrying a tag redacted by `[export.redact]` are left out.
+fn bibliography(config: &Config, references: &[(Uuid, Option<String>)]) -> Vec<Citation> {
+    let h_manager = HypothesisManager::new(config.clone());
+    let l_manager = LiteratureManager::new(config.clone());
+    let k_manager = KnowledgeManager::new(config.clone());
+    let j_manager = JournalManager::new(config.clone());
+    let d_manager = DatasetManager::new(config.clone());
+    let m_manager = MeetingManager::new(config.clone());
+    let e_manager = ExperimentManager::new(config.clone());
+
+    let other = |kind: &str, title: &str, status: String| {
+        format!("{} \"{}\" ({})", capitalize(kind), title, status)
+    };
+    references
+        .iter()
+        .filter_map(|(id, kind)| {
+            let id = id.to_string();
+            let (text, url, tags) = if let Ok((log, _)) = l_manager.find(&id) {
+                (literature_citation(&log), log.source.url(), log.base.tags)
+            } else if let Ok((log, _)) = h_manager.find(&id) {
+                let text = other(HypothesisLog::KIND, &log.base.title, log.status.to_string());
+                (text, None, log.base.tags)
+            } else if let Ok((log, _)) = k_manager.find(&id) {
+                let text = other(KnowledgeLog::KIND, &log.base.title, log.status.to_string());
+                (text, None, log.base.tags)
+            } else if let Ok((log, _)) = j_manager.find(&id) {
+                let text = other(JournalLog::KIND, &log.base.title, log.status.to_string());
+                (text, None, log.base.tags)
+            } else if let Ok((log, _)) = d_manager.find(&id) {
+                let text = other(DatasetLog::KIND, &log.base.title, log.status.to_string());
+                (text, log.source.url, log.base.tags)
+            } else if let Ok((log, _)) = m_manager.find(&id) {
+                let text = other(MeetingLog::KIND, &log.base.title, log.status.to_string());
+                (text, None, log.base.tags)
+            } else if let Ok((log, _)) = e_manager.find(&id) {
+                let text = other(ExperimentLog::KIND, &log.base.title, log.status.to_string());
+                (text, None, log.base.tags)
+            } else {
+                return None;
+            };
+            if config.export.redact.hides(&tags) {
+                return None;
+            }
+            Some(Citation {
+                kind: kind.clone(),
+                text,
+                url,
+            })
+        })
+        .collect()
+}
+
+/// Authors, title and venue, e.g. `Ada Lovelace et al. Surface codes. ICLR 2024.`
+fn literature_citation(log: &LiteratureLog) -> String {
+    let source = &log.source;
+    let mut parts = Vec::new();
+    match source.authors.len() {
+        0 => {}
+        1..=3 => parts.push(source.authors.join(", ")),
+        _ => parts.push(format!("{} et al", source.authors[0])),
+    }
+    parts.push(log.base.title.clone());
+    parts.extend(source.venue.clone());
+    let parts: Vec<&str> = parts.iter().map(|p| p.trim_end_matches('.')).collect();
+    format!("{}.", parts.join(". "))
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn find_browser() -> Option<PathBuf> {
+    if let Some(browser) = std::env::var_os(BROWSER_ENV_VAR) {
+        return Some(browser.into());
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .flat_map(|dir| BROWSERS.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+fn print_to_pdf(html: &str, out: &Path) -> Result<()> {
+    let browser = find_browser().ok_or_else(|| {
+        anyhow::anyhow!(
+            "PDF export needs Chromium or Google Chrome on PATH, or {} naming a browser. \
+             Export with --format html and print the page instead",
+            BROWSER_ENV_VAR
+        )
+    })?;
+    let out = std::path::absolute(out)?;
+    let page = std::env::temp_dir().join(format!("dxlog-export-{}.html", Uuid::new_v4()));
+    write_page(&page, html)?;
+
+    let output = Command::new(&browser)
+        .args(["--headless", "--disable-gpu", "--no-pdf-header-footer"])
+        .arg(format!("--print-to-pdf={}", out.display()))
+        .arg(&page)
+        .output()
+        .with_context(|| format!("Failed to run {}", browser.display()));
+    let _ = fs::remove_file(&page);
+    let output = output?;
+    if !output.status.success() || !out.exists() {
+        return Err(anyhow::anyhow!(
+            "{} could not print the PDF: {}",
+            browser.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::author;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_literature_citation() {
+        let mut log = LiteratureLog::new("Surface codes.".to_string(), HashSet::new(), author());
+        assert_eq!(literature_citation(&log), "Surface codes.");
+
+        log.source.authors = vec!["A. Fowler".to_string(), "J. Martinis".to_string()];
+        log.source.venue = Some("PRA 2012".to_string());
+        assert_eq!(
+            literature_citation(&log),
+            "A. Fowler, J. Martinis. Surface codes. PRA 2012."
+        );
+
+        log.source
+            .authors
+            .extend(["C. Cleland".to_string(), "M. Mariantoni".to_string()]);
+        assert!(literature_citation(&log).starts_with("A. Fowler et al. Surface codes."));
+    }
+}
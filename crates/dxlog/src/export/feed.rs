@@ -0,0 +1,165 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, SecondsFormat, TimeZone};
+use minijinja::{context, Environment};
+use serde::Serialize;
+use std::{fs, path::Path};
+
+use super::site::{html_file_name, markdown_to_html};
+use crate::{
+    load_config, md_frontmatter::extract_frontmatter, research_log::ResearchLog, utils,
+    utils::BaseLog, KnowledgeLog, KnowledgeManager, KnowledgeStatus, ListFilter, LiteratureLog,
+    LiteratureManager, LiteratureStatus,
+};
+
+#[derive(Serialize)]
+struct FeedEntry {
+    #[serde(skip)]
+    published_at: DateTime<FixedOffset>,
+    id: String,
+    kind: &'static str,
+    title: String,
+    author: String,
+    orcid: Option<String>,
+    tags: Vec<String>,
+    updated: String,
+    link: Option<String>,
+    content: String,
+}
+
+/// Writes an Atom feed of the `limit` most recently published knowledge entries
+/// and completed literature reviews, newest first.
+///
+/// With `base_url`, where the output of `dxlog export site` is served, entries
+/// link to their pages there. Returns the number of entries in the feed.
+pub fn export_feed(out: &Path, title: &str, base_url: Option<&str>, limit: usize) -> Result<usize> {
+    let config = load_config()?;
+    let base_url = base_url.map(|url| url.trim_end_matches('/'));
+
+    let mut entries = Vec::new();
+    let knowledge = KnowledgeManager::new(config.clone())
+        .manager
+        .list_log_files(&ListFilter::with_status(KnowledgeStatus::Published))?;
+    for (log, path) in knowledge {
+        entries.push(feed_entry::<KnowledgeLog>(
+            &log,
+            &path,
+            "knowledge",
+            base_url,
+        )?);
+    }
+    let literature = LiteratureManager::new(config)
+        .manager
+        .list_log_files(&ListFilter::with_status(LiteratureStatus::Completed))?;
+    for (log, path) in literature {
+        entries.push(feed_entry::<LiteratureLog>(
+            &log,
+            &path,
+            "literature",
+            base_url,
+        )?);
+    }
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.published_at));
+    entries.truncate(limit);
+
+    let updated = entries
+        .first()
+        .map(|entry| entry.updated.clone())
+        .unwrap_or_else(|| utils::now().to_rfc3339_opts(SecondsFormat::Secs, true));
+    let feed_id = match base_url {
+        Some(url) => format!("{}/feed.xml", url),
+        None => "urn:dxlog:feed".to_string(),
+    };
+
+    let mut env = Environment::new();
+    env.add_template(
+        "feed.xml",
+        include_str!("../templates/export/feed.xml.jinja"),
+    )?;
+    let feed = env.get_template("feed.xml")?.render(context! {
+        title => title,
+        feed_id => feed_id,
+        updated => updated,
+        base_url => base_url,
+        entries => entries,
+    })?;
+    fs::write(out, feed).with_context(|| format!("Failed to write file: {}", out.display()))?;
+    Ok(entries.len())
+}
+
+fn feed_entry<T: ResearchLog>(
+    log: &T,
+    path: &Path,
+    kind: &'static str,
+    base_url: Option<&str>,
+) -> Result<FeedEntry> {
+    let content = utils::load_entry_content(path)?;
+    let (_, body) = extract_frontmatter::<T>(&content)?;
+    let base = log.base();
+    let published_at = published_at(base, &log.status().to_string());
+
+    let mut tags: Vec<String> = base.tags.iter().cloned().collect();
+    tags.sort();
+    Ok(FeedEntry {
+        published_at,
+        id: base.id.to_string(),
+        kind,
+        title: base.title.clone(),
+        author: base.created_by.name.clone(),
+        orcid: base.created_by.orcid.clone(),
+        tags,
+        updated: published_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        link: base_url.map(|url| format!("{}/{}", url, html_file_name(path))),
+        content: markdown_to_html(&body),
+    })
+}
+
+/// When the entry last moved to `status`, from its history. The history only
+/// records the day, so `updated_at` is used when it falls on that day.
+fn published_at(base: &BaseLog, status: &str) -> DateTime<FixedOffset> {
+    let day = base
+        .history
+        .iter()
+        .rev()
+        .find(|change| change.to == status)
+        .and_then(|change| NaiveDate::parse_from_str(&change.date, "%Y-%m-%d").ok());
+    match (day, base.updated_at) {
+        (Some(day), Some(updated_at)) if updated_at.date_naive() == day => updated_at,
+        (Some(day), _) => Local
+            .from_local_datetime(&day.and_time(Default::default()))
+            .earliest()
+            .map_or(base.created_at, |midnight| midnight.fixed_offset()),
+        (None, updated_at) => updated_at.unwrap_or(base.created_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_support::author, utils::StatusChange};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_published_at() {
+        let mut log = KnowledgeLog::new("Finding".to_string(), HashSet::new(), author());
+        let created_at = DateTime::parse_from_rfc3339("2025-01-01T09:00:00+01:00").unwrap();
+        let updated_at = DateTime::parse_from_rfc3339("2025-03-04T15:30:00+01:00").unwrap();
+        log.base.created_at = created_at;
+        log.base.updated_at = None;
+        assert_eq!(published_at(&log.base, "published"), created_at);
+
+        log.base.updated_at = Some(updated_at);
+        log.base.history.push(StatusChange {
+            from: "draft".to_string(),
+            to: "published".to_string(),
+            reason: String::new(),
+            date: "2025-03-04".to_string(),
+        });
+        assert_eq!(published_at(&log.base, "published"), updated_at);
+
+        log.base.history[0].date = "2025-02-01".to_string();
+        assert_eq!(
+            published_at(&log.base, "published").date_naive(),
+            NaiveDate::from_ymd_opt(2025, 2, 1).unwrap()
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDate, Utc};
+use std::{fs, path::Path};
+
+use crate::{due_items, milestone_status};
+
+struct Event {
+    uid: String,
+    date: NaiveDate,
+    summary: String,
+    description: String,
+}
+
+/// Writes the due dates of open entries (see [`due_items`]) and all milestones
+/// to an iCalendar file, as all-day events.
+///
+/// Returns the number of events written.
+pub fn export_ical(out: &Path) -> Result<usize> {
+    let mut events = Vec::new();
+    for item in due_items(None)? {
+        events.push(Event {
+            uid: format!("{}@dxlog", item.id),
+            date: item.due,
+            summary: format!("Due: {}", item.title),
+            description: format!("{} {} ({})", item.kind, item.id, item.status),
+        });
+    }
+    for status in milestone_status(None)? {
+        events.push(Event {
+            uid: format!("{}@dxlog", status.milestone.id),
+            date: status.milestone.due,
+            summary: format!("Milestone: {}", status.milestone.title),
+            description: format!(
+                "{}/{} attached entries resolved",
+                status.resolved(),
+                status.entries.len()
+            ),
+        });
+    }
+
+    let calendar = render(&events, &Utc::now().format("%Y%m%dT%H%M%SZ").to_string());
+    fs::write(out, calendar).with_context(|| format!("Failed to write file: {}", out.display()))?;
+    Ok(events.len())
+}
+
+fn render(events: &[Event], stamp: &str) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//dxlog//dxlog//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for event in events {
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", event.uid),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")),
+            format!(
+                "DTEND;VALUE=DATE:{}",
+                (event.date + Duration::days(1)).format("%Y%m%d")
+            ),
+            format!("SUMMARY:{}", escape(&event.summary)),
+            format!("DESCRIPTION:{}", escape(&event.description)),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// Escapes a TEXT value (RFC 5545, section 3.3.11).
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits lines longer than 75 octets, continuing them with a space
+/// (RFC 5545, section 3.1). Never splits inside a character.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_escapes_and_folds() {
+        let event = Event {
+            uid: "1@dxlog".to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 6, 30).unwrap(),
+            summary: format!("Due: Loss; accuracy, and {}", "ä".repeat(40)),
+            description: "hypothesis".to_string(),
+        };
+        let calendar = render(&[event], "20250101T000000Z");
+
+        assert!(calendar.contains("DTSTART;VALUE=DATE:20250630\r\n"));
+        assert!(calendar.contains("DTEND;VALUE=DATE:20250701\r\n"));
+        assert!(calendar.contains(r"SUMMARY:Due: Loss\; accuracy\, and"));
+        for line in calendar.split("\r\n") {
+            assert!(line.len() <= 75, "{:?} is too long", line);
+        }
+        let summary = calendar.replace("\r\n ", "");
+        assert!(summary.contains(&"ä".repeat(40)));
+    }
+}
@@ -0,0 +1,261 @@
+//! `dxlog export mdbook`: the published knowledge base as the source of an
+//! mdBook, for mdBook's search and theming.
+
+use anyhow::{Context, Result};
+use std::{collections::BTreeMap, fs, path::Path};
+
+use super::site::{published_entries, SiteEntry};
+use crate::{load_config, utils, Config};
+
+const BOOK_TOML: &str = "[book]\ntitle = \"Knowledge base\"\nsrc = \"src\"\n";
+
+/// How [`export_mdbook`] arranges chapters in `SUMMARY.md`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BookGrouping {
+    Tag,
+    Project,
+}
+
+impl BookGrouping {
+    /// Groups an entry is listed on; the first one holds its chapter.
+    fn groups(self, entry: &SiteEntry) -> Vec<String> {
+        match self {
+            BookGrouping::Tag if entry.tags.is_empty() => vec!["Untagged".to_string()],
+            BookGrouping::Tag => entry.tags.clone(),
+            BookGrouping::Project => {
+                vec![entry
+                    .project
+                    .clone()
+                    .unwrap_or_else(|| "No project".to_string())]
+            }
+        }
+    }
+}
+
+/// File name of a group page.
+fn slug(group: &str) -> String {
+    group
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+/// File name of the chapter of an entry, named like its page in the site.
+fn chapter_name(file_name: &str) -> String {
+    format!(
+        "{}.md",
+        file_name.strip_suffix(".html").unwrap_or(file_name)
+    )
+}
+
+/// Escapes a title for use as link text.
+fn link_text(title: &str) -> String {
+    title.replace('[', "\\[").replace(']', "\\]")
+}
+
+fn chapter(entry: &SiteEntry) -> String {
+    // Keep the heading the body starts with, as the chapter title mdBook shows
+    let body = entry.body.trim_start();
+    let (heading, rest) = match body.strip_prefix("# ") {
+        Some(_) => body.split_once('\n').unwrap_or((body, "")),
+        None => ("", body),
+    };
+    let mut out = if heading.is_empty() {
+        format!("# {}\n\n", entry.title)
+    } else {
+        format!("{}\n\n", heading.trim_end())
+    };
+
+    let author = match entry.affiliation.as_str() {
+        "" => entry.author.clone(),
+        affiliation => format!("{} ({})", entry.author, affiliation),
+    };
+    let orcid = match entry.orcid.as_str() {
+        "" => String::new(),
+        orcid => format!("ORCID [{}](https://orcid.org/{})", orcid, orcid),
+    };
+    let meta: Vec<&str> = [entry.kind, &entry.status, &entry.date, &author, &orcid]
+        .into_iter()
+        .filter(|field| !field.is_empty())
+        .collect();
+    out.push_str(&format!("*{}*\n\n", meta.join(" · ")));
+    if !entry.tags.is_empty() {
+        let tags: Vec<String> = entry.tags.iter().map(|tag| format!("`{}`", tag)).collect();
+        out.push_str(&format!("Tags: {}\n\n", tags.join(" ")));
+    }
+    if !entry.sources.is_empty() {
+        let sources: Vec<String> = entry
+            .sources
+            .iter()
+            .map(|source| format!("[{}]({})", source.label, source.url))
+            .collect();
+        out.push_str(&format!("{}\n\n", sources.join(" · ")));
+    }
+
+    let rest = rest.trim();
+    if !rest.is_empty() {
+        out.push_str(rest);
+        out.push_str("\n\n");
+    }
+    if !entry.references.is_empty() {
+        out.push_str("## References\n\n");
+        for reference in &entry.references {
+            out.push_str(&format!(
+                "- [{}]({})\n",
+                link_text(&reference.title),
+                chapter_name(&reference.file_name)
+            ));
+        }
+    }
+    format!("{}\n", out.trim_end())
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents).with_context(|| format!("Failed to write file: {}", path.display()))
+}
+
+fn build_book(config: &Config, out_dir: &Path, grouping: BookGrouping) -> Result<usize> {
+    let entries = published_entries(config)?;
+
+    let src = out_dir.join("src");
+    for dir in [src.join("entries"), src.join("groups")] {
+        // Chapters of entries no longer published must not linger
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .with_context(|| format!("Failed to remove {}", dir.display()))?;
+        }
+        utils::ensure_directory(&dir)?;
+    }
+    let book_toml = out_dir.join("book.toml");
+    if !book_toml.exists() {
+        write(&book_toml, BOOK_TOML)?;
+    }
+
+    // Each entry is listed on the page of every group it is in, but its
+    // chapter appears once in SUMMARY.md, under its first group.
+    let mut listed: BTreeMap<String, Vec<&SiteEntry>> = BTreeMap::new();
+    let mut chapters: BTreeMap<String, Vec<&SiteEntry>> = BTreeMap::new();
+    for entry in &entries {
+        write(
+            &src.join("entries").join(chapter_name(&entry.file_name)),
+            &chapter(entry),
+        )?;
+        let groups = grouping.groups(entry);
+        chapters.entry(groups[0].clone()).or_default().push(entry);
+        for group in groups {
+            listed.entry(group).or_default().push(entry);
+        }
+    }
+
+    let mut summary = String::from("# Summary\n\n[Introduction](README.md)\n\n");
+    for (group, entries) in &listed {
+        let mut page = format!("# {}\n\n", group);
+        for entry in entries {
+            page.push_str(&format!(
+                "- [{}](../entries/{}) — {}\n",
+                link_text(&entry.title),
+                chapter_name(&entry.file_name),
+                entry.date
+            ));
+        }
+        write(
+            &src.join("groups").join(format!("{}.md", slug(group))),
+            &page,
+        )?;
+
+        summary.push_str(&format!(
+            "- [{}](groups/{}.md)\n",
+            link_text(group),
+            slug(group)
+        ));
+        for entry in chapters.get(group).into_iter().flatten() {
+            summary.push_str(&format!(
+                "  - [{}](entries/{})\n",
+                link_text(&entry.title),
+                chapter_name(&entry.file_name)
+            ));
+        }
+    }
+    write(&src.join("SUMMARY.md"), &summary)?;
+    write(
+        &src.join("README.md"),
+        &format!(
+            "# Knowledge base\n\nPublished knowledge and completed literature reviews: {} entries, exported on {}.\n",
+            entries.len(),
+            chrono::Local::now().format(&config.date_format)
+        ),
+    )?;
+
+    Ok(entries.len())
+}
+
+/// Writes published knowledge and completed literature reviews as the source
+/// of an mdBook: a chapter per entry, arranged in `SUMMARY.md` by tag or
+/// project. `book.toml` is only written if missing, so its title and theme
+/// can be changed.
+///
+/// Returns the number of entries written.
+pub fn export_mdbook(out_dir: &Path, grouping: BookGrouping) -> Result<usize> {
+    let config = load_config()?;
+    build_book(&config, out_dir, grouping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::md_frontmatter::update_markdown_frontmatter;
+    use crate::{
+        research_log::ResearchLog,
+        test_support::{author, TempRepo},
+        KnowledgeLog, KnowledgeManager, KnowledgeStatus,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_build_book() {
+        let repo = TempRepo::new();
+        let root = repo.root();
+        let mut config = repo.config();
+        config.storage.knowledge_base_dir = root.join("kb");
+        let manager = KnowledgeManager::new(config.clone()).manager;
+        let save = |title: &str, tags: &[&str], body: &str| {
+            let tags: HashSet<String> = tags.iter().map(|t| t.to_string()).collect();
+            let mut log = KnowledgeLog::new(title.into(), tags, author());
+            log.status = KnowledgeStatus::Published;
+            manager.assign_ids(&mut log).unwrap();
+            let content = update_markdown_frontmatter(&log, body).unwrap();
+            manager.save_log(&log, &content, false).unwrap();
+            log.base().handle.clone().unwrap()
+        };
+        save(
+            "Surface codes",
+            &["qec", "codes"],
+            "# Surface codes\n\nThreshold near 1%.\n",
+        );
+        save("[Draft] notes", &[], "Untitled body\n");
+
+        let out = root.join("book");
+        assert_eq!(build_book(&config, &out, BookGrouping::Tag).unwrap(), 2);
+        let summary = fs::read_to_string(out.join("src/SUMMARY.md")).unwrap();
+        assert!(summary.contains("- [codes](groups/codes.md)\n  - [Surface codes](entries/"));
+        assert!(summary.contains("- [Untagged](groups/untagged.md)\n  - [\\[Draft\\] notes]"));
+        // Entries get one chapter, under their first tag
+        assert!(summary.ends_with("- [qec](groups/qec.md)\n"));
+        let qec = fs::read_to_string(out.join("src/groups/qec.md")).unwrap();
+        assert!(qec.contains("[Surface codes](../entries/"));
+
+        let chapters: Vec<String> = fs::read_dir(out.join("src/entries"))
+            .unwrap()
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        assert!(chapters.iter().any(|c| c
+            .starts_with("# Surface codes\n\n*knowledge · published")
+            && c.ends_with("Threshold near 1%.\n")));
+        assert!(chapters
+            .iter()
+            .any(|c| c.starts_with("# [Draft] notes\n\n")));
+        assert!(out.join("book.toml").exists());
+    }
+}
@@ -0,0 +1,20 @@
+mod anki;
+mod bundle;
+mod entry;
+mod feed;
+mod ical;
+mod mdbook;
+mod notebook;
+mod notion;
+mod site;
+
+pub use anki::*;
+pub use bundle::*;
+pub use entry::*;
+pub use feed::*;
+pub use ical::*;
+pub use mdbook::*;
+pub(crate) use notebook::*;
+pub(crate) use notion::without_title;
+pub use notion::*;
+pub use site::*;
@@ -0,0 +1,190 @@
+use anyhow::{Context, Result};
+use minijinja::{context, Environment};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+use super::site::{markdown_to_html, STYLE};
+
+const NOTEBOOK_STYLE: &str = "pre{background:#f6f8fa;padding:.5rem;overflow-x:auto}\
+.prompt{color:#555;font-family:monospace;font-size:.85em}\
+pre.output{background:none;border-left:3px solid #ddd}\
+pre.error{border-left-color:#c33}\
+img{max-width:100%}";
+
+/// ANSI color codes, which tracebacks are full of.
+static ANSI_ESCAPE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap());
+
+#[derive(Serialize)]
+struct Cell {
+    kind: String,
+    source: String,
+    /// Rendered markdown, for markdown cells.
+    html: String,
+    execution_count: Option<u64>,
+    outputs: Vec<Output>,
+}
+
+#[derive(Serialize, Default)]
+struct Output {
+    text: String,
+    /// Data URI of an image.
+    image: Option<String>,
+    html: Option<String>,
+    error: bool,
+}
+
+/// Source and text outputs are stored either as one string or as a list of lines.
+fn text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+fn output(value: &Value) -> Output {
+    match value["output_type"].as_str() {
+        Some("stream") => Output {
+            text: text(value.get("text")),
+            ..Default::default()
+        },
+        Some("error") => {
+            let traceback: Vec<String> = value["traceback"]
+                .as_array()
+                .map(|lines| {
+                    lines
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let traceback = if traceback.is_empty() {
+                format!(
+                    "{}: {}",
+                    value["ename"].as_str().unwrap_or_default(),
+                    value["evalue"].as_str().unwrap_or_default()
+                )
+            } else {
+                traceback.join("\n")
+            };
+            Output {
+                text: ANSI_ESCAPE.replace_all(&traceback, "").into_owned(),
+                error: true,
+                ..Default::default()
+            }
+        }
+        _ => {
+            let data = &value["data"];
+            for mime in ["image/png", "image/jpeg", "image/gif"] {
+                if data.get(mime).is_some() {
+                    let encoded: String = text(data.get(mime)).split_whitespace().collect();
+                    return Output {
+                        image: Some(format!("data:{};base64,{}", mime, encoded)),
+                        ..Default::default()
+                    };
+                }
+            }
+            if data.get("text/html").is_some() {
+                return Output {
+                    html: Some(text(data.get("text/html"))),
+                    ..Default::default()
+                };
+            }
+            if data.get("text/markdown").is_some() {
+                return Output {
+                    html: Some(markdown_to_html(&text(data.get("text/markdown")))),
+                    ..Default::default()
+                };
+            }
+            Output {
+                text: text(data.get("text/plain")),
+                ..Default::default()
+            }
+        }
+    }
+}
+
+/// Renders a Jupyter notebook (nbformat 4) to a standalone HTML page, with
+/// its outputs and images embedded so the page stands on its own. `source`
+/// names the notebook in the page header.
+pub(crate) fn notebook_to_html(notebook: &str, source: &str, date_format: &str) -> Result<String> {
+    let notebook: Value = serde_json::from_str(notebook).context("Not a Jupyter notebook")?;
+    let cells = notebook["cells"]
+        .as_array()
+        .context("Not a Jupyter notebook: it has no cells")?
+        .iter()
+        .map(|cell| {
+            let kind = cell["cell_type"].as_str().unwrap_or("raw").to_string();
+            let source = text(cell.get("source"));
+            Cell {
+                html: if kind == "markdown" {
+                    markdown_to_html(&source)
+                } else {
+                    String::new()
+                },
+                execution_count: cell["execution_count"].as_u64(),
+                outputs: cell["outputs"]
+                    .as_array()
+                    .map(|outputs| outputs.iter().map(output).collect())
+                    .unwrap_or_default(),
+                kind,
+                source,
+            }
+        })
+        .collect::<Vec<_>>();
+    let metadata = &notebook["metadata"];
+    let language = metadata["language_info"]["name"]
+        .as_str()
+        .or_else(|| metadata["kernelspec"]["language"].as_str())
+        .unwrap_or("python");
+
+    let mut env = Environment::new();
+    env.add_template(
+        "notebook.html",
+        include_str!("../templates/export/notebook.html.jinja"),
+    )?;
+    Ok(env.get_template("notebook.html")?.render(context! {
+        title => source,
+        source => source,
+        cells => cells,
+        language => language,
+        rendered_at => chrono::Local::now().format(date_format).to_string(),
+        style => format!("{}{}", STYLE, NOTEBOOK_STYLE),
+    })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notebook_to_html() {
+        let notebook = r##"{
+            "cells": [
+                {"cell_type": "markdown", "source": ["# Fit\n", "Lorentzian *fit*"]},
+                {"cell_type": "code", "execution_count": 3, "source": "print(1 < 2)",
+                 "outputs": [
+                    {"output_type": "stream", "name": "stdout", "text": ["True\n"]},
+                    {"output_type": "display_data", "data": {"image/png": "iVBORw0K\nGgo=\n", "text/plain": "<Figure>"}},
+                    {"output_type": "error", "ename": "ValueError", "evalue": "bad",
+                     "traceback": ["\u001b[0;31mValueError\u001b[0m: bad"]}
+                 ]}
+            ],
+            "metadata": {"language_info": {"name": "python"}},
+            "nbformat": 4
+        }"##;
+        let html = notebook_to_html(notebook, "fit.ipynb", "%Y-%m-%d").unwrap();
+        assert!(html.contains("<h1>Fit</h1>"));
+        assert!(html.contains("<em>fit</em>"));
+        assert!(html.contains("In [3]:"));
+        assert!(html.contains("print(1 &lt; 2)"));
+        assert!(html.contains(";base64,iVBORw0KGgo"));
+        assert!(html.contains("ValueError: bad"));
+        assert!(!html.contains("&lt;Figure&gt;"));
+
+        assert!(notebook_to_html("{}", "x.ipynb", "%Y-%m-%d").is_err());
+    }
+}
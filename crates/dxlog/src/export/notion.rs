@@ -0,0 +1,71 @@
+use anyhow::Result;
+use dxlog_tools::{write_notion_pages, NotionPage};
+
+use crate::{
+    load_config, md_frontmatter::extract_frontmatter, utils, KnowledgeLog, KnowledgeManager,
+    KnowledgeStatus, ListFilter, NotionLink,
+};
+
+/// Writes every published knowledge entry to a page in the Notion database
+/// `database`: the title, tags and status as properties and the body as blocks.
+///
+/// The page is remembered in the entry, so later exports to the same database
+/// update it instead of adding another. Returns each entry with whether its
+/// page was created (rather than updated), or why it failed.
+pub fn export_notion(token: &str, database: &str) -> Result<Vec<(KnowledgeLog, Result<bool>)>> {
+    let database = database.replace('-', "");
+    let manager = KnowledgeManager::new(load_config()?);
+    let published = manager
+        .manager
+        .list_log_files(&ListFilter::with_status(KnowledgeStatus::Published))?;
+
+    let mut pages = Vec::new();
+    for (log, path) in &published {
+        let content = utils::load_entry_content(path)?;
+        let (_, body) = extract_frontmatter::<KnowledgeLog>(&content)?;
+        let mut tags: Vec<String> = log.base.tags.iter().cloned().collect();
+        tags.sort();
+        let page_id = log
+            .notion
+            .as_ref()
+            .filter(|link| link.database == database)
+            .map(|link| link.page.clone());
+        pages.push((
+            page_id,
+            NotionPage {
+                title: log.base.title.clone(),
+                tags,
+                status: log.status.to_string(),
+                markdown: without_title(&body, &log.base.title).to_string(),
+            },
+        ));
+    }
+
+    let written = write_notion_pages(token, &database, &pages)?;
+    let mut results = Vec::new();
+    for (((mut log, path), (old_page, _)), page) in published.into_iter().zip(pages).zip(written) {
+        let result = page.and_then(|page| {
+            let created = old_page.is_none();
+            if old_page.as_deref() != Some(page.as_str()) {
+                log.notion = Some(NotionLink {
+                    database: database.clone(),
+                    page,
+                });
+                manager.manager.update_log(&mut log, &path)?;
+            }
+            Ok(created)
+        });
+        results.push((log, result));
+    }
+    Ok(results)
+}
+
+/// `body` without a leading `# title` heading, which the page title already shows.
+pub(crate) fn without_title<'a>(body: &'a str, title: &str) -> &'a str {
+    let trimmed = body.trim_start();
+    match trimmed.split_once('\n') {
+        Some((first, rest)) if first.trim_end() == format!("# {}", title) => rest,
+        None if trimmed.trim_end() == format!("# {}", title) => "",
+        _ => body,
+    }
+}
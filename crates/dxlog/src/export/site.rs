@@ -0,0 +1,404 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
+use minijinja::{context, Environment};
+use pulldown_cmark::{html, Options, Parser};
+use regex::Regex;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+    sync::LazyLock,
+};
+use uuid::Uuid;
+
+use crate::{
+    load_config,
+    md_frontmatter::extract_frontmatter,
+    research_log::ResearchLog,
+    utils::{self, BaseLog},
+    Config, DatasetLog, KnowledgeLog, KnowledgeManager, KnowledgeStatus, ListFilter, LiteratureLog,
+    LiteratureManager, LiteratureStatus, RedactConfig, RedactField,
+};
+
+pub(super) const STYLE: &str =
+    "body{font-family:sans-serif;max-width:52rem;margin:2rem auto;padding:0 1rem;line-height:1.5}\
+table.frontmatter th{text-align:left;padding-right:1rem;color:#555}\
+.meta{color:#777;font-size:.9em}";
+
+#[derive(Serialize)]
+pub(super) struct SourceLink {
+    pub(super) label: &'static str,
+    pub(super) url: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct SiteReference {
+    kind: Option<String>,
+    pub(super) title: String,
+    pub(super) file_name: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct SiteEntry {
+    #[serde(skip)]
+    id: Uuid,
+    #[serde(skip)]
+    pub(super) reference_ids: Vec<(Uuid, Option<String>)>,
+    #[serde(skip)]
+    created_at: DateTime<FixedOffset>,
+    #[serde(skip)]
+    pub(super) project: Option<String>,
+    pub(super) kind: &'static str,
+    pub(super) title: String,
+    pub(super) status: String,
+    pub(super) date: String,
+    pub(super) author: String,
+    /// ORCID iD of the author, empty if unknown.
+    pub(super) orcid: String,
+    pub(super) affiliation: String,
+    pub(super) tags: Vec<String>,
+    pub(super) sources: Vec<SourceLink>,
+    pub(super) file_name: String,
+    /// The markdown the page is rendered from.
+    #[serde(skip)]
+    pub(super) body: String,
+    body_html: String,
+    pub(super) references: Vec<SiteReference>,
+}
+
+/// Email addresses, as masked by `[export.redact] emails`.
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}").unwrap()
+});
+
+impl SiteEntry {
+    /// Leaves out what `[export.redact]` lists.
+    pub(super) fn redact(&mut self, redact: &RedactConfig) {
+        if redact.redacts(RedactField::Tags) {
+            self.tags.clear();
+        } else {
+            self.tags
+                .retain(|tag| !redact.tags.iter().any(|r| utils::is_tag_within(tag, r)));
+        }
+        if redact.redacts(RedactField::Author) {
+            self.author.clear();
+            self.orcid.clear();
+            self.affiliation.clear();
+        }
+        if redact.redacts(RedactField::Status) {
+            self.status.clear();
+        }
+        if redact.redacts(RedactField::Date) {
+            self.date.clear();
+        }
+        if redact.redacts(RedactField::Sources) {
+            self.sources.clear();
+        }
+        if redact.redacts(RedactField::References) {
+            self.reference_ids.clear();
+        }
+        if redact.emails {
+            self.body = EMAIL
+                .replace_all(&self.body, "[email redacted]")
+                .into_owned();
+            self.body_html = EMAIL
+                .replace_all(&self.body_html, "[email redacted]")
+                .into_owned();
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TagGroup<'a> {
+    tag: String,
+    entries: Vec<&'a SiteEntry>,
+}
+
+/// Renders published knowledge and completed literature reviews to a static HTML site.
+///
+/// Returns the number of entry pages written (the index page is not counted).
+pub fn export_site(out_dir: &Path) -> Result<usize> {
+    let config = load_config()?;
+    build_site(&config, out_dir)
+}
+
+/// Published knowledge and completed literature reviews, newest first, with
+/// `[export.redact]` applied and their references to each other resolved.
+pub(super) fn published_entries(config: &Config) -> Result<Vec<SiteEntry>> {
+    let knowledge = KnowledgeManager::new(config.clone())
+        .manager
+        .list_log_files(&ListFilter::with_status(KnowledgeStatus::Published))?;
+    let literature = LiteratureManager::new(config.clone())
+        .manager
+        .list_log_files(&ListFilter::with_status(LiteratureStatus::Completed))?;
+
+    let mut entries = Vec::new();
+    for (log, path) in knowledge {
+        entries.push(site_entry::<KnowledgeLog>(
+            &log,
+            &path,
+            "knowledge",
+            &config.date_format,
+            Vec::new(),
+        )?);
+    }
+    for (log, path) in literature {
+        let sources = literature_sources(&log);
+        entries.push(site_entry::<LiteratureLog>(
+            &log,
+            &path,
+            "literature",
+            &config.date_format,
+            sources,
+        )?);
+    }
+
+    for entry in &mut entries {
+        entry.redact(&config.export.redact);
+    }
+
+    // Only entries that are part of the export can be linked to.
+    let exported: HashMap<Uuid, (String, String)> = entries
+        .iter()
+        .map(|e| (e.id, (e.title.clone(), e.file_name.clone())))
+        .collect();
+    for entry in &mut entries {
+        entry.references = entry
+            .reference_ids
+            .iter()
+            .filter_map(|(id, kind)| {
+                exported.get(id).map(|(title, file_name)| SiteReference {
+                    kind: kind.clone(),
+                    title: title.clone(),
+                    file_name: file_name.clone(),
+                })
+            })
+            .collect();
+    }
+    entries.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    Ok(entries)
+}
+
+fn build_site(config: &Config, out_dir: &Path) -> Result<usize> {
+    let entries = published_entries(config)?;
+
+    let mut env = Environment::new();
+    env.add_template(
+        "entry.html",
+        include_str!("../templates/site/entry.html.jinja"),
+    )?;
+    env.add_template(
+        "index.html",
+        include_str!("../templates/site/index.html.jinja"),
+    )?;
+
+    utils::ensure_directory(out_dir)?;
+    let entry_template = env.get_template("entry.html")?;
+    for entry in &entries {
+        let rendered = entry_template.render(context! { entry => entry, style => STYLE })?;
+        write_page(&out_dir.join(&entry.file_name), &rendered)?;
+    }
+
+    let (groups, untagged) = tag_groups(&entries);
+
+    let index = env.get_template("index.html")?.render(context! {
+        groups => groups,
+        untagged => untagged,
+        entry_count => entries.len(),
+        exported_at => chrono::Local::now().format(&config.date_format).to_string(),
+        style => STYLE,
+    })?;
+    write_page(&out_dir.join("index.html"), &index)?;
+
+    Ok(entries.len())
+}
+
+/// Entries grouped by tag for the index, and the entries without tags, which
+/// are kept apart so they never merge with a tag named "untagged".
+fn tag_groups(entries: &[SiteEntry]) -> (Vec<TagGroup<'_>>, Vec<&SiteEntry>) {
+    let mut by_tag: BTreeMap<String, Vec<&SiteEntry>> = BTreeMap::new();
+    let mut untagged = Vec::new();
+    for entry in entries {
+        if entry.tags.is_empty() {
+            untagged.push(entry);
+        }
+        for tag in &entry.tags {
+            by_tag.entry(tag.clone()).or_default().push(entry);
+        }
+    }
+    let groups = by_tag
+        .into_iter()
+        .map(|(tag, entries)| TagGroup { tag, entries })
+        .collect();
+    (groups, untagged)
+}
+
+pub(super) fn site_entry<T: ResearchLog>(
+    log: &T,
+    path: &Path,
+    kind: &'static str,
+    date_format: &str,
+    sources: Vec<SourceLink>,
+) -> Result<SiteEntry> {
+    let content = utils::load_entry_content(path)?;
+    let (_, body) = extract_frontmatter::<T>(&content)?;
+    let base: &BaseLog = log.base();
+
+    let mut tags: Vec<String> = base.tags.iter().cloned().collect();
+    tags.sort();
+
+    let mut reference_ids: Vec<(Uuid, Option<String>)> = base
+        .references
+        .iter()
+        .map(|r| (r.id, r.kind.map(|k| k.to_string())))
+        .collect();
+    reference_ids.sort();
+
+    Ok(SiteEntry {
+        id: base.id,
+        reference_ids,
+        kind,
+        title: base.title.clone(),
+        status: log.status().to_string(),
+        created_at: base.created_at,
+        project: base.project.clone(),
+        date: base.display_date(date_format),
+        author: base.created_by.name.clone(),
+        orcid: base.created_by.orcid.clone().unwrap_or_default(),
+        affiliation: base.created_by.affiliation.clone().unwrap_or_default(),
+        tags,
+        sources,
+        file_name: html_file_name(path),
+        body_html: markdown_to_html(&body),
+        body,
+        references: Vec::new(),
+    })
+}
+
+pub(super) fn literature_sources(log: &LiteratureLog) -> Vec<SourceLink> {
+    let source = &log.source;
+    let mut links = Vec::new();
+    if let Some(url) = &source.arxiv_url {
+        links.push(SourceLink {
+            label: "arXiv",
+            url: url.clone(),
+        });
+    }
+    if let Some(url) = &source.repository_url {
+        links.push(SourceLink {
+            label: "Repository",
+            url: url.clone(),
+        });
+    }
+    if let Some(url) = &source.pdf_url {
+        links.push(SourceLink {
+            label: "PDF",
+            url: url.clone(),
+        });
+    }
+    if let Some(doi) = &source.doi {
+        links.push(SourceLink {
+            label: "DOI",
+            url: format!("https://doi.org/{}", doi),
+        });
+    }
+    links
+}
+
+pub(super) fn dataset_sources(log: &DatasetLog) -> Vec<SourceLink> {
+    log.source
+        .url
+        .iter()
+        .map(|url| SourceLink {
+            label: "Source",
+            url: url.clone(),
+        })
+        .collect()
+}
+
+pub(super) fn html_file_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    format!("{}.html", stem)
+}
+
+pub(crate) fn markdown_to_html(markdown: &str) -> String {
+    let parser = Parser::new_ext(markdown, Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS);
+    let mut out = String::new();
+    html::push_html(&mut out, parser);
+    out
+}
+
+pub(super) fn write_page(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content).with_context(|| format!("Failed to write file: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, tags: &[&str]) -> SiteEntry {
+        SiteEntry {
+            id: Uuid::new_v4(),
+            reference_ids: vec![(Uuid::new_v4(), None)],
+            created_at: chrono::Local::now().fixed_offset(),
+            project: None,
+            kind: "knowledge",
+            title: title.to_string(),
+            status: "published".to_string(),
+            date: "2024-05-01".to_string(),
+            author: "Ada Lovelace".to_string(),
+            orcid: "0000-0002-1825-0097".to_string(),
+            affiliation: "Analytical Engines".to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            sources: Vec::new(),
+            file_name: format!("{}.html", title.to_lowercase().replace(' ', "-")),
+            body: "Ask ada.lovelace@example.org".to_string(),
+            body_html: "<p>Ask ada.lovelace@example.org</p>".to_string(),
+            references: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_redact() {
+        let mut entry = entry("Surface codes", &["internal/review", "qec"]);
+        let redact = RedactConfig {
+            tags: vec!["internal".to_string()],
+            fields: vec![RedactField::Author, RedactField::References],
+            emails: true,
+        };
+        assert!(redact.hides(&entry.tags.iter().cloned().collect()));
+
+        entry.redact(&redact);
+        assert_eq!(entry.tags, vec!["qec"]);
+        assert!(entry.author.is_empty() && entry.orcid.is_empty() && entry.affiliation.is_empty());
+        assert!(entry.reference_ids.is_empty());
+        assert_eq!(entry.status, "published");
+        assert_eq!(entry.body, "Ask [email redacted]");
+        assert_eq!(entry.body_html, "<p>Ask [email redacted]</p>");
+    }
+
+    #[test]
+    fn test_untagged_entries_stay_apart_from_an_untagged_tag() {
+        let entries = vec![
+            entry("Surface codes", &["qec", "untagged"]),
+            entry("Loose notes", &[]),
+        ];
+        let (groups, untagged) = tag_groups(&entries);
+
+        let tags: Vec<&str> = groups.iter().map(|group| group.tag.as_str()).collect();
+        assert_eq!(tags, ["qec", "untagged"]);
+        let titles = |entries: &[&SiteEntry]| -> Vec<String> {
+            entries.iter().map(|entry| entry.title.clone()).collect()
+        };
+        assert_eq!(titles(&groups[1].entries), ["Surface codes"]);
+        assert_eq!(titles(&untagged), ["Loose notes"]);
+    }
+}
@@ -0,0 +1,134 @@
+//! Short entry IDs such as `H-042`, easier to say and type than UUIDs.
+//!
+//! Each log type numbers its entries from a counter kept in
+//! [`COUNTERS_FILE`], which is committed along with the entries. A handle is
+//! stored in the frontmatter next to the UUID and accepted wherever a partial
+//! ID is, in any letter case and with or without the leading zeros.
+
+use anyhow::{Context, Result};
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{
+    dry_run,
+    log_manager::{ListFilter, LogManager},
+    research_log::ResearchLog,
+    utils, Config,
+};
+
+/// File, relative to the repository root, holding the last number used per log type.
+pub const COUNTERS_FILE: &str = ".dxlog/counters.toml";
+
+fn format(prefix: &str, number: u32) -> String {
+    format!("{}-{:03}", prefix, number)
+}
+
+/// The number of `text` if it is a handle with `prefix`, e.g. 42 for `h-42`.
+pub(crate) fn parse(prefix: &str, text: &str) -> Option<u32> {
+    let (start, number) = text.split_once('-')?;
+    if !start.eq_ignore_ascii_case(prefix)
+        || number.is_empty()
+        || !number.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    number.parse().ok()
+}
+
+/// The handle `text` refers to as it is stored, e.g. `H-042` for `h-42`.
+pub(crate) fn normalize(prefix: &str, text: &str) -> Option<String> {
+    parse(prefix, text).map(|number| format(prefix, number))
+}
+
+/// Whether `text` refers to the stored `handle`.
+pub(crate) fn matches(handle: &str, text: &str) -> bool {
+    handle
+        .split_once('-')
+        .and_then(|(prefix, _)| normalize(prefix, text))
+        .is_some_and(|normalized| normalized == handle)
+}
+
+/// Takes the next handle for a new entry of type `T`.
+///
+/// Repositories without a counter for `T` yet continue after the highest handle
+/// among its entries, so a lost counter file never hands out a number twice.
+pub(crate) fn next<T: ResearchLog>(manager: &LogManager<T>) -> Result<String> {
+    take(&manager.config, T::KIND, T::HANDLE_PREFIX, || {
+        Ok(manager
+            .list_logs(&ListFilter::default())?
+            .into_iter()
+            .filter_map(|log| log.base().handle.clone())
+            .collect())
+    })
+}
+
+/// Takes the next handle of the log type `kind`, falling back to the highest
+/// of the `existing` handles when it has no counter yet.
+pub(crate) fn take(
+    config: &Config,
+    kind: &str,
+    prefix: &str,
+    existing: impl FnOnce() -> Result<Vec<String>>,
+) -> Result<String> {
+    let path = config.root.join(COUNTERS_FILE);
+    let mut counters = load_counters(&path)?;
+
+    let last = match counters.get(kind) {
+        Some(&last) => last,
+        None => existing()?
+            .iter()
+            .filter_map(|handle| parse(prefix, handle))
+            .max()
+            .unwrap_or(0),
+    };
+    let number = last + 1;
+    counters.insert(kind.to_string(), number);
+    dry_run::write(&path, &toml::to_string(&counters)?)
+        .with_context(|| format!("Failed to update {}", path.display()))?;
+    Ok(format(prefix, number))
+}
+
+fn load_counters(path: &Path) -> Result<BTreeMap<String, u32>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    toml::from_str(&utils::load_entry_content(path)?)
+        .with_context(|| format!("Invalid counters in {}", path.display()))
+}
+
+/// Raises the counter of the log type `kind` to the number of `handle`, so an
+/// entry that arrives with its handle, e.g. from an import, is not numbered again.
+pub(crate) fn raise(config: &Config, kind: &str, prefix: &str, handle: &str) -> Result<()> {
+    let Some(number) = parse(prefix, handle) else {
+        return Ok(());
+    };
+    let path = config.root.join(COUNTERS_FILE);
+    let mut counters = load_counters(&path)?;
+    // Without a counter, the next handle follows the highest one in use anyway.
+    if counters.get(kind).is_none_or(|&last| last >= number) {
+        return Ok(());
+    }
+    counters.insert(kind.to_string(), number);
+    dry_run::write(&path, &toml::to_string(&counters)?)
+        .with_context(|| format!("Failed to update {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_match_handles() {
+        assert_eq!(parse("H", "H-042"), Some(42));
+        assert_eq!(parse("H", "h-42"), Some(42));
+        assert_eq!(parse("H", "L-042"), None);
+        assert_eq!(parse("H", "H-"), None);
+        assert_eq!(parse("H", "H-4a"), None);
+        assert_eq!(parse("H", "b71d1d2a"), None);
+        assert_eq!(normalize("L", "l-7").as_deref(), Some("L-007"));
+        assert_eq!(normalize("K", "K-1234").as_deref(), Some("K-1234"));
+
+        assert!(matches("H-042", "h-0042"));
+        assert!(!matches("H-042", "H-04"));
+        assert!(!matches("H-042", "L-042"));
+    }
+}
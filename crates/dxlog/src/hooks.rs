@@ -0,0 +1,224 @@
+//! User commands run on entry lifecycle events, configured in `[hooks]`.
+//!
+//! A hook runs through the shell in the repository root once the change is
+//! saved. It gets the entry's metadata as `DXLOG_*` environment variables and
+//! as JSON on stdin; its output goes to stderr so it never mixes with the
+//! command's own output. A failing hook never undoes or fails the change; the
+//! error is kept for the caller to report with [`take_hook_failures`].
+
+use std::{
+    cell::RefCell,
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::{dry_run::is_dry_run, research_log::LogEntry, Config};
+
+thread_local! {
+    /// Kept per thread, so a request of `dxlog serve` or `dxlog mcp` only
+    /// reports the hooks it ran itself.
+    static FAILURES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the hooks that failed on this thread so far, and forgets them.
+pub fn take_hook_failures() -> Vec<String> {
+    FAILURES.with(|failures| failures.take())
+}
+
+/// A point in the life of an entry a hook can run at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HookEvent {
+    Create,
+    StatusChange,
+    Delete,
+}
+
+impl HookEvent {
+    fn name(self) -> &'static str {
+        match self {
+            HookEvent::Create => "on_create",
+            HookEvent::StatusChange => "on_status_change",
+            HookEvent::Delete => "on_delete",
+        }
+    }
+
+    fn command(self, config: &Config) -> Option<&str> {
+        let hooks = &config.hooks;
+        match self {
+            HookEvent::Create => hooks.on_create.as_deref(),
+            HookEvent::StatusChange => hooks.on_status_change.as_deref(),
+            HookEvent::Delete => hooks.on_delete.as_deref(),
+        }
+    }
+}
+
+/// What a hook reads on stdin.
+#[derive(Serialize)]
+struct HookPayload<'a, T> {
+    event: &'static str,
+    kind: &'a str,
+    path: &'a Path,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_status: Option<&'a str>,
+    entry: &'a T,
+}
+
+/// The environment variables describing `log` to a hook.
+fn hook_env<E: LogEntry>(
+    event: HookEvent,
+    log: &E,
+    path: &Path,
+    previous_status: Option<&str>,
+) -> Vec<(&'static str, String)> {
+    let base = log.base_log();
+    let mut env = vec![
+        ("DXLOG_EVENT", event.name().to_string()),
+        ("DXLOG_KIND", log.kind_name().to_string()),
+        ("DXLOG_ID", base.id.to_string()),
+        ("DXLOG_TITLE", base.title.clone()),
+        ("DXLOG_STATUS", log.status_name()),
+        ("DXLOG_PATH", path.display().to_string()),
+    ];
+    if let Some(previous) = previous_status {
+        env.push(("DXLOG_PREVIOUS_STATUS", previous.to_string()));
+    }
+    env
+}
+
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C").arg(command);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        shell
+    }
+}
+
+fn run(config: &Config, command: &str, env: Vec<(&str, String)>, payload: &str) -> Result<()> {
+    debug!(command, "Running hook");
+    let mut child = shell(command)
+        .current_dir(&config.root)
+        .envs(env)
+        .stdin(Stdio::piped())
+        .stdout(std::io::stderr())
+        .spawn()
+        .context("Failed to start the shell")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Hooks that only read the environment may exit without reading stdin.
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+    let status = child.wait().context("Failed to wait for the hook")?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("exited with {}", status));
+    }
+    Ok(())
+}
+
+/// Runs the hook configured for `event`, if any, for the entry `log` stored at `path`.
+/// `previous_status` is the status a status change started from.
+pub(crate) fn run_hook<E: LogEntry>(
+    config: &Config,
+    event: HookEvent,
+    log: &E,
+    path: &Path,
+    previous_status: Option<&str>,
+) {
+    let Some(command) = event.command(config) else {
+        return;
+    };
+    if is_dry_run() {
+        return;
+    }
+
+    let payload = HookPayload {
+        event: event.name(),
+        kind: log.kind_name(),
+        path,
+        previous_status,
+        entry: log,
+    };
+    let result = serde_json::to_string(&payload)
+        .context("Failed to serialize the entry")
+        .and_then(|payload| {
+            run(
+                config,
+                command,
+                hook_env(event, log, path, previous_status),
+                &payload,
+            )
+        });
+    if let Err(e) = result {
+        let failure = format!("{} hook `{}` {:#}", event.name(), command, e);
+        FAILURES.with(|failures| failures.borrow_mut().push(failure));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        research_log::ResearchLog,
+        test_support::{author, TempRepo},
+        KnowledgeLog,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_hook_env_and_payload() {
+        let log = KnowledgeLog::new("Cavity modes".to_string(), HashSet::new(), author());
+        let path = Path::new("knowledge/cavity-modes.md");
+
+        let env = hook_env(HookEvent::StatusChange, &log, path, Some("draft"));
+        let var = |name: &str| {
+            env.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(var("DXLOG_EVENT"), Some("on_status_change"));
+        assert_eq!(var("DXLOG_KIND"), Some("knowledge"));
+        assert_eq!(var("DXLOG_TITLE"), Some("Cavity modes"));
+        assert_eq!(var("DXLOG_PREVIOUS_STATUS"), Some("draft"));
+        assert!(hook_env(HookEvent::Create, &log, path, None)
+            .iter()
+            .all(|(key, _)| *key != "DXLOG_PREVIOUS_STATUS"));
+
+        let payload = serde_json::to_value(HookPayload {
+            event: HookEvent::Create.name(),
+            kind: KnowledgeLog::KIND,
+            path,
+            previous_status: None,
+            entry: &log,
+        })
+        .unwrap();
+        assert_eq!(payload["entry"]["title"], "Cavity modes");
+        assert_eq!(payload["path"], "knowledge/cavity-modes.md");
+        assert!(payload.get("previous_status").is_none());
+    }
+
+    #[test]
+    fn test_failures_stay_with_their_thread() {
+        let repo = TempRepo::new();
+        let mut config = repo.config();
+        config.hooks.on_create = Some("exit 3".to_string());
+        let log = KnowledgeLog::new("Cavity modes".to_string(), HashSet::new(), author());
+        let path = Path::new("knowledge/cavity-modes.md");
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                run_hook(&config, HookEvent::Create, &log, path, None);
+                let failures = take_hook_failures();
+                assert_eq!(failures.len(), 1);
+                assert!(failures[0].starts_with("on_create hook `exit 3`"));
+            });
+        });
+        assert!(take_hook_failures().is_empty());
+    }
+}
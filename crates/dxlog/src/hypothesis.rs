@@ -1,51 +1,213 @@
 use anyhow::Result;
+use chrono::NaiveDate;
 use minijinja::context;
-use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, path::PathBuf};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 use uuid::Uuid;
 
 use crate::{
     load_config,
-    log_manager::LogManager,
+    log_manager::{ListFilter, LogManager, Renamed},
     md_frontmatter::{extract_frontmatter, serialize_yaml_frontmatter},
-    research_log::ResearchLog,
-    utils::{self, generate_filename, Author, BaseLog},
-    Config,
+    migration::SCHEMA_VERSION,
+    research_log::{LogStatus, ResearchLog},
+    template::entry_context,
+    utils::{self, generate_filename, Author, BaseLog, CommitInfo},
+    Config, EntryMatch,
 };
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, clap::ValueEnum)]
-pub enum HypothesisStatus {
-    Active,
-    Proven,
-    Disproven,
-    Inconclusive,
-    Suspended,
-    Abandoned,
-}
-
-impl ToString for HypothesisStatus {
-    fn to_string(&self) -> String {
-        match self {
-            HypothesisStatus::Active => "active",
-            HypothesisStatus::Proven => "proven",
-            HypothesisStatus::Disproven => "disproven",
-            HypothesisStatus::Inconclusive => "inconclusive",
-            HypothesisStatus::Suspended => "suspended",
-            HypothesisStatus::Abandoned => "abandoned",
+/// Status of a hypothesis: one of [`HypothesisStatus::BUILT_IN`], or one
+/// configured under `[statuses.hypothesis]`.
+///
+/// Any well-formed name parses; [`LogStatus::parse`] also checks that the
+/// repository knows it.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct HypothesisStatus(Cow<'static, str>);
+
+impl HypothesisStatus {
+    pub const ACTIVE: Self = Self(Cow::Borrowed("active"));
+    pub const PROVEN: Self = Self(Cow::Borrowed("proven"));
+    pub const DISPROVEN: Self = Self(Cow::Borrowed("disproven"));
+    pub const INCONCLUSIVE: Self = Self(Cow::Borrowed("inconclusive"));
+    pub const SUSPENDED: Self = Self(Cow::Borrowed("suspended"));
+    pub const ABANDONED: Self = Self(Cow::Borrowed("abandoned"));
+
+    /// The statuses of a repository without `[statuses.hypothesis]`.
+    pub const BUILT_IN: [Self; 6] = [
+        Self::ACTIVE,
+        Self::PROVEN,
+        Self::DISPROVEN,
+        Self::INCONCLUSIVE,
+        Self::SUSPENDED,
+        Self::ABANDONED,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the hypothesis has been concluded one way or the other.
+    pub fn is_concluded(&self) -> bool {
+        [Self::PROVEN, Self::DISPROVEN, Self::INCONCLUSIVE].contains(self)
+    }
+}
+
+impl fmt::Display for HypothesisStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Accepts any letter case and `_` or spaces for `-`, as well as the
+/// capitalized names (`Active`) dxlog wrote before statuses were configurable.
+impl FromStr for HypothesisStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut name = String::with_capacity(s.len());
+        let mut previous = None;
+        for c in s.trim().chars() {
+            match c {
+                '_' | ' ' | '-' => name.push('-'),
+                c if c.is_ascii_uppercase() => {
+                    if previous.is_some_and(|p: char| p.is_ascii_lowercase()) {
+                        name.push('-');
+                    }
+                    name.push(c.to_ascii_lowercase());
+                }
+                c if c.is_ascii_alphanumeric() => name.push(c),
+                _ => return Err(anyhow::anyhow!("Invalid hypothesis status '{}'", s)),
+            }
+            previous = Some(c);
+        }
+        if name.is_empty() || name.starts_with('-') || name.ends_with('-') || name.contains("--") {
+            return Err(anyhow::anyhow!("Invalid hypothesis status '{}'", s));
         }
-        .to_string()
+        Ok(Self(Cow::Owned(name)))
     }
 }
 
+impl<'de> Deserialize<'de> for HypothesisStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl LogStatus for HypothesisStatus {
+    fn all(config: &Config) -> Vec<Self> {
+        config.statuses.hypothesis.resolve(&Self::BUILT_IN)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceDirection {
+    Supporting,
+    Refuting,
+}
+
+impl fmt::Display for EvidenceDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            EvidenceDirection::Supporting => "supporting",
+            EvidenceDirection::Refuting => "refuting",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceStrength {
+    Weak,
+    Moderate,
+    Strong,
+}
+
+impl fmt::Display for EvidenceStrength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            EvidenceStrength::Weak => "weak",
+            EvidenceStrength::Moderate => "moderate",
+            EvidenceStrength::Strong => "strong",
+        };
+        f.write_str(s)
+    }
+}
+
+/// A commit linked to a hypothesis, as found in the current repository.
+#[derive(Debug, Clone)]
+pub enum LinkedCommit {
+    Found(CommitInfo),
+    /// The commit no longer exists, e.g. after a rebase.
+    Missing {
+        sha: String,
+    },
+}
+
+/// A piece of evidence for or against a hypothesis.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Evidence {
+    pub description: String,
+    pub direction: EvidenceDirection,
+    pub strength: EvidenceStrength,
+    /// ID of a related research log, or a URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    pub date: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct HypothesisLog {
     #[serde(flatten)]
     pub base: BaseLog,
     pub status: HypothesisStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commits: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<Evidence>,
+    /// The GitHub issue the hypothesis was last synced to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_issue: Option<GitHubIssueLink>,
+}
+
+/// An issue in a GitHub repository written by `dxlog hypothesis sync-issues`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GitHubIssueLink {
+    /// Repository as `owner/name`.
+    pub repo: String,
+    pub number: u64,
+    /// Whether the issue was closed because the hypothesis was resolved.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub closed: bool,
+}
+
+impl HypothesisLog {
+    /// Number of supporting and refuting evidence items.
+    pub fn evidence_summary(&self) -> (usize, usize) {
+        let supporting = self
+            .evidence
+            .iter()
+            .filter(|e| e.direction == EvidenceDirection::Supporting)
+            .count();
+        (supporting, self.evidence.len() - supporting)
+    }
 }
 
 impl ResearchLog for HypothesisLog {
     type Status = HypothesisStatus;
+    const KIND: &'static str = "hypothesis";
+    const HANDLE_PREFIX: &'static str = "H";
 
     fn base(&self) -> &BaseLog {
         &self.base
@@ -64,17 +226,30 @@ impl ResearchLog for HypothesisLog {
     }
 
     fn new(title: String, tags: HashSet<String>, author: Author) -> Self {
-        let now = chrono::Local::now();
+        let now = utils::now();
         Self {
             base: BaseLog {
-                id: Uuid::new_v4(),
-                date: now.format("%Y-%m-%d").to_string(),
+                schema_version: SCHEMA_VERSION,
+                id: Uuid::now_v7(),
+                handle: None,
+                created_at: now,
+                updated_at: Some(now),
                 title,
                 tags,
+                project: None,
+                milestone: None,
                 created_by: author,
+                assignee: None,
                 references: HashSet::new(),
+                history: Vec::new(),
+                attachments: Vec::new(),
+                encrypted: false,
             },
-            status: HypothesisStatus::Active,
+            status: HypothesisStatus::ACTIVE,
+            due: None,
+            commits: Vec::new(),
+            evidence: Vec::new(),
+            github_issue: None,
         }
     }
 
@@ -82,18 +257,25 @@ impl ResearchLog for HypothesisLog {
         self.status = new_status;
     }
 
-    fn get_target_path(&self, config: &Config, current_path: &PathBuf) -> Result<PathBuf> {
+    fn is_complete(&self) -> bool {
+        self.status.is_concluded()
+    }
+
+    fn get_target_path(&self, config: &Config, current_path: &Path) -> Result<PathBuf> {
         let filename = current_path.file_name().unwrap();
-        match self.status {
-            HypothesisStatus::Abandoned => Ok(config.storage.archive_dir.join(filename)),
-            HypothesisStatus::Proven
-            | HypothesisStatus::Disproven
-            | HypothesisStatus::Inconclusive => Ok(config
+        if let Some(dir) = config.layout_dir(Self::KIND, &self.status.to_string()) {
+            return Ok(dir.join(filename));
+        }
+        if self.status == HypothesisStatus::ABANDONED {
+            Ok(config.storage.archive_dir.join(filename))
+        } else if self.status.is_concluded() {
+            Ok(config
                 .storage
                 .knowledge_base_dir
                 .join("hypotheses")
-                .join(filename)),
-            _ => Ok(config.storage.active_dir.join(filename)),
+                .join(filename))
+        } else {
+            Ok(config.storage.active_dir.join(filename))
         }
     }
 }
@@ -107,44 +289,190 @@ impl HypothesisManager {
         let search_dirs = vec![
             config.storage.active_dir.clone(),
             config.storage.knowledge_base_dir.join("hypotheses"),
+            config.storage.archive_dir.clone(),
         ];
         Self {
             manager: LogManager::<HypothesisLog>::new(config, search_dirs),
         }
     }
 
-    pub fn create(&self, title: &str, tags: Option<Vec<String>>) -> Result<HypothesisLog> {
-        let author = utils::get_git_author()?;
-        let hypothesis = HypothesisLog::new(title.to_string(), utils::normalize_tags(tags), author);
+    pub fn create(
+        &self,
+        title: &str,
+        tags: Option<Vec<String>>,
+        body: Option<&str>,
+        template: Option<&str>,
+        allow_duplicate_title: bool,
+        due: Option<NaiveDate>,
+    ) -> Result<HypothesisLog> {
+        let author = utils::get_author(&self.manager.config)?;
+        let mut hypothesis =
+            HypothesisLog::new(title.to_string(), utils::normalize_tags(tags), author);
+        hypothesis.due = due;
+        self.manager.assign_ids(&mut hypothesis)?;
 
         let yaml = serialize_yaml_frontmatter(&hypothesis)?;
-        let template_path = self.manager.config.templates.hypothesis.clone();
+        let template_path = self.manager.config.template_for(
+            HypothesisLog::KIND,
+            template,
+            &hypothesis.base.tags,
+        )?;
         let template_content = utils::load_entry_content(&template_path)?;
 
-        let env = minijinja::Environment::new();
-        let template = env.template_from_str(&template_content)?;
-        let rendered = template.render(context! {
-            research_log => yaml,
-            title => hypothesis.base.title,
-        })?;
+        let body = body.map(str::trim);
+        let rendered = utils::render_entry(
+            &template_content,
+            context! {
+                body => body,
+                ..entry_context(&self.manager.config, &hypothesis.base, yaml)
+            },
+            body,
+        )?;
 
-        self.manager.save_log(&hypothesis, &rendered)?;
+        self.manager
+            .save_log(&hypothesis, &rendered, allow_duplicate_title)?;
         Ok(hypothesis)
     }
 
-    pub fn update_status(&self, partial_id: &str, new_status: HypothesisStatus) -> Result<()> {
-        let (mut hypothesis, file_path): (HypothesisLog, PathBuf) =
-            self.manager.find_log(partial_id)?;
-        hypothesis.update_status(new_status);
-        self.manager.update_log(&mut hypothesis, &file_path)
+    /// Retitles an entry, renaming its file and updating links to it.
+    pub fn rename(
+        &self,
+        partial_id: &str,
+        title: &str,
+        allow_duplicate_title: bool,
+    ) -> Result<Renamed<HypothesisLog>> {
+        self.manager
+            .rename_log(partial_id, title, allow_duplicate_title)
+    }
+
+    pub fn update_status(
+        &self,
+        partial_id: &str,
+        new_status: HypothesisStatus,
+        reason: Option<&str>,
+        force: bool,
+    ) -> Result<()> {
+        let new_status = HypothesisStatus::parse(&self.manager.config, new_status.as_str())?;
+        self.manager
+            .change_status(partial_id, new_status, reason, force)?;
+        Ok(())
     }
 
-    pub fn list(
+    /// The hypotheses named by `partial_ids`, or matching `filter` if none are given.
+    pub fn select(
         &self,
-        status: Option<HypothesisStatus>,
-        tags: Option<Vec<String>>,
+        partial_ids: &[String],
+        filter: &ListFilter<HypothesisStatus>,
+    ) -> Result<Vec<EntryMatch>> {
+        if let Some(status) = &filter.status {
+            HypothesisStatus::parse(&self.manager.config, status.as_str())?;
+        }
+        Ok(self
+            .manager
+            .select(partial_ids, filter)?
+            .into_iter()
+            .map(|(log, _)| EntryMatch::new(&log.base, HypothesisLog::KIND, log.status.to_string()))
+            .collect())
+    }
+
+    /// Changes the status of several hypotheses at once, leaving them all
+    /// unchanged if any of the changes is not allowed.
+    pub fn update_statuses(
+        &self,
+        partial_ids: &[String],
+        new_status: HypothesisStatus,
+        reason: Option<&str>,
+        force: bool,
     ) -> Result<Vec<HypothesisLog>> {
-        self.manager.list_logs(status, tags)
+        let new_status = HypothesisStatus::parse(&self.manager.config, new_status.as_str())?;
+        self.manager
+            .change_status_many(partial_ids, new_status, reason, force)
+    }
+
+    /// Sets the date the hypothesis should be resolved by, or clears it with `None`.
+    pub fn set_due(&self, partial_id: &str, due: Option<NaiveDate>) -> Result<HypothesisLog> {
+        let (mut hypothesis, file_path) = self.manager.find_log(partial_id)?;
+        hypothesis.due = due;
+        self.manager.update_log(&mut hypothesis, &file_path)?;
+        Ok(hypothesis)
+    }
+
+    /// Moves a concluded or abandoned hypothesis back to active.
+    pub fn reopen(&self, partial_id: &str, reason: Option<&str>) -> Result<HypothesisLog> {
+        self.manager.reopen(
+            partial_id,
+            HypothesisStatus::ACTIVE,
+            reason.unwrap_or("reopened"),
+        )
+    }
+
+    pub fn link_commit(&self, partial_id: &str, rev: &str) -> Result<CommitInfo> {
+        let (mut hypothesis, file_path) = self.manager.find_log(partial_id)?;
+        let commit = utils::resolve_commit(&self.manager.config.root, rev)?;
+        if !hypothesis.commits.contains(&commit.sha) {
+            hypothesis.commits.push(commit.sha.clone());
+        }
+        self.manager.update_log(&mut hypothesis, &file_path)?;
+        Ok(commit)
+    }
+
+    /// Returns the linked commits, resolved against the current repository.
+    pub fn commits(&self, partial_id: &str) -> Result<Vec<LinkedCommit>> {
+        let (hypothesis, _) = self.manager.find_log(partial_id)?;
+        Ok(hypothesis
+            .commits
+            .iter()
+            .map(
+                |sha| match utils::resolve_commit(&self.manager.config.root, sha) {
+                    Ok(commit) => LinkedCommit::Found(commit),
+                    Err(_) => LinkedCommit::Missing { sha: sha.clone() },
+                },
+            )
+            .collect())
+    }
+
+    pub fn add_evidence(
+        &self,
+        partial_id: &str,
+        description: &str,
+        direction: EvidenceDirection,
+        strength: EvidenceStrength,
+        link: Option<String>,
+    ) -> Result<Evidence> {
+        let (mut hypothesis, file_path) = self.manager.find_log(partial_id)?;
+        let evidence = Evidence {
+            description: description.to_string(),
+            direction,
+            strength,
+            link,
+            date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+        };
+        hypothesis.evidence.push(evidence.clone());
+        self.manager.update_log(&mut hypothesis, &file_path)?;
+        Ok(evidence)
+    }
+
+    /// Removes the evidence item at `index` (1-based, as shown by `evidence list`).
+    pub fn remove_evidence(&self, partial_id: &str, index: usize) -> Result<Evidence> {
+        let (mut hypothesis, file_path) = self.manager.find_log(partial_id)?;
+        if index == 0 || index > hypothesis.evidence.len() {
+            return Err(anyhow::anyhow!(
+                "No evidence #{} on hypothesis '{}' ({} items)",
+                index,
+                hypothesis.base.title,
+                hypothesis.evidence.len()
+            ));
+        }
+        let removed = hypothesis.evidence.remove(index - 1);
+        self.manager.update_log(&mut hypothesis, &file_path)?;
+        Ok(removed)
+    }
+
+    pub fn list(&self, filter: &ListFilter<HypothesisStatus>) -> Result<Vec<HypothesisLog>> {
+        if let Some(status) = &filter.status {
+            HypothesisStatus::parse(&self.manager.config, status.as_str())?;
+        }
+        self.manager.list_logs(filter)
     }
 
     pub fn find(&self, partial_id: &str) -> Result<(HypothesisLog, PathBuf)> {
@@ -152,30 +480,125 @@ impl HypothesisManager {
     }
 }
 
-pub fn create_hypothesis(title: &str, tags: Option<Vec<String>>) -> Result<HypothesisLog> {
+pub fn create_hypothesis(
+    title: &str,
+    tags: Option<Vec<String>>,
+    body: Option<&str>,
+    template: Option<&str>,
+    allow_duplicate_title: bool,
+    due: Option<NaiveDate>,
+) -> Result<HypothesisLog> {
     let config = load_config()?;
     let manager = HypothesisManager::new(config);
-    manager.create(title, tags)
+    manager.create(title, tags, body, template, allow_duplicate_title, due)
 }
 
-pub fn update_hypothesis_status(partial_id: &str, new_status: HypothesisStatus) -> Result<()> {
+pub fn set_hypothesis_due(partial_id: &str, due: Option<NaiveDate>) -> Result<HypothesisLog> {
     let config = load_config()?;
     let manager = HypothesisManager::new(config);
-    manager.update_status(partial_id, new_status)
+    manager.set_due(partial_id, due)
 }
 
-pub fn list_hypotheses(
-    status: Option<HypothesisStatus>,
-    tags: Option<Vec<String>>,
+pub fn update_hypothesis_status(
+    partial_id: &str,
+    new_status: HypothesisStatus,
+    reason: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let manager = HypothesisManager::new(config);
+    manager.update_status(partial_id, new_status, reason, force)
+}
+
+pub fn select_hypotheses(
+    partial_ids: &[String],
+    filter: &ListFilter<HypothesisStatus>,
+) -> Result<Vec<EntryMatch>> {
+    let config = load_config()?;
+    let manager = HypothesisManager::new(config);
+    manager.select(partial_ids, filter)
+}
+
+pub fn update_hypothesis_statuses(
+    partial_ids: &[String],
+    new_status: HypothesisStatus,
+    reason: Option<&str>,
+    force: bool,
 ) -> Result<Vec<HypothesisLog>> {
     let config = load_config()?;
     let manager = HypothesisManager::new(config);
-    manager.list(status, tags)
+    manager.update_statuses(partial_ids, new_status, reason, force)
+}
+
+pub fn rename_hypothesis(
+    partial_id: &str,
+    title: &str,
+    allow_duplicate_title: bool,
+) -> Result<Renamed<HypothesisLog>> {
+    let config = load_config()?;
+    let manager = HypothesisManager::new(config);
+    manager.rename(partial_id, title, allow_duplicate_title)
+}
+
+pub fn reopen_hypothesis(partial_id: &str, reason: Option<&str>) -> Result<HypothesisLog> {
+    let config = load_config()?;
+    let manager = HypothesisManager::new(config);
+    manager.reopen(partial_id, reason)
+}
+
+pub fn link_hypothesis_commit(partial_id: &str, rev: &str) -> Result<CommitInfo> {
+    let config = load_config()?;
+    let manager = HypothesisManager::new(config);
+    manager.link_commit(partial_id, rev)
+}
+
+pub fn list_hypothesis_commits(partial_id: &str) -> Result<Vec<LinkedCommit>> {
+    let config = load_config()?;
+    let manager = HypothesisManager::new(config);
+    manager.commits(partial_id)
+}
+
+pub fn add_hypothesis_evidence(
+    partial_id: &str,
+    description: &str,
+    direction: EvidenceDirection,
+    strength: EvidenceStrength,
+    link: Option<String>,
+) -> Result<Evidence> {
+    let config = load_config()?;
+    let manager = HypothesisManager::new(config);
+    manager.add_evidence(partial_id, description, direction, strength, link)
+}
+
+pub fn remove_hypothesis_evidence(partial_id: &str, index: usize) -> Result<Evidence> {
+    let config = load_config()?;
+    let manager = HypothesisManager::new(config);
+    manager.remove_evidence(partial_id, index)
+}
+
+pub fn list_hypothesis_evidence(partial_id: &str) -> Result<Vec<Evidence>> {
+    let config = load_config()?;
+    let manager = HypothesisManager::new(config);
+    let (hypothesis, _) = manager.find(partial_id)?;
+    Ok(hypothesis.evidence)
+}
+
+pub fn list_hypotheses(filter: &ListFilter<HypothesisStatus>) -> Result<Vec<HypothesisLog>> {
+    list_hypotheses_in(&load_config()?, filter)
+}
+
+/// Like [`list_hypotheses`], in the repository of `config`.
+pub fn list_hypotheses_in(
+    config: &Config,
+    filter: &ListFilter<HypothesisStatus>,
+) -> Result<Vec<HypothesisLog>> {
+    let manager = HypothesisManager::new(config.clone());
+    manager.list(filter)
 }
 
 pub fn _create_hypothesis(title: &str, tags: Option<Vec<String>>) -> Result<HypothesisLog> {
     let config = load_config()?;
-    let author = utils::get_git_author()?;
+    let author = utils::get_author(&config)?;
 
     let hypothesis = HypothesisLog::new(
         title.to_string(),
@@ -194,7 +617,10 @@ pub fn _create_hypothesis(title: &str, tags: Option<Vec<String>>) -> Result<Hypo
         title => hypothesis.base.title,
     })?;
 
-    let file_name = generate_filename(&hypothesis.base.title, &hypothesis.base.date);
+    let file_name = generate_filename(
+        &hypothesis.base.title,
+        &hypothesis.base.created_date().to_string(),
+    );
     let file_path = config.storage.active_dir.join(&file_name);
     utils::save_entry_content(&file_path, &rendered)?;
 
@@ -264,3 +690,50 @@ pub fn _find_hypothesis_file(config: &Config, partial_id: &str) -> Result<PathBu
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        md_frontmatter::update_markdown_frontmatter,
+        test_support::{author, TempRepo},
+    };
+
+    #[test]
+    fn test_commits_reports_missing_commits() {
+        let temp = TempRepo::new();
+        let repo = git2::Repository::init(temp.root()).unwrap();
+        let signature = git2::Signature::now("Ada", "ada@example.org").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let sha = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "Add shielding",
+                &tree,
+                &[],
+            )
+            .unwrap()
+            .to_string();
+
+        let mut config = temp.config();
+        config.storage.active_dir = temp.root().join("research-logs");
+        let manager = HypothesisManager {
+            manager: LogManager::new(config.clone(), vec![config.storage.active_dir.clone()]),
+        };
+        let mut log = HypothesisLog::new("Shielding".into(), HashSet::new(), author());
+        log.commits = vec![sha.clone(), "0123456789abcdef".to_string()];
+        let content = update_markdown_frontmatter(&log, "# Shielding").unwrap();
+        manager.manager.save_log(&log, &content, false).unwrap();
+
+        let commits = manager.commits(&log.base.id.to_string()).unwrap();
+        assert!(matches!(
+            &commits[..],
+            [LinkedCommit::Found(found), LinkedCommit::Missing { sha: missing }]
+                if found.sha == sha && found.summary == "Add shielding" && missing == "0123456789abcdef"
+        ));
+    }
+}
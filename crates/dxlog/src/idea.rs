@@ -0,0 +1,298 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fmt,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    load_config,
+    log_manager::{ListFilter, LogManager},
+    md_frontmatter::update_markdown_frontmatter,
+    migration::SCHEMA_VERSION,
+    research_log::ResearchLog,
+    utils::{self, Author, BaseLog},
+    HypothesisLog, HypothesisManager, KnowledgeLog, KnowledgeManager,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum IdeaStatus {
+    Inbox,
+    Archived,
+}
+
+impl fmt::Display for IdeaStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            IdeaStatus::Inbox => "inbox",
+            IdeaStatus::Archived => "archived",
+        };
+        f.write_str(s)
+    }
+}
+
+/// A one-line idea captured in the inbox, to be promoted to a full entry or
+/// left archived.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IdeaLog {
+    #[serde(flatten)]
+    pub base: BaseLog,
+    pub status: IdeaStatus,
+    /// Entry the idea was promoted to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promoted_to: Option<Uuid>,
+}
+
+impl ResearchLog for IdeaLog {
+    type Status = IdeaStatus;
+    const KIND: &'static str = "idea";
+    const HANDLE_PREFIX: &'static str = "I";
+
+    fn base(&self) -> &BaseLog {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseLog {
+        &mut self.base
+    }
+
+    fn status(&self) -> &Self::Status {
+        &self.status
+    }
+
+    fn status_mut(&mut self) -> &mut Self::Status {
+        &mut self.status
+    }
+
+    fn new(title: String, tags: HashSet<String>, author: Author) -> Self {
+        let now = utils::now();
+        Self {
+            base: BaseLog {
+                schema_version: SCHEMA_VERSION,
+                id: Uuid::now_v7(),
+                handle: None,
+                created_at: now,
+                updated_at: Some(now),
+                title,
+                tags,
+                project: None,
+                milestone: None,
+                created_by: author,
+                assignee: None,
+                references: HashSet::new(),
+                history: Vec::new(),
+                attachments: Vec::new(),
+                encrypted: false,
+            },
+            status: IdeaStatus::Inbox,
+            promoted_to: None,
+        }
+    }
+
+    fn update_status(&mut self, new_status: Self::Status) {
+        self.status = new_status;
+    }
+
+    /// An idea still in the inbox has not been looked at yet.
+    fn is_complete(&self) -> bool {
+        !matches!(self.status, IdeaStatus::Inbox)
+    }
+
+    fn get_target_path(&self, config: &Config, current_path: &Path) -> Result<PathBuf> {
+        let filename = current_path.file_name().unwrap();
+        if let Some(dir) = config.layout_dir(Self::KIND, &self.status.to_string()) {
+            return Ok(dir.join(filename));
+        }
+        // Archived ideas get a directory of their own inside the inbox: the
+        // shared archive holds knowledge entries, which ideas would pass for.
+        match self.status {
+            IdeaStatus::Inbox => Ok(config.storage.inbox_dir.join(filename)),
+            IdeaStatus::Archived => Ok(archived_ideas_dir(config).join(filename)),
+        }
+    }
+}
+
+fn archived_ideas_dir(config: &Config) -> PathBuf {
+    config.storage.inbox_dir.join("archived")
+}
+
+/// Log types an idea can be promoted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PromoteTarget {
+    Hypothesis,
+    Knowledge,
+}
+
+/// Outcome of [`IdeaManager::promote`].
+#[derive(Debug)]
+pub struct Promotion {
+    pub idea: IdeaLog,
+    /// Log type of the new entry.
+    pub kind: &'static str,
+    pub id: Uuid,
+    pub handle: Option<String>,
+}
+
+pub struct IdeaManager {
+    pub manager: LogManager<IdeaLog>,
+}
+
+impl IdeaManager {
+    pub fn new(config: Config) -> Self {
+        let search_dirs = vec![
+            config.storage.inbox_dir.clone(),
+            archived_ideas_dir(&config),
+        ];
+
+        Self {
+            manager: LogManager::<IdeaLog>::new(config, search_dirs),
+        }
+    }
+
+    /// Puts an idea in the inbox. There is no template and no duplicate check,
+    /// so capturing never gets in the way.
+    pub fn capture(&self, text: &str, tags: Option<Vec<String>>) -> Result<IdeaLog> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(anyhow::anyhow!("The idea is empty"));
+        }
+        let author = utils::get_author(&self.manager.config)?;
+        let mut idea = IdeaLog::new(text.to_string(), utils::normalize_tags(tags), author);
+        self.manager.assign_ids(&mut idea)?;
+
+        let content = update_markdown_frontmatter(&idea, &format!("\n# {}\n", text))?;
+        self.manager.save_log(&idea, &content, true)?;
+        Ok(idea)
+    }
+
+    /// Creates a hypothesis or knowledge entry from an idea in the inbox and
+    /// archives the idea. The new entry gets the idea's tags, and any notes
+    /// below its heading as body; `title` replaces the one-liner as its title.
+    pub fn promote(
+        &self,
+        partial_id: &str,
+        target: PromoteTarget,
+        title: Option<&str>,
+        allow_duplicate_title: bool,
+    ) -> Result<Promotion> {
+        let (idea, path) = self.manager.find_log(partial_id)?;
+        if idea.status != IdeaStatus::Inbox {
+            return Err(anyhow::anyhow!(
+                "Idea '{}' is {} and can no longer be promoted",
+                idea.base.title,
+                idea.status
+            ));
+        }
+
+        let body = self.manager.read_body(&path)?;
+        let notes = notes(&body, &idea.base.title);
+        let title = title.unwrap_or(&idea.base.title);
+        let tags = Some(idea.base.tags.iter().cloned().collect());
+        let config = self.manager.config.clone();
+        let (kind, base) = match target {
+            PromoteTarget::Hypothesis => {
+                let log = HypothesisManager::new(config).create(
+                    title,
+                    tags,
+                    notes,
+                    None,
+                    allow_duplicate_title,
+                    None,
+                )?;
+                (HypothesisLog::KIND, log.base)
+            }
+            PromoteTarget::Knowledge => {
+                let log = KnowledgeManager::new(config).create(
+                    title,
+                    tags,
+                    notes,
+                    None,
+                    allow_duplicate_title,
+                    None,
+                )?;
+                (KnowledgeLog::KIND, log.base)
+            }
+        };
+
+        let idea = self.manager.change_status_with(
+            partial_id,
+            IdeaStatus::Archived,
+            None,
+            true,
+            |idea| {
+                idea.promoted_to = Some(base.id);
+                Ok(())
+            },
+        )?;
+        Ok(Promotion {
+            idea,
+            kind,
+            id: base.id,
+            handle: base.handle,
+        })
+    }
+
+    pub fn list(&self, filter: &ListFilter<IdeaStatus>) -> Result<Vec<IdeaLog>> {
+        self.manager.list_logs(filter)
+    }
+
+    pub fn find(&self, partial_id: &str) -> Result<(IdeaLog, PathBuf)> {
+        self.manager.find_log(partial_id)
+    }
+}
+
+/// What was written below the heading of an idea, if anything.
+fn notes<'a>(body: &'a str, title: &str) -> Option<&'a str> {
+    let heading = format!("# {}", title);
+    let notes = body.strip_prefix(&heading).unwrap_or(body).trim();
+    (!notes.is_empty()).then_some(notes)
+}
+
+pub fn capture_idea(text: &str, tags: Option<Vec<String>>) -> Result<IdeaLog> {
+    let config = load_config()?;
+    let manager = IdeaManager::new(config);
+    manager.capture(text, tags)
+}
+
+pub fn promote_idea(
+    partial_id: &str,
+    target: PromoteTarget,
+    title: Option<&str>,
+    allow_duplicate_title: bool,
+) -> Result<Promotion> {
+    let config = load_config()?;
+    let manager = IdeaManager::new(config);
+    manager.promote(partial_id, target, title, allow_duplicate_title)
+}
+
+pub fn list_ideas(filter: &ListFilter<IdeaStatus>) -> Result<Vec<IdeaLog>> {
+    list_ideas_in(&load_config()?, filter)
+}
+
+/// Like [`list_ideas`], in the repository of `config`.
+pub fn list_ideas_in(config: &Config, filter: &ListFilter<IdeaStatus>) -> Result<Vec<IdeaLog>> {
+    let manager = IdeaManager::new(config.clone());
+    manager.list(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notes_below_heading() {
+        let title = "Shielding halves the error rate";
+        assert_eq!(notes("# Shielding halves the error rate", title), None);
+        assert_eq!(
+            notes(
+                "# Shielding halves the error rate\n\nTry lead first.",
+                title
+            ),
+            Some("Try lead first.")
+        );
+        assert_eq!(notes("Rewritten by hand", title), Some("Rewritten by hand"));
+    }
+}
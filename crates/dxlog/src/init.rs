@@ -8,6 +8,11 @@ pub fn init_repository(base_path: &Path) -> Result<()> {
         "archived",
         "knowledge-base/literature",
         "knowledge-base/hypotheses",
+        "journal",
+        "datasets",
+        "meetings",
+        "inbox",
+        "experiments",
         "research-logs",
     ];
 
@@ -20,6 +25,11 @@ pub fn init_repository(base_path: &Path) -> Result<()> {
     let hypothesis_template = include_str!("templates/hypothesis.default.jinja");
     let literature_template = include_str!("templates/literature.default.jinja");
     let knowledge_template = include_str!("templates/knowledge.default.jinja");
+    let journal_template = include_str!("templates/journal.default.jinja");
+    let dataset_template = include_str!("templates/dataset.default.jinja");
+    let meeting_template = include_str!("templates/meeting.default.jinja");
+    let experiment_template = include_str!("templates/experiment.default.jinja");
+    let report_template = include_str!("templates/report.default.jinja");
 
     fs::write(
         base_path.join("templates/hypothesis.jinja"),
@@ -38,6 +48,24 @@ pub fn init_repository(base_path: &Path) -> Result<()> {
     )
     .with_context(|| "Failed to write knowledge template")?;
 
+    fs::write(base_path.join("templates/journal.jinja"), journal_template)
+        .with_context(|| "Failed to write journal template")?;
+
+    fs::write(base_path.join("templates/dataset.jinja"), dataset_template)
+        .with_context(|| "Failed to write dataset template")?;
+
+    fs::write(base_path.join("templates/meeting.jinja"), meeting_template)
+        .with_context(|| "Failed to write meeting template")?;
+
+    fs::write(
+        base_path.join("templates/experiment.jinja"),
+        experiment_template,
+    )
+    .with_context(|| "Failed to write experiment template")?;
+
+    fs::write(base_path.join("templates/report.jinja"), report_template)
+        .with_context(|| "Failed to write report template")?;
+
     create_default_config(base_path)?;
 
     Ok(())
@@ -0,0 +1,179 @@
+use anyhow::Result;
+use dxlog_tools::{write_github_issues, IssueDraft};
+
+use crate::{
+    export::without_title, is_dry_run, load_config, md_frontmatter::extract_frontmatter, utils,
+    GitHubIssueLink, HypothesisLog, HypothesisManager, HypothesisStatus, ListFilter,
+};
+
+/// What `dxlog hypothesis sync-issues` did with the issue of a hypothesis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueAction {
+    Create,
+    Update,
+    /// The hypothesis is active again after its issue was closed.
+    Reopen,
+    Close,
+}
+
+/// The action for `log` in `repo`, with the issue number to reuse, or `None`
+/// if its issue needs no changes. Active hypotheses get an open issue; resolved
+/// ones have their issue closed once; issues in other repositories are ignored.
+fn plan(log: &HypothesisLog, repo: &str) -> Option<(IssueAction, Option<u64>)> {
+    let link = log.github_issue.as_ref().filter(|link| link.repo == repo);
+    if log.status == HypothesisStatus::ACTIVE {
+        Some(match link {
+            None => (IssueAction::Create, None),
+            Some(link) if link.closed => (IssueAction::Reopen, Some(link.number)),
+            Some(link) => (IssueAction::Update, Some(link.number)),
+        })
+    } else if log.status.is_concluded() || log.status == HypothesisStatus::ABANDONED {
+        link.filter(|link| !link.closed)
+            .map(|link| (IssueAction::Close, Some(link.number)))
+    } else {
+        None
+    }
+}
+
+/// The issue for `log` with the entry `body`. Abandoned and disproven
+/// hypotheses close as not planned, the other resolutions as completed.
+fn draft(log: &HypothesisLog, body: &str) -> IssueDraft {
+    let mut tags: Vec<String> = log.base.tags.iter().cloned().collect();
+    tags.sort();
+    let text = if log.base.encrypted {
+        "_The hypothesis is encrypted._".to_string()
+    } else {
+        without_title(body, &log.base.title).trim().to_string()
+    };
+    let entry = match &log.base.handle {
+        Some(handle) => handle.clone(),
+        None => log.base.id.to_string(),
+    };
+    IssueDraft {
+        title: log.base.title.clone(),
+        body: format!(
+            "{}\n\n---\nSynced from dxlog ({}, status: {}).",
+            text, entry, log.status
+        )
+        .trim_start()
+        .to_string(),
+        labels: tags,
+        open: log.status == HypothesisStatus::ACTIVE,
+        not_planned: [HypothesisStatus::ABANDONED, HypothesisStatus::DISPROVEN]
+            .contains(&log.status),
+    }
+}
+
+/// Writes a GitHub issue in `repo` (`owner/name`) for every active hypothesis,
+/// with its title, its body and its tags as labels, and closes the issues of
+/// hypotheses that have been concluded or abandoned since.
+///
+/// The issue is remembered in the hypothesis, so later syncs update it instead
+/// of opening another. Returns each changed hypothesis with what was done, and
+/// the issue number or why it failed. With `--dry-run` nothing is written and
+/// new issues have number 0.
+pub fn sync_hypothesis_issues(
+    token: &str,
+    repo: &str,
+) -> Result<Vec<(HypothesisLog, IssueAction, Result<u64>)>> {
+    match repo.split_once('/') {
+        Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {}
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Invalid GitHub repository '{}', expected owner/name",
+                repo
+            ))
+        }
+    }
+    let manager = HypothesisManager::new(load_config()?);
+    let mut planned = Vec::new();
+    let mut issues = Vec::new();
+    for (log, path) in manager.manager.list_log_files(&ListFilter::default())? {
+        let Some((action, number)) = plan(&log, repo) else {
+            continue;
+        };
+        let content = utils::load_entry_content(&path)?;
+        let (_, body) = extract_frontmatter::<HypothesisLog>(&content)?;
+        issues.push((number, draft(&log, &body)));
+        planned.push((log, path, action));
+    }
+
+    if is_dry_run() {
+        return Ok(planned
+            .into_iter()
+            .zip(issues)
+            .map(|((log, _, action), (number, _))| (log, action, Ok(number.unwrap_or(0))))
+            .collect());
+    }
+
+    let written = write_github_issues(token, repo, &issues)?;
+    let mut results = Vec::new();
+    for (((mut log, path, action), (_, issue)), number) in
+        planned.into_iter().zip(issues).zip(written)
+    {
+        let result = number.and_then(|number| {
+            let link = GitHubIssueLink {
+                repo: repo.to_string(),
+                number,
+                closed: !issue.open,
+            };
+            if log.github_issue.as_ref() != Some(&link) {
+                log.github_issue = Some(link);
+                manager.manager.update_log(&mut log, &path)?;
+            }
+            Ok(number)
+        });
+        results.push((log, action, result));
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{research_log::ResearchLog, test_support::author};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_plan_and_draft() {
+        let tags = HashSet::from(["perf".to_string(), "cache".to_string()]);
+        let mut log = HypothesisLog::new("Caching helps".to_string(), tags, author());
+        log.base.handle = Some("H-007".to_string());
+        assert_eq!(plan(&log, "acme/lab"), Some((IssueAction::Create, None)));
+
+        let issue = draft(&log, "# Caching helps\n\nWarm caches halve latency.\n");
+        assert_eq!(issue.labels, ["cache", "perf"]);
+        assert!(issue.open);
+        assert_eq!(
+            issue.body,
+            "Warm caches halve latency.\n\n---\nSynced from dxlog (H-007, status: active)."
+        );
+
+        log.github_issue = Some(GitHubIssueLink {
+            repo: "acme/lab".to_string(),
+            number: 12,
+            closed: false,
+        });
+        assert_eq!(
+            plan(&log, "acme/lab"),
+            Some((IssueAction::Update, Some(12)))
+        );
+        assert_eq!(plan(&log, "acme/other"), Some((IssueAction::Create, None)));
+
+        log.status = HypothesisStatus::DISPROVEN;
+        assert_eq!(plan(&log, "acme/lab"), Some((IssueAction::Close, Some(12))));
+        assert_eq!(plan(&log, "acme/other"), None);
+        let issue = draft(&log, "");
+        assert!(!issue.open && issue.not_planned);
+
+        log.github_issue.as_mut().unwrap().closed = true;
+        assert_eq!(plan(&log, "acme/lab"), None);
+        log.status = HypothesisStatus::ACTIVE;
+        assert_eq!(
+            plan(&log, "acme/lab"),
+            Some((IssueAction::Reopen, Some(12)))
+        );
+        log.status = HypothesisStatus::SUSPENDED;
+        assert_eq!(plan(&log, "acme/lab"), None);
+    }
+}
@@ -0,0 +1,211 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fmt,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    hooks::{run_hook, HookEvent},
+    load_config,
+    log_manager::{ListFilter, LogManager},
+    md_frontmatter::serialize_yaml_frontmatter,
+    migration::SCHEMA_VERSION,
+    research_log::ResearchLog,
+    template::{builtin_template, entry_context},
+    utils::{self, Author, BaseLog},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum JournalStatus {
+    Open,
+    Archived,
+}
+
+impl fmt::Display for JournalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            JournalStatus::Open => "open",
+            JournalStatus::Archived => "archived",
+        };
+        f.write_str(s)
+    }
+}
+
+/// A day in the lab notebook. There is at most one entry per day, named after its date.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JournalLog {
+    #[serde(flatten)]
+    pub base: BaseLog,
+    pub status: JournalStatus,
+}
+
+impl ResearchLog for JournalLog {
+    type Status = JournalStatus;
+    const KIND: &'static str = "journal";
+    const HANDLE_PREFIX: &'static str = "J";
+
+    fn base(&self) -> &BaseLog {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseLog {
+        &mut self.base
+    }
+
+    fn status(&self) -> &Self::Status {
+        &self.status
+    }
+
+    fn status_mut(&mut self) -> &mut Self::Status {
+        &mut self.status
+    }
+
+    fn new(title: String, tags: HashSet<String>, author: Author) -> Self {
+        let now = utils::now();
+        Self {
+            base: BaseLog {
+                schema_version: SCHEMA_VERSION,
+                id: Uuid::now_v7(),
+                handle: None,
+                created_at: now,
+                updated_at: Some(now),
+                title,
+                tags,
+                project: None,
+                milestone: None,
+                created_by: author,
+                assignee: None,
+                references: HashSet::new(),
+                history: Vec::new(),
+                attachments: Vec::new(),
+                encrypted: false,
+            },
+            status: JournalStatus::Open,
+        }
+    }
+
+    fn update_status(&mut self, new_status: Self::Status) {
+        self.status = new_status;
+    }
+
+    fn get_target_path(&self, config: &Config, current_path: &Path) -> Result<PathBuf> {
+        let filename = current_path.file_name().unwrap();
+        if let Some(dir) = config.layout_dir(Self::KIND, &self.status.to_string()) {
+            return Ok(dir.join(filename));
+        }
+        match self.status {
+            JournalStatus::Archived => Ok(config.storage.archive_dir.join(filename)),
+            JournalStatus::Open => Ok(config.storage.journal_dir.join(filename)),
+        }
+    }
+}
+
+pub struct JournalManager {
+    pub manager: LogManager<JournalLog>,
+}
+
+impl JournalManager {
+    pub fn new(config: Config) -> Self {
+        let search_dirs = vec![config.storage.journal_dir.clone()];
+
+        Self {
+            manager: LogManager::<JournalLog>::new(config, search_dirs),
+        }
+    }
+
+    /// Returns today's entry, creating it from the journal template if there is none yet.
+    ///
+    /// The flag is `true` if the entry was created by this call.
+    pub fn today(&self, template: Option<&str>) -> Result<(JournalLog, PathBuf, bool)> {
+        let today = chrono::Local::now().date_naive();
+        let filter = ListFilter {
+            since: Some(today),
+            until: Some(today),
+            ..Default::default()
+        };
+        if let Some((log, path)) = self.manager.list_log_files(&filter)?.into_iter().next() {
+            return Ok((log, path, false));
+        }
+
+        let config = &self.manager.config;
+        let author = utils::get_author(config)?;
+        let title = format!("Journal {}", today.format(&config.date_format));
+        let mut journal = JournalLog::new(title, HashSet::new(), author);
+        self.manager.assign_ids(&mut journal)?;
+
+        let yaml = serialize_yaml_frontmatter(&journal)?;
+        let template_path = config.template_for(JournalLog::KIND, template, &journal.base.tags)?;
+        // Repositories set up before journals existed have no journal template yet.
+        let template_content = match (template_path.exists(), template) {
+            (false, None | Some("default")) => builtin_template(JournalLog::KIND)
+                .unwrap_or_default()
+                .to_string(),
+            _ => utils::load_entry_content(&template_path)?,
+        };
+        let rendered = utils::render_entry(
+            &template_content,
+            entry_context(config, &journal.base, yaml),
+            None,
+        )?;
+
+        let file_name = format!("{}.md", today.format("%Y-%m-%d"));
+        let path = utils::unused_path(journal.get_target_path(config, Path::new(&file_name))?);
+        utils::save_entry_content(&path, &rendered)?;
+        run_hook(config, HookEvent::Create, &journal, &path, None);
+        Ok((journal, path, true))
+    }
+
+    pub fn update_status(
+        &self,
+        partial_id: &str,
+        new_status: JournalStatus,
+        reason: Option<&str>,
+        force: bool,
+    ) -> Result<()> {
+        self.manager
+            .change_status(partial_id, new_status, reason, force)?;
+        Ok(())
+    }
+
+    pub fn list(&self, filter: &ListFilter<JournalStatus>) -> Result<Vec<JournalLog>> {
+        self.manager.list_logs(filter)
+    }
+
+    pub fn find(&self, partial_id: &str) -> Result<(JournalLog, PathBuf)> {
+        self.manager.find_log(partial_id)
+    }
+}
+
+pub fn journal_today(template: Option<&str>) -> Result<(JournalLog, PathBuf, bool)> {
+    let config = load_config()?;
+    let manager = JournalManager::new(config);
+    manager.today(template)
+}
+
+pub fn update_journal_status(
+    partial_id: &str,
+    new_status: JournalStatus,
+    reason: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let manager = JournalManager::new(config);
+    manager.update_status(partial_id, new_status, reason, force)
+}
+
+pub fn list_journal(filter: &ListFilter<JournalStatus>) -> Result<Vec<JournalLog>> {
+    list_journal_in(&load_config()?, filter)
+}
+
+/// Like [`list_journal`], in the repository of `config`.
+pub fn list_journal_in(
+    config: &Config,
+    filter: &ListFilter<JournalStatus>,
+) -> Result<Vec<JournalLog>> {
+    let manager = JournalManager::new(config.clone());
+    manager.list(filter)
+}
@@ -1,33 +1,43 @@
 use anyhow::Result;
+use chrono::{DateTime, FixedOffset, NaiveDate};
 use minijinja::context;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::HashSet,
+    fmt,
+    path::{Path, PathBuf},
+};
 use uuid::Uuid;
 
 use crate::{
     config::Config,
     load_config,
-    log_manager::LogManager,
+    log_manager::{ListFilter, LogManager, Renamed},
     md_frontmatter::serialize_yaml_frontmatter,
+    migration::SCHEMA_VERSION,
     research_log::ResearchLog,
+    team::find_team_member,
+    template::entry_context,
     utils::{self, Author, BaseLog},
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, clap::ValueEnum)]
 pub enum KnowledgeStatus {
     Draft,
+    InReview,
     Published,
     Archived,
 }
 
-impl ToString for KnowledgeStatus {
-    fn to_string(&self) -> String {
-        match self {
+impl fmt::Display for KnowledgeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
             KnowledgeStatus::Draft => "draft",
+            KnowledgeStatus::InReview => "in_review",
             KnowledgeStatus::Published => "published",
             KnowledgeStatus::Archived => "archived",
-        }
-        .to_string()
+        };
+        f.write_str(s)
     }
 }
 
@@ -36,10 +46,35 @@ pub struct KnowledgeLog {
     #[serde(flatten)]
     pub base: BaseLog,
     pub status: KnowledgeStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<Review>,
+    /// The Notion page the entry was last exported to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notion: Option<NotionLink>,
+}
+
+/// A page in a Notion database written by `dxlog export notion`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotionLink {
+    pub database: String,
+    pub page: String,
+}
+
+/// A request for a team member to review a knowledge entry before it is published.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Review {
+    pub reviewer: Author,
+    pub requested_at: DateTime<FixedOffset>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_at: Option<DateTime<FixedOffset>>,
 }
 
 impl ResearchLog for KnowledgeLog {
     type Status = KnowledgeStatus;
+    const KIND: &'static str = "knowledge";
+    const HANDLE_PREFIX: &'static str = "K";
 
     fn base(&self) -> &BaseLog {
         &self.base
@@ -58,17 +93,29 @@ impl ResearchLog for KnowledgeLog {
     }
 
     fn new(title: String, tags: HashSet<String>, author: Author) -> Self {
-        let now = chrono::Local::now();
+        let now = utils::now();
         Self {
             base: BaseLog {
-                id: Uuid::new_v4(),
-                date: now.format("%Y-%m-%d").to_string(),
+                schema_version: SCHEMA_VERSION,
+                id: Uuid::now_v7(),
+                handle: None,
+                created_at: now,
+                updated_at: Some(now),
                 title,
                 tags,
+                project: None,
+                milestone: None,
                 created_by: author,
+                assignee: None,
                 references: HashSet::new(),
+                history: Vec::new(),
+                attachments: Vec::new(),
+                encrypted: false,
             },
             status: KnowledgeStatus::Draft,
+            due: None,
+            review: None,
+            notion: None,
         }
     }
 
@@ -76,8 +123,15 @@ impl ResearchLog for KnowledgeLog {
         self.status = new_status;
     }
 
-    fn get_target_path(&self, config: &Config, current_path: &PathBuf) -> Result<PathBuf> {
+    fn is_complete(&self) -> bool {
+        matches!(self.status, KnowledgeStatus::Published)
+    }
+
+    fn get_target_path(&self, config: &Config, current_path: &Path) -> Result<PathBuf> {
         let filename = current_path.file_name().unwrap();
+        if let Some(dir) = config.layout_dir(Self::KIND, &self.status.to_string()) {
+            return Ok(dir.join(filename));
+        }
         match self.status {
             KnowledgeStatus::Archived => Ok(config.storage.archive_dir.join(filename)),
             KnowledgeStatus::Published => Ok(config.storage.knowledge_base_dir.join(filename)),
@@ -102,36 +156,167 @@ impl KnowledgeManager {
         }
     }
 
-    pub fn create(&self, title: &str, tags: Option<Vec<String>>) -> Result<KnowledgeLog> {
-        let author = utils::get_git_author()?;
-        let knowledge = KnowledgeLog::new(title.to_string(), utils::normalize_tags(tags), author);
+    pub fn create(
+        &self,
+        title: &str,
+        tags: Option<Vec<String>>,
+        body: Option<&str>,
+        template: Option<&str>,
+        allow_duplicate_title: bool,
+        due: Option<NaiveDate>,
+    ) -> Result<KnowledgeLog> {
+        let mut knowledge = self.new_entry(title, tags)?;
+        knowledge.due = due;
+        self.save_new(knowledge, body, template, allow_duplicate_title)
+    }
+
+    /// A knowledge entry by the current git author, to fill in before [`Self::save_new`].
+    pub(crate) fn new_entry(&self, title: &str, tags: Option<Vec<String>>) -> Result<KnowledgeLog> {
+        let author = utils::get_author(&self.manager.config)?;
+        Ok(KnowledgeLog::new(
+            title.to_string(),
+            utils::normalize_tags(tags),
+            author,
+        ))
+    }
+
+    /// Gives a new entry its IDs and writes it from its template.
+    pub(crate) fn save_new(
+        &self,
+        mut knowledge: KnowledgeLog,
+        body: Option<&str>,
+        template: Option<&str>,
+        allow_duplicate_title: bool,
+    ) -> Result<KnowledgeLog> {
+        self.manager.assign_ids(&mut knowledge)?;
 
         let yaml = serialize_yaml_frontmatter(&knowledge)?;
-        let template_content = utils::load_entry_content(&self.manager.config.templates.knowledge)?;
+        let template_path =
+            self.manager
+                .config
+                .template_for(KnowledgeLog::KIND, template, &knowledge.base.tags)?;
+        let template_content = utils::load_entry_content(&template_path)?;
 
-        let env = minijinja::Environment::new();
-        let template = env.template_from_str(&template_content)?;
-        let rendered = template.render(context! {
-            research_log => yaml,
-            title => knowledge.base.title,
-        })?;
+        let body = body.map(str::trim);
+        let rendered = utils::render_entry(
+            &template_content,
+            context! {
+                body => body,
+                ..entry_context(&self.manager.config, &knowledge.base, yaml)
+            },
+            body,
+        )?;
 
-        self.manager.save_log(&knowledge, &rendered)?;
+        self.manager
+            .save_log(&knowledge, &rendered, allow_duplicate_title)?;
         Ok(knowledge)
     }
 
-    pub fn update_status(&self, partial_id: &str, new_status: KnowledgeStatus) -> Result<()> {
+    /// Retitles an entry, renaming its file and updating links to it.
+    pub fn rename(
+        &self,
+        partial_id: &str,
+        title: &str,
+        allow_duplicate_title: bool,
+    ) -> Result<Renamed<KnowledgeLog>> {
+        self.manager
+            .rename_log(partial_id, title, allow_duplicate_title)
+    }
+
+    /// Sets the date the draft should be published by, or clears it with `None`.
+    pub fn set_due(&self, partial_id: &str, due: Option<NaiveDate>) -> Result<KnowledgeLog> {
         let (mut knowledge, file_path) = self.manager.find_log(partial_id)?;
-        knowledge.update_status(new_status);
-        self.manager.update_log(&mut knowledge, &file_path)
+        knowledge.due = due;
+        self.manager.update_log(&mut knowledge, &file_path)?;
+        Ok(knowledge)
     }
 
-    pub fn list(
+    /// Changes the status of an entry. With `[workflow] require_review`, entries
+    /// are only published once their review is approved, unless `force` is set.
+    pub fn update_status(
         &self,
-        status: Option<KnowledgeStatus>,
-        tags: Option<Vec<String>>,
-    ) -> Result<Vec<KnowledgeLog>> {
-        self.manager.list_logs(status, tags)
+        partial_id: &str,
+        new_status: KnowledgeStatus,
+        reason: Option<&str>,
+        force: bool,
+    ) -> Result<()> {
+        let require_review = !force
+            && new_status == KnowledgeStatus::Published
+            && self.manager.config.workflow.require_review;
+        self.manager
+            .change_status_with(partial_id, new_status, reason, force, |knowledge| {
+                let approved = knowledge
+                    .review
+                    .as_ref()
+                    .is_some_and(|review| review.approved_at.is_some());
+                if require_review && !approved {
+                    return Err(anyhow::anyhow!(
+                        "'{}' needs an approved review before it can be published. \
+                         Use `dxlog knowledge request-review` and `dxlog knowledge approve`, \
+                         or --force",
+                        knowledge.base.title
+                    ));
+                }
+                Ok(())
+            })?;
+        Ok(())
+    }
+
+    /// Puts a draft in review by `reviewer`, a member of `[team]`. Requesting
+    /// again replaces the reviewer and any earlier approval.
+    pub fn request_review(
+        &self,
+        partial_id: &str,
+        reviewer: &str,
+        force: bool,
+    ) -> Result<KnowledgeLog> {
+        let reviewer = find_team_member(&self.manager.config, reviewer)?;
+        self.manager.change_status_with(
+            partial_id,
+            KnowledgeStatus::InReview,
+            None,
+            force,
+            |knowledge| {
+                if !matches!(
+                    knowledge.status,
+                    KnowledgeStatus::Draft | KnowledgeStatus::InReview
+                ) {
+                    return Err(anyhow::anyhow!(
+                        "Only drafts can be reviewed, but '{}' is {}",
+                        knowledge.base.title,
+                        knowledge.status
+                    ));
+                }
+                knowledge.review = Some(Review {
+                    reviewer,
+                    requested_at: utils::now(),
+                    approved_at: None,
+                });
+                Ok(())
+            },
+        )
+    }
+
+    /// Records the approval of an entry in review, so it can be published.
+    pub fn approve(&self, partial_id: &str) -> Result<KnowledgeLog> {
+        let (mut knowledge, file_path) = self.manager.find_log(partial_id)?;
+        let Some(review) = knowledge
+            .review
+            .as_mut()
+            .filter(|_| knowledge.status == KnowledgeStatus::InReview)
+        else {
+            return Err(anyhow::anyhow!(
+                "'{}' is not in review. Use `dxlog knowledge request-review` first",
+                knowledge.base.title
+            ));
+        };
+        review.approved_at = Some(utils::now());
+        self.manager.update_log(&mut knowledge, &file_path)?;
+        Ok(knowledge)
+    }
+
+    pub fn list(&self, filter: &ListFilter<KnowledgeStatus>) -> Result<Vec<KnowledgeLog>> {
+        self.manager.list_logs(filter)
     }
 
     pub fn find(&self, partial_id: &str) -> Result<(KnowledgeLog, PathBuf)> {
@@ -139,23 +324,71 @@ impl KnowledgeManager {
     }
 }
 
-pub fn create_knowledge(title: &str, tags: Option<Vec<String>>) -> Result<KnowledgeLog> {
+pub fn create_knowledge(
+    title: &str,
+    tags: Option<Vec<String>>,
+    body: Option<&str>,
+    template: Option<&str>,
+    allow_duplicate_title: bool,
+    due: Option<NaiveDate>,
+) -> Result<KnowledgeLog> {
     let config = load_config()?;
     let manager = KnowledgeManager::new(config);
-    manager.create(title, tags)
+    manager.create(title, tags, body, template, allow_duplicate_title, due)
 }
 
-pub fn update_knowledge_status(partial_id: &str, new_status: KnowledgeStatus) -> Result<()> {
+pub fn set_knowledge_due(partial_id: &str, due: Option<NaiveDate>) -> Result<KnowledgeLog> {
     let config = load_config()?;
     let manager = KnowledgeManager::new(config);
-    manager.update_status(partial_id, new_status)
+    manager.set_due(partial_id, due)
 }
 
-pub fn list_knowledge(
-    status: Option<KnowledgeStatus>,
-    tags: Option<Vec<String>>,
-) -> Result<Vec<KnowledgeLog>> {
+pub fn update_knowledge_status(
+    partial_id: &str,
+    new_status: KnowledgeStatus,
+    reason: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let manager = KnowledgeManager::new(config);
+    manager.update_status(partial_id, new_status, reason, force)
+}
+
+pub fn request_knowledge_review(
+    partial_id: &str,
+    reviewer: &str,
+    force: bool,
+) -> Result<KnowledgeLog> {
     let config = load_config()?;
     let manager = KnowledgeManager::new(config);
-    manager.list(status, tags)
+    manager.request_review(partial_id, reviewer, force)
+}
+
+pub fn approve_knowledge(partial_id: &str) -> Result<KnowledgeLog> {
+    let config = load_config()?;
+    let manager = KnowledgeManager::new(config);
+    manager.approve(partial_id)
+}
+
+pub fn rename_knowledge(
+    partial_id: &str,
+    title: &str,
+    allow_duplicate_title: bool,
+) -> Result<Renamed<KnowledgeLog>> {
+    let config = load_config()?;
+    let manager = KnowledgeManager::new(config);
+    manager.rename(partial_id, title, allow_duplicate_title)
+}
+
+pub fn list_knowledge(filter: &ListFilter<KnowledgeStatus>) -> Result<Vec<KnowledgeLog>> {
+    list_knowledge_in(&load_config()?, filter)
+}
+
+/// Like [`list_knowledge`], in the repository of `config`.
+pub fn list_knowledge_in(
+    config: &Config,
+    filter: &ListFilter<KnowledgeStatus>,
+) -> Result<Vec<KnowledgeLog>> {
+    let manager = KnowledgeManager::new(config.clone());
+    manager.list(filter)
 }
@@ -1,18 +1,119 @@
+mod attachment;
+mod backup;
+mod board;
+mod citation;
 mod config;
+mod dataset;
+mod dry_run;
+mod due;
+mod encryption;
+mod error;
+mod experiment;
+mod export;
+mod handle;
+mod hooks;
 mod hypothesis;
+mod idea;
 mod init;
+mod issues;
+mod journal;
 mod knowledge;
 mod literature;
+#[cfg(feature = "llm")]
+mod llm;
 mod log_manager;
+mod log_type;
+mod lookup;
 mod md_frontmatter;
+mod meeting;
+mod migration;
+mod milestone;
+mod note;
+mod notify;
+mod operations;
+mod pdf_text;
+mod project;
+mod recent;
 mod reference;
+mod related;
+mod report;
 mod research_log;
+mod search;
+#[cfg(feature = "llm")]
+mod semantic;
+mod sync;
+mod tag;
+mod team;
+mod template;
+#[cfg(test)]
+mod test_support;
+mod trash;
+mod watch;
+mod workspace;
+mod zotero;
 
 pub mod utils;
 
+pub use attachment::*;
+pub use backup::{
+    create_backup, list_backups, pull_backup, push_backup, restore_backup, Backup, BACKUP_DIR,
+};
+pub use board::*;
+pub use citation::*;
 pub use config::*;
+pub use dataset::*;
+pub use dry_run::{is_dry_run, take_planned_changes, PlannedChange, DRY_RUN_ENV_VAR};
+pub use due::*;
+pub use encryption::{
+    decrypt_entry, encrypt_entry, identity_path, read_entry, write_entry_body, EntryContent,
+    IDENTITY_ENV_VAR,
+};
+pub use error::*;
+pub use experiment::*;
+pub use export::*;
+pub use handle::COUNTERS_FILE;
+pub use hooks::take_hook_failures;
 pub use hypothesis::*;
+pub use idea::*;
 pub use init::*;
+pub use issues::*;
+pub use journal::*;
 pub use knowledge::*;
 pub use literature::*;
+#[cfg(feature = "llm")]
+pub use llm::*;
+pub use log_manager::{
+    is_strict, take_skipped_entries, ListFilter, Renamed, SkippedEntry, SortKey, TextQuery,
+    STRICT_ENV_VAR,
+};
+pub use log_type::*;
+pub use lookup::*;
+pub use meeting::*;
+pub use migration::{migrate_entries, MigrationReport, SCHEMA_VERSION};
+pub use milestone::*;
+pub use note::*;
+pub use notify::take_notification_failures;
+pub use operations::{last_operation, undo_last_operation, FileChange, Operation, OPERATIONS_FILE};
+pub use pdf_text::{quote_literature, text_path, Passage, Quotes, PAGE_BREAK};
+pub use project::*;
+pub use recent::*;
 pub use reference::*;
+pub use related::*;
+pub use report::*;
+pub use research_log::LogStatus;
+pub use search::*;
+#[cfg(feature = "llm")]
+pub use semantic::*;
+pub use sync::{sync_repository, FrontmatterConflict, SyncOptions, SyncReport};
+pub use tag::*;
+pub use team::*;
+pub use template::*;
+pub use trash::*;
+pub use watch::{watch_entries, WatchEvent, WatchOptions};
+pub use workspace::{
+    qualified_id, select_repos, workspace_repos, workspaces_path, WorkspaceRepo, ALL_REPOS,
+    WORKSPACES_ENV_VAR,
+};
+pub use zotero::*;
+
+pub use dxlog_tools::{ChatService, TrackerAuth, OFFLINE_ENV_VAR};
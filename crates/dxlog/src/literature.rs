@@ -1,17 +1,29 @@
 use anyhow::Result;
-use chrono::Local;
-use dxlog_tools::{fetch_arxiv_metadata, fetch_github_metadata};
+use dxlog_tools::{
+    biorxiv_doi, bypass_cache, fetch_arxiv_metadata, fetch_biorxiv_metadata,
+    fetch_crossref_metadata, fetch_github_metadata, fetch_openreview_metadata, is_offline,
+    normalize_doi, parse_bibtex, parse_ris, BibRecord, OpenReviewReview,
+};
 use minijinja::context;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+use crate::citation::{citation, unique_citekey};
 use crate::config::{load_config, Config};
-use crate::log_manager::LogManager;
+use crate::error::LogError;
+use crate::log_manager::{ListFilter, LogManager, Renamed};
+use crate::lookup::EntryMatch;
 use crate::md_frontmatter::{extract_frontmatter, serialize_yaml_frontmatter};
+use crate::migration::SCHEMA_VERSION;
+use crate::note::section_text;
 use crate::research_log::ResearchLog;
+use crate::template::entry_context;
+use crate::trash::Tombstone;
 use crate::utils::{self, Author, BaseLog};
+use crate::{KnowledgeLog, KnowledgeManager};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, clap::ValueEnum)]
 pub enum LiteratureStatus {
@@ -20,23 +32,139 @@ pub enum LiteratureStatus {
     Archived,
 }
 
-impl ToString for LiteratureStatus {
-    fn to_string(&self) -> String {
-        match self {
+impl fmt::Display for LiteratureStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
             LiteratureStatus::InProgress => "in_progress",
             LiteratureStatus::Completed => "completed",
             LiteratureStatus::Archived => "archived",
-        }
-        .to_string()
+        };
+        f.write_str(s)
+    }
+}
+
+/// How thoroughly a paper has been read.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReadingProgress {
+    Skimmed,
+    Read,
+    DeepRead,
+}
+
+impl fmt::Display for ReadingProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ReadingProgress::Skimmed => "skimmed",
+            ReadingProgress::Read => "read",
+            ReadingProgress::DeepRead => "deep-read",
+        };
+        f.write_str(s)
     }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 pub struct Source {
     pub doi: Option<String>,
     pub arxiv_url: Option<String>,
     pub pdf_url: Option<String>,
     pub repository_url: Option<String>,
+    /// bioRxiv or medRxiv page of a preprint.
+    pub preprint_url: Option<String>,
+    /// OpenReview forum of a conference submission.
+    pub openreview_url: Option<String>,
+    /// Where the paper was published or submitted, e.g. "ICLR 2024 poster".
+    pub venue: Option<String>,
+    /// Year the paper was published, when known.
+    pub year: Option<i32>,
+    /// Acceptance decision reported by OpenReview.
+    pub decision: Option<String>,
+    /// GitHub stars of `repository_url` when last fetched.
+    pub stars: Option<u32>,
+    /// Citations of `doi` known to Crossref when last fetched.
+    pub citations: Option<u32>,
+    /// Authors of the paper, in the order listed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    /// GitHub topics of `repository_url`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+}
+
+impl Source {
+    /// The link the entry was created from, or the DOI resolver for DOI-only sources.
+    pub fn url(&self) -> Option<String> {
+        [
+            &self.preprint_url,
+            &self.openreview_url,
+            &self.arxiv_url,
+            &self.repository_url,
+        ]
+        .into_iter()
+        .find_map(Clone::clone)
+        .or_else(|| {
+            self.doi
+                .as_ref()
+                .map(|doi| format!("https://doi.org/{}", doi))
+        })
+        .or_else(|| self.pdf_url.clone())
+    }
+
+    pub fn from_record(record: &BibRecord) -> Self {
+        let mut source = Source {
+            doi: record.doi.clone(),
+            pdf_url: record.pdf_url.clone(),
+            year: record.year.as_deref().and_then(parse_year),
+            authors: record.authors.clone(),
+            ..Default::default()
+        };
+        if let Some(url) = &record.url {
+            if is_biorxiv_url(url) {
+                source.preprint_url = Some(url.clone());
+            } else if url.contains("openreview.net") {
+                source.openreview_url = Some(url.clone());
+            } else if url.contains("arxiv.org") {
+                source.arxiv_url = Some(url.clone());
+            } else if url.contains("github.com") {
+                source.repository_url = Some(url.clone());
+            } else if url.to_lowercase().ends_with(".pdf") && source.pdf_url.is_none() {
+                source.pdf_url = Some(url.clone());
+            }
+        }
+        source
+    }
+}
+
+/// A frontmatter field changed by [`LiteratureManager::refresh`]. `None` means unset.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// Where a literature entry lives in Zotero.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ZoteroLink {
+    /// API path of the library, `users/<id>` or `groups/<id>`.
+    pub library: String,
+    /// Key of the item in the library.
+    pub item: String,
+    /// Key of the note the completed review was pushed to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Section of a review whose contents [`LiteratureManager::distill`] copies.
+const TAKEAWAYS_HEADING: &str = "## Takeaways";
+
+#[derive(Default)]
+pub struct ImportSummary {
+    pub created: Vec<LiteratureLog>,
+    /// Titles (or URLs) of records skipped because their source or title already exists.
+    pub duplicates: Vec<String>,
+    /// Record title (or key) and the reason it could not be imported.
+    pub failed: Vec<(String, String)>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -45,14 +173,30 @@ pub struct LiteratureLog {
     pub base: BaseLog,
     pub status: LiteratureStatus,
     pub source: Source,
+    /// Key to cite the entry with, e.g. `[@smith2024quantum]` in Pandoc.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citekey: Option<String>,
+    /// The Zotero item the entry was imported from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zotero: Option<ZoteroLink>,
+    /// How worthwhile the paper is, from 1 to 5.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading_progress: Option<ReadingProgress>,
     #[serde(skip)]
     pub abstract_text: Option<String>,
     #[serde(skip)]
     pub repository_description: Option<String>,
+    /// Reviews to write into the body when the entry is created.
+    #[serde(skip)]
+    pub reviews: Vec<OpenReviewReview>,
 }
 
 impl ResearchLog for LiteratureLog {
     type Status = LiteratureStatus;
+    const KIND: &'static str = "literature";
+    const HANDLE_PREFIX: &'static str = "L";
 
     fn base(&self) -> &BaseLog {
         &self.base
@@ -71,20 +215,34 @@ impl ResearchLog for LiteratureLog {
     }
 
     fn new(title: String, tags: HashSet<String>, author: Author) -> Self {
-        let now = Local::now();
+        let now = utils::now();
         Self {
             base: BaseLog {
-                id: Uuid::new_v4(),
-                date: now.format("%Y-%m-%d").to_string(),
+                schema_version: SCHEMA_VERSION,
+                id: Uuid::now_v7(),
+                handle: None,
+                created_at: now,
+                updated_at: Some(now),
                 title,
                 tags,
+                project: None,
+                milestone: None,
                 created_by: author,
+                assignee: None,
                 references: HashSet::new(),
+                history: Vec::new(),
+                attachments: Vec::new(),
+                encrypted: false,
             },
             status: LiteratureStatus::InProgress,
             source: Source::default(),
+            citekey: None,
+            zotero: None,
+            rating: None,
+            reading_progress: None,
             abstract_text: None,
             repository_description: None,
+            reviews: Vec::new(),
         }
     }
 
@@ -92,8 +250,15 @@ impl ResearchLog for LiteratureLog {
         self.status = new_status;
     }
 
-    fn get_target_path(&self, config: &Config, current_path: &PathBuf) -> Result<PathBuf> {
+    fn is_complete(&self) -> bool {
+        matches!(self.status, LiteratureStatus::Completed)
+    }
+
+    fn get_target_path(&self, config: &Config, current_path: &Path) -> Result<PathBuf> {
         let filename = current_path.file_name().unwrap();
+        if let Some(dir) = config.layout_dir(Self::KIND, &self.status.to_string()) {
+            return Ok(dir.join(filename));
+        }
         let lit_path = |base: PathBuf| base.join("literature").join(filename);
 
         match self.status {
@@ -113,18 +278,43 @@ impl LiteratureManager {
         let search_dirs = vec![
             config.storage.active_dir.join("literature"),
             config.storage.knowledge_base_dir.join("literature"),
+            config.storage.archive_dir.join("literature"),
         ];
         Self {
             manager: LogManager::new(config, search_dirs),
         }
     }
 
-    pub fn create(&self, url: &str, tags: Option<Vec<String>>) -> Result<LiteratureLog> {
-        let author = utils::get_git_author()?;
+    /// Creates an entry for `url`, using `title` instead of the fetched one if given.
+    ///
+    /// When offline (`DXLOG_OFFLINE`) metadata only comes from the response
+    /// cache; without it the entry is created from `url` and `title` alone.
+    pub fn create(
+        &self,
+        url: &str,
+        title: Option<&str>,
+        tags: Option<Vec<String>>,
+        template: Option<&str>,
+        allow_duplicate_title: bool,
+        include_reviews: bool,
+    ) -> Result<LiteratureLog> {
+        let author = utils::get_author(&self.manager.config)?;
 
-        let source = if url.contains("arxiv.org") {
+        let mut source = if is_biorxiv_url(url) {
+            Source {
+                doi: biorxiv_doi(url),
+                preprint_url: Some(url.to_string()),
+                ..Default::default()
+            }
+        } else if url.contains("arxiv.org") {
             Source {
                 arxiv_url: Some(url.to_string()),
+                year: arxiv_year(url),
+                ..Default::default()
+            }
+        } else if url.contains("openreview.net") {
+            Source {
+                openreview_url: Some(url.to_string()),
                 ..Default::default()
             }
         } else if url.contains("github.com") {
@@ -141,46 +331,361 @@ impl LiteratureManager {
             return Err(anyhow::anyhow!("Unsupported URL format"));
         };
 
-        let (title, abstract_text, repo_description) = fetch_metadata(&source)?;
+        let metadata = match fetch_metadata(&mut source) {
+            Ok(metadata) => metadata,
+            Err(_) if is_offline() && title.is_some() => SourceMetadata::default(),
+            Err(e) if is_offline() => {
+                return Err(
+                    e.context("Working offline without cached metadata; a title is required")
+                )
+            }
+            Err(e) => return Err(e),
+        };
+        let title = title.map_or(metadata.title, str::to_string);
         let mut literature = LiteratureLog::new(title, utils::normalize_tags(tags), author);
         literature.source = source;
-        literature.abstract_text = abstract_text.clone();
-        literature.repository_description = repo_description;
+        literature.abstract_text = metadata.abstract_text;
+        literature.repository_description = metadata.repository_description;
+        if include_reviews {
+            literature.reviews = metadata.reviews;
+        }
+        literature.citekey = Some(unique_citekey(&literature, &mut self.citekeys()?));
+        self.manager.assign_ids(&mut literature)?;
+
+        self.render_and_save(&literature, template, allow_duplicate_title)?;
+        Ok(literature)
+    }
+
+    /// Creates one entry per imported record, skipping records whose DOI is already
+    /// in the collection (or earlier in the same import).
+    pub fn import(
+        &self,
+        records: Vec<BibRecord>,
+        tags: Option<Vec<String>>,
+    ) -> Result<ImportSummary> {
+        self.import_with(records, tags, |_, _| {})
+    }
+
+    /// Like [`Self::import`], applying `edit` to each entry before it is saved.
+    pub(crate) fn import_with(
+        &self,
+        records: Vec<BibRecord>,
+        tags: Option<Vec<String>>,
+        edit: impl Fn(&BibRecord, &mut LiteratureLog),
+    ) -> Result<ImportSummary> {
+        let author = utils::get_author(&self.manager.config)?;
+        let tags = utils::normalize_tags(tags);
+        let mut known_dois: HashSet<String> = self
+            .manager
+            .list_logs(&ListFilter::default())?
+            .into_iter()
+            .filter_map(|l| l.source.doi.map(|d| normalize_doi(&d)))
+            .collect();
+
+        let mut citekeys = self.citekeys()?;
+
+        let mut summary = ImportSummary::default();
+        for record in records {
+            if record.title.is_empty() {
+                summary
+                    .failed
+                    .push((record.key.unwrap_or_default(), "record has no title".into()));
+                continue;
+            }
+            if let Some(doi) = &record.doi {
+                if !known_dois.insert(normalize_doi(doi)) {
+                    summary.duplicates.push(record.title);
+                    continue;
+                }
+            }
+
+            let mut literature =
+                LiteratureLog::new(record.title.clone(), tags.clone(), author.clone());
+            literature.source = Source::from_record(&record);
+            literature.abstract_text = record.abstract_text.clone();
+            edit(&record, &mut literature);
+            literature.citekey = Some(unique_citekey(&literature, &mut citekeys));
+            self.manager.assign_ids(&mut literature)?;
+
+            match self.render_and_save(&literature, None, false) {
+                Ok(_) => summary.created.push(literature),
+                Err(e) if matches!(e.downcast_ref(), Some(LogError::DuplicateTitle { .. })) => {
+                    summary.duplicates.push(record.title)
+                }
+                Err(e) => summary.failed.push((record.title, e.to_string())),
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Creates one entry per URL, fetching metadata as [`Self::create`] does.
+    ///
+    /// Failures are collected instead of aborting, so one bad link does not stop
+    /// the rest. URLs already in the collection (or imported earlier in the list) are skipped.
+    pub fn import_urls(
+        &self,
+        urls: Vec<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<ImportSummary> {
+        let mut known_sources: HashSet<String> = self
+            .manager
+            .list_logs(&ListFilter::default())?
+            .into_iter()
+            .flat_map(|l| {
+                let source = l.source;
+                [
+                    source.doi,
+                    source.arxiv_url,
+                    source.repository_url,
+                    source.preprint_url,
+                    source.openreview_url,
+                ]
+            })
+            .flatten()
+            .map(|s| source_key(&s))
+            .collect();
+
+        let mut summary = ImportSummary::default();
+        for url in urls {
+            let key = source_key(&url);
+            if known_sources.contains(&key) {
+                summary.duplicates.push(url);
+                continue;
+            }
+            match self.create(&url, None, tags.clone(), None, false, false) {
+                Ok(literature) => {
+                    known_sources.insert(key);
+                    summary.created.push(literature)
+                }
+                Err(e) if matches!(e.downcast_ref(), Some(LogError::DuplicateTitle { .. })) => {
+                    summary.duplicates.push(url)
+                }
+                Err(e) => summary.failed.push((url, format!("{:#}", e))),
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Re-fetches the metadata of an entry and saves it if anything changed.
+    pub fn refresh(&self, partial_id: &str) -> Result<(LiteratureLog, Vec<FieldChange>)> {
+        let (mut literature, path) = self.manager.find_log(partial_id)?;
+        let changes = self.refresh_entry(&mut literature, &path)?;
+        Ok((literature, changes))
+    }
+
+    /// Refreshes every entry. A failed fetch is reported for that entry only.
+    pub fn refresh_all(&self) -> Result<Vec<(LiteratureLog, Result<Vec<FieldChange>>)>> {
+        Ok(self
+            .manager
+            .list_log_files(&ListFilter::default())?
+            .into_iter()
+            .map(|(mut literature, path)| {
+                let changes = self.refresh_entry(&mut literature, &path);
+                (literature, changes)
+            })
+            .collect())
+    }
 
-        let yaml = serialize_yaml_frontmatter(&literature)?;
-        let template_content =
-            utils::load_entry_content(&self.manager.config.templates.literature)?;
+    fn refresh_entry(
+        &self,
+        literature: &mut LiteratureLog,
+        path: &Path,
+    ) -> Result<Vec<FieldChange>> {
+        let mut source = literature.source.clone();
+        let metadata = bypass_cache(|| fetch_metadata(&mut source))?;
+
+        let mut changes = Vec::new();
+        if !metadata.title.is_empty() && metadata.title != literature.base.title {
+            changes.push(FieldChange {
+                field: "title".to_string(),
+                old: Some(std::mem::replace(
+                    &mut literature.base.title,
+                    metadata.title,
+                )),
+                new: Some(literature.base.title.clone()),
+            });
+        }
+        changes.extend(source_changes(&literature.source, &source)?);
+
+        if !changes.is_empty() {
+            literature.source = source;
+            self.manager.update_log(literature, path)?;
+        }
+        Ok(changes)
+    }
+
+    /// Citation keys already in use.
+    pub(crate) fn citekeys(&self) -> Result<HashSet<String>> {
+        Ok(self
+            .manager
+            .list_logs(&ListFilter::default())?
+            .into_iter()
+            .filter_map(|l| l.citekey)
+            .collect())
+    }
+
+    fn render_and_save(
+        &self,
+        literature: &LiteratureLog,
+        template: Option<&str>,
+        allow_duplicate_title: bool,
+    ) -> Result<PathBuf> {
+        let yaml = serialize_yaml_frontmatter(literature)?;
+        let template_path = self.manager.config.template_for(
+            LiteratureLog::KIND,
+            template,
+            &literature.base.tags,
+        )?;
+        let template_content = utils::load_entry_content(&template_path)?;
 
         let env = minijinja::Environment::new();
         let template = env.template_from_str(&template_content)?;
+        let source = &literature.source;
         let rendered = template.render(context! {
-            research_log => yaml,
-            title => literature.base.title,
-            abstract_text => abstract_text,
+            abstract_text => literature.abstract_text,
+            repository_description => literature.repository_description,
+            authors => source.authors,
+            url => source.url(),
+            source,
+            ..entry_context(&self.manager.config, &literature.base, yaml)
         })?;
+        let rendered = if literature.reviews.is_empty() {
+            rendered
+        } else {
+            format!(
+                "{}\n\n{}",
+                rendered.trim_end(),
+                reviews_markdown(&literature.reviews)
+            )
+        };
 
-        self.manager.save_log(&literature, &rendered)?;
-        Ok(literature)
+        self.manager
+            .save_log(literature, &rendered, allow_duplicate_title)
     }
 
-    pub fn update_status(&self, partial_id: &str, new_status: LiteratureStatus) -> Result<()> {
-        let (mut literature, file_path) = self.manager.find_log(partial_id)?;
-        literature.update_status(new_status);
-        self.manager.update_log(&mut literature, &file_path)
+    /// Moves a completed or archived review back to in progress.
+    pub fn reopen(&self, partial_id: &str, reason: Option<&str>) -> Result<LiteratureLog> {
+        self.manager.reopen(
+            partial_id,
+            LiteratureStatus::InProgress,
+            reason.unwrap_or("reopened"),
+        )
+    }
+
+    /// Retitles an entry, renaming its file and updating links to it.
+    pub fn rename(
+        &self,
+        partial_id: &str,
+        title: &str,
+        allow_duplicate_title: bool,
+    ) -> Result<Renamed<LiteratureLog>> {
+        self.manager
+            .rename_log(partial_id, title, allow_duplicate_title)
     }
 
-    pub fn delete(&self, partial_id: &str) -> Result<()> {
-        let (_, file_path) = self.manager.find_log(partial_id)?;
-        std::fs::remove_file(file_path)?;
+    pub fn update_status(
+        &self,
+        partial_id: &str,
+        new_status: LiteratureStatus,
+        reason: Option<&str>,
+        force: bool,
+    ) -> Result<()> {
+        self.manager
+            .change_status(partial_id, new_status, reason, force)?;
         Ok(())
     }
 
-    pub fn list(
+    /// The entries named by `partial_ids`, or matching `filter` if none are given.
+    pub fn select(
         &self,
-        status: Option<LiteratureStatus>,
-        tags: Option<Vec<String>>,
+        partial_ids: &[String],
+        filter: &ListFilter<LiteratureStatus>,
+    ) -> Result<Vec<EntryMatch>> {
+        Ok(self
+            .manager
+            .select(partial_ids, filter)?
+            .into_iter()
+            .map(|(log, _)| EntryMatch::new(&log.base, LiteratureLog::KIND, log.status.to_string()))
+            .collect())
+    }
+
+    /// Changes the status of several entries at once, leaving them all
+    /// unchanged if any of the changes is not allowed.
+    pub fn update_statuses(
+        &self,
+        partial_ids: &[String],
+        new_status: LiteratureStatus,
+        reason: Option<&str>,
+        force: bool,
     ) -> Result<Vec<LiteratureLog>> {
-        self.manager.list_logs(status, tags)
+        self.manager
+            .change_status_many(partial_ids, new_status, reason, force)
+    }
+
+    /// Sets the rating (1 to 5) and reading progress of an entry. Either is
+    /// left as it was when `None`; `clear` removes both first.
+    pub fn rate(
+        &self,
+        partial_id: &str,
+        rating: Option<u8>,
+        progress: Option<ReadingProgress>,
+        clear: bool,
+    ) -> Result<LiteratureLog> {
+        if let Some(rating) = rating {
+            if !(1..=5).contains(&rating) {
+                return Err(anyhow::anyhow!(
+                    "Rating must be between 1 and 5, got {}",
+                    rating
+                ));
+            }
+        }
+        let (mut literature, file_path) = self.manager.find_log(partial_id)?;
+        if clear {
+            literature.rating = None;
+            literature.reading_progress = None;
+        }
+        literature.rating = rating.or(literature.rating);
+        literature.reading_progress = progress.or(literature.reading_progress);
+        self.manager.update_log(&mut literature, &file_path)?;
+        Ok(literature)
+    }
+
+    /// Creates a knowledge draft from an entry, referencing it: a citation of
+    /// the paper followed by a `## Key takeaways` section holding the review's
+    /// `## Takeaways`, or left to fill in. `title` replaces the paper's title,
+    /// and the draft gets the entry's tags.
+    pub fn distill(
+        &self,
+        partial_id: &str,
+        title: Option<&str>,
+        allow_duplicate_title: bool,
+    ) -> Result<KnowledgeLog> {
+        let (literature, path) = self.manager.find_log(partial_id)?;
+        let review = self.manager.read_plain_body(&literature, &path)?;
+        let takeaways = section_text(&review, TAKEAWAYS_HEADING)
+            .unwrap_or_else(|| "<!-- What to remember from the paper, one bullet each -->".into());
+        let body = format!(
+            "{}\n\n## Key takeaways\n\n{}",
+            citation(&literature),
+            takeaways
+        );
+
+        let title = title.unwrap_or(&literature.base.title);
+        let tags = Some(literature.base.tags.iter().cloned().collect());
+        let knowledge_manager = KnowledgeManager::new(self.manager.config.clone());
+        let mut knowledge = knowledge_manager.new_entry(title, tags)?;
+        knowledge.base.set_reference(literature.base.id, None);
+        knowledge_manager.save_new(knowledge, Some(&body), None, allow_duplicate_title)
+    }
+
+    pub fn delete(&self, partial_id: &str, cascade: bool, force: bool) -> Result<Tombstone> {
+        self.manager.delete_log(partial_id, cascade, force)
+    }
+
+    pub fn list(&self, filter: &ListFilter<LiteratureStatus>) -> Result<Vec<LiteratureLog>> {
+        self.manager.list_logs(filter)
     }
 
     pub fn find(&self, partial_id: &str) -> Result<(LiteratureLog, PathBuf)> {
@@ -188,31 +693,174 @@ impl LiteratureManager {
     }
 }
 
-pub fn create_literature(url: &str, tags: Option<Vec<String>>) -> Result<LiteratureLog> {
+pub fn create_literature(
+    url: &str,
+    title: Option<&str>,
+    tags: Option<Vec<String>>,
+    template: Option<&str>,
+    allow_duplicate_title: bool,
+    include_reviews: bool,
+) -> Result<LiteratureLog> {
     let config = load_config()?;
     let manager = LiteratureManager::new(config);
-    manager.create(url, tags)
+    manager.create(
+        url,
+        title,
+        tags,
+        template,
+        allow_duplicate_title,
+        include_reviews,
+    )
 }
 
-pub fn update_literature_status(partial_id: &str, new_status: LiteratureStatus) -> Result<()> {
+/// Imports a BibTeX (`.bib`) or RIS (`.ris`) file into the literature collection.
+pub fn import_literature(path: &Path, tags: Option<Vec<String>>) -> Result<ImportSummary> {
+    let content = utils::load_entry_content(path)?;
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    let records = match extension.as_deref() {
+        Some("bib") | Some("bibtex") => parse_bibtex(&content)?,
+        Some("ris") => parse_ris(&content)?,
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Unsupported import format: {} (expected .bib or .ris)",
+                path.display()
+            ))
+        }
+    };
+
     let config = load_config()?;
     let manager = LiteratureManager::new(config);
-    manager.update_status(partial_id, new_status)
+    manager.import(records, tags)
 }
 
-pub fn delete_literature(partial_id: &str) -> Result<()> {
+/// Creates literature entries from a file with one arXiv URL, GitHub URL or DOI
+/// per line. Blank lines and lines starting with `#` are ignored.
+pub fn import_literature_urls(path: &Path, tags: Option<Vec<String>>) -> Result<ImportSummary> {
+    let content = utils::load_entry_content(path)?;
+    let urls = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect();
+
     let config = load_config()?;
     let manager = LiteratureManager::new(config);
-    manager.delete(partial_id)
+    manager.import_urls(urls, tags)
 }
 
-pub fn list_literature(
-    status: Option<LiteratureStatus>,
-    tags: Option<Vec<String>>,
+/// Normalizes a source URL or DOI so the same source is recognised however it was written.
+fn source_key(source: &str) -> String {
+    // Preprint URLs differ per version, so they are compared by DOI.
+    let source = normalize_doi(&biorxiv_doi(source).unwrap_or_else(|| source.to_string()));
+    source
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+pub fn refresh_literature(partial_id: &str) -> Result<(LiteratureLog, Vec<FieldChange>)> {
+    let config = load_config()?;
+    let manager = LiteratureManager::new(config);
+    manager.refresh(partial_id)
+}
+
+pub fn refresh_all_literature() -> Result<Vec<(LiteratureLog, Result<Vec<FieldChange>>)>> {
+    let config = load_config()?;
+    let manager = LiteratureManager::new(config);
+    manager.refresh_all()
+}
+
+pub fn update_literature_status(
+    partial_id: &str,
+    new_status: LiteratureStatus,
+    reason: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let manager = LiteratureManager::new(config);
+    manager.update_status(partial_id, new_status, reason, force)
+}
+
+pub fn select_literature(
+    partial_ids: &[String],
+    filter: &ListFilter<LiteratureStatus>,
+) -> Result<Vec<EntryMatch>> {
+    let config = load_config()?;
+    let manager = LiteratureManager::new(config);
+    manager.select(partial_ids, filter)
+}
+
+pub fn update_literature_statuses(
+    partial_ids: &[String],
+    new_status: LiteratureStatus,
+    reason: Option<&str>,
+    force: bool,
 ) -> Result<Vec<LiteratureLog>> {
     let config = load_config()?;
     let manager = LiteratureManager::new(config);
-    manager.list(status, tags)
+    manager.update_statuses(partial_ids, new_status, reason, force)
+}
+
+pub fn rename_literature(
+    partial_id: &str,
+    title: &str,
+    allow_duplicate_title: bool,
+) -> Result<Renamed<LiteratureLog>> {
+    let config = load_config()?;
+    let manager = LiteratureManager::new(config);
+    manager.rename(partial_id, title, allow_duplicate_title)
+}
+
+pub fn reopen_literature(partial_id: &str, reason: Option<&str>) -> Result<LiteratureLog> {
+    let config = load_config()?;
+    let manager = LiteratureManager::new(config);
+    manager.reopen(partial_id, reason)
+}
+
+pub fn rate_literature(
+    partial_id: &str,
+    rating: Option<u8>,
+    progress: Option<ReadingProgress>,
+    clear: bool,
+) -> Result<LiteratureLog> {
+    let config = load_config()?;
+    let manager = LiteratureManager::new(config);
+    manager.rate(partial_id, rating, progress, clear)
+}
+
+pub fn distill_literature(
+    partial_id: &str,
+    title: Option<&str>,
+    allow_duplicate_title: bool,
+) -> Result<KnowledgeLog> {
+    let config = load_config()?;
+    let manager = LiteratureManager::new(config);
+    manager.distill(partial_id, title, allow_duplicate_title)
+}
+
+pub fn delete_literature(partial_id: &str, cascade: bool, force: bool) -> Result<Tombstone> {
+    let config = load_config()?;
+    let manager = LiteratureManager::new(config);
+    manager.delete(partial_id, cascade, force)
+}
+
+pub fn list_literature(filter: &ListFilter<LiteratureStatus>) -> Result<Vec<LiteratureLog>> {
+    list_literature_in(&load_config()?, filter)
+}
+
+/// Like [`list_literature`], in the repository of `config`.
+pub fn list_literature_in(
+    config: &Config,
+    filter: &ListFilter<LiteratureStatus>,
+) -> Result<Vec<LiteratureLog>> {
+    let manager = LiteratureManager::new(config.clone());
+    manager.list(filter)
 }
 
 pub fn _find_literature_file(config: &Config, partial_id: &str) -> Result<PathBuf> {
@@ -243,22 +891,191 @@ pub fn _find_literature_file(config: &Config, partial_id: &str) -> Result<PathBu
     }
 }
 
-pub fn fetch_metadata(source: &Source) -> Result<(String, Option<String>, Option<String>)> {
-    let mut title = String::new();
-    let mut abstract_text = None;
-    let mut repo_description = None;
+/// bioRxiv and medRxiv share an API; `"biorxiv.org"` is checked before `"arxiv.org"`
+/// since it contains it.
+fn is_biorxiv_url(url: &str) -> bool {
+    url.contains("biorxiv.org") || url.contains("medrxiv.org")
+}
+
+/// The year at the start of a BibTeX or RIS date, e.g. `2024` or `2024/03/01`.
+fn parse_year(date: &str) -> Option<i32> {
+    date.trim().get(..4)?.parse().ok()
+}
+
+/// The submission year encoded in an arXiv identifier: `2401.12345` (new
+/// style) or `hep-th/9901001` (old style).
+fn arxiv_year(url: &str) -> Option<i32> {
+    let id = url
+        .split_once("arxiv.org/")?
+        .1
+        .trim_start_matches("abs/")
+        .trim_start_matches("pdf/");
+    let digits = match id.split_once('/') {
+        Some((_, number)) => number,
+        None => id,
+    };
+    let yy: i32 = digits.get(..2)?.parse().ok()?;
+    if !digits.get(2..4)?.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(if yy >= 91 { 1900 + yy } else { 2000 + yy })
+}
 
-    if let Some(arxiv_url) = &source.arxiv_url {
-        let metadata = fetch_arxiv_metadata(arxiv_url)?;
-        title = metadata.title;
-        abstract_text = Some(metadata.abstract_text);
+/// Metadata fetched for a new literature entry.
+#[derive(Debug, Default)]
+pub struct SourceMetadata {
+    pub title: String,
+    pub abstract_text: Option<String>,
+    pub repository_description: Option<String>,
+    pub reviews: Vec<OpenReviewReview>,
+}
+
+/// Fetches metadata for `source`, also filling in source fields the service
+/// reports (such as the OpenReview venue and decision).
+pub fn fetch_metadata(source: &mut Source) -> Result<SourceMetadata> {
+    let mut metadata = SourceMetadata::default();
+
+    if let Some(preprint_url) = &source.preprint_url {
+        let preprint = fetch_biorxiv_metadata(preprint_url)?;
+        metadata.title = preprint.title;
+        metadata.abstract_text = Some(preprint.abstract_text);
+        set_authors(source, preprint.authors);
+    } else if let Some(openreview_url) = &source.openreview_url {
+        let paper = fetch_openreview_metadata(openreview_url)?;
+        metadata.title = paper.title;
+        metadata.abstract_text = Some(paper.abstract_text);
+        metadata.reviews = paper.reviews;
+        set_authors(source, paper.authors);
+        source.venue = paper.venue;
+        source.decision = paper.decision;
+    } else if let Some(arxiv_url) = &source.arxiv_url {
+        let paper = fetch_arxiv_metadata(arxiv_url)?;
+        metadata.title = paper.title;
+        metadata.abstract_text = Some(paper.abstract_text);
+        set_authors(source, paper.authors);
+        if paper.doi.is_some() {
+            source.doi = paper.doi;
+        }
     } else if let Some(repo_url) = &source.repository_url {
         if repo_url.contains("github.com") {
             let git_repo = fetch_github_metadata(repo_url)?;
-            repo_description = git_repo.description;
-            title = git_repo.name;
+            metadata.repository_description = git_repo.description;
+            metadata.title = git_repo.name;
+            source.stars = Some(git_repo.stargazers_count);
+            source.topics = git_repo.topics;
         }
+    } else if let Some(doi) = &source.doi {
+        let work = fetch_crossref_metadata(doi)?;
+        metadata.title = work.title;
+        metadata.abstract_text = work.abstract_text;
+        set_authors(source, work.authors);
+        source.citations = Some(work.citation_count);
+    }
+
+    Ok(metadata)
+}
+
+/// Keeps the known authors when a service does not list any.
+fn set_authors(source: &mut Source, authors: Vec<String>) {
+    if !authors.is_empty() {
+        source.authors = authors;
     }
+}
+
+/// Field-by-field differences between two sources, named as in the frontmatter.
+fn source_changes(old: &Source, new: &Source) -> Result<Vec<FieldChange>> {
+    let old = serde_yaml::to_value(old)?;
+    let new = serde_yaml::to_value(new)?;
+    let Some(fields) = new.as_mapping() else {
+        return Ok(Vec::new());
+    };
+
+    let scalar = |value: Option<&serde_yaml::Value>| match value? {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Sequence(items) => Some(
+            items
+                .iter()
+                .filter_map(serde_yaml::Value::as_str)
+                .collect::<Vec<_>>()
+                .join("; "),
+        ),
+        _ => None,
+    };
+    Ok(fields
+        .iter()
+        .filter_map(|(key, value)| {
+            let (before, after) = (scalar(old.get(key)), scalar(Some(value)));
+            (before != after).then(|| FieldChange {
+                field: format!("source.{}", key.as_str().unwrap_or_default()),
+                old: before,
+                new: after,
+            })
+        })
+        .collect())
+}
 
-    Ok((title, abstract_text, repo_description))
+/// Renders OpenReview reviews as a markdown section appended to new entries.
+fn reviews_markdown(reviews: &[OpenReviewReview]) -> String {
+    let mut markdown = String::from("## Reviews\n");
+    for (i, review) in reviews.iter().enumerate() {
+        let scores: Vec<String> = [
+            ("rating", &review.rating),
+            ("confidence", &review.confidence),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.as_ref().map(|v| format!("{}: {}", name, v)))
+        .collect();
+        markdown.push_str(&format!("\n### Review {}", i + 1));
+        if !scores.is_empty() {
+            markdown.push_str(&format!(" ({})", scores.join(", ")));
+        }
+        markdown.push_str(&format!("\n\n{}\n", review.text));
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_changes() {
+        let old = Source {
+            repository_url: Some("https://github.com/owner/repo".to_string()),
+            stars: Some(10),
+            ..Default::default()
+        };
+        let new = Source {
+            stars: Some(12),
+            doi: Some("10.1234/x".to_string()),
+            ..old.clone()
+        };
+
+        let changes = source_changes(&old, &new).unwrap();
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|c| (c.field.as_str(), c.old.as_deref(), c.new.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("source.doi", None, Some("10.1234/x")),
+                ("source.stars", Some("10"), Some("12")),
+            ]
+        );
+        assert!(source_changes(&old, &old).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_arxiv_year() {
+        assert_eq!(arxiv_year("https://arxiv.org/abs/2401.12345"), Some(2024));
+        assert_eq!(arxiv_year("https://arxiv.org/pdf/1706.03762v7"), Some(2017));
+        assert_eq!(
+            arxiv_year("https://arxiv.org/abs/hep-th/9901001"),
+            Some(1999)
+        );
+        assert_eq!(arxiv_year("https://github.com/owner/repo"), None);
+        assert_eq!(parse_year("2023-05-01"), Some(2023));
+    }
 }
@@ -0,0 +1,250 @@
+//! Summaries and tag suggestions from a language model behind the
+//! OpenAI-compatible API configured under `[llm]`.
+
+use anyhow::Result;
+use dxlog_tools::complete_chat;
+use serde::Serialize;
+use std::{collections::HashSet, path::Path};
+
+use crate::{
+    load_config,
+    log_manager::LogManager,
+    note::{replace_section, section_text},
+    research_log::ResearchLog,
+    tags_in_use, utils, Config, DatasetManager, ExperimentManager, HypothesisManager, IdeaManager,
+    JournalManager, KnowledgeManager, LiteratureManager, MeetingManager,
+};
+
+pub const SUMMARY_HEADING: &str = "## Summary";
+
+/// Most characters of an entry body sent to the model.
+const MAX_BODY_CHARS: usize = 12_000;
+
+/// Most tags suggested for one entry.
+const MAX_TAGS: usize = 5;
+
+const SUMMARY_INSTRUCTIONS: &str = "You summarize research papers for a lab notebook. \
+Write at most 150 words of plain markdown covering the question the paper addresses, \
+its method and its main findings. Do not start with a heading.";
+
+const TAG_INSTRUCTIONS: &str = "You tag the entries of a research notebook. \
+Reply with at most 5 tags separated by commas, and nothing else. Tags are lowercase, \
+words are joined with dashes, and `/` puts a tag under a broader one, as in \
+`ml/transformers`. Prefer tags that are already in use.";
+
+/// A summary written by [`summarize_literature`].
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub title: String,
+    pub text: String,
+    /// Whether an earlier summary was replaced.
+    pub replaced: bool,
+}
+
+/// Tags suggested by [`suggest_tags`].
+#[derive(Debug, Serialize)]
+pub struct TagSuggestions {
+    pub title: String,
+    /// Tags the entry has, sorted.
+    pub current: Vec<String>,
+    /// Tags the model suggests, some of which the entry may already have.
+    pub suggested: Vec<String>,
+}
+
+fn ask(config: &Config, api_key: Option<&str>, instructions: &str, prompt: &str) -> Result<String> {
+    let api_key = api_key.map(str::to_string).or_else(|| config.llm.api_key());
+    complete_chat(
+        config.llm.endpoint(),
+        api_key.as_deref(),
+        config.llm.model(),
+        instructions,
+        prompt,
+    )
+}
+
+/// What the model is told about an entry.
+fn entry_text(title: &str, abstract_text: Option<&str>, body: Option<&str>) -> String {
+    let mut text = format!("Title: {}\n", title);
+    if let Some(abstract_text) = abstract_text.map(str::trim).filter(|a| !a.is_empty()) {
+        text.push_str(&format!("\nAbstract:\n{}\n", abstract_text));
+    }
+    if let Some(body) = body.map(str::trim).filter(|b| !b.is_empty()) {
+        let body: String = body.chars().take(MAX_BODY_CHARS).collect();
+        text.push_str(&format!("\nNotes:\n{}\n", body));
+    }
+    text
+}
+
+/// The body of an entry to send to the model, or `None` if it is encrypted:
+/// encrypted bodies never leave the machine.
+fn shareable_body<T: ResearchLog>(
+    manager: &LogManager<T>,
+    log: &T,
+    path: &Path,
+) -> Result<Option<String>> {
+    if log.base().encrypted {
+        return Ok(None);
+    }
+    manager.read_body(path).map(Some)
+}
+
+/// Asks the model to summarize a literature entry from its abstract and notes,
+/// and writes the reply into its `## Summary` section.
+///
+/// Fails if the entry already has a summary, unless `overwrite` is set.
+/// `api_key` defaults to the one `[llm]` points at.
+pub fn summarize_literature(
+    partial_id: &str,
+    api_key: Option<&str>,
+    overwrite: bool,
+) -> Result<Summary> {
+    let config = load_config()?;
+    let manager = LiteratureManager::new(config.clone());
+    let (literature, path) = manager.find(partial_id)?;
+    let title = literature.base.title.clone();
+
+    let body = manager.manager.read_plain_body(&literature, &path)?;
+    let replaced = section_text(&body, SUMMARY_HEADING).is_some();
+    if replaced && !overwrite {
+        return Err(anyhow::anyhow!(
+            "Literature \"{}\" already has a summary; pass --overwrite to replace it",
+            title
+        ));
+    }
+
+    let notes = shareable_body(&manager.manager, &literature, &path)?;
+    let abstract_text = literature.abstract_text.as_deref();
+    if abstract_text.is_none_or(|a| a.trim().is_empty())
+        && notes.as_deref().is_none_or(|n| n.trim().is_empty())
+    {
+        return Err(anyhow::anyhow!(
+            "Literature \"{}\" has no abstract or notes to summarize",
+            title
+        ));
+    }
+    let prompt = entry_text(&title, abstract_text, notes.as_deref());
+    let text = ask(&config, api_key, SUMMARY_INSTRUCTIONS, &prompt)?;
+
+    manager
+        .manager
+        .update_body(&literature.base.id.to_string(), |body| {
+            replace_section(body, SUMMARY_HEADING, &text)
+        })?;
+    Ok(Summary {
+        title,
+        text,
+        replaced,
+    })
+}
+
+/// Asks the model for tags fitting an entry, given its title, abstract and
+/// notes and the tags already in use. Nothing is written; see [`add_tags`](crate::add_tags).
+pub fn suggest_tags(partial_id: &str, api_key: Option<&str>) -> Result<TagSuggestions> {
+    let config = load_config()?;
+    let h_manager = HypothesisManager::new(config.clone());
+    let l_manager = LiteratureManager::new(config.clone());
+    let k_manager = KnowledgeManager::new(config.clone());
+    let j_manager = JournalManager::new(config.clone());
+    let d_manager = DatasetManager::new(config.clone());
+    let m_manager = MeetingManager::new(config.clone());
+    let i_manager = IdeaManager::new(config.clone());
+    let e_manager = ExperimentManager::new(config.clone());
+
+    let (title, tags, mut prompt) = if let Ok((log, path)) = h_manager.find(partial_id) {
+        entry_prompt(&h_manager.manager, &log, &path, None)?
+    } else if let Ok((log, path)) = l_manager.find(partial_id) {
+        entry_prompt(
+            &l_manager.manager,
+            &log,
+            &path,
+            log.abstract_text.as_deref(),
+        )?
+    } else if let Ok((log, path)) = k_manager.find(partial_id) {
+        entry_prompt(&k_manager.manager, &log, &path, None)?
+    } else if let Ok((log, path)) = j_manager.find(partial_id) {
+        entry_prompt(&j_manager.manager, &log, &path, None)?
+    } else if let Ok((log, path)) = d_manager.find(partial_id) {
+        entry_prompt(&d_manager.manager, &log, &path, None)?
+    } else if let Ok((log, path)) = m_manager.find(partial_id) {
+        entry_prompt(&m_manager.manager, &log, &path, None)?
+    } else if let Ok((log, path)) = i_manager.find(partial_id) {
+        entry_prompt(&i_manager.manager, &log, &path, None)?
+    } else if let Ok((log, path)) = e_manager.find(partial_id) {
+        entry_prompt(&e_manager.manager, &log, &path, None)?
+    } else {
+        return Err(anyhow::anyhow!(
+            "No entry found with ID starting with '{}'",
+            partial_id
+        ));
+    };
+
+    let in_use = tags_in_use()?;
+    if !in_use.is_empty() {
+        let in_use: Vec<String> = in_use.into_iter().collect();
+        prompt.push_str(&format!("\nTags in use: {}\n", in_use.join(", ")));
+    }
+    let reply = ask(&config, api_key, TAG_INSTRUCTIONS, &prompt)?;
+
+    let mut current: Vec<String> = tags.into_iter().collect();
+    current.sort();
+    Ok(TagSuggestions {
+        title,
+        current,
+        suggested: parse_tags(&reply),
+    })
+}
+
+fn entry_prompt<T: ResearchLog>(
+    manager: &LogManager<T>,
+    log: &T,
+    path: &Path,
+    abstract_text: Option<&str>,
+) -> Result<(String, HashSet<String>, String)> {
+    let body = shareable_body(manager, log, path)?;
+    let base = log.base();
+    let prompt = entry_text(&base.title, abstract_text, body.as_deref());
+    Ok((base.title.clone(), base.tags.clone(), prompt))
+}
+
+/// The tags in a reply of the model, cleaned up and without repeats. Replies
+/// listing them one per line, as bullets or numbered, are understood too.
+fn parse_tags(reply: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    reply
+        .split([',', '\n'])
+        .filter_map(|item| {
+            let item = item.trim();
+            let item = match item.split_once(['.', ')']) {
+                Some((number, rest)) if number.chars().all(|c| c.is_ascii_digit()) => rest,
+                _ => item,
+            };
+            let item = item
+                .trim_start_matches(|c: char| matches!(c, '-' | '*' | '#') || c.is_whitespace())
+                .trim()
+                .trim_matches(['`', '"', '\''])
+                .to_lowercase();
+            let item = item.split_whitespace().collect::<Vec<_>>().join("-");
+            utils::normalize_tag(&item)
+        })
+        .filter(|tag| seen.insert(tag.clone()))
+        .take(MAX_TAGS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags("optics, ML/Transformers , `cavity noise`, optics"),
+            vec!["optics", "ml/transformers", "cavity-noise"]
+        );
+        assert_eq!(
+            parse_tags("1. optics\n2) thermal-noise\n- #cryo\n\n"),
+            vec!["optics", "thermal-noise", "cryo"]
+        );
+        assert_eq!(parse_tags("a, b, c, d, e, f, g").len(), MAX_TAGS);
+    }
+}
@@ -1,14 +1,286 @@
+use crate::research_log::LogStatus;
 use crate::{
+    dry_run, handle,
+    hooks::{run_hook, HookEvent},
     md_frontmatter::{extract_frontmatter, update_markdown_frontmatter},
+    migration,
+    note::{append_to_section, note_line, NOTES_HEADING},
+    operations,
     research_log::ResearchLog,
-    utils::{self, load_entry_content, save_entry_content},
-    Config,
+    trash::{Tombstone, Trash},
+    utils::{
+        self, load_entry_content, save_entry_content, Author, BaseLog, StatusChange, TagFilter,
+    },
+    Config, EntryMatch, LogError,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
 use std::{
+    cmp::Ordering,
+    collections::HashSet,
     marker::PhantomData,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering as AtomicOrdering},
+        Arc, Mutex,
+    },
 };
+use tracing::{debug, trace};
+use uuid::Uuid;
+
+/// Outcome of [`LogManager::rename_log`].
+#[derive(Debug)]
+pub struct Renamed<T> {
+    pub log: T,
+    pub old_path: PathBuf,
+    pub new_path: PathBuf,
+    /// Other entries whose links were updated.
+    pub relinked: Vec<PathBuf>,
+}
+
+/// Environment variable that, when set to anything but `0`/`false`/`no`/`off`, makes
+/// entry files that cannot be read fail the command instead of being skipped.
+pub const STRICT_ENV_VAR: &str = "DXLOG_STRICT";
+
+thread_local! {
+    /// Kept per thread, so each request of `dxlog serve` or `dxlog mcp` only
+    /// reports the files it skipped itself.
+    static SKIPPED: Skipped = Skipped::default();
+}
+
+/// An entry file left out of the results because it could not be read or parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedEntry {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Whether strict mode has been turned on, through [`RunOptions`](crate::RunOptions) or else
+/// [`STRICT_ENV_VAR`].
+pub fn is_strict() -> bool {
+    crate::config::run_options().strict
+}
+
+/// Returns the entry files skipped on this thread so far, each once, and forgets them.
+pub fn take_skipped_entries() -> Vec<SkippedEntry> {
+    SKIPPED.with(|skipped| std::mem::take(&mut *skipped.lock()))
+}
+
+/// The entry files skipped by one thread. Entries are loaded on rayon's
+/// workers, so they record into the handle of the thread that asked for them.
+#[derive(Clone, Default)]
+pub(crate) struct Skipped(Arc<Mutex<Vec<SkippedEntry>>>);
+
+impl Skipped {
+    /// The handle of the calling thread.
+    pub(crate) fn current() -> Self {
+        SKIPPED.with(Skipped::clone)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<SkippedEntry>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Passes `result` on in strict mode. Otherwise a failure to load the entry at
+/// `path` is recorded in `skipped` for [`take_skipped_entries`] and the entry
+/// left out, so one broken file does not stop every command.
+pub(crate) fn skip_malformed<V>(
+    skipped: &Skipped,
+    path: &Path,
+    result: Result<Option<V>>,
+) -> Result<Option<V>> {
+    match result {
+        Err(e) if !is_strict() => {
+            debug!(path = %path.display(), "Skipping entry: {:#}", e);
+            let entry = SkippedEntry {
+                path: path.to_path_buf(),
+                reason: e.root_cause().to_string(),
+            };
+            let mut all = skipped.lock();
+            // Several log types share directories, so the same file can fail more than once.
+            if !all.contains(&entry) {
+                all.push(entry);
+            }
+            Ok(None)
+        }
+        result => result,
+    }
+}
+
+/// Field used to order list results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum SortKey {
+    #[default]
+    Date,
+    Title,
+    Status,
+    Author,
+    /// Creation order for UUIDv7 IDs; IDs of older entries are random.
+    Id,
+}
+
+/// Text the title of an entry, and optionally its body, must contain, ignoring case.
+#[derive(Debug, Clone)]
+pub struct TextQuery {
+    pattern: Regex,
+    /// Whether the body is searched as well as the title.
+    pub in_body: bool,
+}
+
+impl TextQuery {
+    /// Matches `text` literally, or as a regular expression with `regex`.
+    pub fn new(text: &str, regex: bool, in_body: bool) -> Result<Self> {
+        let pattern = if regex {
+            text.to_string()
+        } else {
+            regex::escape(text)
+        };
+        let pattern = RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .with_context(|| format!("Invalid regular expression '{}'", text))?;
+        Ok(Self { pattern, in_body })
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.pattern.is_match(text)
+    }
+}
+
+/// Criteria for [`LogManager::list_logs`]. Unset fields match every entry.
+///
+/// Results are ordered by `sort` (oldest first by default), with ties broken by
+/// title and then ID so the output does not depend on filesystem order.
+#[derive(Debug, Clone)]
+pub struct ListFilter<S> {
+    pub status: Option<S>,
+    pub tags: TagFilter,
+    /// Matches the creator's email exactly or their name as a substring, ignoring case.
+    pub author: Option<String>,
+    /// Matches entries assigned to this project.
+    pub project: Option<String>,
+    /// Matches the assignee like `author` matches the creator; unassigned entries never match.
+    pub assignee: Option<String>,
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    /// Matches the title, or the title or body if [`TextQuery::in_body`] is set.
+    pub query: Option<TextQuery>,
+    pub sort: SortKey,
+    pub reverse: bool,
+    pub limit: Option<usize>,
+}
+
+impl<S> Default for ListFilter<S> {
+    fn default() -> Self {
+        Self {
+            status: None,
+            tags: TagFilter::default(),
+            author: None,
+            project: None,
+            assignee: None,
+            since: None,
+            until: None,
+            query: None,
+            sort: SortKey::default(),
+            reverse: false,
+            limit: None,
+        }
+    }
+}
+
+impl<S> ListFilter<S> {
+    pub fn with_status(status: S) -> Self {
+        Self {
+            status: Some(status),
+            ..Default::default()
+        }
+    }
+}
+
+impl<S: ToString> ListFilter<S> {
+    pub fn matches<T: ResearchLog<Status = S>>(&self, log: &T) -> bool {
+        let base = log.base();
+
+        if let Some(target_status) = &self.status {
+            if log.status().to_string() != target_status.to_string() {
+                return false;
+            }
+        }
+
+        if !self.tags.matches(&base.tags) {
+            return false;
+        }
+
+        if let Some(author) = &self.author {
+            if !is_person(&base.created_by, author) {
+                return false;
+            }
+        }
+
+        if self.project.is_some() && self.project != base.project {
+            return false;
+        }
+
+        if let Some(assignee) = &self.assignee {
+            if !base
+                .assignee
+                .as_ref()
+                .is_some_and(|person| is_person(person, assignee))
+            {
+                return false;
+            }
+        }
+
+        if self.since.is_some() || self.until.is_some() {
+            let date = base.created_date();
+            if self.since.is_some_and(|since| date < since)
+                || self.until.is_some_and(|until| date > until)
+            {
+                return false;
+            }
+        }
+
+        if let Some(query) = &self.query {
+            // Queries on the body too are applied once the body is read, in
+            // `LogManager::list_log_files`.
+            if !query.in_body && !query.is_match(&base.title) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn order<T: ResearchLog<Status = S>>(&self, a: &T, b: &T) -> Ordering {
+        let (base_a, base_b) = (a.base(), b.base());
+        let primary = match self.sort {
+            SortKey::Date => base_a.created_at.cmp(&base_b.created_at),
+            SortKey::Title => base_a
+                .title
+                .to_lowercase()
+                .cmp(&base_b.title.to_lowercase()),
+            SortKey::Status => a.status().to_string().cmp(&b.status().to_string()),
+            SortKey::Author => base_a
+                .created_by
+                .name
+                .to_lowercase()
+                .cmp(&base_b.created_by.name.to_lowercase()),
+            SortKey::Id => base_a.id.cmp(&base_b.id),
+        };
+        primary
+            .then_with(|| base_a.title.cmp(&base_b.title))
+            .then_with(|| base_a.id.cmp(&base_b.id))
+    }
+}
+
+/// Whether `query` is the person's email, or part of their name, ignoring case.
+fn is_person(person: &Author, query: &str) -> bool {
+    let query = query.to_lowercase();
+    person.email.to_lowercase() == query || person.name.to_lowercase().contains(&query)
+}
 
 pub struct LogManager<T: ResearchLog> {
     pub(crate) config: Config,
@@ -17,7 +289,14 @@ pub struct LogManager<T: ResearchLog> {
 }
 
 impl<T: ResearchLog> LogManager<T> {
-    pub fn new(config: Config, search_dirs: Vec<PathBuf>) -> Self {
+    /// Creates a manager reading from `search_dirs` and any directories
+    /// `[storage.layout]` assigns to this log type.
+    pub fn new(config: Config, mut search_dirs: Vec<PathBuf>) -> Self {
+        for dir in config.layout_dirs(T::KIND) {
+            if !search_dirs.contains(&dir) {
+                search_dirs.push(dir);
+            }
+        }
         Self {
             config,
             search_dirs,
@@ -25,101 +304,1259 @@ impl<T: ResearchLog> LogManager<T> {
         }
     }
 
+    /// The entry whose ID starts with `partial_id` or whose handle it is.
+    /// Failing that, `partial_id` may name the entry's file or part of its
+    /// title, ignoring case.
     pub fn find_log(&self, partial_id: &str) -> Result<(T, PathBuf)> {
-        let mut matches = Vec::new();
-
-        for dir in &self.search_dirs {
-            let files = utils::list_entries(dir, "md")?;
-            for file_path in files {
-                let content = load_entry_content(&file_path)?;
-                let (log, _) = extract_frontmatter::<T>(&content)?;
-                if log.base().id.to_string().starts_with(partial_id) {
-                    matches.push((log, file_path.clone()));
-                }
-            }
+        let files = self.entry_files()?;
+        // UUIDv7 IDs start with their creation time, and file names with the
+        // creation date, so the files of that day are read first. Random IDs can
+        // still start the same way, so files from other days are only skipped
+        // once the ID is settled: a full ID matched, or a prefix matched twice.
+        let (likely, others): (Vec<_>, Vec<_>) = match creation_window(partial_id) {
+            Some((first, last)) => files.into_iter().partition(|path| {
+                file_date(path).is_none_or(|date| (first..=last).contains(&date))
+            }),
+            None => (files, Vec::new()),
+        };
+        if !others.is_empty() {
+            debug!(
+                kind = T::KIND,
+                likely = likely.len(),
+                others = others.len(),
+                "Narrowed lookup by ID creation time"
+            );
+        }
+        let mut matches = self.find_in(likely, partial_id)?;
+        let settled = match matches.len() {
+            0 => false,
+            1 => Uuid::parse_str(partial_id).is_ok(),
+            _ => true,
+        };
+        if !settled && !others.is_empty() {
+            matches.extend(self.find_in(others, partial_id)?);
+        }
+        if matches.is_empty() {
+            matches = match_file_or_title(self.load_entries()?, partial_id, |log| log.base());
         }
 
+        debug!(
+            kind = T::KIND,
+            partial_id,
+            matches = matches.len(),
+            "Looked up entry"
+        );
         match matches.len() {
-            0 => Err(anyhow::anyhow!(
-                "No log found with ID starting with '{}'",
-                partial_id
-            )),
+            0 => Err(LogError::NotFound {
+                kind: T::KIND.to_string(),
+                partial_id: partial_id.to_string(),
+            }
+            .into()),
             1 => Ok(matches.remove(0)),
-            _ => Err(anyhow::anyhow!(
-                "Multiple logs found with ID starting with '{}'. Please provide more characters.",
-                partial_id
-            )),
+            _ => Err(LogError::Ambiguous {
+                partial_id: partial_id.to_string(),
+                matches: self.matching(partial_id)?,
+            }
+            .into()),
         }
     }
 
-    pub fn list_logs(
-        &self,
-        status: Option<T::Status>,
-        tags: Option<Vec<String>>,
-    ) -> Result<Vec<T>> {
-        let filter_tags = utils::normalize_tags(tags);
-        let mut logs = Vec::new();
+    /// Every entry whose ID starts with `partial_id` or whose handle it is,
+    /// or failing that, the entries [`find_log`](LogManager::find_log) falls
+    /// back to, newest first. Unlike `find_log`, this reads all entries.
+    pub(crate) fn matching(&self, partial_id: &str) -> Result<Vec<EntryMatch>> {
+        let (mut found, others): (Vec<_>, Vec<_>) =
+            self.load_entries()?.into_iter().partition(|(log, _)| {
+                let base = log.base();
+                base.id.to_string().starts_with(partial_id)
+                    || base
+                        .handle
+                        .as_deref()
+                        .is_some_and(|h| handle::matches(h, partial_id))
+            });
+        if found.is_empty() {
+            found = match_file_or_title(others, partial_id, |log| log.base());
+        }
+        let mut matches: Vec<EntryMatch> = found
+            .into_iter()
+            .map(|(log, _)| EntryMatch::new(log.base(), T::KIND, log.status().to_string()))
+            .collect();
+        matches.sort_by_key(|entry| std::cmp::Reverse(entry.created_at));
+        Ok(matches)
+    }
 
-        for dir in &self.search_dirs {
-            let files = utils::list_entries(dir, "md")?;
-            for file_path in files {
-                let content = load_entry_content(&file_path)?;
-                let (log, _) = extract_frontmatter::<T>(&content)?;
-
-                if let Some(target_status) = &status {
-                    if log.status().to_string() != target_status.to_string() {
-                        continue;
+    /// The entries among `files` whose ID starts with `partial_id`, or whose handle it is.
+    fn find_in(&self, files: Vec<PathBuf>, partial_id: &str) -> Result<Vec<(T, PathBuf)>> {
+        let config = &self.config;
+        let skipped = Skipped::current();
+        if Uuid::parse_str(partial_id).is_ok() {
+            // A full ID can match at most one entry, so stop at the first hit.
+            Ok(files
+                .into_par_iter()
+                .map(|path| {
+                    skip_malformed(
+                        &skipped,
+                        &path,
+                        load_matching::<T>(config, &path, partial_id, None),
+                    )
+                })
+                .find_map_any(|r| r.transpose())
+                .transpose()?
+                .into_iter()
+                .collect())
+        } else {
+            let handle = handle::normalize(T::HANDLE_PREFIX, partial_id);
+            // Two matches already make the ID ambiguous; skip the remaining files.
+            let found = AtomicUsize::new(0);
+            files
+                .into_par_iter()
+                .map(|path| {
+                    if found.load(AtomicOrdering::Relaxed) > 1 {
+                        return Ok(None);
                     }
-                }
+                    let entry = skip_malformed(
+                        &skipped,
+                        &path,
+                        load_matching::<T>(config, &path, partial_id, handle.as_deref()),
+                    )?;
+                    if entry.is_some() {
+                        found.fetch_add(1, AtomicOrdering::Relaxed);
+                    }
+                    Ok(entry)
+                })
+                .filter_map(|r| r.transpose())
+                .collect()
+        }
+    }
 
-                if !filter_tags.is_empty() && !filter_tags.is_subset(&log.base().tags) {
-                    continue;
-                }
+    pub fn list_logs(&self, filter: &ListFilter<T::Status>) -> Result<Vec<T>> {
+        Ok(self
+            .list_log_files(filter)?
+            .into_iter()
+            .map(|(log, _)| log)
+            .collect())
+    }
 
-                logs.push(log);
-            }
+    /// Like [`LogManager::list_logs`], but also returns the path of each entry.
+    pub fn list_log_files(&self, filter: &ListFilter<T::Status>) -> Result<Vec<(T, PathBuf)>> {
+        let mut logs: Vec<(T, PathBuf)> = self
+            .load_entries()?
+            .into_iter()
+            .filter(|(log, _)| filter.matches(log))
+            .collect();
+        if let Some(query) = filter.query.as_ref().filter(|query| query.in_body) {
+            // Only bodies of entries passing the other filters are read.
+            logs = logs
+                .into_par_iter()
+                .map(|(log, path)| {
+                    let found = query.is_match(&log.base().title) || {
+                        let content = load_entry_content(&path)?;
+                        let (_, body) = extract_frontmatter::<serde_yaml::Value>(&content)?;
+                        query.is_match(&body)
+                    };
+                    Ok(found.then_some((log, path)))
+                })
+                .filter_map(|r| r.transpose())
+                .collect::<Result<_>>()?;
+        }
+
+        logs.sort_by(|(a, _), (b, _)| filter.order(a, b));
+        if filter.reverse {
+            logs.reverse();
+        }
+        if let Some(limit) = filter.limit {
+            logs.truncate(limit);
         }
 
         Ok(logs)
     }
 
+    /// Finds an entry of this log type with the same title, ignoring case.
     fn find_existing_log(&self, title: &str) -> Result<Option<(String, PathBuf)>> {
+        Ok(self
+            .load_entries()?
+            .into_iter()
+            .find(|(log, _)| log.base().title.to_lowercase() == title.to_lowercase())
+            .map(|(log, path)| (log.base().title.clone(), path)))
+    }
+
+    /// Reads every entry of this log type from the search directories.
+    ///
+    /// Hypotheses and knowledge drafts share the active directory, so files whose
+    /// status belongs to a different log type are skipped rather than failing to parse.
+    fn load_entries(&self) -> Result<Vec<(T, PathBuf)>> {
+        let config = &self.config;
+        let skipped = Skipped::current();
+        self.entry_files()?
+            .into_par_iter()
+            .map(|path| {
+                skip_malformed(&skipped, &path, load_entry::<T>(config, &path))
+                    .map(|log| log.map(|log| (log, path)))
+            })
+            .filter_map(|r| r.transpose())
+            .collect()
+    }
+
+    /// Reads the file at `path` as an entry of this log type. `None` if it is
+    /// not an entry file in one of this type's directories, otherwise whether
+    /// it is an entry of this type rather than of another sharing the directory.
+    pub(crate) fn check_file(&self, path: &Path) -> Option<Result<bool>> {
+        let dir = path.parent()?;
+        if path.extension().and_then(|s| s.to_str()) != Some("md")
+            || !self.search_dirs.iter().any(|search_dir| search_dir == dir)
+        {
+            return None;
+        }
+        Some(load_entry::<T>(&self.config, path).map(|log| log.is_some()))
+    }
+
+    fn entry_files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
         for dir in &self.search_dirs {
-            let files = utils::list_entries(dir, "md")?;
-            for file_path in files {
-                let content = load_entry_content(&file_path)?;
-                let (log, _) = extract_frontmatter::<T>(&content)?;
-                if log.base().title.to_lowercase() == title.to_lowercase() {
-                    return Ok(Some((log.base().title.clone(), file_path)));
+            let entries = utils::list_entries(dir, "md")?;
+            debug!(kind = T::KIND, dir = %dir.display(), files = entries.len(), "Scanned");
+            files.extend(entries);
+        }
+        Ok(files)
+    }
+
+    /// Gives a new entry an ID of the configured version and the next handle of its type.
+    pub(crate) fn assign_ids(&self, log: &mut T) -> Result<()> {
+        let handle = handle::next(self)?;
+        let base = log.base_mut();
+        base.id = self.config.id_version.new_id();
+        base.handle = Some(handle);
+        Ok(())
+    }
+
+    /// Writes a new entry. Unless `allow_duplicate_title` is set, fails with
+    /// [`LogError::DuplicateTitle`] if an entry of the same type has the same title.
+    pub fn save_log(&self, log: &T, content: &str, allow_duplicate_title: bool) -> Result<PathBuf> {
+        if !allow_duplicate_title {
+            if let Some((title, path)) = self.find_existing_log(&log.base().title)? {
+                return Err(LogError::DuplicateTitle {
+                    kind: T::KIND.to_string(),
+                    title,
+                    path,
                 }
+                .into());
             }
         }
-        Ok(None)
+        let file_name =
+            utils::generate_filename(&log.base().title, &log.base().created_date().to_string());
+        // Entries allowed to share a title, or of different types, may map to the same file name.
+        let file_path =
+            utils::unused_path(log.get_target_path(&self.config, Path::new(&file_name))?);
+        operations::record(
+            &self.config,
+            format!("create {} '{}'", T::KIND, log.base().title),
+            || save_entry_content(&file_path, content),
+        )?;
+        run_hook(&self.config, HookEvent::Create, log, &file_path, None);
+        Ok(file_path)
     }
 
-    pub fn save_log(&self, log: &T, content: &str) -> Result<PathBuf> {
-        if let Some((existing_title, existing_path)) = self.find_existing_log(&log.base().title)? {
+    /// Checks a status change against the `[workflow]` section of the config.
+    pub fn check_transition(&self, log: &T, new_status: &T::Status) -> Result<()> {
+        let known: Vec<String> = T::Status::all(&self.config)
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        self.config.workflow.check_transition(
+            T::KIND,
+            &known,
+            &log.base().title,
+            &log.status().to_string(),
+            &new_status.to_string(),
+        )
+    }
+
+    /// Checks that a status change comes with a reason if `[workflow]
+    /// require_reason` asks for one. Unlike the transitions, this is checked
+    /// even when forcing the change.
+    pub fn check_reason(
+        &self,
+        log: &T,
+        new_status: &T::Status,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        let to = new_status.to_string();
+        if self.config.workflow.requires_reason(&to)
+            && reason.is_none_or(|reason| reason.trim().is_empty())
+            && log.status().to_string() != to
+        {
             return Err(anyhow::anyhow!(
-                "A research log with title '{}' already exists at: {}",
-                existing_title,
-                existing_path.display()
+                "The workflow requires a reason to mark {} '{}' {}. Use --reason",
+                T::KIND,
+                log.base().title,
+                to
             ));
         }
-        let file_name = utils::generate_filename(&log.base().title, &log.base().date);
-        let file_path = self.config.storage.active_dir.join(&file_name);
-        save_entry_content(&file_path, content)?;
-        Ok(file_path)
+        Ok(())
+    }
+
+    /// Changes the status of an entry, recording the change and `reason` in
+    /// its history. A reason is also added to the entry's notes.
+    ///
+    /// Unless `force` is set, the change must be allowed by the configured workflow.
+    pub fn change_status(
+        &self,
+        partial_id: &str,
+        new_status: T::Status,
+        reason: Option<&str>,
+        force: bool,
+    ) -> Result<T> {
+        self.change_status_with(partial_id, new_status, reason, force, |_| Ok(()))
+    }
+
+    /// Like [`Self::change_status`], but first applies `edit` to the entry,
+    /// which can also veto the change by returning an error.
+    pub fn change_status_with(
+        &self,
+        partial_id: &str,
+        new_status: T::Status,
+        reason: Option<&str>,
+        force: bool,
+        edit: impl FnOnce(&mut T) -> Result<()>,
+    ) -> Result<T> {
+        let (mut log, file_path) = self.find_log(partial_id)?;
+        if !force {
+            self.check_transition(&log, &new_status)?;
+        }
+        self.check_reason(&log, &new_status, reason)?;
+        edit(&mut log)?;
+        self.apply_status(log, &file_path, new_status, reason)
+    }
+
+    /// The entries a change to several at once applies to: those named by
+    /// `partial_ids`, or every entry matching `filter` if no IDs are given.
+    /// Entries named more than once are returned once.
+    pub fn select(
+        &self,
+        partial_ids: &[String],
+        filter: &ListFilter<T::Status>,
+    ) -> Result<Vec<(T, PathBuf)>> {
+        let mut entries = if partial_ids.is_empty() {
+            self.list_log_files(filter)?
+        } else {
+            partial_ids
+                .iter()
+                .map(|partial_id| self.find_log(partial_id))
+                .collect::<Result<_>>()?
+        };
+        let mut seen = HashSet::new();
+        entries.retain(|(log, _)| seen.insert(log.base().id));
+        Ok(entries)
+    }
+
+    /// Changes the status of every entry named by `partial_ids`, like
+    /// [`Self::change_status`].
+    ///
+    /// All entries are looked up and checked against the workflow before any
+    /// is written, so an unknown ID or a disallowed change leaves every entry
+    /// as it was.
+    pub fn change_status_many(
+        &self,
+        partial_ids: &[String],
+        new_status: T::Status,
+        reason: Option<&str>,
+        force: bool,
+    ) -> Result<Vec<T>> {
+        let entries = self.select(partial_ids, &ListFilter::default())?;
+        for (log, _) in &entries {
+            if !force {
+                self.check_transition(log, &new_status)?;
+            }
+            self.check_reason(log, &new_status, reason)?;
+        }
+        let description = match entries.as_slice() {
+            [(log, _)] => describe_status_change(log, &new_status),
+            _ => format!(
+                "change {} {} entries to {}",
+                entries.len(),
+                T::KIND,
+                new_status.to_string()
+            ),
+        };
+        operations::record(&self.config, description, || {
+            entries
+                .into_iter()
+                .map(|(log, file_path)| {
+                    self.apply_status(log, &file_path, new_status.clone(), reason)
+                })
+                .collect()
+        })
+    }
+
+    /// Records the change of `log` to `new_status` and writes it out, adding
+    /// `reason` to the entry's notes.
+    fn apply_status(
+        &self,
+        mut log: T,
+        file_path: &Path,
+        new_status: T::Status,
+        reason: Option<&str>,
+    ) -> Result<T> {
+        let from = log.status().to_string();
+        let description = describe_status_change(&log, &new_status);
+        let reason = reason.map(str::trim).filter(|reason| !reason.is_empty());
+        // Read before the entry may move, as a dry run leaves it in place.
+        let body = match reason {
+            Some(_) => Some(self.read_plain_body(&log, file_path)?),
+            None => None,
+        };
+        let to = new_status.to_string();
+        record_status(&mut log, new_status, reason.unwrap_or(""));
+        let file_path = operations::record(&self.config, description, || {
+            let file_path = self.write_updated(&mut log, file_path)?;
+            if let (Some(reason), Some(body)) = (reason, body) {
+                let line = note_line(&self.config, &format!("Marked {}: {}", to, reason));
+                let body = append_to_section(&body, NOTES_HEADING, &line);
+                self.write_body(&mut log, &file_path, &body)?;
+            }
+            Ok(file_path)
+        })?;
+        crate::notify::status_changed(&self.config, &log, &from);
+        run_hook(
+            &self.config,
+            HookEvent::StatusChange,
+            &log,
+            &file_path,
+            Some(&from),
+        );
+        Ok(log)
+    }
+
+    /// Moves a closed entry back to `open_status`, recording the reversal in its history.
+    ///
+    /// Reopening is an explicit override, so the configured workflow is not consulted.
+    pub fn reopen(&self, partial_id: &str, open_status: T::Status, reason: &str) -> Result<T> {
+        let (mut log, file_path) = self.find_log(partial_id)?;
+        if log.status().to_string() == open_status.to_string() {
+            return Err(anyhow::anyhow!(
+                "Entry '{}' is already {}",
+                log.base().title,
+                open_status.to_string()
+            ));
+        }
+
+        let from = log.status().to_string();
+        let description = format!("reopen {} '{}' from {}", T::KIND, log.base().title, from);
+        record_status(&mut log, open_status, reason);
+        let file_path = operations::record(&self.config, description, || {
+            self.write_updated(&mut log, &file_path)
+        })?;
+        crate::notify::status_changed(&self.config, &log, &from);
+        run_hook(
+            &self.config,
+            HookEvent::StatusChange,
+            &log,
+            &file_path,
+            Some(&from),
+        );
+        Ok(log)
+    }
+
+    /// Returns the markdown body of an entry, without its frontmatter.
+    pub fn read_body(&self, file_path: &Path) -> Result<String> {
+        let content = load_entry_content(file_path)?;
+        let (_, body) = extract_frontmatter::<T>(&content)?;
+        Ok(body)
+    }
+
+    /// Returns the markdown body of an entry in plain text, decrypting it if
+    /// the entry is encrypted.
+    pub fn read_plain_body(&self, log: &T, file_path: &Path) -> Result<String> {
+        let body = self.read_body(file_path)?;
+        if log.base().encrypted {
+            crate::encryption::decrypt_body(&body)
+                .with_context(|| format!("Failed to decrypt {}", file_path.display()))
+        } else {
+            Ok(body)
+        }
+    }
+
+    /// Saves `body` as the body of an entry along with its frontmatter,
+    /// encrypting it if the entry is encrypted.
+    pub(crate) fn write_body(&self, log: &mut T, file_path: &Path, body: &str) -> Result<()> {
+        let body = if log.base().encrypted {
+            crate::encryption::encrypt_body(&self.config, body)?
+        } else {
+            body.to_string()
+        };
+        log.base_mut().updated_at = Some(utils::now());
+        dry_run::write(file_path, &update_markdown_frontmatter(log, &body)?)
     }
 
     pub fn update_log(&self, log: &mut T, file_path: &Path) -> Result<()> {
+        self.write_updated(log, file_path).map(|_| ())
+    }
+
+    /// Saves the frontmatter of `log`, moving the file if its status now belongs
+    /// in another directory. Returns where the entry ended up.
+    pub(crate) fn write_updated(&self, log: &mut T, file_path: &Path) -> Result<PathBuf> {
         let content = load_entry_content(file_path)?;
         let (_, content) = extract_frontmatter::<T>(&content)?;
+        log.base_mut().updated_at = Some(utils::now());
         let updated_content = update_markdown_frontmatter(log, &content)?;
 
-        let new_path = log.get_target_path(&self.config, &file_path.to_path_buf())?;
-        utils::ensure_directory(new_path.parent().unwrap())?;
-        std::fs::rename(file_path, &new_path)?;
-        std::fs::write(new_path, updated_content)?;
-        Ok(())
+        let mut new_path = log.get_target_path(&self.config, file_path)?;
+        if new_path != file_path {
+            new_path = utils::unused_path(new_path);
+            dry_run::rename(file_path, &new_path)?;
+        }
+        dry_run::write(&new_path, &updated_content)?;
+        Ok(new_path)
+    }
+
+    /// Rewrites the markdown body of an entry with `edit`, keeping its frontmatter.
+    /// The body of an encrypted entry is decrypted for `edit` and encrypted again.
+    pub fn update_body(
+        &self,
+        partial_id: &str,
+        edit: impl FnOnce(&str) -> String,
+    ) -> Result<(T, PathBuf)> {
+        let (mut log, file_path) = self.find_log(partial_id)?;
+        let body = edit(&self.read_plain_body(&log, &file_path)?);
+        self.write_body(&mut log, &file_path, &body)?;
+        Ok((log, file_path))
+    }
+
+    /// Adds `tags` to an entry, or with `replace` makes them its only tags.
+    pub fn update_tags(
+        &self,
+        partial_id: &str,
+        tags: &[String],
+        replace: bool,
+    ) -> Result<(T, PathBuf)> {
+        let (mut log, file_path) = self.find_log(partial_id)?;
+        let current = &mut log.base_mut().tags;
+        if replace {
+            current.clear();
+        }
+        current.extend(tags.iter().filter_map(|tag| utils::normalize_tag(tag)));
+        let file_path = self.write_updated(&mut log, &file_path)?;
+        Ok((log, file_path))
+    }
+
+    /// Changes the title of an entry and renames its file to match, then points
+    /// other entries' links to the old file name at the new one.
+    ///
+    /// Unless `allow_duplicate_title` is set, fails with [`LogError::DuplicateTitle`]
+    /// if another entry of the same type already has the title.
+    pub fn rename_log(
+        &self,
+        partial_id: &str,
+        title: &str,
+        allow_duplicate_title: bool,
+    ) -> Result<Renamed<T>> {
+        let (mut log, old_path) = self.find_log(partial_id)?;
+        let title = title.trim();
+        if title.is_empty() {
+            return Err(anyhow::anyhow!("The title cannot be empty"));
+        }
+        if !allow_duplicate_title {
+            let id = log.base().id;
+            if let Some((other, path)) = self.load_entries()?.into_iter().find(|(other, _)| {
+                other.base().id != id && other.base().title.to_lowercase() == title.to_lowercase()
+            }) {
+                return Err(LogError::DuplicateTitle {
+                    kind: T::KIND.to_string(),
+                    title: other.base().title.clone(),
+                    path,
+                }
+                .into());
+            }
+        }
+
+        let old_title = std::mem::replace(&mut log.base_mut().title, title.to_string());
+        let file_name = utils::generate_filename(title, &log.base().created_date().to_string());
+        let mut new_path = old_path.with_file_name(file_name);
+        if new_path != old_path {
+            new_path = utils::unused_path(new_path);
+        }
+
+        let body = self.read_body(&old_path)?;
+        log.base_mut().updated_at = Some(utils::now());
+        dry_run::write(&old_path, &update_markdown_frontmatter(&log, &body)?)?;
+        if new_path != old_path {
+            utils::rename_file(&old_path, &new_path)?;
+        }
+
+        let relinked =
+            crate::reference::relink(&self.config, &old_path, &new_path, &old_title, title)?;
+        Ok(Renamed {
+            log,
+            old_path,
+            new_path,
+            relinked,
+        })
+    }
+
+    /// Entries of this log type written with an older frontmatter schema.
+    pub(crate) fn outdated_entries(&self) -> Result<Vec<PathBuf>> {
+        let mut outdated = Vec::new();
+        let skipped = Skipped::current();
+        for path in self.entry_files()? {
+            let frontmatter = load_entry_content(&path).and_then(|content| {
+                extract_frontmatter::<serde_yaml::Value>(&content)
+                    .map(|(frontmatter, _)| Some(frontmatter))
+                    .with_context(|| format!("Invalid entry: {}", path.display()))
+            });
+            if let Some(frontmatter) = skip_malformed(&skipped, &path, frontmatter)? {
+                if is_kind::<T>(&self.config, &frontmatter) && migration::is_outdated(&frontmatter)
+                {
+                    outdated.push(path);
+                }
+            }
+        }
+        Ok(outdated)
+    }
+
+    /// Rewrites the frontmatter of an entry in the current schema. Fields dxlog
+    /// does not know are kept.
+    pub(crate) fn upgrade_entry(&self, file_path: &Path) -> Result<()> {
+        let content = load_entry_content(file_path)?;
+        let (mut frontmatter, body) = extract_frontmatter::<serde_yaml::Value>(&content)?;
+        migration::upgrade(T::KIND, &mut frontmatter)?;
+        serde_yaml::from_value::<T>(frontmatter.clone())
+            .context("The upgraded entry is still invalid")?;
+        dry_run::write(
+            file_path,
+            &update_markdown_frontmatter(&frontmatter, &body)?,
+        )
+    }
+
+    /// Moves an entry to the trash. It can be brought back with [`Trash::restore`].
+    ///
+    /// Fails with [`LogError::Referenced`] if other entries reference it, unless
+    /// `cascade` removes those references or `force` leaves them dangling.
+    pub fn delete_log(&self, partial_id: &str, cascade: bool, force: bool) -> Result<Tombstone> {
+        let (log, file_path) = self.find_log(partial_id)?;
+        let description = format!("delete {} '{}'", T::KIND, log.base().title);
+        let tombstone = operations::record(&self.config, description, || {
+            crate::reference::check_inbound_references(
+                &self.config,
+                log.base().id,
+                cascade,
+                force,
+            )?;
+            Trash::new(&self.config).put(log.base(), T::KIND, &file_path)
+        })?;
+        run_hook(&self.config, HookEvent::Delete, &log, &file_path, None);
+        Ok(tombstone)
+    }
+}
+
+/// How a status change is listed in the undo journal.
+fn describe_status_change<T: ResearchLog>(log: &T, new_status: &T::Status) -> String {
+    format!(
+        "change {} '{}' from {} to {}",
+        T::KIND,
+        log.base().title,
+        log.status().to_string(),
+        new_status.to_string()
+    )
+}
+
+/// Sets the status of `log`, adding the change to its history.
+fn record_status<T: ResearchLog>(log: &mut T, new_status: T::Status, reason: &str) {
+    let from = log.status().to_string();
+    let to = new_status.to_string();
+    log.update_status(new_status);
+    if from != to {
+        log.base_mut().history.push(StatusChange {
+            from,
+            to,
+            reason: reason.to_string(),
+            date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+        });
+    }
+}
+
+/// Hex digits of a UUIDv7 prefix needed before its creation time is worth
+/// narrowing a lookup by; 8 pin it down to about a minute.
+const MIN_TIMESTAMP_DIGITS: usize = 8;
+
+/// The first and last day an entry whose ID starts with `partial_id` can have
+/// been created on, if that is a UUIDv7 prefix long enough to tell.
+///
+/// The first 12 hex digits of a UUIDv7 are its creation time in milliseconds
+/// since the epoch, in UTC; a day is added on either side for the timezone the
+/// file name's date is in.
+fn creation_window(partial_id: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let digits: String = partial_id.chars().filter(|&c| c != '-').collect();
+    if digits.len() < MIN_TIMESTAMP_DIGITS || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    // The 13th digit is the version.
+    if digits.len() > 12 && !digits[12..].starts_with('7') {
+        return None;
+    }
+    let known = digits.len().min(12);
+    let prefix = u64::from_str_radix(&digits[..known], 16).ok()?;
+    let unknown_bits = 4 * (12 - known) as u32;
+    let first_ms = prefix << unknown_bits;
+    let last_ms = first_ms + (1 << unknown_bits) - 1;
+    let day = |ms: u64| {
+        chrono::DateTime::from_timestamp_millis(i64::try_from(ms).ok()?)
+            .map(|time| time.date_naive())
+    };
+    Some((day(first_ms)?.pred_opt()?, day(last_ms)?.succ_opt()?))
+}
+
+/// The creation date entry file names start with, e.g. `2025-03-01-title.md`.
+fn file_date(path: &Path) -> Option<NaiveDate> {
+    let name = path.file_name()?.to_str()?;
+    NaiveDate::parse_from_str(name.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// The entry stored in the file `text` names, or else the entries whose title
+/// contains `text`, ignoring case. Lets an entry be named by its path or title
+/// wherever an ID is taken.
+pub(crate) fn match_file_or_title<L>(
+    entries: Vec<(L, PathBuf)>,
+    text: &str,
+    base: impl Fn(&L) -> &BaseLog,
+) -> Vec<(L, PathBuf)> {
+    let mut entries = entries;
+    if let Ok(file) = Path::new(text).canonicalize() {
+        let (named, others): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|(_, path)| path.canonicalize().is_ok_and(|path| path == file));
+        if !named.is_empty() {
+            return named;
+        }
+        entries = others;
+    }
+    let text = text.to_lowercase();
+    entries
+        .into_iter()
+        .filter(|(log, _)| base(log).title.to_lowercase().contains(&text))
+        .collect()
+}
+
+/// Loads the entry at `path` if its handle is `handle`, or if there is none, its
+/// ID starts with `partial_id`.
+///
+/// IDs and handles are stored verbatim in the frontmatter, so files that do not
+/// contain the one looked for are skipped without being parsed.
+fn load_matching<T: ResearchLog>(
+    config: &Config,
+    path: &Path,
+    partial_id: &str,
+    handle: Option<&str>,
+) -> Result<Option<(T, PathBuf)>> {
+    let content = load_entry_content(path)?;
+    if !content.contains(handle.unwrap_or(partial_id)) {
+        return Ok(None);
+    }
+    Ok(parse_entry::<T>(config, &content, path)?
+        .filter(|log| match handle {
+            Some(handle) => log.base().handle.as_deref() == Some(handle),
+            None => log.base().id.to_string().starts_with(partial_id),
+        })
+        .map(|log| (log, path.to_path_buf())))
+}
+
+fn load_entry<T: ResearchLog>(config: &Config, path: &Path) -> Result<Option<T>> {
+    trace!(kind = T::KIND, path = %path.display(), "Reading entry");
+    parse_entry(config, &load_entry_content(path)?, path)
+}
+
+/// Parses an entry of type `T`, or returns `None` if it belongs to another log type.
+///
+/// Hypotheses and knowledge drafts share the active directory, so files whose
+/// status is not one of `T`'s statuses are skipped rather than failing to parse.
+fn parse_entry<T: ResearchLog>(config: &Config, content: &str, path: &Path) -> Result<Option<T>> {
+    let (frontmatter, _) = extract_frontmatter::<serde_yaml::Value>(content)
+        .with_context(|| format!("Invalid entry: {}", path.display()))?;
+    if !is_kind::<T>(config, &frontmatter) {
+        return Ok(None);
+    }
+    let mut frontmatter = frontmatter;
+    migration::upgrade(T::KIND, &mut frontmatter)
+        .with_context(|| format!("Invalid entry: {}", path.display()))?;
+    let log = serde_yaml::from_value(frontmatter)
+        .with_context(|| format!("Invalid entry: {}", path.display()))?;
+    Ok(Some(log))
+}
+
+/// Whether an entry's `status` is one of `T`'s statuses.
+///
+/// Entries without a readable status are claimed, so that parse errors surface.
+fn is_kind<T: ResearchLog>(config: &Config, frontmatter: &serde_yaml::Value) -> bool {
+    let Some(status) = frontmatter.get("status").and_then(|s| s.as_str()) else {
+        return true;
+    };
+
+    let normalize = |s: &str| s.replace(['_', '-'], "").to_lowercase();
+    let status = normalize(status);
+    T::Status::all(config)
+        .iter()
+        .any(|v| normalize(&v.to_string()) == status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{author, person, TempRepo};
+    use crate::{HypothesisLog, HypothesisStatus, KnowledgeLog, KnowledgeStatus};
+    use std::collections::{BTreeMap, HashSet};
+
+    fn knowledge(date: &str, name: &str, email: &str) -> KnowledgeLog {
+        let mut log = KnowledgeLog::new("Entry".to_string(), HashSet::new(), person(name, email));
+        log.base.created_at =
+            chrono::DateTime::parse_from_rfc3339(&format!("{}T09:30:00+02:00", date)).unwrap();
+        log
+    }
+
+    #[test]
+    fn test_list_filter_author_and_date_range() {
+        let log = knowledge("2025-03-10", "Ada Lovelace", "ada@example.org");
+
+        let filter = ListFilter::<KnowledgeStatus> {
+            author: Some("lovelace".to_string()),
+            since: NaiveDate::from_ymd_opt(2025, 3, 1),
+            until: NaiveDate::from_ymd_opt(2025, 3, 10),
+            ..Default::default()
+        };
+        assert!(filter.matches(&log));
+
+        let by_email = ListFilter::<KnowledgeStatus> {
+            author: Some("ADA@example.org".to_string()),
+            ..Default::default()
+        };
+        assert!(by_email.matches(&log));
+
+        let too_late = ListFilter::<KnowledgeStatus> {
+            since: NaiveDate::from_ymd_opt(2025, 3, 11),
+            ..Default::default()
+        };
+        assert!(!too_late.matches(&log));
+    }
+
+    #[test]
+    fn test_list_filter_query() {
+        let mut log = knowledge("2025-03-10", "Ada Lovelace", "ada@example.org");
+        log.base.title = "FPGA timing (v2)".to_string();
+        let filter = |text: &str, regex: bool| ListFilter::<KnowledgeStatus> {
+            query: Some(TextQuery::new(text, regex, false).unwrap()),
+            ..Default::default()
+        };
+
+        assert!(filter("fpga", false).matches(&log));
+        assert!(filter("(v2)", false).matches(&log));
+        assert!(!filter("asic", false).matches(&log));
+        assert!(filter(r"^fpga\s+tim", true).matches(&log));
+        assert!(!filter("^timing", true).matches(&log));
+        assert!(TextQuery::new("(unclosed", true, false).is_err());
+
+        // Body queries are left to `list_log_files`, which reads the body.
+        let in_body = ListFilter::<KnowledgeStatus> {
+            query: Some(TextQuery::new("asic", false, true).unwrap()),
+            ..Default::default()
+        };
+        assert!(in_body.matches(&log));
+    }
+
+    #[test]
+    fn test_list_filter_project() {
+        let mut log = knowledge("2025-03-10", "Ada Lovelace", "ada@example.org");
+        let optics = ListFilter::<KnowledgeStatus> {
+            project: Some("optics".to_string()),
+            ..Default::default()
+        };
+        assert!(!optics.matches(&log));
+
+        log.base.project = Some("optics".to_string());
+        assert!(optics.matches(&log));
+        assert!(ListFilter::<KnowledgeStatus>::default().matches(&log));
+    }
+
+    #[test]
+    fn test_list_filter_assignee() {
+        let mut log = knowledge("2025-03-10", "Ada Lovelace", "ada@example.org");
+        let assigned_to = |query: &str| ListFilter::<KnowledgeStatus> {
+            assignee: Some(query.to_string()),
+            ..Default::default()
+        };
+        // The creator is not the assignee.
+        assert!(!assigned_to("ada@example.org").matches(&log));
+
+        log.base.assignee = Some(person("Alan Turing", "alan@example.org"));
+        assert!(assigned_to("ALAN@example.org").matches(&log));
+        assert!(assigned_to("turing").matches(&log));
+        assert!(!assigned_to("ada@example.org").matches(&log));
+    }
+
+    #[test]
+    fn test_parse_entry_skips_other_log_types() {
+        let hypothesis =
+            "---\nid: 2fca92d9-3fd1-4567-ab4d-f9217fa728dd\nstatus: Active\n---\n# Body";
+        let knowledge = "---\nid: 2fca92d9-3fd1-4567-ab4d-f9217fa728dd\nstatus: Draft\n---\n# Body";
+
+        let path = Path::new("entry.md");
+        assert!(
+            parse_entry::<KnowledgeLog>(&Config::default(), hypothesis, path)
+                .unwrap()
+                .is_none()
+        );
+        // Claimed by status, so the missing fields are reported instead of skipped.
+        assert!(parse_entry::<KnowledgeLog>(&Config::default(), knowledge, path).is_err());
+        assert!(parse_entry::<KnowledgeLog>(&Config::default(), "no frontmatter", path).is_err());
+    }
+
+    #[test]
+    fn test_skip_malformed_records_each_file_once() {
+        let path = Path::new("broken-entry-for-skip-test.md");
+        let broken = || -> Result<Option<()>> { Err(anyhow::anyhow!("missing field `title`")) };
+        let current = Skipped::current();
+
+        assert!(skip_malformed(&current, path, broken()).unwrap().is_none());
+        assert!(skip_malformed(&current, path, broken()).unwrap().is_none());
+        assert_eq!(
+            skip_malformed(&current, path, Ok(Some(1))).unwrap(),
+            Some(1)
+        );
+
+        // Another thread neither sees this thread's files nor takes them away
+        std::thread::spawn(|| assert!(take_skipped_entries().is_empty()))
+            .join()
+            .unwrap();
+        let skipped = take_skipped_entries();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, path);
+        assert_eq!(skipped[0].reason, "missing field `title`");
+    }
+
+    #[test]
+    fn test_parse_entry_reads_legacy_date() {
+        let entry = |date_line: &str| {
+            format!(
+                "---\nid: 2fca92d9-3fd1-4567-ab4d-f9217fa728dd\n{}\ntitle: Entry\ntags: []\n\
+                 created_by:\n  name: Ada\n  email: ada@example.org\nreferences: []\n\
+                 status: Draft\n---\n# Body",
+                date_line
+            )
+        };
+        let path = Path::new("entry.md");
+
+        let legacy =
+            parse_entry::<KnowledgeLog>(&Config::default(), &entry("date: 2025-03-10"), path)
+                .unwrap()
+                .unwrap();
+        assert_eq!(
+            legacy.base.created_date(),
+            NaiveDate::from_ymd_opt(2025, 3, 10).unwrap()
+        );
+        assert!(legacy.base.updated_at.is_none());
+
+        let current = parse_entry::<KnowledgeLog>(
+            &Config::default(),
+            &entry("created_at: 2025-03-10T23:15:00-05:00"),
+            path,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            current.base.created_date(),
+            NaiveDate::from_ymd_opt(2025, 3, 10).unwrap()
+        );
+        assert_eq!(current.base.display_date("%d.%m.%Y"), "10.03.2025");
+
+        let serialized = serde_yaml::to_string(&current).unwrap();
+        assert!(serialized.contains("created_at: 2025-03-10T23:15:00-05:00"));
+        assert!(!serialized.contains("date:"));
+    }
+
+    #[test]
+    fn test_creation_window_of_v7_ids() {
+        let id = Uuid::now_v7().to_string();
+        let today = chrono::Utc::now().date_naive();
+        for prefix in [&id[..8], &id[..15], id.as_str()] {
+            let (first, last) = creation_window(prefix).unwrap();
+            assert!(first < today && today < last, "{}", prefix);
+        }
+        // A precise prefix narrows to the creation day and one on either side.
+        let (first, last) = creation_window(&id).unwrap();
+        assert_eq!((last - first).num_days(), 2);
+
+        assert!(creation_window(&id[..6]).is_none());
+        assert!(creation_window(&Uuid::new_v4().to_string()).is_none());
+        assert!(creation_window("H-042").is_none());
+
+        assert_eq!(
+            file_date(Path::new("logs/2025-03-01-cavity-modes.md")),
+            NaiveDate::from_ymd_opt(2025, 3, 1)
+        );
+        assert_eq!(file_date(Path::new("logs/cavity-modes.md")), None);
+    }
+
+    #[test]
+    fn test_check_transition_uses_workflow() {
+        let mut config = Config::default();
+        config.workflow.transitions.insert(
+            "knowledge".to_string(),
+            BTreeMap::from([("draft".to_string(), vec!["archived".to_string()])]),
+        );
+        let manager = LogManager::<KnowledgeLog>::new(config, Vec::new());
+        let log = knowledge("2025-03-10", "Ada Lovelace", "ada@example.org");
+
+        assert!(manager
+            .check_transition(&log, &KnowledgeStatus::Archived)
+            .is_ok());
+        assert!(manager
+            .check_transition(&log, &KnowledgeStatus::Published)
+            .is_err());
+
+        let mut typo = manager.config.clone();
+        typo.workflow
+            .transitions
+            .get_mut("knowledge")
+            .unwrap()
+            .insert("publshed".to_string(), Vec::new());
+        let manager = LogManager::<KnowledgeLog>::new(typo, Vec::new());
+        assert!(manager
+            .check_transition(&log, &KnowledgeStatus::Archived)
+            .is_err());
+    }
+
+    #[test]
+    fn test_duplicate_titles_are_scoped_per_type() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        let mut config = Config::default();
+        config.storage.active_dir = root.clone();
+        let new_hypothesis = || HypothesisLog::new("Same".into(), HashSet::new(), author());
+        let new_knowledge = || KnowledgeLog::new("same".into(), HashSet::new(), author());
+        let save = |manager: &LogManager<KnowledgeLog>, allow: bool| {
+            let log = new_knowledge();
+            let content = update_markdown_frontmatter(&log, "# Body")?;
+            manager.save_log(&log, &content, allow)
+        };
+
+        let hypotheses = LogManager::<HypothesisLog>::new(config.clone(), vec![root.clone()]);
+        let log = new_hypothesis();
+        let content = update_markdown_frontmatter(&log, "# Body").unwrap();
+        hypotheses.save_log(&log, &content, false).unwrap();
+
+        let knowledge = LogManager::<KnowledgeLog>::new(config, vec![root.clone()]);
+        let first = save(&knowledge, false).unwrap();
+        let err = save(&knowledge, false).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(LogError::DuplicateTitle { kind, path, .. }) if kind == "knowledge" && path == &first
+        ));
+        let second = save(&knowledge, true).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(utils::list_entries(&root, "md").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_ambiguous_id_lists_matches() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        let mut config = Config::default();
+        config.storage.active_dir = root.clone();
+        let manager = LogManager::<HypothesisLog>::new(config, vec![root.clone()]);
+        for title in ["First", "Second"] {
+            let log = HypothesisLog::new(title.into(), HashSet::new(), author());
+            let content = update_markdown_frontmatter(&log, "# Body").unwrap();
+            manager.save_log(&log, &content, false).unwrap();
+        }
+
+        let err = manager.find_log("").unwrap_err();
+        let Some(LogError::Ambiguous { matches, .. }) = err.downcast_ref() else {
+            panic!("expected an ambiguous ID, got {}", err);
+        };
+        let titles: Vec<&str> = matches.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles.len(), 2);
+        assert!(titles.contains(&"First") && titles.contains(&"Second"));
+        assert!(matches.iter().all(|m| m.kind == "hypothesis"));
+    }
+
+    #[test]
+    fn test_random_id_sharing_a_v7_prefix_is_ambiguous() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        let mut config = Config::default();
+        config.storage.active_dir = root.clone();
+        let manager = LogManager::<HypothesisLog>::new(config, vec![root.clone()]);
+        let log = HypothesisLog::new("Recent".into(), HashSet::new(), author());
+        let content = update_markdown_frontmatter(&log, "# Body").unwrap();
+        manager.save_log(&log, &content, false).unwrap();
+        let id = log.base().id.to_string();
+
+        // A hand-written v4 ID starting like the v7 one, in a file of another day.
+        let mut bytes = *Uuid::new_v4().as_bytes();
+        bytes[..4].copy_from_slice(&log.base().id.as_bytes()[..4]);
+        let mut random = HypothesisLog::new("Imported".into(), HashSet::new(), author());
+        random.base_mut().id = Uuid::from_bytes(bytes);
+        let content = update_markdown_frontmatter(&random, "# Body").unwrap();
+        std::fs::write(root.join("2020-01-01-imported.md"), content).unwrap();
+
+        let err = manager.find_log(&id[..8]).unwrap_err();
+        let Some(LogError::Ambiguous { matches, .. }) = err.downcast_ref() else {
+            panic!("expected an ambiguous ID, got {}", err);
+        };
+        assert_eq!(matches.len(), 2);
+        assert_eq!(manager.find_log(&id).unwrap().0.base().title, "Recent");
+        let random_id = random.base().id.to_string();
+        assert_eq!(
+            manager.find_log(&random_id).unwrap().0.base().title,
+            "Imported"
+        );
+    }
+
+    #[test]
+    fn test_find_by_file_or_title() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        let mut config = Config::default();
+        config.storage.active_dir = root.clone();
+        let manager = LogManager::<HypothesisLog>::new(config, vec![root.clone()]);
+        let mut paths = Vec::new();
+        for title in ["Noise scaling", "Noise floor"] {
+            let log = HypothesisLog::new(title.into(), HashSet::new(), author());
+            let content = update_markdown_frontmatter(&log, "# Body").unwrap();
+            paths.push(manager.save_log(&log, &content, false).unwrap());
+        }
+
+        let (log, _) = manager.find_log("FLOOR").unwrap();
+        assert_eq!(log.base.title, "Noise floor");
+        let (log, _) = manager.find_log(paths[0].to_str().unwrap()).unwrap();
+        assert_eq!(log.base.title, "Noise scaling");
+        let err = manager.find_log("noise").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(LogError::Ambiguous { matches, .. }) if matches.len() == 2
+        ));
+        let err = manager.find_log("signal").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(LogError::NotFound { kind, .. }) if kind == "hypothesis"
+        ));
+    }
+
+    #[test]
+    fn test_update_tags() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        let mut config = Config::default();
+        config.storage.active_dir = root.clone();
+        let manager = LogManager::<HypothesisLog>::new(config, vec![root.clone()]);
+        let tags = HashSet::from(["optics".to_string()]);
+        let log = HypothesisLog::new("Noise scaling".into(), tags, author());
+        let content = update_markdown_frontmatter(&log, "# Body").unwrap();
+        manager.save_log(&log, &content, false).unwrap();
+        let id = log.base.id.to_string();
+
+        let (log, _) = manager
+            .update_tags(&id, &[" ml / vision".to_string()], false)
+            .unwrap();
+        assert!(log.base.tags.contains("optics") && log.base.tags.contains("ml/vision"));
+        let (log, path) = manager
+            .update_tags(&id, &["lab".to_string()], true)
+            .unwrap();
+        assert_eq!(log.base.tags, HashSet::from(["lab".to_string()]));
+        assert_eq!(manager.find_log(&id).unwrap().0.base.tags, log.base.tags);
+        assert_eq!(manager.read_body(&path).unwrap(), "# Body");
+    }
+
+    #[test]
+    fn test_change_status_many_is_all_or_nothing() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        let mut config = Config::default();
+        config.storage.active_dir = root.clone();
+        config.workflow.transitions.insert(
+            "hypothesis".to_string(),
+            BTreeMap::from([
+                (
+                    "active".to_string(),
+                    vec!["suspended".to_string(), "proven".to_string()],
+                ),
+                ("suspended".to_string(), vec!["active".to_string()]),
+            ]),
+        );
+        let manager = LogManager::<HypothesisLog>::new(config, vec![root.clone()]);
+        let mut ids = Vec::new();
+        for title in ["First", "Second"] {
+            let log = HypothesisLog::new(title.into(), HashSet::new(), author());
+            let content = update_markdown_frontmatter(&log, "# Body").unwrap();
+            manager.save_log(&log, &content, false).unwrap();
+            ids.push(log.base.id.to_string());
+        }
+        let status = |id: &str| manager.find_log(id).unwrap().0.status.to_string();
+
+        manager
+            .change_status(&ids[0], HypothesisStatus::SUSPENDED, None, false)
+            .unwrap();
+        assert!(manager
+            .change_status_many(&ids, HypothesisStatus::PROVEN, None, false)
+            .is_err());
+        assert_eq!(status(&ids[0]), "suspended");
+        assert_eq!(status(&ids[1]), "active");
+
+        let named_twice = vec![ids[1].clone(), ids[1].clone()];
+        assert_eq!(
+            manager
+                .select(&named_twice, &ListFilter::default())
+                .unwrap()
+                .len(),
+            1
+        );
+        let changed = manager
+            .change_status_many(&ids, HypothesisStatus::SUSPENDED, None, false)
+            .unwrap();
+        assert_eq!(changed.len(), 2);
+        assert_eq!(status(&ids[1]), "suspended");
+
+        let suspended = ListFilter::with_status(HypothesisStatus::SUSPENDED);
+        assert_eq!(manager.select(&[], &suspended).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_change_status_with_reason() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        let mut config = Config::default();
+        config.storage.active_dir = root.clone();
+        config.storage.knowledge_base_dir = root.join("kb");
+        config.workflow.require_reason = true;
+        let search_dirs = vec![root.clone(), root.join("kb").join("hypotheses")];
+        let manager = LogManager::<HypothesisLog>::new(config, search_dirs);
+        let log = HypothesisLog::new("Noise scaling".into(), HashSet::new(), author());
+        let content = update_markdown_frontmatter(&log, "# Body").unwrap();
+        manager.save_log(&log, &content, false).unwrap();
+        let id = log.base.id.to_string();
+
+        for reason in [None, Some("  ")] {
+            assert!(manager
+                .change_status(&id, HypothesisStatus::DISPROVEN, reason, true)
+                .is_err());
+        }
+        manager
+            .change_status(&id, HypothesisStatus::SUSPENDED, None, false)
+            .unwrap();
+        let log = manager
+            .change_status(
+                &id,
+                HypothesisStatus::DISPROVEN,
+                Some("No effect below 4 K"),
+                false,
+            )
+            .unwrap();
+
+        let change = log.base.history.last().unwrap();
+        assert_eq!(change.to, "disproven");
+        assert_eq!(change.reason, "No effect below 4 K");
+        let (log, file_path) = manager.find_log(&id).unwrap();
+        let body = manager.read_plain_body(&log, &file_path).unwrap();
+        assert!(body.contains("## Notes"));
+        assert!(body
+            .lines()
+            .any(|line| line.ends_with(": Marked disproven: No effect below 4 K")));
     }
 }
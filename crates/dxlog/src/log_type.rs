@@ -0,0 +1,622 @@
+//! Log types declared under `[types]` in the config, such as protocols or
+//! datasets, next to the built-in ones.
+//!
+//! Entries of a declared type have the frontmatter every entry has, plus their
+//! `type` and a `status` from the type's list. They are kept in the type's
+//! directory, or in the one `[storage.layout.<type>]` gives their status.
+
+use anyhow::{Context, Result};
+use minijinja::context;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::log_type_prefix,
+    dry_run, handle,
+    hooks::{run_hook, HookEvent},
+    load_config,
+    log_manager::{match_file_or_title, skip_malformed, Skipped},
+    md_frontmatter::{
+        extract_frontmatter, serialize_yaml_frontmatter, update_markdown_frontmatter,
+    },
+    migration::{self, SCHEMA_VERSION},
+    note::{append_to_section, note_line, NOTES_HEADING},
+    notify, operations,
+    research_log::{LogEntry, LogStatus, ResearchLog},
+    template::entry_context,
+    utils::{self, BaseLog, StatusChange, TagFilter},
+    Config, DatasetLog, EntryMatch, ExperimentLog, HypothesisLog, IdeaLog, JournalLog,
+    KnowledgeLog, LiteratureLog, LogError, MeetingLog,
+};
+
+/// Template of new entries of a type without one of its own.
+const DEFAULT_TEMPLATE: &str = include_str!("templates/entry.default.jinja");
+
+/// A kind of entry, built in or declared in `[types]`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogType {
+    pub name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// Statuses in workflow order; new entries of declared types start in the first.
+    pub statuses: Vec<String>,
+    pub handle_prefix: String,
+    /// Whether the type ships with dxlog rather than being declared in `[types]`.
+    pub built_in: bool,
+}
+
+impl LogType {
+    fn built_in<T: ResearchLog>(config: &Config) -> Self {
+        Self {
+            name: T::KIND.to_string(),
+            description: String::new(),
+            statuses: T::Status::all(config)
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            handle_prefix: T::HANDLE_PREFIX.to_string(),
+            built_in: true,
+        }
+    }
+
+    /// The status called `name`, in any letter case and with `_` for `-`.
+    pub(crate) fn status(&self, name: &str) -> Result<String> {
+        let wanted = name.replace('_', "-").to_lowercase();
+        self.statuses
+            .iter()
+            .find(|status| **status == wanted)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Invalid value '{}', expected one of: {}",
+                    name,
+                    self.statuses.join(", ")
+                )
+            })
+    }
+}
+
+/// The log types of a repository: the built-in ones, then those from `[types]`.
+#[derive(Debug, Clone)]
+pub struct LogTypeRegistry {
+    types: Vec<LogType>,
+}
+
+impl LogTypeRegistry {
+    /// Names and handle prefixes of the log types that ship with dxlog.
+    pub const BUILT_IN: [(&'static str, &'static str); 8] = [
+        (HypothesisLog::KIND, HypothesisLog::HANDLE_PREFIX),
+        (LiteratureLog::KIND, LiteratureLog::HANDLE_PREFIX),
+        (KnowledgeLog::KIND, KnowledgeLog::HANDLE_PREFIX),
+        (JournalLog::KIND, JournalLog::HANDLE_PREFIX),
+        (DatasetLog::KIND, DatasetLog::HANDLE_PREFIX),
+        (MeetingLog::KIND, MeetingLog::HANDLE_PREFIX),
+        (IdeaLog::KIND, IdeaLog::HANDLE_PREFIX),
+        (ExperimentLog::KIND, ExperimentLog::HANDLE_PREFIX),
+    ];
+
+    pub fn new(config: &Config) -> Self {
+        let mut types = vec![
+            LogType::built_in::<HypothesisLog>(config),
+            LogType::built_in::<LiteratureLog>(config),
+            LogType::built_in::<KnowledgeLog>(config),
+            LogType::built_in::<JournalLog>(config),
+            LogType::built_in::<DatasetLog>(config),
+            LogType::built_in::<MeetingLog>(config),
+            LogType::built_in::<IdeaLog>(config),
+            LogType::built_in::<ExperimentLog>(config),
+        ];
+        types.extend(config.types.iter().map(|(name, log_type)| LogType {
+            name: name.clone(),
+            description: log_type.description.clone(),
+            statuses: log_type.statuses.clone(),
+            handle_prefix: log_type_prefix(name, log_type),
+            built_in: false,
+        }));
+        Self { types }
+    }
+
+    pub fn types(&self) -> &[LogType] {
+        &self.types
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LogType> {
+        self.types.iter().find(|log_type| log_type.name == name)
+    }
+
+    /// The type `name` declared in `[types]`.
+    pub fn declared(&self, name: &str) -> Result<&LogType> {
+        match self.get(name) {
+            Some(log_type) if log_type.built_in => Err(anyhow::anyhow!(
+                "'{}' is a built-in log type, use `dxlog {}` instead",
+                name,
+                name
+            )),
+            Some(log_type) => Ok(log_type),
+            None => {
+                let declared: Vec<&str> = self
+                    .types
+                    .iter()
+                    .filter(|log_type| !log_type.built_in)
+                    .map(|log_type| log_type.name.as_str())
+                    .collect();
+                if declared.is_empty() {
+                    Err(anyhow::anyhow!(
+                        "Unknown log type '{}'. No types are declared under [types] in the config",
+                        name
+                    ))
+                } else {
+                    Err(anyhow::anyhow!(
+                        "Unknown log type '{}'. Declared types: {}",
+                        name,
+                        declared.join(", ")
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// An entry of a type declared in `[types]`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomLog {
+    #[serde(flatten)]
+    pub base: BaseLog,
+    /// Name of the type in `[types]`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub status: String,
+}
+
+impl LogEntry for CustomLog {
+    fn kind_name(&self) -> &str {
+        &self.kind
+    }
+
+    fn base_log(&self) -> &BaseLog {
+        &self.base
+    }
+
+    fn status_name(&self) -> String {
+        self.status.clone()
+    }
+}
+
+/// Reads and writes the entries of one declared log type.
+pub struct CustomLogManager {
+    pub config: Config,
+    pub log_type: LogType,
+}
+
+impl CustomLogManager {
+    pub fn new(config: Config, name: &str) -> Result<Self> {
+        let log_type = LogTypeRegistry::new(&config).declared(name)?.clone();
+        Ok(Self { config, log_type })
+    }
+
+    fn search_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![self.config.type_dir(&self.log_type.name)];
+        dirs.extend(self.config.layout_dirs(&self.log_type.name));
+        dirs.sort();
+        dirs.dedup();
+        dirs
+    }
+
+    fn target_dir(&self, status: &str) -> PathBuf {
+        self.config
+            .layout_dir(&self.log_type.name, status)
+            .unwrap_or_else(|| self.config.type_dir(&self.log_type.name))
+    }
+
+    /// Parses an entry, or returns `None` if it is of another type.
+    fn load(&self, path: &Path) -> Result<Option<CustomLog>> {
+        let content = utils::load_entry_content(path)?;
+        let (mut frontmatter, _) = extract_frontmatter::<serde_yaml::Value>(&content)
+            .with_context(|| format!("Invalid entry: {}", path.display()))?;
+        if frontmatter.get("type").and_then(|t| t.as_str()) != Some(&self.log_type.name) {
+            return Ok(None);
+        }
+        migration::upgrade(&self.log_type.name, &mut frontmatter)
+            .with_context(|| format!("Invalid entry: {}", path.display()))?;
+        let log = serde_yaml::from_value(frontmatter)
+            .with_context(|| format!("Invalid entry: {}", path.display()))?;
+        Ok(Some(log))
+    }
+
+    /// Every entry of the type, with the file it is stored in.
+    fn load_entries(&self) -> Result<Vec<(CustomLog, PathBuf)>> {
+        let mut files = Vec::new();
+        for dir in self.search_dirs() {
+            files.extend(utils::list_entries(&dir, "md")?);
+        }
+        let skipped = Skipped::current();
+        files
+            .into_par_iter()
+            .map(|path| {
+                skip_malformed(&skipped, &path, self.load(&path))
+                    .map(|log| log.map(|log| (log, path)))
+            })
+            .filter_map(|r| r.transpose())
+            .collect()
+    }
+
+    /// Writes a new entry in the first status of the type. Unless
+    /// `allow_duplicate_title` is set, fails with [`LogError::DuplicateTitle`]
+    /// if an entry of the type has the same title.
+    pub fn create(
+        &self,
+        title: &str,
+        tags: Option<Vec<String>>,
+        body: Option<&str>,
+        allow_duplicate_title: bool,
+    ) -> Result<(CustomLog, PathBuf)> {
+        let config = &self.config;
+        let name = &self.log_type.name;
+        let entries = self.load_entries()?;
+        if !allow_duplicate_title {
+            if let Some((log, path)) = entries
+                .iter()
+                .find(|(log, _)| log.base.title.to_lowercase() == title.to_lowercase())
+            {
+                return Err(LogError::DuplicateTitle {
+                    kind: name.clone(),
+                    title: log.base.title.clone(),
+                    path: path.clone(),
+                }
+                .into());
+            }
+        }
+
+        let handle = handle::take(config, name, &self.log_type.handle_prefix, || {
+            Ok(entries
+                .into_iter()
+                .filter_map(|(log, _)| log.base.handle)
+                .collect())
+        })?;
+        let now = utils::now();
+        let log = CustomLog {
+            base: BaseLog {
+                schema_version: SCHEMA_VERSION,
+                id: config.id_version.new_id(),
+                handle: Some(handle),
+                created_at: now,
+                updated_at: Some(now),
+                title: title.to_string(),
+                tags: utils::normalize_tags(tags),
+                project: None,
+                milestone: None,
+                created_by: utils::get_author(config)?,
+                assignee: None,
+                references: HashSet::new(),
+                history: Vec::new(),
+                attachments: Vec::new(),
+                encrypted: false,
+            },
+            kind: name.clone(),
+            status: self.log_type.statuses[0].clone(),
+        };
+
+        let template = match config.types.get(name).and_then(|t| t.template.as_deref()) {
+            Some(path) => utils::load_entry_content(path)?,
+            None => DEFAULT_TEMPLATE.to_string(),
+        };
+        let body = body.map(str::trim);
+        let rendered = utils::render_entry(
+            &template,
+            context! {
+                body => body,
+                ..entry_context(config, &log.base, serialize_yaml_frontmatter(&log)?)
+            },
+            body,
+        )?;
+
+        let path = self.save(&log, &rendered)?;
+        Ok((log, path))
+    }
+
+    /// Writes a new entry with `content` to the directory of its status.
+    pub(crate) fn save(&self, log: &CustomLog, content: &str) -> Result<PathBuf> {
+        let file_name =
+            utils::generate_filename(&log.base.title, &log.base.created_date().to_string());
+        let path = utils::unused_path(self.target_dir(&log.status).join(file_name));
+        operations::record(
+            &self.config,
+            format!("create {} '{}'", self.log_type.name, log.base.title),
+            || utils::save_entry_content(&path, content),
+        )?;
+        run_hook(&self.config, HookEvent::Create, log, &path, None);
+        Ok(path)
+    }
+
+    /// Takes the next handle for a new entry of the type.
+    pub(crate) fn next_handle(&self) -> Result<String> {
+        handle::take(
+            &self.config,
+            &self.log_type.name,
+            &self.log_type.handle_prefix,
+            || {
+                Ok(self
+                    .load_entries()?
+                    .into_iter()
+                    .filter_map(|(log, _)| log.base.handle)
+                    .collect())
+            },
+        )
+    }
+
+    /// Entries with `status`, if given, and tags matching `tags`, oldest first.
+    pub fn list(
+        &self,
+        status: Option<&str>,
+        tags: &TagFilter,
+    ) -> Result<Vec<(CustomLog, PathBuf)>> {
+        let status = status.map(|s| self.log_type.status(s)).transpose()?;
+        let mut logs: Vec<_> = self
+            .load_entries()?
+            .into_iter()
+            .filter(|(log, _)| status.as_ref().is_none_or(|s| log.status == *s))
+            .filter(|(log, _)| tags.matches(&log.base.tags))
+            .collect();
+        logs.sort_by_key(|(log, _)| log.base.created_at);
+        Ok(logs)
+    }
+
+    /// The entries whose ID starts with `partial_id` or whose handle it is.
+    /// Failing that, those whose file or title `partial_id` names.
+    fn matches(&self, partial_id: &str) -> Result<Vec<(CustomLog, PathBuf)>> {
+        let (matches, others): (Vec<_>, Vec<_>) =
+            self.load_entries()?.into_iter().partition(|(log, _)| {
+                log.base.id.to_string().starts_with(partial_id)
+                    || log
+                        .base
+                        .handle
+                        .as_deref()
+                        .is_some_and(|handle| handle::matches(handle, partial_id))
+            });
+        if matches.is_empty() {
+            return Ok(match_file_or_title(others, partial_id, |log| &log.base));
+        }
+        Ok(matches)
+    }
+
+    /// The entries [`Self::find`] chooses from, newest first.
+    pub(crate) fn matching(&self, partial_id: &str) -> Result<Vec<EntryMatch>> {
+        Ok(self.entry_matches(&self.matches(partial_id)?))
+    }
+
+    fn entry_matches(&self, found: &[(CustomLog, PathBuf)]) -> Vec<EntryMatch> {
+        let mut matches: Vec<EntryMatch> = found
+            .iter()
+            .map(|(log, _)| EntryMatch::new(&log.base, &self.log_type.name, log.status.clone()))
+            .collect();
+        matches.sort_by_key(|entry| std::cmp::Reverse(entry.created_at));
+        matches
+    }
+
+    /// The entry whose ID starts with `partial_id`, or whose handle it is.
+    /// Failing that, `partial_id` may name the entry's file or part of its title.
+    pub fn find(&self, partial_id: &str) -> Result<(CustomLog, PathBuf)> {
+        let mut matches = self.matches(partial_id)?;
+        match matches.len() {
+            0 => Err(LogError::NotFound {
+                kind: self.log_type.name.clone(),
+                partial_id: partial_id.to_string(),
+            }
+            .into()),
+            1 => Ok(matches.remove(0)),
+            _ => Err(LogError::Ambiguous {
+                partial_id: partial_id.to_string(),
+                matches: self.entry_matches(&matches),
+            }
+            .into()),
+        }
+    }
+
+    /// Whether `log` is in the last status of its type, where its work is done.
+    pub(crate) fn is_complete(&self, log: &CustomLog) -> bool {
+        self.log_type.statuses.last() == Some(&log.status)
+    }
+
+    /// Returns the markdown body of an entry, without its frontmatter.
+    pub(crate) fn read_body(&self, path: &Path) -> Result<String> {
+        let (_, body) =
+            extract_frontmatter::<serde_yaml::Value>(&utils::load_entry_content(path)?)?;
+        Ok(body)
+    }
+
+    /// Saves `log` with `body`, moving the file if its status now belongs in
+    /// another directory. Returns where the entry ended up.
+    pub(crate) fn write(&self, log: &mut CustomLog, path: &Path, body: &str) -> Result<PathBuf> {
+        log.base.updated_at = Some(utils::now());
+        let updated = update_markdown_frontmatter(log, body)?;
+        let mut new_path = self
+            .target_dir(&log.status)
+            .join(path.file_name().context("Entry path has no file name")?);
+        if new_path != path {
+            new_path = utils::unused_path(new_path);
+            dry_run::rename(path, &new_path)?;
+        }
+        dry_run::write(&new_path, &updated)?;
+        Ok(new_path)
+    }
+
+    /// Changes the status of an entry, recording the change and `reason` in its
+    /// history and moving the file if the new status belongs in another
+    /// directory. A reason is also added to the entry's notes.
+    ///
+    /// Unless `force` is set, the change must be allowed by `[workflow.<type>]`.
+    pub fn update_status(
+        &self,
+        partial_id: &str,
+        status: &str,
+        reason: Option<&str>,
+        force: bool,
+    ) -> Result<(CustomLog, PathBuf)> {
+        let name = &self.log_type.name;
+        let status = self.log_type.status(status)?;
+        let (mut log, path) = self.find(partial_id)?;
+        if !force {
+            self.config.workflow.check_transition(
+                name,
+                &self.log_type.statuses,
+                &log.base.title,
+                &log.status,
+                &status,
+            )?;
+        }
+        let reason = reason.map(str::trim).filter(|reason| !reason.is_empty());
+        if log.status != status && reason.is_none() && self.config.workflow.requires_reason(&status)
+        {
+            return Err(anyhow::anyhow!(
+                "The workflow requires a reason to mark {} '{}' {}. Use --reason",
+                name,
+                log.base.title,
+                status
+            ));
+        }
+
+        let from = log.status.clone();
+        let description = format!(
+            "change {} '{}' from {} to {}",
+            name, log.base.title, from, status
+        );
+        if log.status != status {
+            log.base.history.push(StatusChange {
+                from: std::mem::replace(&mut log.status, status),
+                to: log.status.clone(),
+                reason: reason.unwrap_or_default().to_string(),
+                date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            });
+        }
+
+        let mut body = self.read_body(&path)?;
+        if let Some(reason) = reason {
+            let line = note_line(&self.config, &format!("Marked {}: {}", log.status, reason));
+            body = append_to_section(&body, NOTES_HEADING, &line);
+        }
+        let new_path = operations::record(&self.config, description, || {
+            self.write(&mut log, &path, &body)
+        })?;
+        notify::status_changed(&self.config, &log, &from);
+        run_hook(
+            &self.config,
+            HookEvent::StatusChange,
+            &log,
+            &new_path,
+            Some(&from),
+        );
+        Ok((log, new_path))
+    }
+}
+
+pub fn log_types() -> Result<Vec<LogType>> {
+    let config = load_config()?;
+    Ok(LogTypeRegistry::new(&config).types().to_vec())
+}
+
+pub fn create_custom_entry(
+    kind: &str,
+    title: &str,
+    tags: Option<Vec<String>>,
+    body: Option<&str>,
+    allow_duplicate_title: bool,
+) -> Result<(CustomLog, PathBuf)> {
+    let config = load_config()?;
+    CustomLogManager::new(config, kind)?.create(title, tags, body, allow_duplicate_title)
+}
+
+pub fn list_custom_entries(
+    kind: &str,
+    status: Option<&str>,
+    tags: &TagFilter,
+) -> Result<Vec<(CustomLog, PathBuf)>> {
+    let config = load_config()?;
+    CustomLogManager::new(config, kind)?.list(status, tags)
+}
+
+pub fn update_custom_entry_status(
+    kind: &str,
+    partial_id: &str,
+    status: &str,
+    reason: Option<&str>,
+    force: bool,
+) -> Result<(CustomLog, PathBuf)> {
+    let config = load_config()?;
+    CustomLogManager::new(config, kind)?.update_status(partial_id, status, reason, force)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogTypeConfig;
+
+    #[test]
+    fn test_registry() {
+        let mut config = Config::default();
+        config.types.insert(
+            "protocol".to_string(),
+            LogTypeConfig {
+                statuses: vec!["draft".to_string(), "validated".to_string()],
+                ..Default::default()
+            },
+        );
+        let registry = LogTypeRegistry::new(&config);
+
+        assert_eq!(registry.types().len(), 9);
+        let protocol = registry.declared("protocol").unwrap();
+        assert_eq!(protocol.handle_prefix, "P");
+        assert_eq!(protocol.status("VALIDATED").unwrap(), "validated");
+        assert!(protocol.status("retired").is_err());
+        assert!(registry.get("hypothesis").unwrap().built_in);
+        assert!(registry.declared("hypothesis").is_err());
+        assert!(registry.declared("dataset").is_err());
+    }
+
+    #[test]
+    fn test_declared_types_follow_the_workflow() {
+        use crate::{
+            config::CONFIG_FILE_NAME,
+            lookup::find_entry,
+            test_support::{TempRepo, AUTHOR_SETTINGS},
+        };
+        use std::fs;
+
+        let repo = TempRepo::new();
+        let settings = format!(
+            "{}\n[types.protocol]\nstatuses = [\"draft\", \"validated\", \"retired\"]\n",
+            AUTHOR_SETTINGS
+        );
+        let config = repo.load(&format!(
+            "{}\n[workflow.protocol]\ndraft = [\"validated\"]\n",
+            settings
+        ));
+        let manager = CustomLogManager::new(config.clone(), "protocol").unwrap();
+        let (log, _) = manager.create("PCR", None, None, false).unwrap();
+        let id = log.base.id.to_string();
+
+        assert!(manager.update_status(&id, "retired", None, false).is_err());
+        let (log, _) = manager.update_status(&id, "retired", None, true).unwrap();
+        assert_eq!(log.status, "retired");
+
+        let mut entry = find_entry(&config, "P-1").unwrap();
+        assert!(entry.is_complete());
+        entry.base_mut().tags.insert("wetlab".to_string());
+        entry.save().unwrap();
+        let (log, _) = manager.find(&id).unwrap();
+        assert!(log.base.tags.contains("wetlab"));
+
+        fs::write(
+            repo.root().join(CONFIG_FILE_NAME),
+            format!(
+                "{}\n[workflow.protcol]\ndraft = [\"validated\"]\n",
+                settings
+            ),
+        )
+        .unwrap();
+        assert!(Config::load(repo.root()).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::{
+    load_config, log_manager::LogManager, research_log::ResearchLog, utils::BaseLog, Config,
+    CustomLog, CustomLogManager, DatasetManager, ExperimentManager, HypothesisManager, IdeaManager,
+    JournalManager, KnowledgeManager, LiteratureManager, LogError, MeetingManager,
+};
+
+/// An entry matching a partial ID, as listed by [`matching_entries`] and
+/// [`LogError::Ambiguous`](crate::LogError::Ambiguous).
+#[derive(Debug, Clone, Serialize)]
+pub struct EntryMatch {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub title: String,
+    pub status: String,
+    pub created_at: DateTime<FixedOffset>,
+}
+
+impl EntryMatch {
+    pub(crate) fn new(base: &BaseLog, kind: &str, status: String) -> Self {
+        Self {
+            id: base.id,
+            handle: base.handle.clone(),
+            kind: kind.to_string(),
+            title: base.title.clone(),
+            status,
+            created_at: base.created_at,
+        }
+    }
+}
+
+/// Lists the entries whose ID starts with `partial_id` or whose handle it is,
+/// newest first, to choose from when an ID is ambiguous or missing.
+///
+/// Only entries of type `kind` are listed if it is given; an empty
+/// `partial_id` matches every entry.
+pub fn matching_entries(kind: Option<&str>, partial_id: &str) -> Result<Vec<EntryMatch>> {
+    let config = load_config()?;
+    let mut matches = Vec::new();
+    collect(
+        &HypothesisManager::new(config.clone()).manager,
+        kind,
+        partial_id,
+        &mut matches,
+    )?;
+    collect(
+        &LiteratureManager::new(config.clone()).manager,
+        kind,
+        partial_id,
+        &mut matches,
+    )?;
+    collect(
+        &KnowledgeManager::new(config.clone()).manager,
+        kind,
+        partial_id,
+        &mut matches,
+    )?;
+    collect(
+        &JournalManager::new(config.clone()).manager,
+        kind,
+        partial_id,
+        &mut matches,
+    )?;
+    collect(
+        &DatasetManager::new(config.clone()).manager,
+        kind,
+        partial_id,
+        &mut matches,
+    )?;
+    collect(
+        &MeetingManager::new(config.clone()).manager,
+        kind,
+        partial_id,
+        &mut matches,
+    )?;
+    collect(
+        &IdeaManager::new(config.clone()).manager,
+        kind,
+        partial_id,
+        &mut matches,
+    )?;
+    collect(
+        &ExperimentManager::new(config.clone()).manager,
+        kind,
+        partial_id,
+        &mut matches,
+    )?;
+    for name in config.types.keys() {
+        if kind.is_none_or(|kind| kind == name) {
+            matches.extend(CustomLogManager::new(config.clone(), name)?.matching(partial_id)?);
+        }
+    }
+
+    matches.sort_by_key(|entry| std::cmp::Reverse(entry.created_at));
+    Ok(matches)
+}
+
+fn collect<T: ResearchLog>(
+    manager: &LogManager<T>,
+    kind: Option<&str>,
+    partial_id: &str,
+    matches: &mut Vec<EntryMatch>,
+) -> Result<()> {
+    if kind.is_some_and(|kind| kind != T::KIND) {
+        return Ok(());
+    }
+    matches.extend(manager.matching(partial_id)?);
+    Ok(())
+}
+
+/// An entry of any log type, as found by [`find_entry`], that is written back
+/// through the manager of its type.
+pub(crate) trait AnyEntry {
+    fn base(&self) -> &BaseLog;
+    fn base_mut(&mut self) -> &mut BaseLog;
+    fn kind(&self) -> &str;
+    /// See [`ResearchLog::is_complete`].
+    fn is_complete(&self) -> bool;
+    /// Saves the frontmatter, moving the file if the entry's status belongs in
+    /// another directory.
+    fn save(&mut self) -> Result<()>;
+    /// The body as stored, still encrypted if the entry is.
+    fn read_body(&self) -> Result<String>;
+    /// Rewrites the body with `edit`, keeping the frontmatter.
+    fn update_body(&mut self, edit: &dyn Fn(&str) -> String) -> Result<()>;
+}
+
+struct Found<T: ResearchLog> {
+    manager: LogManager<T>,
+    log: T,
+    path: PathBuf,
+}
+
+impl<T: ResearchLog> AnyEntry for Found<T> {
+    fn base(&self) -> &BaseLog {
+        self.log.base()
+    }
+
+    fn base_mut(&mut self) -> &mut BaseLog {
+        self.log.base_mut()
+    }
+
+    fn kind(&self) -> &str {
+        T::KIND
+    }
+
+    fn is_complete(&self) -> bool {
+        self.log.is_complete()
+    }
+
+    fn save(&mut self) -> Result<()> {
+        self.path = self.manager.write_updated(&mut self.log, &self.path)?;
+        Ok(())
+    }
+
+    fn read_body(&self) -> Result<String> {
+        self.manager.read_body(&self.path)
+    }
+
+    fn update_body(&mut self, edit: &dyn Fn(&str) -> String) -> Result<()> {
+        let body = edit(&self.manager.read_plain_body(&self.log, &self.path)?);
+        self.manager.write_body(&mut self.log, &self.path, &body)
+    }
+}
+
+struct FoundCustom {
+    manager: CustomLogManager,
+    log: CustomLog,
+    path: PathBuf,
+}
+
+impl AnyEntry for FoundCustom {
+    fn base(&self) -> &BaseLog {
+        &self.log.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseLog {
+        &mut self.log.base
+    }
+
+    fn kind(&self) -> &str {
+        &self.log.kind
+    }
+
+    fn is_complete(&self) -> bool {
+        self.manager.is_complete(&self.log)
+    }
+
+    fn save(&mut self) -> Result<()> {
+        let body = self.manager.read_body(&self.path)?;
+        self.path = self.manager.write(&mut self.log, &self.path, &body)?;
+        Ok(())
+    }
+
+    fn read_body(&self) -> Result<String> {
+        self.manager.read_body(&self.path)
+    }
+
+    fn update_body(&mut self, edit: &dyn Fn(&str) -> String) -> Result<()> {
+        let body = edit(&self.manager.read_body(&self.path)?);
+        self.path = self.manager.write(&mut self.log, &self.path, &body)?;
+        Ok(())
+    }
+}
+
+/// Looks for the entry in the entries of one type.
+type Finder<'a> = Box<dyn Fn() -> Result<Option<Box<dyn AnyEntry>>> + 'a>;
+
+/// The entry of any type whose ID starts with `partial_id` or whose handle it
+/// is, failing that, whose file or title it names.
+///
+/// Types are tried in turn and the first match wins, but a `partial_id` that
+/// is ambiguous within a type fails with [`LogError::Ambiguous`].
+pub(crate) fn find_entry(config: &Config, partial_id: &str) -> Result<Box<dyn AnyEntry>> {
+    let mut finders: Vec<Finder> = vec![
+        Box::new(|| find_in(HypothesisManager::new(config.clone()).manager, partial_id)),
+        Box::new(|| find_in(LiteratureManager::new(config.clone()).manager, partial_id)),
+        Box::new(|| find_in(KnowledgeManager::new(config.clone()).manager, partial_id)),
+        Box::new(|| find_in(JournalManager::new(config.clone()).manager, partial_id)),
+        Box::new(|| find_in(DatasetManager::new(config.clone()).manager, partial_id)),
+        Box::new(|| find_in(MeetingManager::new(config.clone()).manager, partial_id)),
+        Box::new(|| find_in(IdeaManager::new(config.clone()).manager, partial_id)),
+        Box::new(|| find_in(ExperimentManager::new(config.clone()).manager, partial_id)),
+    ];
+    for name in config.types.keys() {
+        finders.push(Box::new(move || {
+            let manager = CustomLogManager::new(config.clone(), name)?;
+            match manager.find(partial_id) {
+                Ok((log, path)) => Ok(Some(Box::new(FoundCustom { manager, log, path }))),
+                Err(err) if is_ambiguous(&err) => Err(err),
+                Err(_) => Ok(None),
+            }
+        }));
+    }
+    for find in finders {
+        if let Some(entry) = find()? {
+            return Ok(entry);
+        }
+    }
+    Err(LogError::NotFound {
+        kind: "entry".to_string(),
+        partial_id: partial_id.to_string(),
+    }
+    .into())
+}
+
+fn find_in<T: ResearchLog + 'static>(
+    manager: LogManager<T>,
+    partial_id: &str,
+) -> Result<Option<Box<dyn AnyEntry>>> {
+    match manager.find_log(partial_id) {
+        Ok((log, path)) => Ok(Some(Box::new(Found { manager, log, path }))),
+        Err(err) if is_ambiguous(&err) => Err(err),
+        Err(_) => Ok(None),
+    }
+}
+
+fn is_ambiguous(err: &anyhow::Error) -> bool {
+    matches!(err.downcast_ref(), Some(LogError::Ambiguous { .. }))
+}
@@ -0,0 +1,432 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use minijinja::context;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fmt,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    load_config,
+    log_manager::{ListFilter, LogManager, Renamed},
+    md_frontmatter::serialize_yaml_frontmatter,
+    migration::SCHEMA_VERSION,
+    project::ensure_registered,
+    research_log::ResearchLog,
+    template::{builtin_template, entry_context},
+    utils::{self, Author, BaseLog},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum MeetingStatus {
+    Open,
+    Archived,
+}
+
+impl fmt::Display for MeetingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            MeetingStatus::Open => "open",
+            MeetingStatus::Archived => "archived",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Something agreed on in a meeting that someone has to do.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ActionItem {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// Notes of a meeting. The action items are kept in the frontmatter so they
+/// can be collected across meetings with `dxlog actions list`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MeetingLog {
+    #[serde(flatten)]
+    pub base: BaseLog,
+    pub status: MeetingStatus,
+    pub date: NaiveDate,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attendees: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub action_items: Vec<ActionItem>,
+}
+
+impl ResearchLog for MeetingLog {
+    type Status = MeetingStatus;
+    const KIND: &'static str = "meeting";
+    const HANDLE_PREFIX: &'static str = "M";
+
+    fn base(&self) -> &BaseLog {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BaseLog {
+        &mut self.base
+    }
+
+    fn status(&self) -> &Self::Status {
+        &self.status
+    }
+
+    fn status_mut(&mut self) -> &mut Self::Status {
+        &mut self.status
+    }
+
+    fn new(title: String, tags: HashSet<String>, author: Author) -> Self {
+        let now = utils::now();
+        Self {
+            base: BaseLog {
+                schema_version: SCHEMA_VERSION,
+                id: Uuid::now_v7(),
+                handle: None,
+                created_at: now,
+                updated_at: Some(now),
+                title,
+                tags,
+                project: None,
+                milestone: None,
+                created_by: author,
+                assignee: None,
+                references: HashSet::new(),
+                history: Vec::new(),
+                attachments: Vec::new(),
+                encrypted: false,
+            },
+            status: MeetingStatus::Open,
+            date: now.date_naive(),
+            attendees: Vec::new(),
+            action_items: Vec::new(),
+        }
+    }
+
+    fn update_status(&mut self, new_status: Self::Status) {
+        self.status = new_status;
+    }
+
+    fn get_target_path(&self, config: &Config, current_path: &Path) -> Result<PathBuf> {
+        let filename = current_path.file_name().unwrap();
+        if let Some(dir) = config.layout_dir(Self::KIND, &self.status.to_string()) {
+            return Ok(dir.join(filename));
+        }
+        // Archived meetings stay with the others: entries are told apart by
+        // status, and the archive already holds archived journal days.
+        Ok(config.storage.meetings_dir.join(filename))
+    }
+}
+
+/// Details of a new meeting besides its title.
+#[derive(Debug, Default, Clone)]
+pub struct MeetingDetails {
+    /// Day of the meeting; today if not given.
+    pub date: Option<NaiveDate>,
+    pub attendees: Vec<String>,
+    /// Project from `[projects]` the meeting was about.
+    pub project: Option<String>,
+    pub action_items: Vec<ActionItem>,
+}
+
+/// An action item together with the meeting it was agreed in, as returned by
+/// [`list_action_items`].
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingAction {
+    pub meeting_id: Uuid,
+    pub meeting_handle: Option<String>,
+    pub meeting: String,
+    pub date: NaiveDate,
+    /// Position of the item in the meeting, 1-based as shown by `meeting action list`.
+    pub index: usize,
+    #[serde(flatten)]
+    pub item: ActionItem,
+}
+
+pub struct MeetingManager {
+    pub manager: LogManager<MeetingLog>,
+}
+
+impl MeetingManager {
+    pub fn new(config: Config) -> Self {
+        let search_dirs = vec![config.storage.meetings_dir.clone()];
+
+        Self {
+            manager: LogManager::<MeetingLog>::new(config, search_dirs),
+        }
+    }
+
+    /// Records a meeting. Recurring meetings share a title, so duplicate titles
+    /// are always allowed; the date tells them apart.
+    pub fn create(
+        &self,
+        title: &str,
+        details: MeetingDetails,
+        tags: Option<Vec<String>>,
+        body: Option<&str>,
+        template: Option<&str>,
+    ) -> Result<MeetingLog> {
+        let config = &self.manager.config;
+        if let Some(project) = &details.project {
+            ensure_registered(config, project)?;
+        }
+        let author = utils::get_author(config)?;
+        let mut meeting = MeetingLog::new(title.to_string(), utils::normalize_tags(tags), author);
+        if let Some(date) = details.date {
+            meeting.date = date;
+        }
+        meeting.attendees = details.attendees;
+        meeting.action_items = details.action_items;
+        meeting.base.project = details.project;
+        self.manager.assign_ids(&mut meeting)?;
+
+        let yaml = serialize_yaml_frontmatter(&meeting)?;
+        let template_path = config.template_for(MeetingLog::KIND, template, &meeting.base.tags)?;
+        // Repositories set up before meetings existed have no meeting template yet.
+        let template_content = match (template_path.exists(), template) {
+            (false, None | Some("default")) => builtin_template(MeetingLog::KIND)
+                .unwrap_or_default()
+                .to_string(),
+            _ => utils::load_entry_content(&template_path)?,
+        };
+
+        let body = body.map(str::trim);
+        let rendered = utils::render_entry(
+            &template_content,
+            context! {
+                body => body,
+                date => meeting.date.format(&config.date_format).to_string(),
+                attendees => meeting.attendees,
+                action_items => meeting.action_items,
+                ..entry_context(config, &meeting.base, yaml)
+            },
+            body,
+        )?;
+
+        self.manager.save_log(&meeting, &rendered, true)?;
+        Ok(meeting)
+    }
+
+    /// Retitles an entry, renaming its file and updating links to it.
+    pub fn rename(&self, partial_id: &str, title: &str) -> Result<Renamed<MeetingLog>> {
+        self.manager.rename_log(partial_id, title, true)
+    }
+
+    pub fn add_action(&self, partial_id: &str, item: ActionItem) -> Result<ActionItem> {
+        let (mut meeting, file_path) = self.manager.find_log(partial_id)?;
+        meeting.action_items.push(item.clone());
+        self.manager.update_log(&mut meeting, &file_path)?;
+        Ok(item)
+    }
+
+    /// Checks or unchecks the action item at `index` (1-based, as shown by
+    /// `meeting action list`).
+    pub fn set_action_done(
+        &self,
+        partial_id: &str,
+        index: usize,
+        done: bool,
+    ) -> Result<ActionItem> {
+        let (mut meeting, file_path) = self.manager.find_log(partial_id)?;
+        if index == 0 || index > meeting.action_items.len() {
+            return Err(anyhow::anyhow!(
+                "No action item #{} in meeting '{}' ({} items)",
+                index,
+                meeting.base.title,
+                meeting.action_items.len()
+            ));
+        }
+        let item = &mut meeting.action_items[index - 1];
+        item.done = done;
+        let item = item.clone();
+        self.manager.update_log(&mut meeting, &file_path)?;
+        Ok(item)
+    }
+
+    pub fn update_status(
+        &self,
+        partial_id: &str,
+        new_status: MeetingStatus,
+        reason: Option<&str>,
+        force: bool,
+    ) -> Result<()> {
+        self.manager
+            .change_status(partial_id, new_status, reason, force)?;
+        Ok(())
+    }
+
+    pub fn list(&self, filter: &ListFilter<MeetingStatus>) -> Result<Vec<MeetingLog>> {
+        self.manager.list_logs(filter)
+    }
+
+    pub fn find(&self, partial_id: &str) -> Result<(MeetingLog, PathBuf)> {
+        self.manager.find_log(partial_id)
+    }
+
+    /// Collects the action items of every meeting, earliest due first; items
+    /// without a due date follow, oldest meeting first.
+    pub fn action_items(
+        &self,
+        open_only: bool,
+        owner: Option<&str>,
+        project: Option<&str>,
+    ) -> Result<Vec<MeetingAction>> {
+        let filter = ListFilter {
+            project: project.map(str::to_string),
+            ..Default::default()
+        };
+        let mut actions: Vec<MeetingAction> = self
+            .list(&filter)?
+            .into_iter()
+            .flat_map(|meeting| {
+                let MeetingLog {
+                    base,
+                    date,
+                    action_items,
+                    ..
+                } = meeting;
+                action_items
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(i, item)| MeetingAction {
+                        meeting_id: base.id,
+                        meeting_handle: base.handle.clone(),
+                        meeting: base.title.clone(),
+                        date,
+                        index: i + 1,
+                        item,
+                    })
+            })
+            .filter(|action| !(open_only && action.item.done))
+            .filter(|action| match owner {
+                Some(owner) => action
+                    .item
+                    .owner
+                    .as_deref()
+                    .is_some_and(|o| o.eq_ignore_ascii_case(owner)),
+                None => true,
+            })
+            .collect();
+        actions.sort_by_key(|action| (action.item.due.is_none(), action.item.due, action.date));
+        Ok(actions)
+    }
+}
+
+pub fn create_meeting(
+    title: &str,
+    details: MeetingDetails,
+    tags: Option<Vec<String>>,
+    body: Option<&str>,
+    template: Option<&str>,
+) -> Result<MeetingLog> {
+    let config = load_config()?;
+    let manager = MeetingManager::new(config);
+    manager.create(title, details, tags, body, template)
+}
+
+pub fn add_meeting_action(partial_id: &str, item: ActionItem) -> Result<ActionItem> {
+    let config = load_config()?;
+    let manager = MeetingManager::new(config);
+    manager.add_action(partial_id, item)
+}
+
+pub fn set_meeting_action_done(partial_id: &str, index: usize, done: bool) -> Result<ActionItem> {
+    let config = load_config()?;
+    let manager = MeetingManager::new(config);
+    manager.set_action_done(partial_id, index, done)
+}
+
+pub fn list_meeting_actions(partial_id: &str) -> Result<Vec<ActionItem>> {
+    let config = load_config()?;
+    let manager = MeetingManager::new(config);
+    Ok(manager.find(partial_id)?.0.action_items)
+}
+
+pub fn update_meeting_status(
+    partial_id: &str,
+    new_status: MeetingStatus,
+    reason: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let config = load_config()?;
+    let manager = MeetingManager::new(config);
+    manager.update_status(partial_id, new_status, reason, force)
+}
+
+pub fn rename_meeting(partial_id: &str, title: &str) -> Result<Renamed<MeetingLog>> {
+    let config = load_config()?;
+    let manager = MeetingManager::new(config);
+    manager.rename(partial_id, title)
+}
+
+pub fn list_meetings(filter: &ListFilter<MeetingStatus>) -> Result<Vec<MeetingLog>> {
+    list_meetings_in(&load_config()?, filter)
+}
+
+/// Like [`list_meetings`], in the repository of `config`.
+pub fn list_meetings_in(
+    config: &Config,
+    filter: &ListFilter<MeetingStatus>,
+) -> Result<Vec<MeetingLog>> {
+    let manager = MeetingManager::new(config.clone());
+    manager.list(filter)
+}
+
+/// Action items across all meetings; see [`MeetingManager::action_items`].
+pub fn list_action_items(
+    open_only: bool,
+    owner: Option<&str>,
+    project: Option<&str>,
+) -> Result<Vec<MeetingAction>> {
+    let config = load_config()?;
+    let manager = MeetingManager::new(config);
+    manager.action_items(open_only, owner, project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::author;
+
+    #[test]
+    fn test_action_items_in_frontmatter() {
+        let mut meeting = MeetingLog::new("Advisor sync".to_string(), HashSet::new(), author());
+        meeting.date = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+        meeting.attendees = vec!["Ada".to_string(), "Charles".to_string()];
+        meeting.action_items.push(ActionItem {
+            text: "Rerun the cavity scan".to_string(),
+            owner: Some("ada".to_string()),
+            due: None,
+            done: false,
+        });
+        let yaml = serde_yaml::to_string(&meeting).unwrap();
+        assert!(yaml.contains("date: 2026-03-04"));
+        assert!(yaml.contains("- Charles"));
+        assert!(yaml.contains("text: Rerun the cavity scan"));
+        assert!(yaml.contains("done: false"));
+        assert!(!yaml.contains("due:"));
+
+        let parsed: MeetingLog = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.action_items, meeting.action_items);
+        assert_eq!(parsed.status, MeetingStatus::Open);
+    }
+
+    #[test]
+    fn test_meeting_without_action_items_parses() {
+        let meeting = MeetingLog::new("Group meeting".to_string(), HashSet::new(), author());
+        let yaml = serde_yaml::to_string(&meeting).unwrap();
+        assert!(!yaml.contains("action_items"));
+        assert!(!yaml.contains("attendees"));
+
+        let parsed: MeetingLog = serde_yaml::from_str(&yaml).unwrap();
+        assert!(parsed.action_items.is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+//! Upgrades of entry frontmatter written by older versions of dxlog.
+//!
+//! Every entry records the `schema_version` it was written with. Entries are
+//! upgraded in memory whenever they are read, and rewritten on disk by
+//! [`migrate_entries`]. A change to the frontmatter format ships with a
+//! [`Migration`] in [`MIGRATIONS`] and a bump of [`SCHEMA_VERSION`].
+
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate, TimeZone};
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+use std::path::PathBuf;
+
+use crate::{
+    backup::{self, Backup},
+    load_config,
+    log_manager::LogManager,
+    research_log::ResearchLog,
+    DatasetManager, ExperimentManager, HypothesisLog, HypothesisManager, HypothesisStatus,
+    IdeaManager, JournalManager, KnowledgeManager, LiteratureManager, MeetingManager,
+};
+
+/// Version of the frontmatter format written by this version of dxlog.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Frontmatter field holding the schema version. Entries without it are version 0.
+const VERSION_FIELD: &str = "schema_version";
+
+/// An upgrade of the frontmatter from one schema version to the next.
+pub(crate) struct Migration {
+    /// Version the migration upgrades from; entries end up at `from + 1`.
+    pub from: u32,
+    pub description: &'static str,
+    /// Rewrites the frontmatter of an entry of the given log type.
+    pub upgrade: fn(kind: &str, frontmatter: &mut Mapping) -> Result<()>,
+}
+
+/// Every migration, in order of the version they upgrade from.
+pub(crate) const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "Replace the `date` of entries with a `created_at` timestamp",
+        upgrade: date_to_created_at,
+    },
+    Migration {
+        from: 1,
+        description: "Write hypothesis statuses in lowercase, like configured ones",
+        upgrade: lowercase_hypothesis_status,
+    },
+];
+
+/// Entries written before timestamps only have a `date`; it becomes local midnight.
+fn date_to_created_at(_kind: &str, frontmatter: &mut Mapping) -> Result<()> {
+    let Some(date) = frontmatter.get("date") else {
+        return Ok(());
+    };
+    if frontmatter.contains_key("created_at") {
+        frontmatter.shift_remove("date");
+        return Ok(());
+    }
+    let date = date.as_str().context("`date` is not a string")?;
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .with_context(|| format!("Invalid date '{}', expected YYYY-MM-DD", date))?;
+    let created_at = Local
+        .from_local_datetime(&day.and_time(Default::default()))
+        .earliest()
+        .with_context(|| format!("Invalid date '{}'", date))?
+        .fixed_offset()
+        .to_rfc3339();
+    rename_field(frontmatter, "date", "created_at", created_at.into());
+    Ok(())
+}
+
+/// Hypothesis statuses were capitalized (`Active`) until they became configurable.
+fn lowercase_hypothesis_status(kind: &str, frontmatter: &mut Mapping) -> Result<()> {
+    if kind != HypothesisLog::KIND {
+        return Ok(());
+    }
+    if let Some(status) = frontmatter.get_mut("status") {
+        let name = status.as_str().context("`status` is not a string")?;
+        let parsed: HypothesisStatus = name.parse()?;
+        *status = parsed.to_string().into();
+    }
+    Ok(())
+}
+
+/// Replaces the field `old` with `new`, keeping its place in the frontmatter.
+fn rename_field(frontmatter: &mut Mapping, old: &str, new: &str, value: Value) {
+    let mut value = Some(value);
+    *frontmatter = std::mem::take(frontmatter)
+        .into_iter()
+        .map(|(key, current)| {
+            if key == *old {
+                (new.into(), value.take().unwrap_or(current))
+            } else {
+                (key, current)
+            }
+        })
+        .collect();
+}
+
+/// The schema version recorded in `frontmatter`.
+fn version(frontmatter: &Mapping) -> u32 {
+    frontmatter
+        .get(VERSION_FIELD)
+        .and_then(Value::as_u64)
+        .map_or(0, |version| version as u32)
+}
+
+/// Whether `frontmatter` was written with an older schema.
+pub(crate) fn is_outdated(frontmatter: &Value) -> bool {
+    frontmatter
+        .as_mapping()
+        .is_some_and(|frontmatter| version(frontmatter) < SCHEMA_VERSION)
+}
+
+/// Brings the frontmatter of an entry of type `kind` up to [`SCHEMA_VERSION`].
+///
+/// Fails for entries written by a newer version of dxlog, which this one
+/// cannot know how to read.
+pub(crate) fn upgrade(kind: &str, frontmatter: &mut Value) -> Result<()> {
+    let Some(mapping) = frontmatter.as_mapping_mut() else {
+        return Ok(());
+    };
+    let written = version(mapping);
+    if written > SCHEMA_VERSION {
+        return Err(anyhow::anyhow!(
+            "Entry was written with schema version {}, but this dxlog only reads up to {}. Please upgrade dxlog.",
+            written,
+            SCHEMA_VERSION
+        ));
+    }
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= written) {
+        (migration.upgrade)(kind, mapping).with_context(|| {
+            format!(
+                "Failed to upgrade from schema version {}: {}",
+                migration.from, migration.description
+            )
+        })?;
+    }
+    if mapping.contains_key(VERSION_FIELD) {
+        mapping.insert(VERSION_FIELD.into(), SCHEMA_VERSION.into());
+    } else {
+        // New entries start with the version, so put it first here too.
+        let mut versioned = Mapping::new();
+        versioned.insert(VERSION_FIELD.into(), SCHEMA_VERSION.into());
+        versioned.extend(std::mem::take(mapping));
+        *mapping = versioned;
+    }
+    Ok(())
+}
+
+/// Outcome of [`migrate_entries`].
+#[derive(Debug, Default, Serialize)]
+pub struct MigrationReport {
+    /// Entries rewritten with the current schema.
+    pub migrated: Vec<PathBuf>,
+    /// Entries that could not be upgraded, with the reason.
+    pub failed: Vec<(PathBuf, String)>,
+    /// Snapshot taken before any entry was rewritten; none if all were up to date.
+    pub backup: Option<Backup>,
+}
+
+fn migrate<T: ResearchLog>(
+    manager: &LogManager<T>,
+    outdated: Vec<PathBuf>,
+    report: &mut MigrationReport,
+) {
+    for path in outdated {
+        match manager.upgrade_entry(&path) {
+            Ok(()) => report.migrated.push(path),
+            Err(e) => report.failed.push((path, format!("{:#}", e))),
+        }
+    }
+}
+
+/// Rewrites every entry written with an older schema in the current one. The
+/// entry directories are backed up first.
+pub fn migrate_entries() -> Result<MigrationReport> {
+    let config = load_config()?;
+    let hypotheses = HypothesisManager::new(config.clone()).manager;
+    let literature = LiteratureManager::new(config.clone()).manager;
+    let knowledge = KnowledgeManager::new(config.clone()).manager;
+    let journals = JournalManager::new(config.clone()).manager;
+    let datasets = DatasetManager::new(config.clone()).manager;
+    let meetings = MeetingManager::new(config.clone()).manager;
+    let ideas = IdeaManager::new(config.clone()).manager;
+    let experiments = ExperimentManager::new(config.clone()).manager;
+
+    let outdated_hypotheses = hypotheses.outdated_entries()?;
+    let outdated_literature = literature.outdated_entries()?;
+    let outdated_knowledge = knowledge.outdated_entries()?;
+    let outdated_journals = journals.outdated_entries()?;
+    let outdated_datasets = datasets.outdated_entries()?;
+    let outdated_meetings = meetings.outdated_entries()?;
+    let outdated_ideas = ideas.outdated_entries()?;
+    let outdated_experiments = experiments.outdated_entries()?;
+    if outdated_hypotheses.is_empty()
+        && outdated_literature.is_empty()
+        && outdated_knowledge.is_empty()
+        && outdated_journals.is_empty()
+        && outdated_datasets.is_empty()
+        && outdated_meetings.is_empty()
+        && outdated_ideas.is_empty()
+        && outdated_experiments.is_empty()
+    {
+        return Ok(MigrationReport::default());
+    }
+
+    let mut report = MigrationReport {
+        backup: Some(backup::create(&config, Some("migrate"))?),
+        ..Default::default()
+    };
+    migrate(&hypotheses, outdated_hypotheses, &mut report);
+    migrate(&literature, outdated_literature, &mut report);
+    migrate(&knowledge, outdated_knowledge, &mut report);
+    migrate(&journals, outdated_journals, &mut report);
+    migrate(&datasets, outdated_datasets, &mut report);
+    migrate(&meetings, outdated_meetings, &mut report);
+    migrate(&ideas, outdated_ideas, &mut report);
+    migrate(&experiments, outdated_experiments, &mut report);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade() {
+        assert!(MIGRATIONS
+            .iter()
+            .enumerate()
+            .all(|(i, migration)| migration.from == i as u32));
+        assert_eq!(MIGRATIONS.len() as u32, SCHEMA_VERSION);
+
+        let mut legacy: Value = serde_yaml::from_str("title: Entry\ndate: 2025-03-10").unwrap();
+        assert!(is_outdated(&legacy));
+        upgrade("knowledge", &mut legacy).unwrap();
+        assert!(!is_outdated(&legacy));
+        assert_eq!(legacy[VERSION_FIELD], Value::from(SCHEMA_VERSION));
+        assert!(legacy.get("date").is_none());
+        assert!(legacy["created_at"]
+            .as_str()
+            .unwrap()
+            .starts_with("2025-03-10T00:00:00"));
+
+        let mut hypothesis: Value =
+            serde_yaml::from_str("schema_version: 1\nstatus: Active").unwrap();
+        upgrade("hypothesis", &mut hypothesis).unwrap();
+        assert_eq!(hypothesis["status"], Value::from("active"));
+        let mut knowledge: Value =
+            serde_yaml::from_str("schema_version: 1\nstatus: Draft").unwrap();
+        upgrade("knowledge", &mut knowledge).unwrap();
+        assert_eq!(knowledge["status"], Value::from("Draft"));
+
+        let mut newer: Value =
+            serde_yaml::from_str(&format!("{}: {}", VERSION_FIELD, SCHEMA_VERSION + 1)).unwrap();
+        assert!(upgrade("knowledge", &mut newer).is_err());
+    }
+}
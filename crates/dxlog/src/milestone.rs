@@ -0,0 +1,234 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    dry_run, load_config, log_manager::LogManager, project::edit_entry, research_log::ResearchLog,
+    utils, Config, DatasetManager, DatasetStatus, ExperimentManager, ExperimentStatus,
+    HypothesisManager, HypothesisStatus, JournalManager, KnowledgeManager, KnowledgeStatus,
+    ListFilter, LiteratureManager, LiteratureStatus, MeetingManager, MeetingStatus,
+};
+
+/// A target date entries can be attached to, kept in `storage.milestones-file`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Milestone {
+    pub id: Uuid,
+    pub title: String,
+    pub due: NaiveDate,
+    pub created_at: DateTime<FixedOffset>,
+}
+
+/// An entry attached to a milestone.
+#[derive(Debug, Serialize)]
+pub struct MilestoneEntry {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: String,
+    pub status: String,
+    pub resolved: bool,
+}
+
+/// Progress towards a milestone, as shown by `dxlog milestone status`.
+#[derive(Debug, Serialize)]
+pub struct MilestoneStatus {
+    #[serde(flatten)]
+    pub milestone: Milestone,
+    /// Days until the due date; negative once it has passed.
+    pub days_remaining: i64,
+    pub entries: Vec<MilestoneEntry>,
+}
+
+impl MilestoneStatus {
+    pub fn resolved(&self) -> usize {
+        self.entries.iter().filter(|e| e.resolved).count()
+    }
+}
+
+fn load(config: &Config) -> Result<Vec<Milestone>> {
+    let path = &config.storage.milestones_file;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = utils::load_entry_content(path)?;
+    let mut milestones: Vec<Milestone> = serde_yaml::from_str::<Option<Vec<Milestone>>>(&content)
+        .with_context(|| format!("Invalid milestones file: {}", path.display()))?
+        .unwrap_or_default();
+    milestones.sort_by(|a, b| a.due.cmp(&b.due).then_with(|| a.title.cmp(&b.title)));
+    Ok(milestones)
+}
+
+/// Finds a milestone by ID prefix or by title, ignoring case.
+fn find(milestones: &[Milestone], query: &str) -> Result<Milestone> {
+    if let Some(milestone) = milestones
+        .iter()
+        .find(|m| m.title.to_lowercase() == query.to_lowercase())
+    {
+        return Ok(milestone.clone());
+    }
+    let mut matches: Vec<&Milestone> = milestones
+        .iter()
+        .filter(|m| m.id.to_string().starts_with(query))
+        .collect();
+    match matches.len() {
+        0 => Err(anyhow::anyhow!(
+            "No milestone found with title or ID starting with '{}'",
+            query
+        )),
+        1 => Ok(matches.remove(0).clone()),
+        _ => Err(anyhow::anyhow!(
+            "Multiple milestones found with ID starting with '{}'. Please provide more characters.",
+            query
+        )),
+    }
+}
+
+/// Adds a milestone due on `due`. Titles must be unique, ignoring case.
+pub fn create_milestone(title: &str, due: NaiveDate) -> Result<Milestone> {
+    let config = load_config()?;
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(anyhow::anyhow!("The title cannot be empty"));
+    }
+    let mut milestones = load(&config)?;
+    if let Some(existing) = milestones
+        .iter()
+        .find(|m| m.title.to_lowercase() == title.to_lowercase())
+    {
+        return Err(anyhow::anyhow!(
+            "A milestone titled '{}' already exists (due {})",
+            existing.title,
+            existing.due
+        ));
+    }
+
+    let milestone = Milestone {
+        id: config.id_version.new_id(),
+        title: title.to_string(),
+        due,
+        created_at: utils::now(),
+    };
+    milestones.push(milestone.clone());
+    dry_run::write(
+        &config.storage.milestones_file,
+        &serde_yaml::to_string(&milestones)?,
+    )?;
+    Ok(milestone)
+}
+
+/// Attaches an entry of any type to a milestone, or detaches it with `None`.
+/// Returns the entry's title and the milestone.
+pub fn attach_to_milestone(
+    partial_id: &str,
+    milestone: Option<&str>,
+) -> Result<(String, Option<Milestone>)> {
+    let config = load_config()?;
+    let milestone = milestone
+        .map(|query| find(&load(&config)?, query))
+        .transpose()?;
+    let id = milestone.as_ref().map(|m| m.id);
+    let title = edit_entry(&config, partial_id, |base| base.milestone = id)?;
+    Ok((title, milestone))
+}
+
+fn attached<T: ResearchLog>(
+    manager: &LogManager<T>,
+    open: &[String],
+    entries: &mut Vec<(Uuid, MilestoneEntry)>,
+) -> Result<()> {
+    for log in manager.list_logs(&ListFilter::default())? {
+        let base = log.base();
+        let Some(milestone) = base.milestone else {
+            continue;
+        };
+        let status = log.status().to_string();
+        entries.push((
+            milestone,
+            MilestoneEntry {
+                id: base.id,
+                handle: base.handle.clone(),
+                kind: T::KIND,
+                title: base.title.clone(),
+                resolved: !open.contains(&status),
+                status,
+            },
+        ));
+    }
+    Ok(())
+}
+
+/// Reports progress on every milestone, soonest due first, or only on the one
+/// matching `query` (a title or ID prefix).
+///
+/// Hypotheses that are active or suspended, literature in progress, knowledge
+/// drafts, including those in review, candidate datasets, open meetings and
+/// experiments that are planned or running count as open; everything else attached counts as resolved.
+pub fn milestone_status(query: Option<&str>) -> Result<Vec<MilestoneStatus>> {
+    let config = load_config()?;
+    let mut milestones = load(&config)?;
+    if let Some(query) = query {
+        milestones = vec![find(&milestones, query)?];
+    }
+
+    let mut entries = Vec::new();
+    attached(
+        &HypothesisManager::new(config.clone()).manager,
+        &[
+            HypothesisStatus::ACTIVE.to_string(),
+            HypothesisStatus::SUSPENDED.to_string(),
+        ],
+        &mut entries,
+    )?;
+    attached(
+        &LiteratureManager::new(config.clone()).manager,
+        &[LiteratureStatus::InProgress.to_string()],
+        &mut entries,
+    )?;
+    attached(
+        &KnowledgeManager::new(config.clone()).manager,
+        &[
+            KnowledgeStatus::Draft.to_string(),
+            KnowledgeStatus::InReview.to_string(),
+        ],
+        &mut entries,
+    )?;
+    attached(
+        &JournalManager::new(config.clone()).manager,
+        &[],
+        &mut entries,
+    )?;
+    attached(
+        &DatasetManager::new(config.clone()).manager,
+        &[DatasetStatus::Candidate.to_string()],
+        &mut entries,
+    )?;
+    attached(
+        &MeetingManager::new(config.clone()).manager,
+        &[MeetingStatus::Open.to_string()],
+        &mut entries,
+    )?;
+    attached(
+        &ExperimentManager::new(config.clone()).manager,
+        &[
+            ExperimentStatus::Planned.to_string(),
+            ExperimentStatus::Running.to_string(),
+        ],
+        &mut entries,
+    )?;
+
+    let today = chrono::Local::now().date_naive();
+    let mut statuses = Vec::new();
+    for milestone in milestones {
+        let (mine, rest) = entries.into_iter().partition(|(id, _)| *id == milestone.id);
+        entries = rest;
+        statuses.push(MilestoneStatus {
+            days_remaining: (milestone.due - today).num_days(),
+            milestone,
+            entries: mine.into_iter().map(|(_, entry)| entry).collect(),
+        });
+    }
+    Ok(statuses)
+}
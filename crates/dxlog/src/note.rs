@@ -0,0 +1,219 @@
+use anyhow::Result;
+
+use crate::{
+    load_config, utils, Config, DatasetManager, ExperimentManager, HypothesisManager, IdeaManager,
+    JournalManager, KnowledgeManager, LiteratureManager, MeetingManager,
+};
+
+pub(crate) const NOTES_HEADING: &str = "## Notes";
+
+/// Appends a timestamped bullet to the `## Notes` section of an entry, creating
+/// the section at the end of the body if it does not exist yet.
+///
+/// Returns the line that was added.
+pub fn add_note(partial_id: &str, text: &str) -> Result<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(anyhow::anyhow!("Note text cannot be empty"));
+    }
+
+    let config = load_config()?;
+    let line = note_line(&config, text);
+    let edit = |body: &str| append_to_section(body, NOTES_HEADING, &line);
+
+    let h_manager = HypothesisManager::new(config.clone());
+    let l_manager = LiteratureManager::new(config.clone());
+    let k_manager = KnowledgeManager::new(config.clone());
+    let j_manager = JournalManager::new(config.clone());
+    let d_manager = DatasetManager::new(config.clone());
+    let m_manager = MeetingManager::new(config.clone());
+    let i_manager = IdeaManager::new(config.clone());
+    let e_manager = ExperimentManager::new(config);
+
+    if h_manager.find(partial_id).is_ok() {
+        h_manager.manager.update_body(partial_id, edit)?;
+    } else if l_manager.find(partial_id).is_ok() {
+        l_manager.manager.update_body(partial_id, edit)?;
+    } else if k_manager.find(partial_id).is_ok() {
+        k_manager.manager.update_body(partial_id, edit)?;
+    } else if j_manager.find(partial_id).is_ok() {
+        j_manager.manager.update_body(partial_id, edit)?;
+    } else if d_manager.find(partial_id).is_ok() {
+        d_manager.manager.update_body(partial_id, edit)?;
+    } else if m_manager.find(partial_id).is_ok() {
+        m_manager.manager.update_body(partial_id, edit)?;
+    } else if i_manager.find(partial_id).is_ok() {
+        i_manager.manager.update_body(partial_id, edit)?;
+    } else if e_manager.find(partial_id).is_ok() {
+        e_manager.manager.update_body(partial_id, edit)?;
+    } else {
+        return Err(anyhow::anyhow!(
+            "No entry found with ID starting with '{}'",
+            partial_id
+        ));
+    }
+    Ok(line)
+}
+
+/// A bullet for the `## Notes` section, stamped with the current date and time.
+pub(crate) fn note_line(config: &Config, text: &str) -> String {
+    let now = utils::now();
+    format!(
+        "- {} {}: {}",
+        now.format(&config.date_format),
+        now.format("%H:%M"),
+        text
+    )
+}
+
+/// Inserts `line` after the last item of the `heading` section, which ends at
+/// the next heading of the same or a higher level. The section is added at the
+/// end of the body if it does not exist.
+pub(crate) fn append_to_section(body: &str, heading: &str, line: &str) -> String {
+    let mut lines: Vec<&str> = body.lines().collect();
+    let Some(start) = lines.iter().position(|l| l.trim_end() == heading) else {
+        let body = body.trim_end();
+        return if body.is_empty() {
+            format!("{}\n\n{}\n", heading, line)
+        } else {
+            format!("{}\n\n{}\n\n{}\n", body, heading, line)
+        };
+    };
+
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.starts_with("# ") || l.starts_with("## "))
+        .map_or(lines.len(), |i| start + 1 + i);
+    let last = lines[start + 1..end]
+        .iter()
+        .rposition(|l| !l.trim().is_empty())
+        .map_or(start, |i| start + 1 + i);
+
+    if last == start {
+        lines.insert(start + 1, "");
+        lines.insert(start + 2, line);
+        if start + 3 < lines.len() && !lines[start + 3].trim().is_empty() {
+            lines.insert(start + 3, "");
+        }
+    } else {
+        lines.insert(last + 1, line);
+    }
+    lines.join("\n") + "\n"
+}
+
+/// The text of the `heading` section, up to the next heading of the same or a
+/// higher level, or `None` if the section is missing or empty. Headings are
+/// compared ignoring case.
+pub(crate) fn section_text(body: &str, heading: &str) -> Option<String> {
+    let lines: Vec<&str> = body.lines().collect();
+    let start = lines
+        .iter()
+        .position(|l| l.trim_end().eq_ignore_ascii_case(heading))?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.starts_with("# ") || l.starts_with("## "))
+        .map_or(lines.len(), |i| start + 1 + i);
+    let text = lines[start + 1..end].join("\n");
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Sets the text of the `heading` section, replacing whatever it held. A
+/// missing section is added before the first `## ` section, or at the end of
+/// the body if there is none.
+#[cfg(feature = "llm")]
+pub(crate) fn replace_section(body: &str, heading: &str, text: &str) -> String {
+    let lines: Vec<&str> = body.lines().collect();
+    let (before, after) = match lines
+        .iter()
+        .position(|l| l.trim_end().eq_ignore_ascii_case(heading))
+    {
+        Some(start) => {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|l| l.starts_with("# ") || l.starts_with("## "))
+                .map_or(lines.len(), |i| start + 1 + i);
+            (&lines[..start], &lines[end..])
+        }
+        None => {
+            let first = lines
+                .iter()
+                .position(|l| l.starts_with("## "))
+                .unwrap_or(lines.len());
+            (&lines[..first], &lines[first..])
+        }
+    };
+
+    let mut result = String::new();
+    let before = before.join("\n");
+    if !before.trim().is_empty() {
+        result.push_str(before.trim_end());
+        result.push_str("\n\n");
+    }
+    result.push_str(&format!("{}\n\n{}\n", heading, text.trim()));
+    let after = after.join("\n");
+    if !after.trim().is_empty() {
+        result.push('\n');
+        result.push_str(after.trim());
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_to_section() {
+        let insert_note = |body, line| append_to_section(body, NOTES_HEADING, line);
+        assert_eq!(
+            insert_note("# Title\n\nText", "- one"),
+            "# Title\n\nText\n\n## Notes\n\n- one\n"
+        );
+        assert_eq!(
+            insert_note(
+                "# Title\n\n## Notes\n\n- one\n\n## Results\n\nDone",
+                "- two"
+            ),
+            "# Title\n\n## Notes\n\n- one\n- two\n\n## Results\n\nDone\n"
+        );
+        assert_eq!(
+            insert_note("## Notes\n## Results", "- one"),
+            "## Notes\n\n- one\n\n## Results\n"
+        );
+    }
+
+    #[test]
+    fn test_section_text() {
+        let body = "# Title\n\n## Takeaways\n\n- one\n### Detail\n- two\n\n## Notes\n\n- later\n";
+        assert_eq!(
+            section_text(body, "## takeaways").as_deref(),
+            Some("- one\n### Detail\n- two")
+        );
+        assert_eq!(section_text(body, "## Results"), None);
+        assert_eq!(
+            section_text("## Takeaways\n\n## Notes", "## Takeaways"),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "llm")]
+    fn test_replace_section() {
+        let set_summary = |body| replace_section(body, "## Summary", "New.");
+        assert_eq!(
+            set_summary("# Title\n\nIntro\n\n## Notes\n\n- one\n"),
+            "# Title\n\nIntro\n\n## Summary\n\nNew.\n\n## Notes\n\n- one\n"
+        );
+        assert_eq!(
+            set_summary("# Title\n\n## summary\n\nOld.\n### Detail\n\n## Notes\n- one"),
+            "# Title\n\n## Summary\n\nNew.\n\n## Notes\n- one\n"
+        );
+        assert_eq!(
+            set_summary("# Title\n\nText"),
+            "# Title\n\nText\n\n## Summary\n\nNew.\n"
+        );
+        assert_eq!(set_summary(""), "## Summary\n\nNew.\n");
+    }
+}
@@ -0,0 +1,92 @@
+//! Chat notifications of status changes, posted to the webhooks in `[notifications]`.
+//!
+//! A failed post never undoes or fails the status change; the error is kept
+//! for the caller to report with [`take_notification_failures`].
+
+use std::cell::RefCell;
+
+use dxlog_tools::post_webhook;
+
+use crate::{dry_run::is_dry_run, research_log::LogEntry, utils, Config};
+
+thread_local! {
+    /// Kept per thread, so each request of `dxlog serve` or `dxlog mcp` only
+    /// hears of its own failures.
+    static FAILURES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the notifications this thread could not post so far, and forgets them.
+pub fn take_notification_failures() -> Vec<String> {
+    FAILURES.with(|failures| failures.take())
+}
+
+/// Posts the change of `log` from the status `from` to every webhook that wants it.
+pub(crate) fn status_changed<E: LogEntry>(config: &Config, log: &E, from: &str) {
+    let to = log.status_name();
+    let webhooks: Vec<_> = config
+        .notifications
+        .webhooks
+        .iter()
+        .filter(|webhook| webhook.wants(log.kind_name(), &to))
+        .collect();
+    if webhooks.is_empty() || from == to || is_dry_run() {
+        return;
+    }
+
+    let base = log.base_log();
+    let who = utils::get_author(config)
+        .map(|author| author.name)
+        .unwrap_or_else(|_| base.created_by.name.clone());
+    let reason = base
+        .history
+        .last()
+        .map(|change| change.reason.as_str())
+        .filter(|reason| !reason.is_empty());
+
+    for webhook in webhooks {
+        let mut text = format!(
+            "{} moved {} {} from {} to {}",
+            who,
+            log.kind_name(),
+            webhook.service.bold(&base.title),
+            from,
+            webhook.service.bold(&to)
+        );
+        if let Some(reason) = reason {
+            text.push_str(&format!(": {}", reason));
+        }
+        if let Err(e) = post_webhook(&webhook.url, webhook.service, &text) {
+            FAILURES.with(|failures| failures.borrow_mut().push(format!("{:#}", e)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::research_log::ResearchLog;
+    use crate::{config::Webhook, test_support::author, HypothesisLog, HypothesisStatus};
+    use dxlog_tools::ChatService;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_failures_stay_with_their_thread() {
+        let mut config = Config::default();
+        // Nothing listens on the discard port, so the post fails
+        config.notifications.webhooks.push(Webhook {
+            url: "http://127.0.0.1:9/".to_string(),
+            service: ChatService::Slack,
+            events: Vec::new(),
+        });
+        let mut log = HypothesisLog::new("Noise scaling".into(), HashSet::new(), author());
+        log.status = HypothesisStatus::PROVEN;
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                status_changed(&config, &log, "active");
+                assert_eq!(take_notification_failures().len(), 1);
+            });
+        });
+        assert!(take_notification_failures().is_empty());
+    }
+}
@@ -0,0 +1,374 @@
+//! Journal of the last changes made to entries (creating them, changing their
+//! status, deleting them and adding references), kept under `.dxlog` so that
+//! `dxlog undo` can revert the latest one.
+//!
+//! An operation is recorded by running it inside [`record`]: every file
+//! [`dry_run`] writes, moves or removes meanwhile is noted along with what it
+//! held before.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::{debug, warn};
+
+use crate::{dry_run, load_config, utils, Config};
+
+/// File, relative to the repository root, the journal is kept in.
+pub const OPERATIONS_FILE: &str = ".dxlog/operations.json";
+
+/// Number of operations kept; older ones can no longer be undone.
+const MAX_OPERATIONS: usize = 20;
+
+/// A recorded change to the repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    /// What was done, e.g. `create hypothesis 'Noise scaling'`.
+    pub description: String,
+    pub performed_at: String,
+    /// The file changes making up the operation, in the order they were made.
+    pub changes: Vec<FileChange>,
+}
+
+/// A file written, moved or removed by an [`Operation`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum FileChange {
+    /// `path` was written; `previous` is what it held before, or `None` if it
+    /// did not exist.
+    Write {
+        path: PathBuf,
+        previous: Option<String>,
+        sha256: String,
+    },
+    Move {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    Remove {
+        path: PathBuf,
+        previous: String,
+    },
+}
+
+/// The operation being recorded: the repository it works on and the changes
+/// made so far.
+struct Recording {
+    root: PathBuf,
+    changes: Vec<FileChange>,
+}
+
+thread_local! {
+    /// Kept per thread, so operations running at the same time on different
+    /// threads are each recorded on their own.
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+fn with_recording<R>(f: impl FnOnce(&mut Option<Recording>) -> R) -> R {
+    RECORDING.with(|recording| f(&mut recording.borrow_mut()))
+}
+
+fn sha256(contents: impl AsRef<[u8]>) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+/// Runs `operation`, recording the files it changes in the journal of the
+/// repository of `config` as `description`.
+///
+/// Operations run inside another on the same thread are recorded as part of
+/// it. Nothing is recorded in dry-run mode, or for a config without a
+/// repository root.
+pub(crate) fn record<R>(
+    config: &Config,
+    description: String,
+    operation: impl FnOnce() -> Result<R>,
+) -> Result<R> {
+    if dry_run::is_dry_run() || config.root.as_os_str().is_empty() {
+        return operation();
+    }
+    let nested = with_recording(|recording| {
+        if recording.is_some() {
+            return true;
+        }
+        *recording = Some(Recording {
+            root: config.root.clone(),
+            changes: Vec::new(),
+        });
+        false
+    });
+    if nested {
+        return operation();
+    }
+
+    // Changes made before a failure are recorded too, so they can be undone.
+    let result = operation();
+    let changes = with_recording(|recording| recording.take())
+        .map(|recording| recording.changes)
+        .unwrap_or_default();
+    if !changes.is_empty() {
+        let operation = Operation {
+            description,
+            performed_at: chrono::Local::now().to_rfc3339(),
+            changes,
+        };
+        if let Err(err) = push(config, operation) {
+            warn!("Could not record the operation for `dxlog undo`: {:#}", err);
+        }
+    }
+    result
+}
+
+/// Notes a change about to be made, if an operation is being recorded and
+/// `path` belongs to its repository.
+fn note(path: &Path, change: impl FnOnce() -> Option<FileChange>) {
+    with_recording(|recording| {
+        let Some(recording) = recording.as_mut().filter(|r| path.starts_with(&r.root)) else {
+            return;
+        };
+        if let Some(change) = change() {
+            recording.changes.push(change);
+        }
+    });
+}
+
+pub(crate) fn note_write(path: &Path, contents: &str) {
+    note(path, || {
+        Some(FileChange::Write {
+            path: path.to_path_buf(),
+            previous: fs::read_to_string(path).ok(),
+            sha256: sha256(contents),
+        })
+    });
+}
+
+/// Noted like a write; files that cannot be read are not noted.
+pub(crate) fn note_copy(from: &Path, to: &Path) {
+    note(to, || {
+        Some(FileChange::Write {
+            path: to.to_path_buf(),
+            previous: fs::read_to_string(to).ok(),
+            sha256: sha256(fs::read(from).ok()?),
+        })
+    });
+}
+
+pub(crate) fn note_move(from: &Path, to: &Path) {
+    note(from, || {
+        Some(FileChange::Move {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        })
+    });
+}
+
+/// Files that cannot be read as text are not noted, and stay removed on undo.
+pub(crate) fn note_remove(path: &Path) {
+    note(path, || {
+        Some(FileChange::Remove {
+            path: path.to_path_buf(),
+            previous: fs::read_to_string(path).ok()?,
+        })
+    });
+}
+
+fn load(config: &Config) -> Result<Vec<Operation>> {
+    let path = config.root.join(OPERATIONS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid journal: {}", path.display()))
+}
+
+/// Writes the journal. It is bookkeeping rather than a change to entries, so
+/// it bypasses [`dry_run`].
+fn save(config: &Config, operations: &[Operation]) -> Result<()> {
+    let path = config.root.join(OPERATIONS_FILE);
+    if let Some(parent) = path.parent() {
+        utils::ensure_directory(parent)?;
+    }
+    debug!(path = %path.display(), operations = operations.len(), "Writing journal");
+    fs::write(&path, serde_json::to_string_pretty(operations)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn push(config: &Config, operation: Operation) -> Result<()> {
+    let mut operations = load(config)?;
+    operations.push(operation);
+    let excess = operations.len().saturating_sub(MAX_OPERATIONS);
+    operations.drain(..excess);
+    save(config, &operations)
+}
+
+/// State a file is left in by an operation.
+enum Expected {
+    Absent,
+    Present,
+    Content(String),
+}
+
+/// Fails if a file `operation` changed has been changed again since, as
+/// undoing it would then lose the later change.
+fn check_unchanged(operation: &Operation) -> Result<()> {
+    let mut expected: HashMap<&Path, Expected> = HashMap::new();
+    for change in &operation.changes {
+        match change {
+            FileChange::Write { path, sha256, .. } => {
+                expected.insert(path, Expected::Content(sha256.clone()));
+            }
+            FileChange::Move { from, to } => {
+                let state = expected.remove(from.as_path()).unwrap_or(Expected::Present);
+                expected.insert(from, Expected::Absent);
+                expected.insert(to, state);
+            }
+            FileChange::Remove { path, .. } => {
+                expected.insert(path, Expected::Absent);
+            }
+        }
+    }
+
+    for (path, state) in expected {
+        let unchanged = match state {
+            Expected::Absent => !path.exists(),
+            Expected::Present => path.exists(),
+            Expected::Content(hash) => fs::read(path).is_ok_and(|c| sha256(c) == hash),
+        };
+        if !unchanged {
+            return Err(anyhow::anyhow!(
+                "Cannot undo \"{}\": {} has changed since. Revert it by hand, or restore a backup with `dxlog backup restore`",
+                operation.description,
+                path.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn revert(change: &FileChange) -> Result<()> {
+    match change {
+        FileChange::Write {
+            path,
+            previous: Some(previous),
+            ..
+        } => dry_run::write(path, previous),
+        FileChange::Write {
+            path,
+            previous: None,
+            ..
+        } => dry_run::remove_file(path),
+        FileChange::Move { from, to } => dry_run::rename(to, from),
+        FileChange::Remove { path, previous } => dry_run::write(path, previous),
+    }
+}
+
+pub(crate) fn undo_last(config: &Config) -> Result<Operation> {
+    let mut operations = load(config)?;
+    let operation = operations
+        .pop()
+        .ok_or_else(|| anyhow::anyhow!("Nothing to undo"))?;
+    check_unchanged(&operation)?;
+    for change in operation.changes.iter().rev() {
+        revert(change)?;
+    }
+    if !dry_run::is_dry_run() {
+        save(config, &operations)?;
+    }
+    Ok(operation)
+}
+
+/// The operation [`undo_last_operation`] would revert, if any.
+pub fn last_operation() -> Result<Option<Operation>> {
+    let config = load_config()?;
+    Ok(load(&config)?.pop())
+}
+
+/// Reverts the last recorded operation, restoring the files it changed, and
+/// removes it from the journal.
+///
+/// Fails without changing anything if one of those files has been changed
+/// again since.
+pub fn undo_last_operation() -> Result<Operation> {
+    let config = load_config()?;
+    undo_last(&config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        log_manager::LogManager,
+        md_frontmatter::update_markdown_frontmatter,
+        research_log::ResearchLog,
+        test_support::{author, TempRepo},
+        HypothesisLog, HypothesisStatus,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_undo_reverts_the_last_operation() {
+        let repo = TempRepo::new();
+        let mut config = repo.config();
+        config.storage.active_dir = config.root.clone();
+        config.storage.knowledge_base_dir = config.root.join("kb");
+        let manager = LogManager::<HypothesisLog>::new(config.clone(), vec![config.root.clone()]);
+        let log = HypothesisLog::new("Noise scaling".into(), HashSet::new(), author());
+        let content = update_markdown_frontmatter(&log, "# Body").unwrap();
+        let created = manager.save_log(&log, &content, false).unwrap();
+        let id = log.base.id.to_string();
+        manager
+            .change_status(&id, HypothesisStatus::PROVEN, None, false)
+            .unwrap();
+        assert!(!created.exists());
+
+        let undone = undo_last(&config).unwrap();
+        assert_eq!(
+            undone.description,
+            "change hypothesis 'Noise scaling' from active to proven"
+        );
+        assert_eq!(fs::read_to_string(&created).unwrap(), content);
+
+        fs::write(&created, "edited").unwrap();
+        assert!(undo_last(&config).is_err());
+        fs::write(&created, &content).unwrap();
+        undo_last(&config).unwrap();
+        assert!(!created.exists());
+        assert!(undo_last(&config).is_err());
+    }
+
+    #[test]
+    fn test_concurrent_operations_are_recorded_apart() {
+        let barrier = std::sync::Barrier::new(2);
+        let repos = [TempRepo::new(), TempRepo::new()];
+        std::thread::scope(|scope| {
+            for repo in &repos {
+                let barrier = &barrier;
+                scope.spawn(move || {
+                    let config = repo.config();
+                    let root = repo.root();
+                    record(&config, "write both".to_string(), || {
+                        dry_run::write(&root.join("a.md"), "a")?;
+                        // Both operations are being recorded at this point
+                        barrier.wait();
+                        dry_run::write(&root.join("b.md"), "b")
+                    })
+                    .unwrap();
+                });
+            }
+        });
+
+        for repo in &repos {
+            let config = repo.config();
+            let operations = load(&config).unwrap();
+            assert_eq!(operations.len(), 1);
+            assert_eq!(operations[0].changes.len(), 2);
+            undo_last(&config).unwrap();
+            assert!(!repo.root().join("a.md").exists() && !repo.root().join("b.md").exists());
+        }
+    }
+}
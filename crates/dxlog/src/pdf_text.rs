@@ -0,0 +1,226 @@
+//! Text of the PDFs attached to literature entries, extracted into a `.txt`
+//! file next to each PDF so that it can be searched and quoted.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::{
+    fs, panic,
+    path::{Path, PathBuf},
+};
+use tracing::{debug, warn};
+
+use crate::{
+    attachment_dir, config::Config, dry_run, load_config, utils::BaseLog, LiteratureManager,
+};
+
+/// Separates the pages in an extracted text file, as `pdftotext` does.
+pub const PAGE_BREAK: char = '\x0c';
+
+/// Characters shown on either side of a quoted phrase.
+const CONTEXT_CHARS: usize = 150;
+
+/// A passage of an attached PDF containing the phrase given to
+/// [`quote_literature`].
+#[derive(Debug, Serialize, Clone)]
+pub struct Passage {
+    /// Name of the attached PDF.
+    pub file: String,
+    /// Page the passage is on, from 1.
+    pub page: usize,
+    /// The phrase with the text around it, on one line.
+    pub text: String,
+}
+
+/// Outcome of [`quote_literature`].
+#[derive(Debug, Serialize)]
+pub struct Quotes {
+    pub title: String,
+    pub passages: Vec<Passage>,
+}
+
+/// Whether an attachment is a PDF, going by its name.
+pub(crate) fn is_pdf(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"))
+}
+
+/// File the text of `pdf` is extracted to: `paper.pdf.txt` for `paper.pdf`.
+pub fn text_path(pdf: &Path) -> PathBuf {
+    let mut name = pdf.as_os_str().to_os_string();
+    name.push(".txt");
+    PathBuf::from(name)
+}
+
+/// The text of each page of a PDF.
+fn extract_pages(pdf: &Path) -> Result<Vec<String>> {
+    let bytes = fs::read(pdf).with_context(|| format!("Failed to read {}", pdf.display()))?;
+    // pdf-extract panics on some malformed files rather than returning an error
+    panic::catch_unwind(|| pdf_extract::extract_text_from_mem_by_pages(&bytes))
+        .map_err(|_| anyhow::anyhow!("Failed to extract the text of {}", pdf.display()))?
+        .with_context(|| format!("Failed to extract the text of {}", pdf.display()))
+}
+
+/// Extracts the text of `pdf` into `path`, pages separated by [`PAGE_BREAK`],
+/// and returns its pages.
+pub(crate) fn extract_pdf_text(pdf: &Path, path: &Path) -> Result<Vec<String>> {
+    let pages = extract_pages(pdf)?;
+    debug!(path = %path.display(), pages = pages.len(), "Writing extracted text");
+    dry_run::write(path, &pages.join(&PAGE_BREAK.to_string()))?;
+    Ok(pages)
+}
+
+/// The extracted text of the PDFs attached to an entry, for full-text search.
+/// PDFs whose text has not been extracted are left out.
+pub(crate) fn attachment_text(config: &Config, base: &BaseLog) -> String {
+    let dir = attachment_dir(config, base);
+    base.attachments
+        .iter()
+        .filter(|attachment| is_pdf(&attachment.name))
+        .filter_map(|attachment| fs::read_to_string(text_path(&dir.join(&attachment.name))).ok())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Finds the passages containing `phrase`, ignoring case and line breaks, in
+/// the PDFs attached to a literature entry, with the page each is on.
+///
+/// PDFs attached before their text was extracted on attaching are extracted
+/// first.
+pub fn quote_literature(partial_id: &str, phrase: &str) -> Result<Quotes> {
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    if phrase.is_empty() {
+        return Err(anyhow::anyhow!("The phrase to quote is empty"));
+    }
+    let config = load_config()?;
+    let (literature, _) = LiteratureManager::new(config.clone()).find(partial_id)?;
+    let base = &literature.base;
+    let dir = attachment_dir(&config, base);
+
+    let pdfs: Vec<&str> = base
+        .attachments
+        .iter()
+        .map(|attachment| attachment.name.as_str())
+        .filter(|name| is_pdf(name))
+        .collect();
+    if pdfs.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Literature \"{}\" has no attached PDF; attach one with `dxlog attach`",
+            base.title
+        ));
+    }
+
+    let mut passages = Vec::new();
+    for name in pdfs {
+        let pdf = dir.join(name);
+        let path = text_path(&pdf);
+        let pages = match fs::read_to_string(&path) {
+            Ok(text) => text.split(PAGE_BREAK).map(str::to_string).collect(),
+            Err(_) => match extract_pdf_text(&pdf, &path) {
+                Ok(pages) => pages,
+                Err(err) => {
+                    warn!("{:#}; skipping it", err);
+                    continue;
+                }
+            },
+        };
+        for (page, text) in find_passages(&pages, &phrase) {
+            passages.push(Passage {
+                file: name.to_string(),
+                page,
+                text,
+            });
+        }
+    }
+    Ok(Quotes {
+        title: base.title.clone(),
+        passages,
+    })
+}
+
+/// The passages of `pages` containing `phrase`, with their page numbers from 1.
+/// Runs of whitespace count as one space, and case is ignored.
+fn find_passages(pages: &[String], phrase: &str) -> Vec<(usize, String)> {
+    let phrase: Vec<char> = phrase.chars().flat_map(char::to_lowercase).collect();
+    let mut passages = Vec::new();
+    for (index, page) in pages.iter().enumerate() {
+        let chars: Vec<char> = page
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .collect();
+        let lower: Vec<char> = chars
+            .iter()
+            .map(|c| c.to_lowercase().next().unwrap_or(*c))
+            .collect();
+
+        let mut start = 0;
+        while start + phrase.len() <= lower.len() {
+            if lower[start..start + phrase.len()] != phrase[..] {
+                start += 1;
+                continue;
+            }
+            let end = start + phrase.len();
+            passages.push((index + 1, excerpt(&chars, start, end)));
+            start = end;
+        }
+    }
+    passages
+}
+
+/// The text from `start` to `end` with up to [`CONTEXT_CHARS`] around it,
+/// cut at word boundaries.
+fn excerpt(chars: &[char], start: usize, end: usize) -> String {
+    let mut from = start.saturating_sub(CONTEXT_CHARS);
+    if from > 0 {
+        while from < start && chars[from - 1] != ' ' {
+            from += 1;
+        }
+    }
+    let mut to = (end + CONTEXT_CHARS).min(chars.len());
+    if to < chars.len() {
+        while to > end && chars[to] != ' ' {
+            to -= 1;
+        }
+    }
+    let text: String = chars[from..to].iter().collect();
+    format!(
+        "{}{}{}",
+        if from > 0 { "…" } else { "" },
+        text.trim(),
+        if to < chars.len() { "…" } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_passages() {
+        let filler = "word ".repeat(40);
+        let pages = vec![
+            "Introduction\nnothing here".to_string(),
+            format!(
+                "{}Catastrophic\n  forgetting is mitigated by replay. {}",
+                filler, filler
+            ),
+            "and catastrophic forgetting again".to_string(),
+        ];
+        let passages = find_passages(&pages, "catastrophic forgetting");
+        assert_eq!(passages.len(), 2);
+
+        let (page, text) = &passages[0];
+        assert_eq!(*page, 2);
+        assert!(text.starts_with("…word "));
+        assert!(text.contains("Catastrophic forgetting is mitigated by replay."));
+        assert!(text.ends_with(" word…"));
+
+        assert_eq!(
+            passages[1],
+            (3, "and catastrophic forgetting again".to_string())
+        );
+        assert!(find_passages(&pages, "forgotten").is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::research_log::LogStatus;
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    load_config, log_manager::LogManager, research_log::ResearchLog, utils::BaseLog, Config,
+    DatasetManager, ExperimentManager, HypothesisManager, IdeaManager, JournalManager,
+    KnowledgeManager, ListFilter, LiteratureManager, MeetingManager,
+};
+
+/// Entries of one log type in a project, or in the whole repository.
+#[derive(Debug, Serialize)]
+pub struct ProjectCounts {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub total: usize,
+    /// Number of entries per status, in the order the statuses are declared.
+    pub statuses: Vec<(String, usize)>,
+}
+
+/// Summary of a project from `[projects]`, as shown by `dxlog project status`.
+#[derive(Debug, Serialize)]
+pub struct ProjectStatus {
+    pub name: String,
+    pub description: String,
+    pub counts: Vec<ProjectCounts>,
+}
+
+impl ProjectStatus {
+    pub fn total(&self) -> usize {
+        self.counts.iter().map(|c| c.total).sum()
+    }
+}
+
+/// Fails unless `name` is registered under `[projects]`.
+pub(crate) fn ensure_registered(config: &Config, name: &str) -> Result<()> {
+    if config.projects.contains_key(name) {
+        return Ok(());
+    }
+    let known: Vec<&str> = config.projects.keys().map(String::as_str).collect();
+    Err(anyhow::anyhow!(
+        "Unknown project '{}'. {}",
+        name,
+        if known.is_empty() {
+            "Add it under [projects] in dxlog.toml first".to_string()
+        } else {
+            format!("Known projects: {}", known.join(", "))
+        }
+    ))
+}
+
+fn counts<T: ResearchLog>(manager: &LogManager<T>, project: Option<&str>) -> Result<ProjectCounts> {
+    let filter = ListFilter {
+        project: project.map(str::to_string),
+        ..Default::default()
+    };
+    let logs = manager.list_logs(&filter)?;
+    let statuses = T::Status::all(&manager.config)
+        .iter()
+        .map(|status| {
+            let status = status.to_string();
+            let count = logs
+                .iter()
+                .filter(|log| log.status().to_string() == status)
+                .count();
+            (status, count)
+        })
+        .collect();
+    Ok(ProjectCounts {
+        kind: T::KIND,
+        total: logs.len(),
+        statuses,
+    })
+}
+
+fn status_of(config: &Config, name: &str) -> Result<ProjectStatus> {
+    Ok(ProjectStatus {
+        name: name.to_string(),
+        description: config.projects[name].description.clone(),
+        counts: vec![
+            counts(&HypothesisManager::new(config.clone()).manager, Some(name))?,
+            counts(&LiteratureManager::new(config.clone()).manager, Some(name))?,
+            counts(&KnowledgeManager::new(config.clone()).manager, Some(name))?,
+            counts(&JournalManager::new(config.clone()).manager, Some(name))?,
+            counts(&DatasetManager::new(config.clone()).manager, Some(name))?,
+            counts(&MeetingManager::new(config.clone()).manager, Some(name))?,
+            counts(&ExperimentManager::new(config.clone()).manager, Some(name))?,
+        ],
+    })
+}
+
+/// Counts the entries of each type and status in the project `name`.
+pub fn project_status(name: &str) -> Result<ProjectStatus> {
+    let config = load_config()?;
+    ensure_registered(&config, name)?;
+    status_of(&config, name)
+}
+
+/// Summarizes every project in `[projects]`, by name.
+pub fn list_projects() -> Result<Vec<ProjectStatus>> {
+    let config = load_config()?;
+    config
+        .projects
+        .keys()
+        .map(|name| status_of(&config, name))
+        .collect()
+}
+
+/// Counts the entries of each type and status in the repository, as shown
+/// by `dxlog stats`.
+pub fn entry_counts() -> Result<Vec<ProjectCounts>> {
+    entry_counts_in(&load_config()?)
+}
+
+/// Like [`entry_counts`], in the repository of `config`.
+pub fn entry_counts_in(config: &Config) -> Result<Vec<ProjectCounts>> {
+    Ok(vec![
+        counts(&HypothesisManager::new(config.clone()).manager, None)?,
+        counts(&LiteratureManager::new(config.clone()).manager, None)?,
+        counts(&KnowledgeManager::new(config.clone()).manager, None)?,
+        counts(&JournalManager::new(config.clone()).manager, None)?,
+        counts(&DatasetManager::new(config.clone()).manager, None)?,
+        counts(&MeetingManager::new(config.clone()).manager, None)?,
+        counts(&IdeaManager::new(config.clone()).manager, None)?,
+        counts(&ExperimentManager::new(config.clone()).manager, None)?,
+    ])
+}
+
+/// Puts an entry of any type into `project`, or takes it out of its project
+/// with `None`. Returns the entry's title.
+pub fn assign_project(partial_id: &str, project: Option<&str>) -> Result<String> {
+    let config = load_config()?;
+    if let Some(project) = project {
+        ensure_registered(&config, project)?;
+    }
+    edit_entry(&config, partial_id, |base| {
+        base.project = project.map(str::to_string)
+    })
+}
+
+/// Applies `edit` to the frontmatter of an entry of any type and saves it.
+/// Returns the entry's title.
+pub(crate) fn edit_entry(
+    config: &Config,
+    partial_id: &str,
+    edit: impl FnOnce(&mut BaseLog),
+) -> Result<String> {
+    let h_manager = HypothesisManager::new(config.clone());
+    let l_manager = LiteratureManager::new(config.clone());
+    let k_manager = KnowledgeManager::new(config.clone());
+    let j_manager = JournalManager::new(config.clone());
+    let d_manager = DatasetManager::new(config.clone());
+    let m_manager = MeetingManager::new(config.clone());
+    let e_manager = ExperimentManager::new(config.clone());
+
+    if let Ok((mut log, path)) = h_manager.find(partial_id) {
+        edit(&mut log.base);
+        h_manager.manager.update_log(&mut log, &path)?;
+        Ok(log.base.title)
+    } else if let Ok((mut log, path)) = l_manager.find(partial_id) {
+        edit(&mut log.base);
+        l_manager.manager.update_log(&mut log, &path)?;
+        Ok(log.base.title)
+    } else if let Ok((mut log, path)) = k_manager.find(partial_id) {
+        edit(&mut log.base);
+        k_manager.manager.update_log(&mut log, &path)?;
+        Ok(log.base.title)
+    } else if let Ok((mut log, path)) = j_manager.find(partial_id) {
+        edit(&mut log.base);
+        j_manager.manager.update_log(&mut log, &path)?;
+        Ok(log.base.title)
+    } else if let Ok((mut log, path)) = d_manager.find(partial_id) {
+        edit(&mut log.base);
+        d_manager.manager.update_log(&mut log, &path)?;
+        Ok(log.base.title)
+    } else if let Ok((mut log, path)) = m_manager.find(partial_id) {
+        edit(&mut log.base);
+        m_manager.manager.update_log(&mut log, &path)?;
+        Ok(log.base.title)
+    } else if let Ok((mut log, path)) = e_manager.find(partial_id) {
+        edit(&mut log.base);
+        e_manager.manager.update_log(&mut log, &path)?;
+        Ok(log.base.title)
+    } else {
+        Err(anyhow::anyhow!(
+            "No entry found with ID starting with '{}'",
+            partial_id
+        ))
+    }
+}
@@ -0,0 +1,106 @@
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::{
+    load_config, log_manager::LogManager, research_log::ResearchLog, utils, Config, DatasetManager,
+    ExperimentManager, HypothesisManager, IdeaManager, JournalManager, KnowledgeManager,
+    ListFilter, LiteratureManager, MeetingManager,
+};
+
+/// An entry with its latest activity, as listed by [`recent_entries`].
+#[derive(Debug, Serialize, Clone)]
+pub struct RecentEntry {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: String,
+    pub status: String,
+    /// `created` or `updated`.
+    pub action: &'static str,
+    pub at: DateTime<FixedOffset>,
+    pub path: PathBuf,
+}
+
+/// Lists the `limit` most recently created or changed entries of all types,
+/// most recent first, optionally only those active since `since`.
+///
+/// Changes are taken from `updated_at`. Entries written before it existed fall
+/// back to the last commit touching their file, if that is newer than their creation.
+pub fn recent_entries(limit: usize, since: Option<NaiveDate>) -> Result<Vec<RecentEntry>> {
+    let config = load_config()?;
+    let mut entries = Vec::new();
+    collect(
+        &HypothesisManager::new(config.clone()).manager,
+        &mut entries,
+    )?;
+    collect(
+        &LiteratureManager::new(config.clone()).manager,
+        &mut entries,
+    )?;
+    collect(&KnowledgeManager::new(config.clone()).manager, &mut entries)?;
+    collect(&JournalManager::new(config.clone()).manager, &mut entries)?;
+    collect(&DatasetManager::new(config.clone()).manager, &mut entries)?;
+    collect(&MeetingManager::new(config.clone()).manager, &mut entries)?;
+    collect(&IdeaManager::new(config.clone()).manager, &mut entries)?;
+    collect(
+        &ExperimentManager::new(config.clone()).manager,
+        &mut entries,
+    )?;
+
+    apply_commit_times(&config, &mut entries);
+
+    entries.retain(|entry| since.is_none_or(|since| entry.at.date_naive() >= since));
+    entries.sort_by(|a, b| b.at.cmp(&a.at).then_with(|| a.title.cmp(&b.title)));
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+fn collect<T: ResearchLog>(manager: &LogManager<T>, entries: &mut Vec<RecentEntry>) -> Result<()> {
+    for (log, path) in manager.list_log_files(&ListFilter::default())? {
+        let base = log.base();
+        let (action, at) = match base.updated_at {
+            Some(updated_at) if updated_at > base.created_at => ("updated", updated_at),
+            _ => ("created", base.created_at),
+        };
+        entries.push(RecentEntry {
+            id: base.id,
+            handle: base.handle.clone(),
+            kind: T::KIND,
+            title: base.title.clone(),
+            status: log.status().to_string(),
+            action,
+            at,
+            path,
+        });
+    }
+    Ok(())
+}
+
+/// Uses git history for entries that have never recorded an update.
+fn apply_commit_times(config: &Config, entries: &mut [RecentEntry]) {
+    let unchanged: Vec<PathBuf> = entries
+        .iter()
+        .filter(|entry| entry.action == "created")
+        .map(|entry| entry.path.clone())
+        .collect();
+    if unchanged.is_empty() {
+        return;
+    }
+    // History is a bonus here; a repository without commits is not an error.
+    let commit_times: HashMap<PathBuf, DateTime<FixedOffset>> =
+        utils::last_commit_times(&config.root, &unchanged).unwrap_or_default();
+    for entry in entries.iter_mut().filter(|entry| entry.action == "created") {
+        if let Some(at) = commit_times.get(&entry.path) {
+            if *at > entry.at {
+                entry.action = "updated";
+                entry.at = *at;
+            }
+        }
+    }
+}
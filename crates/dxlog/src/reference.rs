@@ -1,153 +1,908 @@
 // crates/dxlog/src/reference.rs
 use crate::{
-    load_config, research_log::ResearchLog, HypothesisManager, HypothesisStatus, KnowledgeManager,
-    KnowledgeStatus, LiteratureManager, LiteratureStatus,
+    dry_run, load_config,
+    log_manager::LogManager,
+    lookup::{find_entry, AnyEntry},
+    note::append_to_section,
+    operations,
+    research_log::ResearchLog,
+    utils::{self, TagFilter},
+    workspace,
+    workspace::WorkspaceRepo,
+    Config, CustomLogManager, DatasetManager, ExperimentManager, HypothesisManager, IdeaManager,
+    JournalManager, KnowledgeManager, ListFilter, LiteratureManager, LogError, MeetingManager,
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::{Path, PathBuf},
 };
-use anyhow::Result;
-use std::collections::HashSet;
 use uuid::Uuid;
 
+/// Section of the body that `--wiki-link` adds `[[...]]` links to.
+const REFERENCES_HEADING: &str = "## References";
+
+/// Why one entry references another.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceKind {
+    Supports,
+    Refutes,
+    Extends,
+    Uses,
+}
+
+impl fmt::Display for ReferenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ReferenceKind::Supports => "supports",
+            ReferenceKind::Refutes => "refutes",
+            ReferenceKind::Extends => "extends",
+            ReferenceKind::Uses => "uses",
+        };
+        f.write_str(s)
+    }
+}
+
+/// A directed, optionally labelled edge to another research log.
+///
+/// Older entries store references as a plain list of UUIDs; those still
+/// deserialize, with no kind attached.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "RawReference")]
+pub struct Reference {
+    pub id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ReferenceKind>,
+    /// Repository of the workspace the target is in, by its registered name;
+    /// `None` for entries of the same repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawReference {
+    Plain(Uuid),
+    Labelled {
+        id: Uuid,
+        #[serde(default)]
+        kind: Option<ReferenceKind>,
+        #[serde(default)]
+        repo: Option<String>,
+    },
+}
+
+impl From<RawReference> for Reference {
+    fn from(raw: RawReference) -> Self {
+        match raw {
+            RawReference::Plain(id) => Reference {
+                id,
+                kind: None,
+                repo: None,
+            },
+            RawReference::Labelled { id, kind, repo } => Reference { id, kind, repo },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
 pub struct ReferenceInfo {
     pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    #[serde(rename = "type")]
     pub type_: String,
     pub title: String,
     pub tags: HashSet<String>,
+    pub kind: Option<ReferenceKind>,
+    /// Registered repository the entry is in, for references to an entry of
+    /// another repository of the workspace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    /// Whether the body links to the entry with an Obsidian-style `[[...]]` link.
+    /// Entries that are only linked this way have no `kind`.
+    pub wiki_link: bool,
+}
+
+/// An entry as it appears in [`ReferenceAnalysis`].
+#[derive(Debug, Serialize)]
+pub struct GraphEntry {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReferencedEntry {
+    #[serde(flatten)]
+    pub entry: GraphEntry,
+    /// Number of entries referencing this one.
+    pub references: usize,
+}
+
+/// The shape of the reference graph, treating references as undirected for
+/// connectivity. Only frontmatter references count; references to entries
+/// that no longer exist are ignored.
+#[derive(Debug, Serialize)]
+pub struct ReferenceAnalysis {
+    /// Entries that neither reference nor are referenced by anything.
+    pub orphans: Vec<GraphEntry>,
+    /// Entries with at least one inbound reference, most referenced first.
+    pub most_referenced: Vec<ReferencedEntry>,
+    /// Groups of two or more connected entries, largest first.
+    pub components: Vec<Vec<GraphEntry>>,
+}
+
+/// What reference listing needs to know about any entry.
+struct EntrySummary {
+    id: Uuid,
+    handle: Option<String>,
+    kind: String,
+    title: String,
+    tags: HashSet<String>,
+    path: PathBuf,
+    references: Vec<Uuid>,
+}
+
+impl EntrySummary {
+    /// The name Obsidian resolves `[[...]]` links against.
+    fn link_name(&self) -> String {
+        link_name(&self.path)
+    }
+
+    fn to_graph_entry(&self) -> GraphEntry {
+        GraphEntry {
+            id: self.id.to_string(),
+            type_: self.kind.to_string(),
+            title: self.title.clone(),
+        }
+    }
+
+    fn to_info(&self, kind: Option<ReferenceKind>, wiki_link: bool) -> ReferenceInfo {
+        ReferenceInfo {
+            id: self.id.to_string(),
+            handle: self.handle.clone(),
+            type_: self.kind.to_string(),
+            title: self.title.clone(),
+            tags: self.tags.clone(),
+            kind,
+            repo: None,
+            wiki_link,
+        }
+    }
+}
+
+fn link_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn summaries<T: ResearchLog>(manager: &LogManager<T>) -> Result<Vec<EntrySummary>> {
+    Ok(manager
+        .list_log_files(&ListFilter::default())?
+        .into_iter()
+        .map(|(log, path)| EntrySummary {
+            id: log.base().id,
+            handle: log.base().handle.clone(),
+            kind: T::KIND.to_string(),
+            title: log.base().title.clone(),
+            tags: log.base().tags.clone(),
+            path,
+            references: log.base().references.iter().map(|r| r.id).collect(),
+        })
+        .collect())
+}
+
+fn all_entries(config: &Config) -> Result<Vec<EntrySummary>> {
+    let mut entries = summaries(&HypothesisManager::new(config.clone()).manager)?;
+    entries.extend(summaries(&LiteratureManager::new(config.clone()).manager)?);
+    entries.extend(summaries(&KnowledgeManager::new(config.clone()).manager)?);
+    entries.extend(summaries(&JournalManager::new(config.clone()).manager)?);
+    entries.extend(summaries(&DatasetManager::new(config.clone()).manager)?);
+    entries.extend(summaries(&MeetingManager::new(config.clone()).manager)?);
+    entries.extend(summaries(&IdeaManager::new(config.clone()).manager)?);
+    entries.extend(summaries(&ExperimentManager::new(config.clone()).manager)?);
+    for name in config.types.keys() {
+        let manager = CustomLogManager::new(config.clone(), name)?;
+        entries.extend(manager.list(None, &TagFilter::default())?.into_iter().map(
+            |(log, path)| EntrySummary {
+                id: log.base.id,
+                handle: log.base.handle.clone(),
+                kind: log.kind,
+                title: log.base.title.clone(),
+                tags: log.base.tags.clone(),
+                path,
+                references: log.base.references.iter().map(|r| r.id).collect(),
+            },
+        ));
+    }
+    Ok(entries)
+}
+
+/// Analyzes the reference graph of the whole repository, keeping the `top`
+/// most referenced entries.
+pub fn analyze_references(top: usize) -> Result<ReferenceAnalysis> {
+    let entries = all_entries(&load_config()?)?;
+    Ok(analyze(&entries, top))
+}
+
+fn analyze(entries: &[EntrySummary], top: usize) -> ReferenceAnalysis {
+    let index: HashMap<Uuid, usize> = entries.iter().enumerate().map(|(i, e)| (e.id, i)).collect();
+    let edges: Vec<(usize, usize)> = entries
+        .iter()
+        .enumerate()
+        .flat_map(|(source, entry)| {
+            entry
+                .references
+                .iter()
+                .filter_map(|id| index.get(id))
+                .filter(move |target| **target != source)
+                .map(move |target| (source, *target))
+        })
+        .collect();
+
+    let mut inbound = vec![0; entries.len()];
+    let mut connected = vec![false; entries.len()];
+    let mut parent: Vec<usize> = (0..entries.len()).collect();
+    for &(source, target) in &edges {
+        inbound[target] += 1;
+        connected[source] = true;
+        connected[target] = true;
+        let (a, b) = (
+            find_root(&mut parent, source),
+            find_root(&mut parent, target),
+        );
+        parent[a] = b;
+    }
+
+    let orphans = entries
+        .iter()
+        .zip(&connected)
+        .filter(|(_, connected)| !**connected)
+        .map(|(entry, _)| entry.to_graph_entry())
+        .collect();
+
+    let mut ranked: Vec<usize> = (0..entries.len()).filter(|i| inbound[*i] > 0).collect();
+    ranked.sort_by(|a, b| {
+        inbound[*b]
+            .cmp(&inbound[*a])
+            .then_with(|| entries[*a].title.cmp(&entries[*b].title))
+    });
+    let most_referenced = ranked
+        .into_iter()
+        .take(top)
+        .map(|i| ReferencedEntry {
+            entry: entries[i].to_graph_entry(),
+            references: inbound[i],
+        })
+        .collect();
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in (0..entries.len()).filter(|i| connected[*i]) {
+        groups.entry(find_root(&mut parent, i)).or_default().push(i);
+    }
+    let mut groups: Vec<Vec<usize>> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    let components = groups
+        .into_iter()
+        .map(|group| {
+            group
+                .into_iter()
+                .map(|i| entries[i].to_graph_entry())
+                .collect()
+        })
+        .collect();
+
+    ReferenceAnalysis {
+        orphans,
+        most_referenced,
+        components,
+    }
+}
+
+fn find_root(parent: &mut [usize], mut node: usize) -> usize {
+    while parent[node] != node {
+        parent[node] = parent[parent[node]];
+        node = parent[node];
+    }
+    node
+}
+
+/// Points links to the file `old_path` in every other entry at `new_path`:
+/// `[[...]]` wiki-links by name and markdown links by file name. Wiki-link
+/// aliases that were the old title get the new one. Returns the changed files.
+pub(crate) fn relink(
+    config: &Config,
+    old_path: &Path,
+    new_path: &Path,
+    old_title: &str,
+    new_title: &str,
+) -> Result<Vec<PathBuf>> {
+    let (old_name, new_name) = (link_name(old_path), link_name(new_path));
+    if old_name == new_name && old_title == new_title {
+        return Ok(Vec::new());
+    }
+
+    let mut relinked = Vec::new();
+    for entry in all_entries(config)? {
+        if entry.path == new_path {
+            continue;
+        }
+        let content = utils::load_entry_content(&entry.path)?;
+        let updated = relink_text(&content, (&old_name, &new_name), (old_title, new_title));
+        if updated != content {
+            dry_run::write(&entry.path, &updated)?;
+            relinked.push(entry.path);
+        }
+    }
+    Ok(relinked)
+}
+
+fn relink_text(text: &str, names: (&str, &str), titles: (&str, &str)) -> String {
+    let (old_name, new_name) = names;
+    let text = rewrite_links(text, "[[", "]]", |link| {
+        let split = link.find(['|', '#']).unwrap_or(link.len());
+        let (target, rest) = link.split_at(split);
+        let (dir, file) = target
+            .rsplit_once('/')
+            .map_or(("", target), |(d, f)| (d, f));
+        let (name, extension) = file.strip_suffix(".md").map_or((file, ""), |n| (n, ".md"));
+        if name.trim() != old_name {
+            return None;
+        }
+        let rest = match rest.strip_prefix('|') {
+            Some(alias) if alias.trim() == titles.0 => format!("|{}", titles.1),
+            _ => rest.to_string(),
+        };
+        let dir = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", dir)
+        };
+        Some(format!("{}{}{}{}", dir, new_name, extension, rest))
+    });
+    let old_file = format!("{}.md", old_name);
+    rewrite_links(&text, "](", ")", |link| {
+        let (dir, file) = link.rsplit_once('/').map_or(("", link), |(d, f)| (d, f));
+        if file != old_file {
+            return None;
+        }
+        let dir = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", dir)
+        };
+        Some(format!("{}{}.md", dir, new_name))
+    })
+}
+
+/// Replaces the text between each `open` and the following `close` for which
+/// `rewrite` returns a new value.
+fn rewrite_links(
+    text: &str,
+    open: &str,
+    close: &str,
+    rewrite: impl Fn(&str) -> Option<String>,
+) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(open) {
+        let (before, after) = rest.split_at(start + open.len());
+        result.push_str(before);
+        let Some(end) = after.find(close) else {
+            rest = after;
+            break;
+        };
+        let link = &after[..end];
+        match rewrite(link) {
+            Some(link) => result.push_str(&link),
+            None => result.push_str(link),
+        }
+        rest = &after[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Targets of the `[[target]]`, `[[target|alias]]` and `[[target#heading]]` links in `body`.
+fn wiki_links(body: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("[[") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("]]") else {
+            break;
+        };
+        let target = rest[..end].split(['|', '#']).next().unwrap_or_default();
+        let target = target.rsplit('/').next().unwrap_or(target).trim();
+        let target = target.strip_suffix(".md").unwrap_or(target);
+        if !target.is_empty() && !links.iter().any(|l| l == target) {
+            links.push(target.to_string());
+        }
+        rest = &rest[end + 2..];
+    }
+    links
 }
 
-pub fn add_reference(source_id: &str, target_id: &str) -> Result<()> {
-    let config = load_config()?;
-    let h_manager = HypothesisManager::new(config.clone());
-    let l_manager = LiteratureManager::new(config.clone());
-    let k_manager = KnowledgeManager::new(config.clone());
+/// Adds a `[[...]]` link to the target under the source's `## References`
+/// heading, unless the body already links to it.
+fn add_wiki_link(
+    config: &Config,
+    source: &mut dyn AnyEntry,
+    target_id: Uuid,
+    kind: Option<ReferenceKind>,
+) -> Result<()> {
+    let target = all_entries(config)?
+        .into_iter()
+        .find(|entry| entry.id == target_id)
+        .context("Referenced entry not found")?;
+    let name = target.link_name();
+    let line = match kind {
+        Some(kind) => format!("- [[{}|{}]] ({})", name, target.title, kind),
+        None => format!("- [[{}|{}]]", name, target.title),
+    };
+    source.update_body(&|body| {
+        if wiki_links(body).contains(&name) {
+            body.to_string()
+        } else {
+            append_to_section(body, REFERENCES_HEADING, &line)
+        }
+    })
+}
 
-    let target_uuid = Uuid::parse_str(target_id)?;
+/// The ID of the entry `target_id` refers to by its ID, the start of it, or its
+/// handle. Full IDs are taken as given, so references to deleted entries can
+/// still be removed.
+pub(crate) fn resolve_target(config: &Config, target_id: &str) -> Result<Uuid> {
+    if let Ok(id) = Uuid::parse_str(target_id) {
+        return Ok(id);
+    }
+    if let Some((repo, id)) = workspace::split_qualified(target_id) {
+        return resolve_in_repo(&repo, id);
+    }
+    Ok(find_entry(config, target_id)?.base().id)
+}
 
-    if let Ok((mut log, path)) = h_manager.find(source_id) {
-        log.base_mut().references.insert(target_uuid);
-        h_manager.manager.update_log(&mut log, &path)
-    } else if let Ok((mut log, path)) = l_manager.find(source_id) {
-        log.base_mut().references.insert(target_uuid);
-        l_manager.manager.update_log(&mut log, &path)
-    } else if let Ok((mut log, path)) = k_manager.find(source_id) {
-        log.base_mut().references.insert(target_uuid);
-        k_manager.manager.update_log(&mut log, &path)
-    } else {
-        Err(anyhow::anyhow!("Source log not found"))
+/// Resolves `id` to an entry of the registered repository `repo`, checking
+/// that the entry is there even when `id` is a full UUID.
+fn resolve_in_repo(repo: &WorkspaceRepo, id: &str) -> Result<Uuid> {
+    let config = Config::load(&repo.root)
+        .with_context(|| format!("Failed to load repository {}", repo.name))?;
+    let uuid = resolve_target(&config, id)
+        .with_context(|| format!("Failed to find '{}' in repository {}", id, repo.name))?;
+    if !all_entries(&config)?.iter().any(|entry| entry.id == uuid) {
+        return Err(anyhow::anyhow!(
+            "No entry with ID {} in repository {}",
+            uuid,
+            repo.name
+        ));
     }
+    Ok(uuid)
 }
 
-fn is_reference_complete(target_id: &str) -> Result<bool> {
-    let config = load_config()?;
-    let h_manager = HypothesisManager::new(config.clone());
-    let l_manager = LiteratureManager::new(config.clone());
-    let k_manager = KnowledgeManager::new(config.clone());
+/// Resolves the target of a new reference: an entry of this repository, or
+/// with a `<repo>:<id>` target, of another repository of the workspace, whose
+/// name is returned with it.
+fn resolve_new_target(config: &Config, target_id: &str) -> Result<(Uuid, Option<String>)> {
+    match workspace::split_qualified(target_id) {
+        Some((repo, id)) if workspace::is_current(config, &repo) => {
+            Ok((resolve_target(config, id)?, None))
+        }
+        Some((repo, id)) => Ok((resolve_in_repo(&repo, id)?, Some(repo.name))),
+        None => Ok((resolve_target(config, target_id)?, None)),
+    }
+}
+
+/// Records that `source_id` references `target_id`. With `wiki_link`, the
+/// source's body also gets an Obsidian `[[...]]` link to the target.
+pub fn add_reference(
+    source_id: &str,
+    target_id: &str,
+    kind: Option<ReferenceKind>,
+    wiki_link: bool,
+) -> Result<()> {
+    add_reference_in(&load_config()?, source_id, target_id, kind, wiki_link)
+}
 
-    if let Ok((log, _)) = h_manager.find(target_id) {
-        Ok(matches!(
-            log.status,
-            HypothesisStatus::Proven | HypothesisStatus::Disproven | HypothesisStatus::Inconclusive
-        ))
-    } else if let Ok((log, _)) = l_manager.find(target_id) {
-        Ok(matches!(log.status, LiteratureStatus::Completed))
-    } else if let Ok((log, _)) = k_manager.find(target_id) {
-        Ok(matches!(log.status, KnowledgeStatus::Published))
-    } else {
-        Err(anyhow::anyhow!("Reference not found"))
+/// Like [`add_reference`], in the repository of `config`.
+pub fn add_reference_in(
+    config: &Config,
+    source_id: &str,
+    target_id: &str,
+    kind: Option<ReferenceKind>,
+    wiki_link: bool,
+) -> Result<()> {
+    if wiki_link && workspace::split_qualified(target_id).is_some() {
+        return Err(anyhow::anyhow!(
+            "Wiki-links can only point to entries of the same repository"
+        ));
     }
+    let description = format!("add reference from {} to {}", source_id, target_id);
+    operations::record(config, description, || {
+        let (target_uuid, target_repo) = resolve_new_target(config, target_id)?;
+        set_reference(config, source_id, target_uuid, kind, target_repo, wiki_link)
+    })
 }
 
-pub fn force_add_reference(source_id: &str, target_id: &str) -> Result<()> {
-    let config = load_config()?;
-    let h_manager = HypothesisManager::new(config.clone());
-    let l_manager = LiteratureManager::new(config.clone());
-    let k_manager = KnowledgeManager::new(config.clone());
+/// Adds the reference to the frontmatter of the entry `source_id` and, with
+/// `wiki_link`, a `[[...]]` link to its body.
+fn set_reference(
+    config: &Config,
+    source_id: &str,
+    target_id: Uuid,
+    kind: Option<ReferenceKind>,
+    target_repo: Option<String>,
+    wiki_link: bool,
+) -> Result<()> {
+    let mut source = find_entry(config, source_id)?;
+    source
+        .base_mut()
+        .set_reference_in(target_id, kind, target_repo);
+    source.save()?;
+    if wiki_link {
+        add_wiki_link(config, source.as_mut(), target_id, kind)?;
+    }
+    Ok(())
+}
 
-    let target_uuid = Uuid::parse_str(target_id)?;
+/// Like [`add_reference`], but fails if the target is not complete (see
+/// [`ResearchLog::is_complete`]).
+pub fn force_add_reference(
+    source_id: &str,
+    target_id: &str,
+    kind: Option<ReferenceKind>,
+    wiki_link: bool,
+) -> Result<()> {
+    force_add_reference_in(&load_config()?, source_id, target_id, kind, wiki_link)
+}
 
-    if !is_reference_complete(target_id)? {
+/// Like [`force_add_reference`], in the repository of `config`.
+pub fn force_add_reference_in(
+    config: &Config,
+    source_id: &str,
+    target_id: &str,
+    kind: Option<ReferenceKind>,
+    wiki_link: bool,
+) -> Result<()> {
+    if wiki_link && workspace::split_qualified(target_id).is_some() {
         return Err(anyhow::anyhow!(
-            "Warning: Referenced research log is not in a complete state (proven, completed, or published). References should ideally point to completed research."
+            "Wiki-links can only point to entries of the same repository"
         ));
     }
+    let description = format!("add reference from {} to {}", source_id, target_id);
+    operations::record(config, description, || {
+        let (target_uuid, target_repo) = resolve_new_target(config, target_id)?;
+
+        if !load_new_target(config, target_id, target_uuid)?.is_complete() {
+            return Err(anyhow::anyhow!(
+                "Warning: Referenced research log is not in a complete state (proven, completed, or published). References should ideally point to completed research."
+            ));
+        }
+
+        set_reference(config, source_id, target_uuid, kind, target_repo, wiki_link)
+    })
+}
 
-    if let Ok((mut log, path)) = h_manager.find(source_id) {
-        log.base_mut().references.insert(target_uuid);
-        h_manager.manager.update_log(&mut log, &path)
-    } else if let Ok((mut log, path)) = l_manager.find(source_id) {
-        log.base_mut().references.insert(target_uuid);
-        l_manager.manager.update_log(&mut log, &path)
-    } else if let Ok((mut log, path)) = k_manager.find(source_id) {
-        log.base_mut().references.insert(target_uuid);
-        k_manager.manager.update_log(&mut log, &path)
-    } else {
-        Err(anyhow::anyhow!("Source log not found"))
+/// Loads the entry `target_uuid` that [`resolve_new_target`] found for
+/// `target_id`, from the repository it is in.
+fn load_new_target(
+    config: &Config,
+    target_id: &str,
+    target_uuid: Uuid,
+) -> Result<Box<dyn AnyEntry>> {
+    match workspace::split_qualified(target_id) {
+        Some((repo, _)) if !workspace::is_current(config, &repo) => {
+            let other = Config::load(&repo.root)
+                .with_context(|| format!("Failed to load repository {}", repo.name))?;
+            find_entry(&other, &target_uuid.to_string())
+        }
+        _ => find_entry(config, &target_uuid.to_string()),
     }
 }
 
 pub fn remove_reference(source_id: &str, target_id: &str) -> Result<()> {
-    let config = load_config()?;
-    let h_manager = HypothesisManager::new(config.clone());
-    let l_manager = LiteratureManager::new(config.clone());
-    let k_manager = KnowledgeManager::new(config.clone());
-
-    let target_uuid = Uuid::parse_str(target_id)?;
-
-    if let Ok((mut log, path)) = h_manager.find(source_id) {
-        log.base_mut().references.remove(&target_uuid);
-        h_manager.manager.update_log(&mut log, &path)
-    } else if let Ok((mut log, path)) = l_manager.find(source_id) {
-        log.base_mut().references.remove(&target_uuid);
-        l_manager.manager.update_log(&mut log, &path)
-    } else if let Ok((mut log, path)) = k_manager.find(source_id) {
-        log.base_mut().references.remove(&target_uuid);
-        k_manager.manager.update_log(&mut log, &path)
-    } else {
-        Err(anyhow::anyhow!("Source log not found"))
-    }
-}
-
-pub fn list_references(id: &str) -> Result<Vec<ReferenceInfo>> {
-    let config = load_config()?;
-    let h_manager = HypothesisManager::new(config.clone());
-    let l_manager = LiteratureManager::new(config.clone());
-    let k_manager = KnowledgeManager::new(config.clone());
-
-    let referenced_ids = if let Ok((log, _)) = h_manager.find(id) {
-        log.base().references.clone()
-    } else if let Ok((log, _)) = l_manager.find(id) {
-        log.base().references.clone()
-    } else if let Ok((log, _)) = k_manager.find(id) {
-        log.base().references.clone()
-    } else {
-        return Err(anyhow::anyhow!("Log not found"));
-    };
+    remove_reference_in(&load_config()?, source_id, target_id)
+}
+
+/// Like [`remove_reference`], in the repository of `config`.
+pub fn remove_reference_in(config: &Config, source_id: &str, target_id: &str) -> Result<()> {
+    let target_uuid = resolve_target(config, target_id)?;
+    unset_reference(config, source_id, target_uuid)
+}
+
+fn unset_reference(config: &Config, source_id: &str, target_uuid: Uuid) -> Result<()> {
+    let mut source = find_entry(config, source_id)?;
+    source.base_mut().remove_reference(&target_uuid);
+    source.save()
+}
+
+/// Checks that nothing references the entry `id` before it is deleted.
+///
+/// With `cascade`, the references are removed from the referencing entries
+/// instead; with `force`, they are left dangling.
+pub(crate) fn check_inbound_references(
+    config: &Config,
+    id: Uuid,
+    cascade: bool,
+    force: bool,
+) -> Result<()> {
+    if force && !cascade {
+        return Ok(());
+    }
+    let entries = all_entries(config)?;
+    let sources: Vec<&EntrySummary> = entries
+        .iter()
+        .filter(|entry| entry.id != id && entry.references.contains(&id))
+        .collect();
+    if sources.is_empty() {
+        return Ok(());
+    }
+    if cascade {
+        for source in sources {
+            unset_reference(config, &source.id.to_string(), id)?;
+        }
+        return Ok(());
+    }
+
+    let target = entries.iter().find(|entry| entry.id == id);
+    Err(LogError::Referenced {
+        kind: target.map(|t| t.kind.clone()).unwrap_or_default(),
+        title: target.map(|t| t.title.clone()).unwrap_or_default(),
+        sources: sources
+            .iter()
+            .map(|source| format!("{} ({})", &source.id.to_string()[..8], source.title))
+            .collect(),
+    }
+    .into())
+}
+
+/// Lists the entries `id` references, both in its frontmatter and through
+/// `[[...]]` wiki-links in its body that resolve to an entry's file name.
+/// With `kind`, only the references of that kind are listed.
+pub fn list_references(id: &str, kind: Option<ReferenceKind>) -> Result<Vec<ReferenceInfo>> {
+    list_references_in(&load_config()?, id, kind)
+}
+
+/// Like [`list_references`], in the repository of `config`.
+pub fn list_references_in(
+    config: &Config,
+    id: &str,
+    kind: Option<ReferenceKind>,
+) -> Result<Vec<ReferenceInfo>> {
+    let entry = find_entry(config, id)?;
+    let referenced_ids = entry.base().references.clone();
+    let body = entry.read_body()?;
+
+    let entries = all_entries(config)?;
+    let mut links = wiki_links(&body);
 
     let mut references = Vec::new();
-    for ref_id in referenced_ids {
-        let short_id = ref_id.to_string();
-        if let Ok((log, _)) = h_manager.find(&short_id) {
-            references.push(ReferenceInfo {
-                id: short_id,
-                type_: "hypothesis".to_string(),
-                title: log.base().title.clone(),
-                tags: log.base().tags.clone(),
-            });
-        } else if let Ok((log, _)) = l_manager.find(&short_id) {
-            references.push(ReferenceInfo {
-                id: short_id,
-                type_: "literature".to_string(),
-                title: log.base().title.clone(),
-                tags: log.base().tags.clone(),
-            });
-        } else if let Ok((log, _)) = k_manager.find(&short_id) {
-            references.push(ReferenceInfo {
-                id: short_id,
-                type_: "knowledge".to_string(),
-                title: log.base().title.clone(),
-                tags: log.base().tags.clone(),
+    let mut elsewhere = Vec::new();
+    for reference in referenced_ids {
+        if reference.repo.is_some() {
+            elsewhere.push(reference);
+        } else if let Some(entry) = entries.iter().find(|e| e.id == reference.id) {
+            let name = entry.link_name();
+            let linked = links.contains(&name);
+            links.retain(|link| *link != name);
+            references.push(entry.to_info(reference.kind, linked));
+        } else {
+            elsewhere.push(reference);
+        }
+    }
+    // References to no entry here may be to one in another repository of the
+    // workspace; those added as `<repo>:<id>` name it
+    if !elsewhere.is_empty() {
+        for (repo, other) in workspace::other_repos(config) {
+            let entries = all_entries(&other)?;
+            elsewhere.retain(|reference| {
+                if reference
+                    .repo
+                    .as_ref()
+                    .is_some_and(|name| *name != repo.name)
+                {
+                    return true;
+                }
+                let Some(entry) = entries.iter().find(|e| e.id == reference.id) else {
+                    return true;
+                };
+                let mut info = entry.to_info(reference.kind, false);
+                info.repo = Some(repo.name.clone());
+                references.push(info);
+                false
             });
         }
     }
+    for link in links {
+        if let Some(entry) = entries.iter().find(|e| e.link_name() == link) {
+            references.push(entry.to_info(None, true));
+        }
+    }
+    if kind.is_some() {
+        references.retain(|reference| reference.kind == kind);
+    }
 
     Ok(references)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        md_frontmatter::update_markdown_frontmatter,
+        test_support::{author, TempRepo},
+        HypothesisLog,
+    };
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_legacy_and_labelled_references() {
+        let yaml = r#"
+- 0b0c3a43-6d0e-4b8e-9b8a-3f1c5f8a2f11
+- id: 7e6b1a6e-2b0f-4a53-8d43-5a4c1f0e9d22
+  kind: supports
+- id: 2fca92d9-3fd1-4567-ab4d-f9217fa728dd
+  repo: lab
+"#;
+
+        let references: Vec<Reference> = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(references[0].kind, None);
+        assert_eq!(references[1].kind, Some(ReferenceKind::Supports));
+        assert_eq!(
+            references[1].id.to_string(),
+            "7e6b1a6e-2b0f-4a53-8d43-5a4c1f0e9d22"
+        );
+        assert_eq!(references[1].repo, None);
+        assert_eq!(references[2].repo.as_deref(), Some("lab"));
+        assert!(!serde_yaml::to_string(&references[1])
+            .unwrap()
+            .contains("repo"));
+    }
+
+    #[test]
+    fn test_analyze() {
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let entry = |i: usize, references: Vec<Uuid>| EntrySummary {
+            id: ids[i],
+            handle: None,
+            kind: "knowledge".to_string(),
+            title: format!("Entry {}", i),
+            tags: HashSet::new(),
+            path: PathBuf::from(format!("entry-{}.md", i)),
+            references,
+        };
+        // 0 -> 2, 1 -> 2, 3 -> 4; 4 also points at a deleted entry.
+        let entries = vec![
+            entry(0, vec![ids[2]]),
+            entry(1, vec![ids[2]]),
+            entry(2, vec![]),
+            entry(3, vec![ids[4]]),
+            entry(4, vec![Uuid::new_v4()]),
+        ];
+
+        let analysis = analyze(&entries, 1);
+        assert!(analysis.orphans.is_empty());
+        assert_eq!(analysis.most_referenced.len(), 1);
+        assert_eq!(analysis.most_referenced[0].entry.title, "Entry 2");
+        assert_eq!(analysis.most_referenced[0].references, 2);
+        let sizes: Vec<usize> = analysis.components.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![3, 2]);
+
+        let analysis = analyze(&entries[2..3], 10);
+        assert_eq!(analysis.orphans.len(), 1);
+        assert!(analysis.components.is_empty());
+    }
+
+    #[test]
+    fn test_relink_text() {
+        let text =
+            "[[2025-01-02-old|Old]] [[notes/2025-01-02-old.md#Method]] [[2025-01-02-older]]\n\
+                    [paper](../research-logs/2025-01-02-old.md) [site](https://example.com/)";
+        assert_eq!(
+            relink_text(text, ("2025-01-02-old", "2025-01-02-new"), ("Old", "New")),
+            "[[2025-01-02-new|New]] [[notes/2025-01-02-new.md#Method]] [[2025-01-02-older]]\n\
+             [paper](../research-logs/2025-01-02-new.md) [site](https://example.com/)"
+        );
+    }
+
+    #[test]
+    fn test_wiki_links() {
+        let body = "See [[2025-01-02-entry|Entry]] and [[notes/other.md#Method]].\n\
+                    Again [[2025-01-02-entry]], not [[ ]] or [[unclosed";
+        assert_eq!(wiki_links(body), vec!["2025-01-02-entry", "other"]);
+    }
+
+    #[test]
+    fn test_reference_from_an_idea() {
+        use crate::IdeaLog;
+
+        let repo = TempRepo::new();
+        let config = repo.load("");
+        let idea = IdeaLog::new("Try a bloom filter".into(), HashSet::new(), author());
+        let content = update_markdown_frontmatter(&idea, "# Try a bloom filter").unwrap();
+        let ideas = IdeaManager::new(config.clone());
+        let idea_path = ideas.manager.save_log(&idea, &content, false).unwrap();
+        let hypothesis = HypothesisLog::new("Caching helps".into(), HashSet::new(), author());
+        let content = update_markdown_frontmatter(&hypothesis, "# Caching helps").unwrap();
+        let hypothesis_path = HypothesisManager::new(config.clone())
+            .manager
+            .save_log(&hypothesis, &content, false)
+            .unwrap();
+
+        let idea_id = idea.base.id.to_string();
+        assert_eq!(
+            resolve_target(&config, &idea_id[..30]).unwrap(),
+            idea.base.id
+        );
+        set_reference(
+            &config,
+            &idea_id[..30],
+            hypothesis.base.id,
+            Some(ReferenceKind::Supports),
+            None,
+            true,
+        )
+        .unwrap();
+        let (found, _) = ideas.find(&idea_id).unwrap();
+        assert_eq!(found.base.references.len(), 1);
+        let link = format!(
+            "- [[{}|Caching helps]] (supports)",
+            link_name(&hypothesis_path)
+        );
+        assert!(fs::read_to_string(&idea_path).unwrap().contains(&link));
+        assert_eq!(fs::read_to_string(&hypothesis_path).unwrap(), content);
+        let listed = |kind| list_references_in(&config, &idea_id, kind).unwrap();
+        assert_eq!(listed(None).len(), 1);
+        assert_eq!(listed(Some(ReferenceKind::Supports)).len(), 1);
+        assert!(listed(Some(ReferenceKind::Refutes)).is_empty());
+
+        unset_reference(&config, &idea_id, hypothesis.base.id).unwrap();
+        let (found, _) = ideas.find(&idea_id).unwrap();
+        assert!(found.base.references.is_empty());
+    }
+
+    #[test]
+    fn test_force_reference_to_another_repository() {
+        use crate::HypothesisStatus;
+
+        let (here_repo, lab_repo) = (TempRepo::new(), TempRepo::new());
+        let (here, lab) = (here_repo.load(""), lab_repo.load(""));
+        let workspaces = here_repo.root().join("workspaces.toml");
+        let settings = format!("[repos]\nlab = {:?}\n", lab_repo.root());
+        fs::write(&workspaces, settings).unwrap();
+        // Only qualified IDs and dangling references read the workspaces file
+        std::env::set_var(workspace::WORKSPACES_ENV_VAR, &workspaces);
+
+        let save = |config: &Config, title: &str, status: HypothesisStatus| {
+            let mut log = HypothesisLog::new(title.into(), HashSet::new(), author());
+            log.status = status;
+            let content = update_markdown_frontmatter(&log, "# Body").unwrap();
+            HypothesisManager::new(config.clone())
+                .manager
+                .save_log(&log, &content, false)
+                .unwrap();
+            log.base.id.to_string()
+        };
+        let source = save(&here, "Caching helps", HypothesisStatus::ACTIVE);
+        let proven = save(&lab, "Bloom filters work", HypothesisStatus::PROVEN);
+        let open = save(&lab, "Tries work", HypothesisStatus::ACTIVE);
+
+        let proven_target = format!("lab:{}", &proven[..30]);
+        force_add_reference_in(&here, &source, &proven_target, None, false).unwrap();
+        let entry = find_entry(&here, &source).unwrap();
+        let references: Vec<&Reference> = entry.base().references.iter().collect();
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].id.to_string(), proven);
+        assert_eq!(references[0].repo.as_deref(), Some("lab"));
+
+        let open_target = format!("lab:{}", &open[..30]);
+        let err = force_add_reference_in(&here, &source, &open_target, None, false).unwrap_err();
+        assert!(err.to_string().contains("not in a complete state"));
+        assert!(force_add_reference_in(&here, &source, &proven_target, None, true).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+//! Suggestions of entries an entry may be missing references to, ranked by the
+//! tags they share and how alike their titles and bodies are.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::{
+    load_config, log_manager::LogManager, reference::resolve_target, research_log::ResearchLog,
+    Config, DatasetManager, ExperimentManager, HypothesisManager, JournalManager, KnowledgeManager,
+    ListFilter, LiteratureManager, MeetingManager,
+};
+
+/// Share of the score that comes from the text; the rest comes from tags.
+const TEXT_WEIGHT: f64 = 0.6;
+
+/// Entries scoring lower than this have too little in common to suggest.
+const MIN_SCORE: f64 = 0.05;
+
+/// Words too common to tell entries apart.
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "from", "that", "this", "these", "those", "are", "was", "were",
+    "has", "have", "had", "not", "but", "can", "will", "would", "should", "could", "into", "onto",
+    "than", "then", "there", "their", "they", "what", "when", "which", "while", "who", "how",
+    "why", "its", "our", "out", "all", "any", "each", "more", "most", "some", "such", "only",
+    "also", "very", "over", "under", "about", "after", "before", "between", "via", "use", "used",
+    "using", "been", "being", "does", "did", "done", "one", "two", "may", "notes", "todo",
+];
+
+/// An entry suggested by [`related_entries`].
+#[derive(Debug, Serialize, Clone)]
+pub struct RelatedEntry {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: String,
+    pub status: String,
+    /// How similar the entry is, from 0 to 1.
+    pub score: f64,
+    /// Tags both entries have, sorted.
+    pub shared_tags: Vec<String>,
+}
+
+/// What ranking needs to know about an entry.
+struct Document {
+    id: Uuid,
+    handle: Option<String>,
+    kind: &'static str,
+    title: String,
+    status: String,
+    tags: HashSet<String>,
+    references: HashSet<Uuid>,
+    /// How often each word occurs in the title and body.
+    terms: HashMap<String, usize>,
+}
+
+/// Lists up to `limit` entries most like the one `partial_id` names, best
+/// first, leaving out those it already references or is referenced by.
+///
+/// Entries are compared by their tags and by TF-IDF similarity of their titles
+/// and bodies; the bodies of encrypted entries are not read.
+pub fn related_entries(partial_id: &str, limit: usize) -> Result<Vec<RelatedEntry>> {
+    let config = load_config()?;
+    let id = resolve_target(&config, partial_id)?;
+    let documents = all_documents(&config)?;
+    let target = documents
+        .iter()
+        .position(|document| document.id == id)
+        .ok_or_else(|| anyhow::anyhow!("No entry found with ID '{}'", id))?;
+    Ok(rank(&documents, target, limit))
+}
+
+fn all_documents(config: &Config) -> Result<Vec<Document>> {
+    let mut documents = Vec::new();
+    collect(
+        &HypothesisManager::new(config.clone()).manager,
+        &mut documents,
+    )?;
+    collect(
+        &LiteratureManager::new(config.clone()).manager,
+        &mut documents,
+    )?;
+    collect(
+        &KnowledgeManager::new(config.clone()).manager,
+        &mut documents,
+    )?;
+    collect(&JournalManager::new(config.clone()).manager, &mut documents)?;
+    collect(&DatasetManager::new(config.clone()).manager, &mut documents)?;
+    collect(&MeetingManager::new(config.clone()).manager, &mut documents)?;
+    collect(
+        &ExperimentManager::new(config.clone()).manager,
+        &mut documents,
+    )?;
+    Ok(documents)
+}
+
+fn collect<T: ResearchLog>(manager: &LogManager<T>, documents: &mut Vec<Document>) -> Result<()> {
+    for (log, path) in manager.list_log_files(&ListFilter::default())? {
+        let base = log.base();
+        let mut text = base.title.clone();
+        if !base.encrypted {
+            text.push('\n');
+            text.push_str(&manager.read_body(&path)?);
+        }
+        documents.push(Document {
+            id: base.id,
+            handle: base.handle.clone(),
+            kind: T::KIND,
+            title: base.title.clone(),
+            status: log.status().to_string(),
+            tags: base.tags.clone(),
+            references: base.references.iter().map(|r| r.id).collect(),
+            terms: terms(&text),
+        });
+    }
+    Ok(())
+}
+
+/// Counts the words of `text`, in lowercase, skipping short words, numbers
+/// and [`STOP_WORDS`].
+fn terms(text: &str) -> HashMap<String, usize> {
+    let mut terms = HashMap::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let word = word.to_lowercase();
+        if word.chars().count() < 3
+            || word.chars().all(|c| c.is_ascii_digit())
+            || STOP_WORDS.contains(&word.as_str())
+        {
+            continue;
+        }
+        *terms.entry(word).or_insert(0) += 1;
+    }
+    terms
+}
+
+/// TF-IDF weights of the terms of each document, scaled to unit length so
+/// that the similarity of two documents is the dot product of their weights.
+fn weights(documents: &[Document]) -> Vec<HashMap<&str, f64>> {
+    let mut frequency: HashMap<&str, usize> = HashMap::new();
+    for document in documents {
+        for term in document.terms.keys() {
+            *frequency.entry(term).or_insert(0) += 1;
+        }
+    }
+    let count = documents.len() as f64;
+
+    documents
+        .iter()
+        .map(|document| {
+            let total: usize = document.terms.values().sum();
+            let mut weights: HashMap<&str, f64> = document
+                .terms
+                .iter()
+                .map(|(term, &occurrences)| {
+                    let idf = ((1.0 + count) / (1.0 + frequency[term.as_str()] as f64)).ln() + 1.0;
+                    (term.as_str(), occurrences as f64 / total as f64 * idf)
+                })
+                .collect();
+            let norm = weights.values().map(|w| w * w).sum::<f64>().sqrt();
+            if norm > 0.0 {
+                weights.values_mut().for_each(|w| *w /= norm);
+            }
+            weights
+        })
+        .collect()
+}
+
+fn cosine(a: &HashMap<&str, f64>, b: &HashMap<&str, f64>) -> f64 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small
+        .iter()
+        .filter_map(|(term, weight)| large.get(term).map(|other| weight * other))
+        .sum()
+}
+
+/// Share of the tags of either entry that both have.
+fn tag_overlap(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+fn rank(documents: &[Document], target: usize, limit: usize) -> Vec<RelatedEntry> {
+    let weights = weights(documents);
+    let entry = &documents[target];
+    let mut related: Vec<RelatedEntry> = documents
+        .iter()
+        .enumerate()
+        .filter(|(i, other)| {
+            *i != target
+                && !entry.references.contains(&other.id)
+                && !other.references.contains(&entry.id)
+        })
+        .filter_map(|(i, other)| {
+            let score = TEXT_WEIGHT * cosine(&weights[target], &weights[i])
+                + (1.0 - TEXT_WEIGHT) * tag_overlap(&entry.tags, &other.tags);
+            if score < MIN_SCORE {
+                return None;
+            }
+            let mut shared_tags: Vec<String> =
+                entry.tags.intersection(&other.tags).cloned().collect();
+            shared_tags.sort();
+            Some(RelatedEntry {
+                id: other.id,
+                handle: other.handle.clone(),
+                kind: other.kind,
+                title: other.title.clone(),
+                status: other.status.clone(),
+                score,
+                shared_tags,
+            })
+        })
+        .collect();
+    related.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    related.truncate(limit);
+    related
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(title: &str, tags: &[&str], body: &str) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            handle: None,
+            kind: "hypothesis",
+            title: title.to_string(),
+            status: "active".to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            references: HashSet::new(),
+            terms: terms(&format!("{}\n{}", title, body)),
+        }
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut documents = vec![
+            document(
+                "Cavity noise scaling",
+                &["optics"],
+                "Thermal noise in the cavity grows with temperature.",
+            ),
+            document(
+                "Noise in cryogenic cavities",
+                &["cryo"],
+                "Cavity noise measured at low temperature.",
+            ),
+            document("Lab budget", &["admin"], "Spending for the year."),
+            document(
+                "Laser alignment",
+                &["optics", "laser"],
+                "Aligning the laser by hand.",
+            ),
+            document(
+                "Cavity noise model",
+                &["optics"],
+                "A model of thermal cavity noise.",
+            ),
+        ];
+        let referenced = documents[4].id;
+        documents[0].references.insert(referenced);
+
+        let related = rank(&documents, 0, 10);
+        let titles: Vec<&str> = related.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["Noise in cryogenic cavities", "Laser alignment"]
+        );
+        assert_eq!(related[1].shared_tags, vec!["optics"]);
+        assert!(related[0].score > related[1].score);
+        assert_eq!(rank(&documents, 0, 1).len(), 1);
+    }
+}
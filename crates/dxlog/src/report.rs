@@ -0,0 +1,238 @@
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDate};
+use minijinja::{context, Environment};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::{
+    load_config, log_manager::LogManager, research_log::ResearchLog, utils, Config,
+    HypothesisManager, HypothesisStatus, KnowledgeManager, KnowledgeStatus, ListFilter,
+    LiteratureLog, LiteratureManager, LiteratureStatus,
+};
+
+const DEFAULT_TEMPLATE: &str = include_str!("templates/report.default.jinja");
+
+#[derive(Serialize)]
+struct ReportEntry {
+    id: String,
+    kind: &'static str,
+    title: String,
+    status: String,
+    date: String,
+}
+
+#[derive(Serialize)]
+struct ReportTransition {
+    id: String,
+    kind: &'static str,
+    title: String,
+    from: String,
+    to: String,
+    reason: String,
+    date: String,
+}
+
+#[derive(Serialize, Default)]
+struct AuthorActivity {
+    name: String,
+    created: Vec<ReportEntry>,
+    transitions: Vec<ReportTransition>,
+    completed: Vec<ReportEntry>,
+    stale: Vec<ReportEntry>,
+}
+
+impl AuthorActivity {
+    fn is_empty(&self) -> bool {
+        self.created.is_empty()
+            && self.transitions.is_empty()
+            && self.completed.is_empty()
+            && self.stale.is_empty()
+    }
+}
+
+/// Parses a report start given as a relative period (`7d`, `2w`) or a date (`2025-03-01`).
+pub fn parse_since(value: &str, today: NaiveDate) -> Result<NaiveDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid period '{}'. Use e.g. 7d, 2w or a date like 2025-03-01",
+            value
+        )
+    };
+    let (count, unit) = value.split_at(value.len().saturating_sub(1));
+    let count: i64 = count.parse().map_err(|_| invalid())?;
+    let days = match unit {
+        "d" => count,
+        "w" => count * 7,
+        _ => return Err(invalid()),
+    };
+    Ok(today - Duration::days(days))
+}
+
+/// Summarizes activity since `since`, grouped by the author of each entry.
+///
+/// The report is rendered through `templates.report` if that file exists, and
+/// through the built-in template otherwise.
+pub fn generate_report(since: NaiveDate) -> Result<String> {
+    let config = load_config()?;
+    build_report(&config, since, chrono::Local::now().date_naive())
+}
+
+fn build_report(config: &Config, since: NaiveDate, today: NaiveDate) -> Result<String> {
+    let window = Window {
+        since,
+        stale_before: today - Duration::days(config.stale_days as i64),
+    };
+
+    let mut activity: BTreeMap<String, AuthorActivity> = BTreeMap::new();
+    window.collect(
+        &HypothesisManager::new(config.clone()).manager,
+        &HypothesisStatus::ACTIVE.to_string(),
+        &mut activity,
+    )?;
+    window.collect(
+        &LiteratureManager::new(config.clone()).manager,
+        &LiteratureStatus::InProgress.to_string(),
+        &mut activity,
+    )?;
+    window.collect(
+        &KnowledgeManager::new(config.clone()).manager,
+        &KnowledgeStatus::Draft.to_string(),
+        &mut activity,
+    )?;
+
+    let authors: Vec<AuthorActivity> = activity
+        .into_values()
+        .filter(|a| !a.is_empty())
+        .map(|mut a| {
+            a.created.sort_by(|x, y| x.date.cmp(&y.date));
+            a.transitions.sort_by(|x, y| x.date.cmp(&y.date));
+            a.stale.sort_by(|x, y| x.date.cmp(&y.date));
+            a
+        })
+        .collect();
+
+    let template_content = if config.templates.report.exists() {
+        utils::load_entry_content(&config.templates.report)?
+    } else {
+        DEFAULT_TEMPLATE.to_string()
+    };
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    let template = env
+        .template_from_str(&template_content)
+        .context("Invalid report template")?;
+
+    Ok(template.render(context! {
+        since => since.format(&config.date_format).to_string(),
+        until => today.format(&config.date_format).to_string(),
+        stale_days => config.stale_days,
+        authors => authors,
+        vars => config.templates.vars,
+    })?)
+}
+
+struct Window {
+    since: NaiveDate,
+    stale_before: NaiveDate,
+}
+
+impl Window {
+    fn contains(&self, date: &str) -> bool {
+        parse_date(date).is_some_and(|d| d >= self.since)
+    }
+
+    /// Adds one log type's activity. Entries still in `open_status` are reported
+    /// as stale when neither they nor their status have changed for `stale_days`.
+    fn collect<T: ResearchLog>(
+        &self,
+        manager: &LogManager<T>,
+        open_status: &str,
+        activity: &mut BTreeMap<String, AuthorActivity>,
+    ) -> Result<()> {
+        for log in manager.list_logs(&ListFilter::default())? {
+            let base = log.base();
+            let status = log.status().to_string();
+            let short_id = base.id.to_string()[..8].to_string();
+            let author = activity
+                .entry(base.created_by.name.clone())
+                .or_insert_with(|| AuthorActivity {
+                    name: base.created_by.name.clone(),
+                    ..Default::default()
+                });
+            let entry = |date: &str| ReportEntry {
+                id: short_id.clone(),
+                kind: T::KIND,
+                title: base.title.clone(),
+                status: status.clone(),
+                date: date.to_string(),
+            };
+
+            let created = base.created_date().to_string();
+            if self.contains(&created) {
+                author.created.push(entry(&created));
+            }
+
+            for change in base.history.iter().filter(|c| self.contains(&c.date)) {
+                if T::KIND == LiteratureLog::KIND
+                    && change.to == LiteratureStatus::Completed.to_string()
+                {
+                    author.completed.push(entry(&change.date));
+                }
+                author.transitions.push(ReportTransition {
+                    id: short_id.clone(),
+                    kind: T::KIND,
+                    title: base.title.clone(),
+                    from: change.from.clone(),
+                    to: change.to.clone(),
+                    reason: change.reason.clone(),
+                    date: change.date.clone(),
+                });
+            }
+
+            let last_activity = base
+                .history
+                .iter()
+                .map(|c| c.date.as_str())
+                .chain(std::iter::once(created.as_str()))
+                .max()
+                .unwrap_or_default();
+            let idle = parse_date(last_activity).is_some_and(|d| d < self.stale_before);
+            if status == open_status && idle {
+                author.stale.push(entry(last_activity));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_since() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        assert_eq!(
+            parse_since("7d", today).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 3, 8).unwrap()
+        );
+        assert_eq!(
+            parse_since("2w", today).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+        );
+        assert_eq!(
+            parse_since("2025-01-31", today).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 1, 31).unwrap()
+        );
+        assert!(parse_since("soon", today).is_err());
+    }
+}
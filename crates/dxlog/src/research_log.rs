@@ -4,10 +4,49 @@ use crate::{
 };
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+/// The statuses entries of a log type can have.
+pub trait LogStatus: ToString + Clone + Sized {
+    /// Every status in a repository using `config`, in workflow order.
+    fn all(config: &Config) -> Vec<Self>;
+
+    /// The status called `name`, in any letter case and with `_` for `-`.
+    fn parse(config: &Config, name: &str) -> Result<Self> {
+        let normalize = |s: &str| s.replace('_', "-").to_lowercase();
+        let statuses = Self::all(config);
+        statuses
+            .iter()
+            .find(|status| normalize(&status.to_string()) == normalize(name))
+            .cloned()
+            .ok_or_else(|| {
+                let expected: Vec<String> = statuses.iter().map(ToString::to_string).collect();
+                anyhow::anyhow!(
+                    "Invalid value '{}', expected one of: {}",
+                    name,
+                    expected.join(", ")
+                )
+            })
+    }
+}
+
+/// Log types with a fixed set of statuses.
+impl<S: clap::ValueEnum + ToString> LogStatus for S {
+    fn all(_config: &Config) -> Vec<Self> {
+        S::value_variants().to_vec()
+    }
+}
+
+pub trait ResearchLog: Serialize + for<'de> Deserialize<'de> + Send {
+    type Status: LogStatus;
 
-pub trait ResearchLog: Serialize + for<'de> Deserialize<'de> {
-    type Status: ToString;
+    /// Name of the log type, e.g. `"hypothesis"`.
+    const KIND: &'static str;
+    /// Prefix of the entries' handles, e.g. `"H"` for `H-042`.
+    const HANDLE_PREFIX: &'static str;
 
     fn base(&self) -> &BaseLog;
     fn base_mut(&mut self) -> &mut BaseLog;
@@ -15,5 +54,32 @@ pub trait ResearchLog: Serialize + for<'de> Deserialize<'de> {
     fn status_mut(&mut self) -> &mut Self::Status;
     fn new(title: String, tags: HashSet<String>, author: Author) -> Self;
     fn update_status(&mut self, new_status: Self::Status);
-    fn get_target_path(&self, config: &Config, current_path: &PathBuf) -> Result<PathBuf>;
+    /// Whether the work the entry records is done, so references can rely on it.
+    /// Entries that only record what happened, like journal days, always are.
+    fn is_complete(&self) -> bool {
+        true
+    }
+    fn get_target_path(&self, config: &Config, current_path: &Path) -> Result<PathBuf>;
+}
+
+/// An entry of any log type, built in or declared in `[types]`, as hooks and
+/// notifications describe it.
+pub(crate) trait LogEntry: Serialize {
+    fn kind_name(&self) -> &str;
+    fn base_log(&self) -> &BaseLog;
+    fn status_name(&self) -> String;
+}
+
+impl<T: ResearchLog> LogEntry for T {
+    fn kind_name(&self) -> &str {
+        T::KIND
+    }
+
+    fn base_log(&self) -> &BaseLog {
+        self.base()
+    }
+
+    fn status_name(&self) -> String {
+        self.status().to_string()
+    }
 }
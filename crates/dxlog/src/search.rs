@@ -0,0 +1,181 @@
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    config::EXPECTED_TYPES,
+    load_config,
+    log_manager::LogManager,
+    pdf_text::attachment_text,
+    research_log::{LogEntry, ResearchLog},
+    utils::{BaseLog, TagFilter},
+    Config, CustomLogManager, DatasetLog, DatasetManager, ExperimentLog, ExperimentManager,
+    HypothesisLog, HypothesisManager, IdeaLog, IdeaManager, JournalLog, JournalManager,
+    KnowledgeLog, KnowledgeManager, ListFilter, LiteratureLog, LiteratureManager, LogTypeRegistry,
+    MeetingLog, MeetingManager,
+};
+
+/// An entry matching a [`search_logs`] query.
+#[derive(Debug, Serialize, Clone)]
+pub struct SearchHit {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub title: String,
+    pub status: String,
+    pub created_at: DateTime<FixedOffset>,
+    pub tags: Vec<String>,
+}
+
+impl SearchHit {
+    pub(crate) fn new<E: LogEntry>(log: &E) -> Self {
+        let base = log.base_log();
+        let mut tags: Vec<String> = base.tags.iter().cloned().collect();
+        tags.sort();
+        Self {
+            id: base.id,
+            handle: base.handle.clone(),
+            kind: log.kind_name().to_string(),
+            title: base.title.clone(),
+            status: log.status_name(),
+            created_at: base.created_at,
+            tags,
+        }
+    }
+}
+
+/// Finds entries whose title, tags, body or the extracted text of their
+/// attached PDFs contain `query`, ignoring case.
+///
+/// `kind` restricts the search to one log type (`"hypothesis"`, `"literature"`,
+/// `"knowledge"`, `"journal"`, `"dataset"`, `"meeting"`, `"idea"` or `"experiment"`). Hits are returned newest first.
+pub fn search_logs(query: &str, kind: Option<&str>) -> Result<Vec<SearchHit>> {
+    search(&load_config()?, query, kind)
+}
+
+/// Like [`search_logs`], in the repository of `config`.
+pub fn search_logs_in(config: &Config, query: &str, kind: Option<&str>) -> Result<Vec<SearchHit>> {
+    search(config, query, kind)
+}
+
+/// Fails unless `kind` is `None` or the name of a log type, built in or
+/// declared in `[types]`.
+pub(crate) fn check_kind(config: &Config, kind: Option<&str>) -> Result<()> {
+    if let Some(kind) = kind {
+        if LogTypeRegistry::new(config).get(kind).is_none() {
+            return Err(anyhow::anyhow!(
+                "Unknown log type '{}'. Expected {}",
+                kind,
+                EXPECTED_TYPES
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn search(config: &Config, query: &str, kind: Option<&str>) -> Result<Vec<SearchHit>> {
+    check_kind(config, kind)?;
+    let query = query.to_lowercase();
+    let wanted = |k: &str| kind.is_none_or(|kind| kind == k);
+
+    let mut hits = Vec::new();
+    if wanted(HypothesisLog::KIND) {
+        search_in(
+            &HypothesisManager::new(config.clone()).manager,
+            &query,
+            &mut hits,
+        )?;
+    }
+    if wanted(LiteratureLog::KIND) {
+        search_in(
+            &LiteratureManager::new(config.clone()).manager,
+            &query,
+            &mut hits,
+        )?;
+    }
+    if wanted(KnowledgeLog::KIND) {
+        search_in(
+            &KnowledgeManager::new(config.clone()).manager,
+            &query,
+            &mut hits,
+        )?;
+    }
+    if wanted(JournalLog::KIND) {
+        search_in(
+            &JournalManager::new(config.clone()).manager,
+            &query,
+            &mut hits,
+        )?;
+    }
+    if wanted(DatasetLog::KIND) {
+        search_in(
+            &DatasetManager::new(config.clone()).manager,
+            &query,
+            &mut hits,
+        )?;
+    }
+    if wanted(MeetingLog::KIND) {
+        search_in(
+            &MeetingManager::new(config.clone()).manager,
+            &query,
+            &mut hits,
+        )?;
+    }
+    if wanted(IdeaLog::KIND) {
+        search_in(&IdeaManager::new(config.clone()).manager, &query, &mut hits)?;
+    }
+    if wanted(ExperimentLog::KIND) {
+        search_in(
+            &ExperimentManager::new(config.clone()).manager,
+            &query,
+            &mut hits,
+        )?;
+    }
+    for name in config.types.keys().filter(|name| wanted(name)) {
+        let manager = CustomLogManager::new(config.clone(), name)?;
+        for (log, path) in manager.list(None, &TagFilter::default())? {
+            if is_hit(config, &log.base, &query, || manager.read_body(&path))? {
+                hits.push(SearchHit::new(&log));
+            }
+        }
+    }
+
+    hits.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    Ok(hits)
+}
+
+/// Whether the title, tags, body or attached PDFs of an entry contain `query`.
+/// The body is only read if the title and tags don't.
+fn is_hit(
+    config: &Config,
+    base: &BaseLog,
+    query: &str,
+    body: impl FnOnce() -> Result<String>,
+) -> Result<bool> {
+    Ok(base.title.to_lowercase().contains(query)
+        || base.tags.iter().any(|t| t.to_lowercase().contains(query))
+        || body()?.to_lowercase().contains(query)
+        || attachment_text(config, base).to_lowercase().contains(query))
+}
+
+fn search_in<T: ResearchLog>(
+    manager: &LogManager<T>,
+    query: &str,
+    hits: &mut Vec<SearchHit>,
+) -> Result<()> {
+    for (log, path) in manager.list_log_files(&ListFilter::default())? {
+        if is_hit(&manager.config, log.base(), query, || {
+            manager.read_body(&path)
+        })? {
+            hits.push(SearchHit::new(&log));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,326 @@
+//! Semantic search, ranking entries by how close the embedding of their text
+//! is to the query's. Embeddings come from the API configured under `[llm]`
+//! and are kept under `.dxlog`, so only new and changed entries are sent again.
+
+use anyhow::{Context, Result};
+use dxlog_tools::embed;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::{
+    load_config,
+    log_manager::LogManager,
+    research_log::{LogEntry, ResearchLog},
+    search::check_kind,
+    utils::{self, TagFilter},
+    Config, CustomLogManager, DatasetManager, ExperimentManager, HypothesisManager, IdeaManager,
+    JournalManager, KnowledgeManager, ListFilter, LiteratureManager, MeetingManager, SearchHit,
+};
+
+/// File, relative to the repository root, embeddings are kept in.
+pub const EMBEDDINGS_FILE: &str = ".dxlog/embeddings.json";
+
+/// Characters of an entry that are embedded: the start of long entries, within
+/// the input limits of embedding models.
+const MAX_TEXT_CHARS: usize = 8_000;
+
+/// An entry found by [`semantic_search`].
+#[derive(Debug, Serialize, Clone)]
+pub struct SemanticHit {
+    #[serde(flatten)]
+    pub entry: SearchHit,
+    /// Cosine similarity of the entry to the query, up to 1.
+    pub score: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Store {
+    /// Model the vectors were made with; they are all made again when it changes.
+    model: String,
+    entries: HashMap<Uuid, Stored>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Stored {
+    /// Hash of the text the vector was made from.
+    sha256: String,
+    vector: Vec<f32>,
+}
+
+/// An entry with the text that is embedded for it.
+struct Document {
+    hit: SearchHit,
+    text: String,
+}
+
+/// Finds the `limit` entries closest in meaning to `query`, best first.
+///
+/// `kind` restricts the results to one log type. Entries that are new or
+/// changed since the last search are embedded first; the bodies of encrypted
+/// entries are left out. `api_key` defaults to the one `[llm]` points at.
+pub fn semantic_search(
+    query: &str,
+    kind: Option<&str>,
+    limit: usize,
+    api_key: Option<&str>,
+) -> Result<Vec<SemanticHit>> {
+    semantic_search_in(&load_config()?, query, kind, limit, api_key)
+}
+
+/// Like [`semantic_search`], in the repository of `config`.
+pub fn semantic_search_in(
+    config: &Config,
+    query: &str,
+    kind: Option<&str>,
+    limit: usize,
+    api_key: Option<&str>,
+) -> Result<Vec<SemanticHit>> {
+    check_kind(config, kind)?;
+    let api_key = api_key.map(str::to_string).or_else(|| config.llm.api_key());
+    let documents = documents(config)?;
+    let (store, _) = update_store(config, &documents, api_key.as_deref())?;
+
+    let query = embed(
+        config.llm.endpoint(),
+        api_key.as_deref(),
+        config.llm.embedding_model(),
+        &[query.to_string()],
+    )?
+    .pop()
+    .context("The API returned no embedding for the query")?;
+
+    let mut hits: Vec<SemanticHit> = documents
+        .into_iter()
+        .filter(|document| kind.is_none_or(|kind| document.hit.kind == kind))
+        .filter_map(|document| {
+            let stored = store.entries.get(&document.hit.id)?;
+            Some(SemanticHit {
+                score: cosine(&query, &stored.vector),
+                entry: document.hit,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+/// Embeds the entries that are new or changed since the last search, as
+/// [`semantic_search`] does first, and returns how many there were.
+pub fn update_embeddings(api_key: Option<&str>) -> Result<usize> {
+    let config = load_config()?;
+    let api_key = api_key.map(str::to_string).or_else(|| config.llm.api_key());
+    let documents = documents(&config)?;
+    let (_, embedded) = update_store(&config, &documents, api_key.as_deref())?;
+    Ok(embedded)
+}
+
+fn documents(config: &Config) -> Result<Vec<Document>> {
+    let mut documents = Vec::new();
+    collect(
+        &HypothesisManager::new(config.clone()).manager,
+        &mut documents,
+    )?;
+    collect(
+        &LiteratureManager::new(config.clone()).manager,
+        &mut documents,
+    )?;
+    collect(
+        &KnowledgeManager::new(config.clone()).manager,
+        &mut documents,
+    )?;
+    collect(&JournalManager::new(config.clone()).manager, &mut documents)?;
+    collect(&DatasetManager::new(config.clone()).manager, &mut documents)?;
+    collect(&MeetingManager::new(config.clone()).manager, &mut documents)?;
+    collect(&IdeaManager::new(config.clone()).manager, &mut documents)?;
+    collect(
+        &ExperimentManager::new(config.clone()).manager,
+        &mut documents,
+    )?;
+    for name in config.types.keys() {
+        let manager = CustomLogManager::new(config.clone(), name)?;
+        for (log, path) in manager.list(None, &TagFilter::default())? {
+            documents.push(document(&log, || manager.read_body(&path))?);
+        }
+    }
+    Ok(documents)
+}
+
+fn collect<T: ResearchLog>(manager: &LogManager<T>, documents: &mut Vec<Document>) -> Result<()> {
+    for (log, path) in manager.list_log_files(&ListFilter::default())? {
+        documents.push(document(&log, || manager.read_body(&path))?);
+    }
+    Ok(())
+}
+
+/// The document of `log`, with its body unless the entry is encrypted.
+fn document<E: LogEntry>(log: &E, body: impl FnOnce() -> Result<String>) -> Result<Document> {
+    let hit = SearchHit::new(log);
+    let mut text = format!("{}\n{}", hit.title, hit.tags.join(", "));
+    if !log.base_log().encrypted {
+        text.push_str("\n\n");
+        text.push_str(&body()?);
+    }
+    let text = text.chars().take(MAX_TEXT_CHARS).collect();
+    Ok(Document { hit, text })
+}
+
+fn sha256(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+/// The documents whose text has no stored vector, or a vector made from
+/// other text, with the hash of their text.
+fn stale<'a>(store: &Store, documents: &'a [Document]) -> Vec<(&'a Document, String)> {
+    documents
+        .iter()
+        .map(|document| (document, sha256(&document.text)))
+        .filter(|(document, hash)| {
+            store
+                .entries
+                .get(&document.hit.id)
+                .is_none_or(|stored| &stored.sha256 != hash)
+        })
+        .collect()
+}
+
+/// Brings the stored embeddings up to date with `documents`: new and changed
+/// entries are embedded and those no longer there are dropped. Returns the
+/// store with the number of entries embedded.
+fn update_store(
+    config: &Config,
+    documents: &[Document],
+    api_key: Option<&str>,
+) -> Result<(Store, usize)> {
+    let model = config.llm.embedding_model();
+    let mut store = load(config)
+        .filter(|store| store.model == model)
+        .unwrap_or_else(|| Store {
+            model: model.to_string(),
+            entries: HashMap::new(),
+        });
+
+    let ids: HashSet<Uuid> = documents.iter().map(|document| document.hit.id).collect();
+    let count = store.entries.len();
+    store.entries.retain(|id, _| ids.contains(id));
+    let mut changed = store.entries.len() != count;
+
+    let stale = stale(&store, documents);
+    let embedded = stale.len();
+    if !stale.is_empty() {
+        debug!(entries = stale.len(), "Embedding new and changed entries");
+        let texts: Vec<String> = stale
+            .iter()
+            .map(|(document, _)| document.text.clone())
+            .collect();
+        let vectors = embed(config.llm.endpoint(), api_key, model, &texts)?;
+        for ((document, sha256), vector) in stale.into_iter().zip(vectors) {
+            store
+                .entries
+                .insert(document.hit.id, Stored { sha256, vector });
+        }
+        changed = true;
+    }
+
+    if changed {
+        if let Err(err) = save(config, &store) {
+            warn!("Could not save the embeddings: {:#}", err);
+        }
+    }
+    Ok((store, embedded))
+}
+
+/// The stored embeddings, or `None` if there are none yet or they cannot be
+/// read, in which case they are made again.
+fn load(config: &Config) -> Option<Store> {
+    let path = config.root.join(EMBEDDINGS_FILE);
+    let content = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(store) => Some(store),
+        Err(err) => {
+            warn!(path = %path.display(), "Ignoring unreadable embeddings: {}", err);
+            None
+        }
+    }
+}
+
+/// Writes the embeddings. They are a cache rather than a change to entries,
+/// so this bypasses dry-run mode.
+fn save(config: &Config, store: &Store) -> Result<()> {
+    if config.root.as_os_str().is_empty() {
+        return Ok(());
+    }
+    let path = config.root.join(EMBEDDINGS_FILE);
+    if let Some(parent) = path.parent() {
+        utils::ensure_directory(parent)?;
+    }
+    debug!(path = %path.display(), entries = store.entries.len(), "Writing embeddings");
+    fs::write(&path, serde_json::to_string(store)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(title: &str) -> Document {
+        Document {
+            hit: SearchHit {
+                id: Uuid::new_v4(),
+                handle: None,
+                kind: "hypothesis".to_string(),
+                title: title.to_string(),
+                status: "active".to_string(),
+                created_at: utils::now(),
+                tags: Vec::new(),
+            },
+            text: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_stale() {
+        let documents = vec![document("Kept"), document("Edited"), document("New")];
+        let mut store = Store {
+            model: "m".to_string(),
+            entries: HashMap::new(),
+        };
+        store.entries.insert(
+            documents[0].hit.id,
+            Stored {
+                sha256: sha256("Kept"),
+                vector: vec![1.0],
+            },
+        );
+        store.entries.insert(
+            documents[1].hit.id,
+            Stored {
+                sha256: sha256("Before the edit"),
+                vector: vec![1.0],
+            },
+        );
+
+        let titles: Vec<&str> = stale(&store, &documents)
+            .into_iter()
+            .map(|(document, _)| document.hit.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Edited", "New"]);
+    }
+}
@@ -0,0 +1,417 @@
+//! `dxlog sync`: commits changed entries, pulls with rebase and pushes,
+//! resolving conflicts that only touch the frontmatter of an entry.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
+use git2::{Repository, RepositoryState, Status, StatusOptions};
+use serde_yaml::{Mapping, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    backup::snapshot_dirs,
+    dry_run::is_dry_run,
+    handle::COUNTERS_FILE,
+    load_config,
+    md_frontmatter::{extract_frontmatter, update_markdown_frontmatter},
+    utils, Config,
+};
+
+/// What [`sync_repository`] pulls from and pushes to.
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Remote to sync the current branch with; the branch's upstream if `None`.
+    pub remote: Option<String>,
+    /// Message of the commit of local changes.
+    pub message: Option<String>,
+    /// Whether to push once the pull went through.
+    pub push: bool,
+}
+
+/// An entry whose versions on the remote and in a local commit only differ
+/// in their frontmatter, with the merge [`sync_repository`] offers.
+#[derive(Debug, Clone)]
+pub struct FrontmatterConflict {
+    pub path: PathBuf,
+    pub remote_status: Option<String>,
+    pub local_status: Option<String>,
+    /// Status of the version updated last, which the merge keeps.
+    pub status: Option<String>,
+    merged: String,
+}
+
+/// What [`sync_repository`] did.
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    /// Changed files committed before pulling; in dry-run mode, those that would be.
+    pub committed: Vec<PathBuf>,
+    /// Conflicting entries resolved by merging their frontmatter.
+    pub resolved: Vec<PathBuf>,
+    /// Conflicting files left to resolve by hand. The rebase stays in progress.
+    pub unresolved: Vec<PathBuf>,
+    pub pushed: bool,
+}
+
+/// Files dxlog keeps entries and their state in, which sync commits.
+fn synced_paths(config: &Config) -> Vec<PathBuf> {
+    let mut paths = snapshot_dirs(config);
+    paths.extend([
+        config.storage.attachments_dir.clone(),
+        config.storage.trash_dir.clone(),
+        config.storage.milestones_file.clone(),
+        config.root.join(COUNTERS_FILE),
+    ]);
+    paths
+}
+
+fn changed_paths(config: &Config, repo: &Repository, workdir: &Path) -> Result<Vec<PathBuf>> {
+    let synced: Vec<PathBuf> = synced_paths(config)
+        .iter()
+        .map(|path| absolute(path))
+        .collect();
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+    let mut changed: Vec<PathBuf> = repo
+        .statuses(Some(&mut options))?
+        .iter()
+        .filter(|entry| entry.status() != Status::CURRENT)
+        .filter_map(|entry| entry.path().map(|path| workdir.join(path)))
+        .filter(|path| synced.iter().any(|dir| path.starts_with(dir)))
+        .collect();
+    changed.sort();
+    Ok(changed)
+}
+
+/// `path` with its directories resolved, so it compares with paths below the
+/// git working directory even where it does not exist.
+fn absolute(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
+    match (path.parent().map(absolute), path.file_name()) {
+        (Some(dir), Some(name)) => dir.join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn git(workdir: &Path, args: &[&str]) -> Result<std::process::Output> {
+    Command::new("git")
+        .current_dir(workdir)
+        .args(args)
+        // `rebase --continue` must not open an editor for the commit message
+        .env("GIT_EDITOR", "true")
+        .output()
+        .context("Failed to run git. Make sure it is installed")
+}
+
+fn run_git(workdir: &Path, args: &[&str]) -> Result<()> {
+    let output = git(workdir, args)?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn rebase_in_progress(repo: &Repository) -> bool {
+    matches!(
+        repo.state(),
+        RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge
+            | RepositoryState::ApplyMailboxOrRebase
+    )
+}
+
+/// The branch checked out, or being rebased while a rebase is in progress.
+fn current_branch(repo: &Repository, root: &Path) -> Result<String> {
+    let rebasing = ["rebase-merge/head-name", "rebase-apply/head-name"]
+        .iter()
+        .find_map(|file| fs::read_to_string(repo.path().join(file)).ok());
+    if let Some(head_name) = rebasing {
+        let head_name = head_name.trim();
+        return Ok(head_name
+            .strip_prefix("refs/heads/")
+            .unwrap_or(head_name)
+            .to_string());
+    }
+    utils::head_state(root)?
+        .branch
+        .context("Cannot sync a detached HEAD; check out a branch first")
+}
+
+/// A path in conflict during a rebase step.
+struct Conflict {
+    /// Relative to the git working directory.
+    path: PathBuf,
+    /// The remote and local versions, where both exist.
+    versions: Option<(String, String)>,
+}
+
+/// The conflicts of the rebase step in progress.
+///
+/// While rebasing, "ours" is the upstream commit being rebased onto and
+/// "theirs" the local commit being replayed.
+fn conflicts(repo: &Repository) -> Result<Vec<Conflict>> {
+    let index = repo.index()?;
+    let blob = |entry: &Option<git2::IndexEntry>| {
+        entry.as_ref().and_then(|entry| {
+            let blob = repo.find_blob(entry.id).ok()?;
+            String::from_utf8(blob.content().to_vec()).ok()
+        })
+    };
+    let mut found = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let Some(path) = [&conflict.our, &conflict.their, &conflict.ancestor]
+            .into_iter()
+            .flatten()
+            .next()
+            .map(|entry| PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned()))
+        else {
+            continue;
+        };
+        found.push(Conflict {
+            path,
+            versions: blob(&conflict.our).zip(blob(&conflict.their)),
+        });
+    }
+    Ok(found)
+}
+
+fn updated_at(frontmatter: &Mapping) -> Option<DateTime<FixedOffset>> {
+    frontmatter
+        .get("updated_at")
+        .and_then(Value::as_str)
+        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+}
+
+fn status(frontmatter: &Mapping) -> Option<String> {
+    frontmatter
+        .get("status")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// The items of both lists under `key`, those of `first` first.
+fn union(first: &Mapping, second: &Mapping, key: &str) -> Option<Value> {
+    let items = |frontmatter: &Mapping| {
+        frontmatter
+            .get(key)
+            .and_then(Value::as_sequence)
+            .cloned()
+            .unwrap_or_default()
+    };
+    if !first.contains_key(key) && !second.contains_key(key) {
+        return None;
+    }
+    let mut merged = items(first);
+    for item in items(second) {
+        if !merged.contains(&item) {
+            merged.push(item);
+        }
+    }
+    Some(Value::Sequence(merged))
+}
+
+/// Merges two versions of an entry with the same body: tags and references
+/// are combined, everything else, the status included, is taken from the
+/// version updated last. `None` if the bodies differ or either version
+/// cannot be read.
+fn merge_frontmatter(path: &Path, remote: &str, local: &str) -> Option<FrontmatterConflict> {
+    let (remote, remote_body) = extract_frontmatter::<Mapping>(remote).ok()?;
+    let (local, local_body) = extract_frontmatter::<Mapping>(local).ok()?;
+    if remote_body != local_body {
+        return None;
+    }
+
+    let (latest, other) = if updated_at(&local) >= updated_at(&remote) {
+        (&local, &remote)
+    } else {
+        (&remote, &local)
+    };
+    let mut merged = latest.clone();
+    for key in ["tags", "references"] {
+        if let Some(items) = union(latest, other, key) {
+            merged.insert(Value::from(key), items);
+        }
+    }
+    let merged = update_markdown_frontmatter(&merged, &local_body).ok()?;
+
+    Some(FrontmatterConflict {
+        path: path.to_path_buf(),
+        remote_status: status(&remote),
+        local_status: status(&local),
+        status: status(latest),
+        merged,
+    })
+}
+
+/// Resolves the conflicts of the rebase step in progress, asking `resolve`
+/// about each frontmatter merge. Returns the paths left unresolved.
+fn resolve_conflicts(
+    repo: &Repository,
+    workdir: &Path,
+    entry_dirs: &[PathBuf],
+    resolve: &mut impl FnMut(&FrontmatterConflict) -> Result<bool>,
+    report: &mut SyncReport,
+) -> Result<Vec<PathBuf>> {
+    let mut unresolved = Vec::new();
+    let mut index = repo.index()?;
+    for Conflict {
+        path: relative,
+        versions,
+    } in conflicts(repo)?
+    {
+        let path = workdir.join(&relative);
+        let is_entry = path.extension().is_some_and(|ext| ext == "md")
+            && entry_dirs.iter().any(|dir| path.starts_with(dir));
+        let conflict = versions
+            .filter(|_| is_entry)
+            .and_then(|(remote, local)| merge_frontmatter(&path, &remote, &local));
+        match conflict {
+            Some(conflict) if resolve(&conflict)? => {
+                fs::write(&path, &conflict.merged)
+                    .with_context(|| format!("Failed to write file: {}", path.display()))?;
+                index.add_path(&relative)?;
+                report.resolved.push(path);
+            }
+            _ => unresolved.push(path),
+        }
+    }
+    index.write()?;
+    Ok(unresolved)
+}
+
+/// Commits changed entries, pulls with rebase and, if `options.push` is set,
+/// pushes the result.
+///
+/// Rebase conflicts in entries whose versions only differ in frontmatter are
+/// merged, if `resolve` agrees: tags and references are combined and the
+/// rest is taken from the version updated last. Other conflicts are left for
+/// the user, with the rebase in progress; running sync again once they are
+/// resolved continues it. In dry-run mode, only the changed files are listed.
+pub fn sync_repository(
+    options: &SyncOptions,
+    mut resolve: impl FnMut(&FrontmatterConflict) -> Result<bool>,
+) -> Result<SyncReport> {
+    let config = load_config()?;
+    let repo = utils::open_repository(&config.root)?;
+    let workdir = repo
+        .workdir()
+        .context("Cannot sync a bare git repository")?
+        .to_path_buf();
+    let workdir = absolute(&workdir);
+    let mut report = SyncReport::default();
+
+    let branch = match &options.remote {
+        Some(_) => current_branch(&repo, &config.root)?,
+        None => String::new(),
+    };
+    let target: Vec<&str> = match &options.remote {
+        Some(remote) => vec![remote, &branch],
+        None => Vec::new(),
+    };
+
+    if !rebase_in_progress(&repo) {
+        report.committed = changed_paths(&config, &repo, &workdir)?;
+        if is_dry_run() {
+            return Ok(report);
+        }
+        if !report.committed.is_empty() {
+            let message = options.message.clone().unwrap_or_else(|| {
+                format!(
+                    "dxlog sync: {} changed file{}",
+                    report.committed.len(),
+                    if report.committed.len() == 1 { "" } else { "s" }
+                )
+            });
+            utils::commit_changes(&report.committed, &message)?;
+        }
+
+        let output = git(
+            &workdir,
+            &[&["pull", "--rebase"], target.as_slice()].concat(),
+        )?;
+        if !output.status.success() && !rebase_in_progress(&Repository::open(&workdir)?) {
+            return Err(anyhow::anyhow!(
+                "git pull --rebase failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    } else if is_dry_run() {
+        return Ok(report);
+    }
+
+    let entry_dirs: Vec<PathBuf> = snapshot_dirs(&config)
+        .iter()
+        .map(|dir| absolute(dir))
+        .collect();
+    loop {
+        // git changes the index and state behind our back, so reopen
+        let repo = Repository::open(&workdir)?;
+        if !rebase_in_progress(&repo) {
+            break;
+        }
+        report.unresolved =
+            resolve_conflicts(&repo, &workdir, &entry_dirs, &mut resolve, &mut report)?;
+        if !report.unresolved.is_empty() {
+            return Ok(report);
+        }
+        let output = git(&workdir, &["rebase", "--continue"])?;
+        if !output.status.success() && conflicts(&Repository::open(&workdir)?)?.is_empty() {
+            return Err(anyhow::anyhow!(
+                "git rebase --continue failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+
+    if options.push {
+        run_git(&workdir, &[&["push"], target.as_slice()].concat())?;
+        report.pushed = true;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_frontmatter() {
+        let remote = "---\nid: 1\nupdated_at: 2026-03-02T10:00:00Z\ntitle: Surface codes\n\
+                      tags:\n- qec\nreferences:\n- id: 01a14754-8505-77d3-8f46-39d78666b5b8\n\
+                      status: Proven\n---\n# Surface codes\n";
+        let local = "---\nid: 1\nupdated_at: 2026-03-01T10:00:00Z\ntitle: Surface codes\n\
+                     tags:\n- codes\n- qec\nreferences: []\nstatus: Active\n---\n# Surface codes\n";
+        let path = Path::new("logs/surface-codes.md");
+
+        let conflict = merge_frontmatter(path, remote, local).unwrap();
+        assert_eq!(conflict.remote_status.as_deref(), Some("Proven"));
+        assert_eq!(conflict.local_status.as_deref(), Some("Active"));
+        // The remote version was updated last
+        assert_eq!(conflict.status.as_deref(), Some("Proven"));
+        let (merged, body) = extract_frontmatter::<Mapping>(&conflict.merged).unwrap();
+        assert_eq!(body, "# Surface codes");
+        assert_eq!(
+            merged.get("tags"),
+            Some(&serde_yaml::from_str("[qec, codes]").unwrap())
+        );
+        assert_eq!(merged["references"].as_sequence().map(Vec::len), Some(1));
+        assert_eq!(merged["updated_at"], "2026-03-02T10:00:00Z");
+
+        // Different bodies are not merged
+        let edited = local.replace("# Surface codes\n", "# Surface codes\n\nThreshold.\n");
+        assert!(merge_frontmatter(path, remote, &edited).is_none());
+    }
+}
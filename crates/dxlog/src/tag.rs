@@ -0,0 +1,277 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    backup::{self, Backup},
+    load_config,
+    log_manager::LogManager,
+    research_log::ResearchLog,
+    utils::{self, TagFilter},
+    Config, DatasetManager, ExperimentManager, HypothesisManager, IdeaManager, JournalManager,
+    KnowledgeManager, ListFilter, LiteratureManager, MeetingManager,
+};
+
+/// Outcome of [`rename_tag`].
+#[derive(Debug, Serialize)]
+pub struct TagRename {
+    /// Files of the entries that were retagged.
+    pub entries: Vec<PathBuf>,
+    /// Snapshot taken before any entry was changed; none if no entry had the tag.
+    pub backup: Option<Backup>,
+}
+
+fn tagged<T: ResearchLog>(manager: &LogManager<T>, tag: &str) -> Result<Vec<(T, PathBuf)>> {
+    manager.list_log_files(&ListFilter {
+        tags: TagFilter::all([tag]),
+        ..Default::default()
+    })
+}
+
+fn retag<T: ResearchLog>(
+    manager: &LogManager<T>,
+    entries: Vec<(T, PathBuf)>,
+    old: &str,
+    new: &str,
+    renamed: &mut Vec<PathBuf>,
+) -> Result<()> {
+    for (mut log, path) in entries {
+        let tags = &mut log.base_mut().tags;
+        *tags = tags
+            .drain()
+            .map(|tag| match tag.strip_prefix(old) {
+                Some(rest) if utils::is_tag_within(&tag, old) => format!("{}{}", new, rest),
+                _ => tag,
+            })
+            .collect();
+        manager.update_log(&mut log, &path)?;
+        renamed.push(path);
+    }
+    Ok(())
+}
+
+/// Replaces the tag `old` with `new` on every entry, merging the two where an
+/// entry already has both. Descendants of `old` move along, so renaming `ml` to
+/// `learning` turns `ml/transformers` into `learning/transformers`. The entry
+/// directories are backed up first.
+pub fn rename_tag(old: &str, new: &str) -> Result<TagRename> {
+    let old = utils::normalize_tag(old).unwrap_or_default();
+    let new = match utils::normalize_tag(new) {
+        Some(new) if !new.contains(char::is_whitespace) => new,
+        _ => return Err(anyhow::anyhow!("Invalid tag '{}'", new.trim())),
+    };
+    let (old, new) = (old.as_str(), new.as_str());
+    if old == new {
+        return Err(anyhow::anyhow!("The tag is already called '{}'", new));
+    }
+
+    let config = load_config()?;
+    let hypotheses = HypothesisManager::new(config.clone()).manager;
+    let literature = LiteratureManager::new(config.clone()).manager;
+    let knowledge = KnowledgeManager::new(config.clone()).manager;
+    let journals = JournalManager::new(config.clone()).manager;
+    let datasets = DatasetManager::new(config.clone()).manager;
+    let meetings = MeetingManager::new(config.clone()).manager;
+    let ideas = IdeaManager::new(config.clone()).manager;
+    let experiments = ExperimentManager::new(config.clone()).manager;
+
+    let tagged_hypotheses = tagged(&hypotheses, old)?;
+    let tagged_literature = tagged(&literature, old)?;
+    let tagged_knowledge = tagged(&knowledge, old)?;
+    let tagged_journals = tagged(&journals, old)?;
+    let tagged_datasets = tagged(&datasets, old)?;
+    let tagged_meetings = tagged(&meetings, old)?;
+    let tagged_ideas = tagged(&ideas, old)?;
+    let tagged_experiments = tagged(&experiments, old)?;
+    let count = tagged_hypotheses.len()
+        + tagged_literature.len()
+        + tagged_knowledge.len()
+        + tagged_journals.len()
+        + tagged_datasets.len()
+        + tagged_meetings.len()
+        + tagged_ideas.len()
+        + tagged_experiments.len();
+    if count == 0 {
+        return Ok(TagRename {
+            entries: Vec::new(),
+            backup: None,
+        });
+    }
+
+    let backup = backup::create(&config, Some("tag-rename"))?;
+    let mut entries = Vec::with_capacity(count);
+    retag(&hypotheses, tagged_hypotheses, old, new, &mut entries)?;
+    retag(&literature, tagged_literature, old, new, &mut entries)?;
+    retag(&knowledge, tagged_knowledge, old, new, &mut entries)?;
+    retag(&journals, tagged_journals, old, new, &mut entries)?;
+    retag(&datasets, tagged_datasets, old, new, &mut entries)?;
+    retag(&meetings, tagged_meetings, old, new, &mut entries)?;
+    retag(&ideas, tagged_ideas, old, new, &mut entries)?;
+    retag(&experiments, tagged_experiments, old, new, &mut entries)?;
+    Ok(TagRename {
+        entries,
+        backup: Some(backup),
+    })
+}
+
+/// A tag in the tag hierarchy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagNode {
+    /// Last part of the tag, e.g. `attention` for `ml/transformers/attention`.
+    pub name: String,
+    /// The full tag.
+    pub tag: String,
+    /// Entries tagged with this tag or one of its descendants.
+    pub count: usize,
+    pub children: Vec<TagNode>,
+}
+
+/// Arranges the tags of `entries` into a hierarchy, sorted by name.
+fn build_tree<'a>(entries: impl IntoIterator<Item = &'a HashSet<String>>) -> Vec<TagNode> {
+    // Keyed by the parts of each tag, so that children sort right after their
+    // parent, which `ml-ops` would otherwise get between `ml` and `ml/nlp`.
+    let mut counts: BTreeMap<Vec<&str>, usize> = BTreeMap::new();
+    for tags in entries {
+        let mut within = BTreeSet::new();
+        for tag in tags {
+            let parts: Vec<&str> = tag.split('/').filter(|part| !part.is_empty()).collect();
+            within.extend((1..=parts.len()).map(|len| parts[..len].to_vec()));
+        }
+        for parts in within {
+            *counts.entry(parts).or_default() += 1;
+        }
+    }
+
+    let mut roots: Vec<TagNode> = Vec::new();
+    for (parts, count) in counts {
+        let mut siblings = &mut roots;
+        for _ in 1..parts.len() {
+            siblings = &mut siblings
+                .last_mut()
+                .expect("parents sort before their children")
+                .children;
+        }
+        siblings.push(TagNode {
+            name: parts[parts.len() - 1].to_string(),
+            tag: parts.join("/"),
+            count,
+            children: Vec::new(),
+        });
+    }
+    roots
+}
+
+fn entry_tags<T: ResearchLog>(manager: &LogManager<T>) -> Result<Vec<HashSet<String>>> {
+    Ok(manager
+        .list_logs(&ListFilter::default())?
+        .into_iter()
+        .map(|log| log.base().tags.clone())
+        .collect())
+}
+
+fn all_entry_tags(config: &Config) -> Result<Vec<HashSet<String>>> {
+    let mut tags = entry_tags(&HypothesisManager::new(config.clone()).manager)?;
+    tags.extend(entry_tags(&LiteratureManager::new(config.clone()).manager)?);
+    tags.extend(entry_tags(&KnowledgeManager::new(config.clone()).manager)?);
+    tags.extend(entry_tags(&JournalManager::new(config.clone()).manager)?);
+    tags.extend(entry_tags(&DatasetManager::new(config.clone()).manager)?);
+    tags.extend(entry_tags(&MeetingManager::new(config.clone()).manager)?);
+    tags.extend(entry_tags(&IdeaManager::new(config.clone()).manager)?);
+    tags.extend(entry_tags(&ExperimentManager::new(config.clone()).manager)?);
+    Ok(tags)
+}
+
+/// Every tag in use, arranged into a hierarchy by `/`.
+pub fn tag_tree() -> Result<Vec<TagNode>> {
+    let config = load_config()?;
+    Ok(build_tree(&all_entry_tags(&config)?))
+}
+
+/// Every tag in use, sorted.
+pub fn tags_in_use() -> Result<BTreeSet<String>> {
+    let config = load_config()?;
+    Ok(all_entry_tags(&config)?.into_iter().flatten().collect())
+}
+
+fn set_tags<T: ResearchLog>(
+    manager: &LogManager<T>,
+    mut log: T,
+    path: &Path,
+    tags: &[String],
+    replace: bool,
+) -> Result<String> {
+    let current = &mut log.base_mut().tags;
+    if replace {
+        current.clear();
+    }
+    current.extend(tags.iter().filter_map(|tag| utils::normalize_tag(tag)));
+    manager.update_log(&mut log, path)?;
+    Ok(log.base().title.clone())
+}
+
+/// Adds `tags` to an entry, or with `replace` makes them its only tags.
+/// Returns the title of the entry.
+pub fn add_tags(partial_id: &str, tags: &[String], replace: bool) -> Result<String> {
+    let config = load_config()?;
+    let h_manager = HypothesisManager::new(config.clone());
+    let l_manager = LiteratureManager::new(config.clone());
+    let k_manager = KnowledgeManager::new(config.clone());
+    let j_manager = JournalManager::new(config.clone());
+    let d_manager = DatasetManager::new(config.clone());
+    let m_manager = MeetingManager::new(config.clone());
+    let i_manager = IdeaManager::new(config.clone());
+    let e_manager = ExperimentManager::new(config);
+
+    if let Ok((log, path)) = h_manager.find(partial_id) {
+        set_tags(&h_manager.manager, log, &path, tags, replace)
+    } else if let Ok((log, path)) = l_manager.find(partial_id) {
+        set_tags(&l_manager.manager, log, &path, tags, replace)
+    } else if let Ok((log, path)) = k_manager.find(partial_id) {
+        set_tags(&k_manager.manager, log, &path, tags, replace)
+    } else if let Ok((log, path)) = j_manager.find(partial_id) {
+        set_tags(&j_manager.manager, log, &path, tags, replace)
+    } else if let Ok((log, path)) = d_manager.find(partial_id) {
+        set_tags(&d_manager.manager, log, &path, tags, replace)
+    } else if let Ok((log, path)) = m_manager.find(partial_id) {
+        set_tags(&m_manager.manager, log, &path, tags, replace)
+    } else if let Ok((log, path)) = i_manager.find(partial_id) {
+        set_tags(&i_manager.manager, log, &path, tags, replace)
+    } else if let Ok((log, path)) = e_manager.find(partial_id) {
+        set_tags(&e_manager.manager, log, &path, tags, replace)
+    } else {
+        Err(anyhow::anyhow!(
+            "No entry found with ID starting with '{}'",
+            partial_id
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_tree() {
+        let entries: Vec<HashSet<String>> = vec![
+            ["ml/transformers/attention", "ml/vision"]
+                .map(String::from)
+                .into(),
+            ["ml/transformers".to_string(), "ml-ops".to_string()].into(),
+            ["optics".to_string()].into(),
+        ];
+        let tree = build_tree(&entries);
+
+        let summary: Vec<(&str, usize)> = tree.iter().map(|n| (n.tag.as_str(), n.count)).collect();
+        assert_eq!(summary, vec![("ml", 2), ("ml-ops", 1), ("optics", 1)]);
+        let ml = &tree[0];
+        assert_eq!(ml.children.len(), 2);
+        assert_eq!(ml.children[0].tag, "ml/transformers");
+        assert_eq!(ml.children[0].count, 2);
+        assert_eq!(ml.children[0].children[0].name, "attention");
+        assert_eq!(ml.children[0].children[0].count, 1);
+        assert_eq!(ml.children[1].name, "vision");
+    }
+}
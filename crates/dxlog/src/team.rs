@@ -0,0 +1,113 @@
+use anyhow::Result;
+
+use crate::{
+    load_config, log_manager::LogManager, research_log::ResearchLog, utils, utils::Author, Config,
+    HypothesisLog, HypothesisManager, KnowledgeLog, KnowledgeManager, LiteratureLog,
+    LiteratureManager,
+};
+
+/// Finds a member of `[team]` by handle, email or full name, ignoring case.
+pub fn find_team_member(config: &Config, query: &str) -> Result<Author> {
+    let query = query.trim();
+    let lowercase = query.to_lowercase();
+    let member = config.team.iter().find(|(handle, member)| {
+        handle.to_lowercase() == lowercase
+            || member.email.to_lowercase() == lowercase
+            || member.name.to_lowercase() == lowercase
+    });
+    if let Some((_, member)) = member {
+        let mut member = member.clone();
+        member.orcid = member
+            .orcid
+            .map(|orcid| utils::parse_orcid(&orcid))
+            .transpose()?;
+        return Ok(member);
+    }
+    let known: Vec<&str> = config.team.keys().map(String::as_str).collect();
+    Err(anyhow::anyhow!(
+        "'{}' is not a team member. {}",
+        query,
+        if known.is_empty() {
+            "Add them under [team] in dxlog.toml first".to_string()
+        } else {
+            format!("Known members: {}", known.join(", "))
+        }
+    ))
+}
+
+/// Turns an `--assignee` value into a [`crate::ListFilter`] query, reading
+/// `me` as the email of the current author.
+pub fn assignee_filter(query: &str) -> Result<String> {
+    if query != "me" {
+        return Ok(query.to_string());
+    }
+    let config = load_config()?;
+    Ok(utils::get_author(&config)?.email)
+}
+
+fn assign<T: ResearchLog>(
+    manager: &LogManager<T>,
+    partial_id: &str,
+    member: Option<&str>,
+) -> Result<T> {
+    let assignee = member
+        .map(|member| find_team_member(&manager.config, member))
+        .transpose()?;
+    let (mut log, path) = manager.find_log(partial_id)?;
+    log.base_mut().assignee = assignee;
+    manager.update_log(&mut log, &path)?;
+    Ok(log)
+}
+
+/// Assigns a hypothesis to a member of `[team]`, or unassigns it with `None`.
+pub fn assign_hypothesis(partial_id: &str, member: Option<&str>) -> Result<HypothesisLog> {
+    let manager = HypothesisManager::new(load_config()?);
+    assign(&manager.manager, partial_id, member)
+}
+
+/// Assigns a literature review to a member of `[team]`, or unassigns it with `None`.
+pub fn assign_literature(partial_id: &str, member: Option<&str>) -> Result<LiteratureLog> {
+    let manager = LiteratureManager::new(load_config()?);
+    assign(&manager.manager, partial_id, member)
+}
+
+/// Assigns a knowledge entry to a member of `[team]`, or unassigns it with `None`.
+pub fn assign_knowledge(partial_id: &str, member: Option<&str>) -> Result<KnowledgeLog> {
+    let manager = KnowledgeManager::new(load_config()?);
+    assign(&manager.manager, partial_id, member)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        md_frontmatter::update_markdown_frontmatter,
+        test_support::{author, TempRepo, AUTHOR_SETTINGS},
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_assign_team_member() {
+        let repo = TempRepo::new();
+        let config = repo.load(&format!(
+            "{}\n[team.alan]\nname = \"Alan Turing\"\nemail = \"alan@example.org\"\n",
+            AUTHOR_SETTINGS
+        ));
+        let manager = HypothesisManager::new(config).manager;
+        let log = HypothesisLog::new("Noise scaling".into(), HashSet::new(), author());
+        let content = update_markdown_frontmatter(&log, "# Body").unwrap();
+        manager.save_log(&log, &content, false).unwrap();
+        let id = log.base.id.to_string();
+
+        for query in ["alan", "ALAN@example.org", " alan turing "] {
+            let log = assign(&manager, &id, Some(query)).unwrap();
+            assert_eq!(log.base.assignee.unwrap().email, "alan@example.org");
+        }
+        let err = assign(&manager, &id, Some("grace")).unwrap_err();
+        assert!(err.to_string().contains("Known members: alan"));
+        assert!(manager.find_log(&id).unwrap().0.base.assignee.is_some());
+
+        assign(&manager, &id, None).unwrap();
+        assert!(manager.find_log(&id).unwrap().0.base.assignee.is_none());
+    }
+}
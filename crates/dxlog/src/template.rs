@@ -0,0 +1,254 @@
+use anyhow::{Context, Result};
+use minijinja::context;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::config::{load_config, Config};
+use crate::utils::{self, BaseLog};
+
+/// Log types that have templates, in the order they are listed.
+pub const TEMPLATE_KINDS: [&str; 8] = [
+    "hypothesis",
+    "literature",
+    "knowledge",
+    "journal",
+    "dataset",
+    "meeting",
+    "experiment",
+    "report",
+];
+
+/// A template new entries can be rendered from.
+#[derive(Debug, Serialize)]
+pub struct TemplateInfo {
+    pub kind: String,
+    /// `default` for the `[templates]` entry, otherwise the name under `[templates.named]`.
+    pub name: String,
+    pub path: PathBuf,
+    /// Tags that select this template automatically.
+    pub tags: Vec<String>,
+    pub exists: bool,
+}
+
+/// A variable a template can use.
+#[derive(Debug, Serialize)]
+pub struct TemplateVariable {
+    pub name: String,
+    pub description: String,
+}
+
+/// Variables every entry template gets.
+const ENTRY_VARIABLES: &[(&str, &str)] = &[
+    (
+        "research_log",
+        "YAML frontmatter of the entry, to put between `---` lines",
+    ),
+    ("id", "Entry ID"),
+    ("short_id", "First 8 characters of the ID"),
+    ("handle", "Short ID such as H-042"),
+    ("title", "Entry title"),
+    ("tags", "Tags, sorted"),
+    ("author", "Author name"),
+    ("author_email", "Author email"),
+    ("author_orcid", "Author ORCID iD, if configured"),
+    ("author_affiliation", "Author affiliation, if configured"),
+    ("date", "Creation date in the configured date-format"),
+    ("created_at", "Creation time (RFC 3339)"),
+];
+
+const BODY_VARIABLES: &[(&str, &str)] =
+    &[("body", "Content given with --body-file or --stdin, if any")];
+
+const LITERATURE_VARIABLES: &[(&str, &str)] = &[
+    ("abstract_text", "Abstract of the paper"),
+    ("authors", "Paper authors, in order"),
+    ("url", "Link the entry was created from (or the DOI link)"),
+    (
+        "source",
+        "All source fields: doi, arxiv_url, pdf_url, repository_url, preprint_url, openreview_url, venue, decision, stars, citations, topics",
+    ),
+    ("repository_description", "Description of a GitHub repository"),
+];
+
+const DATASET_VARIABLES: &[(&str, &str)] = &[
+    ("url", "Where the dataset was obtained from"),
+    ("license", "License of the data"),
+    ("version", "Release or version of the data"),
+    ("hash", "Checksum of the data"),
+    ("path", "Local copy of the data"),
+    ("size", "Size of the data, e.g. 1.5 GB"),
+];
+
+const MEETING_VARIABLES: &[(&str, &str)] = &[
+    ("date", "Day of the meeting, in the configured date format"),
+    ("attendees", "List of the people present"),
+    (
+        "action_items",
+        "Action items given at creation, each with text, owner, due and done",
+    ),
+];
+
+const EXPERIMENT_VARIABLES: &[(&str, &str)] = &[
+    (
+        "commit",
+        "Commit checked out when the experiment was created",
+    ),
+    ("branch", "Branch checked out, if any"),
+    ("dirty", "Whether tracked files had uncommitted changes"),
+    (
+        "lockfile",
+        "Path of the dependency lockfile given with --lockfile",
+    ),
+    ("lockfile_hash", "Git hash of the lockfile"),
+];
+
+const REPORT_VARIABLES: &[(&str, &str)] = &[
+    (
+        "since",
+        "Start of the period, in the configured date-format",
+    ),
+    ("until", "End of the period (today)"),
+    (
+        "stale_days",
+        "Days without changes before an entry counts as stale",
+    ),
+    (
+        "authors",
+        "Per author: name, created, transitions, completed and stale entries",
+    ),
+];
+
+/// Lists the variables templates of `kind` can use, including `vars.*` from the config.
+pub fn template_variables(kind: &str) -> Result<Vec<TemplateVariable>> {
+    let groups: &[&[(&str, &str)]] = match kind {
+        "hypothesis" | "knowledge" => &[ENTRY_VARIABLES, BODY_VARIABLES],
+        "journal" => &[ENTRY_VARIABLES],
+        "dataset" => &[ENTRY_VARIABLES, BODY_VARIABLES, DATASET_VARIABLES],
+        "meeting" => &[ENTRY_VARIABLES, BODY_VARIABLES, MEETING_VARIABLES],
+        "experiment" => &[ENTRY_VARIABLES, BODY_VARIABLES, EXPERIMENT_VARIABLES],
+        "literature" => &[ENTRY_VARIABLES, LITERATURE_VARIABLES],
+        "report" => &[REPORT_VARIABLES],
+        _ => return Err(anyhow::anyhow!("Unknown log type '{}'", kind)),
+    };
+    let config = load_config()?;
+
+    let mut variables: Vec<TemplateVariable> = groups
+        .iter()
+        .flat_map(|group| group.iter())
+        .map(|(name, description)| TemplateVariable {
+            name: name.to_string(),
+            description: description.to_string(),
+        })
+        .collect();
+    variables.extend(
+        config
+            .templates
+            .vars
+            .iter()
+            .map(|(name, value)| TemplateVariable {
+                name: format!("vars.{}", name),
+                description: format!("From [templates.vars]: {}", value),
+            }),
+    );
+    Ok(variables)
+}
+
+/// Context shared by the entry templates; see [`ENTRY_VARIABLES`].
+pub(crate) fn entry_context(
+    config: &Config,
+    base: &BaseLog,
+    research_log: String,
+) -> minijinja::Value {
+    let id = base.id.to_string();
+    let mut tags: Vec<&String> = base.tags.iter().collect();
+    tags.sort();
+    context! {
+        research_log,
+        short_id => id[..8],
+        handle => base.handle,
+        id,
+        title => base.title,
+        tags,
+        author => base.created_by.name,
+        author_email => base.created_by.email,
+        author_orcid => base.created_by.orcid,
+        author_affiliation => base.created_by.affiliation,
+        date => base.display_date(&config.date_format),
+        created_at => base.created_at.to_rfc3339(),
+        vars => config.templates.vars,
+    }
+}
+
+/// The template `dxlog init` writes for `kind`.
+pub(crate) fn builtin_template(kind: &str) -> Option<&'static str> {
+    match kind {
+        "hypothesis" => Some(include_str!("templates/hypothesis.default.jinja")),
+        "literature" => Some(include_str!("templates/literature.default.jinja")),
+        "knowledge" => Some(include_str!("templates/knowledge.default.jinja")),
+        "journal" => Some(include_str!("templates/journal.default.jinja")),
+        "dataset" => Some(include_str!("templates/dataset.default.jinja")),
+        "meeting" => Some(include_str!("templates/meeting.default.jinja")),
+        "experiment" => Some(include_str!("templates/experiment.default.jinja")),
+        "report" => Some(include_str!("templates/report.default.jinja")),
+        _ => None,
+    }
+}
+
+/// Lists the default and named templates, optionally of one log type only.
+pub fn list_templates(kind: Option<&str>) -> Result<Vec<TemplateInfo>> {
+    let config = load_config()?;
+    let mut templates = Vec::new();
+    for kind in TEMPLATE_KINDS
+        .into_iter()
+        .filter(|k| kind.is_none_or(|f| f == *k))
+    {
+        let default = config.template_for(kind, Some("default"), &HashSet::new())?;
+        templates.push(TemplateInfo {
+            kind: kind.to_string(),
+            name: "default".to_string(),
+            exists: default.is_file(),
+            path: default,
+            tags: Vec::new(),
+        });
+        for (name, named) in config.templates.named.get(kind).into_iter().flatten() {
+            templates.push(TemplateInfo {
+                kind: kind.to_string(),
+                name: name.clone(),
+                path: named.path.clone(),
+                tags: named.tags.clone(),
+                exists: named.path.is_file(),
+            });
+        }
+    }
+    Ok(templates)
+}
+
+fn template_path(config: &Config, kind: &str, name: Option<&str>) -> Result<PathBuf> {
+    config.template_for(kind, Some(name.unwrap_or("default")), &HashSet::new())
+}
+
+/// Returns the path and source of a template. A missing default template reads
+/// as the built-in one.
+pub fn read_template(kind: &str, name: Option<&str>) -> Result<(PathBuf, String)> {
+    let path = template_path(&load_config()?, kind, name)?;
+    if !path.exists() && name.is_none_or(|n| n == "default") {
+        if let Some(builtin) = builtin_template(kind) {
+            return Ok((path, builtin.to_string()));
+        }
+    }
+    let content = utils::load_entry_content(&path)?;
+    Ok((path, content))
+}
+
+/// Returns the path of a template for editing, first writing the built-in
+/// template there if the file does not exist yet.
+pub fn prepare_template_edit(kind: &str, name: Option<&str>) -> Result<PathBuf> {
+    let path = template_path(&load_config()?, kind, name)?;
+    if !path.exists() {
+        let builtin =
+            builtin_template(kind).with_context(|| format!("Unknown log type '{}'", kind))?;
+        utils::save_entry_content(&path, builtin)?;
+    }
+    Ok(path)
+}
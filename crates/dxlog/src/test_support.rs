@@ -0,0 +1,67 @@
+//! Fixtures shared by the unit tests.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use uuid::Uuid;
+
+use crate::{config::CONFIG_FILE_NAME, utils::Author, Config};
+
+/// The author of the entries tests create.
+pub(crate) fn author() -> Author {
+    person("Ada Lovelace", "ada@example.org")
+}
+
+/// The `[author]` config block naming [`author`], needed to create entries.
+pub(crate) const AUTHOR_SETTINGS: &str =
+    "[author]\nname = \"Ada Lovelace\"\nemail = \"ada@example.org\"\n";
+
+/// Someone other than [`author`], such as a team member.
+pub(crate) fn person(name: &str, email: &str) -> Author {
+    Author {
+        name: name.to_string(),
+        email: email.to_string(),
+        orcid: None,
+        affiliation: None,
+    }
+}
+
+/// A fresh directory under the system's temporary directory, removed with
+/// everything in it when dropped, so failing tests do not leave it behind.
+pub(crate) struct TempRepo {
+    root: PathBuf,
+}
+
+impl TempRepo {
+    pub(crate) fn new() -> Self {
+        let root = std::env::temp_dir().join(format!("dxlog-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        Self { root }
+    }
+
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The default configuration with the directory as its repository root.
+    pub(crate) fn config(&self) -> Config {
+        Config {
+            root: self.root.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Writes `settings` as the repository's config file and loads it.
+    pub(crate) fn load(&self, settings: &str) -> Config {
+        fs::write(self.root.join(CONFIG_FILE_NAME), settings).unwrap();
+        Config::load(&self.root).unwrap()
+    }
+}
+
+impl Drop for TempRepo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+use crate::{dry_run, handle, load_config, utils, utils::BaseLog, Config};
+
+const TOMBSTONE_EXTENSION: &str = "tombstone";
+
+/// Record of a deleted entry, stored next to the trashed file.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Tombstone {
+    pub id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    pub kind: String,
+    pub title: String,
+    pub original_path: PathBuf,
+    pub deleted_at: String,
+}
+
+pub struct Trash {
+    dir: PathBuf,
+}
+
+impl Trash {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            dir: config.storage.trash_dir.clone(),
+        }
+    }
+
+    fn entry_path(&self, id: &Uuid) -> PathBuf {
+        self.dir.join(format!("{}.md", id))
+    }
+
+    fn tombstone_path(&self, id: &Uuid) -> PathBuf {
+        self.dir.join(format!("{}.{}", id, TOMBSTONE_EXTENSION))
+    }
+
+    /// Moves an entry file into the trash and writes its tombstone.
+    ///
+    /// The tombstone is only written once the entry is in the trash, and the
+    /// entry is moved back if writing it fails, so no tombstone lists an entry
+    /// that is not there.
+    pub fn put(&self, base: &BaseLog, kind: &str, path: &Path) -> Result<Tombstone> {
+        let id = base.id;
+        let tombstone = Tombstone {
+            id,
+            handle: base.handle.clone(),
+            kind: kind.to_string(),
+            title: base.title.clone(),
+            original_path: path.to_path_buf(),
+            deleted_at: chrono::Local::now().to_rfc3339(),
+        };
+        let trashed = self.entry_path(&id);
+        dry_run::rename(path, &trashed)
+            .with_context(|| format!("Failed to move {} to trash", path.display()))?;
+        if let Err(e) = dry_run::write(
+            &self.tombstone_path(&id),
+            &serde_yaml::to_string(&tombstone)?,
+        ) {
+            dry_run::rename(&trashed, path)?;
+            return Err(e.context(format!("Failed to write tombstone for {}", id)));
+        }
+
+        Ok(tombstone)
+    }
+
+    /// Directory files replaced by newer versions are kept in.
+    fn replaced_dir(&self) -> PathBuf {
+        self.dir.join("replaced")
+    }
+
+    /// Moves a file about to be replaced, such as an attachment, into the
+    /// trash, where it is kept until the trash is emptied.
+    pub(crate) fn put_replaced(&self, path: &Path) -> Result<PathBuf> {
+        let name = path
+            .file_name()
+            .with_context(|| format!("{} is not a file", path.display()))?;
+        let target =
+            self.replaced_dir()
+                .join(format!("{}-{}", Uuid::now_v7(), name.to_string_lossy()));
+        dry_run::rename(path, &target)
+            .with_context(|| format!("Failed to move {} to trash", path.display()))?;
+        Ok(target)
+    }
+
+    pub fn list(&self) -> Result<Vec<Tombstone>> {
+        let mut tombstones = Vec::new();
+        for path in utils::list_entries(&self.dir, TOMBSTONE_EXTENSION)? {
+            let content = utils::load_entry_content(&path)?;
+            let tombstone: Tombstone = serde_yaml::from_str(&content)
+                .with_context(|| format!("Invalid tombstone: {}", path.display()))?;
+            tombstones.push(tombstone);
+        }
+        tombstones.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(tombstones)
+    }
+
+    fn find(&self, partial_id: &str) -> Result<Tombstone> {
+        let mut matches: Vec<Tombstone> = self
+            .list()?
+            .into_iter()
+            .filter(|t| {
+                t.id.to_string().starts_with(partial_id)
+                    || t.handle
+                        .as_deref()
+                        .is_some_and(|handle| handle::matches(handle, partial_id))
+            })
+            .collect();
+
+        match matches.len() {
+            0 => Err(anyhow::anyhow!(
+                "No trashed entry found with ID starting with '{}'",
+                partial_id
+            )),
+            1 => Ok(matches.remove(0)),
+            _ => Err(anyhow::anyhow!(
+                "Multiple trashed entries found with ID starting with '{}'. Please provide more characters.",
+                partial_id
+            )),
+        }
+    }
+
+    /// Moves a trashed entry back to where it was deleted from.
+    pub fn restore(&self, partial_id: &str) -> Result<Tombstone> {
+        let tombstone = self.find(partial_id)?;
+        if tombstone.original_path.exists() {
+            return Err(anyhow::anyhow!(
+                "Cannot restore: a file already exists at {}",
+                tombstone.original_path.display()
+            ));
+        }
+
+        dry_run::rename(&self.entry_path(&tombstone.id), &tombstone.original_path)?;
+        dry_run::remove_file(&self.tombstone_path(&tombstone.id))?;
+        Ok(tombstone)
+    }
+
+    /// Permanently deletes everything in the trash, returning the number of entries removed.
+    pub fn empty(&self) -> Result<usize> {
+        let tombstones = self.list()?;
+        for tombstone in &tombstones {
+            let entry_path = self.entry_path(&tombstone.id);
+            if entry_path.exists() {
+                dry_run::remove_file(&entry_path)?;
+            }
+            dry_run::remove_file(&self.tombstone_path(&tombstone.id))?;
+        }
+        if self.replaced_dir().is_dir() {
+            for file in fs::read_dir(self.replaced_dir())? {
+                dry_run::remove_file(&file?.path())?;
+            }
+        }
+        Ok(tombstones.len())
+    }
+}
+
+pub fn list_trash() -> Result<Vec<Tombstone>> {
+    let config = load_config()?;
+    Trash::new(&config).list()
+}
+
+pub fn restore_from_trash(partial_id: &str) -> Result<Tombstone> {
+    let config = load_config()?;
+    Trash::new(&config).restore(partial_id)
+}
+
+pub fn empty_trash() -> Result<usize> {
+    let config = load_config()?;
+    Trash::new(&config).empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        research_log::ResearchLog,
+        test_support::{author, TempRepo},
+        HypothesisLog,
+    };
+    use std::{collections::HashSet, fs};
+
+    #[test]
+    fn test_put_restore_and_empty() {
+        let repo = TempRepo::new();
+        let root = repo.root();
+        let mut config = Config::default();
+        config.storage.trash_dir = root.join(".trash");
+        let trash = Trash::new(&config);
+        let put = |title: &str| {
+            let log = HypothesisLog::new(title.into(), HashSet::new(), author());
+            let path = root.join(format!("{}.md", title));
+            fs::write(&path, title).unwrap();
+            trash.put(&log.base, "hypothesis", &path).unwrap();
+            (log.base.id, path)
+        };
+        let (first, first_path) = put("first");
+        let (_, second_path) = put("second");
+        assert!(!first_path.exists() && !second_path.exists());
+        assert_eq!(trash.list().unwrap().len(), 2);
+
+        let restored = trash.restore(&first.to_string()).unwrap();
+        assert_eq!(restored.original_path, first_path);
+        assert_eq!(fs::read_to_string(&first_path).unwrap(), "first");
+        assert_eq!(trash.list().unwrap().len(), 1);
+
+        assert_eq!(trash.empty().unwrap(), 1);
+        assert!(trash.list().unwrap().is_empty());
+        assert!(!second_path.exists());
+    }
+
+    #[test]
+    fn test_failed_put_leaves_no_tombstone() {
+        let repo = TempRepo::new();
+        let mut config = Config::default();
+        config.storage.trash_dir = repo.root().join(".trash");
+        let trash = Trash::new(&config);
+        let log = HypothesisLog::new("Gone".into(), HashSet::new(), author());
+
+        assert!(trash
+            .put(&log.base, "hypothesis", &repo.root().join("missing.md"))
+            .is_err());
+        assert!(trash.list().unwrap().is_empty());
+    }
+}
@@ -1,31 +1,145 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, SubsecRound, TimeZone};
 use git2::Repository;
-use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+use crate::attachment::Attachment;
+use crate::config::Config;
+use crate::reference::{Reference, ReferenceKind};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Author {
     pub name: String,
     pub email: String,
+    /// ORCID iD such as `0000-0002-1825-0097`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orcid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub affiliation: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BaseLog {
+    /// Version of the frontmatter format the entry was written with; see [`crate::SCHEMA_VERSION`].
+    #[serde(default)]
+    pub schema_version: u32,
     pub id: Uuid,
-    pub date: String,
+    /// Short ID such as `H-042`; entries created before handles existed have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    /// Entries written before timestamps were added only have a `date`, read as local midnight.
+    #[serde(alias = "date", deserialize_with = "deserialize_timestamp")]
+    pub created_at: DateTime<FixedOffset>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<FixedOffset>>,
     pub title: String,
     pub tags: HashSet<String>,
+    /// Name of a project from `[projects]` the entry belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// ID of the milestone the entry counts towards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<Uuid>,
     pub created_by: Author,
-    pub references: HashSet<Uuid>,
+    /// Team member responsible for the entry, from `[team]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<Author>,
+    pub references: HashSet<Reference>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<StatusChange>,
+    /// Files stored with `dxlog attach`, under the attachments directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// Whether the body is encrypted with `dxlog encrypt`; the frontmatter never is.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool,
+}
+
+impl BaseLog {
+    /// Day the entry was created, in the timezone it was created in.
+    pub fn created_date(&self) -> NaiveDate {
+        self.created_at.date_naive()
+    }
+
+    /// Creation date formatted with the configured `date-format`.
+    pub fn display_date(&self, date_format: &str) -> String {
+        self.created_at.format(date_format).to_string()
+    }
+
+    /// Adds a reference to `id`, replacing any existing reference to the same entry.
+    pub fn set_reference(&mut self, id: Uuid, kind: Option<ReferenceKind>) {
+        self.set_reference_in(id, kind, None);
+    }
+
+    /// Like [`set_reference`](Self::set_reference), to an entry of the
+    /// workspace repository `repo`, or of this one with `None`.
+    pub fn set_reference_in(
+        &mut self,
+        id: Uuid,
+        kind: Option<ReferenceKind>,
+        repo: Option<String>,
+    ) {
+        self.remove_reference(&id);
+        self.references.insert(Reference { id, kind, repo });
+    }
+
+    pub fn remove_reference(&mut self, id: &Uuid) {
+        self.references.retain(|r| &r.id != id);
+    }
+
+    pub fn references_to(&self, id: &Uuid) -> bool {
+        self.references.iter().any(|r| &r.id == id)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    pub sha: String,
+    pub summary: String,
+    pub author: String,
+    pub date: String,
 }
 
+/// One entry in a log's status history.
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StatusChange {
     pub from: String,
     pub to: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub reason: String,
+    pub date: String,
+}
+
+/// The current local time, to the second, as stored in `created_at` and `updated_at`.
+pub fn now() -> DateTime<FixedOffset> {
+    Local::now().fixed_offset().trunc_subsecs(0)
+}
+
+fn deserialize_timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<DateTime<FixedOffset>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(&value) {
+        return Ok(timestamp);
+    }
+    NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| {
+            Local
+                .from_local_datetime(&date.and_time(Default::default()))
+                .earliest()
+        })
+        .map(|timestamp| timestamp.fixed_offset())
+        .ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "invalid timestamp '{}', expected RFC 3339 or YYYY-MM-DD",
+                value
+            ))
+        })
 }
 
 pub fn generate_filename(title: &str, date: &str) -> String {
@@ -38,9 +152,36 @@ pub fn generate_filename(title: &str, date: &str) -> String {
     format!("{}-{}.md", date, safe_title)
 }
 
-pub fn get_git_author() -> Result<Author> {
-    let repo = Repository::open_from_env()
-        .context("Failed to open git repository. Make sure you're in a git repository")?;
+/// Returns `path` if it is free, otherwise the first free `<stem>-2.<ext>`, `<stem>-3.<ext>`, ...
+pub fn unused_path(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (2..)
+        .map(|n| path.with_file_name(format!("{}-{}{}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .expect("ran out of file name suffixes")
+}
+
+pub(crate) fn open_repository(root: &Path) -> Result<Repository> {
+    Repository::discover(root).with_context(|| {
+        format!(
+            "Failed to open git repository at {}. Make sure the research logs are in a git repository",
+            root.display()
+        )
+    })
+}
+
+pub fn get_git_author(root: &Path) -> Result<Author> {
+    let repo = open_repository(root)?;
 
     let config = repo.config()?;
 
@@ -51,7 +192,229 @@ pub fn get_git_author() -> Result<Author> {
         .get_string("user.email")
         .context("Git user.email not configured")?;
 
-    Ok(Author { name, email })
+    Ok(Author {
+        name,
+        email,
+        orcid: None,
+        affiliation: None,
+    })
+}
+
+/// The author of new entries: `[author]` in dxlog.toml, with what it leaves
+/// out taken from git config (`user.name`, `user.email`, `dxlog.orcid` and
+/// `dxlog.affiliation`).
+pub fn get_author(config: &Config) -> Result<Author> {
+    let configured = &config.author;
+    let git = open_repository(&config.root).and_then(|repo| Ok(repo.config()?));
+    let required = |value: &Option<String>, key: &str| -> Result<String> {
+        if let Some(value) = value {
+            return Ok(value.clone());
+        }
+        git.as_ref()
+            .map_err(|e| anyhow::anyhow!("{:#}", e))?
+            .get_string(key)
+            .with_context(|| format!("Git {} not configured", key))
+    };
+    let optional = |value: &Option<String>, key: &str| {
+        value
+            .clone()
+            .or_else(|| git.as_ref().ok()?.get_string(key).ok())
+            .filter(|value| !value.trim().is_empty())
+    };
+
+    Ok(Author {
+        name: required(&configured.name, "user.name")?,
+        email: required(&configured.email, "user.email")?,
+        orcid: optional(&configured.orcid, "dxlog.orcid")
+            .map(|orcid| parse_orcid(&orcid))
+            .transpose()?,
+        affiliation: optional(&configured.affiliation, "dxlog.affiliation"),
+    })
+}
+
+/// Checks an ORCID iD, given bare or as an `https://orcid.org/` URL, against
+/// its check digit and returns it bare.
+pub fn parse_orcid(orcid: &str) -> Result<String> {
+    let trimmed = orcid.trim();
+    let id = ["https://orcid.org/", "http://orcid.org/", "orcid.org/"]
+        .iter()
+        .find_map(|prefix| trimmed.strip_prefix(prefix))
+        .unwrap_or(trimmed)
+        .to_uppercase();
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid ORCID iD '{}', expected the form 0000-0002-1825-0097",
+            orcid
+        )
+    };
+    let groups: Vec<&str> = id.split('-').collect();
+    if groups.len() != 4
+        || groups.iter().any(|group| {
+            group.len() != 4 || !group.bytes().all(|b| b.is_ascii_digit() || b == b'X')
+        })
+    {
+        return Err(invalid());
+    }
+    let characters: Vec<char> = groups.concat().chars().collect();
+    let (digits, check) = characters.split_at(15);
+    let mut total = 0;
+    for digit in digits {
+        total = (total + digit.to_digit(10).ok_or_else(invalid)?) * 2;
+    }
+    let expected = match (12 - total % 11) % 11 {
+        10 => 'X',
+        n => char::from_digit(n, 10).unwrap(),
+    };
+    if check[0] != expected {
+        return Err(anyhow::anyhow!(
+            "Invalid ORCID iD '{}': the check digit does not match",
+            orcid
+        ));
+    }
+    Ok(id)
+}
+
+/// Resolves a commit SHA (or any revision git understands) in the repository containing `root`.
+pub fn resolve_commit(root: &Path, rev: &str) -> Result<CommitInfo> {
+    let repo = open_repository(root)?;
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|obj| obj.peel_to_commit())
+        .with_context(|| format!("No commit found for '{}'", rev))?;
+
+    let date = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+
+    let summary = commit.summary().unwrap_or_default().to_string();
+    let author = commit.author().name().unwrap_or_default().to_string();
+
+    Ok(CommitInfo {
+        sha: commit.id().to_string(),
+        summary,
+        author,
+        date,
+    })
+}
+
+/// The commit checked out in a repository, as recorded on experiments.
+#[derive(Debug, Clone)]
+pub struct HeadState {
+    pub commit: String,
+    /// `None` on a detached HEAD.
+    pub branch: Option<String>,
+    /// Whether tracked files have uncommitted changes. Untracked files do not count.
+    pub dirty: bool,
+}
+
+/// Reads the checked out commit of the repository containing `root`.
+pub fn head_state(root: &Path) -> Result<HeadState> {
+    let repo = open_repository(root)?;
+    let head = repo.head().with_context(|| {
+        format!(
+            "The git repository at {} has no commits yet",
+            repo.workdir().unwrap_or(root).display()
+        )
+    })?;
+    let commit = head.peel_to_commit()?.id().to_string();
+    let branch = head
+        .is_branch()
+        .then(|| head.shorthand().map(str::to_string))
+        .flatten();
+
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    let dirty = !repo.statuses(Some(&mut options))?.is_empty();
+
+    Ok(HeadState {
+        commit,
+        branch,
+        dirty,
+    })
+}
+
+/// Time of the most recent commit touching each of `paths`, walking back from HEAD.
+///
+/// Paths no commit touches are left out.
+pub fn last_commit_times(
+    root: &Path,
+    paths: &[PathBuf],
+) -> Result<HashMap<PathBuf, DateTime<FixedOffset>>> {
+    let repo = open_repository(root)?;
+    let Some(workdir) = repo.workdir().and_then(|dir| dir.canonicalize().ok()) else {
+        return Ok(HashMap::new());
+    };
+    let mut wanted: HashMap<PathBuf, &PathBuf> = paths
+        .iter()
+        .filter_map(|path| {
+            let relative = path
+                .canonicalize()
+                .ok()?
+                .strip_prefix(&workdir)
+                .ok()?
+                .to_path_buf();
+            Some((relative, path))
+        })
+        .collect();
+
+    let mut times = HashMap::new();
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(git2::Sort::TIME)?;
+    walk.push_head()?;
+    for oid in walk {
+        if wanted.is_empty() {
+            break;
+        }
+        let commit = repo.find_commit(oid?)?;
+        let parent_tree = match commit.parents().next() {
+            Some(parent) => Some(parent.tree()?),
+            None => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+        let time = commit.time();
+        let Some(at) = FixedOffset::east_opt(time.offset_minutes() * 60)
+            .and_then(|offset| offset.timestamp_opt(time.seconds(), 0).single())
+        else {
+            continue;
+        };
+        for delta in diff.deltas() {
+            if let Some(path) = delta.new_file().path().and_then(|p| wanted.remove(p)) {
+                times.insert(path.clone(), at);
+            }
+        }
+    }
+    Ok(times)
+}
+
+/// Renames `from` to `to`. If `from` is tracked by git, the rename is staged
+/// the way `git mv` does it, so history follows the file.
+pub fn rename_file(from: &Path, to: &Path) -> Result<()> {
+    crate::dry_run::rename(from, to)?;
+    if crate::dry_run::is_dry_run() {
+        return Ok(());
+    }
+
+    let Ok(repo) = Repository::discover(to.parent().unwrap_or(Path::new("."))) else {
+        return Ok(());
+    };
+    let (Some(workdir), Ok(to)) = (repo.workdir(), to.canonicalize()) else {
+        return Ok(());
+    };
+    let from = match (from.parent().map(Path::canonicalize), from.file_name()) {
+        (Some(Ok(dir)), Some(name)) => dir.join(name),
+        _ => return Ok(()),
+    };
+    let (Ok(old), Ok(new)) = (from.strip_prefix(workdir), to.strip_prefix(workdir)) else {
+        return Ok(());
+    };
+
+    let mut index = repo.index()?;
+    if index.get_path(old, 0).is_some() {
+        index.remove_path(old)?;
+        index.add_path(new)?;
+        index.write()?;
+    }
+    Ok(())
 }
 
 pub fn ensure_directory(path: &Path) -> Result<()> {
@@ -62,25 +425,100 @@ pub fn ensure_directory(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Which tags an entry must have, may have and must not have to be listed.
+///
+/// The three parts combine with AND: an entry matches if it has every tag of
+/// `all`, at least one of `any` (when given) and none of `none`. A tag also
+/// matches its descendants, see [`has_tag`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    pub all: Vec<String>,
+    pub any: Vec<String>,
+    pub none: Vec<String>,
+}
+
+impl TagFilter {
+    /// Matches entries with every one of `tags`.
+    pub fn all(tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            all: tags.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.all.is_empty() && self.any.is_empty() && self.none.is_empty()
+    }
+
+    pub fn matches(&self, tags: &HashSet<String>) -> bool {
+        self.all.iter().all(|tag| has_tag(tags, tag))
+            && (self.any.is_empty() || self.any.iter().any(|tag| has_tag(tags, tag)))
+            && !self.none.iter().any(|tag| has_tag(tags, tag))
+    }
+}
+
+/// Whether `tag` is `ancestor` or one of its descendants in the tag hierarchy,
+/// e.g. `ml/transformers` for `ml`.
+pub fn is_tag_within(tag: &str, ancestor: &str) -> bool {
+    let ancestor = ancestor.trim_matches('/');
+    tag.strip_prefix(ancestor)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Whether `tags` contain `tag` or one of its descendants.
+pub fn has_tag(tags: &HashSet<String>, tag: &str) -> bool {
+    tags.iter().any(|t| is_tag_within(t, tag))
+}
+
+/// Cleans up a hierarchical tag: `/ml//transformers ` becomes `ml/transformers`.
+/// Returns `None` for a tag with nothing left.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag
+        .split('/')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+    (!tag.is_empty()).then_some(tag)
+}
+
 pub fn normalize_tags(tags: Option<Vec<String>>) -> HashSet<String> {
-    tags.map(|t| t.into_iter().collect()).unwrap_or_default()
+    tags.unwrap_or_default()
+        .iter()
+        .filter_map(|tag| normalize_tag(tag))
+        .collect()
 }
 
 pub fn load_entry_content(path: &Path) -> Result<String> {
     fs::read_to_string(path).with_context(|| format!("Failed to read file: {}", path.display()))
 }
 
-pub fn save_entry_content(path: &Path, content: &str) -> Result<()> {
-    // Ensure parent directory exists
-    if let Some(parent) = path.parent() {
-        ensure_directory(parent)?;
+/// Renders an entry template with `ctx`, which should include `body`.
+///
+/// Templates written before the `body` variable existed don't mention it, so a
+/// given body is appended to their output instead of being dropped.
+pub fn render_entry(
+    template_content: &str,
+    ctx: minijinja::Value,
+    body: Option<&str>,
+) -> Result<String> {
+    let env = minijinja::Environment::new();
+    let template = env.template_from_str(template_content)?;
+    let rendered = template.render(ctx)?;
+    match body {
+        Some(body) if !template.undeclared_variables(false).contains("body") => {
+            Ok(format!("{}\n\n{}\n", rendered.trim_end(), body))
+        }
+        _ => Ok(rendered),
     }
+}
 
+pub fn save_entry_content(path: &Path, content: &str) -> Result<()> {
     if path.exists() {
         return Err(anyhow::anyhow!("File already exists: {}", path.display()));
     }
 
-    fs::write(path, content).with_context(|| format!("Failed to write file: {}", path.display()))
+    crate::dry_run::write(path, content)
 }
 
 pub fn list_entries(dir: &Path, extension: &str) -> Result<Vec<PathBuf>> {
@@ -100,13 +538,22 @@ pub fn list_entries(dir: &Path, extension: &str) -> Result<Vec<PathBuf>> {
     Ok(entries)
 }
 
+/// Commits `paths` on HEAD of the repository the research logs are in.
+/// Paths that no longer exist are committed as deleted.
 pub fn commit_changes(paths: &[PathBuf], message: &str) -> Result<()> {
-    let repo = Repository::open_from_env()?;
+    let repo = open_repository(&crate::load_config()?.root)?;
     let mut index = repo.index()?;
+    let workdir = repo
+        .workdir()
+        .context("Cannot commit to a bare git repository")?;
 
     for path in paths {
-        let relative_path = path.strip_prefix(repo.workdir().unwrap())?;
-        index.add_path(relative_path)?;
+        let relative_path = path.strip_prefix(workdir)?;
+        if path.exists() {
+            index.add_path(relative_path)?;
+        } else {
+            index.remove_path(relative_path)?;
+        }
     }
 
     index.write()?;
@@ -114,7 +561,9 @@ pub fn commit_changes(paths: &[PathBuf], message: &str) -> Result<()> {
     let tree = repo.find_tree(tree_id)?;
 
     let signature = repo.signature()?;
-    let parent = repo.head()?.peel_to_commit()?;
+    // A repository without commits yet has no parent to commit on
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
 
     repo.commit(
         Some("HEAD"),
@@ -122,37 +571,152 @@ pub fn commit_changes(paths: &[PathBuf], message: &str) -> Result<()> {
         &signature,
         message,
         &tree,
-        &[&parent],
+        &parents,
     )?;
 
     Ok(())
 }
 
-pub fn detect_cycles(references: &HashSet<Uuid>, new_ref: Uuid, logs: &[BaseLog]) -> bool {
+/// Whether a reference from `source_id` to `new_ref` would close a cycle, i.e.
+/// whether `source_id` can already be reached from `new_ref`. Entries reached
+/// along two paths are not a cycle.
+pub fn detect_cycles(source_id: Uuid, new_ref: Uuid, logs: &[BaseLog]) -> bool {
     let mut visited = HashSet::new();
     let mut stack = vec![new_ref];
 
     while let Some(current) = stack.pop() {
-        if !visited.insert(current) {
+        if current == source_id {
             return true;
         }
+        if !visited.insert(current) {
+            continue;
+        }
         if let Some(log) = logs.iter().find(|l| l.id == current) {
-            stack.extend(log.references.iter());
+            stack.extend(log.references.iter().map(|r| r.id));
         }
     }
     false
 }
 
-pub fn add_reference(log: &mut BaseLog, ref_id: Uuid, all_logs: &[BaseLog]) -> Result<()> {
-    if detect_cycles(&log.references, ref_id, all_logs) {
+pub fn add_reference(
+    log: &mut BaseLog,
+    ref_id: Uuid,
+    kind: Option<ReferenceKind>,
+    all_logs: &[BaseLog],
+) -> Result<()> {
+    if detect_cycles(log.id, ref_id, all_logs) {
         return Err(anyhow::anyhow!(
             "Adding this reference would create a cycle"
         ));
     }
-    log.references.insert(ref_id);
+    log.set_reference(ref_id, kind);
     Ok(())
 }
 
 pub fn remove_reference(log: &mut BaseLog, ref_id: &Uuid) {
-    log.references.remove(ref_id);
+    log.remove_reference(ref_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::author;
+
+    #[test]
+    fn test_detect_cycles() {
+        use crate::{research_log::ResearchLog, HypothesisLog};
+
+        let mut logs: Vec<BaseLog> = (0..4)
+            .map(|i| HypothesisLog::new(format!("Entry {}", i), HashSet::new(), author()).base)
+            .collect();
+        let ids: Vec<Uuid> = logs.iter().map(|log| log.id).collect();
+        // 1 -> 2 -> 3 and 1 -> 3
+        logs[1].set_reference(ids[2], None);
+        logs[1].set_reference(ids[3], None);
+        logs[2].set_reference(ids[3], None);
+
+        // 0 reaches 3 along two paths through 1, which is no cycle
+        assert!(!detect_cycles(ids[0], ids[1], &logs));
+        assert!(detect_cycles(ids[3], ids[1], &logs));
+        assert!(detect_cycles(ids[2], ids[1], &logs));
+        assert!(!detect_cycles(ids[1], ids[0], &logs));
+    }
+
+    #[test]
+    fn test_tag_filter() {
+        let tags: HashSet<String> = ["optics", "cryo"].map(String::from).into();
+
+        assert!(TagFilter::default().is_empty());
+        assert!(TagFilter::default().matches(&tags));
+        assert!(TagFilter::all(["optics", "cryo"]).matches(&tags));
+        assert!(!TagFilter::all(["optics", "ml"]).matches(&tags));
+
+        let any = |tags: &[&str]| TagFilter {
+            any: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(any(&["ml", "cryo"]).matches(&tags));
+        assert!(!any(&["ml", "nlp"]).matches(&tags));
+
+        let none = |tags: &[&str]| TagFilter {
+            none: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(none(&["ml"]).matches(&tags));
+        assert!(!none(&["ml", "cryo"]).matches(&tags));
+
+        // optics AND (ml OR cryo) AND NOT draft
+        let combined = TagFilter {
+            all: vec!["optics".into()],
+            any: vec!["ml".into(), "cryo".into()],
+            none: vec!["draft".into()],
+        };
+        assert!(combined.matches(&tags));
+        let mut draft = tags.clone();
+        draft.insert("draft".into());
+        assert!(!combined.matches(&draft));
+        assert!(!combined.matches(&["optics".to_string()].into()));
+    }
+
+    #[test]
+    fn test_tag_hierarchy() {
+        assert_eq!(
+            normalize_tags(Some(vec![
+                "/ml//transformers/ ".to_string(),
+                " optics".to_string(),
+                "/".to_string(),
+            ])),
+            ["ml/transformers", "optics"].map(String::from).into()
+        );
+
+        assert!(is_tag_within("ml/transformers/attention", "ml"));
+        assert!(is_tag_within("ml/transformers", "ml/transformers/"));
+        assert!(is_tag_within("ml", "ml"));
+        assert!(!is_tag_within("mlops", "ml"));
+        assert!(!is_tag_within("ml", "ml/transformers"));
+
+        let tags: HashSet<String> = ["ml/transformers/attention".to_string()].into();
+        assert!(TagFilter::all(["ml"]).matches(&tags));
+        assert!(!TagFilter::all(["ml/vision"]).matches(&tags));
+        let exclude = TagFilter {
+            none: vec!["ml/transformers".into()],
+            ..Default::default()
+        };
+        assert!(!exclude.matches(&tags));
+    }
+
+    #[test]
+    fn test_parse_orcid() {
+        assert_eq!(
+            parse_orcid("0000-0002-1825-0097").unwrap(),
+            "0000-0002-1825-0097"
+        );
+        assert_eq!(
+            parse_orcid("https://orcid.org/0000-0002-1694-233x").unwrap(),
+            "0000-0002-1694-233X"
+        );
+        assert!(parse_orcid("0000-0002-1825-0098").is_err());
+        assert!(parse_orcid("0000-0002-1825").is_err());
+        assert!(parse_orcid("0000-0002-1825-009é").is_err());
+    }
 }
@@ -0,0 +1,286 @@
+//! `dxlog watch`: follows changes to the entry directories, checking each
+//! changed entry and rebuilding what is derived from the entries.
+
+use anyhow::{Context, Result};
+use notify::{event::ModifyKind, EventKind, RecursiveMode, Watcher};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+use tracing::debug;
+
+use crate::{
+    backup::snapshot_dirs, export_site, load_config, log_manager::LogManager,
+    research_log::ResearchLog, Config, DatasetManager, ExperimentManager, HypothesisManager,
+    IdeaManager, JournalManager, KnowledgeManager, LiteratureManager, MeetingManager,
+};
+
+/// How long the directories must be quiet before a batch of changes is
+/// handled, so that an editor saving a file counts as one change.
+const SETTLE: Duration = Duration::from_millis(300);
+
+/// What [`watch_entries`] keeps up to date besides checking entries.
+#[derive(Debug, Default, Clone)]
+pub struct WatchOptions {
+    /// Directory to export the static site to on every change.
+    pub site: Option<PathBuf>,
+    /// Embed new and changed entries for `dxlog search --semantic`.
+    #[cfg(feature = "llm")]
+    pub embeddings: bool,
+    #[cfg(feature = "llm")]
+    pub api_key: Option<String>,
+}
+
+/// Something [`watch_entries`] did.
+#[derive(Debug, Clone)]
+pub enum WatchEvent {
+    /// The directories are being watched.
+    Started {
+        dirs: Vec<PathBuf>,
+    },
+    /// An entry was created or changed and reads fine.
+    Changed {
+        path: PathBuf,
+    },
+    /// An entry was created or changed but cannot be read.
+    Invalid {
+        path: PathBuf,
+        reason: String,
+    },
+    Removed {
+        path: PathBuf,
+    },
+    /// The static site was exported.
+    SiteExported {
+        dir: PathBuf,
+        entries: usize,
+    },
+    /// Entries were embedded for semantic search.
+    Embedded {
+        entries: usize,
+    },
+    /// Rebuilding something failed; watching goes on.
+    Failed {
+        action: &'static str,
+        error: String,
+    },
+}
+
+/// Whether an entry file reads fine, found by [`check_path`].
+#[derive(Debug, PartialEq, Eq)]
+enum Check {
+    Valid,
+    Invalid(String),
+    /// Not an entry file of a built-in log type.
+    Ignored,
+}
+
+fn check_with<T: ResearchLog>(manager: &LogManager<T>, path: &Path, check: &mut Check) {
+    match manager.check_file(path) {
+        Some(Ok(true)) => *check = Check::Valid,
+        Some(Ok(false)) if *check == Check::Ignored => {
+            *check =
+                Check::Invalid("its status is not one of the log types stored here".to_string());
+        }
+        Some(Err(err)) if *check != Check::Valid => {
+            *check = Check::Invalid(err.root_cause().to_string());
+        }
+        _ => {}
+    }
+}
+
+/// Reads the file at `path` as an entry of each log type whose directory it is in.
+fn check_path(config: &Config, path: &Path) -> Check {
+    let mut check = Check::Ignored;
+    check_with(
+        &HypothesisManager::new(config.clone()).manager,
+        path,
+        &mut check,
+    );
+    check_with(
+        &LiteratureManager::new(config.clone()).manager,
+        path,
+        &mut check,
+    );
+    check_with(
+        &KnowledgeManager::new(config.clone()).manager,
+        path,
+        &mut check,
+    );
+    check_with(
+        &JournalManager::new(config.clone()).manager,
+        path,
+        &mut check,
+    );
+    check_with(
+        &DatasetManager::new(config.clone()).manager,
+        path,
+        &mut check,
+    );
+    check_with(
+        &MeetingManager::new(config.clone()).manager,
+        path,
+        &mut check,
+    );
+    check_with(&IdeaManager::new(config.clone()).manager, path, &mut check);
+    check_with(
+        &ExperimentManager::new(config.clone()).manager,
+        path,
+        &mut check,
+    );
+    check
+}
+
+/// Whether an event is a file being created, written, renamed or removed.
+fn is_change(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_)
+            | EventKind::Remove(_)
+            | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Name(_) | ModifyKind::Any)
+    )
+}
+
+/// Rebuilds what `options` asks for from the current entries.
+fn rebuild(options: &WatchOptions, report: &mut impl FnMut(WatchEvent)) {
+    if let Some(dir) = &options.site {
+        match export_site(dir) {
+            Ok(entries) => report(WatchEvent::SiteExported {
+                dir: dir.clone(),
+                entries,
+            }),
+            Err(err) => report(WatchEvent::Failed {
+                action: "export the site",
+                error: format!("{:#}", err),
+            }),
+        }
+    }
+    #[cfg(feature = "llm")]
+    if options.embeddings {
+        match crate::semantic::update_embeddings(options.api_key.as_deref()) {
+            Ok(0) => {}
+            Ok(entries) => report(WatchEvent::Embedded { entries }),
+            Err(err) => report(WatchEvent::Failed {
+                action: "update the embeddings",
+                error: format!("{:#}", err),
+            }),
+        }
+    }
+}
+
+/// Watches the entry directories until the process is stopped, reporting
+/// each change through `report`.
+///
+/// Every created or changed entry file is read to check its frontmatter.
+/// What `options` asks for is rebuilt once when watching starts and again
+/// after each batch of changes.
+pub fn watch_entries(options: &WatchOptions, mut report: impl FnMut(WatchEvent)) -> Result<()> {
+    let config = load_config()?;
+    let dirs: Vec<PathBuf> = snapshot_dirs(&config)
+        .into_iter()
+        .filter(|dir| dir.is_dir())
+        .collect();
+    if dirs.is_empty() {
+        return Err(anyhow::anyhow!("There are no entry directories to watch"));
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(sender).context("Failed to start watching for changes")?;
+    for dir in &dirs {
+        watcher
+            .watch(dir, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch {}", dir.display()))?;
+    }
+    report(WatchEvent::Started { dirs });
+    rebuild(options, &mut report);
+
+    loop {
+        let mut paths = BTreeSet::new();
+        let mut event = receiver
+            .recv()
+            .context("Stopped receiving changes to the entry directories")?;
+        loop {
+            match event {
+                // Reading entries, as checking them does, is an event too
+                Ok(event) if is_change(&event.kind) => paths.extend(event.paths),
+                Ok(_) => {}
+                Err(err) => debug!("Watch error: {}", err),
+            }
+            match receiver.recv_timeout(SETTLE) {
+                Ok(next) => event = next,
+                Err(_) => break,
+            }
+        }
+
+        let mut changed = false;
+        for path in paths {
+            if path.extension().and_then(|s| s.to_str()) != Some("md") {
+                continue;
+            }
+            if !path.exists() {
+                changed = true;
+                report(WatchEvent::Removed { path });
+                continue;
+            }
+            match check_path(&config, &path) {
+                Check::Valid => {
+                    changed = true;
+                    report(WatchEvent::Changed { path });
+                }
+                Check::Invalid(reason) => report(WatchEvent::Invalid { path, reason }),
+                Check::Ignored => debug!(path = %path.display(), "Ignoring change"),
+            }
+        }
+        if changed {
+            rebuild(options, &mut report);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempRepo;
+    use std::fs;
+
+    #[test]
+    fn test_check_path() {
+        let repo = TempRepo::new();
+        let root = repo.root().to_path_buf();
+        let mut config = Config::default();
+        config.storage.active_dir = root.join("logs");
+        config.storage.knowledge_base_dir = root.join("knowledge");
+        config.root = root.clone();
+        fs::create_dir_all(&config.storage.active_dir).unwrap();
+
+        let entry = |status: &str, title: &str| {
+            format!(
+                "---\nid: 2fca92d9-3fd1-4567-ab4d-f9217fa728dd\ncreated_at: 2025-03-01T10:00:00+00:00\n\
+                 {}tags: []\ncreated_by:\n  name: Ada\n  email: ada@example.org\n\
+                 references: []\nstatus: {}\n---\n# Body\n",
+                title, status
+            )
+        };
+        let write = |name: &str, content: &str| {
+            let path = config.storage.active_dir.join(name);
+            fs::write(&path, content).unwrap();
+            path
+        };
+
+        let valid = write("valid.md", &entry("Active", "title: Entry\n"));
+        assert_eq!(check_path(&config, &valid), Check::Valid);
+        let draft = write("draft.md", &entry("Draft", "title: Draft\n"));
+        assert_eq!(check_path(&config, &draft), Check::Valid);
+        let untitled = write("untitled.md", &entry("Active", ""));
+        assert_eq!(
+            check_path(&config, &untitled),
+            Check::Invalid("missing field `title`".to_string())
+        );
+        let notes = root.join("notes.md");
+        fs::write(&notes, "# Notes").unwrap();
+        assert_eq!(check_path(&config, &notes), Check::Ignored);
+    }
+}
@@ -0,0 +1,218 @@
+//! Workspaces: dxlog repositories registered by name in
+//! `~/.config/dxlog/workspaces.toml`, so that lists, searches and stats can
+//! span several of them and references can point from one to another.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::warn;
+
+use crate::Config;
+
+/// Environment variable pointing at the workspaces file, overriding the default location.
+pub const WORKSPACES_ENV_VAR: &str = "DXLOG_WORKSPACES";
+
+/// Selection of [`select_repos`] naming every registered repository.
+pub const ALL_REPOS: &str = "all";
+
+/// Separates the repository from the ID in a repo-qualified ID such as `qec:H-001`.
+const QUALIFIER: char = ':';
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkspacesFile {
+    #[serde(default)]
+    repos: BTreeMap<String, PathBuf>,
+}
+
+/// A repository registered under `[repos]` in the workspaces file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkspaceRepo {
+    pub name: String,
+    pub root: PathBuf,
+}
+
+/// Where the workspaces file is looked for: `$DXLOG_WORKSPACES`, then
+/// `$XDG_CONFIG_HOME/dxlog/workspaces.toml` or `~/.config/dxlog/workspaces.toml`.
+pub fn workspaces_path() -> Option<PathBuf> {
+    std::env::var_os(WORKSPACES_ENV_VAR)
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("XDG_CONFIG_HOME")
+                .map(|d| PathBuf::from(d).join("dxlog/workspaces.toml"))
+        })
+        .or_else(|| {
+            std::env::var_os("HOME").map(|d| PathBuf::from(d).join(".config/dxlog/workspaces.toml"))
+        })
+}
+
+/// Resolves a registered path: `~/` is the home directory and relative paths
+/// are relative to the workspaces file.
+fn expand(path: &Path, file_dir: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~") {
+        if let Some(home) = std::env::var_os("HOME") {
+            return PathBuf::from(home).join(rest);
+        }
+    }
+    file_dir.join(path)
+}
+
+fn parse(path: &Path, content: &str) -> Result<Vec<WorkspaceRepo>> {
+    let file: WorkspacesFile = toml::from_str(content)
+        .with_context(|| format!("Invalid workspaces file: {}", path.display()))?;
+    let file_dir = path.parent().unwrap_or(Path::new("."));
+    file.repos
+        .into_iter()
+        .map(|(name, root)| {
+            if name == ALL_REPOS || name.contains(QUALIFIER) || name.contains(',') {
+                return Err(anyhow::anyhow!(
+                    "Invalid workspaces file: {}: '{}' cannot name a repository",
+                    path.display(),
+                    name
+                ));
+            }
+            Ok(WorkspaceRepo {
+                root: expand(&root, file_dir),
+                name,
+            })
+        })
+        .collect()
+}
+
+/// The repositories registered in the workspaces file, by name. There are
+/// none if the file does not exist.
+pub fn workspace_repos() -> Result<Vec<WorkspaceRepo>> {
+    let Some(path) = workspaces_path().filter(|path| path.is_file()) else {
+        return Ok(Vec::new());
+    };
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+    parse(&path, &content)
+}
+
+fn select(repos: Vec<WorkspaceRepo>, selection: &str) -> Result<Vec<WorkspaceRepo>> {
+    if repos.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No repositories registered. Add them under [repos] in {}",
+            workspaces_path()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "~/.config/dxlog/workspaces.toml".to_string())
+        ));
+    }
+    if selection == ALL_REPOS {
+        return Ok(repos);
+    }
+    selection
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            repos
+                .iter()
+                .find(|repo| repo.name == name)
+                .cloned()
+                .with_context(|| {
+                    let known: Vec<&str> = repos.iter().map(|repo| repo.name.as_str()).collect();
+                    format!(
+                        "Unknown repository '{}'. Registered repositories: {}",
+                        name,
+                        known.join(", ")
+                    )
+                })
+        })
+        .collect()
+}
+
+/// The registered repositories named by `selection`: `all`, or a
+/// comma-separated list of names.
+pub fn select_repos(selection: &str) -> Result<Vec<WorkspaceRepo>> {
+    select(workspace_repos()?, selection)
+}
+
+/// An ID qualified with the repository it belongs to, e.g. `qec:H-001`.
+pub fn qualified_id(repo: &str, id: &str) -> String {
+    format!("{}{}{}", repo, QUALIFIER, id)
+}
+
+/// Splits a repo-qualified ID into the registered repository it names and
+/// the ID within it.
+pub(crate) fn split_qualified(id: &str) -> Option<(WorkspaceRepo, &str)> {
+    let (name, id) = id.split_once(QUALIFIER)?;
+    let repo = workspace_repos()
+        .ok()?
+        .into_iter()
+        .find(|repo| repo.name == name)?;
+    Some((repo, id))
+}
+
+/// Whether `repo` is the repository of `config`.
+pub(crate) fn is_current(config: &Config, repo: &WorkspaceRepo) -> bool {
+    let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    canonical(&repo.root) == canonical(&config.root)
+}
+
+/// The registered repositories other than the one of `config`, with their
+/// configs. Repositories that cannot be loaded are left out with a warning.
+pub(crate) fn other_repos(config: &Config) -> Vec<(WorkspaceRepo, Config)> {
+    let repos = match workspace_repos() {
+        Ok(repos) => repos,
+        Err(err) => {
+            warn!("{:#}", err);
+            return Vec::new();
+        }
+    };
+    repos
+        .into_iter()
+        .filter(|repo| !is_current(config, repo))
+        .filter_map(|repo| match Config::load(&repo.root) {
+            Ok(config) => Some((repo, config)),
+            Err(err) => {
+                warn!("Skipping repository {}: {:#}", repo.name, err);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_select() {
+        let path = Path::new("/home/ada/.config/dxlog/workspaces.toml");
+        let repos = parse(path, "[repos]\nqec = \"/data/qec-log\"\nml = \"logs/ml\"\n").unwrap();
+        assert_eq!(
+            repos,
+            vec![
+                WorkspaceRepo {
+                    name: "ml".to_string(),
+                    root: PathBuf::from("/home/ada/.config/dxlog/logs/ml"),
+                },
+                WorkspaceRepo {
+                    name: "qec".to_string(),
+                    root: PathBuf::from("/data/qec-log"),
+                },
+            ]
+        );
+        assert!(parse(path, "[repos]\nall = \"/data\"\n").is_err());
+        assert!(parse(path, "[repos]\n\"a:b\" = \"/data\"\n").is_err());
+
+        assert_eq!(select(repos.clone(), "all").unwrap().len(), 2);
+        let picked = select(repos.clone(), "qec, ml").unwrap();
+        assert_eq!(picked[0].name, "qec");
+        assert_eq!(picked[1].name, "ml");
+        let err = select(repos, "optics").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Unknown repository 'optics'. Registered repositories: ml, qec"
+        );
+        assert!(select(Vec::new(), "all").is_err());
+
+        assert_eq!(qualified_id("qec", "H-001"), "qec:H-001");
+    }
+}
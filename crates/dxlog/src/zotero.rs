@@ -0,0 +1,101 @@
+use anyhow::Result;
+use dxlog_tools::{add_zotero_note, fetch_zotero_collection, BibRecord};
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    export::markdown_to_html, load_config, md_frontmatter::extract_frontmatter, utils,
+    ImportSummary, ListFilter, LiteratureLog, LiteratureManager, LiteratureStatus, ZoteroLink,
+};
+
+/// Creates literature entries for the items of a Zotero collection, found by
+/// name or key in the API key owner's library or in the group `group`.
+///
+/// Items already imported from the same library, or whose DOI is already in
+/// the collection, are skipped.
+pub fn import_zotero(
+    api_key: &str,
+    group: Option<u64>,
+    collection: &str,
+    tags: Option<Vec<String>>,
+) -> Result<ImportSummary> {
+    let manager = LiteratureManager::new(load_config()?);
+    let (library, items) = fetch_zotero_collection(api_key, group, collection)?;
+
+    let imported: HashSet<String> = manager
+        .manager
+        .list_logs(&ListFilter::default())?
+        .into_iter()
+        .filter_map(|literature| literature.zotero)
+        .filter(|link| link.library == library)
+        .map(|link| link.item)
+        .collect();
+
+    let mut duplicates = Vec::new();
+    let mut venues = HashMap::new();
+    let mut records = Vec::new();
+    for item in items {
+        if imported.contains(&item.key) {
+            duplicates.push(item.title);
+            continue;
+        }
+        venues.insert(item.key.clone(), item.venue);
+        records.push(BibRecord {
+            entry_type: "zotero".to_string(),
+            key: Some(item.key),
+            title: item.title,
+            authors: item.authors,
+            year: item.year,
+            doi: item.doi,
+            url: item.url,
+            pdf_url: None,
+            abstract_text: item.abstract_text,
+        });
+    }
+
+    let mut summary = manager.import_with(records, tags, |record, literature| {
+        let item = record.key.clone().unwrap_or_default();
+        literature.source.venue = venues.get(&item).cloned().flatten();
+        literature.zotero = Some(ZoteroLink {
+            library: library.clone(),
+            item,
+            note: None,
+        });
+    })?;
+    summary.duplicates.extend(duplicates);
+    Ok(summary)
+}
+
+/// Adds the body of every completed review imported from Zotero as a note on
+/// its Zotero item, once. Returns each review pushed with the note's key, or
+/// why it failed.
+pub fn push_zotero_notes(api_key: &str) -> Result<Vec<(LiteratureLog, Result<String>)>> {
+    let manager = LiteratureManager::new(load_config()?);
+    let completed = manager
+        .manager
+        .list_log_files(&ListFilter::with_status(LiteratureStatus::Completed))?;
+
+    let mut pushed = Vec::new();
+    for (mut literature, path) in completed {
+        let Some(link) = literature.zotero.clone() else {
+            continue;
+        };
+        if link.note.is_some() {
+            continue;
+        }
+        let note = push_note(api_key, &link, &path).and_then(|note| {
+            if let Some(link) = &mut literature.zotero {
+                link.note = Some(note.clone());
+            }
+            manager.manager.update_log(&mut literature, &path)?;
+            Ok(note)
+        });
+        pushed.push((literature, note));
+    }
+    Ok(pushed)
+}
+
+fn push_note(api_key: &str, link: &ZoteroLink, path: &std::path::Path) -> Result<String> {
+    let content = utils::load_entry_content(path)?;
+    let (_, body) = extract_frontmatter::<LiteratureLog>(&content)?;
+    add_zotero_note(api_key, &link.library, &link.item, &markdown_to_html(&body))
+}